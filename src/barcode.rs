@@ -0,0 +1,625 @@
+//! Drawables for QR codes and linear barcodes, for example for tickets, badges and labels.
+//!
+//! QR codes are encoded with the [`qrcode`](https://docs.rs/qrcode) crate, while [Code 128]
+//! barcodes are encoded by RIL itself. Both are drawn directly onto an image, scaled by a whole
+//! number of pixels per module, which is the size of the smallest square of a QR code or the
+//! width of the narrowest bar of a barcode.
+//!
+//! [Code 128]: https://en.wikipedia.org/wiki/Code_128
+//!
+//! # Example
+//! ```
+//! # use ril::prelude::*;
+//! # fn main() -> ril::Result<()> {
+//! let qr = QrCode::new(
+//!     "https://example.com",
+//!     QrErrorCorrection::Medium,
+//!     Rgb::black(),
+//!     Rgb::white(),
+//! )?
+//! .with_position(16, 16)
+//! .with_module_size(4);
+//! let barcode = Barcode::code128("TICKET-0042", Rgb::black(), Rgb::white())?
+//!     .with_position(16, 200)
+//!     .with_height(48);
+//!
+//! let mut image = Image::new(320, 280, Rgb::white());
+//! image.draw(&qr);
+//! image.draw(&barcode);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Draw, Error, Image, OverlayMode, Pixel, Result};
+use alloc::{format, vec::Vec};
+use core::ops::DerefMut;
+
+/// The error correction level of a QR code, which determines how much of the code can be damaged
+/// or covered while still being readable. Higher levels make the code larger.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QrErrorCorrection {
+    /// About 7% of the code can be restored.
+    Low,
+    /// About 15% of the code can be restored. This is a good default for most codes.
+    Medium,
+    /// About 25% of the code can be restored.
+    Quartile,
+    /// About 30% of the code can be restored. Use this when the code has a logo hole.
+    High,
+}
+
+impl From<QrErrorCorrection> for qrcode::EcLevel {
+    fn from(level: QrErrorCorrection) -> Self {
+        match level {
+            QrErrorCorrection::Low => Self::L,
+            QrErrorCorrection::Medium => Self::M,
+            QrErrorCorrection::Quartile => Self::Q,
+            QrErrorCorrection::High => Self::H,
+        }
+    }
+}
+
+/// Fills the given region of the image with the given pixel, clipping it to the bounds of the
+/// image.
+fn fill_region<P: Pixel>(
+    image: &mut Image<P>,
+    (x1, y1, x2, y2): (u32, u32, u32, u32),
+    pixel: P,
+    mode: OverlayMode,
+) {
+    let (x2, y2) = (x2.min(image.width()), y2.min(image.height()));
+
+    for y in y1..y2 {
+        for x in x1..x2 {
+            image.overlay_pixel_with_mode(x, y, pixel, mode);
+        }
+    }
+}
+
+/// A QR code which can be drawn onto an image.
+///
+/// The code is encoded when it is created, and is drawn with its top-left corner, including the
+/// quiet zone, at [`position`](Self::position). Use [`Self::dimensions`] to get the size of the
+/// drawn code in pixels.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let qr = QrCode::new("hello", QrErrorCorrection::Medium, Rgb::black(), Rgb::white())?
+///     .with_module_size(8);
+/// let (width, height) = qr.dimensions();
+///
+/// let image = Image::new(width, height, Rgb::white()).with(&qr);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct QrCode<P: Pixel> {
+    /// The modules of the code, row by row, where `true` is a dark module.
+    modules: Vec<bool>,
+    /// The amount of modules along each side of the code.
+    width: u32,
+    /// The position of the top-left corner of the code, including the quiet zone.
+    pub position: (u32, u32),
+    /// The size of each module in pixels. Defaults to `4`.
+    pub module_size: u32,
+    /// The width of the blank margin around the code in modules. Defaults to `4`, which is the
+    /// minimum required by the QR code specification.
+    pub quiet_zone: u32,
+    /// The color of the dark modules.
+    pub foreground: P,
+    /// The color of the light modules and of the quiet zone.
+    pub background: P,
+    /// The size in pixels of the area in the center of the code which is left blank for a logo,
+    /// if any.
+    pub logo_hole: Option<(u32, u32)>,
+    /// The overlay mode to use when drawing the code, or `None` to use the image's overlay mode.
+    pub overlay: Option<OverlayMode>,
+}
+
+impl<P: Pixel> QrCode<P> {
+    /// Encodes the given data into a QR code with the given error correction level, drawn with the
+    /// given foreground and background colors. The smallest QR code version which fits the data is
+    /// used.
+    ///
+    /// # Errors
+    /// * `BarcodeError`: The data is too long to fit in a QR code with the given error correction
+    ///   level.
+    pub fn new(
+        data: impl AsRef<[u8]>,
+        error_correction: QrErrorCorrection,
+        foreground: P,
+        background: P,
+    ) -> Result<Self> {
+        let code = qrcode::QrCode::with_error_correction_level(data, error_correction.into())
+            .map_err(|err| Error::BarcodeError(format!("could not encode QR code: {err}")))?;
+
+        Ok(Self {
+            width: code.width() as u32,
+            modules: code
+                .into_colors()
+                .into_iter()
+                .map(|color| color == qrcode::Color::Dark)
+                .collect(),
+            position: (0, 0),
+            module_size: 4,
+            quiet_zone: 4,
+            foreground,
+            background,
+            logo_hole: None,
+            overlay: None,
+        })
+    }
+
+    /// Sets the position of the top-left corner of the code, including the quiet zone.
+    #[must_use]
+    pub const fn with_position(mut self, x: u32, y: u32) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the size of each module in pixels.
+    ///
+    /// # Panics
+    /// * `module_size` is zero.
+    #[must_use]
+    pub fn with_module_size(mut self, module_size: u32) -> Self {
+        assert_ne!(module_size, 0, "module size must be non-zero");
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the width of the blank margin around the code in modules.
+    #[must_use]
+    pub const fn with_quiet_zone(mut self, quiet_zone: u32) -> Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Leaves an area of the given size in pixels blank in the center of the code, so that a logo
+    /// can be pasted there after drawing the code. The area is rounded up to whole modules. Use
+    /// [`Self::logo_position`] to get where a logo of this size should be pasted.
+    ///
+    /// Covering modules relies on error correction to keep the code readable, so the hole should
+    /// cover only a small part of the code, and [`QrErrorCorrection::High`] should be used.
+    #[must_use]
+    pub const fn with_logo_hole(mut self, width: u32, height: u32) -> Self {
+        self.logo_hole = Some((width, height));
+        self
+    }
+
+    /// Sets the overlay mode to use when drawing the code.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Returns the amount of modules along each side of the code, excluding the quiet zone.
+    #[must_use]
+    pub const fn modules(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns whether the module at the given coordinates is dark, excluding the quiet zone.
+    ///
+    /// # Panics
+    /// * The coordinates are out of bounds.
+    #[must_use]
+    pub fn is_dark(&self, x: u32, y: u32) -> bool {
+        assert!(x < self.width && y < self.width, "module out of bounds");
+        self.modules[(y * self.width + x) as usize]
+    }
+
+    /// Returns the width and height of the drawn code in pixels, including the quiet zone.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        let size = (self.width + self.quiet_zone * 2) * self.module_size;
+        (size, size)
+    }
+
+    /// Returns the position at which a logo with the size given to [`Self::with_logo_hole`]
+    /// should be pasted to be centered in the logo hole, or `None` if there is no logo hole.
+    #[must_use]
+    pub const fn logo_position(&self) -> Option<(u32, u32)> {
+        match self.logo_hole {
+            Some((width, height)) => {
+                let (size, _) = self.dimensions();
+                Some((
+                    self.position.0 + size.saturating_sub(width) / 2,
+                    self.position.1 + size.saturating_sub(height) / 2,
+                ))
+            }
+            None => None,
+        }
+    }
+
+    /// Returns the range of modules along one axis covered by a logo hole of the given size in
+    /// pixels, rounded up to whole modules and kept centered.
+    fn hole_range(&self, size: u32) -> (u32, u32) {
+        let mut modules =
+            (size.saturating_add(self.module_size - 1) / self.module_size).min(self.width);
+        if (self.width - modules) & 1 == 1 {
+            modules += 1;
+        }
+        let start = (self.width - modules) / 2;
+
+        (start, start + modules)
+    }
+}
+
+impl<P: Pixel> Draw<P> for QrCode<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let overlay = self.overlay.unwrap_or(image.overlay);
+        let (x, y) = self.position;
+        let (size, _) = self.dimensions();
+        let offset = self.quiet_zone * self.module_size;
+
+        fill_region(
+            &mut image,
+            (x, y, x + size, y + size),
+            self.background,
+            overlay,
+        );
+
+        let hole = self
+            .logo_hole
+            .map(|(width, height)| (self.hole_range(width), self.hole_range(height)));
+        let in_hole = |mx: u32, my: u32| match hole {
+            Some(((x1, x2), (y1, y2))) => (x1..x2).contains(&mx) && (y1..y2).contains(&my),
+            None => false,
+        };
+
+        for my in 0..self.width {
+            for mx in (0..self.width).filter(|&mx| self.is_dark(mx, my) && !in_hole(mx, my)) {
+                let x1 = x + offset + mx * self.module_size;
+                let y1 = y + offset + my * self.module_size;
+
+                fill_region(
+                    &mut image,
+                    (x1, y1, x1 + self.module_size, y1 + self.module_size),
+                    self.foreground,
+                    overlay,
+                );
+            }
+        }
+    }
+}
+
+/// The bar and space widths of every Code 128 symbol, where each set bit is a dark module. Every
+/// symbol is 11 modules wide, except for the stop symbol which is 13 modules wide.
+const CODE128_PATTERNS: [u16; 107] = [
+    0b110_1100_1100,
+    0b110_0110_1100,
+    0b110_0110_0110,
+    0b100_1001_1000,
+    0b100_1000_1100,
+    0b100_0100_1100,
+    0b100_1100_1000,
+    0b100_1100_0100,
+    0b100_0110_0100,
+    0b110_0100_1000,
+    0b110_0100_0100,
+    0b110_0010_0100,
+    0b101_1001_1100,
+    0b100_1101_1100,
+    0b100_1100_1110,
+    0b101_1100_1100,
+    0b100_1110_1100,
+    0b100_1110_0110,
+    0b110_0111_0010,
+    0b110_0101_1100,
+    0b110_0100_1110,
+    0b110_1110_0100,
+    0b110_0111_0100,
+    0b111_0110_1110,
+    0b111_0100_1100,
+    0b111_0010_1100,
+    0b111_0010_0110,
+    0b111_0110_0100,
+    0b111_0011_0100,
+    0b111_0011_0010,
+    0b110_1101_1000,
+    0b110_1100_0110,
+    0b110_0011_0110,
+    0b101_0001_1000,
+    0b100_0101_1000,
+    0b100_0100_0110,
+    0b101_1000_1000,
+    0b100_0110_1000,
+    0b100_0110_0010,
+    0b110_1000_1000,
+    0b110_0010_1000,
+    0b110_0010_0010,
+    0b101_1011_1000,
+    0b101_1000_1110,
+    0b100_0110_1110,
+    0b101_1101_1000,
+    0b101_1100_0110,
+    0b100_0111_0110,
+    0b111_0111_0110,
+    0b110_1000_1110,
+    0b110_0010_1110,
+    0b110_1110_1000,
+    0b110_1110_0010,
+    0b110_1110_1110,
+    0b111_0101_1000,
+    0b111_0100_0110,
+    0b111_0001_0110,
+    0b111_0110_1000,
+    0b111_0110_0010,
+    0b111_0001_1010,
+    0b111_0111_1010,
+    0b110_0100_0010,
+    0b111_1000_1010,
+    0b101_0011_0000,
+    0b101_0000_1100,
+    0b100_1011_0000,
+    0b100_1000_0110,
+    0b100_0010_1100,
+    0b100_0010_0110,
+    0b101_1001_0000,
+    0b101_1000_0100,
+    0b100_1101_0000,
+    0b100_1100_0010,
+    0b100_0011_0100,
+    0b100_0011_0010,
+    0b110_0001_0010,
+    0b110_0101_0000,
+    0b111_1011_1010,
+    0b110_0001_0100,
+    0b100_0111_1010,
+    0b101_0011_1100,
+    0b100_1011_1100,
+    0b100_1001_1110,
+    0b101_1110_0100,
+    0b100_1111_0100,
+    0b100_1111_0010,
+    0b111_1010_0100,
+    0b111_1001_0100,
+    0b111_1001_0010,
+    0b110_1101_1110,
+    0b110_1111_0110,
+    0b111_1011_0110,
+    0b101_0111_1000,
+    0b101_0001_1110,
+    0b100_0101_1110,
+    0b101_1110_1000,
+    0b101_1110_0010,
+    0b111_1010_1000,
+    0b111_1010_0010,
+    0b101_1101_1110,
+    0b101_1110_1110,
+    0b111_0101_1110,
+    0b111_1010_1110,
+    // Start A, B and C
+    0b110_1000_0100,
+    0b110_1001_0000,
+    0b110_1001_1100,
+    // Stop
+    0b1_1000_1110_1011,
+];
+
+const CODE128_START_B: u16 = 104;
+const CODE128_START_C: u16 = 105;
+const CODE128_STOP: u16 = 106;
+
+/// Encodes the given data as Code 128 and returns its modules, where `true` is a dark module.
+fn encode_code128(data: &str) -> Result<Vec<bool>> {
+    if data.is_empty() {
+        return Err(Error::BarcodeError(
+            "cannot encode an empty barcode".to_string(),
+        ));
+    }
+
+    // Code set C encodes pairs of digits as a single symbol, which halves the width of numeric
+    // data. Otherwise, code set B covers printable ASCII.
+    let bytes = data.as_bytes();
+    let symbols = if bytes.len() & 1 == 0 && bytes.iter().all(u8::is_ascii_digit) {
+        core::iter::once(CODE128_START_C)
+            .chain(
+                bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from(pair[0] - b'0') * 10 + u16::from(pair[1] - b'0')),
+            )
+            .collect::<Vec<_>>()
+    } else {
+        core::iter::once(Ok(CODE128_START_B))
+            .chain(data.chars().map(|c| match c {
+                ' '..='~' => Ok(c as u16 - 32),
+                _ => Err(Error::BarcodeError(format!(
+                    "character {c:?} cannot be encoded in a Code 128 barcode"
+                ))),
+            }))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let checksum = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, &symbol)| i.max(1) as u32 * u32::from(symbol))
+        .sum::<u32>()
+        % 103;
+
+    let mut modules = Vec::with_capacity((symbols.len() + 1) * 11 + 13);
+    for &symbol in symbols.iter().chain(&[checksum as u16]) {
+        let pattern = CODE128_PATTERNS[symbol as usize];
+        modules.extend((0..11).rev().map(|bit| pattern >> bit & 1 == 1));
+    }
+    let stop = CODE128_PATTERNS[CODE128_STOP as usize];
+    modules.extend((0..13).rev().map(|bit| stop >> bit & 1 == 1));
+
+    Ok(modules)
+}
+
+/// A linear barcode which can be drawn onto an image.
+///
+/// The barcode is encoded when it is created, and is drawn with its top-left corner, including
+/// the quiet zone, at [`position`](Self::position). Use [`Self::dimensions`] to get the size of
+/// the drawn barcode in pixels.
+///
+/// Currently, only [Code 128](https://en.wikipedia.org/wiki/Code_128) barcodes are supported.
+#[derive(Clone, Debug)]
+pub struct Barcode<P: Pixel> {
+    /// The modules of the barcode from left to right, where `true` is a dark module.
+    modules: Vec<bool>,
+    /// The position of the top-left corner of the barcode, including the quiet zone.
+    pub position: (u32, u32),
+    /// The width of the narrowest bar in pixels. Defaults to `2`.
+    pub module_size: u32,
+    /// The height of the bars in pixels. Defaults to `64`.
+    pub height: u32,
+    /// The width of the blank margins to the left and right of the barcode in modules. Defaults
+    /// to `10`, which is the minimum required by the Code 128 specification.
+    pub quiet_zone: u32,
+    /// The color of the bars.
+    pub foreground: P,
+    /// The color of the spaces and of the quiet zone.
+    pub background: P,
+    /// The overlay mode to use when drawing the barcode, or `None` to use the image's overlay
+    /// mode.
+    pub overlay: Option<OverlayMode>,
+}
+
+impl<P: Pixel> Barcode<P> {
+    /// Encodes the given text into a Code 128 barcode drawn with the given foreground and
+    /// background colors. Text made up of an even amount of digits is encoded more compactly.
+    ///
+    /// # Errors
+    /// * `BarcodeError`: The text is empty or contains characters other than printable ASCII.
+    pub fn code128(data: &str, foreground: P, background: P) -> Result<Self> {
+        Ok(Self {
+            modules: encode_code128(data)?,
+            position: (0, 0),
+            module_size: 2,
+            height: 64,
+            quiet_zone: 10,
+            foreground,
+            background,
+            overlay: None,
+        })
+    }
+
+    /// Sets the position of the top-left corner of the barcode, including the quiet zone.
+    #[must_use]
+    pub const fn with_position(mut self, x: u32, y: u32) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the width of the narrowest bar in pixels.
+    ///
+    /// # Panics
+    /// * `module_size` is zero.
+    #[must_use]
+    pub fn with_module_size(mut self, module_size: u32) -> Self {
+        assert_ne!(module_size, 0, "module size must be non-zero");
+        self.module_size = module_size;
+        self
+    }
+
+    /// Sets the height of the bars in pixels.
+    #[must_use]
+    pub const fn with_height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the width of the blank margins to the left and right of the barcode in modules.
+    #[must_use]
+    pub const fn with_quiet_zone(mut self, quiet_zone: u32) -> Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Sets the overlay mode to use when drawing the barcode.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Returns the modules of the barcode from left to right, excluding the quiet zone, where
+    /// `true` is a dark module.
+    #[must_use]
+    pub fn modules(&self) -> &[bool] {
+        &self.modules
+    }
+
+    /// Returns the width and height of the drawn barcode in pixels, including the quiet zone.
+    #[must_use]
+    pub fn dimensions(&self) -> (u32, u32) {
+        let modules = self.modules.len() as u32 + self.quiet_zone * 2;
+        (modules * self.module_size, self.height)
+    }
+}
+
+impl<P: Pixel> Draw<P> for Barcode<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let overlay = self.overlay.unwrap_or(image.overlay);
+        let (x, y) = self.position;
+        let (width, height) = self.dimensions();
+
+        fill_region(
+            &mut image,
+            (x, y, x + width, y + height),
+            self.background,
+            overlay,
+        );
+
+        let offset = x + self.quiet_zone * self.module_size;
+        for (i, _) in (0..).zip(&self.modules).filter(|(_, &dark)| dark) {
+            let x1 = offset + i * self.module_size;
+
+            fill_region(
+                &mut image,
+                (x1, y, x1 + self.module_size, y + height),
+                self.foreground,
+                overlay,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code128_patterns() {
+        for (i, &pattern) in CODE128_PATTERNS.iter().enumerate() {
+            // Every symbol is made of three bars and three spaces, each one to four modules wide,
+            // except for the stop symbol which has a trailing bar
+            let bits = if i == CODE128_STOP as usize { 13 } else { 11 };
+            let mut runs = Vec::new();
+            for bit in (0..bits).rev().map(|bit| pattern >> bit & 1) {
+                match runs.last_mut() {
+                    Some((last, width)) if *last == bit => *width += 1,
+                    _ => runs.push((bit, 1)),
+                }
+            }
+
+            assert_eq!(runs.len(), if bits == 13 { 7 } else { 6 }, "symbol {i}");
+            assert_eq!(runs[0].0, 1, "symbol {i}");
+            assert!(runs.iter().all(|&(_, width)| width <= 4), "symbol {i}");
+            assert_eq!(pattern.count_ones() % 2, 0, "symbol {i}");
+            assert!(!CODE128_PATTERNS[..i].contains(&pattern), "symbol {i}");
+        }
+    }
+
+    #[test]
+    fn test_encode_code128() {
+        // Start B, "P", "J", "J", "1", "2", "3", "C", checksum 55, stop
+        let modules = encode_code128("PJJ123C").unwrap();
+        assert_eq!(modules.len(), 11 * 9 + 13);
+
+        let checksum = modules[11 * 8..11 * 9]
+            .iter()
+            .fold(0, |acc, &dark| acc << 1 | u16::from(dark));
+        assert_eq!(checksum, CODE128_PATTERNS[55]);
+
+        assert!(encode_code128("").is_err());
+        assert!(encode_code128("café").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Processes many image files in parallel, which is useful for tasks such as generating
+//! thumbnails for a whole directory of images.
+//!
+//! Each file is decoded, transformed and re-encoded independently on the rayon thread pool, and is
+//! dropped as soon as it has been saved. At most one image per worker thread is held in memory at
+//! a time, regardless of how many files are processed. A file that fails to be processed does not
+//! stop the others from being processed; instead, its error is collected into the returned
+//! [`BatchReport`].
+//!
+//! # Example
+//! ```no_run
+//! # use ril::prelude::*;
+//! use ril::batch::Batch;
+//!
+//! let paths = std::fs::read_dir("photos")?
+//!     .map(|entry| entry.map(|entry| entry.path()))
+//!     .collect::<std::io::Result<Vec<_>>>()?;
+//!
+//! let report = Batch::new()
+//!     .with_output_dir("thumbnails")
+//!     .with_format(ImageFormat::Png)
+//!     .process(paths, |image: Image<Rgba>| image.cropped(0, 0, 128, 128));
+//!
+//! for (path, err) in &report.failed {
+//!     eprintln!("failed to process {}: {err}", path.display());
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::{Error, Image, ImageFormat, Pixel, Result};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// The outcome of processing a batch of files.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// The paths of the files which were saved successfully, in the order of their input paths.
+    pub succeeded: Vec<PathBuf>,
+    /// The input paths of the files which failed to be processed, along with their errors, in the
+    /// order of their input paths.
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+impl BatchReport {
+    /// Returns whether every file was processed successfully.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Options for processing many image files in parallel. See the [module-level documentation]
+/// for more information.
+///
+/// [module-level documentation]: crate::batch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Batch {
+    /// The directory processed files are saved to, or `None` to overwrite the input files.
+    /// Defaults to `None`.
+    pub output_dir: Option<PathBuf>,
+    /// The encoding processed files are saved with, or `None` to keep the encoding of the input
+    /// file. When set, the extension of the output file is replaced to match it. Defaults to
+    /// `None`.
+    pub format: Option<ImageFormat>,
+    /// The amount of worker threads to use, or `None` to use the global rayon thread pool.
+    /// Defaults to `None`.
+    pub threads: Option<usize>,
+}
+
+/// Returns the canonical file extension of the given format.
+const fn extension(format: ImageFormat) -> Option<&'static str> {
+    match format {
+        ImageFormat::Png => Some("png"),
+        ImageFormat::Jpeg => Some("jpg"),
+        ImageFormat::Gif => Some("gif"),
+        ImageFormat::Bmp => Some("bmp"),
+        ImageFormat::Tiff => Some("tiff"),
+        ImageFormat::WebP => Some("webp"),
+        ImageFormat::Unknown => None,
+    }
+}
+
+impl Batch {
+    /// Creates a new batch which overwrites the input files, keeping their encodings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory processed files are saved to, which is created if it does not exist.
+    /// Output files keep the file names of their input files.
+    #[must_use]
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// Sets the encoding processed files are saved with. The extension of each output file is
+    /// replaced to match it.
+    #[must_use]
+    pub const fn with_format(mut self, format: ImageFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets the amount of worker threads to use, which is also the maximum amount of images held
+    /// in memory at once.
+    #[must_use]
+    pub const fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Returns the path the given input file is saved to.
+    fn output_path(&self, path: &Path) -> PathBuf {
+        let mut output = match (&self.output_dir, path.file_name()) {
+            (Some(dir), Some(name)) => dir.join(name),
+            _ => path.to_path_buf(),
+        };
+        if let Some(ext) = self.format.and_then(extension) {
+            output.set_extension(ext);
+        }
+
+        output
+    }
+
+    fn process_file<P: Pixel>(
+        &self,
+        path: &Path,
+        f: &(impl Fn(Image<P>) -> Image<P> + Sync),
+    ) -> Result<PathBuf> {
+        if let Some(dir) = &self.output_dir {
+            std::fs::create_dir_all(dir).map_err(|err| Error::from(err).with_path(dir))?;
+        }
+
+        let image = Image::<P>::open(path)?;
+        let format = match self.format {
+            Some(format) => format,
+            None => image.format(),
+        };
+        let output = self.output_path(path);
+
+        f(image).save(format, &output)?;
+        Ok(output)
+    }
+
+    /// Decodes every file at the given paths, transforms it with the given function and saves it
+    /// according to these options, processing files in parallel.
+    ///
+    /// Errors are collected per file into the returned report instead of stopping the batch.
+    ///
+    /// # See Also
+    /// * [`process_files`] to overwrite the input files with the default options.
+    pub fn process<P, F>(
+        &self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        f: F,
+    ) -> BatchReport
+    where
+        P: Pixel,
+        F: Fn(Image<P>) -> Image<P> + Sync,
+    {
+        let paths = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+        let run = || {
+            paths
+                .into_par_iter()
+                .map(|path| {
+                    let result = self.process_file(&path, &f);
+                    (path, result)
+                })
+                .collect::<Vec<_>>()
+        };
+        let results = match self
+            .threads
+            .map(|threads| rayon::ThreadPoolBuilder::new().num_threads(threads).build())
+        {
+            Some(Ok(pool)) => pool.install(run),
+            // Fall back to the global thread pool if a dedicated one could not be created
+            _ => run(),
+        };
+
+        let mut report = BatchReport::default();
+        for (path, result) in results {
+            match result {
+                Ok(output) => report.succeeded.push(output),
+                Err(err) => report.failed.push((path, err)),
+            }
+        }
+
+        report
+    }
+}
+
+/// Decodes every file at the given paths, transforms it with the given function and overwrites
+/// it with the result, processing files in parallel. Errors are collected per file into the
+/// returned report instead of stopping the batch.
+///
+/// This is a shorthand for [`Batch::process`] with the default options.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// let report = ril::batch::process_files(["a.png", "b.png"], |image: Image<Rgba>| image.inverted());
+/// assert!(report.is_ok());
+/// ```
+pub fn process_files<P, F>(paths: impl IntoIterator<Item = impl AsRef<Path>>, f: F) -> BatchReport
+where
+    P: Pixel,
+    F: Fn(Image<P>) -> Image<P> + Sync,
+{
+    Batch::new().process(paths, f)
+}
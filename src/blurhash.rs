@@ -0,0 +1,245 @@
+//! Encoding and decoding of [BlurHash](https://blurha.sh) placeholders, which are short strings
+//! describing a blurred version of an image that can be shown while the full image loads.
+//!
+//! See [`Image::to_blurhash`] and [`Image::from_blurhash`].
+
+use crate::{
+    color::{linear_to_srgb_u8, srgb_u8_to_linear},
+    Error, Image, Result, TrueColor,
+};
+use alloc::{format, string::String, vec::Vec};
+use core::f32::consts::PI;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The characters of the base 83 encoding used by BlurHash, in order of their value.
+const BASE83: &[u8; 83] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// Appends `value` to the given string as `length` base 83 digits.
+fn encode_base83(out: &mut String, value: u32, length: u32) {
+    for i in (0..length).rev() {
+        out.push(BASE83[(value / 83_u32.pow(i) % 83) as usize] as char);
+    }
+}
+
+/// Decodes the given base 83 digits.
+fn decode_base83(digits: &str) -> Result<u32> {
+    digits.bytes().try_fold(0, |value, digit| {
+        BASE83
+            .iter()
+            .position(|&c| c == digit)
+            .map(|position| value * 83 + position as u32)
+            .ok_or_else(|| {
+                Error::DecodingError(format!(
+                    "invalid character in blurhash: {:?}",
+                    digit as char
+                ))
+            })
+    })
+}
+
+/// Raises the absolute value of `value` to the given power, keeping its sign.
+fn sign_pow(value: f32, exp: f32) -> f32 {
+    let result = value.abs().powf(exp);
+    if value < 0. {
+        -result
+    } else {
+        result
+    }
+}
+
+/// Returns the cosine basis values `cos(PI * component * i / length)` of every component along
+/// an axis of the given length, indexed by `component * length + i`.
+fn basis(components: u32, length: u32) -> Vec<f32> {
+    (0..components)
+        .flat_map(|c| (0..length).map(move |i| (PI * c as f32 * i as f32 / length as f32).cos()))
+        .collect()
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Encodes this image into a [BlurHash](https://blurha.sh) with the given amount of
+    /// components along each axis. More components preserve more detail, but make the hash
+    /// longer. `4` by `3` is a good default for landscape images.
+    ///
+    /// BlurHash does not support transparency, so the alpha channel of the image is ignored.
+    /// Since every pixel contributes to every component, consider encoding a downscaled version of
+    /// large images, which gives practically the same hash.
+    ///
+    /// # Panics
+    /// * `components_x` or `components_y` is not in the range `1..=9`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::horizontal_gradient(64, 32, Rgb::new(255, 0, 0), Rgb::new(0, 0, 255));
+    /// let hash = image.to_blurhash(4, 3);
+    ///
+    /// assert_eq!(hash.len(), 4 + 2 * 4 * 3);
+    /// ```
+    #[must_use]
+    pub fn to_blurhash(&self, components_x: u32, components_y: u32) -> String {
+        assert!(
+            (1..=9).contains(&components_x) && (1..=9).contains(&components_y),
+            "blurhash components must be in the range 1..=9"
+        );
+
+        let (width, height) = self.dimensions();
+        let basis_x = basis(components_x, width);
+        let basis_y = basis(components_y, height);
+        let linear = self
+            .data
+            .iter()
+            .map(|pixel| {
+                let (r, g, b, _) = pixel.as_rgba_tuple();
+                [
+                    srgb_u8_to_linear(r),
+                    srgb_u8_to_linear(g),
+                    srgb_u8_to_linear(b),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let mut factors = Vec::with_capacity((components_x * components_y) as usize);
+        for cy in 0..components_y {
+            for cx in 0..components_x {
+                let normalisation = if cx == 0 && cy == 0 { 1. } else { 2. };
+                let scale = normalisation / (width as f32 * height as f32);
+                let bx = &basis_x[(cx * width) as usize..][..width as usize];
+                let by = &basis_y[(cy * height) as usize..][..height as usize];
+
+                let mut factor = [0.; 3];
+                for (row, &y) in linear.chunks_exact(width as usize).zip(by) {
+                    for (pixel, &x) in row.iter().zip(bx) {
+                        for (f, c) in factor.iter_mut().zip(pixel) {
+                            *f += x * y * c;
+                        }
+                    }
+                }
+                factors.push(factor.map(|f| f * scale));
+            }
+        }
+
+        let mut hash = String::with_capacity(4 + 2 * factors.len());
+        encode_base83(&mut hash, (components_x - 1) + (components_y - 1) * 9, 1);
+
+        let ac = &factors[1..];
+        let maximum = if ac.is_empty() {
+            encode_base83(&mut hash, 0, 1);
+            1.
+        } else {
+            let actual = ac.iter().flatten().fold(0_f32, |max, c| max.max(c.abs()));
+            let quantised = (actual * 166. - 0.5).floor().clamp(0., 82.) as u32;
+            encode_base83(&mut hash, quantised, 1);
+            (quantised + 1) as f32 / 166.
+        };
+
+        let [r, g, b] = factors[0].map(|c| u32::from(linear_to_srgb_u8(c)));
+        encode_base83(&mut hash, (r << 16) | (g << 8) | b, 4);
+
+        for factor in ac {
+            let [r, g, b] = factor.map(|c| {
+                (sign_pow(c / maximum, 0.5) * 9. + 9.5)
+                    .floor()
+                    .clamp(0., 18.) as u32
+            });
+            encode_base83(&mut hash, r * 19 * 19 + g * 19 + b, 2);
+        }
+
+        hash
+    }
+
+    /// Decodes the given [BlurHash](https://blurha.sh) into an image with the given dimensions.
+    /// Since the hash only describes a blurred version of the original image, it is usually
+    /// decoded into a small image, for example 32x32, which is then scaled up.
+    ///
+    /// The decoded image is fully opaque.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The hash is malformed.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::from_blurhash("LEHV6nWB2yk8pyo0adR*.7kCMdnj", 32, 32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_blurhash(hash: &str, width: u32, height: u32) -> Result<Self> {
+        if !hash.is_ascii() {
+            return Err(Error::DecodingError(
+                "blurhash contains non-ASCII characters".into(),
+            ));
+        }
+        if hash.len() < 6 {
+            return Err(Error::DecodingError("blurhash is too short".into()));
+        }
+
+        let size = decode_base83(&hash[..1])?;
+        let (components_x, components_y) = (size % 9 + 1, size / 9 + 1);
+        let expected = 4 + 2 * (components_x * components_y) as usize;
+        if hash.len() != expected {
+            return Err(Error::DecodingError(format!(
+                "blurhash with {components_x}x{components_y} components should be {expected} \
+                characters long, received {} instead",
+                hash.len()
+            )));
+        }
+
+        let maximum = (decode_base83(&hash[1..2])? + 1) as f32 / 166.;
+        let dc = decode_base83(&hash[2..6])?;
+        let mut colors = Vec::with_capacity((components_x * components_y) as usize);
+        colors.push([dc >> 16, (dc >> 8) & 255, dc & 255].map(|c| srgb_u8_to_linear(c as u8)));
+
+        for i in 1..(components_x * components_y) as usize {
+            let value = decode_base83(&hash[4 + i * 2..6 + i * 2])?;
+            let quantised = [value / (19 * 19), value / 19 % 19, value % 19];
+            colors.push(quantised.map(|q| sign_pow((q as f32 - 9.) / 9., 2.) * maximum));
+        }
+
+        let basis_x = basis(components_x, width);
+        let basis_y = basis(components_y, height);
+        let image = Self::from_fn(width, height, |x, y| {
+            let mut color = [0.; 3];
+            for cy in 0..components_y {
+                for cx in 0..components_x {
+                    let weight =
+                        basis_x[(cx * width + x) as usize] * basis_y[(cy * height + y) as usize];
+                    let factor = colors[(cy * components_x + cx) as usize];
+                    for (c, f) in color.iter_mut().zip(factor) {
+                        *c += f * weight;
+                    }
+                }
+            }
+
+            let [r, g, b] = color.map(linear_to_srgb_u8);
+            P::from_rgba_tuple((r, g, b, 255))
+        });
+
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Image, Rgb};
+
+    #[test]
+    fn test_blurhash_solid() {
+        let color = Rgb::new(10, 120, 200);
+        let image = Image::new(16, 16, color);
+        assert_eq!(image.to_blurhash(4, 3).len(), 28);
+
+        // A single component only stores the average color
+        let hash = image.to_blurhash(1, 1);
+        assert_eq!(hash.len(), 6);
+
+        let decoded = Image::<Rgb>::from_blurhash(&hash, 8, 8).unwrap();
+        assert!(decoded.data.iter().all(|&pixel| pixel == color));
+        assert!(Image::<Rgb>::from_blurhash(&hash[1..], 8, 8).is_err());
+    }
+}
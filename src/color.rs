@@ -0,0 +1,1134 @@
+//! Color spaces other than RGB, and conversions between them and RGB pixels.
+//!
+//! All conversions assume RGB pixels are in the sRGB color space with a D65 white point.
+//!
+//! The color types in this module are intended for color manipulation: convert a pixel into a
+//! color type, adjust it, and convert it back. [`Hsl`], [`Hsv`] and [`Lab`] are also pixels, so
+//! whole images can be converted into them with [`Image::convert`][crate::Image::convert]. Images
+//! of them are converted back into RGB when they are encoded.
+//!
+//! # Example
+//! ```
+//! # use ril::prelude::*;
+//! let hsl = Hsl::from(Rgb::new(255, 0, 0));
+//! assert_eq!(hsl, Hsl::new(0.0, 1.0, 0.5));
+//!
+//! let rotated = Rgb::from(hsl.hue_rotated(120.0));
+//! assert_eq!(rotated, Rgb::new(0, 255, 0));
+//!
+//! // Desaturate a whole image in the HSL color space
+//! let image = Image::new(2, 2, Rgb::new(255, 0, 0))
+//!     .convert::<Hsl>()
+//!     .map_pixels(|hsl| hsl.with_saturation(0.5))
+//!     .convert::<Rgb>();
+//! assert_eq!(image.pixel(0, 0), &Rgb::new(191, 64, 64));
+//! ```
+
+use crate::{
+    encodings::ColorType,
+    image::BlendMode,
+    pixel::{Modulate, Pixel},
+    Dynamic, Result, Rgb, Rgb32F, Rgba, Rgba32F,
+};
+use core::{
+    hash::{Hash, Hasher},
+    ops::{Mul, Not},
+};
+use num_traits::Euclid;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Wraps the given hue in degrees into the range `0.0..360.0`.
+pub(crate) fn wrap_hue(hue: f32) -> f32 {
+    let hue = Euclid::rem_euclid(&hue, &360.);
+
+    // `rem_euclid` may round up to exactly 360 for tiny negative values
+    if hue >= 360. {
+        0.
+    } else {
+        hue
+    }
+}
+
+/// Computes the hue in degrees and chroma of the given RGB components, along with their maximum.
+fn hue_chroma(Rgb32F { r, g, b }: Rgb32F) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+
+    let hue = if chroma <= 0. {
+        0.
+    } else if max == r {
+        60. * ((g - b) / chroma)
+    } else if max == g {
+        60. * ((b - r) / chroma + 2.)
+    } else {
+        60. * ((r - g) / chroma + 4.)
+    };
+
+    (wrap_hue(hue), chroma, max)
+}
+
+/// Computes RGB components from the given hue in degrees, chroma, and lightness offset.
+fn from_hue_chroma(hue: f32, chroma: f32, offset: f32) -> Rgb32F {
+    let h = wrap_hue(hue) / 60.;
+    let x = chroma * (1. - (h % 2. - 1.).abs());
+
+    let (r, g, b) = match h as u8 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+
+    Rgb32F::new(r + offset, g + offset, b + offset)
+}
+
+/// Represents a color in the HSL (hue, saturation, lightness) color space.
+///
+/// # See Also
+/// * [`Hsv`] for the HSV color space, which is usually more intuitive for picking colors.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsl {
+    /// The hue of the color in degrees, between 0.0 and 360.0.
+    pub h: f32,
+    /// The saturation of the color, between 0.0 and 1.0.
+    pub s: f32,
+    /// The lightness of the color, between 0.0 and 1.0.
+    pub l: f32,
+}
+
+impl Hsl {
+    /// Creates a new HSL color. The hue is wrapped into the range `0.0..360.0`, and saturation and
+    /// lightness are clamped into the range `0.0..=1.0`.
+    #[must_use]
+    pub fn new(h: f32, s: f32, l: f32) -> Self {
+        Self {
+            h: wrap_hue(h),
+            s: s.clamp(0., 1.),
+            l: l.clamp(0., 1.),
+        }
+    }
+
+    /// Rotates the hue of this color by the given amount of degrees.
+    pub fn hue_rotate(&mut self, degrees: f32) {
+        self.h = wrap_hue(self.h + degrees);
+    }
+
+    /// Takes this color and rotates its hue by the given amount of degrees. Useful for method
+    /// chaining.
+    #[must_use]
+    pub fn hue_rotated(mut self, degrees: f32) -> Self {
+        self.hue_rotate(degrees);
+        self
+    }
+
+    /// Takes this color and sets its saturation, clamped into the range `0.0..=1.0`.
+    #[must_use]
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.s = saturation.clamp(0., 1.);
+        self
+    }
+
+    /// Takes this color and sets its lightness, clamped into the range `0.0..=1.0`.
+    #[must_use]
+    pub fn with_lightness(mut self, lightness: f32) -> Self {
+        self.l = lightness.clamp(0., 1.);
+        self
+    }
+}
+
+impl From<Rgb32F> for Hsl {
+    fn from(rgb: Rgb32F) -> Self {
+        let (h, chroma, max) = hue_chroma(rgb);
+        let l = max - chroma / 2.;
+        let s = if l <= 0. || l >= 1. {
+            0.
+        } else {
+            chroma / (1. - 2.0f32.mul_add(l, -1.).abs())
+        };
+
+        Self::new(h, s, l)
+    }
+}
+
+impl From<Hsl> for Rgb32F {
+    fn from(Hsl { h, s, l }: Hsl) -> Self {
+        let chroma = (1. - 2.0f32.mul_add(l, -1.).abs()) * s;
+
+        from_hue_chroma(h, chroma, l - chroma / 2.)
+    }
+}
+
+/// Represents a color in the HSV (hue, saturation, value) color space, also known as HSB.
+///
+/// # See Also
+/// * [`Hsl`] for the HSL color space.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsv {
+    /// The hue of the color in degrees, between 0.0 and 360.0.
+    pub h: f32,
+    /// The saturation of the color, between 0.0 and 1.0.
+    pub s: f32,
+    /// The value, or brightness of the color, between 0.0 and 1.0.
+    pub v: f32,
+}
+
+impl Hsv {
+    /// Creates a new HSV color. The hue is wrapped into the range `0.0..360.0`, and saturation and
+    /// value are clamped into the range `0.0..=1.0`.
+    #[must_use]
+    pub fn new(h: f32, s: f32, v: f32) -> Self {
+        Self {
+            h: wrap_hue(h),
+            s: s.clamp(0., 1.),
+            v: v.clamp(0., 1.),
+        }
+    }
+
+    /// Rotates the hue of this color by the given amount of degrees.
+    pub fn hue_rotate(&mut self, degrees: f32) {
+        self.h = wrap_hue(self.h + degrees);
+    }
+
+    /// Takes this color and rotates its hue by the given amount of degrees. Useful for method
+    /// chaining.
+    #[must_use]
+    pub fn hue_rotated(mut self, degrees: f32) -> Self {
+        self.hue_rotate(degrees);
+        self
+    }
+
+    /// Takes this color and sets its saturation, clamped into the range `0.0..=1.0`.
+    #[must_use]
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.s = saturation.clamp(0., 1.);
+        self
+    }
+
+    /// Takes this color and sets its value, clamped into the range `0.0..=1.0`.
+    #[must_use]
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.v = value.clamp(0., 1.);
+        self
+    }
+}
+
+impl From<Rgb32F> for Hsv {
+    fn from(rgb: Rgb32F) -> Self {
+        let (h, chroma, max) = hue_chroma(rgb);
+        let s = if max <= 0. { 0. } else { chroma / max };
+
+        Self::new(h, s, max)
+    }
+}
+
+impl From<Hsv> for Rgb32F {
+    fn from(Hsv { h, s, v }: Hsv) -> Self {
+        let chroma = v * s;
+
+        from_hue_chroma(h, chroma, v - chroma)
+    }
+}
+
+impl From<Hsl> for Hsv {
+    fn from(hsl: Hsl) -> Self {
+        Rgb32F::from(hsl).into()
+    }
+}
+
+impl From<Hsv> for Hsl {
+    fn from(hsv: Hsv) -> Self {
+        Rgb32F::from(hsv).into()
+    }
+}
+
+/// The linear light value of every 8-bit gamma-encoded sRGB component, precomputed with
+/// [`srgb_to_linear`].
+#[allow(clippy::unreadable_literal, clippy::excessive_precision)]
+static SRGB_TO_LINEAR: [f32; 256] = [
+    0.0,
+    0.000303526991,
+    0.000607053982,
+    0.000910580973,
+    0.00121410796,
+    0.00151763496,
+    0.00182116195,
+    0.00212468882,
+    0.00242821593,
+    0.0027317428,
+    0.00303526991,
+    0.00334653584,
+    0.00367650739,
+    0.00402471703,
+    0.00439144205,
+    0.00477695325,
+    0.00518151652,
+    0.00560539169,
+    0.00604883302,
+    0.00651209056,
+    0.00699541019,
+    0.00749903219,
+    0.00802319311,
+    0.00856812578,
+    0.00913405884,
+    0.00972121768,
+    0.010329823,
+    0.0109600937,
+    0.0116122449,
+    0.012286488,
+    0.0129830325,
+    0.0137020834,
+    0.0144438436,
+    0.0152085144,
+    0.0159962941,
+    0.0168073755,
+    0.0176419541,
+    0.01850022,
+    0.0193823613,
+    0.0202885624,
+    0.0212190095,
+    0.0221738853,
+    0.0231533665,
+    0.0241576321,
+    0.0251868591,
+    0.0262412224,
+    0.0273208916,
+    0.02842604,
+    0.0295568351,
+    0.0307134446,
+    0.0318960324,
+    0.0331047662,
+    0.0343398079,
+    0.0356013142,
+    0.0368894488,
+    0.0382043719,
+    0.0395462364,
+    0.0409151986,
+    0.0423114114,
+    0.043735031,
+    0.045186203,
+    0.0466650873,
+    0.0481718257,
+    0.0497065671,
+    0.0512694567,
+    0.0528606474,
+    0.054480277,
+    0.0561284907,
+    0.0578054301,
+    0.0595112368,
+    0.0612460524,
+    0.0630100146,
+    0.064803265,
+    0.0666259378,
+    0.0684781671,
+    0.0703600943,
+    0.0722718537,
+    0.0742135718,
+    0.0761853829,
+    0.078187421,
+    0.0802198201,
+    0.0822827071,
+    0.0843762085,
+    0.0865004584,
+    0.0886555836,
+    0.0908417106,
+    0.0930589661,
+    0.0953074694,
+    0.097587347,
+    0.0998987257,
+    0.102241732,
+    0.104616486,
+    0.107023105,
+    0.10946171,
+    0.111932427,
+    0.114435375,
+    0.116970666,
+    0.119538426,
+    0.122138776,
+    0.124771819,
+    0.127437681,
+    0.130136475,
+    0.13286832,
+    0.135633335,
+    0.138431609,
+    0.141263291,
+    0.144128472,
+    0.147027269,
+    0.149959788,
+    0.152926147,
+    0.155926466,
+    0.158960834,
+    0.162029371,
+    0.165132195,
+    0.168269396,
+    0.171441108,
+    0.174647406,
+    0.177888423,
+    0.18116425,
+    0.18447499,
+    0.187820777,
+    0.191201687,
+    0.194617838,
+    0.198069319,
+    0.20155625,
+    0.205078736,
+    0.208636865,
+    0.212230757,
+    0.215860501,
+    0.219526201,
+    0.223227963,
+    0.226965874,
+    0.230740055,
+    0.23455058,
+    0.238397568,
+    0.242281124,
+    0.246201321,
+    0.25015828,
+    0.254152089,
+    0.258182853,
+    0.262250662,
+    0.266355604,
+    0.270497799,
+    0.274677306,
+    0.278894275,
+    0.283148736,
+    0.287440836,
+    0.291770637,
+    0.296138257,
+    0.300543785,
+    0.304987311,
+    0.309468925,
+    0.313988715,
+    0.318546772,
+    0.323143214,
+    0.327778101,
+    0.332451522,
+    0.337163627,
+    0.341914415,
+    0.346704066,
+    0.351532608,
+    0.356400132,
+    0.361306787,
+    0.366252601,
+    0.371237695,
+    0.376262128,
+    0.38132602,
+    0.386429429,
+    0.391572475,
+    0.396755219,
+    0.401977777,
+    0.407240212,
+    0.412542611,
+    0.417885065,
+    0.423267663,
+    0.428690493,
+    0.434153646,
+    0.439657182,
+    0.445201188,
+    0.450785786,
+    0.456411034,
+    0.462076992,
+    0.467783809,
+    0.473531485,
+    0.479320168,
+    0.48514995,
+    0.491020858,
+    0.496932983,
+    0.502886474,
+    0.50888133,
+    0.514917672,
+    0.520995557,
+    0.527115107,
+    0.533276379,
+    0.539479494,
+    0.545724452,
+    0.55201143,
+    0.558340371,
+    0.564711511,
+    0.571124852,
+    0.577580452,
+    0.584078431,
+    0.590618849,
+    0.597201765,
+    0.603827357,
+    0.610495567,
+    0.617206573,
+    0.623960376,
+    0.630757153,
+    0.637596846,
+    0.644479692,
+    0.651405632,
+    0.658374846,
+    0.665387273,
+    0.672443151,
+    0.679542482,
+    0.686685324,
+    0.693871737,
+    0.701101899,
+    0.708375752,
+    0.715693474,
+    0.723055124,
+    0.730460763,
+    0.73791039,
+    0.745404184,
+    0.752942204,
+    0.760524511,
+    0.768151164,
+    0.775822222,
+    0.783537805,
+    0.791297913,
+    0.799102724,
+    0.806952238,
+    0.814846575,
+    0.822785735,
+    0.830769897,
+    0.838799,
+    0.846873224,
+    0.854992628,
+    0.863157213,
+    0.871367097,
+    0.8796224,
+    0.887923121,
+    0.896269381,
+    0.904661179,
+    0.913098633,
+    0.921581864,
+    0.930110872,
+    0.938685715,
+    0.947306514,
+    0.955973327,
+    0.964686275,
+    0.973445296,
+    0.982250571,
+    0.991102099,
+    1.0,
+];
+
+/// Converts a gamma-encoded sRGB component in the range `0.0..=1.0` into linear light.
+///
+/// # See Also
+/// * [`srgb_u8_to_linear`] for a faster version for 8-bit components.
+#[must_use]
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light component in the range `0.0..=1.0` into a gamma-encoded sRGB
+/// component.
+#[must_use]
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055f32.mul_add(c.powf(1. / 2.4), -0.055)
+    }
+}
+
+/// Converts an 8-bit gamma-encoded sRGB component into linear light in the range `0.0..=1.0`.
+/// This uses a lookup table, so it is much faster than [`srgb_to_linear`].
+#[must_use]
+pub fn srgb_u8_to_linear(c: u8) -> f32 {
+    SRGB_TO_LINEAR[c as usize]
+}
+
+/// Converts a linear light component into an 8-bit gamma-encoded sRGB component, clamping it into
+/// the range `0.0..=1.0` first.
+#[must_use]
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+pub fn linear_to_srgb_u8(c: f32) -> u8 {
+    (linear_to_srgb(c.clamp(0., 1.)) * 255.).round() as u8
+}
+
+/// Returns the angle of the given vector in degrees, wrapped into the range `0.0..360.0`.
+fn angle_degrees(y: f32, x: f32) -> f32 {
+    wrap_hue(y.atan2(x).to_degrees())
+}
+
+// The reference white of the D65 illuminant in CIE XYZ.
+const WHITE_X: f32 = 0.950_47;
+const WHITE_Z: f32 = 1.088_83;
+
+// Thresholds of the piecewise function used by CIELAB.
+const LAB_EPSILON: f32 = 6. / 29.;
+
+fn lab_f(t: f32) -> f32 {
+    if t > LAB_EPSILON * LAB_EPSILON * LAB_EPSILON {
+        t.cbrt()
+    } else {
+        t / (3. * LAB_EPSILON * LAB_EPSILON) + 4. / 29.
+    }
+}
+
+fn lab_f_inverse(t: f32) -> f32 {
+    if t > LAB_EPSILON {
+        t * t * t
+    } else {
+        3. * LAB_EPSILON * LAB_EPSILON * (t - 4. / 29.)
+    }
+}
+
+/// Represents a color in the CIELAB (L\*a\*b\*) color space, relative to the D65 white point.
+///
+/// CIELAB is designed to be perceptually uniform, meaning the distance between two colors
+/// roughly corresponds to how different they look. See [`Self::delta_e`] and
+/// [`Self::delta_e_2000`] for measuring this distance.
+///
+/// # See Also
+/// * [`Oklab`], a more modern perceptual color space with better hue uniformity.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lab {
+    /// The perceptual lightness of the color, between 0.0 and 100.0.
+    pub l: f32,
+    /// The position of the color between green (negative) and red (positive), usually between
+    /// -128.0 and 127.0.
+    pub a: f32,
+    /// The position of the color between blue (negative) and yellow (positive), usually between
+    /// -128.0 and 127.0.
+    pub b: f32,
+}
+
+impl Lab {
+    /// Creates a new CIELAB color.
+    #[must_use]
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    /// Returns the CIE76 color difference (ΔE\*ab) between this color and the given color, which
+    /// is their euclidean distance. A difference of about 2.3 is considered just noticeable.
+    ///
+    /// This is fast, but overestimates differences between saturated colors. Use
+    /// [`Self::delta_e_2000`] for a more accurate measure.
+    #[must_use]
+    pub fn delta_e(self, other: Self) -> f32 {
+        let (dl, da, db) = (self.l - other.l, self.a - other.a, self.b - other.b);
+
+        db.mul_add(db, dl.mul_add(dl, da * da)).sqrt()
+    }
+
+    /// Returns the CIEDE2000 color difference (ΔE\*00) between this color and the given color.
+    ///
+    /// This corrects the perceptual non-uniformities of [`Self::delta_e`], at the cost of being
+    /// considerably more expensive to compute.
+    #[must_use]
+    pub fn delta_e_2000(self, other: Self) -> f32 {
+        let pow25_7 = 25f32.powi(7);
+        let Self {
+            l: l1,
+            a: a1,
+            b: b1,
+        } = self;
+        let Self {
+            l: l2,
+            a: a2,
+            b: b2,
+        } = other;
+
+        let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.;
+        let c_bar7 = c_bar.powi(7);
+        let g = 0.5 * (1. - (c_bar7 / (c_bar7 + pow25_7)).sqrt());
+
+        let (a1, a2) = (a1 * (1. + g), a2 * (1. + g));
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+        let h1 = if c1 == 0. { 0. } else { angle_degrees(b1, a1) };
+        let h2 = if c2 == 0. { 0. } else { angle_degrees(b2, a2) };
+
+        let dl = l2 - l1;
+        let dc = c2 - c1;
+        let dh = if c1 * c2 == 0. {
+            0.
+        } else if h2 - h1 > 180. {
+            h2 - h1 - 360.
+        } else if h2 - h1 < -180. {
+            h2 - h1 + 360.
+        } else {
+            h2 - h1
+        };
+        let dh = 2. * (c1 * c2).sqrt() * (dh / 2.).to_radians().sin();
+
+        let l_bar = (l1 + l2) / 2.;
+        let c_bar = (c1 + c2) / 2.;
+        let h_bar = if c1 * c2 == 0. {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180. {
+            (h1 + h2) / 2.
+        } else if h1 + h2 < 360. {
+            (h1 + h2 + 360.) / 2.
+        } else {
+            (h1 + h2 - 360.) / 2.
+        };
+
+        let t = 1. - 0.17 * (h_bar - 30.).to_radians().cos()
+            + 0.24 * (2. * h_bar).to_radians().cos()
+            + 0.32 * (3. * h_bar + 6.).to_radians().cos()
+            - 0.20 * (4. * h_bar - 63.).to_radians().cos();
+        let d_theta = 30. * (-((h_bar - 275.) / 25.).powi(2)).exp();
+        let c_bar7 = c_bar.powi(7);
+        let r_c = 2. * (c_bar7 / (c_bar7 + pow25_7)).sqrt();
+
+        let l_offset = (l_bar - 50.).powi(2);
+        let s_l = 1. + 0.015 * l_offset / (20. + l_offset).sqrt();
+        let s_c = 1. + 0.045 * c_bar;
+        let s_h = 1. + 0.015 * c_bar * t;
+        let r_t = -(2. * d_theta).to_radians().sin() * r_c;
+
+        let (l, c, h) = (dl / s_l, dc / s_c, dh / s_h);
+        (l * l + c * c + h * h + r_t * c * h).sqrt()
+    }
+}
+
+impl From<Rgb32F> for Lab {
+    fn from(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+        let x = 0.180_437_5f32.mul_add(b, 0.412_456_4f32.mul_add(r, 0.357_576_1 * g));
+        let y = 0.072_175f32.mul_add(b, 0.212_672_9f32.mul_add(r, 0.715_152_2 * g));
+        let z = 0.950_304_1f32.mul_add(b, 0.019_333_9f32.mul_add(r, 0.119_192 * g));
+
+        let (fx, fy, fz) = (lab_f(x / WHITE_X), lab_f(y), lab_f(z / WHITE_Z));
+
+        Self::new(116f32.mul_add(fy, -16.), 500. * (fx - fy), 200. * (fy - fz))
+    }
+}
+
+impl From<Lab> for Rgb32F {
+    fn from(Lab { l, a, b }: Lab) -> Self {
+        let fy = (l + 16.) / 116.;
+        let x = lab_f_inverse(fy + a / 500.) * WHITE_X;
+        let y = lab_f_inverse(fy);
+        let z = lab_f_inverse(fy - b / 200.) * WHITE_Z;
+
+        let r = (-0.498_531_4f32).mul_add(z, 3.240_454_2f32.mul_add(x, -1.537_138_5 * y));
+        let g = 0.041_556f32.mul_add(z, (-0.969_266f32).mul_add(x, 1.876_010_8 * y));
+        let b = 1.057_225_2f32.mul_add(z, 0.055_643_4f32.mul_add(x, -0.204_025_9 * y));
+
+        Self::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+    }
+}
+
+/// Represents a color in the Oklab color space.
+///
+/// Oklab is a perceptual color space which predicts lightness, chroma, and hue more uniformly
+/// than CIELAB. Interpolating between colors in Oklab avoids the muddy or grayish midpoints of
+/// interpolating in RGB, and [`Self::distance`] is a simple yet good measure of how different two
+/// colors look.
+///
+/// # See Also
+/// * [`Oklch`], the polar form of this color space.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklab {
+    /// The perceptual lightness of the color, between 0.0 and 1.0.
+    pub l: f32,
+    /// The position of the color between green (negative) and red (positive), usually between
+    /// -0.4 and 0.4.
+    pub a: f32,
+    /// The position of the color between blue (negative) and yellow (positive), usually between
+    /// -0.4 and 0.4.
+    pub b: f32,
+}
+
+impl Oklab {
+    /// Creates a new Oklab color.
+    #[must_use]
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    /// Returns the euclidean distance between this color and the given color, also known as
+    /// ΔEOK.
+    #[must_use]
+    pub fn distance(self, other: Self) -> f32 {
+        let (dl, da, db) = (self.l - other.l, self.a - other.a, self.b - other.b);
+
+        db.mul_add(db, dl.mul_add(dl, da * da)).sqrt()
+    }
+
+    /// Linearly interpolates between this color and the given color, where `t` is between `0.0`
+    /// (this color) and `1.0` (the other color).
+    #[must_use]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(
+            (other.l - self.l).mul_add(t, self.l),
+            (other.a - self.a).mul_add(t, self.a),
+            (other.b - self.b).mul_add(t, self.b),
+        )
+    }
+}
+
+impl From<Rgb32F> for Oklab {
+    fn from(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+        let l = 0.051_445_99f32.mul_add(b, 0.412_221_46f32.mul_add(r, 0.536_332_55 * g));
+        let m = 0.107_396_96f32.mul_add(b, 0.211_903_5f32.mul_add(r, 0.680_699_5 * g));
+        let s = 0.629_978_7f32.mul_add(b, 0.088_302_46f32.mul_add(r, 0.281_718_85 * g));
+        let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+        Self::new(
+            (-0.004_072_047f32).mul_add(s, 0.210_454_26f32.mul_add(l, 0.793_617_8 * m)),
+            0.450_593_7f32.mul_add(s, 1.977_998_5f32.mul_add(l, -2.428_592_2 * m)),
+            (-0.808_675_77f32).mul_add(s, 0.025_904_037f32.mul_add(l, 0.782_771_77 * m)),
+        )
+    }
+}
+
+impl From<Oklab> for Rgb32F {
+    fn from(Oklab { l, a, b }: Oklab) -> Self {
+        let l_ = 0.215_803_76f32.mul_add(b, 0.396_337_78f32.mul_add(a, l));
+        let m_ = (-0.063_854_17f32).mul_add(b, (-0.105_561_346f32).mul_add(a, l));
+        let s_ = (-1.291_485_5f32).mul_add(b, (-0.089_484_18f32).mul_add(a, l));
+        let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+        let r = 0.230_969_94f32.mul_add(s, 4.076_741_7f32.mul_add(l, -3.307_711_6 * m));
+        let g = (-0.341_319_4f32).mul_add(s, (-1.268_438f32).mul_add(l, 2.609_757_4 * m));
+        let b = 1.707_614_7f32.mul_add(s, (-0.004_196_086_3f32).mul_add(l, -0.703_418_6 * m));
+
+        Self::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+    }
+}
+
+/// Represents a color in the Oklch color space, which is the polar form of [`Oklab`].
+///
+/// This is useful for perceptually uniform adjustments of chroma (colorfulness) and hue.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oklch {
+    /// The perceptual lightness of the color, between 0.0 and 1.0.
+    pub l: f32,
+    /// The chroma, or colorfulness of the color, usually between 0.0 and 0.4.
+    pub c: f32,
+    /// The hue of the color in degrees, between 0.0 and 360.0.
+    pub h: f32,
+}
+
+impl Oklch {
+    /// Creates a new Oklch color. The hue is wrapped into the range `0.0..360.0`.
+    #[must_use]
+    pub fn new(l: f32, c: f32, h: f32) -> Self {
+        Self {
+            l,
+            c,
+            h: wrap_hue(h),
+        }
+    }
+
+    /// Rotates the hue of this color by the given amount of degrees.
+    pub fn hue_rotate(&mut self, degrees: f32) {
+        self.h = wrap_hue(self.h + degrees);
+    }
+
+    /// Takes this color and rotates its hue by the given amount of degrees. Useful for method
+    /// chaining.
+    #[must_use]
+    pub fn hue_rotated(mut self, degrees: f32) -> Self {
+        self.hue_rotate(degrees);
+        self
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(Oklab { l, a, b }: Oklab) -> Self {
+        Self::new(l, a.hypot(b), angle_degrees(b, a))
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(Oklch { l, c, h }: Oklch) -> Self {
+        let (sin, cos) = h.to_radians().sin_cos();
+
+        Self::new(l, c * cos, c * sin)
+    }
+}
+
+impl From<Rgb32F> for Oklch {
+    fn from(rgb: Rgb32F) -> Self {
+        Oklab::from(rgb).into()
+    }
+}
+
+impl From<Oklch> for Rgb32F {
+    fn from(color: Oklch) -> Self {
+        Oklab::from(color).into()
+    }
+}
+
+// Conversions from other pixel types are performed through `Rgb32F`. Alpha is discarded when
+// converting into a color, and is fully opaque when converting back.
+macro_rules! impl_color_cast {
+    ($($t:ident),+) => {
+        $(
+            impl From<Rgb> for $t {
+                fn from(rgb: Rgb) -> Self {
+                    Rgb32F::from(rgb).into()
+                }
+            }
+
+            impl From<Rgba> for $t {
+                fn from(rgba: Rgba) -> Self {
+                    Rgb32F::from(rgba).into()
+                }
+            }
+
+            impl From<Rgba32F> for $t {
+                fn from(rgba: Rgba32F) -> Self {
+                    Rgb32F::from(rgba).into()
+                }
+            }
+
+            impl From<$t> for Rgb {
+                fn from(color: $t) -> Self {
+                    Rgb32F::from(color).into()
+                }
+            }
+
+            impl From<$t> for Rgba {
+                fn from(color: $t) -> Self {
+                    Rgb32F::from(color).into()
+                }
+            }
+
+            impl From<$t> for Rgba32F {
+                fn from(color: $t) -> Self {
+                    Rgb32F::from(color).into()
+                }
+            }
+        )+
+    };
+}
+
+impl_color_cast!(Hsl, Hsv, Lab, Oklab, Oklch);
+
+// Implements `Pixel` for the given color types. Images of them are encoded as RGB, since their
+// color type is custom, and blending and arithmetic are performed in RGB.
+macro_rules! impl_color_pixel {
+    ($($t:ident { $x:ident, $y:ident, $z:ident } => $hue:expr),+) => {
+        $(
+            impl PartialEq for $t {
+                fn eq(&self, other: &Self) -> bool {
+                    self.as_bytes() == other.as_bytes()
+                }
+            }
+
+            impl Eq for $t {}
+
+            impl Hash for $t {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.as_bytes().hash(state);
+                }
+            }
+
+            impl Pixel for $t {
+                const COLOR_TYPE: ColorType = ColorType::Custom(3);
+                const BIT_DEPTH: u8 = 32;
+                const HUE_CHANNEL: Option<usize> = $hue;
+
+                type Subpixel = f32;
+                type Color = Self;
+                type Data = [u8; 12];
+
+                fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+                where
+                    F: Fn(Self::Subpixel) -> Self::Subpixel,
+                    A: Fn(Self::Subpixel) -> Self::Subpixel,
+                {
+                    Self {
+                        $x: f(self.$x),
+                        $y: f(self.$y),
+                        $z: f(self.$z),
+                    }
+                }
+
+                fn channel(&self, index: usize) -> Self::Subpixel {
+                    [self.$x, self.$y, self.$z][index]
+                }
+
+                fn from_raw_parts_paletted<P: Pixel>(
+                    color_type: ColorType,
+                    bit_depth: u8,
+                    data: &[u8],
+                    palette: Option<&[P]>,
+                ) -> Result<Self> {
+                    if color_type == Self::COLOR_TYPE && bit_depth == Self::BIT_DEPTH {
+                        return Ok(Self::from_bytes(data));
+                    }
+
+                    Rgb32F::from_raw_parts_paletted(color_type, bit_depth, data, palette)
+                        .map(Self::from)
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Self {
+                    let c = |i: usize| {
+                        f32::from_ne_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+                    };
+
+                    Self {
+                        $x: c(0),
+                        $y: c(4),
+                        $z: c(8),
+                    }
+                }
+
+                fn as_bytes(&self) -> Self::Data {
+                    let mut bytes = [0; 12];
+                    for (chunk, c) in bytes.chunks_exact_mut(4).zip([self.$x, self.$y, self.$z]) {
+                        chunk.copy_from_slice(&c.to_ne_bytes());
+                    }
+
+                    bytes
+                }
+
+                fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+                    Rgb32F::from(self)
+                        .merge_with_alpha(other.into(), alpha)
+                        .into()
+                }
+
+                fn blend(self, other: Self, mode: BlendMode) -> Self {
+                    Rgb32F::from(self).blend(other.into(), mode).into()
+                }
+
+                fn from_dynamic(dynamic: Dynamic) -> Self {
+                    Rgb::from_dynamic(dynamic).into()
+                }
+
+                fn max(self, other: Self) -> Self {
+                    Self {
+                        $x: self.$x.max(other.$x),
+                        $y: self.$y.max(other.$y),
+                        $z: self.$z.max(other.$z),
+                    }
+                }
+
+                fn min(self, other: Self) -> Self {
+                    Self {
+                        $x: self.$x.min(other.$x),
+                        $y: self.$y.min(other.$y),
+                        $z: self.$z.min(other.$z),
+                    }
+                }
+
+                fn as_rgb(&self) -> Rgb {
+                    (*self).into()
+                }
+
+                fn as_rgba(&self) -> Rgba {
+                    (*self).into()
+                }
+            }
+
+            impl Not for $t {
+                type Output = Self;
+
+                fn not(self) -> Self::Output {
+                    (!Rgb32F::from(self)).into()
+                }
+            }
+
+            impl Mul<f32> for $t {
+                type Output = Self;
+
+                fn mul(self, rhs: f32) -> Self::Output {
+                    (Rgb32F::from(self) * rhs).into()
+                }
+            }
+
+            impl Mul<$t> for $t {
+                type Output = Self;
+
+                fn mul(self, rhs: $t) -> Self::Output {
+                    (Rgb32F::from(self) * Rgb32F::from(rhs)).into()
+                }
+            }
+        )+
+    };
+}
+
+impl_color_pixel!(
+    Hsl { h, s, l } => Some(0),
+    Hsv { h, s, v } => Some(0),
+    Lab { l, a, b } => None
+);
+
+impl Modulate for Rgb {
+    /// Modulates this pixel in the HSV color space. The hue is rotated by `hue` degrees, while
+    /// saturation and value are multiplied by `saturation` and `brightness` respectively.
+    fn modulate(self, hue: f64, saturation: f64, brightness: f64) -> Self {
+        let hsv = Hsv::from(self).hue_rotated(hue as f32);
+
+        Hsv::new(hsv.h, hsv.s * saturation as f32, hsv.v * brightness as f32).into()
+    }
+}
+
+impl Modulate for Hsl {
+    /// Rotates the hue of this color by `hue` degrees, while saturation and lightness are
+    /// multiplied by `saturation` and `brightness` respectively.
+    fn modulate(self, hue: f64, saturation: f64, brightness: f64) -> Self {
+        Self::new(
+            self.h + hue as f32,
+            self.s * saturation as f32,
+            self.l * brightness as f32,
+        )
+    }
+}
+
+impl Modulate for Hsv {
+    /// Rotates the hue of this color by `hue` degrees, while saturation and value are multiplied
+    /// by `saturation` and `brightness` respectively.
+    fn modulate(self, hue: f64, saturation: f64, brightness: f64) -> Self {
+        Self::new(
+            self.h + hue as f32,
+            self.s * saturation as f32,
+            self.v * brightness as f32,
+        )
+    }
+}
+
+impl Modulate for Lab {
+    /// Rotates the hue of this color, i.e. its `a` and `b` components, by `hue` degrees, while its
+    /// chroma and lightness are multiplied by `saturation` and `brightness` respectively.
+    fn modulate(self, hue: f64, saturation: f64, brightness: f64) -> Self {
+        let (sin, cos) = (hue as f32).to_radians().sin_cos();
+        let saturation = saturation as f32;
+
+        Self::new(
+            (self.l * brightness as f32).clamp(0., 100.),
+            self.a.mul_add(cos, -self.b * sin) * saturation,
+            self.a.mul_add(sin, self.b * cos) * saturation,
+        )
+    }
+}
+
+impl Modulate for Rgba {
+    /// Modulates this pixel in the HSV color space, leaving alpha untouched. See
+    /// [`Rgb::modulate`][Modulate::modulate] for more information.
+    fn modulate(self, hue: f64, saturation: f64, brightness: f64) -> Self {
+        let Rgb { r, g, b } = Rgb::from(self).modulate(hue, saturation, brightness);
+
+        Self::new(r, g, b, self.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srgb_lookup_table() {
+        for c in 0..=255 {
+            let expected = srgb_to_linear(f32::from(c) / 255.);
+            assert!((srgb_u8_to_linear(c) - expected).abs() < 1e-6);
+            assert_eq!(linear_to_srgb_u8(srgb_u8_to_linear(c)), c);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Parsing of the `COLR` and `CPAL` tables of OpenType fonts, which describe color glyphs (most
+//! commonly emoji) as a stack of regular outline glyphs, each filled with a color from a palette.
+//!
+//! Only version 0 of `COLR` is supported, which is also present at the start of version 1 tables.
+
+use crate::pixel::Rgba;
+use alloc::vec::Vec;
+use std::collections::HashMap;
+
+/// A single layer of a color glyph: the glyph index of its outline and its color. A color of
+/// `None` means the layer is drawn with the fill of the text instead.
+pub(crate) type ColorLayer = (u16, Option<Rgba>);
+
+/// The color glyphs of a font.
+#[derive(Clone)]
+pub(crate) struct ColorGlyphs {
+    /// Maps base glyph indices to the range of their layers in `layers`.
+    glyphs: HashMap<u16, (usize, usize)>,
+    layers: Vec<ColorLayer>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Returns the contents of the table with the given tag in the font at the given index of the
+/// font file (which may be a font collection).
+fn find_table<'a>(data: &'a [u8], index: u32, tag: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut directory = 0;
+    if data.get(..4)? == b"ttcf" {
+        directory = read_u32(data, 12 + 4 * index as usize)? as usize;
+    }
+
+    let tables = read_u16(data, directory + 4)? as usize;
+    (0..tables)
+        .map(|i| directory + 12 + 16 * i)
+        .find(|&record| data.get(record..record + 4) == Some(&tag[..]))
+        .and_then(|record| {
+            let offset = read_u32(data, record + 8)? as usize;
+            let length = read_u32(data, record + 12)? as usize;
+            data.get(offset..offset.checked_add(length)?)
+        })
+}
+
+/// Reads the colors of the first palette of the given `CPAL` table.
+fn read_palette(cpal: &[u8]) -> Option<Vec<Rgba>> {
+    let entries = read_u16(cpal, 2)? as usize;
+    let records = read_u32(cpal, 8)? as usize;
+    let first = read_u16(cpal, 12)? as usize;
+
+    (0..entries)
+        .map(|i| {
+            // Colors are stored in BGRA order
+            let offset = records + 4 * (first + i);
+            cpal.get(offset..offset + 4)
+                .map(|bgra| Rgba::new(bgra[2], bgra[1], bgra[0], bgra[3]))
+        })
+        .collect()
+}
+
+impl ColorGlyphs {
+    /// Parses the color glyphs of the font at the given collection index of the given font file.
+    /// Returns `None` if the font has no (valid) color glyphs.
+    pub fn parse(data: &[u8], index: u32) -> Option<Self> {
+        let colr = find_table(data, index, b"COLR")?;
+        let palette = find_table(data, index, b"CPAL").and_then(read_palette)?;
+
+        let base_count = read_u16(colr, 2)? as usize;
+        let base_offset = read_u32(colr, 4)? as usize;
+        let layer_offset = read_u32(colr, 8)? as usize;
+        let layer_count = read_u16(colr, 12)? as usize;
+
+        let layers = (0..layer_count)
+            .map(|i| {
+                let offset = layer_offset + 4 * i;
+                let glyph = read_u16(colr, offset)?;
+                let color = read_u16(colr, offset + 2)?;
+                // 0xFFFF is reserved for the foreground color of the text
+                Some((glyph, palette.get(color as usize).copied()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let glyphs = (0..base_count)
+            .filter_map(|i| {
+                let offset = base_offset + 6 * i;
+                let glyph = read_u16(colr, offset)?;
+                let first = read_u16(colr, offset + 2)? as usize;
+                let count = read_u16(colr, offset + 4)? as usize;
+
+                if count > 0 && first + count <= layers.len() {
+                    Some((glyph, (first, count)))
+                } else {
+                    None
+                }
+            })
+            .collect::<HashMap<_, _>>();
+
+        if glyphs.is_empty() {
+            return None;
+        }
+        Some(Self { glyphs, layers })
+    }
+
+    /// Returns the layers of the glyph with the given index, from bottom to top, or `None` if it
+    /// is not a color glyph.
+    pub fn layers(&self, glyph: u16) -> Option<&[ColorLayer]> {
+        self.glyphs
+            .get(&glyph)
+            .map(|&(first, count)| &self.layers[first..first + count])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorGlyphs;
+    use crate::pixel::Rgba;
+
+    #[test]
+    fn test_parse_color_glyphs() {
+        // A font with a single color glyph (5) made of two layers, the second of which uses the
+        // foreground color
+        let colr = [
+            &[0, 0, 0, 1, 0, 0, 0, 14, 0, 0, 0, 20, 0, 2][..],
+            &[0, 5, 0, 0, 0, 2],
+            &[0, 6, 0, 0, 0, 7, 0xFF, 0xFF],
+        ]
+        .concat();
+        let cpal = [0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 14, 0, 0, 30, 20, 10, 255];
+
+        let mut font = vec![0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0];
+        for (tag, offset, table) in [(b"COLR", 44, &colr[..]), (b"CPAL", 44 + colr.len(), &cpal)] {
+            font.extend_from_slice(tag);
+            font.extend_from_slice(&[0; 4]);
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        }
+        font.extend_from_slice(&colr);
+        font.extend_from_slice(&cpal);
+
+        let glyphs = ColorGlyphs::parse(&font, 0).unwrap();
+        assert_eq!(
+            glyphs.layers(5).unwrap(),
+            [(6, Some(Rgba::new(10, 20, 30, 255))), (7, None)]
+        );
+        assert!(glyphs.layers(6).is_none());
+        assert!(ColorGlyphs::parse(&font[..40], 0).is_none());
+    }
+}
@@ -1,10 +1,14 @@
 //! Encloses most drawing implementations and drawable objects.
 
 use crate::{
-    fill::{Fill, IntoFill},
-    BitPixel, Image, OverlayMode, Pixel,
+    fill::{BoundingBox, Fill, IntoFill, SolidFill},
+    image::{copy_rebound, rebind_pixel},
+    raster, BitPixel, Image, ImageViewMut, OutOfBounds, OverlayMode, Pixel, Rect, Scratch, L,
 };
-use std::ops::DerefMut;
+use alloc::{vec, vec::Vec};
+use core::ops::DerefMut;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 /// A common trait for all objects able to be drawn on an image.
 ///
@@ -12,6 +16,159 @@ use std::ops::DerefMut;
 pub trait Draw<P: Pixel> {
     /// Draws the object to the given image.
     fn draw<I: DerefMut<Target = Image<P>>>(&self, image: I);
+
+    /// Draws the object to the given image, borrowing any temporary buffers it needs from the
+    /// given scratch instead of allocating them. By default, this is the same as [`Self::draw`].
+    ///
+    /// See the [`scratch`](crate::scratch) module for more information.
+    fn draw_with_scratch<I: DerefMut<Target = Image<P>>>(
+        &self,
+        image: I,
+        scratch: &mut Scratch<P>,
+    ) {
+        let _ = scratch;
+        self.draw(image);
+    }
+
+    /// Draws the object to the given view, with coordinates relative to the view. Anything outside
+    /// of the view is clipped.
+    ///
+    /// By default, the region is copied into a temporary image which is drawn onto and then copied
+    /// back. Shapes override this to draw straight onto the view through [`DrawTarget`].
+    fn draw_view(&self, view: &mut ImageViewMut<'_, P>) {
+        let mut region = view.as_view().to_image();
+        self.draw(&mut region);
+        view.copy_from_image(&region);
+    }
+}
+
+/// A surface which shapes and fills can be plotted onto directly, which is either a whole
+/// [`Image`] or an [`ImageViewMut`] into a region of one.
+///
+/// Coordinates are relative to the top-left corner of the surface, and pixels plotted outside of
+/// it are clipped.
+pub trait DrawTarget<P: Pixel> {
+    /// Returns the dimensions of this surface as `(width, height)`.
+    fn dimensions(&self) -> (u32, u32);
+
+    /// Returns the overlay mode used for objects which do not specify their own.
+    fn overlay_mode(&self) -> OverlayMode;
+
+    /// Returns a reference to the pixel at the given coordinates, but only if it exists.
+    fn get_pixel(&self, x: u32, y: u32) -> Option<&P>;
+
+    /// Returns a mutable reference to the pixel at the given coordinates, but only if it exists.
+    fn get_pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut P>;
+
+    /// Returns the width of this surface.
+    fn width(&self) -> u32 {
+        self.dimensions().0
+    }
+
+    /// Returns the height of this surface.
+    fn height(&self) -> u32 {
+        self.dimensions().1
+    }
+
+    /// Overlays the pixel at the given coordinates with the given pixel according to the given
+    /// overlay mode. Nothing occurs if the coordinates are out of bounds.
+    fn overlay_pixel_with_mode(&mut self, x: u32, y: u32, pixel: P, mode: OverlayMode) {
+        if let Some(target) = self.get_pixel_mut(x, y) {
+            *target = target.overlay(pixel, mode);
+        }
+    }
+
+    /// Overlays the pixel at the given coordinates with the given pixel and alpha according to the
+    /// given overlay mode. Nothing occurs if the coordinates are out of bounds.
+    fn overlay_pixel_with_alpha(&mut self, x: u32, y: u32, pixel: P, mode: OverlayMode, alpha: u8) {
+        if let Some(target) = self.get_pixel_mut(x, y) {
+            *target = target.overlay_with_alpha(pixel, mode, alpha);
+        }
+    }
+}
+
+impl<P: Pixel> DrawTarget<P> for Image<P> {
+    fn dimensions(&self) -> (u32, u32) {
+        self.dimensions()
+    }
+
+    fn overlay_mode(&self) -> OverlayMode {
+        self.overlay
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Option<&P> {
+        self.get_pixel(x, y)
+    }
+
+    fn get_pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut P> {
+        self.try_pixel_mut(x, y).ok()
+    }
+
+    fn overlay_pixel_with_mode(&mut self, x: u32, y: u32, pixel: P, mode: OverlayMode) {
+        self.overlay_pixel_with_mode(x, y, pixel, mode);
+    }
+
+    fn overlay_pixel_with_alpha(&mut self, x: u32, y: u32, pixel: P, mode: OverlayMode, alpha: u8) {
+        self.overlay_pixel_with_alpha(x, y, pixel, mode, alpha);
+    }
+}
+
+impl<P: Pixel> DrawTarget<P> for ImageViewMut<'_, P> {
+    fn dimensions(&self) -> (u32, u32) {
+        self.dimensions()
+    }
+
+    fn overlay_mode(&self) -> OverlayMode {
+        self.as_view().image().overlay_mode()
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Option<&P> {
+        (x < self.width() && y < self.height()).then(|| self.pixel(x, y))
+    }
+
+    fn get_pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut P> {
+        if x < self.width() && y < self.height() {
+            Some(self.pixel_mut(x, y))
+        } else {
+            None
+        }
+    }
+}
+
+/// An extension trait for drawing multiple objects onto an image fluently.
+///
+/// This is implemented for both owned images and mutable references to images, so objects can be
+/// drawn in a chain whether or not the image is owned. For owned images, this is equivalent to
+/// [`Image::with`].
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// fn decorate(image: &mut Image<Rgb>) {
+///     image
+///         .with(&Rectangle::from_bounding_box(0, 0, 64, 64).with_fill(Rgb::black()))
+///         .with(&Ellipse::circle(32, 32, 16).with_fill(Rgb::white()));
+/// }
+///
+/// let mut image = Image::new(64, 64, Rgb::white());
+/// decorate(&mut image);
+/// ```
+pub trait DrawExt<P: Pixel>: Sized {
+    /// Draws the given object or shape onto this image and returns it, for chaining.
+    fn with(self, entity: &impl Draw<P>) -> Self;
+}
+
+impl<P: Pixel> DrawExt<P> for Image<P> {
+    fn with(self, entity: &impl Draw<P>) -> Self {
+        Image::with(self, entity)
+    }
+}
+
+impl<P: Pixel> DrawExt<P> for &mut Image<P> {
+    fn with(self, entity: &impl Draw<P>) -> Self {
+        entity.draw(&mut *self);
+        self
+    }
 }
 
 /// Represents whether a border is inset, outset, or if it lays in the center.
@@ -128,6 +285,10 @@ pub struct Line<F: IntoFill> {
     /// Whether the line should be antialiased. Note that drawing antialiased lines is slower than
     /// drawing non-antialiased lines. Defaults to `false`.
     pub antialiased: bool,
+    /// The start and end points of the line with sub-pixel precision, or `None` to use
+    /// [`start`][Self::start] and [`end`][Self::end]. This is only taken into account when drawing
+    /// antialiased lines with a thickness of 1. Defaults to `None`.
+    pub subpixel_endpoints: Option<((f32, f32), (f32, f32))>,
     /// Whether the endpoints of the line should be "rounded off" with circles. Defaults to `false`.
     /// Currently, endpoints are not antialiased, regardless of the value of `antialiased`.
     ///
@@ -142,12 +303,13 @@ pub struct Line<F: IntoFill> {
 #[inline]
 unsafe fn _unsafe_default_fields<F: IntoFill>() -> Line<F> {
     Line {
-        color: std::mem::zeroed::<F>().into_fill(),
+        color: core::mem::zeroed::<F>().into_fill(),
         mode: None,
         thickness: 1,
         start: (0, 0),
         end: (0, 0),
         antialiased: false,
+        subpixel_endpoints: None,
         rounded: false,
         position: BorderPosition::Center,
     }
@@ -195,17 +357,18 @@ impl<F: IntoFill> Line<F> {
         self
     }
 
-    #[allow(clippy::missing_const_for_fn)]
-    pub(crate) fn with_fill_color(mut self, color: F::Fill) -> Self {
-        self.color = color;
+    /// Sets the overlay mode of the line.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.mode = Some(mode);
         self
     }
 
     /// Sets the overlay mode of the line.
     #[must_use]
-    pub const fn with_mode(mut self, mode: OverlayMode) -> Self {
-        self.mode = Some(mode);
-        self
+    #[deprecated(note = "use `with_overlay_mode` instead")]
+    pub const fn with_mode(self, mode: OverlayMode) -> Self {
+        self.with_overlay_mode(mode)
     }
 
     /// Sets the thickness of the line.
@@ -219,6 +382,9 @@ impl<F: IntoFill> Line<F> {
     #[must_use]
     pub fn with_start(mut self, x: u32, y: u32) -> Self {
         self.start = (x, y);
+        if let Some((ref mut start, _)) = self.subpixel_endpoints {
+            *start = (x as f32, y as f32);
+        }
         self.update_bounding_box();
         self
     }
@@ -227,10 +393,37 @@ impl<F: IntoFill> Line<F> {
     #[must_use]
     pub fn with_end(mut self, x: u32, y: u32) -> Self {
         self.end = (x, y);
+        if let Some((_, ref mut end)) = self.subpixel_endpoints {
+            *end = (x as f32, y as f32);
+        }
         self.update_bounding_box();
         self
     }
 
+    /// Sets the start and end coordinates of the line with sub-pixel precision, where integer
+    /// coordinates lie on the center of a pixel.
+    ///
+    /// This also sets [`start`][Self::start] and [`end`][Self::end] to the rounded coordinates and
+    /// enables antialiasing, since sub-pixel precision only affects antialiased lines.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(32, 32, Rgba::black());
+    /// image.draw(&Line::new((0, 0), (0, 0), Rgba::white()).with_subpixel_endpoints((2.5, 4.25), (29.75, 20.5)));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn with_subpixel_endpoints(mut self, start: (f32, f32), end: (f32, f32)) -> Self {
+        let round = |(x, y): (f32, f32)| (x.round().max(0.0) as u32, y.round().max(0.0) as u32);
+
+        self.start = round(start);
+        self.end = round(end);
+        self.subpixel_endpoints = Some((start, end));
+        self.update_bounding_box();
+        self.with_antialiased(true)
+    }
+
     /// Sets whether the line should be antialiased. If this is set to `true`, the overlay
     /// mode of this line will also be set to [`OverlayMode::Merge`].
     #[must_use]
@@ -256,32 +449,33 @@ impl<F: IntoFill> Line<F> {
         self
     }
 
-    fn plot_endpoints(&self, image: &mut Image<F::Pixel>) {
+    fn plot_endpoints(&self, image: &mut impl DrawTarget<F::Pixel>) {
         if self.rounded && self.thickness > 1 {
             let (x1, y1) = self.start;
             let (x2, y2) = self.end;
-            let mut reference = Ellipse::<F>::circle(x1, y1, self.thickness / 2)
-                .with_fill_color(self.color.clone());
+            let mut reference =
+                Ellipse::<F>::circle(i64::from(x1), i64::from(y1), self.thickness / 2)
+                    .with_fill_color(self.color.clone())
+                    .with_antialiased(false);
 
             if let Some(mode) = self.mode {
                 reference = reference.with_overlay_mode(mode);
             }
 
-            image.draw(&reference);
-            image.draw(
-                &reference
-                    .with_position(x2, y2)
-                    .with_fill_color(self.color.clone()),
-            );
+            reference.render(image);
+            reference
+                .with_position(i64::from(x2), i64::from(y2))
+                .with_fill_color(self.color.clone())
+                .render(image);
         }
     }
 
     // assumes that `x1 == x2 || y1 == y2`
-    fn plot_perfect_line(&self, image: &mut Image<F::Pixel>) {
-        let (mut x1, mut y1) = self.start;
-        let (mut x2, mut y2) = self.end;
-        let adjustment = self.thickness / 2;
-        let difference = self.thickness - adjustment;
+    fn plot_perfect_line(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let (mut x1, mut y1) = (i64::from(self.start.0), i64::from(self.start.1));
+        let (mut x2, mut y2) = (i64::from(self.end.0), i64::from(self.end.1));
+        let adjustment = i64::from(self.thickness / 2);
+        let difference = i64::from(self.thickness) - adjustment;
 
         // vertical line, adjust horizontal
         if x1 == x2 {
@@ -300,7 +494,7 @@ impl<F: IntoFill> Line<F> {
             rect = rect.with_overlay_mode(mode);
         }
 
-        image.draw(&rect);
+        rect.render(image);
     }
 
     #[inline]
@@ -311,20 +505,20 @@ impl<F: IntoFill> Line<F> {
         // absolute slope is greater than 1, optimize by swapping x and y
         let swapped = y1.abs_diff(y2) > x1.abs_diff(x2);
         if swapped {
-            std::mem::swap(&mut x1, &mut y1);
-            std::mem::swap(&mut x2, &mut y2);
+            core::mem::swap(&mut x1, &mut y1);
+            core::mem::swap(&mut x2, &mut y2);
         }
 
         // swap start and end if necessary, this preserves the order and prevents underflow
         if x1 > x2 {
-            std::mem::swap(&mut x1, &mut x2);
-            std::mem::swap(&mut y1, &mut y2);
+            core::mem::swap(&mut x1, &mut x2);
+            core::mem::swap(&mut y1, &mut y2);
         }
 
         (swapped, x1, y1, x2, y2)
     }
 
-    fn draw_thin_line(&self, image: &mut Image<F::Pixel>) {
+    fn draw_thin_line(&self, image: &mut impl DrawTarget<F::Pixel>) {
         let (swapped, mut x1, y1, x2, y2) = self.setup_points();
 
         let dx = (x2 - x1) as f32;
@@ -333,7 +527,7 @@ impl<F: IntoFill> Line<F> {
 
         let mut y = y1 as i32;
         let y_step = if y1 < y2 { 1 } else { -1 };
-        let overlay = self.mode.unwrap_or(image.overlay);
+        let overlay = self.mode.unwrap_or(image.overlay_mode());
 
         while x1 <= x2 {
             x1 += 1;
@@ -352,58 +546,85 @@ impl<F: IntoFill> Line<F> {
         }
     }
 
-    fn draw_antialiased_line(&self, image: &mut Image<F::Pixel>) {
-        let (swapped, x1_u, y1, x2_u, y2) = self.setup_points();
-        let (x1, mut y1, x2, y2) = (x1_u as f32, y1 as f32, x2_u as f32, y2 as f32);
+    /// Draws the line using Xiaolin Wu's line algorithm. Endpoints are only partially covered when
+    /// sub-pixel endpoints are given.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    fn draw_antialiased_line(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        #[inline]
+        fn fpart(x: f32) -> f32 {
+            x - x.floor()
+        }
+
+        let ((mut x1, mut y1), (mut x2, mut y2)) = self.subpixel_endpoints.unwrap_or((
+            (self.start.0 as f32, self.start.1 as f32),
+            (self.end.0 as f32, self.end.1 as f32),
+        ));
+
+        // absolute slope is greater than 1, optimize by swapping x and y
+        let swapped = (y2 - y1).abs() > (x2 - x1).abs();
+        if swapped {
+            core::mem::swap(&mut x1, &mut y1);
+            core::mem::swap(&mut x2, &mut y2);
+        }
+        if x1 > x2 {
+            core::mem::swap(&mut x1, &mut x2);
+            core::mem::swap(&mut y1, &mut y2);
+        }
 
         let dx = x2 - x1;
-        let gradient = if dx == 0.0 {
-            1.0
-        } else {
-            // slope
-            (y2 - y1) / dx
+        let gradient = if dx == 0.0 { 1.0 } else { (y2 - y1) / dx };
+        let overlay = self.mode.unwrap_or(image.overlay_mode());
+        let subpixel = self.subpixel_endpoints.is_some();
+
+        let mut plot = |x: f32, y: f32, coverage: f32| {
+            let (x, y) = if swapped { (y, x) } else { (x, y) };
+            if x < 0.0 || y < 0.0 || coverage <= 0.0 {
+                return;
+            }
+
+            let alpha = (coverage.min(1.0) * 255.0).round() as u8;
+            self.color
+                .plot_with_alpha(image, x as u32, y as u32, overlay, alpha);
         };
 
-        let mut x = x1_u;
-        let mut lower = false;
-        let overlay = self.mode.unwrap_or(image.overlay);
+        // Plot both endpoints, which may only partially cover their pixels horizontally.
+        let mut endpoint = |x: f32, y: f32, gap: f32| {
+            let px = x.round();
+            let py = gradient.mul_add(px - x, y);
+            let gap = if subpixel { gap } else { 1.0 };
 
-        while x <= x2_u {
-            let fract = y1.fract();
-            let mut py = y1 as u32;
-            if lower {
-                py += 1;
-            }
+            plot(px, py.floor(), (1.0 - fpart(py)) * gap);
+            plot(px, py.floor() + 1.0, fpart(py) * gap);
+            (px, py)
+        };
+        // Both endpoints lie in the same pixel, so plot it only once, covered by the whole line.
+        if x1.round() == x2.round() {
+            endpoint(x1, y1, dx);
+            return;
+        }
 
-            let (px, py) = if swapped { (py, x) } else { (x, py) };
-            if lower {
-                lower = false;
-                x += 1;
-                y1 += gradient;
-                self.color
-                    .plot_with_alpha(image, px, py, overlay, (fract * 255.0) as u8);
-            } else {
-                if fract > 0.0 {
-                    lower = true;
-                } else {
-                    x += 1;
-                    y1 += gradient;
-                }
-                self.color
-                    .plot_with_alpha(image, px, py, overlay, ((1.0 - fract) * 255.0) as u8);
-            }
+        let (start, mut intery) = endpoint(x1, y1, 1.0 - fpart(x1 + 0.5));
+        let (end, _) = endpoint(x2, y2, fpart(x2 + 0.5));
+        intery += gradient;
+
+        let mut x = start + 1.0;
+        while x < end {
+            plot(x, intery.floor(), 1.0 - fpart(intery));
+            plot(x, intery.floor() + 1.0, fpart(intery));
+            intery += gradient;
+            x += 1.0;
         }
     }
 
-    fn draw_thick_line(&self, image: &mut Image<F::Pixel>) {
+    fn draw_thick_line(&self, image: &mut impl DrawTarget<F::Pixel>) {
         let (x1, y1) = self.start;
         let (x2, y2) = self.end;
         let (x1, y1, x2, y2) = (x1 as f32, y1 as f32, x2 as f32, y2 as f32);
 
         let mut angle = (y2 - y1).atan2(x2 - x1);
         let polygon = if self.position == BorderPosition::Center {
-            let upper = angle + std::f32::consts::FRAC_PI_2;
-            let lower = angle - std::f32::consts::FRAC_PI_2;
+            let upper = angle + core::f32::consts::FRAC_PI_2;
+            let lower = angle - core::f32::consts::FRAC_PI_2;
 
             let thickness = self.thickness as f32 / 2.0;
             let upper_cos = thickness * upper.cos();
@@ -419,9 +640,9 @@ impl<F: IntoFill> Line<F> {
             ])
         } else {
             if self.position == BorderPosition::Inset {
-                angle += std::f32::consts::PI;
+                angle += core::f32::consts::PI;
             } else {
-                angle -= std::f32::consts::PI;
+                angle -= core::f32::consts::PI;
             }
 
             let thickness = self.thickness as f32;
@@ -436,176 +657,1110 @@ impl<F: IntoFill> Line<F> {
             ])
         };
 
-        image.draw(
-            &polygon
-                .with_fill_color(self.color.clone())
-                .with_antialiased(self.antialiased),
-        );
+        let mut polygon = polygon
+            .with_fill_color(self.color.clone())
+            .with_antialiased(self.antialiased);
+        if let Some(mode) = self.mode {
+            polygon = polygon.with_overlay_mode(mode);
+        }
+
+        polygon.render(image);
     }
 }
 
-impl<F: IntoFill> Draw<F::Pixel> for Line<F> {
-    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+impl<F: IntoFill> Line<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
         let (x1, y1) = self.start;
         let (x2, y2) = self.end;
-        let image = &mut *image;
-
         // TODO: still have to adjust endpoints for lines with adjusted positions
         // TODO: make endpoints smoothly adjust to gradient fills
         self.plot_endpoints(image);
 
-        if x1 == x2 || y1 == y2 {
+        if self.thickness == 1 && self.antialiased {
+            self.draw_antialiased_line(image);
+        } else if x1 == x2 || y1 == y2 {
             self.plot_perfect_line(image);
         } else if self.thickness == 1 {
-            if self.antialiased {
-                self.draw_antialiased_line(image);
-            } else {
-                self.draw_thin_line(image);
-            }
+            self.draw_thin_line(image);
         } else {
             self.draw_thick_line(image);
         }
     }
 }
 
-/// A polygon.
-#[derive(Clone, Debug)]
-pub struct Polygon<F: IntoFill> {
-    /// A `Vec` of vertices that make up the polygon. The vertices are connected in the order they
-    /// are given.
-    ///
-    /// When drawing, a panic will occur if there is less than 3 vertices in this `Vec`.
-    ///
-    /// If the first and last vertices are the same, these points will remain untouched. Otherwise,
-    /// and extra vertex equivalent to the first vertex will be added to the end of the `Vec` to
-    /// close the polygon.
-    pub vertices: Vec<(u32, u32)>,
-    /// The border of the polygon. Either this or `fill` must be `Some`.
-    pub border: Option<Border<F::Pixel>>,
-    /// Whether the border should be rounded off by drawing circles at each vertex. This is only
-    /// applied if `border` is `Some`. Additionally, these circles will not antialias.
-    pub rounded: bool,
-    /// The fill color of the polygon. Either this or `border` must be `Some`.
-    pub fill: Option<F::Fill>,
-    /// The overlay mode of the polygon. If `None`, the image's overlay mode will be used.
-    pub overlay: Option<OverlayMode>,
-    /// Whether to antialias the polygon's edges.
-    pub antialiased: bool,
+impl<F: IntoFill> Draw<F::Pixel> for Line<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
 }
 
-impl<F: IntoFill> Default for Polygon<F> {
-    fn default() -> Self {
-        Self {
-            vertices: Vec::default(),
-            border: None,
-            rounded: false,
-            fill: None,
-            overlay: None,
-            antialiased: false,
-        }
+/// Determines which regions of a self-intersecting or otherwise complex shape, such as a
+/// [`Polygon`], are considered to be inside of the shape and are therefore filled.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// A point is inside of the shape if a ray cast from it crosses the outline of the shape an odd
+    /// number of times. Overlapping regions of a self-intersecting shape are left unfilled. This
+    /// is the default behavior.
+    #[default]
+    EvenOdd,
+    /// A point is inside of the shape if the outline of the shape winds around it a non-zero
+    /// number of times, taking its direction into account. Overlapping regions of a
+    /// self-intersecting shape are usually filled.
+    NonZero,
+}
+
+/// Clamps a bounding box with signed coordinates into the unsigned bounding box used by fills.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn clip_bounding_box((x1, y1, x2, y2): BoundingBox<i64>) -> BoundingBox<u32> {
+    let clamp = |value: i64| value.clamp(0, i64::from(u32::MAX)) as u32;
+    (clamp(x1), clamp(y1), clamp(x2), clamp(y2))
+}
+
+/// Plots the given fill at the given coordinates, given the coverage of the pixel. Pixels which are
+/// not covered by at least half are skipped if antialiasing is disabled.
+#[inline]
+fn plot_coverage<P: Pixel>(
+    fill: &impl Fill<P>,
+    image: &mut impl DrawTarget<P>,
+    (x, y): (u32, u32),
+    overlay: OverlayMode,
+    coverage: u8,
+    antialiased: bool,
+) {
+    if coverage == u8::MAX || !antialiased && coverage >= 128 {
+        fill.plot(image, x, y, overlay);
+    } else if antialiased {
+        fill.plot_with_alpha(image, x, y, overlay, coverage);
     }
 }
 
-#[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
-impl<F: IntoFill> Polygon<F> {
-    /// Creates a new empty polygon.
+/// Determines the shape used to join two segments of a [`Polyline`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// Extend the outer edges of both segments until they meet at a sharp corner. If the corner
+    /// would extend further than the miter limit, a bevel join is used instead. This is the
+    /// default behavior.
+    #[default]
+    Miter,
+    /// Round off the corner with a circle centered at the joint.
+    Round,
+    /// Cut off the corner by connecting the outer edges of both segments with a straight line.
+    Bevel,
+}
+
+/// Determines the shape at the endpoints of an open [`Polyline`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// End the stroke exactly at the endpoint. This is the default behavior.
+    #[default]
+    Butt,
+    /// Round off the endpoint with a semicircle.
+    Round,
+    /// Extend the stroke past the endpoint by half of its thickness.
+    Square,
+}
+
+/// A polyline, i.e. a sequence of connected line segments, stroked with a given thickness.
+///
+/// Unlike [`Line`], vertices are specified with sub-pixel precision, where integer coordinates lie
+/// on the center of a pixel. Segments are connected with the given [`LineJoin`] and the endpoints
+/// of open polylines are capped with the given [`LineCap`].
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::new(64, 64, Rgba::white());
+/// image.draw(
+///     &Polyline::new([(8.0, 56.0), (24.0, 16.0), (40.0, 40.0), (56.0, 8.0)], Rgba::black())
+///         .with_thickness(4.0)
+///         .with_join(LineJoin::Round)
+///         .with_cap(LineCap::Round),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Polyline<F: IntoFill> {
+    /// The vertices of the polyline, connected in the order they are given.
+    pub vertices: Vec<(f32, f32)>,
+    /// The color of the polyline.
+    pub color: F::Fill,
+    /// The overlay mode of the polyline, or None to inherit from the overlay mode of the image.
+    pub mode: Option<OverlayMode>,
+    /// The thickness of the stroke, in pixels. Defaults to 1.
+    pub thickness: f32,
+    /// How segments are joined together. Defaults to [`LineJoin::Miter`].
+    pub join: LineJoin,
+    /// How the endpoints are capped. This is ignored for closed polylines. Defaults to
+    /// [`LineCap::Butt`].
+    pub cap: LineCap,
+    /// The maximum ratio of the length of a miter join to half of the thickness of the stroke,
+    /// before falling back to a bevel join. Defaults to `4.0`.
+    pub miter_limit: f32,
+    /// Whether the last vertex should be connected back to the first vertex. Defaults to `false`.
+    pub closed: bool,
+    /// Whether the polyline should be antialiased. Defaults to `true`.
+    pub antialiased: bool,
+}
+
+impl<F: IntoFill> Polyline<F> {
+    /// Creates a new polyline with the given vertices and color.
     #[must_use]
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(vertices: impl IntoIterator<Item = (f32, f32)>, color: F) -> Self {
+        let mut this = Self {
+            vertices: vertices.into_iter().collect(),
+            color: color.into_fill(),
+            mode: None,
+            thickness: 1.0,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            miter_limit: 4.0,
+            closed: false,
+            antialiased: true,
+        };
+        this.update_bounding_box();
+        this
     }
 
-    /// Updates the polygon's bounding box. This is automatically called, unless explicitly
-    /// specified in documentation.
+    /// Updates the bounding box of the fill of this polyline. This is automatically called, unless
+    /// the vertices are modified directly.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     pub fn update_bounding_box(&mut self) {
-        if let Some(ref mut fill) = self.fill {
-            let x_iter = self.vertices.iter().map(|(x, _)| *x);
-            let y_iter = self.vertices.iter().map(|(_, y)| *y);
+        let (mut x1, mut y1) = (f32::INFINITY, f32::INFINITY);
+        let (mut x2, mut y2) = (0.0_f32, 0.0_f32);
+        for &(x, y) in &self.vertices {
+            x1 = x1.min(x);
+            y1 = y1.min(y);
+            x2 = x2.max(x);
+            y2 = y2.max(y);
+        }
 
-            fill.set_bounding_box((
-                x_iter.clone().min().unwrap_or(0),
-                y_iter.clone().min().unwrap_or(0),
-                x_iter.max().unwrap_or(0),
-                y_iter.max().unwrap_or(0),
-            ));
+        if x1.is_finite() {
+            self.color
+                .set_bounding_box((x1 as u32, y1 as u32, x2.ceil() as u32, y2.ceil() as u32));
         }
     }
 
-    /// Creates a new polygon with the given vertices.
-    #[must_use]
-    pub fn from_vertices(vertices: impl IntoIterator<Item = (u32, u32)>) -> Self {
-        Self {
-            vertices: vertices.into_iter().collect(),
-            ..Self::default()
-        }
+    /// Adds a vertex to the polyline in place.
+    pub fn push_vertex(&mut self, x: f32, y: f32) {
+        self.vertices.push((x, y));
+        self.update_bounding_box();
     }
 
-    /// Creates a regular polygon with `n` sides centered at `center`, with each of the points
-    /// `radius` away from the center. `angle` is measured in radians and is the angle offset of the
-    /// first point, which for `angle=0.0`, will be horizontally to the right of the center
-    /// (i.e. a unit circle).
-    ///
-    /// For angles specified in degrees, the [`f64::to_radians`] method can be used for conversion.
-    ///
-    /// # Note
-    /// Apart from `n=4`, the polygon will not be pixel-perfect since no polygons other than squares
-    /// have all of their vertices as perfect integers. This means that they will be rounded to the
-    /// nearest pixel.
-    ///
-    /// # Panics
-    /// * If `n < 3`
+    /// Takes this polyline and adds a vertex to it. Useful for method chaining.
     #[must_use]
-    #[allow(clippy::cast_lossless)]
-    pub fn regular_rotated(n: u32, center: (u32, u32), radius: u32, angle: f64) -> Self {
-        assert!(n >= 3, "n must be greater than or equal to 3");
-
-        let mut vertices = Vec::with_capacity(n as usize);
-        let base = std::f64::consts::TAU / n as f64;
-        let (cx, cy) = (center.0 as f64, center.1 as f64);
-        let radius = radius as f64;
-
-        for i in 0..n {
-            let (angle_sin, angle_cos) = base.mul_add(i as f64, -angle).sin_cos();
-            let x = radius.mul_add(angle_cos, cx).round() as u32;
-            let y = radius.mul_add(angle_sin, cy).round() as u32;
-
-            vertices.push((x, y));
-        }
-
-        Self::from_vertices(vertices)
+    pub fn with_vertex(mut self, x: f32, y: f32) -> Self {
+        self.push_vertex(x, y);
+        self
     }
 
-    /// Creates a regular polygon with the first point vertically up from the center (the polygon
-    /// will seem to be facing "upwards").
-    ///
-    /// This is a shortcut to calling [`Polygon::regular_rotated`] with `angle = PI / 2` (`90deg`).
-    ///
-    /// # See Also
-    /// * [`Polygon::regular_rotated`] for more information.
+    /// Returns a slice of the vertices in the polyline.
     #[must_use]
-    pub fn regular(n: u32, center: (u32, u32), radius: u32) -> Self {
-        Self::regular_rotated(n, center, radius, std::f64::consts::FRAC_PI_2)
+    pub fn vertices(&self) -> &[(f32, f32)] {
+        &self.vertices
     }
 
-    /// Adds a vertex to the polygon.
+    /// Sets the color of the polyline.
     #[must_use]
-    pub fn with_vertex(mut self, x: u32, y: u32) -> Self {
-        self.push_vertex(x, y);
+    pub fn with_color(mut self, color: F) -> Self {
+        self.color = color.into_fill();
         self.update_bounding_box();
         self
     }
 
-    /// Adds a vertex to the polygon in place.
-    pub fn push_vertex(&mut self, x: u32, y: u32) {
-        self.vertices.push((x, y));
+    /// Sets the overlay mode of the polyline.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.mode = Some(mode);
+        self
     }
 
-    /// Returns a slice of the vertices in the polygon.
+    /// Sets the overlay mode of the polyline.
     #[must_use]
-    pub fn vertices(&self) -> &[(u32, u32)] {
-        &self.vertices
+    #[deprecated(note = "use `with_overlay_mode` instead")]
+    pub const fn with_mode(self, mode: OverlayMode) -> Self {
+        self.with_overlay_mode(mode)
     }
 
-    /// Returns a mutable slice of the vertices in the polygon.
+    /// Sets the thickness of the polyline.
+    #[must_use]
+    pub const fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Sets how segments of the polyline are joined together.
+    #[must_use]
+    pub const fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Sets how the endpoints of the polyline are capped.
+    #[must_use]
+    pub const fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets the miter limit of the polyline.
+    #[must_use]
+    pub const fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Sets whether the last vertex should be connected back to the first vertex.
+    #[must_use]
+    pub const fn with_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// Sets whether the polyline should be antialiased. If this is set to `true`, the overlay mode
+    /// of this polyline will also be set to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased {
+            self.mode = Some(OverlayMode::Merge);
+        }
+        self
+    }
+}
+
+impl<F: IntoFill> Polyline<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let contours = raster::stroke(
+            &self.vertices,
+            self.closed,
+            self.thickness,
+            self.join,
+            self.cap,
+            self.miter_limit,
+        );
+        let overlay = self.mode.unwrap_or(image.overlay_mode());
+        let dimensions = image.dimensions();
+
+        raster::rasterize(&contours, FillRule::NonZero, dimensions, |x, y, alpha| {
+            plot_coverage(&self.color, image, (x, y), overlay, alpha, self.antialiased);
+        });
+    }
+}
+
+impl<F: IntoFill> Draw<F::Pixel> for Polyline<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
+/// A quadratic or cubic Bézier curve, stroked with a given thickness.
+///
+/// Like [`Polyline`], points are specified with sub-pixel precision, where integer coordinates lie
+/// on the center of a pixel. The curve is flattened into line segments before it is stroked. To
+/// draw several connected curves or to fill the area they enclose, use a [`Path`] instead.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::new(64, 64, Rgba::white());
+/// image.draw(
+///     &Bezier::cubic((4.0, 56.0), (16.0, 4.0), (48.0, 60.0), (60.0, 8.0), Rgba::black())
+///         .with_thickness(3.0)
+///         .with_cap(LineCap::Round),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Bezier<F: IntoFill> {
+    /// The start point of the curve.
+    pub start: (f32, f32),
+    /// The first control point of the curve.
+    pub control1: (f32, f32),
+    /// The second control point of the curve, or None if the curve is quadratic.
+    pub control2: Option<(f32, f32)>,
+    /// The end point of the curve.
+    pub end: (f32, f32),
+    /// The color of the curve.
+    pub color: F::Fill,
+    /// The overlay mode of the curve, or None to inherit from the overlay mode of the image.
+    pub mode: Option<OverlayMode>,
+    /// The thickness of the stroke, in pixels. Defaults to 1.
+    pub thickness: f32,
+    /// How the line segments approximating the curve are joined together. Defaults to
+    /// [`LineJoin::Miter`].
+    pub join: LineJoin,
+    /// How the endpoints are capped. Defaults to [`LineCap::Butt`].
+    pub cap: LineCap,
+    /// The maximum ratio of the length of a miter join to half of the thickness of the stroke,
+    /// before falling back to a bevel join. Defaults to `4.0`.
+    pub miter_limit: f32,
+    /// Whether the curve should be antialiased. Defaults to `true`.
+    pub antialiased: bool,
+}
+
+impl<F: IntoFill> Bezier<F> {
+    fn new(
+        start: (f32, f32),
+        control1: (f32, f32),
+        control2: Option<(f32, f32)>,
+        end: (f32, f32),
+        color: F,
+    ) -> Self {
+        let mut this = Self {
+            start,
+            control1,
+            control2,
+            end,
+            color: color.into_fill(),
+            mode: None,
+            thickness: 1.0,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            miter_limit: 4.0,
+            antialiased: true,
+        };
+        this.update_bounding_box();
+        this
+    }
+
+    /// Creates a new quadratic Bézier curve from `start` to `end`, using `control` as its control
+    /// point.
+    #[must_use]
+    pub fn quadratic(start: (f32, f32), control: (f32, f32), end: (f32, f32), color: F) -> Self {
+        Self::new(start, control, None, end, color)
+    }
+
+    /// Creates a new cubic Bézier curve from `start` to `end`, using `control1` and `control2` as
+    /// its control points.
+    #[must_use]
+    pub fn cubic(
+        start: (f32, f32),
+        control1: (f32, f32),
+        control2: (f32, f32),
+        end: (f32, f32),
+        color: F,
+    ) -> Self {
+        Self::new(start, control1, Some(control2), end, color)
+    }
+
+    /// Updates the bounding box of the fill of this curve. This is automatically called, unless
+    /// the points are modified directly.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn update_bounding_box(&mut self) {
+        // The curve always lies within the convex hull of its points
+        let (mut x1, mut y1) = self.start;
+        let (mut x2, mut y2) = self.start;
+        for (x, y) in [self.control1, self.end].into_iter().chain(self.control2) {
+            x1 = x1.min(x);
+            y1 = y1.min(y);
+            x2 = x2.max(x);
+            y2 = y2.max(y);
+        }
+
+        self.color.set_bounding_box((
+            x1.max(0.0) as u32,
+            y1.max(0.0) as u32,
+            x2.ceil().max(0.0) as u32,
+            y2.ceil().max(0.0) as u32,
+        ));
+    }
+
+    /// Returns the points of the line segments approximating this curve.
+    fn flatten(&self) -> Vec<(f32, f32)> {
+        let path = Path::<F>::new().move_to(self.start.0, self.start.1);
+        let path = match self.control2 {
+            Some(control2) => path.cubic_to(self.control1, control2, self.end),
+            None => path.quad_to(self.control1, self.end),
+        };
+
+        path.subpaths.into_iter().next().unwrap_or_default().points
+    }
+
+    /// Sets the color of the curve.
+    #[must_use]
+    pub fn with_color(mut self, color: F) -> Self {
+        self.color = color.into_fill();
+        self.update_bounding_box();
+        self
+    }
+
+    /// Sets the overlay mode of the curve.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the thickness of the curve.
+    #[must_use]
+    pub const fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Sets how the line segments approximating the curve are joined together.
+    #[must_use]
+    pub const fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Sets how the endpoints of the curve are capped.
+    #[must_use]
+    pub const fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets the miter limit of the curve.
+    #[must_use]
+    pub const fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Sets whether the curve should be antialiased. If this is set to `true`, the overlay mode of
+    /// this curve will also be set to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased {
+            self.mode = Some(OverlayMode::Merge);
+        }
+        self
+    }
+}
+
+impl<F: IntoFill> Bezier<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let contours = raster::stroke(
+            &self.flatten(),
+            false,
+            self.thickness,
+            self.join,
+            self.cap,
+            self.miter_limit,
+        );
+        let overlay = self.mode.unwrap_or(image.overlay_mode());
+        let dimensions = image.dimensions();
+
+        raster::rasterize(&contours, FillRule::NonZero, dimensions, |x, y, alpha| {
+            plot_coverage(&self.color, image, (x, y), overlay, alpha, self.antialiased);
+        });
+    }
+}
+
+impl<F: IntoFill> Draw<F::Pixel> for Bezier<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
+/// Represents the stroke of a [`Path`].
+#[derive(Clone, Debug)]
+pub struct PathStroke<F: IntoFill> {
+    /// The fill of the stroke, which is bound to the bounding box of the stroke.
+    pub fill: F::Fill,
+    /// The thickness of the stroke, in pixels.
+    pub thickness: f32,
+    /// How segments are joined together. Defaults to [`LineJoin::Miter`].
+    pub join: LineJoin,
+    /// How the endpoints of open subpaths are capped. Defaults to [`LineCap::Butt`].
+    pub cap: LineCap,
+    /// The maximum ratio of the length of a miter join to half of the thickness of the stroke,
+    /// before falling back to a bevel join. Defaults to `4.0`.
+    pub miter_limit: f32,
+}
+
+impl<F: IntoFill> PathStroke<F> {
+    /// Creates a new stroke with the given fill and thickness.
+    #[must_use]
+    pub fn new(fill: F, thickness: f32) -> Self {
+        Self {
+            fill: fill.into_fill(),
+            thickness,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+
+    /// Sets the fill of the stroke.
+    #[must_use]
+    pub fn with_fill(mut self, fill: F) -> Self {
+        self.fill = fill.into_fill();
+        self
+    }
+
+    /// Sets the thickness of the stroke.
+    #[must_use]
+    pub const fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Sets how segments of the stroke are joined together.
+    #[must_use]
+    pub const fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Sets how the endpoints of open subpaths are capped.
+    #[must_use]
+    pub const fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets the miter limit of the stroke.
+    #[must_use]
+    pub const fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+}
+
+/// A subpath of a [`Path`], flattened into line segments.
+#[derive(Clone, Debug, Default)]
+struct Subpath {
+    points: Vec<(f32, f32)>,
+    closed: bool,
+}
+
+/// A vector path, consisting of any number of subpaths made of lines and Bézier curves, which can
+/// be filled, stroked, or both.
+///
+/// Coordinates are specified with sub-pixel precision, where integer coordinates lie on the center
+/// of a pixel. Curves are flattened into line segments as they are added.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// // A heart shape
+/// let heart = Path::new()
+///     .move_to(32.0, 56.0)
+///     .cubic_to((4.0, 36.0), (8.0, 4.0), (32.0, 18.0))
+///     .cubic_to((56.0, 4.0), (60.0, 36.0), (32.0, 56.0))
+///     .close()
+///     .with_fill(Rgba::new(255, 0, 0, 255))
+///     .with_stroke(PathStroke::new(Rgba::black(), 2.0).with_join(LineJoin::Round));
+///
+/// let mut image = Image::new(64, 64, Rgba::white());
+/// image.draw(&heart);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Path<F: IntoFill> {
+    subpaths: Vec<Subpath>,
+    /// The fill of the path, or None if the path should not be filled. Subpaths are implicitly
+    /// closed when filling.
+    pub fill: Option<F::Fill>,
+    /// The rule used to determine which regions of the path are filled. Defaults to
+    /// [`FillRule::NonZero`].
+    pub fill_rule: FillRule,
+    /// The stroke of the path, or None if the path should not be stroked.
+    pub stroke: Option<PathStroke<F>>,
+    /// The overlay mode of the path, or None to inherit from the overlay mode of the image.
+    pub overlay: Option<OverlayMode>,
+    /// Whether the path should be antialiased. Defaults to `true`.
+    pub antialiased: bool,
+}
+
+impl<F: IntoFill> Default for Path<F> {
+    fn default() -> Self {
+        Self {
+            subpaths: Vec::new(),
+            fill: None,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+            overlay: None,
+            antialiased: true,
+        }
+    }
+}
+
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+impl<F: IntoFill> Path<F> {
+    /// Creates a new empty path.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the last point of the current subpath, starting a new subpath at `(0, 0)` if there
+    /// is none.
+    fn current(&mut self) -> (f32, f32) {
+        match self.subpaths.last() {
+            Some(subpath) if !subpath.closed => {
+                // Subpaths always contain at least one point
+                *subpath.points.last().unwrap()
+            }
+            Some(subpath) => {
+                let start = subpath.points[0];
+                self.subpaths.push(Subpath {
+                    points: vec![start],
+                    closed: false,
+                });
+                start
+            }
+            None => self.move_to_in_place(0.0, 0.0),
+        }
+    }
+
+    fn move_to_in_place(&mut self, x: f32, y: f32) -> (f32, f32) {
+        match self.subpaths.last_mut() {
+            // Collapse consecutive moves into a single one
+            Some(subpath) if !subpath.closed && subpath.points.len() == 1 => {
+                subpath.points[0] = (x, y);
+            }
+            _ => self.subpaths.push(Subpath {
+                points: vec![(x, y)],
+                closed: false,
+            }),
+        }
+        (x, y)
+    }
+
+    fn push_point(&mut self, point: (f32, f32)) {
+        // `current` is always called beforehand, ensuring there is an open subpath
+        self.subpaths.last_mut().unwrap().points.push(point);
+    }
+
+    /// Starts a new subpath at the given point.
+    #[must_use]
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.move_to_in_place(x, y);
+        self
+    }
+
+    /// Adds a straight line from the current point to the given point.
+    #[must_use]
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.current();
+        self.push_point((x, y));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve from the current point to `end`, using `control` as its
+    /// control point.
+    #[must_use]
+    pub fn quad_to(mut self, control: (f32, f32), end: (f32, f32)) -> Self {
+        let start = self.current();
+        let n = Self::segments(&[start, control, end]);
+
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let u = 1.0 - t;
+            let (a, b, c) = (u * u, 2.0 * u * t, t * t);
+
+            self.push_point((
+                a.mul_add(start.0, b.mul_add(control.0, c * end.0)),
+                a.mul_add(start.1, b.mul_add(control.1, c * end.1)),
+            ));
+        }
+        self
+    }
+
+    /// Adds a cubic Bézier curve from the current point to `end`, using `control1` and
+    /// `control2` as its control points.
+    #[must_use]
+    pub fn cubic_to(mut self, control1: (f32, f32), control2: (f32, f32), end: (f32, f32)) -> Self {
+        let start = self.current();
+        let n = Self::segments(&[start, control1, control2, end]);
+
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+
+            self.push_point((
+                a.mul_add(
+                    start.0,
+                    b.mul_add(control1.0, c.mul_add(control2.0, d * end.0)),
+                ),
+                a.mul_add(
+                    start.1,
+                    b.mul_add(control1.1, c.mul_add(control2.1, d * end.1)),
+                ),
+            ));
+        }
+        self
+    }
+
+    /// Closes the current subpath by connecting its current point back to its start.
+    #[must_use]
+    pub fn close(mut self) -> Self {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.closed = true;
+        }
+        self
+    }
+
+    /// Returns the number of line segments used to approximate a curve with the given control
+    /// points, based on the length of its control polygon.
+    fn segments(points: &[(f32, f32)]) -> usize {
+        let length = points
+            .windows(2)
+            .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+            .sum::<f32>();
+
+        ((length.sqrt() * 2.0).ceil() as usize).clamp(1, 256)
+    }
+
+    /// Sets the fill of the path.
+    #[must_use]
+    pub fn with_fill(mut self, fill: F) -> Self {
+        self.fill = Some(fill.into_fill());
+        self
+    }
+
+    /// Sets the rule used to determine which regions of the path are filled.
+    #[must_use]
+    pub const fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Sets the stroke of the path.
+    #[must_use]
+    pub fn with_stroke(mut self, stroke: PathStroke<F>) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    /// Sets the overlay mode of the path.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Sets whether the path should be antialiased. If this is set to `true`, the overlay mode of
+    /// this path will also be set to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased {
+            self.overlay = Some(OverlayMode::Merge);
+        }
+        self
+    }
+
+    /// Returns the bounding box of all points of this path, or `None` if this path is empty.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<(f32, f32, f32, f32)> {
+        bounds(self.subpaths.iter().flat_map(|subpath| &subpath.points))
+    }
+}
+
+/// Returns the bounding box of the given points, or `None` if there are none.
+fn bounds<'a>(points: impl Iterator<Item = &'a (f32, f32)>) -> Option<(f32, f32, f32, f32)> {
+    points.fold(None, |acc, &(x, y)| {
+        Some(
+            acc.map_or((x, y, x, y), |(x1, y1, x2, y2): (f32, f32, f32, f32)| {
+                (x1.min(x), y1.min(y), x2.max(x), y2.max(y))
+            }),
+        )
+    })
+}
+
+/// Rounds the given bounding box outwards to the pixels it covers, for binding fills to it.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn pixel_bounds((x1, y1, x2, y2): (f32, f32, f32, f32)) -> BoundingBox<u32> {
+    (
+        x1.max(0.0) as u32,
+        y1.max(0.0) as u32,
+        x2.ceil().max(0.0) as u32,
+        y2.ceil().max(0.0) as u32,
+    )
+}
+
+impl<F: IntoFill> Path<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
+        let dimensions = image.dimensions();
+
+        if let (Some(fill), Some(bounds)) = (&self.fill, self.bounding_box()) {
+            let fill = fill.clone().with_bounding_box(pixel_bounds(bounds));
+            let contours = self
+                .subpaths
+                .iter()
+                .map(|subpath| subpath.points.clone())
+                .collect::<Vec<_>>();
+
+            raster::rasterize(&contours, self.fill_rule, dimensions, |x, y, alpha| {
+                plot_coverage(&fill, image, (x, y), overlay, alpha, self.antialiased);
+            });
+        }
+
+        if let Some(ref stroke) = self.stroke {
+            let contours = self
+                .subpaths
+                .iter()
+                .flat_map(|subpath| {
+                    raster::stroke(
+                        &subpath.points,
+                        subpath.closed,
+                        stroke.thickness,
+                        stroke.join,
+                        stroke.cap,
+                        stroke.miter_limit,
+                    )
+                })
+                .collect::<Vec<_>>();
+            // The stroke extends past the points of the path, so its fill is bound to the stroke
+            let bounding_box = match bounds(contours.iter().flatten()) {
+                Some(bounds) => pixel_bounds(bounds),
+                None => return,
+            };
+            let fill = stroke.fill.clone().with_bounding_box(bounding_box);
+
+            raster::rasterize(&contours, FillRule::NonZero, dimensions, |x, y, alpha| {
+                plot_coverage(&fill, image, (x, y), overlay, alpha, self.antialiased);
+            });
+        }
+    }
+}
+
+impl<F: IntoFill> Draw<F::Pixel> for Path<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
+/// A polygon.
+#[derive(Clone, Debug)]
+pub struct Polygon<F: IntoFill> {
+    /// A `Vec` of vertices that make up the polygon. The vertices are connected in the order they
+    /// are given.
+    ///
+    /// When drawing, a panic will occur if there is less than 3 vertices in this `Vec`.
+    ///
+    /// If the first and last vertices are the same, these points will remain untouched. Otherwise,
+    /// and extra vertex equivalent to the first vertex will be added to the end of the `Vec` to
+    /// close the polygon.
+    pub vertices: Vec<(u32, u32)>,
+    /// The border of the polygon. Either this or `fill` must be `Some`.
+    pub border: Option<Border<F::Pixel>>,
+    /// Whether the border should be rounded off by drawing circles at each vertex. This is only
+    /// applied if `border` is `Some`. Additionally, these circles will not antialias.
+    pub rounded: bool,
+    /// The fill color of the polygon. Either this or `border` must be `Some`.
+    pub fill: Option<F::Fill>,
+    /// The overlay mode of the polygon. If `None`, the image's overlay mode will be used.
+    pub overlay: Option<OverlayMode>,
+    /// Whether to antialias the polygon's edges.
+    pub antialiased: bool,
+    /// The rule used to determine which regions of a self-intersecting polygon are filled.
+    /// Defaults to [`FillRule::EvenOdd`].
+    pub fill_rule: FillRule,
+}
+
+impl<F: IntoFill> Default for Polygon<F> {
+    fn default() -> Self {
+        Self {
+            vertices: Vec::default(),
+            border: None,
+            rounded: false,
+            fill: None,
+            overlay: None,
+            antialiased: false,
+            fill_rule: FillRule::default(),
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
+impl<F: IntoFill> Polygon<F> {
+    /// Creates a new empty polygon.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the polygon's bounding box. This is automatically called, unless explicitly
+    /// specified in documentation.
+    pub fn update_bounding_box(&mut self) {
+        if let Some(ref mut fill) = self.fill {
+            let x_iter = self.vertices.iter().map(|(x, _)| *x);
+            let y_iter = self.vertices.iter().map(|(_, y)| *y);
+
+            fill.set_bounding_box((
+                x_iter.clone().min().unwrap_or(0),
+                y_iter.clone().min().unwrap_or(0),
+                x_iter.max().unwrap_or(0),
+                y_iter.max().unwrap_or(0),
+            ));
+        }
+    }
+
+    /// Creates a new polygon with the given vertices.
+    #[must_use]
+    pub fn from_vertices(vertices: impl IntoIterator<Item = (u32, u32)>) -> Self {
+        Self {
+            vertices: vertices.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a regular polygon with `n` sides centered at `center`, with each of the points
+    /// `radius` away from the center. `angle` is measured in radians and is the angle offset of the
+    /// first point, which for `angle=0.0`, will be horizontally to the right of the center
+    /// (i.e. a unit circle).
+    ///
+    /// For angles specified in degrees, the [`f64::to_radians`] method can be used for conversion.
+    ///
+    /// # Note
+    /// Apart from `n=4`, the polygon will not be pixel-perfect since no polygons other than squares
+    /// have all of their vertices as perfect integers. This means that they will be rounded to the
+    /// nearest pixel.
+    ///
+    /// # Panics
+    /// * If `n < 3`
+    #[must_use]
+    #[allow(clippy::cast_lossless)]
+    pub fn regular_rotated(n: u32, center: (u32, u32), radius: u32, angle: f64) -> Self {
+        assert!(n >= 3, "n must be greater than or equal to 3");
+
+        let mut vertices = Vec::with_capacity(n as usize);
+        let base = core::f64::consts::TAU / n as f64;
+        let (cx, cy) = (center.0 as f64, center.1 as f64);
+        let radius = radius as f64;
+
+        for i in 0..n {
+            let (angle_sin, angle_cos) = base.mul_add(i as f64, -angle).sin_cos();
+            let x = radius.mul_add(angle_cos, cx).round() as u32;
+            let y = radius.mul_add(angle_sin, cy).round() as u32;
+
+            vertices.push((x, y));
+        }
+
+        Self::from_vertices(vertices)
+    }
+
+    /// Creates a regular polygon with the first point vertically up from the center (the polygon
+    /// will seem to be facing "upwards").
+    ///
+    /// This is a shortcut to calling [`Polygon::regular_rotated`] with `angle = PI / 2` (`90deg`).
+    ///
+    /// # See Also
+    /// * [`Polygon::regular_rotated`] for more information.
+    #[must_use]
+    pub fn regular(n: u32, center: (u32, u32), radius: u32) -> Self {
+        Self::regular_rotated(n, center, radius, core::f64::consts::FRAC_PI_2)
+    }
+
+    /// Creates a star with `n` points centered at `center`, where the tips of the star are
+    /// `outer_radius` away from the center and the inner vertices between them are `inner_radius`
+    /// away from the center. `angle` is measured in radians and is the angle offset of the first
+    /// tip, following the same convention as [`Polygon::regular_rotated`].
+    ///
+    /// Since the star is not self-intersecting, it is filled entirely regardless of the fill rule.
+    ///
+    /// # Note
+    /// Similarly to [`Polygon::regular_rotated`], vertices are rounded to the nearest pixel.
+    ///
+    /// # Panics
+    /// * If `n < 2`
+    #[must_use]
+    #[allow(clippy::cast_lossless)]
+    pub fn star_rotated(
+        n: u32,
+        center: (u32, u32),
+        outer_radius: u32,
+        inner_radius: u32,
+        angle: f64,
+    ) -> Self {
+        assert!(n >= 2, "n must be greater than or equal to 2");
+
+        let mut vertices = Vec::with_capacity(n as usize * 2);
+        let base = core::f64::consts::PI / n as f64;
+        let (cx, cy) = (center.0 as f64, center.1 as f64);
+
+        for i in 0..n * 2 {
+            let radius = if i % 2 == 0 {
+                outer_radius
+            } else {
+                inner_radius
+            } as f64;
+            let (angle_sin, angle_cos) = base.mul_add(i as f64, -angle).sin_cos();
+            let x = radius.mul_add(angle_cos, cx).round() as u32;
+            let y = radius.mul_add(angle_sin, cy).round() as u32;
+
+            vertices.push((x, y));
+        }
+
+        Self::from_vertices(vertices)
+    }
+
+    /// Creates a star with the first tip vertically up from the center (the star will seem to be
+    /// facing "upwards").
+    ///
+    /// This is a shortcut to calling [`Polygon::star_rotated`] with `angle = PI / 2` (`90deg`).
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// // A five-pointed rating star
+    /// let star = Polygon::star(5, (32, 32), 30, 12)
+    ///     .with_fill(Rgba::new(255, 200, 0, 255))
+    ///     .with_antialiased(true);
+    ///
+    /// let mut image = Image::new(64, 64, Rgba::transparent());
+    /// image.draw(&star);
+    /// ```
+    ///
+    /// # See Also
+    /// * [`Polygon::star_rotated`] for more information.
+    #[must_use]
+    pub fn star(n: u32, center: (u32, u32), outer_radius: u32, inner_radius: u32) -> Self {
+        Self::star_rotated(
+            n,
+            center,
+            outer_radius,
+            inner_radius,
+            core::f64::consts::FRAC_PI_2,
+        )
+    }
+
+    /// Adds a vertex to the polygon.
+    #[must_use]
+    pub fn with_vertex(mut self, x: u32, y: u32) -> Self {
+        self.push_vertex(x, y);
+        self.update_bounding_box();
+        self
+    }
+
+    /// Adds a vertex to the polygon in place.
+    pub fn push_vertex(&mut self, x: u32, y: u32) {
+        self.vertices.push((x, y));
+    }
+
+    /// Returns a slice of the vertices in the polygon.
+    #[must_use]
+    pub fn vertices(&self) -> &[(u32, u32)] {
+        &self.vertices
+    }
+
+    /// Returns a mutable slice of the vertices in the polygon.
     #[must_use]
     pub fn vertices_mut(&mut self) -> &mut [(u32, u32)] {
         &mut self.vertices
@@ -668,6 +1823,26 @@ impl<F: IntoFill> Polygon<F> {
         self
     }
 
+    /// Sets the rule used to determine which regions of a self-intersecting polygon are filled.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// // A pentagram, with its center pentagon filled in
+    /// let star = Polygon::from_vertices([(32, 4), (49, 58), (4, 24), (60, 24), (15, 58)])
+    ///     .with_fill(Rgb::black())
+    ///     .with_fill_rule(FillRule::NonZero);
+    ///
+    /// let mut image = Image::new(64, 64, Rgb::white());
+    /// image.draw(&star);
+    /// assert_eq!(image.pixel(32, 36), &Rgb::black());
+    /// ```
+    #[must_use]
+    pub const fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
     #[inline]
     fn sanitize_vertices(&self) -> Vec<(u32, u32)> {
         assert!(
@@ -683,7 +1858,24 @@ impl<F: IntoFill> Polygon<F> {
         vertices
     }
 
-    fn rasterize_fill(&self, image: &mut Image<F::Pixel>, vertices: &[(u32, u32)]) {
+    /// Fills the polygon using coverage-based rasterization, which supports antialiasing and all
+    /// fill rules.
+    fn rasterize_coverage(&self, image: &mut impl DrawTarget<F::Pixel>, vertices: &[(u32, u32)]) {
+        // SAFETY: this method is only called if `self.fill` is `Some`
+        let fill = unsafe { self.fill.as_ref().unwrap_unchecked() };
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
+        let dimensions = image.dimensions();
+        let contour = vertices
+            .iter()
+            .map(|&(x, y)| (x as f32, y as f32))
+            .collect();
+
+        raster::rasterize(&[contour], self.fill_rule, dimensions, |x, y, alpha| {
+            plot_coverage(fill, image, (x, y), overlay, alpha, self.antialiased);
+        });
+    }
+
+    fn rasterize_fill(&self, image: &mut impl DrawTarget<F::Pixel>, vertices: &[(u32, u32)]) {
         let vertices = vertices
             .iter()
             .map(|(x, y)| (*x as i32, *y as i32))
@@ -706,7 +1898,7 @@ impl<F: IntoFill> Polygon<F> {
         };
         // SAFETY: this method is only called if `self.fill` is `Some`
         let fill = unsafe { self.fill.as_ref().unwrap_unchecked() };
-        let overlay = self.overlay.unwrap_or(image.overlay);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
         let mut intersections = Vec::new();
 
         (y_min..=y_max).for_each(|y| {
@@ -743,33 +1935,20 @@ impl<F: IntoFill> Polygon<F> {
     }
 }
 
-impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
-    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+impl<F: IntoFill> Polygon<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
         debug_assert!(
             self.fill.is_some() || self.border.is_some(),
             "polygon must have a fill or border"
         );
 
-        let image = &mut *image;
         let vertices = self.sanitize_vertices();
 
-        if let Some(ref fill) = self.fill {
-            self.rasterize_fill(image, &vertices);
-
-            if self.border.is_none() && self.antialiased {
-                for edge in vertices.windows(2) {
-                    unsafe {
-                        // SAFETY: windows(2) ensures that there are at least 2 points
-                        let &from = edge.get_unchecked(0);
-                        let &to = edge.get_unchecked(1);
-                        image.draw(
-                            // SAFETY: color is overridden
-                            &Line::new(from, to, std::mem::zeroed::<F>())
-                                .with_fill_color(fill.clone())
-                                .with_antialiased(true),
-                        );
-                    }
-                }
+        if self.fill.is_some() {
+            if self.antialiased || self.fill_rule != FillRule::EvenOdd {
+                self.rasterize_coverage(image, &vertices);
+            } else {
+                self.rasterize_fill(image, &vertices);
             }
         }
 
@@ -779,17 +1958,17 @@ impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
                     // SAFETY: windows(2) ensures that there are at least 2 points
                     let &from @ (x, y) = edge.get_unchecked(0);
                     let &to = edge.get_unchecked(1);
-                    image.draw(
-                        &Line::new(from, to, border.color)
-                            .with_antialiased(self.antialiased)
-                            .with_thickness(border.thickness)
-                            .with_position(border.position),
-                    );
+                    Line::new(from, to, border.color)
+                        .with_antialiased(self.antialiased)
+                        .with_thickness(border.thickness)
+                        .with_position(border.position)
+                        .render(image);
 
                     if self.rounded {
-                        image.draw(
-                            &Ellipse::circle(x, y, border.thickness / 2).with_fill(border.color),
-                        );
+                        Ellipse::circle(i64::from(x), i64::from(y), border.thickness / 2)
+                            .with_fill(border.color)
+                            .with_antialiased(false)
+                            .render(image);
                     }
                 }
             }
@@ -797,6 +1976,16 @@ impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
     }
 }
 
+impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
 /// A rectangle.
 ///
 /// # Note
@@ -807,11 +1996,22 @@ impl<F: IntoFill> Draw<F::Pixel> for Polygon<F> {
 ///
 /// Additionally, a panic will be raised during drawing if you do not specify either a fill color
 /// or a border. these can be set with [`with_fill`] and [`with_border`] respectively.
+///
+/// Corners can be rounded off with [`with_corner_radius`] or [`with_corner_radii`], in which case
+/// the border follows the rounded corners.
+///
+/// [`with_size`]: Rectangle::with_size
+/// [`from_bounding_box`]: Rectangle::from_bounding_box
+/// [`with_fill`]: Rectangle::with_fill
+/// [`with_border`]: Rectangle::with_border
+/// [`with_corner_radius`]: Rectangle::with_corner_radius
+/// [`with_corner_radii`]: Rectangle::with_corner_radii
 #[derive(Clone, Debug)]
 pub struct Rectangle<F: IntoFill> {
     /// The position of the rectangle. The top-left corner of the rectangle will be rendered at
-    /// this position.
-    pub position: (u32, u32),
+    /// this position, which may be negative or outside of the image, in which case the rectangle
+    /// is clipped.
+    pub position: (i64, i64),
     /// The dimensions of the rectangle, in pixels.
     pub size: (u32, u32),
     /// The border data of the rectangle, or None if there is no border.
@@ -820,6 +2020,16 @@ pub struct Rectangle<F: IntoFill> {
     pub fill: Option<F::Fill>,
     /// The overlay mode of the rectangle, or None to inherit from the overlay mode of the image.
     pub overlay: Option<OverlayMode>,
+    /// The radii of the corners of the rectangle, in pixels, in the order top-left, top-right,
+    /// bottom-right, bottom-left. If the radii of two adjacent corners exceed the length of the
+    /// side between them, all radii are scaled down proportionally. Defaults to all zeros.
+    pub radii: [u32; 4],
+    /// Whether to antialias the rounded corners and sub-pixel edges of the rectangle. Edges at
+    /// integer coordinates are aligned to pixels and never need antialiasing. Defaults to `true`.
+    pub antialiased: bool,
+    /// The bounding box of the rectangle with sub-pixel precision as `(x1, y1, x2, y2)`, or `None`
+    /// to use [`position`][Self::position] and [`size`][Self::size]. Defaults to `None`.
+    pub subpixel_bounds: Option<(f32, f32, f32, f32)>,
 }
 
 impl<F: IntoFill> Default for Rectangle<F> {
@@ -830,6 +2040,9 @@ impl<F: IntoFill> Default for Rectangle<F> {
             border: None,
             fill: None,
             overlay: None,
+            radii: [0; 4],
+            antialiased: true,
+            subpixel_bounds: None,
         }
     }
 }
@@ -856,21 +2069,21 @@ impl<F: IntoFill> Rectangle<F> {
     /// You must explicitly set the size of the rectangle with [`with_size`]. If no size is set
     /// before drawing, you will receive a panic.
     #[must_use]
-    pub fn at(x: u32, y: u32) -> Self {
+    pub fn at(x: i64, y: i64) -> Self {
         Self::default().with_position(x, y)
     }
 
     fn update_bounding_box(&mut self) {
         if let Some(ref mut fill) = self.fill {
             let (x, y) = self.position;
-            let (w, h) = self.size;
-            fill.set_bounding_box((x, y, x + w, y + h));
+            let (w, h) = (i64::from(self.size.0), i64::from(self.size.1));
+            fill.set_bounding_box(clip_bounding_box((x, y, x + w, y + h)));
         }
     }
 
     /// Creates a new square with side length `s` with the top-left corner at the given coordinates.
     #[must_use]
-    pub fn square(s: u32, (x, y): (u32, u32)) -> Self {
+    pub fn square(s: u32, (x, y): (i64, i64)) -> Self {
         Self::at(x, y).with_size(s, s)
     }
 
@@ -879,23 +2092,26 @@ impl<F: IntoFill> Rectangle<F> {
     /// The first coordinate is the top-left corner of the rectangle, and the second coordinate is
     /// the bottom-right corner of the rectangle.
     #[must_use]
-    pub fn from_bounding_box(mut x1: u32, mut y1: u32, mut x2: u32, mut y2: u32) -> Self {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn from_bounding_box(mut x1: i64, mut y1: i64, mut x2: i64, mut y2: i64) -> Self {
         if x1 > x2 {
-            std::mem::swap(&mut x1, &mut x2);
+            core::mem::swap(&mut x1, &mut x2);
         }
         if y1 > y2 {
-            std::mem::swap(&mut y1, &mut y2);
+            core::mem::swap(&mut y1, &mut y2);
         }
 
         Self::default()
             .with_position(x1, y1)
-            .with_size(x2 - x1, y2 - y1)
+            .with_size((x2 - x1) as u32, (y2 - y1) as u32)
     }
 
-    /// Sets the position of the rectangle.
+    /// Sets the position of the rectangle. The position may be negative, in which case the
+    /// rectangle is partially drawn off-canvas.
     #[must_use]
-    pub fn with_position(mut self, x: u32, y: u32) -> Self {
+    pub fn with_position(mut self, x: i64, y: i64) -> Self {
         self.position = (x, y);
+        self.subpixel_bounds = None;
         self.update_bounding_box();
         self
     }
@@ -904,6 +2120,40 @@ impl<F: IntoFill> Rectangle<F> {
     #[must_use]
     pub fn with_size(mut self, width: u32, height: u32) -> Self {
         self.size = (width, height);
+        self.subpixel_bounds = None;
+        self.update_bounding_box();
+        self
+    }
+
+    /// Sets the bounding box of the rectangle with sub-pixel precision, in the same coordinate
+    /// system as [`from_bounding_box`][Self::from_bounding_box]. Edges which don't lie on integer
+    /// coordinates partially cover the pixels they cross.
+    ///
+    /// This also sets [`position`][Self::position] and [`size`][Self::size] to the smallest
+    /// integer bounding box containing the rectangle.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(16, 16, Rgba::black());
+    /// image.draw(
+    ///     &Rectangle::at(0, 0)
+    ///         .with_subpixel_bounds(2.5, 2.25, 13.5, 9.75)
+    ///         .with_fill(Rgba::white()),
+    /// );
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn with_subpixel_bounds(mut self, x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        let (x1, x2) = (x1.min(x2), x1.max(x2));
+        let (y1, y2) = (y1.min(y2), y1.max(y2));
+
+        self.position = (x1.floor() as i64, y1.floor() as i64);
+        self.size = (
+            (x2.ceil() - x1.floor()) as u32,
+            (y2.ceil() - y1.floor()) as u32,
+        );
+        self.subpixel_bounds = Some((x1, y1, x2, y2));
         self.update_bounding_box();
         self
     }
@@ -938,10 +2188,100 @@ impl<F: IntoFill> Rectangle<F> {
         self.overlay = Some(mode);
         self
     }
+
+    /// Rounds off all corners of the rectangle with the given radius.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let card = Rectangle::from_bounding_box(8, 8, 120, 72)
+    ///     .with_fill(Rgba::white())
+    ///     .with_border(Border::new(Rgba::black(), 2))
+    ///     .with_corner_radius(12)
+    ///     .with_antialiased(true);
+    ///
+    /// let mut image = Image::new(128, 80, Rgba::transparent());
+    /// image.draw(&card);
+    /// ```
+    #[must_use]
+    pub const fn with_corner_radius(self, radius: u32) -> Self {
+        self.with_corner_radii(radius, radius, radius, radius)
+    }
+
+    /// Rounds off each corner of the rectangle with its own radius.
+    #[must_use]
+    pub const fn with_corner_radii(
+        mut self,
+        top_left: u32,
+        top_right: u32,
+        bottom_right: u32,
+        bottom_left: u32,
+    ) -> Self {
+        self.radii = [top_left, top_right, bottom_right, bottom_left];
+        self
+    }
+
+    /// Sets whether to antialias the rounded corners and sub-pixel edges of the rectangle. If set
+    /// to `true`, this will also set the overlay mode to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased {
+            self.overlay = Some(OverlayMode::Merge);
+        }
+        self
+    }
+
+    /// Draws the rectangle with rounded corners or sub-pixel edges using coverage-based
+    /// rasterization.
+    #[allow(clippy::cast_precision_loss)]
+    fn draw_coverage(&self, image: &mut impl DrawTarget<F::Pixel>, overlay: OverlayMode) {
+        let (x, y) = self.position;
+        let (w, h) = self.size;
+        let dimensions = image.dimensions();
+
+        // Pixel `x` spans `[x - 0.5, x + 0.5)`, so offset the edges of the rectangle accordingly.
+        let (x1, y1, x2, y2) = self.subpixel_bounds.map_or_else(
+            || {
+                let (x1, y1) = (x as f32 - 0.5, y as f32 - 0.5);
+                (x1, y1, x1 + w as f32, y1 + h as f32)
+            },
+            |(x1, y1, x2, y2)| (x1 - 0.5, y1 - 0.5, x2 - 0.5, y2 - 0.5),
+        );
+        let radii = raster::fit_radii(self.radii.map(|r| r as f32), (x2 - x1, y2 - y1));
+
+        if let Some(ref fill) = self.fill {
+            let contour = raster::rounded_rectangle((x1, y1, x2, y2), radii);
+
+            raster::rasterize(&[contour], FillRule::NonZero, dimensions, |x, y, alpha| {
+                plot_coverage(fill, image, (x, y), overlay, alpha, self.antialiased);
+            });
+        }
+
+        if let Some((inner, outer, color)) = self.border.as_ref().map(Border::bounds) {
+            let (inner, outer) = (inner as f32, outer as f32);
+            let mut contours = vec![raster::rounded_rectangle(
+                (x1 - outer, y1 - outer, x2 + outer, y2 + outer),
+                radii.map(|r| if r > 0.0 { r + outer } else { 0.0 }),
+            )];
+
+            if x2 - x1 > inner * 2.0 && y2 - y1 > inner * 2.0 {
+                contours.push(raster::rounded_rectangle(
+                    (x1 + inner, y1 + inner, x2 - inner, y2 - inner),
+                    radii.map(|r| (r - inner).max(0.0)),
+                ));
+            }
+
+            let fill = SolidFill::new(color);
+            raster::rasterize(&contours, FillRule::EvenOdd, dimensions, |x, y, alpha| {
+                plot_coverage(&fill, image, (x, y), overlay, alpha, self.antialiased);
+            });
+        }
+    }
 }
 
-impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
-    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+impl<F: IntoFill> Rectangle<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
         assert!(
             self.fill.is_some() || self.border.is_some(),
             "must provide one of either fill or border, try calling .with_fill()"
@@ -952,17 +2292,26 @@ impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
         );
 
         let (x1, y1) = self.position;
-        let (w, h) = self.size;
+        let (w, h) = (i64::from(self.size.0), i64::from(self.size.1));
         // Exclusive bounds
         let (x2, y2) = (x1 + w, y1 + h);
-        let overlay = self.overlay.unwrap_or(image.overlay);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
+
+        if self.radii.iter().any(|&r| r > 0) || self.subpixel_bounds.is_some() {
+            self.draw_coverage(image, overlay);
+            return;
+        }
+
+        let (width, height) = (i64::from(image.width()), i64::from(image.height()));
+        let clip = |(x1, y1, x2, y2): BoundingBox<i64>| {
+            let xs = x1.max(0)..x2.min(width);
+            (y1.max(0)..y2.min(height)).flat_map(move |y| xs.clone().map(move |x| (x, y)))
+        };
 
         // Draw the fill first
         if let Some(ref fill) = self.fill {
-            for y in y1..y2 {
-                for x in x1..x2 {
-                    fill.plot(&mut image, x, y, overlay);
-                }
+            for (x, y) in clip((x1, y1, x2, y2)) {
+                fill.plot(image, x as u32, y as u32, overlay);
             }
         }
 
@@ -971,23 +2320,33 @@ impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
         // normal though and I do plan to fix it, for example calculating border bounds first and
         // only filling in pixels that are not in those bounds.
         if let Some((inner, outer, color)) = self.border.as_ref().map(Border::bounds) {
-            // Top and bottom border
-            for y in (y1 - outer..y1 + inner).chain(y2 - inner..y2 + outer) {
-                for x in x1..x2 {
-                    image.overlay_pixel_with_mode(x, y, color, overlay);
-                }
-            }
-
-            // Left and right border
-            for x in (x1 - outer..x1 + inner).chain(x2 - inner..x2 + outer) {
-                for y in y1 - outer..y2 + outer {
-                    image.overlay_pixel_with_mode(x, y, color, overlay);
-                }
+            let (inner, outer) = (i64::from(inner), i64::from(outer));
+            let edges = [
+                // Top and bottom border
+                (x1, y1 - outer, x2, y1 + inner),
+                (x1, y2 - inner, x2, y2 + outer),
+                // Left and right border
+                (x1 - outer, y1 - outer, x1 + inner, y2 + outer),
+                (x2 - inner, y1 - outer, x2 + outer, y2 + outer),
+            ];
+
+            for (x, y) in edges.into_iter().flat_map(clip) {
+                image.overlay_pixel_with_mode(x as u32, y as u32, color, overlay);
             }
         }
     }
 }
 
+impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
 /// An ellipse, which could be a circle.
 ///
 /// Using any of the predefined constructors will automatically set the position to `(0, 0)` and
@@ -1000,8 +2359,9 @@ impl<F: IntoFill> Draw<F::Pixel> for Rectangle<F> {
 #[derive(Clone, Debug)]
 pub struct Ellipse<F: IntoFill> {
     /// The center position of the ellipse.
-    /// The center of this ellipse will be rendered at this position.
-    pub position: (u32, u32),
+    /// The center of this ellipse will be rendered at this position, which may be negative or
+    /// outside of the image, in which case the ellipse is clipped.
+    pub position: (i64, i64),
     /// The radii of the ellipse, in pixels; (horizontal, vertical).
     pub radii: (u32, u32),
     // The border data for the ellipse if any.
@@ -1010,6 +2370,16 @@ pub struct Ellipse<F: IntoFill> {
     pub fill: Option<F::Fill>,
     // The overlay mode for the ellipse or None to inherit from the image's overlay mode.
     pub overlay: Option<OverlayMode>,
+    /// The rotation of the ellipse around its center in radians, clockwise. Defaults to `0.0`.
+    pub rotation: f32,
+    /// Whether to antialias the edges of the ellipse. Defaults to `true`.
+    pub antialiased: bool,
+    /// The center position of the ellipse with sub-pixel precision, or `None` to use
+    /// [`position`][Self::position]. Defaults to `None`.
+    pub subpixel_position: Option<(f32, f32)>,
+    /// The radii of the ellipse with sub-pixel precision, or `None` to use
+    /// [`radii`][Self::radii]. Defaults to `None`.
+    pub subpixel_radii: Option<(f32, f32)>,
 }
 
 impl<F: IntoFill> Default for Ellipse<F> {
@@ -1020,6 +2390,10 @@ impl<F: IntoFill> Default for Ellipse<F> {
             border: None,
             fill: None,
             overlay: None,
+            rotation: 0.0,
+            antialiased: true,
+            subpixel_position: None,
+            subpixel_radii: None,
         }
     }
 }
@@ -1041,14 +2415,35 @@ impl<F: IntoFill> Ellipse<F> {
         Self::default()
     }
 
+    /// Returns the horizontal and vertical distance from the center of the ellipse to the edges of
+    /// its bounding box, taking rotation into account.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn extents(&self) -> (u32, u32) {
+        #[allow(clippy::float_cmp)]
+        if self.rotation == 0.0 {
+            return self.radii;
+        }
+
+        let (a, b) = self.float_radii();
+        let (sin, cos) = self.rotation.sin_cos();
+
+        (
+            (a * cos).hypot(b * sin).ceil() as u32,
+            (a * sin).hypot(b * cos).ceil() as u32,
+        )
+    }
+
     fn update_bounding_box(&mut self) {
+        let (w, h) = self.extents();
+        let (w, h) = (i64::from(w), i64::from(h));
+        let (x, y) = self.position;
+
         if let Some(ref mut fill) = self.fill {
-            fill.set_bounding_box((
-                self.position.0 - self.radii.0,
-                self.position.1 - self.radii.1,
-                self.position.0 + self.radii.0,
-                self.position.1 + self.radii.1,
-            ));
+            fill.set_bounding_box(clip_bounding_box((x - w, y - h, x + w, y + h)));
         }
     }
 
@@ -1058,28 +2453,33 @@ impl<F: IntoFill> Ellipse<F> {
     /// * `x2 < x1`
     /// * `y2 < y1`
     #[must_use]
-    pub fn from_bounding_box(x1: u32, y1: u32, x2: u32, y2: u32) -> Self {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn from_bounding_box(x1: i64, y1: i64, x2: i64, y2: i64) -> Self {
         assert!(x2 >= x1, "invalid bounding box");
         assert!(y2 >= y1, "invalid bounding box");
 
         let (dx, dy) = (x2 - x1, y2 - y1);
         let (x, y) = (x1 + dx / 2, y1 + dy / 2);
 
-        Self::default().with_position(x, y).with_size(dx, dy)
+        Self::default()
+            .with_position(x, y)
+            .with_size(dx as u32, dy as u32)
     }
 
     /// Creates a new circle with the given center position and radius.
     #[must_use]
-    pub fn circle(x: u32, y: u32, radius: u32) -> Self {
+    pub fn circle(x: i64, y: i64, radius: u32) -> Self {
         Self::default()
             .with_position(x, y)
             .with_radii(radius, radius)
     }
 
-    /// Sets the position of the ellipse.
+    /// Sets the center position of the ellipse. The position may be negative, in which case the
+    /// ellipse is partially drawn off-canvas.
     #[must_use]
-    pub fn with_position(mut self, x: u32, y: u32) -> Self {
+    pub fn with_position(mut self, x: i64, y: i64) -> Self {
         self.position = (x, y);
+        self.subpixel_position = None;
         self.update_bounding_box();
         self
     }
@@ -1088,18 +2488,70 @@ impl<F: IntoFill> Ellipse<F> {
     #[must_use]
     pub fn with_radii(mut self, width: u32, height: u32) -> Self {
         self.radii = (width, height);
+        self.subpixel_radii = None;
         self.update_bounding_box();
         self
     }
 
     /// Sets the diameters of the ellipse in pixels.
     #[must_use]
-    pub fn with_size(mut self, width: u32, height: u32) -> Self {
-        self.radii = (width / 2, height / 2);
+    pub fn with_size(self, width: u32, height: u32) -> Self {
+        self.with_radii(width / 2, height / 2)
+    }
+
+    /// Sets the center position of the ellipse with sub-pixel precision, where integer coordinates
+    /// lie on the center of a pixel.
+    ///
+    /// This also sets [`position`][Self::position] to the rounded coordinates.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(32, 32, Rgba::black());
+    /// image.draw(
+    ///     &Ellipse::new()
+    ///         .with_subpixel_position(15.5, 15.5)
+    ///         .with_subpixel_radii(10.25, 6.5)
+    ///         .with_fill(Rgba::white()),
+    /// );
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn with_subpixel_position(mut self, x: f32, y: f32) -> Self {
+        self.position = (x.round() as i64, y.round() as i64);
+        self.subpixel_position = Some((x, y));
+        self.update_bounding_box();
+        self
+    }
+
+    /// Sets the radii of the ellipse with sub-pixel precision, in the same units as
+    /// [`with_radii`][Self::with_radii].
+    ///
+    /// This also sets [`radii`][Self::radii] to the radii rounded up.
+    ///
+    /// # Panics
+    /// * Either radius is negative.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn with_subpixel_radii(mut self, width: f32, height: f32) -> Self {
+        assert!(
+            width >= 0.0 && height >= 0.0,
+            "ellipse radii must not be negative"
+        );
+
+        self.radii = (width.ceil() as u32, height.ceil() as u32);
+        self.subpixel_radii = Some((width, height));
         self.update_bounding_box();
         self
     }
 
+    /// Returns the radii of the ellipse, with sub-pixel precision if set.
+    #[allow(clippy::cast_precision_loss)]
+    fn float_radii(&self) -> (f32, f32) {
+        self.subpixel_radii
+            .unwrap_or((self.radii.0 as f32, self.radii.1 as f32))
+    }
+
     /// Sets the border of the ellipse.
     #[must_use]
     pub const fn with_border(mut self, border: Border<F::Pixel>) -> Self {
@@ -1128,29 +2580,115 @@ impl<F: IntoFill> Ellipse<F> {
         self
     }
 
+    /// Sets the rotation of the ellipse around its center in radians, clockwise.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let orbit = Ellipse::<Rgba>::from_bounding_box(8, 24, 120, 72)
+    ///     .with_border(Border::new(Rgba::black(), 2))
+    ///     .with_rotation(-core::f32::consts::FRAC_PI_6)
+    ///     .with_antialiased(true);
+    ///
+    /// let mut image = Image::new(128, 96, Rgba::white());
+    /// image.draw(&orbit);
+    /// ```
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self.update_bounding_box();
+        self
+    }
+
+    /// Sets the rotation of the ellipse around its center in degrees, clockwise.
+    ///
+    /// # See Also
+    /// * [`with_rotation`][Ellipse::with_rotation]
+    #[must_use]
+    pub fn with_rotation_degrees(self, rotation: f32) -> Self {
+        self.with_rotation(rotation.to_radians())
+    }
+
+    /// Sets whether to antialias the edges of the ellipse. If set to `true`, this will also set the
+    /// overlay mode to [`OverlayMode::Merge`].
+    #[must_use]
+    pub const fn with_antialiased(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        if antialiased {
+            self.overlay = Some(OverlayMode::Merge);
+        }
+        self
+    }
+
+    /// Renders the ellipse using coverage-based rasterization, which supports rotation and
+    /// antialiasing.
+    #[allow(clippy::cast_precision_loss)]
+    fn render_coverage(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let center = self
+            .subpixel_position
+            .unwrap_or((self.position.0 as f32, self.position.1 as f32));
+        // Extend the radii by half a pixel so that the ellipse covers the same pixels as it would
+        // when rendered without antialiasing.
+        let (a, b) = self.float_radii();
+        let (a, b) = (a + 0.5, b + 0.5);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
+        let dimensions = image.dimensions();
+
+        if let Some(ref fill) = self.fill {
+            let contour = raster::ellipse(center, (a, b), self.rotation);
+
+            raster::rasterize(&[contour], FillRule::NonZero, dimensions, |x, y, alpha| {
+                plot_coverage(fill, image, (x, y), overlay, alpha, self.antialiased);
+            });
+        }
+
+        if let Some((inner, outer, color)) = self.border.as_ref().map(Border::bounds) {
+            let (inner, outer) = (inner as f32, outer as f32);
+            let mut contours = vec![raster::ellipse(
+                center,
+                (a + outer, b + outer),
+                self.rotation,
+            )];
+            if a > inner && b > inner {
+                contours.push(raster::ellipse(
+                    center,
+                    (a - inner, b - inner),
+                    self.rotation,
+                ));
+            }
+
+            let fill = SolidFill::new(color);
+            raster::rasterize(&contours, FillRule::EvenOdd, dimensions, |x, y, alpha| {
+                plot_coverage(&fill, image, (x, y), overlay, alpha, self.antialiased);
+            });
+        }
+    }
+
     // Used when there is no border
-    #[allow(clippy::cast_possible_wrap)]
-    fn rasterize_filled_circle(&self, image: &mut Image<F::Pixel>) {
-        let radius = self.radii.0 as i32;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn rasterize_filled_circle(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let radius = i64::from(self.radii.0);
 
         let mut x = 0;
         let mut y = radius;
         let mut p = 1 - radius;
 
         let (h, k) = self.position;
-        let (h, k) = (h as i32, k as i32);
+        let (width, height) = (i64::from(image.width()), i64::from(image.height()));
 
         #[allow(unused_variables)] // rust knows this, but the external linter doesn't
         let fill = self.fill.as_ref().unwrap();
         #[allow(unused_variables)] // rust knows this, but the external linter doesn't
-        let overlay = self.overlay.unwrap_or(image.overlay);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
 
         macro_rules! line {
             ($from:expr, $to:expr, $y:expr) => {{
-                let y = $y as u32;
+                let y = $y;
 
-                for x in ($from as u32)..=($to as u32) {
-                    fill.plot(image, x, y, overlay);
+                if (0..height).contains(&y) {
+                    for x in ($from).max(0)..=($to).min(width - 1) {
+                        fill.plot(image, x as u32, y as u32, overlay);
+                    }
                 }
             }};
         }
@@ -1172,14 +2710,19 @@ impl<F: IntoFill> Ellipse<F> {
     }
 
     // Used when there is no border
-    #[allow(clippy::cast_possible_wrap, clippy::cast_precision_loss)]
-    fn rasterize_filled_ellipse(&self, image: &mut Image<F::Pixel>) {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn rasterize_filled_ellipse(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        #[allow(unused_variables)] // rust knows this, but the external linter doesn't
         let (ch, k) = self.position;
         #[allow(unused_variables)] // rust knows this, but the external linter doesn't
-        let (ch, k) = (ch as i32, k as i32);
+        let (width, height) = (i64::from(image.width()), i64::from(image.height()));
 
         let (w, h) = self.radii;
-        let (w, h) = (w as i32, h as i32);
+        let (w, h) = (i64::from(w), i64::from(h));
         let (w2, h2) = (w * w, h * h);
 
         let mut x = 0;
@@ -1190,14 +2733,16 @@ impl<F: IntoFill> Ellipse<F> {
         #[allow(unused_variables)] // rust knows this, but the external linter doesn't
         let fill = self.fill.as_ref().unwrap();
         #[allow(unused_variables)] // rust knows this, but the external linter doesn't
-        let overlay = self.overlay.unwrap_or(image.overlay);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
 
         macro_rules! line {
             ($from:expr, $to:expr, $y:expr) => {{
-                let y = ($y) as u32;
+                let y = $y;
 
-                for x in ($from)..=($to) {
-                    fill.plot(image, x as u32, y, overlay);
+                if (0..height).contains(&y) {
+                    for x in ($from).max(0)..=($to).min(width - 1) {
+                        fill.plot(image, x as u32, y as u32, overlay);
+                    }
                 }
             }};
             ($x:expr, $y:expr) => {{
@@ -1241,24 +2786,23 @@ impl<F: IntoFill> Ellipse<F> {
     }
 
     // Standard, slower brute force algorithm that iterates through all pixels
-    #[allow(clippy::cast_possible_wrap)]
-    fn render_circle(&self, image: &mut Image<F::Pixel>) {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn render_circle(&self, image: &mut impl DrawTarget<F::Pixel>) {
         let (h, k) = self.position;
-        let (h, k) = (h as i32, k as i32);
-        let r = self.radii.0 as i32;
+        let r = i64::from(self.radii.0);
         let r2 = r * r;
 
         let (mut x1, mut y1) = (h - r, k - r);
         let (mut x2, mut y2) = (h + r, k + r);
 
-        let overlay = self.overlay.unwrap_or(image.overlay);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
         let border = self
             .border
             .as_ref()
             .map(Border::bounds)
             .map(|(inner, outer, color)| {
-                let inner = inner as i32;
-                let outer = outer as i32;
+                let inner = i64::from(inner);
+                let outer = i64::from(outer);
 
                 x1 -= outer;
                 y1 -= outer;
@@ -1270,8 +2814,9 @@ impl<F: IntoFill> Ellipse<F> {
                 (inner * inner, outer * outer, color)
             });
 
-        for y in y1..=y2 {
-            for x in x1..=x2 {
+        let (width, height) = (i64::from(image.width()), i64::from(image.height()));
+        for y in y1.max(0)..=y2.min(height - 1) {
+            for x in x1.max(0)..=x2.min(width - 1) {
                 let dx = x - h;
                 let dy = y - k;
                 let d2 = dx * dx + dy * dy;
@@ -1293,25 +2838,28 @@ impl<F: IntoFill> Ellipse<F> {
     }
 
     // Standard, slower brute force algorithm that iterates through all pixels
-    #[allow(clippy::cast_possible_wrap, clippy::cast_precision_loss)]
-    fn render_ellipse(&self, image: &mut Image<F::Pixel>) {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn render_ellipse(&self, image: &mut impl DrawTarget<F::Pixel>) {
         let (h, k) = self.position;
-        let (h, k) = (h as i32, k as i32);
         let (a, b) = self.radii;
-        let (a, b) = (a as i32, b as i32);
+        let (a, b) = (i64::from(a), i64::from(b));
         let (a2, b2) = ((a * a) as f32, (b * b) as f32);
 
         let (mut x1, mut y1) = (h - a, k - b);
         let (mut x2, mut y2) = (h + a, k + b);
 
-        let overlay = self.overlay.unwrap_or(image.overlay);
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
         let border = self
             .border
             .as_ref()
             .map(Border::bounds)
             .map(|(inner, outer, color)| {
-                let inner = inner as i32;
-                let outer = outer as i32;
+                let inner = i64::from(inner);
+                let outer = i64::from(outer);
 
                 x1 -= outer;
                 y1 -= outer;
@@ -1330,8 +2878,9 @@ impl<F: IntoFill> Ellipse<F> {
                 )
             });
 
-        for y in y1..=y2 {
-            for x in x1..=x2 {
+        let (width, height) = (i64::from(image.width()), i64::from(image.height()));
+        for y in y1.max(0)..=y2.min(height - 1) {
+            for x in x1.max(0)..=x2.min(width - 1) {
                 let dx = x - h;
                 let dy = y - k;
                 let dx2 = (dx * dx) as f32;
@@ -1353,8 +2902,8 @@ impl<F: IntoFill> Ellipse<F> {
     }
 }
 
-impl<F: IntoFill> Draw<F::Pixel> for Ellipse<F> {
-    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+impl<F: IntoFill> Ellipse<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
         assert!(
             self.fill.is_some() || self.border.is_some(),
             "must provide one of either fill or border, try calling .with_fill()"
@@ -1364,7 +2913,15 @@ impl<F: IntoFill> Draw<F::Pixel> for Ellipse<F> {
             "ellipse must have non-zero radii, have you called .with_size() yet?"
         );
 
-        let image = &mut *image;
+        #[allow(clippy::float_cmp)]
+        if self.antialiased
+            || self.rotation != 0.0
+            || self.subpixel_position.is_some()
+            || self.subpixel_radii.is_some()
+        {
+            self.render_coverage(image);
+            return;
+        }
 
         if self.border.is_none() {
             if self.radii.0 == self.radii.1 {
@@ -1384,6 +2941,165 @@ impl<F: IntoFill> Draw<F::Pixel> for Ellipse<F> {
     }
 }
 
+impl<F: IntoFill> Draw<F::Pixel> for Ellipse<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
+/// Fills the contiguous region of similarly colored pixels around a seed point, similar to the
+/// "bucket fill" tool of image editors.
+///
+/// The region is 4-connected, i.e. it spreads horizontally and vertically but not diagonally.
+/// A pixel belongs to the region if none of its RGBA channels differ from those of the seed pixel
+/// by more than the [`tolerance`][Self::tolerance].
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// let mut image = Image::new(256, 256, Rgb::white());
+/// image.draw(&Ellipse::<Rgb>::circle(128, 128, 64).with_border(Border::new(Rgb::black(), 2)));
+/// // Fill the inside of the circle
+/// image.draw(&FloodFill::new(128, 128, Rgb::new(255, 0, 0)).with_tolerance(16));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FloodFill<F: IntoFill> {
+    /// The seed point of the fill. Nothing is drawn if it lies outside of the image.
+    pub position: (u32, u32),
+    /// The fill of the region. Gradients and patterns span the bounding box of the region.
+    pub fill: F::Fill,
+    /// The maximum difference of any channel of a pixel from the seed pixel for it to be included
+    /// in the region. Defaults to `0`, which only fills pixels of the exact same color.
+    pub tolerance: u8,
+    /// The overlay mode of the fill, or None to inherit from the overlay mode of the image.
+    pub overlay: Option<OverlayMode>,
+}
+
+impl<F: IntoFill> FloodFill<F> {
+    /// Creates a new flood fill starting at the given seed point with the given fill.
+    #[must_use]
+    pub fn new(x: u32, y: u32, fill: F) -> Self {
+        Self {
+            position: (x, y),
+            fill: fill.into_fill(),
+            tolerance: 0,
+            overlay: None,
+        }
+    }
+
+    /// Sets the seed point of the fill.
+    #[must_use]
+    pub const fn with_position(mut self, x: u32, y: u32) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the color tolerance of the fill. See [`tolerance`][Self::tolerance] for details.
+    #[must_use]
+    pub const fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the overlay mode of the fill.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Computes the region to fill as a mask over the image, along with its exclusive bounding
+    /// box. Uses a scanline fill, which pushes one seed per horizontal run rather than per pixel.
+    fn region(&self, image: &impl DrawTarget<F::Pixel>) -> (Vec<bool>, BoundingBox<u32>) {
+        let (width, height) = image.dimensions();
+        let (x, y) = self.position;
+        let rgba = |x, y| image.get_pixel(x, y).expect("pixel is in bounds").as_rgba();
+        let target = rgba(x, y);
+        let matches = |x: u32, y: u32| {
+            let pixel = rgba(x, y);
+            pixel.r.abs_diff(target.r) <= self.tolerance
+                && pixel.g.abs_diff(target.g) <= self.tolerance
+                && pixel.b.abs_diff(target.b) <= self.tolerance
+                && pixel.a.abs_diff(target.a) <= self.tolerance
+        };
+
+        let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+        let mut mask = vec![false; width as usize * height as usize];
+        let mut bounds = (x, y, x + 1, y + 1);
+        let mut stack = vec![(x, y)];
+
+        while let Some((x, y)) = stack.pop() {
+            if mask[index(x, y)] {
+                continue;
+            }
+
+            let mut left = x;
+            while left > 0 && !mask[index(left - 1, y)] && matches(left - 1, y) {
+                left -= 1;
+            }
+            let mut right = x + 1;
+            while right < width && !mask[index(right, y)] && matches(right, y) {
+                right += 1;
+            }
+
+            mask[index(left, y)..index(right, y)].fill(true);
+            bounds = (
+                bounds.0.min(left),
+                bounds.1.min(y),
+                bounds.2.max(right),
+                bounds.3.max(y + 1),
+            );
+
+            let rows = [y.checked_sub(1), Some(y + 1).filter(|&y| y < height)];
+            for row in rows.into_iter().flatten() {
+                let mut in_run = false;
+                for x in left..right {
+                    let fillable = !mask[index(x, row)] && matches(x, row);
+                    if fillable && !in_run {
+                        stack.push((x, row));
+                    }
+                    in_run = fillable;
+                }
+            }
+        }
+
+        (mask, bounds)
+    }
+}
+
+impl<F: IntoFill> FloodFill<F> {
+    fn render(&self, image: &mut impl DrawTarget<F::Pixel>) {
+        let (x, y) = self.position;
+        if x >= image.width() || y >= image.height() {
+            return;
+        }
+
+        let overlay = self.overlay.unwrap_or(image.overlay_mode());
+        let (mask, bounds) = self.region(image);
+        let fill = self.fill.clone().with_bounding_box(bounds);
+        let width = image.width() as usize;
+
+        for (i, _) in mask.into_iter().enumerate().filter(|&(_, filled)| filled) {
+            #[allow(clippy::cast_possible_truncation)]
+            fill.plot(image, (i % width) as u32, (i / width) as u32, overlay);
+        }
+    }
+}
+
+impl<F: IntoFill> Draw<F::Pixel> for FloodFill<F> {
+    fn draw<I: DerefMut<Target = Image<F::Pixel>>>(&self, mut image: I) {
+        self.render(&mut *image);
+    }
+
+    fn draw_view(&self, view: &mut ImageViewMut<'_, F::Pixel>) {
+        self.render(view);
+    }
+}
+
 /// Pastes or overlays an image on top of another image.
 #[derive(Clone)]
 pub struct Paste<'img, 'mask, P: Pixel> {
@@ -1399,6 +3115,16 @@ pub struct Paste<'img, 'mask, P: Pixel> {
     ///
     /// If this is None, all pixels will be overlaid on top of the image.
     pub mask: Option<&'mask Image<BitPixel>>,
+    /// A reference to a grayscale image whose luminance values are used as the opacity of each
+    /// corresponding pixel of the foreground image, where `0` fully masks out the pixel and `255`
+    /// pastes it as-is.
+    ///
+    /// This can be used alongside [`Self::mask`], in which case pixels must pass both masks.
+    pub alpha_mask: Option<&'mask Image<L>>,
+    /// The global opacity of the foreground image, from `0.0` (fully transparent) to `1.0`
+    /// (opaque). This scales the alpha of every pasted pixel, and is combined with
+    /// [`Self::alpha_mask`] if one is set. Defaults to `1.0`.
+    pub opacity: f32,
     /// The overlay mode of the image, or None to inherit from the background image.
     pub overlay: Option<OverlayMode>,
 }
@@ -1411,6 +3137,8 @@ impl<'img, 'mask, P: Pixel> Paste<'img, 'mask, P> {
             position: (0, 0),
             image,
             mask: None,
+            alpha_mask: None,
+            opacity: 1.0,
             overlay: None,
         }
     }
@@ -1459,6 +3187,54 @@ impl<'img, 'mask, P: Pixel> Paste<'img, 'mask, P> {
         self
     }
 
+    /// Sets the grayscale mask image to use. The luminance of each mask pixel determines the
+    /// opacity of the corresponding foreground pixel.
+    ///
+    /// This **must** have the same dimensions as the base foreground image! You will receive a
+    /// panic if this is not the case.
+    ///
+    /// # Panics
+    /// * The mask image has different dimensions than the foreground image.
+    #[must_use]
+    pub fn with_alpha_mask(self, mask: &'mask Image<L>) -> Self {
+        assert_eq!(
+            self.image.dimensions(),
+            mask.dimensions(),
+            "mask image with dimensions {:?} has different dimensions \
+            than foreground image with dimensions {:?}",
+            mask.dimensions(),
+            self.image.dimensions(),
+        );
+
+        // SAFETY: checked dimensions above
+        unsafe { self.with_alpha_mask_unchecked(mask) }
+    }
+
+    /// Sets the grayscale mask image to use without checking its dimensions.
+    ///
+    /// # Safety
+    /// This should have the same dimensions as the base foreground image! This method does not
+    /// check for that though, however if this is not the case, you may get undescriptive panics
+    /// later. Use [`Paste::with_alpha_mask`] instead if you are not 100% sure that the mask
+    /// dimensions are valid.
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub unsafe fn with_alpha_mask_unchecked(mut self, mask: &'mask Image<L>) -> Self {
+        self.alpha_mask = Some(mask);
+        self
+    }
+
+    /// Sets the global opacity of the image, from `0.0` (fully transparent) to `1.0` (opaque).
+    /// Values outside of this range are clamped.
+    ///
+    /// This scales the alpha of every pasted pixel during blending, so there is no need to clone
+    /// the foreground image and pre-multiply its alpha.
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
     /// Sets the overlay mode of the image.
     #[must_use]
     pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
@@ -1473,10 +3249,43 @@ impl<'img, 'mask, P: Pixel> Draw<P> for Paste<'img, 'mask, P> {
         let (w, h) = self.image.dimensions();
         let overlay = self.overlay.unwrap_or(image.overlay);
         let mask = self.mask.as_ref();
+        let opacity = self.opacity.clamp(0.0, 1.0);
 
         // These are exclusive bounds
         let (x2, y2) = (x1 + w as i64, y1 + h as i64);
 
+        // Without masks, every pixel of a row shares the same alpha, so clipped rows can be
+        // blended as whole slices
+        if mask.is_none() && self.alpha_mask.is_none() {
+            let alpha = (255. * opacity).round() as u8;
+            let (x_start, x_end) = (x1.max(0), x2.min(i64::from(image.width())));
+            if alpha == 0 || x_start >= x_end {
+                return;
+            }
+
+            let span = (x_end - x_start) as usize;
+            let alphas = if alpha < u8::MAX {
+                vec![alpha; span]
+            } else {
+                Vec::new()
+            };
+            let width = image.width() as usize;
+
+            for y in y1.max(0)..y2.min(i64::from(image.height())) {
+                let src = (y - y1) as usize * w as usize + (x_start - x1) as usize;
+                let src = &self.image.data[src..src + span];
+                let dest = y as usize * width + x_start as usize;
+                let dest = &mut image.data[dest..dest + span];
+
+                if alpha == u8::MAX {
+                    P::overlay_row(dest, src, overlay);
+                } else {
+                    P::overlay_row_with_alpha(dest, src, overlay, &alphas);
+                }
+            }
+            return;
+        }
+
         for (y, i) in (y1..y2).zip(0..) {
             if y < 0 {
                 continue;
@@ -1489,10 +3298,246 @@ impl<'img, 'mask, P: Pixel> Draw<P> for Paste<'img, 'mask, P> {
                 {
                     continue;
                 }
+                let alpha = self
+                    .alpha_mask
+                    .and_then(|mask| mask.get_pixel(j, i).map(L::value))
+                    .unwrap_or(u8::MAX);
+                let alpha = if opacity < 1.0 {
+                    (f32::from(alpha) * opacity).round() as u8
+                } else {
+                    alpha
+                };
+                if alpha == 0 {
+                    continue;
+                }
                 if let Some(pixel) = self.image.get_pixel(j, i) {
-                    image.overlay_pixel_with_mode(x as u32, y as u32, *pixel, overlay);
+                    if alpha == u8::MAX {
+                        image.overlay_pixel_with_mode(x as u32, y as u32, *pixel, overlay);
+                    } else {
+                        image.overlay_pixel_with_alpha(x as u32, y as u32, *pixel, overlay, alpha);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Wraps any drawable object so that its output is modulated by a grayscale mask.
+///
+/// The luminance of each mask pixel is used as the opacity of the wrapped object at that pixel:
+/// where the mask is `0` the image is left untouched, where it is `255` the object is drawn as
+/// normal, and anything in between is blended. Pixels outside of the mask are left untouched.
+///
+/// The mask is placed on the image at [`Self::position`], which defaults to `(0, 0)`.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::new(64, 64, Rgb::black());
+/// // Fade from transparent (left) to opaque (right)
+/// let mask = Image::from_fn(64, 64, |x, _| L((x * 4) as u8));
+///
+/// image.draw(&Masked::new(
+///     Rectangle::from_bounding_box(0, 0, 64, 64).with_fill(Rgb::white()),
+///     &mask,
+/// ));
+/// ```
+#[derive(Clone)]
+pub struct Masked<'mask, D> {
+    /// The object to draw.
+    pub entity: D,
+    /// The grayscale mask which modulates the opacity of the drawn object.
+    pub mask: &'mask Image<L>,
+    /// The position of the top-left corner of the mask on the image.
+    pub position: (i64, i64),
+}
+
+impl<'mask, D> Masked<'mask, D> {
+    /// Wraps the given drawable object with the given mask, placed at `(0, 0)`.
+    #[must_use]
+    pub const fn new(entity: D, mask: &'mask Image<L>) -> Self {
+        Self {
+            entity,
+            mask,
+            position: (0, 0),
+        }
+    }
+
+    /// Sets the position of the top-left corner of the mask on the image.
+    #[must_use]
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = (x, y);
+        self
+    }
+}
+
+impl<'mask, P: Pixel, D: Draw<P>> Draw<P> for Masked<'mask, D> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let image = &mut *image;
+        let (x1, y1) = self.position;
+        let (w, h) = self.mask.dimensions();
+        let (x2, y2) = (x1 + w as i64, y1 + h as i64);
+        let (width, height) = image.dimensions();
+        let (x2, y2) = (x2.min(width as i64), y2.min(height as i64));
+        if x2 <= 0 || y2 <= 0 {
+            return;
+        }
+
+        let drawn = draw_onto_region(image, &self.entity, x2 as u32, y2 as u32);
+        // SAFETY: pixels are only rebound right before they are written into the image
+        let palette = unsafe { image.detached_palette() };
+
+        for (y, i) in (y1..y2).zip(0..) {
+            if y < 0 {
+                continue;
+            }
+            for (x, j) in (x1..x2).zip(0..) {
+                if x < 0 {
+                    continue;
+                }
+
+                let alpha = self.mask.pixel(j, i).value();
+                let (x, y) = (x as u32, y as u32);
+                if alpha == 0 {
+                    continue;
+                }
+                if let Some(&pixel) = drawn.get_pixel(x, y) {
+                    let pixel = unsafe { rebind_pixel(palette, pixel) };
+                    let base = image.pixel_mut(x, y);
+                    *base = if alpha == u8::MAX {
+                        pixel
+                    } else {
+                        base.merge_with_alpha(pixel, alpha)
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// A region that drawing operations can be clipped to. See [`Clip`].
+#[derive(Copy, Clone)]
+pub enum ClipRegion<'mask> {
+    /// A rectangular region represented by its bounding box `(x1, y1, x2, y2)`. Left and top
+    /// bounds are inclusive; right and bottom bounds are exclusive.
+    Rectangle(u32, u32, u32, u32),
+    /// An arbitrarily shaped region where only pixels that are "on" in the mask are drawn. The
+    /// top-left corner of the mask is aligned with the top-left corner of the image, and pixels
+    /// outside of the mask are clipped out.
+    Mask(&'mask Image<BitPixel>),
+}
+
+impl ClipRegion<'_> {
+    fn contains(&self, x: u32, y: u32) -> bool {
+        match *self {
+            Self::Rectangle(x1, y1, x2, y2) => x >= x1 && x < x2 && y >= y1 && y < y2,
+            Self::Mask(mask) => matches!(mask.get_pixel(x, y), Some(BitPixel(true))),
+        }
+    }
+}
+
+/// Wraps any drawable object so that it only renders inside of a clipping region. Pixels outside
+/// of the region are left untouched.
+///
+/// # Example
+/// Clipping a circle to the top-left quadrant of the image:
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::new(64, 64, Rgb::black());
+/// let circle = Ellipse::circle(32, 32, 24).with_fill(Rgb::white());
+///
+/// image.draw(&Clip::rect(circle, 0, 0, 32, 32));
+/// assert_eq!(image.pixel(40, 40), &Rgb::black());
+/// ```
+///
+/// # See Also
+/// * [`Masked`] for soft clipping using a grayscale mask.
+#[derive(Clone)]
+pub struct Clip<'mask, D> {
+    /// The object to draw.
+    pub entity: D,
+    /// The region to clip the object to.
+    pub region: ClipRegion<'mask>,
+}
+
+impl<'mask, D> Clip<'mask, D> {
+    /// Wraps the given drawable object so that it is clipped to the given region.
+    #[must_use]
+    pub const fn new(entity: D, region: ClipRegion<'mask>) -> Self {
+        Self { entity, region }
+    }
+
+    /// Wraps the given drawable object so that it is clipped to the given rectangular bounding
+    /// box. Left and top bounds are inclusive; right and bottom bounds are exclusive.
+    #[must_use]
+    pub const fn rect(entity: D, x1: u32, y1: u32, x2: u32, y2: u32) -> Self {
+        Self::new(entity, ClipRegion::Rectangle(x1, y1, x2, y2))
+    }
+
+    /// Wraps the given drawable object so that it is only drawn where the given mask is "on".
+    #[must_use]
+    pub const fn mask(entity: D, mask: &'mask Image<BitPixel>) -> Self {
+        Self::new(entity, ClipRegion::Mask(mask))
+    }
+}
+
+impl<'mask, P: Pixel, D: Draw<P>> Draw<P> for Clip<'mask, D> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let image = &mut *image;
+        let (width, height) = image.dimensions();
+        let (x1, y1, x2, y2) = match self.region {
+            ClipRegion::Rectangle(x1, y1, x2, y2) => (x1, y1, x2.min(width), y2.min(height)),
+            ClipRegion::Mask(mask) => (0, 0, mask.width().min(width), mask.height().min(height)),
+        };
+        if x1 >= x2 || y1 >= y2 {
+            return;
+        }
+
+        let drawn = draw_onto_region(image, &self.entity, x2, y2);
+        // SAFETY: pixels are only rebound right before they are written into the image
+        let palette = unsafe { image.detached_palette() };
+        let (x1, x2) = (x1 as usize, x2 as usize);
+
+        for ((row, src), y) in image
+            .data
+            .chunks_exact_mut(width as usize)
+            .zip(drawn.data.chunks_exact(x2))
+            .zip(0..)
+            .take(y2 as usize)
+            .skip(y1 as usize)
+        {
+            match self.region {
+                ClipRegion::Rectangle(..) => unsafe {
+                    copy_rebound(palette, &mut row[x1..x2], &src[x1..]);
+                },
+                ClipRegion::Mask(_) => {
+                    for (x, (pixel, src)) in row.iter_mut().zip(src).enumerate() {
+                        if self.region.contains(x as u32, y) {
+                            *pixel = unsafe { rebind_pixel(palette, *src) };
+                        }
+                    }
                 }
             }
         }
     }
 }
+
+/// Draws the given object onto a copy of the image which only spans from its top-left corner up
+/// to the exclusive bottom-right corner `(x2, y2)`, for wrappers which only write back the pixels
+/// of a region of the image. The object is still drawn in the coordinates of the whole image,
+/// which is why the copy can not start at the top-left corner of the region itself.
+///
+/// Paletted pixels of the copy are bound to its own palette, so they must be rebound to the
+/// palette of the image before they are written into it.
+pub(crate) fn draw_onto_region<P: Pixel>(
+    image: &Image<P>,
+    entity: &impl Draw<P>,
+    x2: u32,
+    y2: u32,
+) -> Image<P> {
+    let mut region = image
+        .copy_rect(Rect::new(0, 0, x2, y2), OutOfBounds::Clamp)
+        .expect("clamped copies never fail");
+    region.draw(entity);
+    region
+}
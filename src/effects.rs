@@ -0,0 +1,650 @@
+//! Creative effects and distortions such as kaleidoscopes, vignettes and swirls.
+
+use crate::{parallel, Draw, Image, OverlayMode, Pixel, TrueColor};
+use core::{
+    f64::consts::{PI, SQRT_2},
+    ops::DerefMut,
+};
+use num_traits::Euclid;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Which half of an image is reflected onto the other half, used by [`Image::mirror_half`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MirrorMode {
+    /// Reflects the left half of the image onto the right half.
+    LeftToRight,
+    /// Reflects the right half of the image onto the left half.
+    RightToLeft,
+    /// Reflects the top half of the image onto the bottom half.
+    TopToBottom,
+    /// Reflects the bottom half of the image onto the top half.
+    BottomToTop,
+}
+
+/// How the red and blue channels are shifted by [`Image::chromatic_aberration`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Aberration {
+    /// Shifts the red channel by the given horizontal and vertical offsets in pixels, and the
+    /// blue channel by the opposite offsets.
+    Linear(f64, f64),
+    /// Scales the red channel outwards from the center of the image and the blue channel inwards,
+    /// where the given amount is the shift in pixels at the corners of the image. The shift
+    /// decreases linearly towards the center, where there is no shift. Negative amounts swap the
+    /// directions.
+    Radial(f64),
+}
+
+/// Darkens or tints the edges and corners of an image with a smooth radial falloff.
+///
+/// The falloff is elliptical, following the aspect ratio of the image. The distance from the
+/// center is normalized so that the corners of the image are at a distance of `1.0`.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::new(64, 48, Rgb::white());
+/// image.draw(&Vignette::new(Rgb::black()).with_strength(0.8).with_radius(0.4));
+///
+/// assert_eq!(image.pixel(32, 24), &Rgb::white());
+/// assert!(image.pixel(0, 0).r < 64);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Vignette<P: Pixel> {
+    /// The color the edges of the image are tinted with, usually black.
+    pub color: P,
+    /// The opacity of the color at the corners of the image, from `0.0` to `1.0`. Defaults to
+    /// `0.5`.
+    pub strength: f64,
+    /// The normalized distance from the center at which the falloff starts, from `0.0` to `1.0`.
+    /// Anything closer to the center is left untouched. Defaults to `0.5`.
+    pub radius: f64,
+    /// The overlay mode used to tint the image, or `None` to use [`OverlayMode::Merge`]
+    /// regardless of the overlay mode of the image.
+    pub overlay: Option<OverlayMode>,
+}
+
+impl<P: Pixel> Vignette<P> {
+    /// Creates a new vignette with the given color and default strength and radius.
+    #[must_use]
+    pub const fn new(color: P) -> Self {
+        Self {
+            color,
+            strength: 0.5,
+            radius: 0.5,
+            overlay: None,
+        }
+    }
+
+    /// Sets the opacity of the color at the corners of the image, from `0.0` to `1.0`. Values
+    /// outside of this range are clamped.
+    #[must_use]
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the normalized distance from the center at which the falloff starts, from `0.0` to
+    /// `1.0`. Values outside of this range are clamped.
+    #[must_use]
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the overlay mode used to tint the image.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+}
+
+impl<P: Pixel> Draw<P> for Vignette<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let (width, height) = image.dimensions();
+        let (half_width, half_height) = (f64::from(width) / 2.0, f64::from(height) / 2.0);
+        let (strength, radius) = (self.strength.clamp(0.0, 1.0), self.radius.clamp(0.0, 1.0));
+        let overlay = self.overlay.unwrap_or(OverlayMode::Merge);
+
+        for (x, y, pixel) in image.pixels_with_coords_mut() {
+            let dx = (f64::from(x) + 0.5 - half_width) / half_width;
+            let dy = (f64::from(y) + 0.5 - half_height) / half_height;
+            let distance = dx.hypot(dy) / SQRT_2;
+            if distance <= radius {
+                continue;
+            }
+
+            // Smoothstep the falloff between the radius and the corners
+            let t = ((distance - radius) / (1.0 - radius)).min(1.0);
+            let alpha = (strength * t * t * (3.0 - 2.0 * t) * 255.0).round() as u8;
+            if alpha > 0 {
+                *pixel = pixel.overlay_with_alpha(self.color, overlay, alpha);
+            }
+        }
+    }
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Samples this image at the given sub-pixel coordinates using bilinear interpolation, where
+    /// the center of the pixel at `(x, y)` lies at `(x, y)`. Coordinates outside of the image are
+    /// clamped to its edges.
+    pub(crate) fn sample_bilinear(&self, x: f64, y: f64) -> P {
+        let (width, height) = (self.width(), self.height());
+        let x = x.clamp(0.0, f64::from(width - 1));
+        let y = y.clamp(0.0, f64::from(height - 1));
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (fx, fy) = (x - f64::from(x0), y - f64::from(y0));
+        // Avoid round-tripping through Rgba when no interpolation is needed, since it is lossy for
+        // some pixel types
+        #[allow(clippy::float_cmp)]
+        if fx == 0.0 && fy == 0.0 {
+            return *self.pixel(x0, y0);
+        }
+
+        let channels = |x: u32, y: u32| {
+            let (r, g, b, a) = self.pixel(x, y).as_rgba_tuple();
+            [r, g, b, a].map(f64::from)
+        };
+        let (top_left, top_right) = (channels(x0, y0), channels(x1, y0));
+        let (bottom_left, bottom_right) = (channels(x0, y1), channels(x1, y1));
+
+        let mut out = [0_u8; 4];
+        for (i, out) in out.iter_mut().enumerate() {
+            let top = top_left[i] + (top_right[i] - top_left[i]) * fx;
+            let bottom = bottom_left[i] + (bottom_right[i] - bottom_left[i]) * fx;
+            *out = (top + (bottom - top) * fy).round() as u8;
+        }
+
+        let [r, g, b, a] = out;
+        P::from_rgba_tuple((r, g, b, a))
+    }
+
+    /// Remaps this image by sampling the source coordinates returned by the given function for
+    /// every destination pixel, in parallel if the `rayon` feature is enabled.
+    pub(crate) fn remap(&mut self, f: impl Fn(u32, u32) -> (f64, f64) + Send + Sync) {
+        let width = self.width();
+        let source = &*self;
+        let f = &f;
+
+        let data = parallel::map_rows(self.height(), |y| {
+            (0..width).map(move |x| {
+                let (sx, sy) = f(x, y);
+                source.sample_bilinear(sx, sy)
+            })
+        });
+        self.data = data;
+    }
+
+    /// Applies an N-fold kaleidoscope effect about the given center in place: a wedge of the image
+    /// spanning `180 / segments` degrees clockwise from the positive x-axis is mirrored and
+    /// repeated around the center, `segments` times in each direction.
+    ///
+    /// # Panics
+    /// * `segments` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(64, 64, |x, y| Rgb::new((x * 4) as u8, (y * 4) as u8, 128));
+    /// image.kaleidoscope(6, (32, 32));
+    /// ```
+    pub fn kaleidoscope(&mut self, segments: u32, center: (u32, u32)) {
+        assert!(segments > 0, "kaleidoscope must have at least one segment");
+
+        let (cx, cy) = (f64::from(center.0), f64::from(center.1));
+        let segment = PI / f64::from(segments);
+
+        self.remap(|x, y| {
+            let (dx, dy) = (f64::from(x) - cx, f64::from(y) - cy);
+            let mut angle = Euclid::rem_euclid(&dy.atan2(dx), &(segment * 2.0));
+            if angle > segment {
+                angle = segment * 2.0 - angle;
+            }
+
+            let (sin, cos) = angle.sin_cos();
+            let radius = dx.hypot(dy);
+            (cx + radius * cos, cy + radius * sin)
+        });
+    }
+
+    /// Takes this image and applies an N-fold kaleidoscope effect about the given center. Useful
+    /// for method chaining.
+    ///
+    /// # Panics
+    /// * `segments` is zero.
+    ///
+    /// # See Also
+    /// * [`Self::kaleidoscope`] for more information.
+    #[must_use]
+    pub fn kaleidoscoped(mut self, segments: u32, center: (u32, u32)) -> Self {
+        self.kaleidoscope(segments, center);
+        self
+    }
+
+    /// Applies a chromatic aberration effect in place, which shifts the red and blue channels of
+    /// this image in opposite directions, as seen in cheap lenses or glitch and VHS style filters.
+    /// The green and alpha channels are left untouched.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(64, 64, Rgb::black());
+    /// image.draw(&Rectangle::from_bounding_box(24, 24, 40, 40).with_fill(Rgb::white()));
+    /// image.chromatic_aberration(Aberration::Linear(3.0, 0.0));
+    ///
+    /// // The red channel was shifted right, and the blue channel left
+    /// assert_eq!(image.pixel(41, 32), &Rgb::new(255, 0, 0));
+    /// assert_eq!(image.pixel(22, 32), &Rgb::new(0, 0, 255));
+    /// ```
+    pub fn chromatic_aberration(&mut self, aberration: Aberration) {
+        let (width, height) = (self.width(), self.height());
+        let (cx, cy) = (f64::from(width - 1) / 2.0, f64::from(height - 1) / 2.0);
+        let half_diagonal = cx.hypot(cy).max(1.0);
+        let source = &*self;
+
+        let data = parallel::map_rows(height, |y| {
+            (0..width).map(move |x| {
+                let (x, y) = (f64::from(x), f64::from(y));
+                let (dx, dy) = match aberration {
+                    Aberration::Linear(dx, dy) => (dx, dy),
+                    Aberration::Radial(amount) => {
+                        let scale = amount / half_diagonal;
+                        ((x - cx) * scale, (y - cy) * scale)
+                    }
+                };
+
+                let (_, g, _, a) = source.pixel(x as u32, y as u32).as_rgba_tuple();
+                let (r, ..) = source.sample_bilinear(x - dx, y - dy).as_rgba_tuple();
+                let (.., b, _) = source.sample_bilinear(x + dx, y + dy).as_rgba_tuple();
+                P::from_rgba_tuple((r, g, b, a))
+            })
+        });
+        self.data = data;
+    }
+
+    /// Takes this image and applies a chromatic aberration effect to it. Useful for method
+    /// chaining.
+    ///
+    /// # See Also
+    /// * [`Self::chromatic_aberration`] for more information.
+    #[must_use]
+    pub fn chromatic_aberrated(mut self, aberration: Aberration) -> Self {
+        self.chromatic_aberration(aberration);
+        self
+    }
+
+    /// Warps this image in place with the given displacement map, where the red and green
+    /// channels of each pixel of the map offset the coordinates this image is sampled at
+    /// horizontally and vertically, respectively. A value of `128` means no offset, `0` offsets
+    /// by `-strength` pixels and `255` offsets by almost `strength` pixels.
+    ///
+    /// Grayscale maps offset both axes equally. This enables effects such as water ripples, heat
+    /// shimmer and glass distortion.
+    ///
+    /// # Panics
+    /// * The displacement map has different dimensions from this image.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(64, 64, |x, y| Rgb::new((x * 4) as u8, (y * 4) as u8, 0));
+    /// // Horizontal ripples
+    /// let map = Image::from_fn(64, 64, |_, y| {
+    ///     let offset = ((y as f64 / 4.0).sin() * 127.0 + 128.0) as u8;
+    ///     Rgb::new(offset, 128, 128)
+    /// });
+    ///
+    /// image.displace(&map, 4.0);
+    /// ```
+    pub fn displace<T: TrueColor>(&mut self, map: &Image<T>, strength: f64) {
+        assert_eq!(
+            self.dimensions(),
+            map.dimensions(),
+            "displacement map with dimensions {:?} must have the \
+            same dimensions as this image with dimensions {:?}",
+            map.dimensions(),
+            self.dimensions(),
+        );
+
+        let offset = |value: u8| (f64::from(value) - 128.0) / 128.0 * strength;
+        self.remap(|x, y| {
+            let (r, g, ..) = map.pixel(x, y).as_rgba_tuple();
+            (f64::from(x) + offset(r), f64::from(y) + offset(g))
+        });
+    }
+
+    /// Takes this image and warps it with the given displacement map. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * The displacement map has different dimensions from this image.
+    ///
+    /// # See Also
+    /// * [`Self::displace`] for more information.
+    #[must_use]
+    pub fn displaced<T: TrueColor>(mut self, map: &Image<T>, strength: f64) -> Self {
+        self.displace(map, strength);
+        self
+    }
+
+    /// Remaps this image radially about its center, where the given function maps the normalized
+    /// distance of a destination pixel from the center to the normalized distance it is sampled
+    /// from. Distances are normalized so that the corners of the image are at a distance of `1.0`.
+    fn remap_radially(&mut self, f: impl Fn(f64) -> f64 + Send + Sync) {
+        let (cx, cy) = (
+            f64::from(self.width() - 1) / 2.0,
+            f64::from(self.height() - 1) / 2.0,
+        );
+        let half_diagonal = cx.hypot(cy).max(1.0);
+
+        self.remap(|x, y| {
+            let (dx, dy) = (f64::from(x) - cx, f64::from(y) - cy);
+            let radius = dx.hypot(dy) / half_diagonal;
+            if radius == 0.0 {
+                return (cx, cy);
+            }
+
+            let scale = f(radius) / radius;
+            (cx + dx * scale, cy + dy * scale)
+        });
+    }
+
+    /// Applies radial lens distortion to this image in place, using the Brown-Conrady model with
+    /// the radial coefficients `k1` and `k2`: each pixel at normalized distance `r` from the center
+    /// is sampled from distance `r * (1 + k1 * r^2 + k2 * r^4)`. Distances are normalized so that
+    /// the corners of the image are at a distance of `1.0`.
+    ///
+    /// Positive coefficients produce barrel distortion, as seen in fisheye lenses, while negative
+    /// coefficients produce pincushion distortion. Areas sampled from outside of the image are
+    /// filled with the nearest edge pixel.
+    ///
+    /// # See Also
+    /// * [`Self::lens_undistort`] to reverse this distortion, e.g. to correct fisheye photos.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(64, 64, |x, y| Rgb::new((x * 4) as u8, (y * 4) as u8, 0));
+    /// // Fisheye effect
+    /// image.lens_distort(0.3, 0.1);
+    /// ```
+    pub fn lens_distort(&mut self, k1: f64, k2: f64) {
+        self.remap_radially(|r| {
+            let r2 = r * r;
+            r * (1.0 + k1 * r2 + k2 * r2 * r2)
+        });
+    }
+
+    /// Takes this image and applies radial lens distortion to it. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::lens_distort`] for more information.
+    #[must_use]
+    pub fn lens_distorted(mut self, k1: f64, k2: f64) -> Self {
+        self.lens_distort(k1, k2);
+        self
+    }
+
+    /// Reverses radial lens distortion with the coefficients `k1` and `k2` in place. This is the
+    /// inverse of [`Self::lens_distort`] with the same coefficients, and can be used to correct
+    /// barrel distortion in fisheye photos, or pincushion distortion in telephoto photos.
+    ///
+    /// The inverse of the distortion model is computed numerically. Areas sampled from outside of
+    /// the image are filled with the nearest edge pixel.
+    ///
+    /// # See Also
+    /// * [`Self::lens_distort`] for more information on the coefficients.
+    pub fn lens_undistort(&mut self, k1: f64, k2: f64) {
+        self.remap_radially(|target| {
+            // Solve `r * (1 + k1 * r^2 + k2 * r^4) = target` with Newton's method
+            let mut r = target;
+            for _ in 0..20 {
+                let r2 = r * r;
+                let value = r * (1.0 + k1 * r2 + k2 * r2 * r2) - target;
+                let derivative = 1.0 + 3.0 * k1 * r2 + 5.0 * k2 * r2 * r2;
+                if derivative.abs() < f64::EPSILON {
+                    break;
+                }
+
+                let step = value / derivative;
+                r -= step;
+                if step.abs() < 1e-9 {
+                    break;
+                }
+            }
+            r
+        });
+    }
+
+    /// Takes this image and reverses radial lens distortion on it. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::lens_undistort`] for more information.
+    #[must_use]
+    pub fn lens_undistorted(mut self, k1: f64, k2: f64) -> Self {
+        self.lens_undistort(k1, k2);
+        self
+    }
+
+    /// Swirls this image in place by rotating the pixels around the given center, where pixels at
+    /// the center are rotated by the given angle in degrees and the rotation falls off smoothly
+    /// to zero at the given radius. Positive angles swirl clockwise.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(64, 64, |x, y| Rgb::new((x * 4) as u8, (y * 4) as u8, 0));
+    /// image.swirl((32, 32), 24, 180.0);
+    ///
+    /// // Pixels outside of the radius are untouched
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(0, 0, 0));
+    /// ```
+    pub fn swirl(&mut self, center: (u32, u32), radius: u32, degrees: f64) {
+        let (cx, cy) = (f64::from(center.0), f64::from(center.1));
+        let radius = f64::from(radius);
+        let angle = degrees.to_radians();
+
+        self.remap(|x, y| {
+            let (dx, dy) = (f64::from(x) - cx, f64::from(y) - cy);
+            let distance = dx.hypot(dy);
+            if distance >= radius {
+                return (f64::from(x), f64::from(y));
+            }
+
+            let falloff = 1.0 - distance / radius;
+            // Rotate backwards to find where the pixel is swirled from
+            let (sin, cos) = (-angle * falloff * falloff).sin_cos();
+            (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+        });
+    }
+
+    /// Takes this image and swirls it around the given center. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::swirl`] for more information.
+    #[must_use]
+    pub fn swirled(mut self, center: (u32, u32), radius: u32, degrees: f64) -> Self {
+        self.swirl(center, radius, degrees);
+        self
+    }
+
+    /// Pixelates this image in place by averaging each square block of the given size and filling
+    /// the block with the average color. Blocks at the right and bottom edges may be smaller.
+    ///
+    /// # Panics
+    /// * `block_size` is zero.
+    ///
+    /// # See Also
+    /// * [`Self::pixelate_region`] for only pixelating part of the image, e.g. to censor it.
+    pub fn pixelate(&mut self, block_size: u32) {
+        self.pixelate_region(block_size, 0, 0, self.width(), self.height());
+    }
+
+    /// Takes this image and pixelates it by averaging each square block of the given size. Useful
+    /// for method chaining.
+    ///
+    /// # Panics
+    /// * `block_size` is zero.
+    ///
+    /// # See Also
+    /// * [`Self::pixelate`] for more information.
+    #[must_use]
+    pub fn pixelated(mut self, block_size: u32) -> Self {
+        self.pixelate(block_size);
+        self
+    }
+
+    /// Pixelates the given region of this image in place, where `(x1, y1)` is the top-left corner
+    /// and `(x2, y2)` is the exclusive bottom-right corner. Blocks are aligned to the top-left
+    /// corner of the region, and the region is clipped to the bounds of the image.
+    ///
+    /// This is commonly used to censor faces or text.
+    ///
+    /// # Panics
+    /// * `block_size` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(64, 64, |x, y| Rgb::new((x * 4) as u8, (y * 4) as u8, 0));
+    /// // Censor a 32x16 area with 8x8 blocks
+    /// image.pixelate_region(8, 16, 24, 48, 40);
+    ///
+    /// assert_eq!(image.pixel(16, 24), image.pixel(23, 31));
+    /// ```
+    pub fn pixelate_region(&mut self, block_size: u32, x1: u32, y1: u32, x2: u32, y2: u32) {
+        assert!(block_size > 0, "block size must be non-zero");
+
+        let (x2, y2) = (x2.min(self.width()), y2.min(self.height()));
+        for by in (y1..y2).step_by(block_size as usize) {
+            let rows = by..(by + block_size).min(y2);
+            for bx in (x1..x2).step_by(block_size as usize) {
+                let columns = bx..(bx + block_size).min(x2);
+                let count = u64::from(rows.len() as u32 * columns.len() as u32);
+
+                let mut sums = [0_u64; 4];
+                for y in rows.clone() {
+                    for x in columns.clone() {
+                        let (r, g, b, a) = self.pixel(x, y).as_rgba_tuple();
+                        for (sum, value) in sums.iter_mut().zip([r, g, b, a]) {
+                            *sum += u64::from(value);
+                        }
+                    }
+                }
+
+                let [r, g, b, a] = sums.map(|sum| ((sum + count / 2) / count) as u8);
+                let average = P::from_rgba_tuple((r, g, b, a));
+                for y in rows.clone() {
+                    let start = (y * self.width() + columns.start) as usize;
+                    self.data[start..start + columns.len()].fill(average);
+                }
+            }
+        }
+    }
+}
+
+impl<P: Pixel> Image<P> {
+    /// Reflects one half of this image onto the other half in place, producing a symmetrical
+    /// image. For images with an odd width or height, the middle row or column is left untouched.
+    ///
+    /// This is different from [`Self::mirror`], which flips the entire image.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(4, 1, |x, _| L(x as u8));
+    /// image.mirror_half(MirrorMode::LeftToRight);
+    ///
+    /// assert_eq!(image.data, [L(0), L(1), L(1), L(0)]);
+    /// ```
+    pub fn mirror_half(&mut self, mode: MirrorMode) {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+
+        match mode {
+            MirrorMode::LeftToRight | MirrorMode::RightToLeft => {
+                for row in self.data.chunks_exact_mut(width) {
+                    let (left, right) = row.split_at_mut(width - width / 2);
+                    let left = &mut left[..width / 2];
+                    if mode == MirrorMode::LeftToRight {
+                        right.copy_from_slice(left);
+                        right.reverse();
+                    } else {
+                        left.copy_from_slice(right);
+                        left.reverse();
+                    }
+                }
+            }
+            MirrorMode::TopToBottom | MirrorMode::BottomToTop => {
+                for y in 0..height / 2 {
+                    let (top, bottom) = self.data.split_at_mut((height - y - 1) * width);
+                    let (top, bottom) =
+                        (&mut top[y * width..(y + 1) * width], &mut bottom[..width]);
+                    if mode == MirrorMode::TopToBottom {
+                        bottom.copy_from_slice(top);
+                    } else {
+                        top.copy_from_slice(bottom);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Takes this image and reflects one half of it onto the other half. Useful for method
+    /// chaining.
+    ///
+    /// # See Also
+    /// * [`Self::mirror_half`] for more information.
+    #[must_use]
+    pub fn mirrored_half(mut self, mode: MirrorMode) -> Self {
+        self.mirror_half(mode);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rgb, L};
+
+    #[test]
+    fn test_kaleidoscope_symmetry() {
+        let image = Image::from_fn(33, 33, |x, y| Rgb::new((x * 7) as u8, (y * 7) as u8, 0))
+            .kaleidoscoped(4, (16, 16));
+
+        for (x, y, pixel) in image.pixels_with_coords() {
+            // Every segment is mirrored about both axes through the center
+            assert_eq!(pixel, image.pixel(32 - x, y));
+            assert_eq!(pixel, image.pixel(x, 32 - y));
+        }
+    }
+
+    #[test]
+    fn test_displace() {
+        let image = Image::from_fn(16, 16, |x, y| L((x * 16 + y) as u8));
+        let neutral = Image::new(16, 16, L(128));
+        assert_eq!(image.clone().displaced(&neutral, 8.0).data, image.data);
+
+        // Sample 4 pixels to the right, clamping at the edge
+        let map = Image::new(16, 16, Rgb::new(192, 128, 128));
+        let displaced = image.clone().displaced(&map, 8.0);
+        assert_eq!(displaced.pixel(3, 5), image.pixel(7, 5));
+        assert_eq!(displaced.pixel(14, 5), image.pixel(15, 5));
+    }
+
+    #[test]
+    fn test_lens_undistort_reverses_distortion() {
+        let image = Image::from_fn(48, 48, |x, y| Rgb::new((x * 5) as u8, (y * 5) as u8, 0));
+        let restored = image
+            .clone()
+            .lens_distorted(0.2, 0.05)
+            .lens_undistorted(0.2, 0.05);
+
+        // Resampling twice blurs slightly, so allow for small differences
+        for (x, y, pixel) in restored.pixels_with_coords() {
+            let original = image.pixel(x, y);
+            assert!((i32::from(pixel.r) - i32::from(original.r)).abs() <= 2);
+            assert!((i32::from(pixel.g) - i32::from(original.g)).abs() <= 2);
+        }
+    }
+}
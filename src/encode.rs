@@ -1,15 +1,22 @@
 //! Houses Encoder, Decoder, and frame iterator traits.
 
-use crate::{ColorType, DisposalMethod, Error, Frame, Image, ImageSequence, LoopCount, Pixel};
+use crate::{
+    BlendMethod, ColorType, DisposalMethod, Error, Frame, Image, ImageMetadata, ImageSequence,
+    LoopCount, Pixel,
+};
 use std::ops::DerefMut;
 use std::{
     io::{Read, Write},
     ops::Deref,
+    path::PathBuf,
     time::Duration,
 };
 
 mod sealed {
-    use super::{ColorType, DisposalMethod, Duration, Frame, Image, LoopCount, Pixel};
+    use super::{
+        BlendMethod, ColorType, DisposalMethod, Duration, Frame, Image, ImageMetadata, LoopCount,
+        Pixel,
+    };
 
     pub trait HasEncoderMetadata<C: Default, P: Pixel>: Sized {
         fn width(&self) -> u32;
@@ -23,6 +30,9 @@ mod sealed {
         fn palette(&self) -> Option<&[P::Color]> {
             None
         }
+        fn image_metadata(&self) -> Option<&ImageMetadata> {
+            None
+        }
         fn config(self) -> C {
             C::default()
         }
@@ -32,6 +42,8 @@ mod sealed {
         fn image(&self) -> &Image<P>;
         fn delay(&self) -> Option<Duration>;
         fn disposal(&self) -> Option<DisposalMethod>;
+        fn offset(&self) -> Option<(u32, u32)>;
+        fn blend(&self) -> Option<BlendMethod>;
     }
 
     impl<P: Pixel> FrameLike<P> for Image<P> {
@@ -44,6 +56,12 @@ mod sealed {
         fn disposal(&self) -> Option<DisposalMethod> {
             None
         }
+        fn offset(&self) -> Option<(u32, u32)> {
+            None
+        }
+        fn blend(&self) -> Option<BlendMethod> {
+            None
+        }
     }
 
     impl<P: Pixel> FrameLike<P> for Frame<P> {
@@ -56,6 +74,12 @@ mod sealed {
         fn disposal(&self) -> Option<DisposalMethod> {
             Some(self.disposal())
         }
+        fn offset(&self) -> Option<(u32, u32)> {
+            Some(self.offset())
+        }
+        fn blend(&self) -> Option<BlendMethod> {
+            Some(self.blend())
+        }
     }
 }
 
@@ -80,6 +104,9 @@ impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a Image<P> {
     fn palette(&self) -> Option<&[P::Color]> {
         Image::palette(self)
     }
+    fn image_metadata(&self) -> Option<&ImageMetadata> {
+        Some(self.metadata())
+    }
 }
 
 impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a Frame<P> {
@@ -101,6 +128,9 @@ impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a Frame<P> {
     fn palette(&self) -> Option<&[P::Color]> {
         Image::palette(self.image())
     }
+    fn image_metadata(&self) -> Option<&ImageMetadata> {
+        Some(self.image().metadata())
+    }
 }
 
 impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a ImageSequence<P> {
@@ -138,6 +168,10 @@ impl<'a, C: Default, P: Pixel> HasEncoderMetadata<C, P> for &'a ImageSequence<P>
         self.first_frame()
             .and_then(|frame| Image::palette(frame.image()))
     }
+
+    fn image_metadata(&self) -> Option<&ImageMetadata> {
+        self.first_frame().map(|frame| frame.image().metadata())
+    }
 }
 
 /// Manually configured encoder metadata. This is used to provide fine-grained control over the
@@ -168,6 +202,9 @@ pub struct EncoderMetadata<P: Pixel> {
     pub bit_depth: u8,
     /// The palette of the image.
     pub palette: Option<Box<[P::Color]>>,
+    /// Metadata such as text, color profiles and physical resolution to write alongside the image,
+    /// if the format supports it.
+    pub image_metadata: ImageMetadata,
 }
 
 macro_rules! impl_from_metadata {
@@ -182,6 +219,7 @@ macro_rules! impl_from_metadata {
                         color_type: HasEncoderMetadata::<(), P>::color_type(&metadata),
                         bit_depth: HasEncoderMetadata::<(), P>::bit_depth(&metadata),
                         palette: HasEncoderMetadata::<(), P>::palette(&metadata).map(|p| p.to_vec().into_boxed_slice()),
+                        image_metadata: HasEncoderMetadata::<(), P>::image_metadata(&metadata).cloned().unwrap_or_default(),
                     }
                 }
             }
@@ -210,6 +248,9 @@ impl<C: Default, P: Pixel> HasEncoderMetadata<C, P> for EncoderMetadata<P> {
     fn palette(&self) -> Option<&[P::Color]> {
         self.palette.as_deref()
     }
+    fn image_metadata(&self) -> Option<&ImageMetadata> {
+        Some(&self.image_metadata)
+    }
 }
 
 impl<P: Pixel> EncoderMetadata<P> {
@@ -232,6 +273,7 @@ impl<P: Pixel> EncoderMetadata<P> {
             color_type: P::COLOR_TYPE,
             bit_depth: P::BIT_DEPTH,
             palette: None,
+            image_metadata: ImageMetadata::new(),
         }
     }
 
@@ -302,6 +344,13 @@ impl<P: Pixel> EncoderMetadata<P> {
         self.palette = Some(palette.to_vec().into_boxed_slice());
         self
     }
+
+    /// Sets the metadata to write alongside the image, such as text and color profiles.
+    #[must_use]
+    pub fn with_image_metadata(mut self, metadata: ImageMetadata) -> Self {
+        self.image_metadata = metadata;
+        self
+    }
 }
 
 /// An [`EncoderMetadata`] with additional configuration parameters for the specific encoder.
@@ -332,6 +381,9 @@ impl<C: Default, P: Pixel> HasEncoderMetadata<C, P> for EncoderMetadataWithConfi
     fn palette(&self) -> Option<&[P::Color]> {
         HasEncoderMetadata::<C, _>::palette(&self.metadata)
     }
+    fn image_metadata(&self) -> Option<&ImageMetadata> {
+        HasEncoderMetadata::<C, _>::image_metadata(&self.metadata)
+    }
     fn config(self) -> C {
         self.config
     }
@@ -539,8 +591,34 @@ pub trait Encoder<P: Pixel, W: Write>: Sized {
     /// * An error occured during encoding.
     fn encode_sequence(sequence: &ImageSequence<P>, dest: W) -> crate::Result<()> {
         let mut encoder = Self::new(dest, sequence)?;
-        for frame in sequence.iter() {
-            encoder.add_frame(frame)?;
+        for (i, frame) in sequence.iter().enumerate() {
+            encoder.add_frame(frame).map_err(|err| err.with_frame(i))?;
+        }
+        encoder.finish()
+    }
+
+    /// Lazily encodes the frames of the given frame iterator into the given writer. Each frame is
+    /// dropped as soon as it is encoded, so only one frame is held in memory at a time.
+    ///
+    /// The encoder is prepared with the metadata of the first frame along with the frame and loop
+    /// counts of the iterator.
+    ///
+    /// # Errors
+    /// * The iterator has no frames.
+    /// * An error occured while decoding or encoding a frame.
+    fn encode_frames(mut frames: impl FrameIterator<P>, dest: W) -> crate::Result<()> {
+        let first = frames.next().ok_or(Error::EmptyImageError)??;
+        let metadata =
+            EncoderMetadata::from(&first).with_sequence(frames.len() as usize, frames.loop_count());
+
+        let mut encoder = Self::new(dest, metadata)?;
+        encoder.add_frame(&first).map_err(|err| err.with_frame(0))?;
+        drop(first);
+
+        for (i, frame) in frames.enumerate() {
+            encoder
+                .add_frame(&frame?)
+                .map_err(|err| err.with_frame(i + 1))?;
         }
         encoder.finish()
     }
@@ -562,6 +640,73 @@ pub trait Decoder<P: Pixel, R: Read> {
     /// # Errors
     /// * An error occured during decoding.
     fn decode_sequence(&mut self, stream: R) -> crate::Result<Self::Sequence>;
+
+    /// Decodes the given stream row by row, passing each row of pixels to the given sink from top
+    /// to bottom. For animated images, only the first frame is decoded.
+    ///
+    /// Decoders that support it only hold a few rows in memory at a time, which allows processing
+    /// images that are too large to be decoded at once, for example to compute a histogram or a
+    /// thumbnail. Other decoders decode the whole image first. Currently, only PNG images that
+    /// are not interlaced are streamed.
+    ///
+    /// # Errors
+    /// * An error occured during decoding.
+    /// * The sink returned an error, in which case decoding is stopped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ril::encodings::png::PngDecoder;
+    /// # use ril::prelude::*;
+    ///
+    /// # fn main() -> ril::Result<()> {
+    /// let file = std::fs::File::open("huge.png")?;
+    /// let mut sum = 0_u64;
+    /// let mut count = 0_u64;
+    ///
+    /// PngDecoder::new().decode_rows(file, &mut |_y, row: &[L]| {
+    ///     sum += row.iter().map(|p| u64::from(p.0)).sum::<u64>();
+    ///     count += row.len() as u64;
+    ///     Ok(())
+    /// })?;
+    /// println!("average brightness: {}", sum / count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn decode_rows(&mut self, stream: R, sink: &mut impl RowSink<P>) -> crate::Result<()> {
+        let image = self.decode(stream)?;
+        sink.start(image.width(), image.height())?;
+
+        for (row, y) in image.data.chunks_exact(image.width() as usize).zip(0..) {
+            sink.row(y, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Receives the rows of an image as it is decoded with [`Decoder::decode_rows`].
+///
+/// This is implemented for closures which take the index of the row and its pixels.
+pub trait RowSink<P: Pixel> {
+    /// Called once with the dimensions of the image before any rows are passed.
+    ///
+    /// # Errors
+    /// * Any error, which stops decoding.
+    fn start(&mut self, width: u32, height: u32) -> crate::Result<()> {
+        let _ = (width, height);
+        Ok(())
+    }
+
+    /// Called with the index and pixels of each row of the image, from top to bottom.
+    ///
+    /// # Errors
+    /// * Any error, which stops decoding.
+    fn row(&mut self, y: u32, pixels: &[P]) -> crate::Result<()>;
+}
+
+impl<P: Pixel, F: FnMut(u32, &[P]) -> crate::Result<()>> RowSink<P> for F {
+    fn row(&mut self, y: u32, pixels: &[P]) -> crate::Result<()> {
+        self(y, pixels)
+    }
 }
 
 /// Represents the lazy decoding of frames from an encoded image sequence, such as an animated
@@ -645,3 +790,284 @@ impl<P: Pixel> Iterator for SingleFrameIterator<P> {
         }
     }
 }
+
+/// Wraps a frame iterator, attaching the index of the frame and, if known, the path of the file
+/// being decoded to any error raised while decoding a frame.
+pub(crate) struct FrameContext<'a, P: Pixel> {
+    frames: Box<dyn FrameIterator<P> + 'a>,
+    index: usize,
+    path: Option<PathBuf>,
+}
+
+impl<'a, P: Pixel> FrameContext<'a, P> {
+    pub(crate) fn new(frames: Box<dyn FrameIterator<P> + 'a>) -> Self {
+        Self {
+            frames,
+            index: 0,
+            path: None,
+        }
+    }
+
+    #[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), allow(dead_code))]
+    pub(crate) fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl<'a, P: Pixel> Iterator for FrameContext<'a, P> {
+    type Item = crate::Result<Frame<P>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index;
+        self.index += 1;
+
+        self.frames.next().map(|frame| {
+            frame.map_err(|err| {
+                let err = err.with_frame(index);
+                match &self.path {
+                    Some(path) => err.with_path(path.clone()),
+                    None => err,
+                }
+            })
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.frames.size_hint()
+    }
+}
+
+impl<'a, P: Pixel> FrameIterator<P> for FrameContext<'a, P> {
+    fn len(&self) -> u32 {
+        self.frames.len()
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        self.frames.loop_count()
+    }
+}
+
+/// A blocking writer which forwards everything written to it to an asynchronous writer.
+///
+/// This is the writer used by [`AsyncEncoder`] to encode images on a blocking thread while the
+/// encoded bytes are written asynchronously. Writes are buffered and sent in chunks of 64 KiB.
+#[cfg(feature = "tokio")]
+pub struct BlockingWriter {
+    buffer: Vec<u8>,
+    sender: tokio::sync::mpsc::Sender<Vec<u8>>,
+}
+
+#[cfg(feature = "tokio")]
+impl BlockingWriter {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    fn send(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(Self::CHUNK_SIZE));
+        self.sender.blocking_send(chunk).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "the asynchronous writer stopped receiving data",
+            )
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Write for BlockingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= Self::CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.send()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for BlockingWriter {
+    fn drop(&mut self) {
+        // Encoders are not required to flush, so whatever is left is sent here. If this fails, the
+        // receiving end is gone and there is nobody to report the error to.
+        let _ = self.send();
+    }
+}
+
+/// Spawns the given function on a blocking thread of the Tokio runtime, resuming any panic it
+/// raised once the returned future is awaited.
+///
+/// # Panics
+/// * Not called from within a Tokio runtime.
+#[cfg(feature = "tokio")]
+pub(crate) fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> crate::Result<T> + Send + 'static,
+) -> impl std::future::Future<Output = crate::Result<T>> {
+    // Spawned eagerly so that the function runs even before the returned future is polled
+    let handle = tokio::task::spawn_blocking(f);
+
+    async move {
+        match handle.await {
+            Ok(result) => result,
+            Err(err) => match err.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(err) => Err(Error::IoError(err.into())),
+            },
+        }
+    }
+}
+
+/// Runs the given encoding function on a blocking thread of the Tokio runtime, writing the bytes
+/// it encodes to the given asynchronous writer as they are produced.
+///
+/// # Panics
+/// * Not called from within a Tokio runtime.
+#[cfg(feature = "tokio")]
+pub(crate) async fn encode_blocking(
+    dest: &mut (impl tokio::io::AsyncWrite + Unpin),
+    encode: impl FnOnce(BlockingWriter) -> crate::Result<()> + Send + 'static,
+) -> crate::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(4);
+    let writer = BlockingWriter {
+        buffer: Vec::with_capacity(BlockingWriter::CHUNK_SIZE),
+        sender,
+    };
+    let task = spawn_blocking(move || encode(writer));
+
+    // The writer is dropped once encoding finishes, which closes the channel. If writing fails,
+    // dropping the receiver makes the encoder fail on its next write instead of blocking.
+    let written = async {
+        while let Some(chunk) = receiver.recv().await {
+            dest.write_all(&chunk).await?;
+        }
+        dest.flush().await
+    }
+    .await;
+    drop(receiver);
+
+    // Errors from writing take precedence, since they are what made the encoder fail
+    let encoded = task.await;
+    written?;
+    encoded
+}
+
+/// Asynchronous counterparts to [`Encoder`], which encode on a blocking thread of the Tokio
+/// runtime while the encoded bytes are written to an asynchronous writer.
+///
+/// This is implemented for every encoder which can encode into a [`BlockingWriter`]. Since the
+/// encoder runs on another thread, the image or sequence to encode is taken by value.
+///
+/// # Panics
+/// * The returned future is not polled within a Tokio runtime.
+///
+/// # Example
+/// ```no_run
+/// use ril::encode::AsyncEncoder;
+/// use ril::encodings::png::PngEncoder;
+/// # use ril::prelude::*;
+///
+/// # async fn run() -> ril::Result<()> {
+/// let image = Image::new(100, 100, Rgb::new(255, 0, 0));
+/// let mut file = tokio::fs::File::create("out.png").await?;
+/// PngEncoder::encode_static_async(image, &mut file).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub trait AsyncEncoder<P: Pixel + Send + 'static>: Encoder<P, BlockingWriter> + 'static {
+    /// Encodes a static image into the given asynchronous writer.
+    ///
+    /// # Errors
+    /// * An error occured during encoding.
+    /// * `IoError`: The writer could not be written to.
+    ///
+    /// # See Also
+    /// * [`Encoder::encode_static`] for the blocking version of this method.
+    fn encode_static_async(
+        image: Image<P>,
+        dest: &mut (impl tokio::io::AsyncWrite + Unpin + Send),
+    ) -> impl std::future::Future<Output = crate::Result<()>> + Send {
+        encode_blocking(dest, move |writer| Self::encode_static(&image, writer))
+    }
+
+    /// Encodes the given image sequence into the given asynchronous writer.
+    ///
+    /// # Errors
+    /// * An error occured during encoding.
+    /// * `IoError`: The writer could not be written to.
+    ///
+    /// # See Also
+    /// * [`Encoder::encode_sequence`] for the blocking version of this method.
+    fn encode_sequence_async(
+        sequence: ImageSequence<P>,
+        dest: &mut (impl tokio::io::AsyncWrite + Unpin + Send),
+    ) -> impl std::future::Future<Output = crate::Result<()>> + Send {
+        encode_blocking(dest, move |writer| Self::encode_sequence(&sequence, writer))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<P: Pixel + Send + 'static, E: Encoder<P, BlockingWriter> + 'static> AsyncEncoder<P> for E {}
+
+/// Asynchronous counterparts to [`Decoder`]. The stream is read to its end without blocking the
+/// executor, after which it is decoded on a blocking thread of the Tokio runtime.
+///
+/// This is implemented for every decoder which can decode from an in-memory
+/// [`Cursor`](std::io::Cursor). Since the decoder runs on another thread, it is taken by value.
+///
+/// # Panics
+/// * The returned future is not polled within a Tokio runtime.
+///
+/// # Example
+/// ```no_run
+/// use ril::encode::AsyncDecoder;
+/// use ril::encodings::png::PngDecoder;
+/// # use ril::prelude::*;
+///
+/// # async fn run() -> ril::Result<()> {
+/// let file = tokio::fs::File::open("image.png").await?;
+/// let image: Image<Rgb> = PngDecoder::new().decode_async(file).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub trait AsyncDecoder<P: Pixel + Send + 'static>:
+    Decoder<P, std::io::Cursor<Vec<u8>>> + Sized + Send + 'static
+{
+    /// Decodes the given asynchronous stream into an image.
+    ///
+    /// # Errors
+    /// * An error occured during decoding.
+    /// * `IoError`: The stream could not be read.
+    ///
+    /// # See Also
+    /// * [`Decoder::decode`] for the blocking version of this method.
+    fn decode_async(
+        mut self,
+        mut stream: impl tokio::io::AsyncRead + Unpin + Send,
+    ) -> impl std::future::Future<Output = crate::Result<Image<P>>> + Send {
+        use tokio::io::AsyncReadExt;
+
+        async move {
+            let mut buffer = Vec::new();
+            stream.read_to_end(&mut buffer).await?;
+
+            spawn_blocking(move || self.decode(std::io::Cursor::new(buffer))).await
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<P: Pixel + Send + 'static, D: Decoder<P, std::io::Cursor<Vec<u8>>> + Send + 'static>
+    AsyncDecoder<P> for D
+{
+}
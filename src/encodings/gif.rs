@@ -1,10 +1,10 @@
 use crate::{
-    encode, encodings::ColorType, pixel::assume_pixel_from_palette, Decoder, DisposalMethod,
-    Dynamic, Encoder, Error, Frame, FrameIterator, Image, ImageFormat, LoopCount, OverlayMode,
-    Pixel, Rgba,
+    encode, encodings::ColorType, pixel::assume_pixel_from_palette, BlendMethod, Decoder,
+    DisposalMethod, Dynamic, Encoder, Error, Frame, FrameIterator, Image, ImageFormat,
+    ImageMetadata, LoopCount, OverlayMode, Pixel, Rgba,
 };
 use std::{
-    io::{Read, Write},
+    io::{Cursor, Read, Write},
     marker::PhantomData,
     num::NonZeroU32,
     time::Duration,
@@ -100,6 +100,11 @@ impl<P: Pixel, W: Write> GifEncoder<P, W> {
             (ColorType::Rgba, 8) => rgba!(data!()),
             (ColorType::L, 1 | 8) => rgb!(data!(crate::Rgb)),
             (ColorType::LA, 1 | 8) => rgba!(data!(crate::Rgba)),
+            (ColorType::Cmyk, 8) => rgb!(image
+                .data
+                .iter()
+                .flat_map(|p| p.as_rgb().as_bytes())
+                .collect::<Vec<_>>()),
             (ColorType::PaletteRgb, 8) => gif::Frame::from_palette_pixels(
                 image.width() as u16,
                 image.height() as u16,
@@ -148,11 +153,14 @@ impl<P: Pixel, W: Write> Encoder<P, W> for GifEncoder<P, W> {
         let mut encoder =
             gif::Encoder::new(dest, metadata.width() as u16, metadata.height() as u16, &[])?;
 
-        if let Some((_, loop_count)) = metadata.sequence() {
-            encoder.set_repeat(match loop_count {
-                LoopCount::Exactly(n) => gif::Repeat::Finite(n as u16),
-                LoopCount::Infinite => gif::Repeat::Infinite,
-            })?;
+        // GIFs without a loop count are played once, otherwise the count is the number of times
+        // the animation is repeated after it is first played
+        match metadata.sequence().map(|(_, loop_count)| loop_count) {
+            Some(LoopCount::Infinite) => encoder.set_repeat(gif::Repeat::Infinite)?,
+            Some(LoopCount::Exactly(n)) if n > 1 => {
+                encoder.set_repeat(gif::Repeat::Finite((n - 1).min(u32::from(u16::MAX)) as u16))?;
+            }
+            _ => (),
         }
 
         Ok(Self {
@@ -165,6 +173,10 @@ impl<P: Pixel, W: Write> Encoder<P, W> for GifEncoder<P, W> {
     #[allow(clippy::cast_precision_loss)]
     fn add_frame(&mut self, frame: &impl encode::FrameLike<P>) -> crate::Result<()> {
         let mut out = self.build_frame(frame.image())?;
+        if let Some((x, y)) = frame.offset() {
+            out.left = x as u16;
+            out.top = y as u16;
+        }
 
         if let Some(delay) = frame.delay() {
             out.delay = (delay.as_millis() as f64 / 10.).round() as u16;
@@ -208,15 +220,63 @@ impl<P: Pixel, R: Read> Default for GifDecoder<P, R> {
     }
 }
 
+/// Walks the blocks of the given GIF file and returns the number of frames in it along with its
+/// loop count, which the underlying decoder does not expose. Returns `None` if the file is
+/// malformed, in which case decoding it will fail anyway.
+fn scan_blocks(data: &[u8]) -> Option<(u32, LoopCount)> {
+    // Skips a sequence of data sub-blocks, returning the offset after its terminator
+    let skip_sub_blocks = |mut offset: usize| loop {
+        match *data.get(offset)? {
+            0 => break Some(offset + 1),
+            size => offset += size as usize + 1,
+        }
+    };
+    // Color tables are present if the top bit of the packed field is set
+    let color_table = |packed: u8| {
+        if packed & 0x80 == 0 {
+            0
+        } else {
+            3 << ((packed & 0x07) + 1)
+        }
+    };
+
+    let mut offset = 13 + color_table(*data.get(10)?);
+    let mut frames = 0;
+    let mut loop_count = LoopCount::Exactly(1);
+    loop {
+        match *data.get(offset)? {
+            // Extension
+            0x21 => {
+                let label = *data.get(offset + 1)?;
+                let app = data.get(offset + 2..offset + 14);
+                if label == 0xFF && matches!(app, Some(b"\x0bNETSCAPE2.0" | b"\x0bANIMEXTS1.0")) {
+                    if let Some(&[3, 1, lo, hi]) = data.get(offset + 14..offset + 18) {
+                        loop_count = match u16::from_le_bytes([lo, hi]) {
+                            0 => LoopCount::Infinite,
+                            n => LoopCount::Exactly(u32::from(n) + 1),
+                        };
+                    }
+                }
+                offset = skip_sub_blocks(offset + 2)?;
+            }
+            // Image descriptor, followed by the minimum LZW code size and the image data
+            0x2C => {
+                frames += 1;
+                offset += 10 + color_table(*data.get(offset + 9)?);
+                offset = skip_sub_blocks(offset + 1)?;
+            }
+            // Trailer
+            0x3B => break Some((frames, loop_count)),
+            _ => return None,
+        }
+    }
+}
+
 fn read_frame<P: Pixel, R: Read>(
     decoder: &mut gif::Decoder<R>,
-) -> Option<crate::Result<(&gif::Frame, Image<P>)>> {
-    #[allow(clippy::cast_lossless)]
-    let width = decoder.width() as u32;
-    #[allow(clippy::cast_lossless)]
-    let height = decoder.height() as u32;
-
+) -> Option<crate::Result<(&gif::Frame, Image<P>, P)>> {
     let global_palette = decoder.global_palette().map(ToOwned::to_owned);
+    let background_index = decoder.bg_color();
     let frame = match decoder.read_next_frame() {
         Ok(Some(frame)) => frame,
         Ok(None) => return None,
@@ -254,18 +314,36 @@ fn read_frame<P: Pixel, R: Read>(
         Err(e) => return Some(Err(e)),
     };
 
+    // Paletted and dynamic pixels have no default, so areas of the canvas that are not covered by
+    // the frame are filled with its transparent color, or else the background color of the GIF
+    let background = if P::COLOR_TYPE.is_paletted() || P::COLOR_TYPE.is_dynamic() {
+        let index = transparent_index
+            .or(background_index)
+            .filter(|&i| i < palette.len())
+            .unwrap_or(0);
+
+        match unsafe { assume_pixel_from_palette(&palette, index) } {
+            Ok(pixel) => pixel,
+            Err(e) => return Some(Err(e)),
+        }
+    } else {
+        P::default()
+    };
+
     Some(Ok((
         frame,
         Image {
-            width: NonZeroU32::new(width).unwrap(),
-            height: NonZeroU32::new(height).unwrap(),
+            width: NonZeroU32::new(u32::from(frame.width)).unwrap(),
+            height: NonZeroU32::new(u32::from(frame.height)).unwrap(),
             data,
             format: ImageFormat::Gif,
             overlay: OverlayMode::default(),
             palette: P::COLOR_TYPE
                 .is_paletted()
                 .then(|| palette.into_boxed_slice()),
+            metadata: ImageMetadata::default(),
         },
+        background,
     )))
 }
 
@@ -278,36 +356,52 @@ impl<P: Pixel, R: Read> Decoder<P, R> for GifDecoder<P, R> {
         decoder.set_color_output(gif::ColorOutput::Indexed);
         let mut decoder = decoder.read_info(stream)?;
 
-        Ok(read_frame(&mut decoder)
-            .unwrap_or(Err(Error::EmptyImageError))?
-            .1)
+        let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+        let (frame, mut image, background) =
+            read_frame(&mut decoder).unwrap_or(Err(Error::EmptyImageError))?;
+
+        // The first frame may only cover part of the canvas
+        let (left, top) = (u32::from(frame.left), u32::from(frame.top));
+        let right = width.saturating_sub(left + image.width());
+        let bottom = height.saturating_sub(top + image.height());
+        if left > 0 || top > 0 || right > 0 || bottom > 0 {
+            image.pad(background, top, right, bottom, left);
+        }
+        Ok(image)
     }
 
-    fn decode_sequence(&mut self, stream: R) -> crate::Result<Self::Sequence> {
+    fn decode_sequence(&mut self, mut stream: R) -> crate::Result<Self::Sequence> {
+        // The whole file is scanned up front for the number of frames and the loop count
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+        let (len, loop_count) = scan_blocks(&data).unwrap_or((0, LoopCount::Infinite));
+
         let mut decoder = gif::DecodeOptions::new();
         decoder.set_color_output(gif::ColorOutput::Indexed);
 
         Ok(GifFrameIterator {
-            decoder: decoder.read_info(stream)?,
+            decoder: decoder.read_info(Cursor::new(data))?,
+            len,
+            loop_count,
             _marker: PhantomData,
         })
     }
 }
 
 pub struct GifFrameIterator<P: Pixel, R: Read> {
-    decoder: gif::Decoder<R>,
-    _marker: PhantomData<P>,
+    decoder: gif::Decoder<Cursor<Vec<u8>>>,
+    len: u32,
+    loop_count: LoopCount,
+    _marker: PhantomData<(P, R)>,
 }
 
 impl<P: Pixel, R: Read> FrameIterator<P> for GifFrameIterator<P, R> {
     fn len(&self) -> u32 {
-        // TODO: Decoder also appears to not provide us with this either
-        0
+        self.len
     }
 
     fn loop_count(&self) -> LoopCount {
-        // TODO: Currently the decoder does not provide us with this info
-        LoopCount::Infinite
+        self.loop_count
     }
 }
 
@@ -316,7 +410,7 @@ impl<P: Pixel, R: Read> Iterator for GifFrameIterator<P, R> {
 
     #[allow(clippy::cast_lossless)]
     fn next(&mut self) -> Option<Self::Item> {
-        let (frame, image) = match read_frame(&mut self.decoder)? {
+        let (frame, image, _) = match read_frame(&mut self.decoder)? {
             Ok(image) => image,
             Err(e) => return Some(Err(e)),
         };
@@ -327,6 +421,8 @@ impl<P: Pixel, R: Read> Iterator for GifFrameIterator<P, R> {
                 gif::DisposalMethod::Keep | gif::DisposalMethod::Any => DisposalMethod::None,
                 gif::DisposalMethod::Background => DisposalMethod::Background,
                 gif::DisposalMethod::Previous => DisposalMethod::Previous,
-            })))
+            })
+            .with_offset(u32::from(frame.left), u32::from(frame.top))
+            .with_blend(BlendMethod::Over)))
     }
 }
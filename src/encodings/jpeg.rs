@@ -1,21 +1,47 @@
 use super::ColorType;
 use crate::{
     encode::{self, Decoder, Encoder},
-    Error, Image, ImageFormat, OverlayMode, Pixel, Result, SingleFrameIterator,
+    Error, Image, ImageFormat, ImageMetadata, OverlayMode, Pixel, Result, SingleFrameIterator,
 };
 
 use jpeg_decoder::PixelFormat as DecoderPixelFormat;
-use jpeg_encoder::ColorType as EncoderColorType;
+use jpeg_encoder::{ColorType as EncoderColorType, SamplingFactor};
 use std::{
     io::{Read, Write},
     marker::PhantomData,
     num::NonZeroU32,
 };
 
+/// The resolution at which the chroma (color) channels of a JPEG image are stored relative to
+/// the luma (brightness) channel. Lower resolutions produce smaller files, at the cost of color
+/// bleeding around sharp edges. Grayscale images are never subsampled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JpegSubsampling {
+    /// 4:4:4, chroma is stored at full resolution.
+    Yuv444,
+    /// 4:2:2, chroma is stored at half of the horizontal resolution.
+    Yuv422,
+    /// 4:2:0, chroma is stored at half of the horizontal and vertical resolution. This is the
+    /// default.
+    Yuv420,
+}
+
+impl JpegSubsampling {
+    const fn sampling_factor(self) -> SamplingFactor {
+        match self {
+            Self::Yuv444 => SamplingFactor::F_1_1,
+            Self::Yuv422 => SamplingFactor::F_2_1,
+            Self::Yuv420 => SamplingFactor::F_2_2,
+        }
+    }
+}
+
 /// JPEG encoder options.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct JpegEncoderOptions {
     quality: u8,
+    progressive: bool,
+    subsampling: JpegSubsampling,
 }
 
 impl Default for JpegEncoderOptions {
@@ -28,7 +54,11 @@ impl JpegEncoderOptions {
     /// Creates a new encoder with default settings.
     #[must_use]
     pub const fn new() -> Self {
-        Self { quality: 90 }
+        Self {
+            quality: 90,
+            progressive: false,
+            subsampling: JpegSubsampling::Yuv420,
+        }
     }
 
     /// Sets the quality of the encoded image. Must be between 0 and 100.
@@ -55,6 +85,22 @@ impl JpegEncoderOptions {
         self.quality = quality;
         self
     }
+
+    /// Sets whether to encode a progressive JPEG, which is stored in multiple scans of increasing
+    /// detail so that a preview can be shown while the image is still loading. Defaults to `false`.
+    #[must_use]
+    pub const fn with_progressive(mut self, progressive: bool) -> Self {
+        self.progressive = progressive;
+        self
+    }
+
+    /// Sets the chroma subsampling of the encoded image. Defaults to
+    /// [`JpegSubsampling::Yuv420`].
+    #[must_use]
+    pub const fn with_subsampling(mut self, subsampling: JpegSubsampling) -> Self {
+        self.subsampling = subsampling;
+        self
+    }
 }
 
 enum JpegSpecialCase {
@@ -68,7 +114,7 @@ pub struct JpegEncoder<P: Pixel, W: Write> {
     native_color_type: ColorType,
     color_type: EncoderColorType,
     special_case: JpegSpecialCase,
-    quality: u8,
+    options: JpegEncoderOptions,
     writer: Option<W>,
     _marker: PhantomData<P>,
 }
@@ -83,6 +129,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
             (ColorType::Rgba | ColorType::PaletteRgba, 8) => EncoderColorType::Rgba,
             // Just like how Rgba strips into Rgb, perform the same thing here manually
             (ColorType::L, 1 | 8) | (ColorType::LA, 8) => EncoderColorType::Luma,
+            (ColorType::Cmyk, 8) => EncoderColorType::Cmyk,
             _ => return Err(Error::UnsupportedColorType),
         };
         let special_case = match sample {
@@ -95,7 +142,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
             native_color_type: metadata.color_type(),
             color_type,
             special_case,
-            quality: metadata.config().quality,
+            options: metadata.config(),
             writer: Some(dest),
             _marker: PhantomData,
         })
@@ -119,12 +166,18 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
             JpegSpecialCase::None => (),
         }
 
-        let encoder = jpeg_encoder::Encoder::new(
+        let mut encoder = jpeg_encoder::Encoder::new(
             self.writer
                 .take()
                 .expect("jpeg cannot encode multiple frames"),
-            self.quality,
+            self.options.quality,
         );
+        encoder.set_progressive(self.options.progressive);
+        encoder.set_sampling_factor(self.options.subsampling.sampling_factor());
+        if let Some(exif) = frame.image().metadata.exif() {
+            // EXIF data is stored in an APP1 segment, after the "Exif" identifier
+            encoder.add_app_segment(1, &[b"Exif\0\0", exif].concat())?;
+        }
         encoder.encode(
             &data,
             frame.image().width() as u16,
@@ -142,6 +195,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for JpegEncoder<P, W> {
 
 /// A JPEG decoder interface over [`jpeg_decoder::Decoder`].
 pub struct JpegDecoder<P: Pixel, R: Read> {
+    apply_orientation: bool,
     _marker: PhantomData<(P, R)>,
 }
 
@@ -150,9 +204,21 @@ impl<P: Pixel, R: Read> JpegDecoder<P, R> {
     #[must_use]
     pub const fn new() -> Self {
         Self {
+            apply_orientation: false,
             _marker: PhantomData,
         }
     }
+
+    /// Sets whether to make decoded images upright according to the orientation stored in their
+    /// EXIF data. Defaults to `false`.
+    ///
+    /// # See Also
+    /// - [`Image::auto_orient`] for more information.
+    #[must_use]
+    pub const fn with_apply_orientation(mut self, apply: bool) -> Self {
+        self.apply_orientation = apply;
+        self
+    }
 }
 
 impl<P: Pixel, R: Read> Decoder<P, R> for JpegDecoder<P, R> {
@@ -167,45 +233,34 @@ impl<P: Pixel, R: Read> Decoder<P, R> for JpegDecoder<P, R> {
         let (color_type, bit_depth) = match info.pixel_format {
             DecoderPixelFormat::L8 => (ColorType::L, 8),
             DecoderPixelFormat::L16 => (ColorType::L, 16),
-            DecoderPixelFormat::RGB24 | DecoderPixelFormat::CMYK32 => (ColorType::Rgb, 8),
+            DecoderPixelFormat::RGB24 => (ColorType::Rgb, 8),
+            DecoderPixelFormat::CMYK32 => (ColorType::Cmyk, 8),
         };
-        let perform_conversion = info.pixel_format == jpeg_decoder::PixelFormat::CMYK32;
 
         let data = data
             .as_slice()
             .chunks_exact(info.pixel_format.pixel_bytes())
-            .map(|chunk| {
-                if color_type == ColorType::L {
-                    return P::from_raw_parts(ColorType::L, bit_depth, chunk);
-                }
-
-                let chunk = &if perform_conversion {
-                    let c = chunk[0] as f32 / 255.0;
-                    let y = chunk[1] as f32 / 255.0;
-                    let m = chunk[2] as f32 / 255.0;
-                    let k = chunk[3] as f32 / 255.0;
-
-                    [
-                        (255.0 * (1.0 - c) * (1.0 - k)).round() as u8,
-                        (255.0 * (1.0 - y) * (1.0 - k)).round() as u8,
-                        (255.0 * (1.0 - m) * (1.0 - k)).round() as u8,
-                    ]
-                } else {
-                    [chunk[0], chunk[1], chunk[2]]
-                };
-
-                P::from_raw_parts(color_type, bit_depth, chunk)
-            })
+            .map(|chunk| P::from_raw_parts(color_type, bit_depth, chunk))
             .collect::<Result<Vec<_>>>()?;
 
-        Ok(Image {
+        let mut metadata = ImageMetadata::new();
+        if let Some(exif) = decoder.exif_data() {
+            metadata = metadata.with_exif(exif);
+        }
+
+        let mut image = Image {
             width: NonZeroU32::new(info.width as u32).unwrap(),
             height: NonZeroU32::new(info.height as u32).unwrap(),
             data,
             format: ImageFormat::Jpeg,
             overlay: OverlayMode::default(),
             palette: None,
-        })
+            metadata,
+        };
+        if self.apply_orientation {
+            image.auto_orient();
+        }
+        Ok(image)
     }
 
     fn decode_sequence(&mut self, stream: R) -> Result<Self::Sequence> {
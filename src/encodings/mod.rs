@@ -6,6 +6,8 @@ pub mod gif;
 pub mod jpeg;
 #[cfg(feature = "png")]
 pub mod png;
+#[cfg(feature = "tiff")]
+pub mod tiff;
 #[cfg(feature = "webp")]
 pub mod webp;
 
@@ -28,6 +30,9 @@ pub enum ColorType {
     PaletteRgb,
     /// A single-channel pixel that holds an index into a palette of RGBA colors.
     PaletteRgba,
+    /// A four-channel pixel that holds cyan, magenta, yellow, and key (black) values. This is
+    /// typically used for print, and is most commonly found in JPEG images.
+    Cmyk,
     /// Dynamic color type that can be used to store any color type. The bit depth of all color
     /// types this can represent should be the same, for example an 8-bit dynamic pixel cannot
     /// represent Rgb16.
@@ -35,6 +40,10 @@ pub enum ColorType {
     /// This is never used during decoding. When encoding, this should not be resolved statically
     /// but instead during runtime, where the color type is known.
     Dynamic,
+    /// The color type of a pixel type defined outside of this crate, which holds the given number
+    /// of channels. Encoders cannot store these pixels directly, so images of them are converted
+    /// into RGBA using [`Pixel::as_rgba`][crate::Pixel::as_rgba] when they are encoded.
+    Custom(u8),
 }
 
 impl ColorType {
@@ -45,8 +54,9 @@ impl ColorType {
             Self::L | Self::PaletteRgb | Self::PaletteRgba => 1,
             Self::LA => 2,
             Self::Rgb => 3,
-            Self::Rgba => 4,
+            Self::Rgba | Self::Cmyk => 4,
             Self::Dynamic => 0,
+            Self::Custom(channels) => *channels as usize,
         }
     }
 
@@ -1,14 +1,15 @@
 use super::ColorType;
 use crate::{
-    encode::{self, Decoder, Encoder, FrameIterator},
+    encode::{self, Decoder, Encoder, FrameIterator, RowSink},
     pixel::assume_pixel_from_palette,
-    DisposalMethod, Dynamic, Frame, Image, ImageFormat, LoopCount, OverlayMode, Pixel, Rgb, Rgba,
+    BlendMethod, DisposalMethod, Dynamic, Frame, Image, ImageFormat, ImageMetadata, LoopCount,
+    Orientation, OverlayMode, Pixel, Rgb, Rgba,
 };
 
+use flate2::write::ZlibEncoder;
 pub use png::{AdaptiveFilterType, Compression, FilterType};
 use std::{
-    borrow::Cow,
-    io::{Read, Write},
+    io::{self, Read, Write},
     marker::PhantomData,
     num::NonZeroU32,
     time::Duration,
@@ -30,17 +31,19 @@ impl From<png::ColorType> for ColorType {
 }
 
 #[allow(clippy::enum_glob_use)]
-const fn get_png_color_type(src: ColorType) -> png::ColorType {
+const fn get_png_color_type(src: ColorType) -> crate::Result<png::ColorType> {
     use png::ColorType::*;
 
-    match src {
+    Ok(match src {
         ColorType::L => Grayscale,
         ColorType::LA => GrayscaleAlpha,
         ColorType::Rgb => Rgb,
         ColorType::Rgba => Rgba,
         ColorType::PaletteRgb | ColorType::PaletteRgba => Indexed,
-        ColorType::Dynamic => unreachable!(),
-    }
+        ColorType::Cmyk | ColorType::Dynamic | ColorType::Custom(_) => {
+            return Err(crate::Error::UnsupportedColorType)
+        }
+    })
 }
 
 /// PNG configuration options for [`PngEncoder`].
@@ -52,6 +55,12 @@ pub struct PngEncoderOptions {
     pub filter: FilterType,
     /// The compression to use.
     pub compression: Compression,
+    /// Whether to interlace the image with Adam7, which allows a coarse preview of the image to
+    /// be displayed before it has fully loaded. Only static images are interlaced.
+    pub interlaced: bool,
+    /// Whether to only write the region of each frame of an animated image that changed since
+    /// the previous frame.
+    pub optimize_frames: bool,
 }
 
 impl PngEncoderOptions {
@@ -62,6 +71,8 @@ impl PngEncoderOptions {
             adaptive_filter: AdaptiveFilterType::NonAdaptive,
             filter: FilterType::Sub,
             compression: Compression::Default,
+            interlaced: false,
+            optimize_frames: false,
         }
     }
 
@@ -85,6 +96,134 @@ impl PngEncoderOptions {
         self.compression = value;
         self
     }
+
+    /// Sets whether to interlace the image with Adam7. Interlaced images are slightly larger, but
+    /// can be displayed progressively while they are loading. Defaults to `false`.
+    ///
+    /// Animated images are never interlaced, and the adaptive filter type is ignored when
+    /// interlacing.
+    #[must_use]
+    pub const fn with_interlacing(mut self, value: bool) -> Self {
+        self.interlaced = value;
+        self
+    }
+
+    /// Sets whether to only write the region of each frame of an animated image that changed
+    /// since the previous frame, which usually makes animations much smaller. Defaults to `false`.
+    ///
+    /// Only frames that cover the whole image, are not offset, replace the frame before them
+    /// ([`BlendMethod::Source`]) and are not disposed ([`DisposalMethod::None`]) are optimized,
+    /// since cropping other frames would change how they are rendered. Frames are rendered the
+    /// same either way, but decoded frames will be cropped and offset; use
+    /// [`ImageSequence::coalesce`][crate::ImageSequence::coalesce] to render them in full.
+    #[must_use]
+    pub const fn with_frame_optimization(mut self, value: bool) -> Self {
+        self.optimize_frames = value;
+        self
+    }
+}
+
+/// Returns the smallest region `(x, y, width, height)` that contains every pixel that differs
+/// between the two given frames of the given width, or `None` if they are identical.
+fn changed_region<P: Pixel>(width: u32, a: &[P], b: &[P]) -> Option<(u32, u32, u32, u32)> {
+    let (mut x1, mut y1, mut x2, mut y2) = (u32::MAX, u32::MAX, 0, 0);
+
+    for ((a, b), i) in a.iter().zip(b).zip(0..) {
+        if a != b {
+            let (x, y) = (i % width, i / width);
+            x1 = x1.min(x);
+            y1 = y1.min(y);
+            x2 = x2.max(x);
+            y2 = y2.max(y);
+        }
+    }
+
+    (x1 <= x2).then(|| (x1, y1, x2 - x1 + 1, y2 - y1 + 1))
+}
+
+/// The starting column, starting row, column spacing and row spacing of each of the seven passes
+/// of Adam7 interlacing.
+const ADAM7_PASSES: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// Applies the given filter to a row of the given number of bytes per pixel, given the previous
+/// (unfiltered) row of the same pass.
+fn filter_row(filter: FilterType, bpp: usize, prev: &[u8], row: &[u8]) -> Vec<u8> {
+    row.iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let a = if i >= bpp { row[i - bpp] } else { 0 };
+            let b = prev[i];
+            let c = if i >= bpp { prev[i - bpp] } else { 0 };
+
+            x.wrapping_sub(match filter {
+                FilterType::NoFilter => 0,
+                FilterType::Sub => a,
+                FilterType::Up => b,
+                FilterType::Avg => ((u16::from(a) + u16::from(b)) / 2) as u8,
+                FilterType::Paeth => {
+                    let p = i16::from(a) + i16::from(b) - i16::from(c);
+                    let (pa, pb, pc) = (
+                        (p - i16::from(a)).abs(),
+                        (p - i16::from(b)).abs(),
+                        (p - i16::from(c)).abs(),
+                    );
+
+                    if pa <= pb && pa <= pc {
+                        a
+                    } else if pb <= pc {
+                        b
+                    } else {
+                        c
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// Filters and compresses the given pixel data as the seven passes of an Adam7-interlaced image,
+/// returning the contents of its `IDAT` chunk.
+fn encode_interlaced(
+    data: &[u8],
+    (width, height): (usize, usize),
+    filter: FilterType,
+    compression: Compression,
+) -> io::Result<Vec<u8>> {
+    let bpp = data.len() / (width * height);
+    let level = match compression {
+        Compression::Fast => flate2::Compression::fast(),
+        Compression::Best => flate2::Compression::best(),
+        _ => flate2::Compression::default(),
+    };
+    let mut encoder = ZlibEncoder::new(Vec::new(), level);
+
+    for (x, y, dx, dy) in ADAM7_PASSES {
+        // The first row of every pass is filtered as if it were preceded by a row of zeros
+        let mut prev = Vec::new();
+
+        for y in (y..height).step_by(dy) {
+            let row = (x..width)
+                .step_by(dx)
+                .flat_map(|x| &data[(y * width + x) * bpp..(y * width + x + 1) * bpp])
+                .copied()
+                .collect::<Vec<_>>();
+            prev.resize(row.len(), 0);
+
+            encoder.write_all(&[filter as u8])?;
+            encoder.write_all(&filter_row(filter, bpp, &prev, &row))?;
+            prev = row;
+        }
+    }
+
+    encoder.finish()
 }
 
 /// A PNG encoder interface around [`png::Encoder`].
@@ -96,9 +235,45 @@ impl PngEncoderOptions {
 pub struct PngEncoder<P: Pixel, W: Write> {
     writer: png::Writer<W>,
     dimensions: (u32, u32),
+    options: PngEncoderOptions,
+    /// The pixels of the previous frame if it can be diffed against, used when optimizing frames.
+    previous: Option<Vec<P>>,
+    /// Whether the previous frame was cropped to the region that changed.
+    cropped: bool,
     _marker: PhantomData<P>,
 }
 
+impl<P: Pixel, W: Write> PngEncoder<P, W> {
+    /// Returns the region of the given frame that changed since the previous frame, if only that
+    /// region needs to be written.
+    fn optimized_region(
+        &mut self,
+        frame: &impl encode::FrameLike<P>,
+    ) -> Option<(u32, u32, u32, u32)> {
+        if !self.options.optimize_frames {
+            return None;
+        }
+
+        let image = frame.image();
+        let previous = self.previous.take();
+        let optimizable = image.dimensions() == self.dimensions
+            && matches!(frame.offset(), None | Some((0, 0)))
+            && matches!(frame.blend(), None | Some(BlendMethod::Source))
+            && matches!(frame.disposal(), None | Some(DisposalMethod::None));
+        if !optimizable {
+            return None;
+        }
+
+        // Frames must be at least one pixel large, even if nothing changed
+        let region = previous.map(|previous| {
+            changed_region(self.dimensions.0, &previous, &image.data).unwrap_or((0, 0, 1, 1))
+        });
+        // The frame is left on the canvas, so the next frame can be diffed against it
+        self.previous = Some(image.data.clone());
+        region
+    }
+}
+
 impl<P: Pixel, W: Write> Encoder<P, W> for PngEncoder<P, W> {
     type Config = PngEncoderOptions;
 
@@ -106,56 +281,119 @@ impl<P: Pixel, W: Write> Encoder<P, W> for PngEncoder<P, W> {
         dest: W,
         metadata: impl encode::HasEncoderMetadata<Self::Config, P>,
     ) -> crate::Result<Self> {
-        let mut encoder = png::Encoder::new(dest, metadata.width(), metadata.height());
-        encoder.set_color(get_png_color_type(metadata.color_type()));
-        encoder.set_depth(png::BitDepth::from_u8(metadata.bit_depth()).unwrap());
-
-        match metadata.color_type() {
+        let (width, height) = (metadata.width(), metadata.height());
+        let color_type = metadata.color_type();
+        let png_color_type = get_png_color_type(color_type)?;
+        let bit_depth = metadata.bit_depth();
+        let sequence = metadata.sequence();
+        let (palette, trns) = match color_type {
             ColorType::PaletteRgb => {
                 let pal = metadata.palette().expect("no palette for paletted image?");
-                encoder.set_palette(pal.iter().flat_map(Pixel::as_bytes).collect::<Cow<_>>());
+                (Some(pal.iter().flat_map(Pixel::as_bytes).collect()), None)
             }
             ColorType::PaletteRgba => {
                 let pal = metadata.palette().expect("no palette for paletted image?");
-                encoder.set_palette(
-                    pal.iter()
-                        .map(Pixel::as_rgb)
-                        .flat_map(|p| p.as_bytes())
-                        .collect::<Cow<_>>(),
-                );
-                encoder.set_trns(pal.iter().map(|p| p.as_rgba().a).collect::<Cow<_>>());
+                (
+                    Some(
+                        pal.iter()
+                            .map(Pixel::as_rgb)
+                            .flat_map(|p| p.as_bytes())
+                            .collect::<Vec<_>>(),
+                    ),
+                    Some(pal.iter().map(|p| p.as_rgba().a).collect::<Vec<_>>()),
+                )
             }
-            _ => (),
+            _ => (None, None),
+        };
+
+        let image_metadata = metadata.image_metadata().cloned().unwrap_or_default();
+        let mut config = metadata.config();
+        // Frames of animated images are written by the PNG encoder, which cannot interlace them.
+        // Interlacing also assumes that every pixel takes up whole bytes.
+        config.interlaced &= sequence.is_none() && bit_depth >= 8;
+
+        let mut info = png::Info::with_size(width, height);
+        info.interlaced = config.interlaced;
+        info.icc_profile = image_metadata.icc_profile().map(|p| p.to_vec().into());
+        info.source_gamma = image_metadata.gamma().map(png::ScaledFloat::new);
+        info.exif_metadata = image_metadata.exif().map(|exif| exif.to_vec().into());
+        if let Some((xppu, yppu)) = image_metadata.pixels_per_meter() {
+            info.pixel_dims = Some(png::PixelDimensions {
+                xppu,
+                yppu,
+                unit: png::Unit::Meter,
+            });
+        }
+        let mut encoder = png::Encoder::with_info(dest, info)?;
+        encoder.set_color(png_color_type);
+        encoder.set_depth(
+            png::BitDepth::from_u8(bit_depth).ok_or(crate::Error::UnsupportedColorType)?,
+        );
+        if let Some(palette) = palette {
+            encoder.set_palette(palette);
+        }
+        if let Some(trns) = trns {
+            encoder.set_trns(trns);
         }
 
-        if let Some((len, loops)) = metadata.sequence() {
+        if let Some((len, loops)) = sequence {
             encoder.set_animated(len as _, loops.count_or_zero())?;
         }
 
-        let dimensions = (metadata.width(), metadata.height());
-        let config = metadata.config();
         encoder.set_adaptive_filter(config.adaptive_filter);
         encoder.set_filter(config.filter);
         encoder.set_compression(config.compression);
 
+        for (keyword, text) in image_metadata.texts() {
+            let (keyword, text) = (keyword.to_string(), text.to_string());
+            // Latin-1 text is stored in tEXt chunks, or zTXt chunks if it is worth compressing;
+            // any other text is stored as UTF-8 in iTXt chunks
+            if text.chars().any(|c| c as u32 > 0xFF) {
+                encoder.add_itxt_chunk(keyword, text)?;
+            } else if text.len() > 1024 {
+                encoder.add_ztxt_chunk(keyword, text)?;
+            } else {
+                encoder.add_text_chunk(keyword, text)?;
+            }
+        }
+
         Ok(Self {
             writer: encoder.write_header()?,
-            dimensions,
+            dimensions: (width, height),
+            options: config,
+            previous: None,
+            cropped: false,
             _marker: PhantomData,
         })
     }
 
     fn add_frame(&mut self, frame: &impl encode::FrameLike<P>) -> crate::Result<()> {
-        if (frame.image().dimensions()) != self.dimensions {
-            self.writer
-                .set_frame_dimension(frame.image().width(), frame.image().height())?;
+        let image = frame.image();
+        let region = self.optimized_region(frame);
+        let (offset, (width, height)) = match region {
+            Some((x, y, width, height)) => (Some((x, y)), (width, height)),
+            // The region of the previous frame is kept unless it is reset
+            None if self.cropped => (frame.offset().or(Some((0, 0))), image.dimensions()),
+            None => (frame.offset(), image.dimensions()),
+        };
+        self.cropped = region.is_some();
+
+        if let Some((x, y)) = offset {
+            // Resetting the position first keeps the new dimensions within bounds
+            self.writer.reset_frame_position()?;
+            self.writer.set_frame_dimension(width, height)?;
+            self.writer.set_frame_position(x, y)?;
+        } else if (width, height) != self.dimensions {
+            self.writer.set_frame_dimension(width, height)?;
         }
-        let data = frame
-            .image()
-            .data
-            .iter()
-            .flat_map(P::as_bytes)
-            .collect::<Vec<_>>();
+        let data = match region {
+            Some((x, y, ..)) => (y..y + height)
+                .map(|y| (y * image.width() + x) as usize)
+                .flat_map(|start| &image.data[start..start + width as usize])
+                .flat_map(P::as_bytes)
+                .collect::<Vec<_>>(),
+            None => image.data.iter().flat_map(P::as_bytes).collect::<Vec<_>>(),
+        };
 
         if let Some(delay) = frame.delay() {
             self.writer
@@ -168,8 +406,24 @@ impl<P: Pixel, W: Write> Encoder<P, W> for PngEncoder<P, W> {
                 DisposalMethod::Previous => png::DisposeOp::Previous,
             })?;
         }
+        if let Some(blend) = frame.blend() {
+            self.writer.set_blend_op(match blend {
+                BlendMethod::Source => png::BlendOp::Source,
+                BlendMethod::Over => png::BlendOp::Over,
+            })?;
+        }
 
-        self.writer.write_image_data(&data)?;
+        if self.options.interlaced {
+            let data = encode_interlaced(
+                &data,
+                (width as usize, height as usize),
+                self.options.filter,
+                self.options.compression,
+            )?;
+            self.writer.write_chunk(png::chunk::IDAT, &data)?;
+        } else {
+            self.writer.write_image_data(&data)?;
+        }
         Ok(())
     }
 
@@ -181,6 +435,7 @@ impl<P: Pixel, W: Write> Encoder<P, W> for PngEncoder<P, W> {
 
 /// A PNG decoder interface around [`png::Decoder`].
 pub struct PngDecoder<P: Pixel, R: Read> {
+    apply_orientation: bool,
     _marker: PhantomData<(P, R)>,
 }
 
@@ -189,25 +444,98 @@ impl<P: Pixel, R: Read> PngDecoder<P, R> {
     #[must_use]
     pub const fn new() -> Self {
         Self {
+            apply_orientation: false,
             _marker: PhantomData,
         }
     }
 
-    fn prepare(stream: R) -> crate::Result<png::Reader<R>> {
-        let decoder = png::Decoder::new(stream);
-        decoder.read_info().map_err(Into::into)
+    /// Sets whether to make decoded images upright according to the orientation stored in their
+    /// `eXIf` chunk. Defaults to `false`. This does not apply to frames of animated images decoded
+    /// with [`Decoder::decode_sequence`].
+    ///
+    /// # See Also
+    /// - [`Image::auto_orient`] for more information.
+    #[must_use]
+    pub const fn with_apply_orientation(mut self, apply: bool) -> Self {
+        self.apply_orientation = apply;
+        self
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn prepare(mut stream: R) -> crate::Result<(png::Reader<HeaderChain<R>>, Option<Vec<u8>>)> {
+        let (header, exif) = read_header_chunks(&mut stream)?;
+        let decoder = png::Decoder::new(io::Cursor::new(header).chain(stream));
+
+        Ok((decoder.read_info()?, exif))
     }
 }
 
+/// The stream passed to the PNG decoder: the chunks read by [`read_header_chunks`], followed by
+/// the rest of the stream.
+type HeaderChain<R> = io::Chain<io::Cursor<Vec<u8>>, R>;
+
+/// Reads the signature and every chunk before the image data, returning the bytes that were read
+/// along with the contents of the `eXIf` chunk, if any. The PNG decoder does not read `eXIf`
+/// chunks, so they are picked out here before the bytes are passed on to it.
+///
+/// Malformed data is passed on as-is, so that the PNG decoder can report it.
+fn read_header_chunks(stream: &mut impl Read) -> io::Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let mut header = Vec::new();
+    let mut exif = None;
+    let mut read = |header: &mut Vec<u8>, len: u64| {
+        stream
+            .by_ref()
+            .take(len)
+            .read_to_end(header)
+            .map(|n| n as u64 == len)
+    };
+
+    if !read(&mut header, 8)? {
+        return Ok((header, exif));
+    }
+    loop {
+        // The length and type of the chunk
+        let start = header.len();
+        if !read(&mut header, 8)? {
+            break;
+        }
+        let length = u32::from_be_bytes(header[start..start + 4].try_into().unwrap());
+        if matches!(&header[start + 4..start + 8], b"IDAT" | b"IEND") {
+            break;
+        }
+
+        // The data of the chunk followed by its CRC
+        let data = header.len();
+        if !read(&mut header, u64::from(length) + 4)? {
+            break;
+        }
+        if &header[start + 4..start + 8] == b"eXIf" {
+            exif = Some(header[data..header.len() - 4].to_vec());
+        }
+    }
+
+    Ok((header, exif))
+}
+
 #[allow(clippy::type_complexity)]
 fn read_data<P: Pixel>(
     buffer: &[u8],
     info: &png::Info,
 ) -> crate::Result<(Vec<P>, Option<Box<[P::Color]>>)> {
-    let color_type: ColorType = info.color_type.into();
-    let bit_depth = info.bit_depth as u8;
+    let palette = read_palette::<P>(info);
+    let data = read_pixels(
+        buffer,
+        info.color_type.into(),
+        info.bit_depth as u8,
+        info.bytes_per_pixel(),
+        palette.as_deref(),
+    )?;
 
-    let palette = info.palette.as_deref().map(|pal| {
+    Ok((data, palette))
+}
+
+fn read_palette<P: Pixel>(info: &png::Info) -> Option<Box<[P::Color]>> {
+    info.palette.as_deref().map(|pal| {
         let pal = pal
             .chunks_exact(3)
             .map(|p| (p[0], p[1], p[2]))
@@ -232,25 +560,58 @@ fn read_data<P: Pixel>(
             .map(P::Color::from_dynamic)
             .collect::<Vec<_>>()
             .into_boxed_slice()
-    });
+    })
+}
 
-    let chunks = buffer.chunks_exact(info.bytes_per_pixel());
-    let data = if P::COLOR_TYPE.is_paletted() {
-        let palette = palette.as_deref().expect("no palette for paletted image?");
+fn read_pixels<P: Pixel>(
+    buffer: &[u8],
+    color_type: ColorType,
+    bit_depth: u8,
+    bytes_per_pixel: usize,
+    palette: Option<&[P::Color]>,
+) -> crate::Result<Vec<P>> {
+    let chunks = buffer.chunks_exact(bytes_per_pixel);
+    if P::COLOR_TYPE.is_paletted() {
+        let palette = palette.expect("no palette for paletted image?");
         chunks
             // SAFETY: considered safe for unartificial types as the safety is upheld by the
             // crate. Otherwise, safety must be upheld by the user.
             .map(|idx| unsafe { assume_pixel_from_palette(palette, idx[0]) })
-            .collect::<crate::Result<Vec<_>>>()?
+            .collect()
     } else {
         chunks
-            .map(|chunk| {
-                P::from_raw_parts_paletted(color_type, bit_depth, chunk, palette.as_deref())
-            })
-            .collect::<crate::Result<Vec<_>>>()?
-    };
+            .map(|chunk| P::from_raw_parts_paletted(color_type, bit_depth, chunk, palette))
+            .collect()
+    }
+}
 
-    Ok((data, palette))
+fn read_metadata(info: &png::Info, exif: Option<Vec<u8>>) -> crate::Result<ImageMetadata> {
+    let mut metadata = ImageMetadata::new();
+
+    for chunk in &info.uncompressed_latin1_text {
+        metadata.add_text(chunk.keyword.clone(), chunk.text.clone());
+    }
+    for chunk in &info.compressed_latin1_text {
+        metadata.add_text(chunk.keyword.clone(), chunk.get_text()?);
+    }
+    for chunk in &info.utf8_text {
+        metadata.add_text(chunk.keyword.clone(), chunk.get_text()?);
+    }
+    if let Some(profile) = info.icc_profile.as_deref() {
+        metadata = metadata.with_icc_profile(profile);
+    }
+    if let Some(gamma) = info.source_gamma {
+        metadata = metadata.with_gamma(gamma.into_value());
+    }
+    // Pixel dimensions without a unit only describe the aspect ratio of pixels
+    if let Some(dims) = info.pixel_dims.filter(|dims| dims.unit == png::Unit::Meter) {
+        metadata = metadata.with_pixels_per_meter(dims.xppu, dims.yppu);
+    }
+    if let Some(exif) = exif {
+        metadata = metadata.with_exif(exif);
+    }
+
+    Ok(metadata)
 }
 
 enum PaletteRepr {
@@ -262,7 +623,7 @@ impl<P: Pixel, R: Read> Decoder<P, R> for PngDecoder<P, R> {
     type Sequence = ApngFrameIterator<P, R>;
 
     fn decode(&mut self, stream: R) -> crate::Result<Image<P>> {
-        let mut reader = Self::prepare(stream)?;
+        let (mut reader, exif) = Self::prepare(stream)?;
 
         // Here we are decoding a single image, so only capture the first frame:
         let buffer = &mut vec![0; reader.output_buffer_size()];
@@ -270,23 +631,99 @@ impl<P: Pixel, R: Read> Decoder<P, R> for PngDecoder<P, R> {
 
         let info = reader.info();
         let (data, palette) = read_data(buffer, info)?;
+        let metadata = read_metadata(info, exif)?;
 
-        Ok(Image {
+        let mut image = Image {
             width: NonZeroU32::new(info.width).unwrap(),
             height: NonZeroU32::new(info.height).unwrap(),
             data,
             format: ImageFormat::Png,
             overlay: OverlayMode::default(),
             palette,
-        })
+            metadata,
+        };
+        if self.apply_orientation {
+            image.auto_orient();
+        }
+        Ok(image)
+    }
+
+    fn decode_rows(&mut self, stream: R, sink: &mut impl RowSink<P>) -> crate::Result<()> {
+        let (mut reader, exif) = Self::prepare(stream)?;
+        let info = reader.info();
+
+        // Reoriented rows are only known once the whole image is decoded
+        let orientation = exif.and_then(|exif| ImageMetadata::new().with_exif(exif).orientation());
+        if self.apply_orientation && !matches!(orientation, None | Some(Orientation::Normal)) {
+            let buffer = &mut vec![0; reader.output_buffer_size()];
+            reader.next_frame(buffer)?;
+
+            let info = reader.info();
+            let (data, palette) = read_data(buffer, info)?;
+            let mut image = Image {
+                width: NonZeroU32::new(info.width).unwrap(),
+                height: NonZeroU32::new(info.height).unwrap(),
+                data,
+                format: ImageFormat::Png,
+                overlay: OverlayMode::default(),
+                palette,
+                metadata: ImageMetadata::default(),
+            };
+            image.apply_orientation(orientation.unwrap_or_default());
+
+            sink.start(image.width(), image.height())?;
+            for (row, y) in image.data.chunks_exact(image.width() as usize).zip(0..) {
+                sink.row(y, row)?;
+            }
+            return Ok(());
+        }
+
+        let (width, height) = (info.width, info.height);
+        let (color_type, bit_depth) = (info.color_type.into(), info.bit_depth as u8);
+        let bytes_per_pixel = info.bytes_per_pixel();
+        let palette = read_palette::<P>(info);
+        sink.start(width, height)?;
+
+        // Rows of interlaced images are decoded out of order, so they have to be decoded at once
+        if info.interlaced {
+            let buffer = &mut vec![0; reader.output_buffer_size()];
+            reader.next_frame(buffer)?;
+
+            let data = read_pixels(
+                buffer,
+                color_type,
+                bit_depth,
+                bytes_per_pixel,
+                palette.as_deref(),
+            )?;
+            for (row, y) in data.chunks_exact(width as usize).zip(0..) {
+                sink.row(y, row)?;
+            }
+            return Ok(());
+        }
+
+        let mut y = 0;
+        while let Some(row) = reader.next_row()? {
+            let row = read_pixels(
+                row.data(),
+                color_type,
+                bit_depth,
+                bytes_per_pixel,
+                palette.as_deref(),
+            )?;
+            sink.row(y, &row)?;
+            y += 1;
+        }
+        Ok(())
     }
 
     fn decode_sequence(&mut self, stream: R) -> crate::Result<Self::Sequence> {
-        let reader = Self::prepare(stream)?;
+        let (reader, exif) = Self::prepare(stream)?;
 
         Ok(ApngFrameIterator {
             seq: 0,
             reader,
+            exif,
             _marker: PhantomData,
         })
     }
@@ -300,7 +737,8 @@ impl<P: Pixel, R: Read> Default for PngDecoder<P, R> {
 
 pub struct ApngFrameIterator<P: Pixel, R: Read> {
     seq: u32,
-    reader: png::Reader<R>,
+    reader: png::Reader<HeaderChain<R>>,
+    exif: Option<Vec<u8>>,
     _marker: PhantomData<P>,
 }
 
@@ -344,6 +782,15 @@ impl<P: Pixel, R: Read> Iterator for ApngFrameIterator<P, R> {
             Ok(o) => o,
             Err(e) => return Some(Err(e)),
         };
+        // The metadata describes the whole file, so it is only attached to the first frame
+        let metadata = if self.seq == 0 {
+            match read_metadata(self.reader.info(), self.exif.take()) {
+                Ok(metadata) => metadata,
+                Err(e) => return Some(Err(e)),
+            }
+        } else {
+            ImageMetadata::default()
+        };
 
         let inner = Image {
             width: NonZeroU32::new(output_info.width).unwrap(),
@@ -352,6 +799,7 @@ impl<P: Pixel, R: Read> Iterator for ApngFrameIterator<P, R> {
             format: ImageFormat::Png,
             overlay: OverlayMode::default(),
             palette,
+            metadata,
         };
 
         self.seq += 1;
@@ -361,12 +809,19 @@ impl<P: Pixel, R: Read> Iterator for ApngFrameIterator<P, R> {
             .with_delay(fc.map_or_else(Duration::default, |f| {
                 Duration::from_secs_f64(f64::from(f.delay_num) / f64::from(f.delay_den))
             }))
-            .with_disposal(fc.map_or_else(
-                DisposalMethod::default,
-                |f| match f.dispose_op {
+            .with_disposal(
+                fc.map_or_else(DisposalMethod::default, |f| match f.dispose_op {
                     png::DisposeOp::None => DisposalMethod::None,
                     png::DisposeOp::Background => DisposalMethod::Background,
                     png::DisposeOp::Previous => DisposalMethod::Previous,
+                }),
+            )
+            .with_offset(fc.map_or(0, |f| f.x_offset), fc.map_or(0, |f| f.y_offset))
+            .with_blend(fc.map_or_else(
+                BlendMethod::default,
+                |f| match f.blend_op {
+                    png::BlendOp::Source => BlendMethod::Source,
+                    png::BlendOp::Over => BlendMethod::Over,
                 },
             ))))
     }
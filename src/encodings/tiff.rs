@@ -0,0 +1,327 @@
+use super::ColorType;
+use crate::{
+    encode::{self, Decoder, Encoder},
+    Error, Frame, FrameIterator, Image, ImageFormat, ImageMetadata, LoopCount, OverlayMode, Pixel,
+    Result,
+};
+
+use std::{
+    io::{Cursor, Read, Seek, Write},
+    marker::PhantomData,
+    num::NonZeroU32,
+};
+use tiff::{
+    decoder::{Decoder as TiffDecoderImpl, DecodingResult},
+    encoder::{colortype, compression, TiffEncoder as TiffEncoderImpl},
+    ColorType as TiffColorType, TiffError,
+};
+
+/// The compression used to store the pages of a TIFF file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TiffCompression {
+    /// No compression.
+    None,
+    /// Lempel-Ziv-Welch compression. This is the default.
+    Lzw,
+    /// Deflate compression, which usually produces smaller files than LZW but is less widely
+    /// supported by older readers.
+    Deflate,
+    /// PackBits run-length encoding, which only compresses runs of repeated bytes.
+    PackBits,
+}
+
+/// TIFF encoder options.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TiffEncoderOptions {
+    compression: TiffCompression,
+}
+
+impl Default for TiffEncoderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TiffEncoderOptions {
+    /// Creates a new encoder with default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            compression: TiffCompression::Lzw,
+        }
+    }
+
+    /// Sets the compression used to store every page. Defaults to [`TiffCompression::Lzw`].
+    #[must_use]
+    pub const fn with_compression(mut self, compression: TiffCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+fn encoding_error(err: TiffError) -> Error {
+    match err {
+        TiffError::IoError(err) => Error::IoError(err),
+        err => Error::encoding(ImageFormat::Tiff, err),
+    }
+}
+
+/// The samples of a page, in the layout they are stored in.
+enum PageData {
+    Gray(Vec<u8>),
+    Rgb(Vec<u8>),
+    Rgba(Vec<u8>),
+    Cmyk(Vec<u8>),
+}
+
+/// A TIFF encoder interface over [`tiff::encoder::TiffEncoder`]. Every frame is stored as a
+/// separate page.
+///
+/// Since TIFF files are written with their page directories at the end, pages are kept in memory
+/// until the encoder is finished.
+pub struct TiffEncoder<P: Pixel, W: Write> {
+    options: TiffEncoderOptions,
+    pages: Vec<(u32, u32, PageData)>,
+    writer: W,
+    _marker: PhantomData<P>,
+}
+
+impl<P: Pixel, W: Write> TiffEncoder<P, W> {
+    fn write_page<T: Write + Seek>(
+        &self,
+        encoder: &mut TiffEncoderImpl<T>,
+        (width, height, data): &(u32, u32, PageData),
+    ) -> tiff::TiffResult<()> {
+        macro_rules! write {
+            ($t:ty, $data:expr) => {{
+                let (width, height) = (*width, *height);
+                match self.options.compression {
+                    TiffCompression::None => encoder.write_image_with_compression::<$t, _>(
+                        width,
+                        height,
+                        compression::Uncompressed,
+                        $data,
+                    ),
+                    TiffCompression::Lzw => encoder.write_image_with_compression::<$t, _>(
+                        width,
+                        height,
+                        compression::Lzw,
+                        $data,
+                    ),
+                    TiffCompression::Deflate => encoder.write_image_with_compression::<$t, _>(
+                        width,
+                        height,
+                        compression::Deflate::default(),
+                        $data,
+                    ),
+                    TiffCompression::PackBits => encoder.write_image_with_compression::<$t, _>(
+                        width,
+                        height,
+                        compression::Packbits,
+                        $data,
+                    ),
+                }
+            }};
+        }
+
+        match data {
+            PageData::Gray(data) => write!(colortype::Gray8, data),
+            PageData::Rgb(data) => write!(colortype::RGB8, data),
+            PageData::Rgba(data) => write!(colortype::RGBA8, data),
+            PageData::Cmyk(data) => write!(colortype::CMYK8, data),
+        }
+    }
+}
+
+impl<P: Pixel, W: Write> Encoder<P, W> for TiffEncoder<P, W> {
+    type Config = TiffEncoderOptions;
+
+    fn new(dest: W, metadata: impl encode::HasEncoderMetadata<Self::Config, P>) -> Result<Self> {
+        Ok(Self {
+            options: metadata.config(),
+            pages: Vec::new(),
+            writer: dest,
+            _marker: PhantomData,
+        })
+    }
+
+    fn add_frame(&mut self, frame: &impl encode::FrameLike<P>) -> Result<()> {
+        let image = frame.image();
+        let data = image.data.iter();
+        let data = match (image.data[0].color_type(), P::BIT_DEPTH) {
+            (ColorType::L, 1 | 8) => PageData::Gray(data.flat_map(P::as_bytes).collect()),
+            (ColorType::Rgb, 8) => PageData::Rgb(data.flat_map(P::as_bytes).collect()),
+            (ColorType::PaletteRgb, 8) => {
+                PageData::Rgb(data.flat_map(|p| p.as_rgb().as_bytes()).collect())
+            }
+            (ColorType::Cmyk, 8) => PageData::Cmyk(data.flat_map(P::as_bytes).collect()),
+            _ => PageData::Rgba(data.flat_map(|p| p.as_rgba().as_bytes()).collect()),
+        };
+
+        self.pages.push((image.width(), image.height(), data));
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoderImpl::new(&mut buffer).map_err(encoding_error)?;
+        for page in &self.pages {
+            self.write_page(&mut encoder, page)
+                .map_err(encoding_error)?;
+        }
+
+        self.writer.write_all(buffer.get_ref())?;
+        Ok(())
+    }
+}
+
+/// A TIFF decoder interface over [`tiff::decoder::Decoder`]. Strip and tile layouts as well as
+/// LZW, Deflate and PackBits compression are supported, and multi-page files are decoded as
+/// sequences with one frame per page.
+///
+/// Samples with a depth of 16 bits are kept losslessly when decoding into 16-bit pixel types such
+/// as [`Rgb16`][crate::Rgb16].
+pub struct TiffDecoder<P: Pixel, R: Read> {
+    _marker: PhantomData<(P, R)>,
+}
+
+impl<P: Pixel, R: Read> TiffDecoder<P, R> {
+    /// Creates a new decoder with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<P: Pixel, R: Read> Default for TiffDecoder<P, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the number of pages in the given TIFF file by following its chain of image file
+/// directories, or `None` if it is not a classic TIFF file.
+fn count_pages(data: &[u8]) -> Option<u32> {
+    let big_endian = match data.get(..4)? {
+        b"II*\0" => false,
+        b"MM\0*" => true,
+        _ => return None,
+    };
+    let read = |offset: usize, len: usize| {
+        let bytes = data.get(offset..offset + len)?;
+        Some(bytes.iter().enumerate().fold(0, |acc, (i, &byte)| {
+            let shift = if big_endian { len - 1 - i } else { i };
+            acc | (byte as usize) << (8 * shift)
+        }))
+    };
+
+    let mut pages = 0;
+    let mut offset = read(4, 4)?;
+    // Every directory takes at least six bytes, which also guards against cyclic chains
+    while offset != 0 && pages < data.len() / 6 {
+        let entries = read(offset, 2)?;
+        offset = read(offset + 2 + entries * 12, 4)?;
+        pages += 1;
+    }
+    Some(pages as u32)
+}
+
+fn read_page<P: Pixel, R: Read + Seek>(decoder: &mut TiffDecoderImpl<R>) -> Result<Image<P>> {
+    let (width, height) = decoder.dimensions()?;
+    let (color_type, channels) = match decoder.colortype()? {
+        TiffColorType::Gray(8 | 16) => (ColorType::L, 1),
+        TiffColorType::GrayA(8 | 16) => (ColorType::LA, 2),
+        TiffColorType::RGB(8 | 16) => (ColorType::Rgb, 3),
+        TiffColorType::RGBA(8 | 16) => (ColorType::Rgba, 4),
+        TiffColorType::CMYK(8 | 16) => (ColorType::Cmyk, 4),
+        _ => return Err(Error::UnsupportedColorType),
+    };
+
+    let (samples, bit_depth) = match decoder.read_image()? {
+        DecodingResult::U8(samples) => (samples, 8),
+        DecodingResult::U16(samples) => {
+            (samples.into_iter().flat_map(u16::to_be_bytes).collect(), 16)
+        }
+        _ => return Err(Error::UnsupportedColorType),
+    };
+    let data = samples
+        .chunks_exact(channels * bit_depth as usize / 8)
+        .map(|chunk| P::from_raw_parts(color_type, bit_depth, chunk))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Image {
+        width: NonZeroU32::new(width).ok_or(Error::EmptyImageError)?,
+        height: NonZeroU32::new(height).ok_or(Error::EmptyImageError)?,
+        data,
+        format: ImageFormat::Tiff,
+        overlay: OverlayMode::default(),
+        palette: None,
+        metadata: ImageMetadata::default(),
+    })
+}
+
+impl<P: Pixel, R: Read> Decoder<P, R> for TiffDecoder<P, R> {
+    type Sequence = TiffFrameIterator<P, R>;
+
+    fn decode(&mut self, mut stream: R) -> Result<Image<P>> {
+        // The decoder needs to seek to the directories of the file, so it is read into memory
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+
+        read_page(&mut TiffDecoderImpl::new(Cursor::new(data))?)
+    }
+
+    fn decode_sequence(&mut self, mut stream: R) -> Result<Self::Sequence> {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+        let len = count_pages(&data).unwrap_or(0);
+
+        Ok(TiffFrameIterator {
+            decoder: TiffDecoderImpl::new(Cursor::new(data))?,
+            len,
+            started: false,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// Iterates over the pages of a TIFF file.
+pub struct TiffFrameIterator<P: Pixel, R: Read> {
+    decoder: TiffDecoderImpl<Cursor<Vec<u8>>>,
+    len: u32,
+    /// Whether the first page has been read. The decoder starts at the first page, and has to be
+    /// advanced to every following page.
+    started: bool,
+    _marker: PhantomData<(P, R)>,
+}
+
+impl<P: Pixel, R: Read> FrameIterator<P> for TiffFrameIterator<P, R> {
+    fn len(&self) -> u32 {
+        self.len
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        // Pages are not an animation
+        LoopCount::Exactly(1)
+    }
+}
+
+impl<P: Pixel, R: Read> Iterator for TiffFrameIterator<P, R> {
+    type Item = Result<Frame<P>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            if !self.decoder.more_images() {
+                return None;
+            }
+            if let Err(err) = self.decoder.next_image() {
+                return Some(Err(err.into()));
+            }
+        }
+        self.started = true;
+
+        Some(read_page(&mut self.decoder).map(Frame::from_image))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    encode, ColorType, Decoder, DisposalMethod, Encoder, Error, Frame, FrameIterator, Image,
-    ImageFormat, LoopCount, OverlayMode, Pixel,
+    encode, BlendMethod, ColorType, Decoder, DisposalMethod, Encoder, Error, Frame, FrameIterator,
+    Image, ImageFormat, ImageMetadata, LoopCount, OverlayMode, Pixel,
 };
 use libwebp_sys as libwebp;
 use std::{
@@ -268,7 +268,14 @@ impl<P: Pixel, W: Write> Encoder<P, W> for WebPMuxEncoder<P, W> {
                 Some(DisposalMethod::None) => libwebp::WEBP_MUX_DISPOSE_NONE,
                 _ => libwebp::WEBP_MUX_DISPOSE_BACKGROUND,
             },
-            ..unsafe { std::mem::zeroed() } // TODO: blend method could be configurable
+            // WebP only supports even offsets, odd offsets are rounded down
+            x_offset: frame.offset().map_or(0, |(x, _)| x) as _,
+            y_offset: frame.offset().map_or(0, |(_, y)| y) as _,
+            blend_method: match frame.blend() {
+                Some(BlendMethod::Source) => libwebp::WEBP_MUX_NO_BLEND,
+                _ => libwebp::WEBP_MUX_BLEND,
+            },
+            ..unsafe { std::mem::zeroed() }
         };
 
         unsafe {
@@ -381,6 +388,7 @@ impl<P: Pixel, R: Read> Decoder<P, R> for WebPDecoder<P, R> {
                 format: ImageFormat::WebP,
                 overlay: OverlayMode::default(),
                 palette: None,
+                metadata: ImageMetadata::default(),
             })
         }
     }
@@ -393,11 +401,16 @@ impl<P: Pixel, R: Read> Decoder<P, R> for WebPDecoder<P, R> {
                 size: bytes.len() as _,
             };
             let demuxer = libwebp::WebPDemux(addr_of!(data));
+            if demuxer.is_null() {
+                return Err(Error::DecodingError("WebP demuxing failed".to_string()));
+            }
 
             Ok(WebPSequenceDecoder {
                 _marker: PhantomData,
                 demuxer,
-                demux_iter: std::ptr::null_mut(),
+                demux_iter: std::mem::zeroed(),
+                started: false,
+                _data: bytes,
             })
         }
     }
@@ -406,7 +419,12 @@ impl<P: Pixel, R: Read> Decoder<P, R> for WebPDecoder<P, R> {
 pub struct WebPSequenceDecoder<P: Pixel> {
     _marker: PhantomData<P>,
     demuxer: *const libwebp::WebPDemuxer,
-    demux_iter: *mut libwebp::WebPIterator,
+    demux_iter: libwebp::WebPIterator,
+    /// Whether the iterator points to a frame yet. The first frame has to be requested from the
+    /// demuxer, every following frame is requested from the iterator.
+    started: bool,
+    /// The demuxer does not copy the encoded data, so it is kept alive for as long as the demuxer
+    _data: Vec<u8>,
 }
 
 impl<P: Pixel> FrameIterator<P> for WebPSequenceDecoder<P> {
@@ -427,11 +445,17 @@ impl<P: Pixel> Iterator for WebPSequenceDecoder<P> {
 
     fn next(&mut self) -> Option<Self::Item> {
         unsafe {
-            if libwebp::WebPDemuxNextFrame(self.demux_iter) == 0 {
+            let found = if self.started {
+                libwebp::WebPDemuxNextFrame(addr_of_mut!(self.demux_iter))
+            } else {
+                self.started = true;
+                libwebp::WebPDemuxGetFrame(self.demuxer, 1, addr_of_mut!(self.demux_iter))
+            };
+            if found == 0 {
                 return None;
             }
 
-            let fragment = (*self.demux_iter).fragment;
+            let fragment = self.demux_iter.fragment;
             let mut width @ mut height = 0;
 
             let out_ptr = libwebp::WebPDecodeRGBA(
@@ -465,11 +489,17 @@ impl<P: Pixel> Iterator for WebPSequenceDecoder<P> {
                 format: ImageFormat::WebP,
                 overlay: OverlayMode::default(),
                 palette: None,
+                metadata: ImageMetadata::default(),
             })
-            .with_delay(Duration::from_millis((*self.demux_iter).duration as _))
-            .with_disposal(match (*self.demux_iter).dispose_method {
+            .with_delay(Duration::from_millis(self.demux_iter.duration as _))
+            .with_disposal(match self.demux_iter.dispose_method {
                 libwebp::WEBP_MUX_DISPOSE_BACKGROUND => DisposalMethod::Background,
                 _ => DisposalMethod::None,
+            })
+            .with_offset(self.demux_iter.x_offset as _, self.demux_iter.y_offset as _)
+            .with_blend(match self.demux_iter.blend_method {
+                libwebp::WEBP_MUX_NO_BLEND => BlendMethod::Source,
+                _ => BlendMethod::Over,
             });
 
             Some(Ok(frame))
@@ -480,7 +510,7 @@ impl<P: Pixel> Iterator for WebPSequenceDecoder<P> {
 impl<P: Pixel> Drop for WebPSequenceDecoder<P> {
     fn drop(&mut self) {
         unsafe {
-            libwebp::WebPDemuxReleaseIterator(self.demux_iter);
+            libwebp::WebPDemuxReleaseIterator(addr_of_mut!(self.demux_iter));
             libwebp::WebPDemuxDelete(self.demuxer as *mut _);
         }
     }
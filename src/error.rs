@@ -1,10 +1,15 @@
 //! Common error types.
 
-use std::ffi::OsString;
-use std::fmt;
+use alloc::{boxed::Box, string::String};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{ffi::OsString, path::PathBuf};
+
+#[cfg(feature = "std")]
+use crate::ImageFormat;
 
 /// A shortcut type equivalent to `Result<T, ril::Error>`.
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Represents an error that occurs within the crate.
 #[derive(Debug)]
@@ -12,6 +17,9 @@ pub enum Error {
     /// An invalid hex code was provided when trying to parse a hex value.
     InvalidHexCode(String),
 
+    /// An invalid CSS color was provided when trying to parse a color.
+    InvalidColor(String),
+
     /// Received an invalid palette index.
     InvalidPaletteIndex,
 
@@ -22,6 +30,7 @@ pub enum Error {
     /// This is **not** an error that occurs when the file extension is not recognized, or
     /// is an unknown image extension. This occurs if the OsStr fails conversion to a native
     /// &str. In the case of this, [`ImageFormat::Unknown`] is used instead.
+    #[cfg(feature = "std")]
     InvalidExtension(OsString),
 
     /// Failed to encode an image.
@@ -29,17 +38,71 @@ pub enum Error {
 
     /// Invalid data was encountered when an image, usually because it is corrupted.
     ///
-    /// Errors can differ across encodings, so the inner ``&'static str`` here is nothing more than
-    /// an error message.
+    /// Errors can differ across encodings, so the inner string here is nothing more than an error
+    /// message. Errors raised by an underlying codec are reported as
+    /// [`Error::CodecDecodingError`] instead.
     DecodingError(String),
 
+    /// The underlying codec failed to encode an image in the given format.
+    #[cfg(feature = "std")]
+    CodecEncodingError {
+        /// The format the image was being encoded in.
+        format: ImageFormat,
+        /// The error raised by the codec.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The underlying codec failed to decode an image in the given format, usually because the
+    /// image is corrupted.
+    #[cfg(feature = "std")]
+    CodecDecodingError {
+        /// The format the image was being decoded from.
+        format: ImageFormat,
+        /// The error raised by the codec.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// An error occured while reading from or writing to the file at the given path.
+    #[cfg(feature = "std")]
+    PathError {
+        /// The path of the file.
+        path: PathBuf,
+        /// The error that occured.
+        source: Box<Error>,
+    },
+
+    /// An error occured while processing the frame at the given index of an image sequence.
+    FrameError {
+        /// The index of the frame, starting from 0.
+        index: usize,
+        /// The error that occured.
+        source: Box<Error>,
+    },
+
     /// An error occured while trying to render or rasterize a font.
     #[cfg(feature = "text")]
     FontError(&'static str),
 
+    /// An error occured while trying to load or apply an ICC color profile.
+    #[cfg(feature = "icc")]
+    ColorProfileError(String),
+
+    /// An error occured while trying to upload an image to or read an image back from the GPU.
+    #[cfg(feature = "wgpu")]
+    GpuError(String),
+
+    /// Failed to encode data into a QR code or barcode.
+    #[cfg(feature = "barcode")]
+    BarcodeError(String),
+
     /// No encoding format could be inferred for the given image.
     UnknownEncodingFormat,
 
+    /// No decoder for the given encoding format is available, usually because the feature which
+    /// enables it is disabled.
+    #[cfg(feature = "std")]
+    UnsupportedFormat(ImageFormat),
+
     /// An image received data incompatible with the image's dimensions.
     IncompatibleImageData {
         width: u32,
@@ -47,6 +110,15 @@ pub enum Error {
         received: usize,
     },
 
+    /// The given coordinates or region lie outside of the bounds of an image.
+    OutOfBounds {
+        /// The requested region as `(x1, y1, x2, y2)`, where `x2` and `y2` are exclusive. A single
+        /// pixel at `(x, y)` is represented as `(x, y, x + 1, y + 1)`.
+        region: (i64, i64, i64, i64),
+        /// The dimensions of the image.
+        dimensions: (u32, u32),
+    },
+
     /// Received an unsupported color type when trying to create a pixel from raw data.
     ///
     /// This occurs when the color type is not supported by the pixel type. This is almost
@@ -55,6 +127,7 @@ pub enum Error {
     UnsupportedColorType,
 
     /// An error occured when trying to read a file or when trying to write to a file.
+    #[cfg(feature = "std")]
     IoError(std::io::Error),
 
     /// Tried to encode an empty image, or an image without data. This is also raised when trying
@@ -71,21 +144,114 @@ pub enum Error {
     },
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// Attaches the path of the file being read or written to this error.
+    #[cfg(feature = "std")]
+    pub(crate) fn with_path(self, path: impl Into<PathBuf>) -> Self {
+        Self::PathError {
+            path: path.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Attaches the index of the frame being processed to this error.
+    #[cfg(feature = "std")]
+    pub(crate) fn with_frame(self, index: usize) -> Self {
+        Self::FrameError {
+            index,
+            source: Box::new(self),
+        }
+    }
+
+    /// Wraps an error raised by the encoder of the given format.
+    #[cfg(feature = "std")]
+    pub(crate) fn encoding(
+        format: ImageFormat,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::CodecEncodingError {
+            format,
+            source: source.into(),
+        }
+    }
+
+    /// Wraps an error raised by the decoder of the given format.
+    #[cfg(feature = "std")]
+    pub(crate) fn decoding(
+        format: ImageFormat,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::CodecDecodingError {
+            format,
+            source: source.into(),
+        }
+    }
+
+    /// Returns the innermost error, skipping over any file path or frame index context.
+    #[must_use]
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            #[cfg(feature = "std")]
+            Self::PathError { source, .. } => source.root_cause(),
+            Self::FrameError { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IoError(err) => Some(err),
+            Self::CodecEncodingError { source, .. } | Self::CodecDecodingError { source, .. } => {
+                Some(source.as_ref())
+            }
+            Self::PathError { source, .. } | Self::FrameError { source, .. } => {
+                Some(source.as_ref())
+            }
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::InvalidHexCode(hex_code) => write!(f, "Invalid hex code: {hex_code}"),
+            Self::InvalidColor(color) => write!(f, "Invalid color: {color}"),
             Self::InvalidPaletteIndex => write!(f, "Invalid palette index"),
+            #[cfg(feature = "std")]
             Self::InvalidExtension(ext) => {
                 write!(f, "Invalid extension: {}", ext.to_string_lossy())
             }
             Self::EncodingError(msg) => write!(f, "Encoding error: {msg}"),
             Self::DecodingError(msg) => write!(f, "Decoding error: {msg}"),
+            #[cfg(feature = "std")]
+            Self::CodecEncodingError { format, source } => {
+                write!(f, "Encoding error ({format}): {source}")
+            }
+            #[cfg(feature = "std")]
+            Self::CodecDecodingError { format, source } => {
+                write!(f, "Decoding error ({format}): {source}")
+            }
+            #[cfg(feature = "std")]
+            Self::PathError { path, source } => write!(f, "{}: {source}", path.display()),
+            Self::FrameError { index, source } => write!(f, "Frame {index}: {source}"),
             #[cfg(feature = "text")]
             Self::FontError(msg) => write!(f, "Font error: {msg}"),
+            #[cfg(feature = "icc")]
+            Self::ColorProfileError(msg) => write!(f, "Color profile error: {msg}"),
+            #[cfg(feature = "wgpu")]
+            Self::GpuError(msg) => write!(f, "GPU error: {msg}"),
+            #[cfg(feature = "barcode")]
+            Self::BarcodeError(msg) => write!(f, "Barcode error: {msg}"),
             Self::UnknownEncodingFormat => write!(f, "Could not infer encoding format"),
+            #[cfg(feature = "std")]
+            Self::UnsupportedFormat(format) => write!(
+                f,
+                "No decoder is available for the {format} format. Did you forget to enable the feature?"
+            ),
             Self::UnsupportedColorType => write!(
                 f,
                 "Unsupported color type. Try using the `Dynamic` pixel type instead."
@@ -99,6 +265,25 @@ impl fmt::Display for Error {
                 "An image with dimensions {width}x{height} should have {} pixels, received {received} instead",
                 width * height,
             ),
+            Self::OutOfBounds {
+                region: (x1, y1, x2, y2),
+                dimensions: (width, height),
+            } => {
+                if x2 - x1 == 1 && y2 - y1 == 1 {
+                    write!(
+                        f,
+                        "Pixel ({x1}, {y1}) is out of bounds of an image with dimensions \
+                        {width}x{height}"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Region ({x1}, {y1}) to ({x2}, {y2}) is out of bounds of an image with \
+                        dimensions {width}x{height}"
+                    )
+                }
+            }
+            #[cfg(feature = "std")]
             Self::IoError(error) => write!(f, "IO error: {error}"),
             Self::EmptyImageError => write!(f, "Tried encoding an empty image"),
             Self::QuantizationOverflow {
@@ -115,6 +300,7 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
         Self::IoError(err)
@@ -126,11 +312,7 @@ impl From<png::EncodingError> for Error {
     fn from(err: png::EncodingError) -> Self {
         match err {
             png::EncodingError::IoError(err) => Self::IoError(err),
-            png::EncodingError::Format(err) => Self::EncodingError(err.to_string()),
-            png::EncodingError::LimitsExceeded => {
-                Self::EncodingError("limits exceeded".to_string())
-            }
-            png::EncodingError::Parameter(err) => Self::EncodingError(err.to_string()),
+            err => Self::encoding(ImageFormat::Png, err),
         }
     }
 }
@@ -140,11 +322,7 @@ impl From<png::DecodingError> for Error {
     fn from(err: png::DecodingError) -> Self {
         match err {
             png::DecodingError::IoError(err) => Self::IoError(err),
-            png::DecodingError::Format(err) => Self::DecodingError(err.to_string()),
-            png::DecodingError::LimitsExceeded => {
-                Self::DecodingError("limits exceeded".to_string())
-            }
-            png::DecodingError::Parameter(err) => Self::DecodingError(err.to_string()),
+            err => Self::decoding(ImageFormat::Png, err),
         }
     }
 }
@@ -154,7 +332,7 @@ impl From<jpeg_decoder::Error> for Error {
     fn from(err: jpeg_decoder::Error) -> Self {
         match err {
             jpeg_decoder::Error::Io(err) => Self::IoError(err),
-            err => Self::DecodingError(err.to_string()),
+            err => Self::decoding(ImageFormat::Jpeg, err),
         }
     }
 }
@@ -164,7 +342,7 @@ impl From<jpeg_encoder::EncodingError> for Error {
     fn from(err: jpeg_encoder::EncodingError) -> Self {
         match err {
             jpeg_encoder::EncodingError::IoError(err) => Self::IoError(err),
-            err => Self::EncodingError(err.to_string()),
+            err => Self::encoding(ImageFormat::Jpeg, err),
         }
     }
 }
@@ -174,7 +352,7 @@ impl From<gif::EncodingError> for Error {
     fn from(err: gif::EncodingError) -> Self {
         match err {
             gif::EncodingError::Io(err) => Self::IoError(err),
-            gif::EncodingError::Format(err) => Self::EncodingError(err.to_string()),
+            err => Self::encoding(ImageFormat::Gif, err),
         }
     }
 }
@@ -184,7 +362,31 @@ impl From<gif::DecodingError> for Error {
     fn from(err: gif::DecodingError) -> Self {
         match err {
             gif::DecodingError::Io(err) => Self::IoError(err),
-            gif::DecodingError::Format(err) => Self::DecodingError(err.to_string()),
+            err => Self::decoding(ImageFormat::Gif, err),
+        }
+    }
+}
+
+#[cfg(feature = "tiff")]
+impl From<tiff::TiffError> for Error {
+    fn from(err: tiff::TiffError) -> Self {
+        match err {
+            tiff::TiffError::IoError(err) => Self::IoError(err),
+            err => Self::decoding(ImageFormat::Tiff, err),
         }
     }
 }
+
+#[cfg(feature = "icc")]
+impl From<lcms2::Error> for Error {
+    fn from(err: lcms2::Error) -> Self {
+        Self::ColorProfileError(err.to_string())
+    }
+}
+
+#[cfg(feature = "wgpu")]
+impl From<wgpu::BufferAsyncError> for Error {
+    fn from(err: wgpu::BufferAsyncError) -> Self {
+        Self::GpuError(err.to_string())
+    }
+}
@@ -0,0 +1,461 @@
+//! A C-compatible interface for using this crate from other languages, for example from Python
+//! through `ctypes` or from Node.js through `napi`.
+//!
+//! Images and fonts are passed around as opaque pointers, which are created by functions such as
+//! [`ril_image_open`] and [`ril_font_open`] and must be freed with [`ril_image_free`] and
+//! [`ril_font_free`] respectively. Images are always stored as RGBA.
+//!
+//! Functions which can fail return a null pointer or `false` on failure, in which case a message
+//! describing the error can be retrieved with [`ril_last_error`]. Panics are caught and reported
+//! the same way instead of unwinding into foreign code.
+//!
+//! # Building
+//! Build a shared library which exports these functions with:
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! # Example
+//! ```c
+//! RilImage *image = ril_image_new(256, 128, 0, 0, 0, 255);
+//! RilFont *font = ril_font_open("Inter.ttf", 24.0);
+//!
+//! if (!ril_image_draw_text(image, font, "Hello, world!", 16, 16, 24.0, 255, 255, 255, 255)) {
+//!     fprintf(stderr, "%s\n", ril_last_error());
+//! }
+//!
+//! size_t len;
+//! uint8_t *png = ril_image_encode(image, "png", &len);
+//! /* ... */
+//! ril_buffer_free(png, len);
+//! ril_font_free(font);
+//! ril_image_free(image);
+//! ```
+
+#[cfg(feature = "resize")]
+use crate::ResizeAlgorithm;
+use crate::{Error, Image, ImageFormat, Rgba};
+#[cfg(feature = "text")]
+use crate::{Font, TextLayout, TextSegment};
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString},
+    fmt::{self, Display},
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+/// An opaque handle to an RGBA image.
+pub struct RilImage(Image<Rgba>);
+
+/// An opaque handle to a font.
+#[cfg(feature = "text")]
+pub struct RilFont(Font);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// An error raised by a function of the C interface.
+enum FfiError {
+    /// A required pointer argument was null.
+    Null(&'static str),
+    /// A string argument was not valid UTF-8.
+    Utf8(&'static str),
+    /// An argument was out of range.
+    Invalid(&'static str),
+    /// An error raised by this crate.
+    Ril(Error),
+    /// A panic, along with its message.
+    Panic(String),
+}
+
+impl From<Error> for FfiError {
+    fn from(err: Error) -> Self {
+        Self::Ril(err)
+    }
+}
+
+impl Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null(arg) => write!(f, "Argument `{arg}` must not be null"),
+            Self::Utf8(arg) => write!(f, "Argument `{arg}` is not valid UTF-8"),
+            Self::Invalid(msg) => f.write_str(msg),
+            Self::Ril(err) => write!(f, "{err}"),
+            Self::Panic(msg) => write!(f, "Panicked: {msg}"),
+        }
+    }
+}
+
+/// Runs the given function, storing the error or panic message and returning the fallback value
+/// if it fails.
+fn guard<T>(fallback: T, f: impl FnOnce() -> Result<T, FfiError>) -> T {
+    let err = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(err)) => err,
+        Err(payload) => FfiError::Panic(
+            payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default(),
+        ),
+    };
+
+    // Interior nul bytes cannot be represented in a C string, so they are stripped
+    let message = CString::new(err.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    fallback
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::Null(name));
+    }
+
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| FfiError::Utf8(name))
+}
+
+unsafe fn bytes_arg<'a>(
+    ptr: *const u8,
+    len: usize,
+    name: &'static str,
+) -> Result<&'a [u8], FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::Null(name));
+    }
+
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn handle_arg<'a, T>(ptr: *mut T, name: &'static str) -> Result<&'a mut T, FfiError> {
+    ptr.as_mut().ok_or(FfiError::Null(name))
+}
+
+fn into_handle<T>(value: T) -> *mut T {
+    Box::into_raw(Box::new(value))
+}
+
+/// Returns the message of the last error raised by a function of this interface on the current
+/// thread, or null if no error has been raised yet.
+///
+/// The returned string is owned by this crate and is valid until the next call to a function of
+/// this interface on the same thread.
+#[no_mangle]
+pub extern "C" fn ril_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |msg| msg.as_ptr())
+    })
+}
+
+/// Creates a new image with the given dimensions, filled with the given color.
+///
+/// Returns null if the width or height is zero.
+#[no_mangle]
+pub extern "C" fn ril_image_new(
+    width: u32,
+    height: u32,
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+) -> *mut RilImage {
+    guard(ptr::null_mut(), || {
+        if width == 0 || height == 0 {
+            return Err(FfiError::Invalid("Image dimensions must not be zero"));
+        }
+
+        Ok(into_handle(RilImage(Image::new(
+            width,
+            height,
+            Rgba::new(r, g, b, a),
+        ))))
+    })
+}
+
+/// Opens the image at the given path, inferring its encoding from its contents.
+///
+/// Returns null if the image could not be opened or decoded.
+///
+/// # Safety
+/// `path` must be null or a valid pointer to a nul-terminated string.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_open(path: *const c_char) -> *mut RilImage {
+    guard(ptr::null_mut(), || {
+        let path = str_arg(path, "path")?;
+
+        Ok(into_handle(RilImage(Image::open(path)?)))
+    })
+}
+
+/// Decodes an image from the given encoded bytes, inferring its encoding from its contents.
+///
+/// Returns null if the image could not be decoded.
+///
+/// # Safety
+/// `data` must be null or a valid pointer to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_from_bytes(data: *const u8, len: usize) -> *mut RilImage {
+    guard(ptr::null_mut(), || {
+        let data = bytes_arg(data, len, "data")?;
+
+        Ok(into_handle(RilImage(Image::from_bytes_inferred(data)?)))
+    })
+}
+
+/// Frees the given image. Does nothing if the image is null.
+///
+/// # Safety
+/// `image` must be null or a pointer returned by a function of this interface which has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_free(image: *mut RilImage) {
+    if !image.is_null() {
+        drop(Box::from_raw(image));
+    }
+}
+
+/// Returns the width of the given image, or `0` if the image is null.
+///
+/// # Safety
+/// `image` must be null or a valid image handle.
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_width(image: *const RilImage) -> u32 {
+    image.as_ref().map_or(0, |image| image.0.width())
+}
+
+/// Returns the height of the given image, or `0` if the image is null.
+///
+/// # Safety
+/// `image` must be null or a valid image handle.
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_height(image: *const RilImage) -> u32 {
+    image.as_ref().map_or(0, |image| image.0.height())
+}
+
+/// Resizes the given image in place to the given dimensions.
+///
+/// The algorithm is one of `0` (nearest), `1` (box), `2` (bilinear), `3` (hamming),
+/// `4` (bicubic), `5` (mitchell) or `6` (lanczos3).
+///
+/// Returns `false` if the image is null, the dimensions are zero or the algorithm is unknown.
+///
+/// # Safety
+/// `image` must be null or a valid image handle.
+#[cfg(feature = "resize")]
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_resize(
+    image: *mut RilImage,
+    width: u32,
+    height: u32,
+    algorithm: u32,
+) -> bool {
+    guard(false, || {
+        let image = handle_arg(image, "image")?;
+        if width == 0 || height == 0 {
+            return Err(FfiError::Invalid("Image dimensions must not be zero"));
+        }
+
+        let algorithm = match algorithm {
+            0 => ResizeAlgorithm::Nearest,
+            1 => ResizeAlgorithm::Box,
+            2 => ResizeAlgorithm::Bilinear,
+            3 => ResizeAlgorithm::Hamming,
+            4 => ResizeAlgorithm::Bicubic,
+            5 => ResizeAlgorithm::Mitchell,
+            6 => ResizeAlgorithm::Lanczos3,
+            _ => return Err(FfiError::Invalid("Unknown resize algorithm")),
+        };
+        image.0.resize(width, height, algorithm);
+
+        Ok(true)
+    })
+}
+
+/// Opens the font at the given path, with the given optimal size in pixels.
+///
+/// Returns null if the font could not be opened or parsed.
+///
+/// # Safety
+/// `path` must be null or a valid pointer to a nul-terminated string.
+#[cfg(all(
+    feature = "text",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+#[no_mangle]
+pub unsafe extern "C" fn ril_font_open(path: *const c_char, size: f32) -> *mut RilFont {
+    guard(ptr::null_mut(), || {
+        let path = str_arg(path, "path")?;
+
+        Ok(into_handle(RilFont(Font::open(path, size)?)))
+    })
+}
+
+/// Parses a font from the given bytes, with the given optimal size in pixels.
+///
+/// Returns null if the font could not be parsed.
+///
+/// # Safety
+/// `data` must be null or a valid pointer to `len` readable bytes.
+#[cfg(feature = "text")]
+#[no_mangle]
+pub unsafe extern "C" fn ril_font_from_bytes(
+    data: *const u8,
+    len: usize,
+    size: f32,
+) -> *mut RilFont {
+    guard(ptr::null_mut(), || {
+        let data = bytes_arg(data, len, "data")?;
+
+        Ok(into_handle(RilFont(Font::from_bytes(data, size)?)))
+    })
+}
+
+/// Frees the given font. Does nothing if the font is null.
+///
+/// # Safety
+/// `font` must be null or a pointer returned by a function of this interface which has not been
+/// freed yet.
+#[cfg(feature = "text")]
+#[no_mangle]
+pub unsafe extern "C" fn ril_font_free(font: *mut RilFont) {
+    if !font.is_null() {
+        drop(Box::from_raw(font));
+    }
+}
+
+/// Draws the given text onto the given image, with its top-left corner at the given position and
+/// with the given size in pixels and color.
+///
+/// Returns `false` if any of the arguments are null or the text is not valid UTF-8.
+///
+/// # Safety
+/// * `image` must be null or a valid image handle.
+/// * `font` must be null or a valid font handle.
+/// * `text` must be null or a valid pointer to a nul-terminated string.
+#[cfg(feature = "text")]
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn ril_image_draw_text(
+    image: *mut RilImage,
+    font: *const RilFont,
+    text: *const c_char,
+    x: u32,
+    y: u32,
+    size: f32,
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+) -> bool {
+    guard(false, || {
+        let image = handle_arg(image, "image")?;
+        let font = font.as_ref().ok_or(FfiError::Null("font"))?;
+        let text = str_arg(text, "text")?;
+
+        let segment = TextSegment::new(&font.0, text, Rgba::new(r, g, b, a)).with_size(size);
+        image
+            .0
+            .draw(&TextLayout::new().with_position(x, y).with_text(&segment));
+
+        Ok(true)
+    })
+}
+
+/// Encodes the given image with the encoding of the given file extension, such as `"png"`, and
+/// returns a buffer of the encoded bytes. The length of the buffer is written to `len`.
+///
+/// The buffer must be freed with [`ril_buffer_free`]. Returns null if the extension is unknown or
+/// the image could not be encoded.
+///
+/// # Safety
+/// * `image` must be null or a valid image handle.
+/// * `format` must be null or a valid pointer to a nul-terminated string.
+/// * `len` must be null or a valid pointer to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_encode(
+    image: *const RilImage,
+    format: *const c_char,
+    len: *mut usize,
+) -> *mut u8 {
+    guard(ptr::null_mut(), || {
+        let image = image.as_ref().ok_or(FfiError::Null("image"))?;
+        let len = handle_arg(len, "len")?;
+        let format = match ImageFormat::from_extension(str_arg(format, "format")?)? {
+            ImageFormat::Unknown => return Err(Error::UnknownEncodingFormat.into()),
+            format => format,
+        };
+
+        let mut buffer = Vec::new();
+        image.0.encode(format, &mut buffer)?;
+
+        let buffer = buffer.into_boxed_slice();
+        *len = buffer.len();
+        Ok(Box::into_raw(buffer).cast())
+    })
+}
+
+/// Encodes the given image and saves it to the given path, inferring the encoding from the
+/// extension of the path.
+///
+/// Returns `false` if the encoding could not be inferred or the image could not be encoded or
+/// saved.
+///
+/// # Safety
+/// * `image` must be null or a valid image handle.
+/// * `path` must be null or a valid pointer to a nul-terminated string.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+#[no_mangle]
+pub unsafe extern "C" fn ril_image_save(image: *const RilImage, path: *const c_char) -> bool {
+    guard(false, || {
+        let image = image.as_ref().ok_or(FfiError::Null("image"))?;
+        let path = str_arg(path, "path")?;
+        image.0.save_inferred(path)?;
+
+        Ok(true)
+    })
+}
+
+/// Frees a buffer returned by [`ril_image_encode`]. Does nothing if the buffer is null.
+///
+/// # Safety
+/// `buffer` must be null or a buffer returned by [`ril_image_encode`] which has not been freed
+/// yet, and `len` must be the length that was written alongside it.
+#[no_mangle]
+pub unsafe extern "C" fn ril_buffer_free(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer, len)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_errors() {
+        unsafe {
+            let image = ril_image_new(4, 2, 255, 0, 0, 255);
+            assert_eq!(ril_image_width(image), 4);
+
+            let mut len = 0;
+            let buffer = ril_image_encode(image, b"txt\0".as_ptr().cast(), &mut len);
+            assert!(buffer.is_null());
+
+            let error = CStr::from_ptr(ril_last_error()).to_str().unwrap();
+            assert_eq!(error, Error::UnknownEncodingFormat.to_string());
+
+            assert!(ril_image_new(0, 2, 0, 0, 0, 0).is_null());
+            ril_image_free(image);
+        }
+    }
+}
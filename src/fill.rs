@@ -1,4 +1,4 @@
-use crate::{Image, OverlayMode, Pixel};
+use crate::{DrawTarget, Image, OverlayMode, Pixel};
 
 pub type BoundingBox<T> = (T, T, T, T);
 
@@ -33,13 +33,20 @@ pub trait Fill<P: Pixel>: Clone {
     /// Gets the color of the fill at the given coordinates.
     fn get_pixel(&self, x: u32, y: u32) -> P;
 
-    /// Plots the fill at the given coordinates on the given image.
-    fn plot(&self, image: &mut Image<P>, x: u32, y: u32, mode: OverlayMode) {
+    /// Plots the fill at the given coordinates on the given image or view.
+    fn plot(&self, image: &mut impl DrawTarget<P>, x: u32, y: u32, mode: OverlayMode) {
         image.overlay_pixel_with_mode(x, y, self.get_pixel(x, y), mode);
     }
 
     /// Plots the fill at the given coordinates on the given image with a custom alpha value.
-    fn plot_with_alpha(&self, image: &mut Image<P>, x: u32, y: u32, mode: OverlayMode, alpha: u8) {
+    fn plot_with_alpha(
+        &self,
+        image: &mut impl DrawTarget<P>,
+        x: u32,
+        y: u32,
+        mode: OverlayMode,
+        alpha: u8,
+    ) {
         image.overlay_pixel_with_alpha(x, y, self.get_pixel(x, y), mode, alpha);
     }
 }
@@ -121,3 +128,75 @@ impl<P: Pixel> Fill<P> for ImageFill<'_, P> {
             .unwrap_or_else(|| self.0.data[0])
     }
 }
+
+/// Represents a fill that repeats an image in both directions, i.e. a tiled pattern.
+///
+/// Unlike [`ImageFill`], the image does not have to cover the object being drawn. By default, the
+/// top-left corner of the pattern is aligned with the top-left corner of the bounding box of the
+/// object being drawn, which can be adjusted with [`with_offset`][Pattern::with_offset].
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let tile = Image::from_fn(8, 8, |x, y| {
+///     if (x < 4) == (y < 4) { Rgb::black() } else { Rgb::white() }
+/// });
+///
+/// let mut image = Image::new(64, 64, Rgb::white());
+/// image.draw(&Rectangle::from_bounding_box(8, 8, 56, 56).with_fill(Pattern::new(&tile)));
+/// ```
+#[derive(Copy, Clone)]
+pub struct Pattern<'a, P: Pixel> {
+    image: &'a Image<P>,
+    offset: (i64, i64),
+    origin: (i64, i64),
+}
+
+impl<'a, P: Pixel> Pattern<'a, P> {
+    /// Creates a new pattern which repeats the given image.
+    #[must_use]
+    pub const fn new(image: &'a Image<P>) -> Self {
+        Self {
+            image,
+            offset: (0, 0),
+            origin: (0, 0),
+        }
+    }
+
+    /// Sets the offset of the pattern relative to the top-left corner of the bounding box of the
+    /// object being drawn.
+    #[must_use]
+    pub const fn with_offset(mut self, x: i64, y: i64) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// Returns a reference to the image being repeated.
+    #[must_use]
+    pub const fn image(&self) -> &'a Image<P> {
+        self.image
+    }
+}
+
+impl<'a, P: Pixel> IntoFill for Pattern<'a, P> {
+    type Pixel = P;
+    type Fill = Self;
+
+    fn into_fill(self) -> Self::Fill {
+        self
+    }
+}
+
+impl<P: Pixel> Fill<P> for Pattern<'_, P> {
+    fn set_bounding_box(&mut self, (x, y, ..): BoundingBox<u32>) {
+        self.origin = (x as i64 + self.offset.0, y as i64 + self.offset.1);
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> P {
+        let (width, height) = self.image.dimensions();
+        let x = (x as i64 - self.origin.0).rem_euclid(width as i64);
+        let y = (y as i64 - self.origin.1).rem_euclid(height as i64);
+
+        *self.image.pixel(x as u32, y as u32)
+    }
+}
@@ -1,8 +1,11 @@
 //! Filters that can be applied on images.
 
-use crate::{BitPixel, Image, Pixel};
+use crate::{parallel, BitPixel, Draw, EdgeMode, Image, Pixel, TrueColor};
+use alloc::{vec, vec::Vec};
+use core::{marker::PhantomData, ops::DerefMut};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 use num_traits::{bounds::UpperBounded, AsPrimitive, FromPrimitive, Zero};
-use std::marker::PhantomData;
 
 /// An image filter than can be lazily applied to an image or a filtered image.
 ///
@@ -86,3 +89,467 @@ pub struct Mask<F: Filter> {
     /// The mask to apply the filter to.
     pub mask: Image<BitPixel>,
 }
+
+/// The weights of a [`Convolution`].
+#[derive(Clone, Debug, PartialEq)]
+enum Kernel {
+    /// A kernel with the given dimensions, whose weights are stored row by row.
+    Full {
+        weights: Vec<f64>,
+        width: u32,
+        height: u32,
+    },
+    /// A kernel which is the product of a row and a column of weights. These are applied in two
+    /// passes, which is much faster for large kernels.
+    Separable {
+        horizontal: Vec<f64>,
+        vertical: Vec<f64>,
+    },
+}
+
+/// Convolves an image with a kernel of weights: every pixel becomes the weighted sum of the pixels
+/// around it. The center of the kernel lies on the pixel being computed, or to the bottom-right
+/// of the center for kernels with an even width or height.
+///
+/// Colors are weighted by their alpha, so transparent pixels do not bleed their color into their
+/// neighbors. Pixels outside of the image are read according to the [`EdgeMode`], which defaults
+/// to [`EdgeMode::Clamp`].
+///
+/// # See Also
+/// - [`GaussianBlur`], [`BoxBlur`], [`Sharpen`] and [`Sobel`] for common convolutions.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::from_fn(5, 5, |x, _| L(if x == 2 { 255 } else { 0 }));
+///
+/// // Averages each pixel with its left and right neighbors
+/// image.draw(&Convolution::new(3, 1, [1.0 / 3.0; 3]));
+///
+/// assert_eq!(image.pixel(1, 0).value(), 85);
+/// assert_eq!(image.pixel(2, 0).value(), 85);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Convolution<P: Pixel> {
+    kernel: Kernel,
+    /// How pixels outside of the image are read. Defaults to [`EdgeMode::Clamp`].
+    pub edge: EdgeMode<P>,
+}
+
+impl<P: Pixel> Convolution<P> {
+    /// Creates a new convolution with the given kernel dimensions and weights, which are given row
+    /// by row.
+    ///
+    /// # Panics
+    /// * The kernel is empty.
+    /// * The amount of weights is not `width * height`.
+    #[must_use]
+    pub fn new(width: u32, height: u32, weights: impl Into<Vec<f64>>) -> Self {
+        let weights = weights.into();
+        assert!(width > 0 && height > 0, "kernel must not be empty");
+        assert_eq!(
+            weights.len(),
+            width as usize * height as usize,
+            "kernel must have width * height weights",
+        );
+
+        Self {
+            kernel: Kernel::Full {
+                weights,
+                width,
+                height,
+            },
+            edge: EdgeMode::Clamp,
+        }
+    }
+
+    /// Creates a new separable convolution, whose kernel is the product of the given row and
+    /// column of weights. The image is convolved with each of them in turn, which is much faster
+    /// than convolving with the full kernel.
+    ///
+    /// # Panics
+    /// * Either of the weights is empty.
+    #[must_use]
+    pub fn separable(horizontal: impl Into<Vec<f64>>, vertical: impl Into<Vec<f64>>) -> Self {
+        let (horizontal, vertical) = (horizontal.into(), vertical.into());
+        assert!(
+            !horizontal.is_empty() && !vertical.is_empty(),
+            "kernel must not be empty",
+        );
+
+        Self {
+            kernel: Kernel::Separable {
+                horizontal,
+                vertical,
+            },
+            edge: EdgeMode::Clamp,
+        }
+    }
+
+    /// Sets how pixels outside of the image are read.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge: EdgeMode<P>) -> Self {
+        self.edge = edge;
+        self
+    }
+}
+
+/// The premultiplied RGBA channels of every pixel of an image.
+struct Channels {
+    data: Vec<[f64; 4]>,
+    width: u32,
+    height: u32,
+}
+
+impl Channels {
+    fn from_image<P: TrueColor>(image: &Image<P>) -> Self {
+        Self {
+            data: parallel::map_rows(image.height(), |y| {
+                let width = image.width() as usize;
+                image.data[y as usize * width..][..width]
+                    .iter()
+                    .map(|&pixel| to_channels(pixel))
+            }),
+            width: image.width(),
+            height: image.height(),
+        }
+    }
+
+    /// Reads the channels at the given coordinates, resolving coordinates outside of the image
+    /// with the given edge mode. Constant pixels are read as `constant`.
+    fn get<P: Pixel>(&self, x: i64, y: i64, edge: &EdgeMode<P>, constant: [f64; 4]) -> [f64; 4] {
+        match (edge.resolve(x, self.width), edge.resolve(y, self.height)) {
+            (Some(x), Some(y)) => self.data[(y * self.width + x) as usize],
+            _ => constant,
+        }
+    }
+
+    /// Convolves these channels with the given kernel, which has its center at
+    /// `(width / 2, height / 2)`.
+    fn convolve<P: Pixel>(
+        &self,
+        weights: &[f64],
+        (width, height): (u32, u32),
+        edge: &EdgeMode<P>,
+        constant: [f64; 4],
+    ) -> Self {
+        let (cx, cy) = (i64::from(width / 2), i64::from(height / 2));
+        let taps = (0..height)
+            .flat_map(|ky| (0..width).map(move |kx| (kx, ky)))
+            .zip(weights)
+            .filter(|(_, &weight)| weight != 0.0)
+            .map(|((kx, ky), &weight)| (i64::from(kx) - cx, i64::from(ky) - cy, weight))
+            .collect::<Vec<_>>();
+        let taps = &taps;
+
+        let data = parallel::map_rows(self.height, |y| {
+            (0..self.width).map(move |x| {
+                let mut sum = [0.0; 4];
+                for &(dx, dy, weight) in taps {
+                    let channels = self.get(i64::from(x) + dx, i64::from(y) + dy, edge, constant);
+                    for (sum, channel) in sum.iter_mut().zip(channels) {
+                        *sum = channel.mul_add(weight, *sum);
+                    }
+                }
+                sum
+            })
+        });
+
+        Self {
+            data,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+fn to_channels<P: TrueColor>(pixel: P) -> [f64; 4] {
+    let (r, g, b, a) = pixel.as_rgba_tuple();
+    let alpha = f64::from(a) / 255.0;
+
+    [
+        f64::from(r) * alpha,
+        f64::from(g) * alpha,
+        f64::from(b) * alpha,
+        f64::from(a),
+    ]
+}
+
+fn from_channels<P: TrueColor>([r, g, b, a]: [f64; 4]) -> P {
+    let a = a.clamp(0.0, 255.0);
+    let unmultiply = |c: f64| {
+        if a > 0.0 {
+            (c * 255.0 / a).round().clamp(0.0, 255.0) as u8
+        } else {
+            0
+        }
+    };
+
+    P::from_rgba_tuple((unmultiply(r), unmultiply(g), unmultiply(b), a.round() as u8))
+}
+
+impl<P: TrueColor> Convolution<P> {
+    fn apply(&self, channels: &Channels) -> Channels {
+        let constant = match self.edge {
+            EdgeMode::Constant(pixel) => to_channels(pixel),
+            _ => [0.0; 4],
+        };
+
+        match &self.kernel {
+            Kernel::Full {
+                weights,
+                width,
+                height,
+            } => channels.convolve(weights, (*width, *height), &self.edge, constant),
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => {
+                let rows = channels.convolve(
+                    horizontal,
+                    (horizontal.len() as u32, 1),
+                    &self.edge,
+                    constant,
+                );
+                // Constant pixels above and below the image have been convolved horizontally too
+                let sum = horizontal.iter().sum::<f64>();
+                let constant = constant.map(|channel| channel * sum);
+
+                rows.convolve(vertical, (1, vertical.len() as u32), &self.edge, constant)
+            }
+        }
+    }
+}
+
+impl<P: TrueColor> Draw<P> for Convolution<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let channels = self.apply(&Channels::from_image(&image));
+        let width = image.width() as usize;
+
+        parallel::for_each_row_mut(&mut image.data, width, |y, row| {
+            let channels = &channels.data[y as usize * width..];
+            for (pixel, &channels) in row.iter_mut().zip(channels) {
+                *pixel = from_channels(channels);
+            }
+        });
+    }
+}
+
+/// Blurs an image with a gaussian kernel of the given standard deviation in pixels, which gives a
+/// smooth blur suitable for shadows and glows. The kernel reaches three standard deviations in
+/// every direction, and is applied as a separable [`Convolution`].
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::from_fn(9, 9, |x, y| L(if (x, y) == (4, 4) { 255 } else { 0 }));
+/// image.draw(&GaussianBlur::new(1.0));
+///
+/// assert!(image.pixel(4, 4).value() < 255);
+/// assert!(image.pixel(5, 4).value() > 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GaussianBlur<P: Pixel> {
+    /// The standard deviation of the gaussian kernel in pixels. Blurs with a standard deviation of
+    /// `0.0` or less do nothing.
+    pub sigma: f64,
+    /// How pixels outside of the image are read. Defaults to [`EdgeMode::Clamp`].
+    pub edge: EdgeMode<P>,
+}
+
+impl<P: Pixel> GaussianBlur<P> {
+    /// Creates a new gaussian blur with the given standard deviation in pixels.
+    #[must_use]
+    pub const fn new(sigma: f64) -> Self {
+        Self {
+            sigma,
+            edge: EdgeMode::Clamp,
+        }
+    }
+
+    /// Sets how pixels outside of the image are read.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge: EdgeMode<P>) -> Self {
+        self.edge = edge;
+        self
+    }
+}
+
+impl<P: TrueColor> Draw<P> for GaussianBlur<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, image: I) {
+        if self.sigma <= 0.0 {
+            return;
+        }
+
+        let radius = (self.sigma * 3.0).ceil() as i64;
+        let weights = (-radius..=radius)
+            .map(|x| (-((x * x) as f64) / (2.0 * self.sigma * self.sigma)).exp())
+            .collect::<Vec<_>>();
+        let sum = weights.iter().sum::<f64>();
+        let weights = weights
+            .iter()
+            .map(|weight| weight / sum)
+            .collect::<Vec<_>>();
+
+        Convolution::separable(weights.clone(), weights)
+            .with_edge_mode(self.edge)
+            .draw(image);
+    }
+}
+
+/// Blurs an image by averaging every pixel with the pixels within the given radius, which is
+/// faster to compute but less smooth than a [`GaussianBlur`]. This is applied as a separable
+/// [`Convolution`].
+#[derive(Clone, Debug)]
+pub struct BoxBlur<P: Pixel> {
+    /// The amount of pixels averaged in every direction. Blurs with a radius of `0` do nothing.
+    pub radius: u32,
+    /// How pixels outside of the image are read. Defaults to [`EdgeMode::Clamp`].
+    pub edge: EdgeMode<P>,
+}
+
+impl<P: Pixel> BoxBlur<P> {
+    /// Creates a new box blur with the given radius in pixels.
+    #[must_use]
+    pub const fn new(radius: u32) -> Self {
+        Self {
+            radius,
+            edge: EdgeMode::Clamp,
+        }
+    }
+
+    /// Sets how pixels outside of the image are read.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge: EdgeMode<P>) -> Self {
+        self.edge = edge;
+        self
+    }
+}
+
+impl<P: TrueColor> Draw<P> for BoxBlur<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, image: I) {
+        if self.radius == 0 {
+            return;
+        }
+
+        let size = self.radius as usize * 2 + 1;
+        let weights = vec![1.0 / size as f64; size];
+        Convolution::separable(weights.clone(), weights)
+            .with_edge_mode(self.edge)
+            .draw(image);
+    }
+}
+
+/// Sharpens an image by subtracting the pixels next to every pixel from it, which increases the
+/// contrast along edges.
+#[derive(Clone, Debug)]
+pub struct Sharpen<P: Pixel> {
+    /// How strongly to sharpen the image, where `0.0` does nothing and `1.0` is a typical amount.
+    pub amount: f64,
+    /// How pixels outside of the image are read. Defaults to [`EdgeMode::Clamp`].
+    pub edge: EdgeMode<P>,
+}
+
+impl<P: Pixel> Sharpen<P> {
+    /// Creates a new sharpening filter of the given amount.
+    #[must_use]
+    pub const fn new(amount: f64) -> Self {
+        Self {
+            amount,
+            edge: EdgeMode::Clamp,
+        }
+    }
+
+    /// Sets how pixels outside of the image are read.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge: EdgeMode<P>) -> Self {
+        self.edge = edge;
+        self
+    }
+}
+
+impl<P: TrueColor> Draw<P> for Sharpen<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, image: I) {
+        let a = self.amount;
+        #[rustfmt::skip]
+        let weights = [
+            0.0, -a, 0.0,
+            -a, 4.0f64.mul_add(a, 1.0), -a,
+            0.0, -a, 0.0,
+        ];
+
+        Convolution::new(3, 3, weights)
+            .with_edge_mode(self.edge)
+            .draw(image);
+    }
+}
+
+/// Detects edges in an image with the Sobel operator. Every color channel is replaced by the
+/// magnitude of its gradient, so edges become bright and flat areas become black. The alpha of
+/// every pixel is kept.
+///
+/// Convert the image to grayscale beforehand to detect edges regardless of their color.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::from_fn(8, 8, |x, _| L(if x < 4 { 0 } else { 255 }));
+/// image.draw(&Sobel::new());
+///
+/// assert_eq!(image.pixel(0, 0).value(), 0);
+/// assert_eq!(image.pixel(4, 4).value(), 255);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sobel<P: Pixel> {
+    /// How pixels outside of the image are read. Defaults to [`EdgeMode::Clamp`].
+    pub edge: EdgeMode<P>,
+}
+
+impl<P: Pixel> Default for Sobel<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Pixel> Sobel<P> {
+    /// Creates a new Sobel edge detector.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            edge: EdgeMode::Clamp,
+        }
+    }
+
+    /// Sets how pixels outside of the image are read.
+    #[must_use]
+    pub const fn with_edge_mode(mut self, edge: EdgeMode<P>) -> Self {
+        self.edge = edge;
+        self
+    }
+}
+
+impl<P: TrueColor> Draw<P> for Sobel<P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let channels = Channels::from_image(&image);
+        let horizontal = Convolution::separable([-1.0, 0.0, 1.0], [1.0, 2.0, 1.0])
+            .with_edge_mode(self.edge)
+            .apply(&channels);
+        let vertical = Convolution::separable([1.0, 2.0, 1.0], [-1.0, 0.0, 1.0])
+            .with_edge_mode(self.edge)
+            .apply(&channels);
+
+        let width = image.width() as usize;
+
+        parallel::for_each_row_mut(&mut image.data, width, |y, row| {
+            let start = y as usize * width;
+            let gradients = horizontal.data[start..].iter().zip(&vertical.data[start..]);
+
+            for (pixel, (gx, gy)) in row.iter_mut().zip(gradients) {
+                let (.., a) = pixel.as_rgba_tuple();
+                let magnitude = |i: usize| gx[i].hypot(gy[i]).round().min(255.0) as u8;
+
+                *pixel = P::from_rgba_tuple((magnitude(0), magnitude(1), magnitude(2), a));
+            }
+        });
+    }
+}
@@ -1,11 +1,16 @@
+#[cfg(feature = "std")]
 use crate::{
-    error::{Error::InvalidExtension, Result},
-    FrameIterator, Image, Pixel,
+    encode::{FrameContext, RowSink},
+    error::{
+        Error::{self, InvalidExtension},
+        Result,
+    },
+    ColorType, FrameIterator, Image, LazySequence, Pixel, Rgba16, Rgba32F,
 };
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
 use std::{
     ffi::OsStr,
-    fmt,
-    fmt::Display,
     io::{Read, Write},
     path::Path,
 };
@@ -16,9 +21,17 @@ use crate::encodings::gif;
 use crate::encodings::jpeg;
 #[cfg(feature = "png")]
 use crate::encodings::png;
+#[cfg(feature = "tiff")]
+use crate::encodings::tiff;
 #[cfg(feature = "webp")]
 use crate::encodings::webp;
-#[cfg(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp"))]
+#[cfg(any(
+    feature = "png",
+    feature = "gif",
+    feature = "jpeg",
+    feature = "tiff",
+    feature = "webp"
+))]
 use crate::{Decoder, Encoder};
 
 /// Represents the underlying encoding format of an image.
@@ -56,6 +69,10 @@ impl Default for ImageFormat {
 }
 
 impl ImageFormat {
+    /// The amount of bytes at the start of an image that [`Self::infer_encoding`] needs to infer
+    /// its format.
+    pub const SIGNATURE_LEN: usize = 12;
+
     /// Returns whether the extension is unknown.
     #[inline]
     #[must_use]
@@ -72,6 +89,7 @@ impl ImageFormat {
     ///
     /// # Errors
     /// * The extension is completely invalid and failed to be converted into a `&str`.
+    #[cfg(feature = "std")]
     pub fn from_extension(ext: impl AsRef<OsStr>) -> Result<Self> {
         let extension = ext.as_ref().to_str();
 
@@ -85,7 +103,7 @@ impl ImageFormat {
                 "jpg" | "jpeg" => Self::Jpeg,
                 "gif" => Self::Gif,
                 "bmp" => Self::Bmp,
-                "tiff" => Self::Tiff,
+                "tif" | "tiff" => Self::Tiff,
                 "webp" => Self::WebP,
                 _ => Self::Unknown,
             },
@@ -101,6 +119,7 @@ impl ImageFormat {
     ///
     /// # Errors
     /// * No extension can be resolved from the path.
+    #[cfg(feature = "std")]
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
         path.as_ref()
             .extension()
@@ -108,6 +127,21 @@ impl ImageFormat {
             .and_then(Self::from_extension)
     }
 
+    /// Reads the signature at the start of the given stream and infers the encoding format from it.
+    /// The bytes that were read are returned along with the format, since they are consumed from
+    /// the stream.
+    #[cfg(feature = "std")]
+    pub(crate) fn infer_from_reader(reader: &mut impl Read) -> Result<(Self, Vec<u8>)> {
+        let mut signature = Vec::with_capacity(Self::SIGNATURE_LEN);
+        // A single read may return fewer bytes than are available, e.g. for network streams
+        reader
+            .by_ref()
+            .take(Self::SIGNATURE_LEN as u64)
+            .read_to_end(&mut signature)?;
+
+        Ok((Self::infer_encoding(&signature), signature))
+    }
+
     /// Returns the format specified by the given MIME type.
     pub fn from_mime_type(mime: impl AsRef<str>) -> Self {
         let mime = mime.as_ref();
@@ -123,22 +157,38 @@ impl ImageFormat {
         }
     }
 
-    /// Infers the encoding format from the given data via a byte stream.
+    /// Infers the encoding format from the signature ("magic bytes") at the start of the given
+    /// data. Only the first [`Self::SIGNATURE_LEN`] bytes are considered, so this does not need
+    /// the whole image. Returns [`ImageFormat::Unknown`] if the data does not start with a known
+    /// signature.
+    ///
+    /// This does not depend on the features that are enabled, so it can also be used to detect
+    /// formats that cannot be decoded.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// assert_eq!(ImageFormat::infer_encoding(b"\x89PNG\r\n\x1a\n..."), ImageFormat::Png);
+    /// assert_eq!(ImageFormat::infer_encoding(b"GIF89a..."), ImageFormat::Gif);
+    /// assert_eq!(ImageFormat::infer_encoding(b"not an image"), ImageFormat::Unknown);
+    /// ```
     #[must_use]
     pub fn infer_encoding(sample: &[u8]) -> Self {
-        if sample.starts_with(b"\x89PNG\x0D\x0A\x1A\x0A") {
+        let sample = &sample[..sample.len().min(Self::SIGNATURE_LEN)];
+
+        if sample.starts_with(b"\x89PNG\r\n\x1a\n") {
             Self::Png
         } else if sample.starts_with(b"\xFF\xD8\xFF") {
             Self::Jpeg
-        } else if sample.starts_with(b"GIF") {
+        } else if sample.starts_with(b"GIF87a") || sample.starts_with(b"GIF89a") {
             Self::Gif
         } else if sample.starts_with(b"BM") {
             Self::Bmp
-        } else if sample.len() > 11 && &sample[8..12] == b"WEBP" {
+        } else if sample.starts_with(b"RIFF") && sample.get(8..12) == Some(b"WEBP") {
             Self::WebP
-        } else if (sample.starts_with(b"\x49\x49\x2A\0") || sample.starts_with(b"\x4D\x4D\0\x2A"))
-            && sample[8] != 0x43
-            && sample[9] != 0x52
+        } else if (sample.starts_with(b"II*\0") || sample.starts_with(b"MM\0*"))
+            // Canon raw images are TIFF files with a "CR" marker after the header
+            && sample.get(8..10) != Some(b"CR")
         {
             Self::Tiff
         } else {
@@ -146,18 +196,36 @@ impl ImageFormat {
         }
     }
 
-    /// Encodes the `Image` into raw bytes.
+    /// Encodes the `Image` into raw bytes. Images of custom pixel types, i.e. pixels with the
+    /// [`ColorType::Custom`] color type, are converted into RGBA before being encoded. So are
+    /// images of 16-bit and floating point pixels, unless they are encoded into PNG, which stores
+    /// floating point pixels as 16-bit RGBA.
     ///
     /// # Errors
     /// * An error occured while encoding.
     ///
     /// # Panics
     /// * No encoder implementation is found for this image encoding.
+    #[cfg(feature = "std")]
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "tiff",
+            feature = "webp"
+        )),
         allow(unused_variables, unreachable_code)
     )]
     pub fn run_encoder<P: Pixel>(&self, image: &Image<P>, dest: impl Write) -> Result<()> {
+        match self.conversion::<P>() {
+            Conversion::Rgba => {
+                return self.run_encoder(&convert_image(image, Pixel::as_rgba), dest)
+            }
+            Conversion::Rgba16 => return self.run_encoder(&convert_image(image, to_rgba16), dest),
+            Conversion::None => (),
+        }
+
         match self {
             #[cfg(feature = "png")]
             Self::Png => png::PngEncoder::encode_static(image, dest),
@@ -165,6 +233,8 @@ impl ImageFormat {
             Self::Jpeg => jpeg::JpegEncoder::encode_static(image, dest),
             #[cfg(feature = "gif")]
             Self::Gif => gif::GifEncoder::encode_static(image, dest),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffEncoder::encode_static(image, dest),
             #[cfg(feature = "webp")]
             Self::WebP => webp::WebPStaticEncoder::encode_static(image, dest),
             _ => panic!(
@@ -175,15 +245,23 @@ impl ImageFormat {
     }
 
     /// Encodes the `ImageSequence` into raw bytes. If the encoding does not supported image
-    /// sequences (or multi-frame images), it will only encode the first frame.
+    /// sequences (or multi-frame images), it will only encode the first frame. Frames are converted
+    /// like images are in [`Self::run_encoder`].
     ///
     /// # Errors
     /// * An error occured while encoding.
     ///
     /// # Panics
     /// * No encoder implementation is found for this image encoding.
+    #[cfg(feature = "std")]
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "tiff",
+            feature = "webp"
+        )),
         allow(unused_variables, unreachable_code)
     )]
     pub fn run_sequence_encoder<P: Pixel>(
@@ -191,6 +269,14 @@ impl ImageFormat {
         seq: &crate::ImageSequence<P>,
         dest: impl Write,
     ) -> Result<()> {
+        if self.conversion::<P>() != Conversion::None {
+            // Frames are converted lazily so that only one converted frame is held at a time
+            let frames = LazySequence::from_frames(seq.iter().cloned(), seq.len() as u32)
+                .with_loop_count(seq.loop_count());
+
+            return self.run_lazy_encoder(frames, dest);
+        }
+
         match self {
             #[cfg(feature = "png")]
             Self::Png => png::PngEncoder::encode_sequence(seq, dest),
@@ -198,6 +284,8 @@ impl ImageFormat {
             Self::Jpeg => jpeg::JpegEncoder::encode_sequence(seq, dest),
             #[cfg(feature = "gif")]
             Self::Gif => gif::GifEncoder::encode_sequence(seq, dest),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffEncoder::encode_sequence(seq, dest),
             #[cfg(feature = "webp")]
             Self::WebP => webp::WebPMuxEncoder::encode_sequence(seq, dest),
             _ => panic!(
@@ -207,15 +295,79 @@ impl ImageFormat {
         }
     }
 
-    /// Decodes the image data from into an image.
+    /// Lazily encodes the frames of the given frame iterator into raw bytes, only holding one
+    /// frame in memory at a time. If the encoding does not support image sequences (or multi-frame
+    /// images), it will only encode the first frame. Frames are converted like images are in
+    /// [`Self::run_encoder`].
     ///
     /// # Errors
-    /// * An error occured while decoding.
+    /// * The iterator has no frames.
+    /// * An error occured while decoding or encoding a frame.
     ///
     /// # Panics
+    /// * No encoder implementation is found for this image encoding.
+    #[cfg(feature = "std")]
+    #[cfg_attr(
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "tiff",
+            feature = "webp"
+        )),
+        allow(unused_variables, unreachable_code)
+    )]
+    pub fn run_lazy_encoder<P: Pixel>(
+        &self,
+        frames: impl FrameIterator<P>,
+        dest: impl Write,
+    ) -> Result<()> {
+        match self.conversion::<P>() {
+            Conversion::Rgba => {
+                let frames =
+                    LazySequence::new(frames).map_frames(|image| image.map_pixels(|p| p.as_rgba()));
+                return self.run_lazy_encoder(frames, dest);
+            }
+            Conversion::Rgba16 => {
+                let frames = LazySequence::new(frames)
+                    .map_frames(|image| image.map_pixels(|p| to_rgba16(&p)));
+                return self.run_lazy_encoder(frames, dest);
+            }
+            Conversion::None => (),
+        }
+
+        match self {
+            #[cfg(feature = "png")]
+            Self::Png => png::PngEncoder::encode_frames(frames, dest),
+            #[cfg(feature = "jpeg")]
+            Self::Jpeg => jpeg::JpegEncoder::encode_frames(frames, dest),
+            #[cfg(feature = "gif")]
+            Self::Gif => gif::GifEncoder::encode_frames(frames, dest),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffEncoder::encode_frames(frames, dest),
+            #[cfg(feature = "webp")]
+            Self::WebP => webp::WebPMuxEncoder::encode_frames(frames, dest),
+            _ => panic!(
+                "No encoder implementation is found for this image format. \
+                 Did you forget to enable the feature?"
+            ),
+        }
+    }
+
+    /// Decodes the image data from into an image.
+    ///
+    /// # Errors
+    /// * An error occured while decoding.
     /// * No decoder implementation is found for this image encoding.
+    #[cfg(feature = "std")]
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "tiff",
+            feature = "webp"
+        )),
         allow(unused_variables, unreachable_code)
     )]
     #[allow(clippy::needless_pass_by_value)] // would require a major refactor
@@ -227,48 +379,152 @@ impl ImageFormat {
             Self::Jpeg => jpeg::JpegDecoder::new().decode(stream),
             #[cfg(feature = "gif")]
             Self::Gif => gif::GifDecoder::new().decode(stream),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffDecoder::new().decode(stream),
             #[cfg(feature = "webp")]
             Self::WebP => webp::WebPDecoder::default().decode(stream),
-            _ => panic!(
-                "No encoder implementation is found for this image format. \
-                 Did you forget to enable the feature?"
-            ),
+            _ => Err(Error::UnsupportedFormat(*self)),
         }
     }
 
-    /// Decodes the image sequence data into an image sequence.
+    /// Decodes the image data row by row, passing each row of pixels to the given sink.
     ///
     /// # Errors
     /// * An error occured while decoding.
-    ///
-    /// # Panics
+    /// * The sink returned an error.
     /// * No decoder implementation is found for this image encoding.
+    ///
+    /// # See Also
+    /// * [`Decoder::decode_rows`][crate::Decoder::decode_rows] for more information.
+    #[cfg(feature = "std")]
     #[cfg_attr(
-        not(any(feature = "png", feature = "gif", feature = "jpeg", feature = "webp")),
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "tiff",
+            feature = "webp"
+        )),
         allow(unused_variables, unreachable_code)
     )]
-    #[allow(clippy::needless_pass_by_value)] // would require a major refactor
+    pub fn run_row_decoder<P: Pixel>(
+        &self,
+        stream: impl Read,
+        sink: &mut impl RowSink<P>,
+    ) -> Result<()> {
+        match self {
+            #[cfg(feature = "png")]
+            Self::Png => png::PngDecoder::new().decode_rows(stream, sink),
+            #[cfg(feature = "jpeg")]
+            Self::Jpeg => jpeg::JpegDecoder::new().decode_rows(stream, sink),
+            #[cfg(feature = "gif")]
+            Self::Gif => gif::GifDecoder::new().decode_rows(stream, sink),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => tiff::TiffDecoder::new().decode_rows(stream, sink),
+            #[cfg(feature = "webp")]
+            Self::WebP => webp::WebPDecoder::default().decode_rows(stream, sink),
+            _ => Err(Error::UnsupportedFormat(*self)),
+        }
+    }
+
+    /// Decodes the image sequence data into an image sequence.
+    ///
+    /// # Errors
+    /// * An error occured while decoding.
+    /// * No decoder implementation is found for this image encoding.
+    #[cfg(feature = "std")]
     pub fn run_sequence_decoder<'a, P: Pixel + 'a, R: Read + 'a>(
         &self,
         stream: R,
     ) -> Result<Box<dyn FrameIterator<P> + 'a>> {
-        Ok(match self {
+        Ok(Box::new(self.sequence_decoder(stream)?))
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(
+        not(any(
+            feature = "png",
+            feature = "gif",
+            feature = "jpeg",
+            feature = "tiff",
+            feature = "webp"
+        )),
+        allow(unused_variables, unreachable_code)
+    )]
+    #[allow(clippy::needless_pass_by_value)] // would require a major refactor
+    pub(crate) fn sequence_decoder<'a, P: Pixel + 'a, R: Read + 'a>(
+        &self,
+        stream: R,
+    ) -> Result<FrameContext<'a, P>> {
+        let frames: Box<dyn FrameIterator<P> + 'a> = match self {
             #[cfg(feature = "png")]
             Self::Png => Box::new(png::PngDecoder::new().decode_sequence(stream)?),
             #[cfg(feature = "jpeg")]
             Self::Jpeg => Box::new(jpeg::JpegDecoder::new().decode_sequence(stream)?),
             #[cfg(feature = "gif")]
             Self::Gif => Box::new(gif::GifDecoder::new().decode_sequence(stream)?),
+            #[cfg(feature = "tiff")]
+            Self::Tiff => Box::new(tiff::TiffDecoder::new().decode_sequence(stream)?),
             #[cfg(feature = "webp")]
             Self::WebP => Box::new(webp::WebPDecoder::default().decode_sequence(stream)?),
-            _ => panic!(
-                "No encoder implementation is found for this image format. \
-                 Did you forget to enable the feature?"
-            ),
-        })
+            _ => return Err(Error::UnsupportedFormat(*self)),
+        };
+
+        Ok(FrameContext::new(frames))
+    }
+}
+
+/// The pixel type images are converted into before they are encoded.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Conversion {
+    /// The pixels are encoded as they are.
+    None,
+    /// The pixels are converted into 8-bit RGBA.
+    Rgba,
+    /// The pixels are converted into 16-bit RGBA.
+    Rgba16,
+}
+
+#[cfg(feature = "std")]
+impl ImageFormat {
+    /// Returns the pixel type images of the given pixel type are converted into before they are
+    /// encoded with this encoding.
+    const fn conversion<P: Pixel>(self) -> Conversion {
+        // Encoders cannot store custom or floating point pixel types, and only PNG can store
+        // 16-bit pixels. Floating point pixels are stored as 16-bit pixels in PNG to keep as
+        // much of their precision as possible.
+        match (P::COLOR_TYPE, P::BIT_DEPTH, self) {
+            (ColorType::Custom(_), ..) => Conversion::Rgba,
+            (_, 32, Self::Png) => Conversion::Rgba16,
+            (_, 16, Self::Png) => Conversion::None,
+            (_, 16 | 32, _) => Conversion::Rgba,
+            _ => Conversion::None,
+        }
     }
 }
 
+/// Copies the given image with each of its pixels converted by the given function.
+#[cfg(feature = "std")]
+fn convert_image<P: Pixel, T: Pixel>(image: &Image<P>, f: impl FnMut(&P) -> T) -> Image<T> {
+    Image {
+        width: image.width,
+        height: image.height,
+        data: image.data.iter().map(f).collect(),
+        format: image.format,
+        overlay: image.overlay,
+        palette: None,
+        metadata: image.metadata.clone(),
+    }
+}
+
+/// Converts the given floating point pixel into a 16-bit RGBA pixel.
+#[cfg(feature = "std")]
+fn to_rgba16<P: Pixel>(pixel: &P) -> Rgba16 {
+    Rgba32F::from_raw_parts(P::COLOR_TYPE, P::BIT_DEPTH, pixel.as_bytes().as_ref())
+        .map_or_else(|_| pixel.as_rgba().into(), Into::into)
+}
+
 impl Display for ImageFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
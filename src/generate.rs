@@ -0,0 +1,255 @@
+//! Generators for test patterns and placeholder images, which are useful for tests, fixtures and
+//! mock content.
+
+use crate::{Image, Line, Pixel, TrueColor};
+use alloc::{format, vec::Vec};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The width of a glyph of the built-in label font, in pixels.
+const GLYPH_WIDTH: u32 = 5;
+/// The height of a glyph of the built-in label font, in pixels.
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Returns the rows of the glyph of the given character in the built-in label font, which only
+/// covers digits and `x`. Each row is stored in the lowest five bits, with the leftmost pixel in
+/// the highest bit.
+const fn glyph(c: char) -> [u8; GLYPH_HEIGHT as usize] {
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'x' => [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11],
+        _ => [0; GLYPH_HEIGHT as usize],
+    }
+}
+
+/// The style of a placeholder image created with [`Image::placeholder`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlaceholderStyle<P: Pixel> {
+    /// The color of the background.
+    pub background: P,
+    /// The color of the label and of the cross.
+    pub foreground: P,
+    /// Whether to label the placeholder with its dimensions, e.g. `300x200`. Defaults to `true`.
+    pub label: bool,
+    /// Whether to draw a border and a cross between the corners of the placeholder, like a
+    /// wireframe. Defaults to `false`.
+    pub cross: bool,
+}
+
+impl<P: Pixel> PlaceholderStyle<P> {
+    /// Creates a new placeholder style with the given background and foreground colors, which
+    /// labels the placeholder with its dimensions.
+    #[must_use]
+    pub const fn new(background: P, foreground: P) -> Self {
+        Self {
+            background,
+            foreground,
+            label: true,
+            cross: false,
+        }
+    }
+
+    /// Sets whether to label the placeholder with its dimensions.
+    #[must_use]
+    pub const fn with_label(mut self, label: bool) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Sets whether to draw a border and a cross between the corners of the placeholder.
+    #[must_use]
+    pub const fn with_cross(mut self, cross: bool) -> Self {
+        self.cross = cross;
+        self
+    }
+}
+
+impl<P: Pixel> Image<P> {
+    /// Creates a checkerboard pattern with the given dimensions, made of square cells with the
+    /// given size alternating between the two given colors. The top-left cell has the first
+    /// color.
+    ///
+    /// # Panics
+    /// * `width`, `height` or `cell_size` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::checkerboard(64, 64, 8, Rgb::white(), Rgb::black());
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::white());
+    /// assert_eq!(image.pixel(8, 0), &Rgb::black());
+    /// ```
+    #[must_use]
+    pub fn checkerboard(width: u32, height: u32, cell_size: u32, first: P, second: P) -> Self {
+        assert_ne!(cell_size, 0, "cell size must be non-zero");
+
+        Self::from_fn(width, height, |x, y| {
+            if (x / cell_size + y / cell_size) & 1 == 0 {
+                first
+            } else {
+                second
+            }
+        })
+    }
+
+    /// Creates a placeholder image with the given dimensions and style, which is by default
+    /// labelled with its dimensions, e.g. `300x200`. The label is drawn with a small built-in
+    /// bitmap font, scaled up to fit the image, and is omitted if the image is too small to fit
+    /// it.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let style = PlaceholderStyle::new(Rgb::new(204, 204, 204), Rgb::new(102, 102, 102))
+    ///     .with_cross(true);
+    /// let image = Image::placeholder(300, 200, &style);
+    /// ```
+    #[must_use]
+    pub fn placeholder(width: u32, height: u32, style: &PlaceholderStyle<P>) -> Self {
+        let mut image = Self::new(width, height, style.background);
+
+        if style.cross {
+            let (x2, y2) = (width - 1, height - 1);
+            for (x, y, pixel) in image.pixels_with_coords_mut() {
+                if x == 0 || y == 0 || x == x2 || y == y2 {
+                    *pixel = style.foreground;
+                }
+            }
+            image.draw(&Line::new((0, 0), (x2, y2), style.foreground));
+            image.draw(&Line::new((x2, 0), (0, y2), style.foreground));
+        }
+
+        if style.label {
+            let label = format!("{width}x{height}").chars().collect::<Vec<_>>();
+            let label_width = label.len() as u32 * (GLYPH_WIDTH + 1) - 1;
+            // Leave some room around the label, and only scale it by whole pixels
+            let scale = (width * 3 / 5 / label_width).min(height * 3 / 10 / GLYPH_HEIGHT);
+
+            if scale > 0 {
+                let x1 = (width - label_width * scale) / 2;
+                let y1 = (height - GLYPH_HEIGHT * scale) / 2;
+
+                for (i, &c) in (0..).zip(&label) {
+                    let gx = x1 + i * (GLYPH_WIDTH + 1) * scale;
+                    for (row, bits) in (0..).zip(glyph(c)) {
+                        for col in (0..GLYPH_WIDTH).filter(|col| bits >> (4 - col) & 1 == 1) {
+                            for (dx, dy) in
+                                (0..scale).flat_map(|dx| (0..scale).map(move |dy| (dx, dy)))
+                            {
+                                image.set_pixel(
+                                    gx + col * scale + dx,
+                                    y1 + row * scale + dy,
+                                    style.foreground,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        image
+    }
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Creates a pattern of seven vertical color bars with the given dimensions, like the
+    /// standard television test pattern. From left to right, the bars are gray, yellow, cyan,
+    /// green, magenta, red and blue, all at 75% intensity.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    #[must_use]
+    pub fn color_bars(width: u32, height: u32) -> Self {
+        const BARS: [(u8, u8, u8); 7] = [
+            (191, 191, 191),
+            (191, 191, 0),
+            (0, 191, 191),
+            (0, 191, 0),
+            (191, 0, 191),
+            (191, 0, 0),
+            (0, 0, 191),
+        ];
+
+        Self::from_fn(width, height, |x, _| {
+            let (r, g, b) = BARS[(u64::from(x) * 7 / u64::from(width)) as usize];
+            P::from_rgba_tuple((r, g, b, 255))
+        })
+    }
+
+    /// Creates a horizontal gradient with the given dimensions, which linearly interpolates from
+    /// the `start` color on the left edge to the `end` color on the right edge.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # See Also
+    /// * [`Self::vertical_gradient`] for a gradient from top to bottom.
+    /// * [`crate::LinearGradient`] for gradients with multiple colors and angles, with the
+    ///   `gradient` feature.
+    #[must_use]
+    pub fn horizontal_gradient(width: u32, height: u32, start: P, end: P) -> Self {
+        Self::from_fn(width, height, |x, _| lerp(start, end, x, width))
+    }
+
+    /// Creates a vertical gradient with the given dimensions, which linearly interpolates from
+    /// the `start` color on the top edge to the `end` color on the bottom edge.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # See Also
+    /// * [`Self::horizontal_gradient`] for a gradient from left to right.
+    #[must_use]
+    pub fn vertical_gradient(width: u32, height: u32, start: P, end: P) -> Self {
+        Self::from_fn(width, height, |_, y| lerp(start, end, y, height))
+    }
+}
+
+/// Linearly interpolates between the two given colors at the given position along the given
+/// length, where the last position is exactly `end`.
+fn lerp<P: TrueColor>(start: P, end: P, position: u32, length: u32) -> P {
+    let (r1, g1, b1, a1) = start.as_rgba_tuple();
+    let (r2, g2, b2, a2) = end.as_rgba_tuple();
+    let t = if length > 1 {
+        position as f32 / (length - 1) as f32
+    } else {
+        0.
+    };
+    let mix = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+
+    P::from_rgba_tuple((mix(r1, r2), mix(g1, g2), mix(b1, b2), mix(a1, a2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rgb;
+
+    #[test]
+    fn test_placeholder_label() {
+        let style = PlaceholderStyle::new(Rgb::white(), Rgb::black());
+        let image = Image::placeholder(120, 60, &style);
+
+        // The label `120x60` is 35 pixels wide, so it is drawn at twice its size
+        assert_eq!(image.pixel(0, 0), &Rgb::white());
+        assert!(image.data.iter().any(|&pixel| pixel == Rgb::black()));
+        assert!(Image::placeholder(8, 8, &style)
+            .data
+            .iter()
+            .all(|&pixel| pixel == Rgb::white()));
+    }
+}
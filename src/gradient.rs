@@ -3,10 +3,11 @@
 #![allow(clippy::cast_lossless, clippy::cast_precision_loss)]
 
 use crate::fill::{BoundingBox, Fill, IntoFill};
-use crate::{Pixel, Rgba};
+use crate::{Draw, Image, OverlayMode, Pixel, Rect, Rgba};
 
+use alloc::vec::Vec;
 pub use colorgrad::{BlendMode, Interpolation};
-use std::marker::PhantomData;
+use core::{marker::PhantomData, ops::DerefMut};
 
 /// Checks if the gradient is safe to call [`normalize_positions`].
 fn check_positions<P: Pixel>(colors: &[(P, f64)]) {
@@ -103,8 +104,40 @@ fn into_colorgrad<P: Pixel>(
     gradient
 }
 
+/// Determines how a gradient is rendered outside of the range `[0.0, 1.0]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum GradientRepeat {
+    /// Extend the colors at either end of the gradient. This is the default behavior.
+    #[default]
+    Pad,
+    /// Repeat the gradient from the start, i.e. `1.25` renders the same color as `0.25`.
+    Repeat,
+    /// Repeat the gradient, reversing its direction every other time, i.e. `1.25` renders the
+    /// same color as `0.75`.
+    Reflect,
+}
+
+impl GradientRepeat {
+    fn sample<P: Pixel>(self, gradient: &colorgrad::Gradient, t: f64) -> P {
+        let color = match self {
+            Self::Pad => gradient.at(t),
+            Self::Repeat => gradient.repeat_at(t),
+            Self::Reflect => gradient.reflect_at(t),
+        };
+
+        let (r, g, b, a) = color.to_linear_rgba_u8();
+        P::from_raw_parts(crate::ColorType::Rgba, 8, &[r, g, b, a]).unwrap()
+    }
+}
+
 /// A linear gradient.
 ///
+/// The direction of the gradient is either determined by its [`angle`][Self::angle], in which
+/// case the gradient spans the entire shape it is filling, or by explicit
+/// [`endpoints`][Self::endpoints].
+///
+/// Gradients can be used as fills for shapes, or be drawn directly over an entire image.
+///
 /// # Example
 /// ```
 /// # use ril::prelude::*;
@@ -122,7 +155,16 @@ fn into_colorgrad<P: Pixel>(
 pub struct LinearGradient<P: Pixel> {
     /// The angle of the gradient in radians. Defaults to 0 radians.  Angles outside of the range
     /// `[0, 2 * PI)` will be normalized.
+    ///
+    /// This is disregarded if [`Self::endpoints`] is set.
     pub angle: f64,
+    /// The start and end points of the gradient, represented as `(start, end)`. The color stop at
+    /// `0.0` is rendered at the start point and the color stop at `1.0` is rendered at the end
+    /// point. If this is `None` (the default), the gradient is determined by [`Self::angle`]
+    /// instead.
+    pub endpoints: Option<(GradientPosition, GradientPosition)>,
+    /// How the gradient is rendered beyond its endpoints. Defaults to [`GradientRepeat::Pad`].
+    pub repeat: GradientRepeat,
     /// A `Vec` of colors and their positions in the gradient, represented as `(color, position)`
     /// where `position` is a value in the range [0.0, 1.0].
     ///
@@ -145,21 +187,48 @@ pub struct LinearGradient<P: Pixel> {
     /// [`BlendMode::LinearRgb`]. If the gradient looks off or some colors are weirdly balanced,
     /// trying different blend modes here could help.
     pub blend_mode: BlendMode,
+    /// The overlay mode to use when drawing the gradient directly onto an image, or `None` to
+    /// inherit from the overlay mode of the image. This is ignored when the gradient is used as
+    /// the fill of a shape, in which case the overlay mode of the shape is used.
+    pub overlay: Option<OverlayMode>,
+    /// The region of the image the gradient spans when drawing it directly onto an image, or
+    /// `None` (the default) to span the entire image. This is ignored when the gradient is used as
+    /// the fill of a shape, in which case it spans the bounding box of the shape.
+    pub region: Option<Rect>,
 }
 
 impl<P: Pixel> Default for LinearGradient<P> {
     fn default() -> Self {
         Self {
             angle: 0.0,
+            endpoints: None,
+            repeat: GradientRepeat::Pad,
             colors: Vec::new(),
             interpolation: Interpolation::Linear,
             blend_mode: BlendMode::LinearRgb,
+            overlay: None,
+            region: None,
         }
     }
 }
 
 macro_rules! gradient_methods {
     () => {
+        /// Sets the overlay mode to use when drawing the gradient directly onto an image.
+        #[must_use]
+        pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+            self.overlay = Some(mode);
+            self
+        }
+
+        /// Sets the region of the image the gradient spans when drawing it directly onto an
+        /// image. Only the part of the region inside of the image is drawn.
+        #[must_use]
+        pub const fn with_region(mut self, region: Rect) -> Self {
+            self.region = Some(region);
+            self
+        }
+
         /// Sets the interpolation mode to use when rendering the gradient.
         #[must_use]
         pub const fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
@@ -286,6 +355,33 @@ impl<P: Pixel> LinearGradient<P> {
         self.with_angle(angle.to_radians())
     }
 
+    /// Sets the start and end points of the gradient. This overrides the angle of the gradient.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// // A horizontal gradient from red to blue over the middle half of the shape
+    /// let gradient = LinearGradient::new()
+    ///     .with_endpoints(
+    ///         GradientPosition::Relative(0.25, 0.5),
+    ///         GradientPosition::Relative(0.75, 0.5),
+    ///     )
+    ///     .with_color(Rgb::new(255, 0, 0))
+    ///     .with_color(Rgb::new(0, 0, 255));
+    /// ```
+    #[must_use]
+    pub const fn with_endpoints(mut self, start: GradientPosition, end: GradientPosition) -> Self {
+        self.endpoints = Some((start, end));
+        self
+    }
+
+    /// Sets how the gradient is rendered beyond its endpoints.
+    #[must_use]
+    pub const fn with_repeat(mut self, repeat: GradientRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
     gradient_methods!();
 }
 
@@ -294,7 +390,7 @@ impl<P: Pixel> IntoFill for LinearGradient<P> {
     type Fill = LinearGradientFill<Self::Pixel>;
 
     fn into_fill(mut self) -> Self::Fill {
-        self.angle = self.angle.rem_euclid(std::f64::consts::TAU);
+        self.angle = self.angle.rem_euclid(core::f64::consts::TAU);
         let (ty, tx) = self.angle.sin_cos();
         let clone_gradient = into_colorgrad(self.colors, self.interpolation, self.blend_mode);
 
@@ -307,6 +403,10 @@ impl<P: Pixel> IntoFill for LinearGradient<P> {
             height: 0.0,
             half_width: 0.0,
             half_height: 0.0,
+            endpoints: self.endpoints,
+            start: (0.0, 0.0),
+            delta: (0.0, 0.0),
+            repeat: self.repeat,
             // SAFETY: validated by `check_positions` and `normalize_positions`.
             gradient: unsafe { clone_gradient.build().unwrap_unchecked() },
             clone_gradient,
@@ -325,6 +425,11 @@ pub struct LinearGradientFill<P: Pixel> {
     height: f64,
     half_width: f64,
     half_height: f64,
+    endpoints: Option<(GradientPosition, GradientPosition)>,
+    start: (f64, f64),
+    /// The vector from the start point to the end point, divided by its squared length.
+    delta: (f64, f64),
+    repeat: GradientRepeat,
     pub(crate) gradient: colorgrad::Gradient,
     clone_gradient: colorgrad::CustomGradient,
     _marker: PhantomData<P>,
@@ -344,7 +449,10 @@ macro_rules! gradient_clone {
 // We can't derive `Clone` because `colorgrad::Gradient` doesn't implement `Clone`.
 impl<P: Pixel> Clone for LinearGradientFill<P> {
     fn clone(&self) -> Self {
-        gradient_clone!(self: x, y, tx, ty, width, height, half_width, half_height)
+        gradient_clone!(
+            self: x, y, tx, ty, width, height, half_width, half_height, endpoints, start, delta,
+            repeat
+        )
     }
 }
 
@@ -359,19 +467,39 @@ impl<P: Pixel> Fill<P> for LinearGradientFill<P> {
         self.height = height;
         self.half_width = width / 2.0;
         self.half_height = height / 2.0;
+
+        if let Some((start, end)) = self.endpoints {
+            let bounding_box = (x1, y1, x2, y2);
+            let (sx, sy) = start.resolve(bounding_box);
+            let (ex, ey) = end.resolve(bounding_box);
+            let (dx, dy) = (ex - sx, ey - sy);
+            let length_squared = dx.mul_add(dx, dy * dy);
+
+            self.start = (sx, sy);
+            self.delta = if length_squared > 0.0 {
+                (dx / length_squared, dy / length_squared)
+            } else {
+                (0.0, 0.0)
+            };
+        }
     }
 
     fn get_pixel(&self, x: u32, y: u32) -> P {
-        // Make the coordinates relative to the center of the bounding box.
-        let x = x as f64 - self.half_width - self.x;
-        let y = y as f64 - self.half_height - self.y;
-
-        // Calculate the dot product of the position vector and the angle vector.
-        let t = (x / self.width).mul_add(self.tx, (y / self.height) * self.ty);
+        let t = if self.endpoints.is_some() {
+            // Project the position vector onto the vector between the two endpoints.
+            let (dx, dy) = self.delta;
+            (x as f64 - self.start.0).mul_add(dx, (y as f64 - self.start.1) * dy)
+        } else {
+            // Make the coordinates relative to the center of the bounding box.
+            let x = x as f64 - self.half_width - self.x;
+            let y = y as f64 - self.half_height - self.y;
+
+            // Calculate the dot product of the position vector and the angle vector.
+            0.5 + (x / self.width).mul_add(self.tx, (y / self.height) * self.ty)
+        };
 
         // Get the color from the gradient.
-        let (r, g, b, a) = self.gradient.at(0.5 + t).to_linear_rgba_u8();
-        P::from_raw_parts(crate::ColorType::Rgba, 8, &[r, g, b, a]).unwrap()
+        self.repeat.sample(&self.gradient, t)
     }
 }
 
@@ -389,6 +517,17 @@ pub enum GradientPosition {
 impl GradientPosition {
     /// A shorthand for `Relative(0.5, 0.5)`.
     pub const CENTER: Self = Self::Relative(0.5, 0.5);
+
+    /// Resolves this position into absolute coordinates with respect to the given bounding box.
+    fn resolve(self, (x1, y1, x2, y2): BoundingBox<u32>) -> (f64, f64) {
+        match self {
+            Self::Relative(x, y) => {
+                let (x1, y1) = (x1 as f64, y1 as f64);
+                (x.mul_add(x2 as f64 - x1, x1), y.mul_add(y2 as f64 - y1, y1))
+            }
+            Self::Absolute(x, y) => (x as f64, y as f64),
+        }
+    }
 }
 
 impl Default for GradientPosition {
@@ -424,6 +563,9 @@ pub struct RadialGradient<P: Pixel> {
     pub position: GradientPosition,
     /// How the gradient should cover the bounding box.
     pub cover: RadialGradientCover,
+    /// How the gradient is rendered beyond the color stop at `1.0`. Defaults to
+    /// [`GradientRepeat::Pad`].
+    pub repeat: GradientRepeat,
     /// A `Vec` of colors and their positions in the gradient, represented as `(color, position)`
     /// where `position` is a value in the range [0.0, 1.0].
     ///
@@ -446,6 +588,14 @@ pub struct RadialGradient<P: Pixel> {
     /// [`BlendMode::LinearRgb`]. If the gradient looks off or some colors are weirdly balanced,
     /// trying different blend modes here could help.
     pub blend_mode: BlendMode,
+    /// The overlay mode to use when drawing the gradient directly onto an image, or `None` to
+    /// inherit from the overlay mode of the image. This is ignored when the gradient is used as
+    /// the fill of a shape, in which case the overlay mode of the shape is used.
+    pub overlay: Option<OverlayMode>,
+    /// The region of the image the gradient spans when drawing it directly onto an image, or
+    /// `None` (the default) to span the entire image. This is ignored when the gradient is used as
+    /// the fill of a shape, in which case it spans the bounding box of the shape.
+    pub region: Option<Rect>,
 }
 
 impl<P: Pixel> Default for RadialGradient<P> {
@@ -453,9 +603,12 @@ impl<P: Pixel> Default for RadialGradient<P> {
         Self {
             position: GradientPosition::CENTER,
             cover: RadialGradientCover::Stretch,
+            repeat: GradientRepeat::Pad,
             colors: Vec::new(),
             interpolation: Interpolation::Linear,
             blend_mode: BlendMode::LinearRgb,
+            overlay: None,
+            region: None,
         }
     }
 }
@@ -482,6 +635,13 @@ impl<P: Pixel> RadialGradient<P> {
         self
     }
 
+    /// Sets how the gradient is rendered beyond the color stop at `1.0`.
+    #[must_use]
+    pub const fn with_repeat(mut self, repeat: GradientRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
     gradient_methods!();
 }
 
@@ -503,6 +663,7 @@ impl<P: Pixel> IntoFill for RadialGradient<P> {
             ratio: 0.0,
             position: self.position,
             cover: self.cover,
+            repeat: self.repeat,
             // SAFETY: validated by `check_positions` and `normalize_positions`.
             gradient: unsafe { clone_gradient.build().unwrap_unchecked() },
             clone_gradient,
@@ -518,6 +679,7 @@ pub struct RadialGradientFill<P: Pixel> {
     ratio: f64,
     position: GradientPosition,
     cover: RadialGradientCover,
+    repeat: GradientRepeat,
     pub(crate) gradient: colorgrad::Gradient,
     clone_gradient: colorgrad::CustomGradient,
     _marker: PhantomData<P>,
@@ -526,7 +688,7 @@ pub struct RadialGradientFill<P: Pixel> {
 // We can't derive `Clone` because `colorgrad::Gradient` doesn't implement `Clone`.
 impl<P: Pixel> Clone for RadialGradientFill<P> {
     fn clone(&self) -> Self {
-        gradient_clone!(self: cx, cy, dist, ratio, position, cover)
+        gradient_clone!(self: cx, cy, dist, ratio, position, cover, repeat)
     }
 }
 
@@ -577,8 +739,7 @@ impl<P: Pixel> Fill<P> for RadialGradientFill<P> {
         };
 
         // Get the color from the gradient
-        let (r, g, b, a) = self.gradient.at(dist / self.dist).to_linear_rgba_u8();
-        P::from_raw_parts(crate::ColorType::Rgba, 8, &[r, g, b, a]).unwrap()
+        self.repeat.sample(&self.gradient, dist / self.dist)
     }
 }
 
@@ -613,6 +774,14 @@ pub struct ConicGradient<P: Pixel> {
     /// [`BlendMode::LinearRgb`]. If the gradient looks off or some colors are weirdly balanced,
     /// trying different blend modes here could help.
     pub blend_mode: BlendMode,
+    /// The overlay mode to use when drawing the gradient directly onto an image, or `None` to
+    /// inherit from the overlay mode of the image. This is ignored when the gradient is used as
+    /// the fill of a shape, in which case the overlay mode of the shape is used.
+    pub overlay: Option<OverlayMode>,
+    /// The region of the image the gradient spans when drawing it directly onto an image, or
+    /// `None` (the default) to span the entire image. This is ignored when the gradient is used as
+    /// the fill of a shape, in which case it spans the bounding box of the shape.
+    pub region: Option<Rect>,
 }
 
 impl<P: Pixel> Default for ConicGradient<P> {
@@ -623,6 +792,8 @@ impl<P: Pixel> Default for ConicGradient<P> {
             colors: Vec::new(),
             interpolation: Interpolation::Linear,
             blend_mode: BlendMode::LinearRgb,
+            overlay: None,
+            region: None,
         }
     }
 }
@@ -719,10 +890,42 @@ impl<P: Pixel> Fill<P> for ConicGradientFill<P> {
 
     fn get_pixel(&self, x: u32, y: u32) -> P {
         let mut angle = (x as f64 - self.cx).atan2(y as f64 - self.cy) - self.angle;
-        angle /= std::f64::consts::TAU;
+        angle /= core::f64::consts::TAU;
 
         // Get the color from the gradient
         let (r, g, b, a) = self.gradient.at(angle + 0.5).to_linear_rgba_u8();
         P::from_raw_parts(crate::ColorType::Rgba, 8, &[r, g, b, a]).unwrap()
     }
 }
+
+macro_rules! impl_gradient_draw {
+    ($($t:ident),+) => {
+        $(
+            impl<P: Pixel> Draw<P> for $t<P> {
+                /// Draws this gradient over its region of the image, or over the entire image if
+                /// it has none, respecting the overlay mode of the gradient, or of the image if it
+                /// is not set. To draw a gradient in the shape of something else, use it as the
+                /// fill of a shape instead.
+                fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+                    let image = &mut *image;
+                    let (width, height) = image.dimensions();
+                    let mode = self.overlay.unwrap_or_else(|| image.overlay_mode());
+
+                    let region = self.region.unwrap_or(Rect::new(0, 0, width, height));
+                    let (x1, y1, x2, y2) = region.bounds();
+                    let clamp = |bound: i64| bound.clamp(0, u32::MAX as i64) as u32;
+                    let (x1, y1, x2, y2) = (clamp(x1), clamp(y1), clamp(x2), clamp(y2));
+                    let fill = self.clone().into_fill().with_bounding_box((x1, y1, x2, y2));
+
+                    for y in y1..y2.min(height) {
+                        for x in x1..x2.min(width) {
+                            fill.plot(image, x, y, mode);
+                        }
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_gradient_draw!(LinearGradient, RadialGradient, ConicGradient);
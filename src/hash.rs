@@ -0,0 +1,204 @@
+//! Perceptual hashes of images, which are used to find duplicate or similar images.
+
+use crate::{Image, Pixel, L};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// A 64-bit perceptual hash of an image.
+///
+/// Unlike cryptographic hashes, perceptual hashes of similar looking images are similar: images
+/// that were resized, recompressed or slightly edited have hashes which only differ in a few bits.
+/// Use [`Self::distance`] to compare two hashes. Hashes are only comparable if they were computed
+/// with the same method.
+///
+/// Hashes are computed with [`Image::ahash`], [`Image::dhash`] or [`Image::phash`].
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let a = Image::<Rgb>::open("photo.jpg")?.phash();
+/// let b = Image::<Rgb>::open("photo_thumbnail.jpg")?.phash();
+///
+/// if a.distance(b) <= 10 {
+///     println!("the images are likely duplicates");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImageHash(pub u64);
+
+impl ImageHash {
+    /// Returns the Hamming distance between this hash and the given hash, which is the amount of
+    /// bits that differ between them. `0` means the hashes are identical, and `64` means every bit
+    /// is different.
+    #[must_use]
+    pub const fn distance(self, other: Self) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Collects the given bits into a hash, with the first bit being the most significant.
+    fn from_bits(bits: impl IntoIterator<Item = bool>) -> Self {
+        Self(
+            bits.into_iter()
+                .take(64)
+                .fold(0, |hash, bit| hash << 1 | u64::from(bit)),
+        )
+    }
+}
+
+impl Display for ImageHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl From<ImageHash> for u64 {
+    fn from(hash: ImageHash) -> Self {
+        hash.0
+    }
+}
+
+/// Computes the one-dimensional DCT-II of the given values.
+#[allow(clippy::cast_precision_loss)]
+fn dct(values: &[f64]) -> Vec<f64> {
+    let n = values.len() as f64;
+
+    (0..values.len())
+        .map(|k| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    value * (core::f64::consts::PI / n * (i as f64 + 0.5) * k as f64).cos()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+impl<P: Pixel + Into<L>> Image<P> {
+    /// Downsamples the luminance of this image to the given dimensions by averaging the pixels
+    /// covered by each cell, returning the cells in row-major order.
+    #[allow(clippy::cast_precision_loss)]
+    fn luminance_grid(&self, width: u32, height: u32) -> Vec<f64> {
+        let (src_width, src_height) = (u64::from(self.width()), u64::from(self.height()));
+        let span = |i: u32, cells: u32, size: u64| {
+            let start = u64::from(i) * size / u64::from(cells);
+            let end = (u64::from(i + 1) * size / u64::from(cells)).max(start + 1);
+            start as usize..end as usize
+        };
+
+        let mut grid = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            let rows = span(y, height, src_height);
+            for x in 0..width {
+                let columns = span(x, width, src_width);
+                let count = (rows.len() * columns.len()) as f64;
+                let sum = self
+                    .rows()
+                    .skip(rows.start)
+                    .take(rows.len())
+                    .flat_map(|row| &row[columns.clone()])
+                    .map(|pixel| f64::from(pixel.luminance()))
+                    .sum::<f64>();
+
+                grid.push(sum / count);
+            }
+        }
+
+        grid
+    }
+
+    /// Computes the average hash of this image, which downsamples the image to 8x8 and sets each
+    /// bit if the corresponding cell is brighter than the average.
+    ///
+    /// This is the fastest hash, but it is the least robust against adjustments such as gamma or
+    /// contrast changes.
+    ///
+    /// # See Also
+    /// * [`ImageHash`] for more information.
+    #[must_use]
+    pub fn ahash(&self) -> ImageHash {
+        let grid = self.luminance_grid(8, 8);
+        let mean = grid.iter().sum::<f64>() / 64.0;
+
+        ImageHash::from_bits(grid.into_iter().map(|value| value > mean))
+    }
+
+    /// Computes the difference hash of this image, which downsamples the image to 9x8 and sets
+    /// each bit if a cell is darker than the cell to its right.
+    ///
+    /// This hash tracks gradients rather than absolute brightness, which makes it robust against
+    /// brightness and contrast changes.
+    ///
+    /// # See Also
+    /// * [`ImageHash`] for more information.
+    #[must_use]
+    pub fn dhash(&self) -> ImageHash {
+        let grid = self.luminance_grid(9, 8);
+
+        ImageHash::from_bits(
+            grid.chunks_exact(9)
+                .flat_map(|row| row.windows(2).map(|pair| pair[0] < pair[1])),
+        )
+    }
+
+    /// Computes the perceptual hash of this image, which downsamples the image to 32x32, computes
+    /// its discrete cosine transform and sets each bit if the corresponding coefficient of the
+    /// lowest 8x8 frequencies is above their median.
+    ///
+    /// This is the slowest hash, but it is the most robust against resizing, compression and
+    /// small edits.
+    ///
+    /// # See Also
+    /// * [`ImageHash`] for more information.
+    #[must_use]
+    pub fn phash(&self) -> ImageHash {
+        const SIZE: usize = 32;
+
+        let grid = self.luminance_grid(SIZE as u32, SIZE as u32);
+        let rows = grid.chunks_exact(SIZE).map(dct).collect::<Vec<_>>();
+        // Only the 8 lowest frequencies of each column are needed
+        let columns = (0..8)
+            .map(|x| dct(&rows.iter().map(|row| row[x]).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let coefficients = (0..8)
+            .flat_map(|y| columns.iter().map(move |column| column[y]))
+            .collect::<Vec<_>>();
+
+        let mut sorted = coefficients.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median = (sorted[31] + sorted[32]) / 2.0;
+
+        ImageHash::from_bits(coefficients.into_iter().map(|value| value > median))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similar_images() {
+        let pattern = |x: u32, y: u32| {
+            let (dx, dy) = (x as i32 - 40, y as i32 - 24);
+            L((dx * dx + dy * dy / 2).min(255) as u8)
+        };
+        let small = Image::from_fn(64, 64, pattern);
+        let large = Image::from_fn(128, 128, |x, y| pattern(x / 2, y / 2));
+        let brighter = small
+            .clone()
+            .map_pixels(|L(value)| L(value.saturating_add(10)));
+        let other = Image::from_fn(64, 64, |x, y| L((x * 2 + y * 2) as u8));
+
+        for hash in [Image::ahash, Image::dhash, Image::phash] {
+            assert!(hash(&small).distance(hash(&large)) <= 4);
+            assert!(hash(&small).distance(hash(&brighter)) <= 4);
+            assert!(hash(&small).distance(hash(&other)) > 10);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Computes histograms of the channels of an image.
+
+use crate::{Image, Pixel, TrueColor, L};
+use alloc::{vec, vec::Vec};
+
+/// The distribution of values of each channel of an image.
+///
+/// Each channel has one bin per possible subpixel value, which is 256 bins for 8-bit pixel types
+/// and 65536 bins for 16-bit pixel types. Histograms are created with [`Image::histogram`] or
+/// [`Image::luminance_histogram`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    channels: Vec<Vec<u32>>,
+    pixel_count: u64,
+}
+
+impl Histogram {
+    /// Computes the histogram of every channel of the given image.
+    pub(crate) fn from_image<P: Pixel>(image: &Image<P>) -> Self {
+        assert!(
+            P::BIT_DEPTH <= 16,
+            "histograms can only be computed for pixel types of up to 16 bits per channel"
+        );
+        let bin_count = if P::BIT_DEPTH > 8 { 1 << 16 } else { 256 };
+        let bytes_per_subpixel = if P::BIT_DEPTH > 8 { 2 } else { 1 };
+
+        let mut channels = Vec::new();
+        for pixel in &image.data {
+            let bytes = pixel.as_bytes();
+            let subpixels = bytes.as_ref().chunks_exact(bytes_per_subpixel);
+            // Dynamic pixels may differ in their amount of channels
+            if subpixels.len() > channels.len() {
+                channels.resize_with(subpixels.len(), || vec![0; bin_count]);
+            }
+
+            for (bins, subpixel) in channels.iter_mut().zip(subpixels) {
+                let value = match *subpixel {
+                    [value] => value as usize,
+                    [a, b] => u16::from_be_bytes([a, b]) as usize,
+                    _ => unreachable!(),
+                };
+                bins[value] += 1;
+            }
+        }
+
+        Self {
+            channels,
+            pixel_count: image.data.len() as u64,
+        }
+    }
+
+    /// Computes the histogram of the red, green and blue channels of the given image, regardless
+    /// of how its pixel type stores its channels.
+    pub(crate) fn from_rgb<P: TrueColor>(image: &Image<P>) -> Self {
+        let mut channels = vec![vec![0; 256]; 3];
+        for pixel in &image.data {
+            let (r, g, b) = pixel.as_rgb_tuple();
+            for (bins, value) in channels.iter_mut().zip([r, g, b]) {
+                bins[value as usize] += 1;
+            }
+        }
+
+        Self {
+            channels,
+            pixel_count: image.data.len() as u64,
+        }
+    }
+
+    /// Computes the histogram of the luminance of the given image.
+    pub(crate) fn from_luminance<P: Pixel + Into<L>>(image: &Image<P>) -> Self {
+        let mut bins = vec![0; 256];
+        for pixel in &image.data {
+            bins[pixel.luminance() as usize] += 1;
+        }
+
+        Self {
+            channels: vec![bins],
+            pixel_count: image.data.len() as u64,
+        }
+    }
+
+    /// Returns the amount of channels in this histogram.
+    #[must_use]
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns the amount of bins of each channel, which is the amount of possible subpixel values.
+    #[must_use]
+    pub fn bin_count(&self) -> usize {
+        self.channels.first().map_or(0, Vec::len)
+    }
+
+    /// Returns the amount of pixels this histogram was computed from.
+    #[must_use]
+    pub const fn pixel_count(&self) -> u64 {
+        self.pixel_count
+    }
+
+    /// Returns the bins of the channel at the given index, where the value at index `i` is the
+    /// amount of pixels where the channel has the value `i`. Returns `None` if the channel does
+    /// not exist.
+    #[must_use]
+    pub fn channel(&self, index: usize) -> Option<&[u32]> {
+        self.channels.get(index).map(Vec::as_slice)
+    }
+
+    /// Returns an iterator over the bins of every channel.
+    pub fn channels(&self) -> impl Iterator<Item = &[u32]> {
+        self.channels.iter().map(Vec::as_slice)
+    }
+
+    /// Returns the cumulative distribution of the channel at the given index, where the value at
+    /// index `i` is the amount of pixels where the channel has a value of at most `i`.
+    ///
+    /// # Panics
+    /// * The channel does not exist.
+    #[must_use]
+    pub fn cumulative(&self, channel: usize) -> Vec<u64> {
+        self.channels[channel]
+            .iter()
+            .scan(0, |sum, &count| {
+                *sum += u64::from(count);
+                Some(*sum)
+            })
+            .collect()
+    }
+
+    /// Returns the smallest value of the channel at the given index such that at least the given
+    /// fraction (between `0.0` and `1.0`) of pixels have a value of at most that value.
+    ///
+    /// For example, `percentile(0, 0.5)` is the median of the first channel.
+    ///
+    /// # Panics
+    /// * The channel does not exist.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percentile(&self, channel: usize, fraction: f64) -> usize {
+        let threshold = (fraction.clamp(0.0, 1.0) * self.pixel_count as f64).max(1.0);
+        self.cumulative(channel)
+            .iter()
+            .position(|&count| count as f64 >= threshold)
+            .unwrap_or_else(|| self.bin_count().saturating_sub(1))
+    }
+
+    /// Returns the mean value of the channel at the given index, or `0.0` if the histogram is
+    /// empty.
+    ///
+    /// # Panics
+    /// * The channel does not exist.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean(&self, channel: usize) -> f64 {
+        let sum = self.channels[channel]
+            .iter()
+            .enumerate()
+            .map(|(value, &count)| value as f64 * f64::from(count))
+            .sum::<f64>();
+
+        sum / (self.pixel_count as f64).max(1.0)
+    }
+}
@@ -1,24 +1,42 @@
 #![allow(clippy::wildcard_imports)]
 
+#[cfg(feature = "std")]
+use crate::quantize::{Dither, QuantizeMethod, Quantizer};
 use crate::{
     draw::Draw,
     error::{Error, Result},
     format::ImageFormat,
+    histogram::Histogram,
+    metadata::{ImageMetadata, Orientation},
+    parallel,
     pixel::*,
-    Dynamic,
+    transform::Affine,
+    Dynamic, Scratch,
 };
 
 #[cfg(feature = "resize")]
 use crate::ResizeAlgorithm;
 
-use num_traits::{SaturatingAdd, SaturatingSub};
-use std::{
+use alloc::{borrow::ToOwned, boxed::Box, vec, vec::Vec};
+use core::{
     fmt::{self, Display},
-    fs::File,
-    io::{Read, Write},
     num::NonZeroU32,
-    path::Path,
 };
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use num_traits::{SaturatingAdd, SaturatingSub, ToPrimitive};
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+use std::{fs::File, path::Path};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
 
 /// The behavior to use when overlaying images on top of each other.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -28,6 +46,20 @@ pub enum OverlayMode {
     /// Merge the alpha values of overlay image with the alpha values of the base image.
     /// This is the default behavior.
     Merge,
+    /// Like [`Self::Merge`], but colors are blended in linear light instead of in gamma-encoded
+    /// sRGB. This is physically correct and avoids the dark, muddy edges that appear when
+    /// blending translucent colors in sRGB, at the cost of being slower.
+    ///
+    /// [`Rgba`] pixels are blended in linear light, as are the antialiased edges of shapes drawn
+    /// onto [`Rgb`] and [`L`] images. Float pixels are assumed to already be linear, and other
+    /// pixel types behave the same as with [`Self::Merge`].
+    MergeLinear,
+    /// Like [`Self::Merge`], but colors are first combined using the given [`BlendMode`], in the
+    /// same way design tools blend layers together.
+    ///
+    /// Pixel types which do not support blend modes, such as [`BitPixel`] and [`Cmyk`], behave
+    /// the same as with [`Self::Merge`].
+    Blend(BlendMode),
 }
 
 impl Default for OverlayMode {
@@ -40,16 +72,304 @@ impl Display for OverlayMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Merge => write!(f, "merge"),
+            Self::MergeLinear => write!(f, "merge_linear"),
             Self::Replace => write!(f, "replace"),
+            Self::Blend(mode) => write!(f, "{mode}"),
+        }
+    }
+}
+
+impl From<BlendMode> for OverlayMode {
+    fn from(mode: BlendMode) -> Self {
+        Self::Blend(mode)
+    }
+}
+
+/// A separable blend mode, which determines how the colors of an overlay pixel are combined with
+/// the colors of the base pixel beneath it. See [`OverlayMode::Blend`].
+///
+/// Blending follows the [W3C Compositing and Blending](https://www.w3.org/TR/compositing-1/)
+/// specification: the blended color is weighted by the alpha of both pixels and then composited
+/// in premultiplied space, so that translucent layers match what design tools produce.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let mut image = Image::new(1, 1, Rgb::new(255, 128, 0));
+/// image.overlay_pixel_with_mode(0, 0, Rgb::new(128, 128, 128), BlendMode::Multiply.into());
+///
+/// assert_eq!(image.pixel(0, 0), &Rgb::new(128, 64, 0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Multiplies the colors, which always results in a darker color.
+    Multiply,
+    /// Multiplies the complements of the colors, which always results in a lighter color.
+    Screen,
+    /// Multiplies dark base colors and screens light base colors, increasing contrast.
+    Overlay,
+    /// Selects the darker of the colors.
+    Darken,
+    /// Selects the lighter of the colors.
+    Lighten,
+    /// Adds the colors, clamping the result. This is also known as linear dodge.
+    Add,
+    /// Subtracts the overlay color from the base color, clamping the result.
+    Subtract,
+    /// Subtracts the darker of the colors from the lighter one.
+    Difference,
+    /// Darkens or lightens the base color depending on the overlay color, like a diffused
+    /// spotlight.
+    SoftLight,
+    /// Multiplies or screens the colors depending on the overlay color, like a harsh spotlight.
+    HardLight,
+}
+
+impl BlendMode {
+    /// Blends the given base and overlay color channels, both normalized to `0.0..=1.0`, without
+    /// taking alpha into account.
+    #[must_use]
+    pub fn apply(self, base: f32, overlay: f32) -> f32 {
+        let screen = |base: f32, overlay: f32| base.mul_add(-overlay, base + overlay);
+        let hard_light = |base: f32, overlay: f32| {
+            if overlay <= 0.5 {
+                base * 2. * overlay
+            } else {
+                screen(base, 2.0f32.mul_add(overlay, -1.))
+            }
+        };
+
+        match self {
+            Self::Multiply => base * overlay,
+            Self::Screen => screen(base, overlay),
+            Self::Overlay => hard_light(overlay, base),
+            Self::Darken => base.min(overlay),
+            Self::Lighten => base.max(overlay),
+            Self::Add => (base + overlay).min(1.),
+            Self::Subtract => (base - overlay).max(0.),
+            Self::Difference => (base - overlay).abs(),
+            Self::SoftLight => {
+                if overlay <= 0.5 {
+                    (1. - 2. * overlay).mul_add(-base * (1. - base), base)
+                } else {
+                    let d = if base <= 0.25 {
+                        (16.0f32.mul_add(base, -12.).mul_add(base, 4.)) * base
+                    } else {
+                        base.sqrt()
+                    };
+                    2.0f32.mul_add(overlay, -1.).mul_add(d - base, base)
+                }
+            }
+            Self::HardLight => hard_light(base, overlay),
+        }
+    }
+}
+
+impl Display for BlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Multiply => "multiply",
+            Self::Screen => "screen",
+            Self::Overlay => "overlay",
+            Self::Darken => "darken",
+            Self::Lighten => "lighten",
+            Self::Add => "add",
+            Self::Subtract => "subtract",
+            Self::Difference => "difference",
+            Self::SoftLight => "soft_light",
+            Self::HardLight => "hard_light",
+        })
+    }
+}
+
+/// Determines which pixels are considered part of the border when trimming an image with
+/// [`Image::trim`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrimMode<P: Pixel> {
+    /// Trim fully transparent pixels.
+    Transparent,
+    /// Trim pixels that are exactly the given color.
+    Color(P),
+    /// Trim pixels where every channel, including alpha, differs from the given color by at most
+    /// the given tolerance. This is useful for scans or lossy images, where borders are rarely
+    /// perfectly uniform.
+    Tolerance(P, u8),
+}
+
+impl<P: TrueColor> TrimMode<P> {
+    /// Returns whether the given pixel is part of the border.
+    fn is_border(&self, pixel: P) -> bool {
+        match *self {
+            Self::Transparent => pixel.as_rgba_tuple().3 == 0,
+            Self::Color(color) => pixel == color,
+            Self::Tolerance(color, tolerance) => {
+                let (r1, g1, b1, a1) = pixel.as_rgba_tuple();
+                let (r2, g2, b2, a2) = color.as_rgba_tuple();
+
+                [(r1, r2), (g1, g2), (b1, b2), (a1, a2)]
+                    .into_iter()
+                    .all(|(a, b)| a.abs_diff(b) <= tolerance)
+            }
+        }
+    }
+}
+
+/// Determines the canvas of an image rotated with [`Image::rotate_about`] or transformed with
+/// [`Image::transform`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RotationCanvas {
+    /// Keep the dimensions and position of the canvas, cutting off the parts of the rotated image
+    /// that fall outside of it. This is the default.
+    #[default]
+    Keep,
+    /// Grow or shrink the canvas to fit the whole rotated image.
+    Expand,
+    /// Use a canvas with the given dimensions, with its top-left pixel at the given position
+    /// relative to the top-left pixel of the original image.
+    Bounds {
+        /// The x-coordinate of the top-left pixel of the canvas.
+        x: i64,
+        /// The y-coordinate of the top-left pixel of the canvas.
+        y: i64,
+        /// The width of the canvas.
+        width: u32,
+        /// The height of the canvas.
+        height: u32,
+    },
+}
+
+/// Determines how pixels outside of the image are read by [`Image::map_with_neighborhood`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EdgeMode<P: Pixel> {
+    /// Read the nearest pixel on the edge of the image. This is the default.
+    #[default]
+    Clamp,
+    /// Mirror the image at its edges without repeating the edge pixels, so that the pixel one
+    /// past the left edge is the second pixel of the row.
+    Reflect,
+    /// Wrap around to the opposite edge of the image, as if it was tiled.
+    Wrap,
+    /// Read the given pixel.
+    Constant(P),
+}
+
+impl<P: Pixel> EdgeMode<P> {
+    /// Resolves the given coordinate along an axis of the given length, or returns `None` if the
+    /// constant pixel should be read instead.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn resolve(&self, coord: i64, length: u32) -> Option<u32> {
+        let length = i64::from(length);
+        if (0..length).contains(&coord) {
+            return Some(coord as u32);
+        }
+
+        let coord = match self {
+            Self::Clamp => coord.clamp(0, length - 1),
+            Self::Reflect if length == 1 => 0,
+            Self::Reflect => {
+                let period = 2 * (length - 1);
+                let coord = coord.rem_euclid(period);
+                if coord < length {
+                    coord
+                } else {
+                    period - coord
+                }
+            }
+            Self::Wrap => coord.rem_euclid(length),
+            Self::Constant(_) => return None,
+        };
+        Some(coord as u32)
+    }
+}
+
+/// A square window of pixels centered on a pixel of an image, passed to the function given to
+/// [`Image::map_with_neighborhood`]. Pixels are addressed by their offset from the center, and
+/// pixels outside of the image are read according to the [`EdgeMode`].
+#[derive(Clone)]
+pub struct Neighborhood<'a, P: Pixel> {
+    image: &'a Image<P>,
+    x: u32,
+    y: u32,
+    radius: u32,
+    edge: EdgeMode<P>,
+}
+
+impl<'a, P: Pixel> Neighborhood<'a, P> {
+    /// Returns the x coordinate of the center pixel.
+    #[must_use]
+    pub const fn x(&self) -> u32 {
+        self.x
+    }
+
+    /// Returns the y coordinate of the center pixel.
+    #[must_use]
+    pub const fn y(&self) -> u32 {
+        self.y
+    }
+
+    /// Returns the radius of the window, which spans `-radius..=radius` along each axis.
+    #[must_use]
+    pub const fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    /// Returns the width and height of the window, which is `2 * radius + 1`.
+    #[must_use]
+    pub const fn size(&self) -> u32 {
+        2 * self.radius + 1
+    }
+
+    /// Returns the center pixel.
+    #[must_use]
+    pub fn center(&self) -> P {
+        *self.image.pixel(self.x, self.y)
+    }
+
+    /// Returns the pixel at the given offset from the center.
+    ///
+    /// # Panics
+    /// * The offset is outside of the window.
+    #[must_use]
+    pub fn get(&self, dx: i32, dy: i32) -> P {
+        assert!(
+            dx.unsigned_abs() <= self.radius && dy.unsigned_abs() <= self.radius,
+            "offset ({dx}, {dy}) is outside of the window with radius {}",
+            self.radius
+        );
+
+        let x = self
+            .edge
+            .resolve(i64::from(self.x) + i64::from(dx), self.image.width());
+        let y = self
+            .edge
+            .resolve(i64::from(self.y) + i64::from(dy), self.image.height());
+        match (x, y, self.edge) {
+            (Some(x), Some(y), _) => *self.image.pixel(x, y),
+            (_, _, EdgeMode::Constant(pixel)) => pixel,
+            // Only constant edges don't resolve to a pixel in the image
+            _ => unreachable!(),
         }
     }
+
+    /// Returns an iterator over the offsets from the center and the pixels of the window, in
+    /// row-major order.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn pixels_with_offsets(&self) -> impl Iterator<Item = (i32, i32, P)> + '_ {
+        let radius = self.radius as i32;
+        (-radius..=radius)
+            .flat_map(move |dy| (-radius..=radius).map(move |dx| (dx, dy, self.get(dx, dy))))
+    }
+
+    /// Returns an iterator over the pixels of the window, in row-major order.
+    pub fn pixels(&self) -> impl Iterator<Item = P> + '_ {
+        self.pixels_with_offsets().map(|(_, _, pixel)| pixel)
+    }
 }
 
 /// A high-level image representation.
 ///
 /// This represents a static, single-frame image.
 /// See [`crate::ImageSequence`] for information on opening animated or multi-frame images.
-#[derive(Clone)]
 pub struct Image<P: Pixel> {
     pub(crate) width: NonZeroU32,
     pub(crate) height: NonZeroU32,
@@ -63,6 +383,276 @@ pub struct Image<P: Pixel> {
     pub(crate) format: ImageFormat,
     pub(crate) overlay: OverlayMode,
     pub(crate) palette: Option<Box<[P::Color]>>,
+    pub(crate) metadata: ImageMetadata,
+}
+
+impl<P: Pixel> Clone for Image<P> {
+    fn clone(&self) -> Self {
+        let palette = self.palette.clone();
+        // Paletted pixels reference the palette of the image they belong to, so they must be
+        // rebound to the cloned palette.
+        let data = match palette.as_deref().filter(|_| P::COLOR_TYPE.is_paletted()) {
+            Some(slice) => {
+                // SAFETY: references will be dropped when the clone is dropped, and the boxed
+                // palette is never moved out of or mutated while they are alive.
+                let slice = unsafe { core::slice::from_raw_parts(slice.as_ptr(), slice.len()) };
+
+                self.data
+                    .iter()
+                    // SAFETY: the pixels are bound to the palette of the clone, which outlives them
+                    .map(|&pixel| unsafe { rebind_pixel(Some(slice), pixel) })
+                    .collect()
+            }
+            None => self.data.clone(),
+        };
+
+        Self {
+            width: self.width,
+            height: self.height,
+            data,
+            format: self.format,
+            overlay: self.overlay,
+            palette,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+/// Rebinds the given pixel to the given palette, or returns it as-is if there is no palette.
+///
+/// Paletted pixels reference the palette of the image they belong to, so pixels copied from
+/// another image, even from a clone of the same image, must be rebound to the palette of the
+/// image they are written into.
+///
+/// # Panics
+/// * The palette index of the pixel is out of bounds of the palette.
+///
+/// # Safety
+/// The rebound pixel must not outlive the palette.
+pub(crate) unsafe fn rebind_pixel<P: Pixel>(palette: Option<&[P::Color]>, pixel: P) -> P {
+    match palette {
+        Some(palette) => assume_pixel_from_palette(palette, pixel.as_bytes().as_ref()[0])
+            .expect("could not rebind paletted pixels to the palette"),
+        None => pixel,
+    }
+}
+
+/// Copies the given pixels into `dest`, rebinding them to the given palette if there is one.
+///
+/// # Panics
+/// * The slices have different lengths.
+/// * The palette index of a pixel is out of bounds of the palette.
+///
+/// # Safety
+/// The rebound pixels must not outlive the palette.
+pub(crate) unsafe fn copy_rebound<P: Pixel>(
+    palette: Option<&[P::Color]>,
+    dest: &mut [P],
+    src: &[P],
+) {
+    match palette {
+        Some(_) => {
+            assert_eq!(dest.len(), src.len(), "slices must have the same length");
+            for (dest, &pixel) in dest.iter_mut().zip(src) {
+                *dest = rebind_pixel(palette, pixel);
+            }
+        }
+        None => dest.copy_from_slice(src),
+    }
+}
+
+/// Collects the dimensions, background, overlay mode and format of an image before creating it.
+///
+/// This is an alternative to calling [`Image::new`] followed by several `with_*` methods, each of
+/// which moves the image. Create one with [`Image::builder`] or [`ImageBuilder::new`].
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let image = Image::builder(256, 128)
+///     .with_background(Rgba::transparent())
+///     .with_overlay_mode(OverlayMode::Replace)
+///     .with_format(ImageFormat::Png)
+///     .build()?;
+///
+/// assert_eq!(image.dimensions(), (256, 128));
+/// assert_eq!(image.overlay_mode(), OverlayMode::Replace);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImageBuilder<P: Pixel> {
+    /// The width of the image.
+    pub width: u32,
+    /// The height of the image.
+    pub height: u32,
+    /// The pixel every pixel of the image is initially set to. Defaults to the default pixel of
+    /// the pixel type, which is usually black.
+    pub background: P,
+    /// The overlay mode of the image.
+    pub overlay: OverlayMode,
+    /// The encoding format of the image. This is nothing more but metadata about the image.
+    pub format: ImageFormat,
+}
+
+impl<P: Pixel> ImageBuilder<P> {
+    /// Creates a new builder for an image with the given dimensions.
+    #[must_use]
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            background: P::default(),
+            overlay: OverlayMode::default(),
+            format: ImageFormat::default(),
+        }
+    }
+
+    /// Sets the dimensions of the image.
+    #[must_use]
+    pub const fn with_dimensions(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets the pixel every pixel of the image is initially set to.
+    #[must_use]
+    pub const fn with_background(mut self, background: P) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Sets the overlay mode of the image.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, overlay: OverlayMode) -> Self {
+        self.overlay = overlay;
+        self
+    }
+
+    /// Sets the encoding format of the image. Note that when saving the image, an encoding format
+    /// will still have to be explicitly specified.
+    #[must_use]
+    pub const fn with_format(mut self, format: ImageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Creates the image.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The width or height is zero.
+    pub fn build(self) -> Result<Image<P>> {
+        let (width, height) = match (NonZeroU32::new(self.width), NonZeroU32::new(self.height)) {
+            (Some(width), Some(height)) => (width, height),
+            _ => return Err(Error::EmptyImageError),
+        };
+
+        Ok(Image {
+            width,
+            height,
+            data: vec![self.background; self.width as usize * self.height as usize],
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+            metadata: ImageMetadata::default(),
+        })
+    }
+}
+
+/// Creates a lookup table by evaluating the given function at every subpixel value, rounding and
+/// clamping its results.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn lut_from_fn(f: impl Fn(f64) -> f64) -> [u8; 256] {
+    let mut lut = [0; 256];
+    for (i, value) in lut.iter_mut().enumerate() {
+        *value = f(i as f64).round().clamp(0.0, 255.0) as u8;
+    }
+
+    lut
+}
+
+/// Approximates the color of a black body at the given temperature in Kelvin, using Tanner
+/// Helland's fit of the CIE color matching functions. Returns `[r, g, b]` in the range `0..=255`.
+fn kelvin_to_rgb(kelvin: f64) -> [f64; 3] {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_727_446 * (t - 60.0).powf(-0.133_204_759_2)
+    };
+    let g = if t <= 66.0 {
+        99.470_802_586_1_f64.mul_add(t.ln(), -161.119_568_166_1)
+    } else {
+        288.122_169_528_3 * (t - 60.0).powf(-0.075_514_849_2)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_731_223_1_f64.mul_add((t - 10.0).ln(), -305.044_792_730_7)
+    };
+
+    [r, g, b].map(|c| c.clamp(0.0, 255.0))
+}
+
+/// Creates a lookup table which linearly maps `low..=high` to `0..=255`, clamping values outside
+/// of that range. Returns the identity if the range is empty.
+#[allow(clippy::cast_precision_loss)]
+fn stretch_lut(low: usize, high: usize) -> [u8; 256] {
+    if high <= low {
+        return lut_from_fn(|value| value);
+    }
+
+    let scale = 255.0 / (high - low) as f64;
+    lut_from_fn(|value| (value - low as f64) * scale)
+}
+
+/// Creates a lookup table which adds the given delta to every value.
+pub(crate) fn brightness_lut(delta: i16) -> [u8; 256] {
+    lut_from_fn(|value| value + f64::from(delta))
+}
+
+/// Creates a lookup table which scales the distance of every value from the midpoint by the given
+/// factor.
+pub(crate) fn contrast_lut(factor: f64) -> [u8; 256] {
+    lut_from_fn(|value| (value - 127.5).mul_add(factor, 127.5))
+}
+
+/// Creates a lookup table which maps every normalized value `c` to `c^(1 / gamma)`.
+///
+/// # Panics
+/// * `gamma` is not positive.
+pub(crate) fn gamma_lut(gamma: f64) -> [u8; 256] {
+    assert!(gamma > 0.0, "gamma must be positive, got {gamma}");
+
+    lut_from_fn(|value| (value / 255.0).powf(gamma.recip()) * 255.0)
+}
+
+/// Creates a lookup table which inverts every value of at least the given threshold.
+pub(crate) fn solarize_lut(threshold: u8) -> [u8; 256] {
+    lut_from_fn(|value| {
+        if value >= f64::from(threshold) {
+            255.0 - value
+        } else {
+            value
+        }
+    })
+}
+
+/// Creates a lookup table which rounds every value to the nearest of the given amount of evenly
+/// spaced levels.
+///
+/// # Panics
+/// * `levels` is less than 2.
+pub(crate) fn posterize_lut(levels: u8) -> [u8; 256] {
+    assert!(levels >= 2, "levels must be at least 2, got {levels}");
+
+    let step = 255.0 / f64::from(levels - 1);
+    lut_from_fn(|value| (value / step).round() * step)
 }
 
 macro_rules! assert_nonzero {
@@ -109,9 +699,20 @@ impl<P: Pixel> Image<P> {
             format: ImageFormat::default(),
             overlay: OverlayMode::default(),
             palette: None,
+            metadata: ImageMetadata::default(),
         }
     }
 
+    /// Returns a builder for an image with the given width and height, which can be used to set
+    /// the background, overlay mode and format of the image before creating it.
+    ///
+    /// # See Also
+    /// * [`ImageBuilder`] for more information.
+    #[must_use]
+    pub fn builder(width: u32, height: u32) -> ImageBuilder<P> {
+        ImageBuilder::new(width, height)
+    }
+
     /// Creates a new image with the given width and height. The pixels are then resolved through
     /// then given callback function which takes two parameters (the x and y coordinates of each
     /// pixel) and returns a pixel.
@@ -170,7 +771,74 @@ impl<P: Pixel> Image<P> {
             format: ImageFormat::default(),
             overlay: OverlayMode::default(),
             palette: None,
+            metadata: ImageMetadata::default(),
+        }
+    }
+
+    /// Creates a new image with the given dimensions from an iterator of pixels in row-major
+    /// order. Unlike [`Self::from_pixels`], this does not panic if the amount of pixels does not
+    /// match the dimensions of the image.
+    ///
+    /// If the iterator knows its exact length, such as an [`ExactSizeIterator`], a mismatched
+    /// length is reported without consuming the iterator and the pixels are collected without
+    /// reallocating.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The width or height is zero.
+    /// * `IncompatibleImageData`: The iterator does not yield exactly `width * height` pixels.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::from_pixels_iter(2, 2, (0..4).map(|i| L(i * 64)))?;
+    /// assert_eq!(image.pixel(1, 1), &L(192));
+    ///
+    /// assert!(Image::from_pixels_iter(3, 3, (0..4).map(L)).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_pixels_iter(
+        width: u32,
+        height: u32,
+        pixels: impl IntoIterator<Item = P>,
+    ) -> Result<Self> {
+        let (w, h) = match (NonZeroU32::new(width), NonZeroU32::new(height)) {
+            (Some(w), Some(h)) => (w, h),
+            _ => return Err(Error::EmptyImageError),
+        };
+        let len = width as usize * height as usize;
+        let mismatch = |received| Error::IncompatibleImageData {
+            width,
+            height,
+            received,
+        };
+
+        let mut pixels = pixels.into_iter();
+        match pixels.size_hint() {
+            (lower, Some(upper)) if lower == upper && lower != len => return Err(mismatch(lower)),
+            (lower, _) if lower > len => return Err(mismatch(lower)),
+            _ => (),
+        }
+
+        let mut data = Vec::with_capacity(len);
+        data.extend(pixels.by_ref().take(len));
+        if data.len() < len {
+            return Err(mismatch(data.len()));
         }
+        if pixels.next().is_some() {
+            return Err(mismatch(len + 1 + pixels.count()));
+        }
+
+        Ok(Self {
+            width: w,
+            height: h,
+            data,
+            format: ImageFormat::default(),
+            overlay: OverlayMode::default(),
+            palette: None,
+            metadata: ImageMetadata::default(),
+        })
     }
 
     /// Creates a new image shaped with the given width and a 1-dimensional sequence of paletted
@@ -224,6 +892,7 @@ impl<P: Pixel> Image<P> {
             format: ImageFormat::default(),
             overlay: OverlayMode::default(),
             palette: Some(palette),
+            metadata: ImageMetadata::default(),
         };
 
         let palette = unsafe {
@@ -231,7 +900,7 @@ impl<P: Pixel> Image<P> {
                 .as_deref()
                 // SAFETY: references will be dropped when `Self` is dropped; we can guarantee that
                 // 'p is only valid for the lifetime of `Self`.
-                .map(|slice| std::slice::from_raw_parts(slice.as_ptr(), slice.len()))
+                .map(|slice| core::slice::from_raw_parts(slice.as_ptr(), slice.len()))
                 // SAFETY: declared palette as `Some` in struct declaration
                 .unwrap_unchecked()
         };
@@ -248,6 +917,9 @@ impl<P: Pixel> Image<P> {
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     ///
+    /// # See Also
+    /// * [`Self::open_with_format`] to decode a file with an explicit encoding.
+    ///
     /// # Example
     /// ```no_run
     /// # use ril::prelude::*;
@@ -257,6 +929,7 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "std")]
     pub fn from_reader(format: ImageFormat, bytes: impl Read) -> Result<Self> {
         format.run_decoder(bytes)
     }
@@ -267,9 +940,7 @@ impl<P: Pixel> Image<P> {
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     /// specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
     ///
     /// # Example
     /// ```no_run
@@ -280,13 +951,11 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "std")]
     pub fn from_reader_inferred(mut bytes: impl Read) -> Result<Self> {
-        let buf = &mut [0; 12];
-        let n = bytes.read(buf)?;
-
-        match ImageFormat::infer_encoding(buf) {
-            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat),
-            format => format.run_decoder((&buf[..n]).chain(bytes)),
+        match ImageFormat::infer_from_reader(&mut bytes)? {
+            (ImageFormat::Unknown, _) => Err(Error::UnknownEncodingFormat),
+            (format, signature) => format.run_decoder(Read::chain(signature.as_slice(), bytes)),
         }
     }
 
@@ -297,9 +966,7 @@ impl<P: Pixel> Image<P> {
     ///
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
     ///
     /// # Examples
     /// ```no_run,ignore
@@ -310,6 +977,7 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "std")]
     pub fn from_bytes(format: ImageFormat, bytes: impl AsRef<[u8]>) -> Result<Self> {
         format.run_decoder(bytes.as_ref())
     }
@@ -323,9 +991,7 @@ impl<P: Pixel> Image<P> {
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     /// specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
     ///
     /// # Examples
     /// ```no_run,ignore
@@ -336,6 +1002,7 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "std")]
     pub fn from_bytes_inferred(bytes: impl AsRef<[u8]>) -> Result<Self> {
         match ImageFormat::infer_encoding(bytes.as_ref()) {
             ImageFormat::Unknown => Err(Error::UnknownEncodingFormat),
@@ -345,17 +1012,18 @@ impl<P: Pixel> Image<P> {
 
     /// Opens a file from the given path and decodes it into an image.
     ///
-    /// The encoding of the image is automatically inferred. You can explicitly pass in an encoding
-    /// by using the [`from_reader`] method.
+    /// The encoding of the image is automatically inferred from the extension of the path, or from
+    /// the contents of the file if the path has no known extension. You can explicitly pass in an
+    /// encoding by using the [`Self::open_with_format`] method.
     ///
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     /// specifying it.
     /// * `IoError`: The file could not be opened.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
     ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// Each of these is wrapped in a `PathError` with the path of the file.
     ///
     /// # Example
     /// ```no_run
@@ -366,20 +1034,70 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        let buffer = &mut Vec::new();
-        let mut file = File::open(path.as_ref())?;
-        file.read_to_end(buffer)?;
-
-        let format = match ImageFormat::from_path(path)? {
-            ImageFormat::Unknown => match ImageFormat::infer_encoding(&buffer[0..12]) {
-                ImageFormat::Unknown => return Err(Error::UnknownEncodingFormat),
+        let path = path.as_ref();
+        let open = || {
+            let buffer = &mut Vec::new();
+            let mut file = File::open(path)?;
+            file.read_to_end(buffer)?;
+
+            // Paths without a known extension, such as temporary files, are inferred from their
+            // contents instead
+            let format = match ImageFormat::from_path(path).unwrap_or_default() {
+                ImageFormat::Unknown => match ImageFormat::infer_encoding(buffer) {
+                    ImageFormat::Unknown => return Err(Error::UnknownEncodingFormat),
+                    format => format,
+                },
                 format => format,
-            },
-            format => format,
+            };
+
+            format.run_decoder(buffer.as_slice())
+        };
+
+        open().map_err(|err| err.with_path(path))
+    }
+
+    /// Opens a file from the given path and decodes it into an image with the explicitly given
+    /// encoding, ignoring the extension of the path. This is useful for files with a wrong or
+    /// missing extension, such as temporary files or downloads.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The image could not be decoded, maybe it is corrupt or not encoded with
+    ///   the given encoding.
+    /// * `IoError`: The file could not be opened.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// Each of these is wrapped in a `PathError` with the path of the file.
+    ///
+    /// # See Also
+    /// * [`Self::from_reader`] to decode an image with an explicit encoding from any reader.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::open_with_format("/tmp/download", ImageFormat::Png)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn open_with_format(path: impl AsRef<Path>, format: ImageFormat) -> Result<Self> {
+        let path = path.as_ref();
+        let open = || {
+            let buffer = &mut Vec::new();
+            File::open(path)?.read_to_end(buffer)?;
+
+            format.run_decoder(buffer.as_slice())
         };
 
-        format.run_decoder(buffer.as_slice())
+        open().map_err(|err| err.with_path(path))
     }
 
     /// Encodes the image with the given encoding and writes it to the given write buffer.
@@ -400,6 +1118,7 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "std")]
     pub fn encode(&self, encoding: ImageFormat, dest: &mut impl Write) -> Result<()> {
         encoding.run_encoder(self, dest)
     }
@@ -422,9 +1141,16 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
     pub fn save(&self, encoding: ImageFormat, path: impl AsRef<Path>) -> Result<()> {
-        let mut file = File::create(path).map_err(Error::IoError)?;
-        self.encode(encoding, &mut file)
+        let path = path.as_ref();
+        File::create(path)
+            .map_err(Error::IoError)
+            .and_then(|mut file| self.encode(encoding, &mut file))
+            .map_err(|err| err.with_path(path))
     }
 
     /// Saves the image to the given path, inferring the encoding from the path/filename extension.
@@ -452,15 +1178,219 @@ impl<P: Pixel> Image<P> {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
     pub fn save_inferred(&self, path: impl AsRef<Path>) -> Result<()> {
-        let encoding = ImageFormat::from_path(path.as_ref())?;
+        let path = path.as_ref();
+        let encoding = ImageFormat::from_path(path).map_err(|err| err.with_path(path))?;
 
         match encoding {
-            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat),
+            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat.with_path(path)),
             _ => self.save(encoding, path),
         }
     }
 
+    /// Decodes an image with the explicitly given image encoding from the given asynchronous read
+    /// stream of bytes.
+    ///
+    /// The stream is read to its end without blocking the executor, which makes this suitable for
+    /// decoding uploads and other network streams. The image is then decoded on a blocking thread
+    /// of the Tokio runtime.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
+    /// * `IoError`: The stream could not be read.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// # Panics
+    /// * Not called within a Tokio runtime.
+    ///
+    /// # See Also
+    /// * [`Self::from_reader`] for the blocking version of this method.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # async fn run() -> ril::Result<()> {
+    /// let file = tokio::fs::File::open("image.png").await?;
+    /// let image = Image::<Rgb>::from_async_reader(ImageFormat::Png, file).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader(
+        format: ImageFormat,
+        mut bytes: impl AsyncRead + Unpin,
+    ) -> Result<Self>
+    where
+        P: Send + 'static,
+    {
+        let mut buffer = Vec::new();
+        bytes.read_to_end(&mut buffer).await?;
+
+        crate::encode::spawn_blocking(move || format.run_decoder(buffer.as_slice())).await
+    }
+
+    /// Decodes an image from the given asynchronous read stream of bytes, inferring its encoding.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
+    /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
+    ///   specifying it.
+    /// * `IoError`: The stream could not be read.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// # Panics
+    /// * Not called within a Tokio runtime.
+    ///
+    /// # See Also
+    /// * [`Self::from_async_reader`] for more information.
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader_inferred(mut bytes: impl AsyncRead + Unpin) -> Result<Self>
+    where
+        P: Send + 'static,
+    {
+        let mut buffer = Vec::new();
+        bytes.read_to_end(&mut buffer).await?;
+
+        crate::encode::spawn_blocking(move || Self::from_bytes_inferred(buffer)).await
+    }
+
+    /// Opens a file from the given path and decodes it into an image without blocking the
+    /// executor. The encoding of the image is inferred just like in [`Self::open`].
+    ///
+    /// The file is read asynchronously and then decoded on a blocking thread of the Tokio runtime.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
+    /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
+    ///   specifying it.
+    /// * `IoError`: The file could not be opened.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// Each of these is wrapped in a `PathError` with the path of the file.
+    ///
+    /// # Panics
+    /// * Not called within a Tokio runtime.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # async fn run() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::open_async("sample.png").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(
+        feature = "tokio",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub async fn open_async(path: impl AsRef<Path>) -> Result<Self>
+    where
+        P: Send + 'static,
+    {
+        let path = path.as_ref();
+        let buffer = tokio::fs::read(path)
+            .await
+            .map_err(|err| Error::IoError(err).with_path(path))?;
+
+        let format = match ImageFormat::from_path(path).unwrap_or_default() {
+            ImageFormat::Unknown => ImageFormat::infer_encoding(&buffer),
+            format => format,
+        };
+        match format {
+            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat.with_path(path)),
+            format => crate::encode::spawn_blocking(move || format.run_decoder(buffer.as_slice()))
+                .await
+                .map_err(|err| err.with_path(path)),
+        }
+    }
+
+    /// Encodes the image with the given encoding and writes it to the given asynchronous write
+    /// stream.
+    ///
+    /// The image is cloned and encoded on a blocking thread of the Tokio runtime, while the encoded
+    /// bytes are written to the stream without blocking the executor.
+    ///
+    /// # Errors
+    /// * An error occured during encoding.
+    /// * `IoError`: The stream could not be written to.
+    ///
+    /// # Panics
+    /// * No encoder implementation for the given encoding format.
+    /// * Not called within a Tokio runtime.
+    ///
+    /// # See Also
+    /// * [`Self::encode`] for the blocking version of this method.
+    /// * [`AsyncEncoder`](crate::encode::AsyncEncoder) to encode without cloning the image.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # async fn run() -> ril::Result<()> {
+    /// let image = Image::new(100, 100, Rgb::new(255, 0, 0));
+    /// let mut file = tokio::fs::File::create("out.png").await?;
+    /// image.encode_async(ImageFormat::Png, &mut file).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn encode_async(
+        &self,
+        encoding: ImageFormat,
+        dest: &mut (impl AsyncWrite + Unpin),
+    ) -> Result<()>
+    where
+        P: Send + 'static,
+    {
+        let image = self.clone();
+        crate::encode::encode_blocking(dest, move |mut writer| image.encode(encoding, &mut writer))
+            .await
+    }
+
+    /// Saves the image with the given encoding to the given path without blocking the executor.
+    ///
+    /// The image is encoded on a blocking thread just like in [`Self::encode_async`].
+    ///
+    /// # Errors
+    /// * An error occured during encoding.
+    /// * `IoError`: The file could not be written to.
+    ///
+    /// Each of these is wrapped in a `PathError` with the path of the file.
+    ///
+    /// # Panics
+    /// * No encoder implementation for the given encoding format.
+    /// * Not called within a Tokio runtime.
+    ///
+    /// # See Also
+    /// * [`Self::save`] for the blocking version of this method.
+    #[cfg(all(
+        feature = "tokio",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub async fn save_async(&self, encoding: ImageFormat, path: impl AsRef<Path>) -> Result<()>
+    where
+        P: Send + 'static,
+    {
+        let path = path.as_ref();
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|err| Error::IoError(err).with_path(path))?;
+
+        self.encode_async(encoding, &mut file)
+            .await
+            .map_err(|err| err.with_path(path))
+    }
+
+    pub(crate) const fn out_of_bounds(&self, region: (i64, i64, i64, i64)) -> Error {
+        Error::OutOfBounds {
+            region,
+            dimensions: self.dimensions(),
+        }
+    }
+
     #[inline]
     #[must_use]
     const fn resolve_coordinate(&self, x: u32, y: u32) -> usize {
@@ -502,6 +1432,40 @@ impl<P: Pixel> Image<P> {
         self.data.chunks_exact(self.width() as usize)
     }
 
+    /// Computes the histogram of every channel of this image, including the alpha channel.
+    /// Channels are ordered the same way as they are in [`Pixel::as_bytes`].
+    ///
+    /// For paletted images, this computes the histogram of the palette indices. Use
+    /// [`Self::flatten_palette`] first to compute the histogram of the colors instead.
+    ///
+    /// # Panics
+    /// * The pixel type has more than 16 bits per channel, such as floating-point pixel types.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(4, 1, |x, _| Rgb::new(x as u8, 0, 255));
+    /// let histogram = image.histogram();
+    ///
+    /// assert_eq!(histogram.channel_count(), 3);
+    /// assert_eq!(histogram.channel(0).unwrap()[..4], [1, 1, 1, 1]);
+    /// assert_eq!(histogram.channel(2).unwrap()[255], 4);
+    /// ```
+    #[must_use]
+    pub fn histogram(&self) -> Histogram {
+        Histogram::from_image(self)
+    }
+
+    /// Computes the histogram of the luminance of this image. The returned histogram has a single
+    /// channel with 256 bins.
+    #[must_use]
+    pub fn luminance_histogram(&self) -> Histogram
+    where
+        P: Into<L>,
+    {
+        Histogram::from_luminance(self)
+    }
+
     /// Returns the encoding format of the image. This is nothing more but metadata about the image.
     /// When saving the image, you will still have to explicitly specify the encoding format.
     #[inline]
@@ -524,6 +1488,30 @@ impl<P: Pixel> Image<P> {
         self
     }
 
+    /// Returns the metadata of the image, such as its text, color profile and resolution.
+    ///
+    /// # See Also
+    /// - [`ImageMetadata`] for more information on which metadata is preserved.
+    #[inline]
+    #[must_use]
+    pub const fn metadata(&self) -> &ImageMetadata {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the metadata of the image.
+    #[inline]
+    #[must_use]
+    pub fn metadata_mut(&mut self) -> &mut ImageMetadata {
+        &mut self.metadata
+    }
+
+    /// Returns the same image with its metadata set to the given value.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: ImageMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     /// Returns the dimensions of the image.
     #[inline]
     #[must_use]
@@ -546,6 +1534,12 @@ impl<P: Pixel> Image<P> {
     }
 
     /// Returns a reference of the pixel at the given coordinates.
+    ///
+    /// # Panics
+    /// * The coordinates are out of bounds.
+    ///
+    /// # See Also
+    /// * [`Self::get_pixel`] for a non-panicking version.
     #[inline]
     #[must_use]
     pub fn pixel(&self, x: u32, y: u32) -> &P {
@@ -560,6 +1554,12 @@ impl<P: Pixel> Image<P> {
     }
 
     /// Returns a mutable reference to the pixel at the given coordinates.
+    ///
+    /// # Panics
+    /// * The coordinates are out of bounds.
+    ///
+    /// # See Also
+    /// * [`Self::try_pixel_mut`] for a non-panicking version.
     #[inline]
     pub fn pixel_mut(&mut self, x: u32, y: u32) -> &mut P {
         let pos = self.resolve_coordinate(x, y);
@@ -567,7 +1567,42 @@ impl<P: Pixel> Image<P> {
         &mut self.data[pos]
     }
 
+    /// Returns a mutable reference to the pixel at the given coordinates, or an error if the
+    /// coordinates are out of bounds.
+    ///
+    /// # Errors
+    /// * `OutOfBounds`: The coordinates are out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(16, 16, Rgb::black());
+    /// *image.try_pixel_mut(4, 4).unwrap() = Rgb::white();
+    ///
+    /// assert!(image.try_pixel_mut(16, 4).is_err());
+    /// ```
+    #[inline]
+    pub fn try_pixel_mut(&mut self, x: u32, y: u32) -> Result<&mut P> {
+        if x >= self.width() || y >= self.height() {
+            return Err(self.out_of_bounds((
+                x.into(),
+                y.into(),
+                i64::from(x) + 1,
+                i64::from(y) + 1,
+            )));
+        }
+        let pos = self.resolve_coordinate(x, y);
+
+        Ok(&mut self.data[pos])
+    }
+
     /// Sets the pixel at the given coordinates to the given pixel.
+    ///
+    /// # Panics
+    /// * The coordinates are out of bounds.
+    ///
+    /// # See Also
+    /// * [`Self::try_pixel_mut`] for a non-panicking alternative.
     #[inline]
     pub fn set_pixel(&mut self, x: u32, y: u32, pixel: P) {
         let pos = self.resolve_coordinate(x, y);
@@ -641,6 +1676,7 @@ impl<P: Pixel> Image<P> {
     /// # let mut image = Image::<Rgb>::new(1, 1, Rgb::black());
     /// image.map_in_place(|_x, _y, pixel| *pixel = !*pixel);
     /// # }
+    /// ```
     pub fn invert(&mut self) {
         self.data.iter_mut().for_each(|p| *p = !*p);
     }
@@ -652,6 +1688,76 @@ impl<P: Pixel> Image<P> {
         self.map_pixels(|pixel| !pixel)
     }
 
+    /// Solarizes this image in place by inverting every color channel with a value of at least
+    /// the given threshold, mimicking the effect of overexposing photographic film. Alpha is left
+    /// untouched.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(1, 1, Rgb::new(100, 200, 128));
+    /// image.solarize(128);
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(100, 55, 127));
+    /// ```
+    pub fn solarize(&mut self, threshold: u8)
+    where
+        P: TrueColor,
+    {
+        self.map_rgb_with_luts(&[solarize_lut(threshold); 3]);
+    }
+
+    /// Takes this image and solarizes it. Useful for method chaining.
+    ///
+    /// See [`Self::solarize`] for more information.
+    #[must_use]
+    pub fn solarized(mut self, threshold: u8) -> Self
+    where
+        P: TrueColor,
+    {
+        self.solarize(threshold);
+        self
+    }
+
+    /// Posterizes this image in place by reducing every color channel to the given amount of
+    /// evenly spaced levels, rounding each value to the nearest level. Alpha is left untouched.
+    ///
+    /// # Panics
+    /// * `levels` is less than 2.
+    ///
+    /// # See Also
+    /// * [`Self::dither`] to reduce the amount of levels while hiding the resulting banding.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(1, 1, Rgb::new(30, 100, 200));
+    /// image.posterize(3);
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(0, 128, 255));
+    /// ```
+    pub fn posterize(&mut self, levels: u8)
+    where
+        P: TrueColor,
+    {
+        self.map_rgb_with_luts(&[posterize_lut(levels); 3]);
+    }
+
+    /// Takes this image and posterizes it. Useful for method chaining.
+    ///
+    /// See [`Self::posterize`] for more information.
+    ///
+    /// # Panics
+    /// * `levels` is less than 2.
+    #[must_use]
+    pub fn posterized(mut self, levels: u8) -> Self
+    where
+        P: TrueColor,
+    {
+        self.posterize(levels);
+        self
+    }
+
     /// Brightens the image by increasing all pixels by the specified amount of subpixels in place.
     /// See [`Self::darken`] to darken the image, since this usually does not take any negative
     /// values.
@@ -720,71 +1826,241 @@ impl<P: Pixel> Image<P> {
         self.map_pixels(|pixel| pixel.map_subpixels(|value| value.saturating_sub(&amount), |a| a))
     }
 
-    #[allow(clippy::cast_lossless)]
-    fn prepare_hue_matrix(degrees: i32) -> (f64, f64, f64, f64, f64, f64, f64, f64, f64) {
-        let degrees = (degrees % 360) as f64;
-        let radians = degrees.to_radians();
-        let sinv = radians.sin();
-        let cosv = radians.cos();
-
-        (
-            sinv.mul_add(-0.213, cosv.mul_add(0.787, 0.213)),
-            sinv.mul_add(-0.715, cosv.mul_add(-0.715, 0.715)),
-            sinv.mul_add(0.928, cosv.mul_add(-0.072, 0.072)),
-            sinv.mul_add(0.143, cosv.mul_add(-0.213, 0.213)),
-            sinv.mul_add(0.140, cosv.mul_add(0.285, 0.715)),
-            sinv.mul_add(-0.283, cosv.mul_add(-0.072, 0.072)),
-            sinv.mul_add(-0.787, cosv.mul_add(-0.213, 0.213)),
-            sinv.mul_add(0.715, cosv.mul_add(-0.715, 0.715)),
-            sinv.mul_add(0.072, cosv.mul_add(0.928, 0.072)),
-        )
-    }
-
-    /// Hue rotates the image by the specified amount of degrees in place.
+    /// Adjusts the brightness of this image in place by adding the given delta to every color
+    /// channel, clamping the result. Negative values darken the image. Unlike [`Self::brighten`],
+    /// this works for any true color pixel type and accepts negative values.
     ///
-    /// The hue is a standard angle degree, that is a value between 0 and 360, although values
-    /// below and above will be wrapped using the modulo operator.
+    /// For anything with alpha, alpha is not adjusted.
     ///
-    /// For anything with alpha, alpha is not rotated.
-    pub fn hue_rotate(&mut self, degrees: i32)
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(1, 1, Rgba::new(100, 200, 50, 128));
+    /// image.adjust_brightness(80);
+    /// assert_eq!(image.pixel(0, 0), &Rgba::new(180, 255, 130, 128));
+    /// ```
+    pub fn adjust_brightness(&mut self, delta: i16)
     where
         P: TrueColor,
     {
-        let mat = Self::prepare_hue_matrix(degrees);
-
-        self.data.iter_mut().for_each(|p| {
-            let (r, g, b, a) = p.as_rgba_tuple();
-            let (r, g, b) = (f64::from(r), f64::from(g), f64::from(b));
-
-            *p = P::from_rgba_tuple((
-                mat.2.mul_add(b, mat.0.mul_add(r, mat.1 * g)) as u8,
-                mat.5.mul_add(b, mat.3.mul_add(r, mat.4 * g)) as u8,
-                mat.8.mul_add(b, mat.6.mul_add(r, mat.7 * g)) as u8,
-                a,
-            ));
-        });
+        self.map_rgb_with_luts(&[brightness_lut(delta); 3]);
     }
 
-    /// Takes this image and hue rotates it by the specified amount of degrees.
-    /// Useful for method chaining.
+    /// Takes this image and adjusts its brightness by adding the given delta to every color
+    /// channel. Useful for method chaining.
     ///
-    /// See [`Self::hue_rotate`] for more information.
+    /// See [`Self::adjust_brightness`] for more information.
     #[must_use]
-    pub fn hue_rotated(mut self, degrees: i32) -> Self
+    pub fn brightness_adjusted(mut self, delta: i16) -> Self
     where
         P: TrueColor,
     {
-        self.hue_rotate(degrees);
+        self.adjust_brightness(delta);
         self
     }
 
-    /// Returns the image replaced with the given data. It is up to you to make sure
-    /// the data is the correct size.
+    /// Adjusts the contrast of this image in place by scaling the distance of every color channel
+    /// from the midpoint by the given factor. Factors above `1.0` increase contrast, factors
+    /// between `0.0` and `1.0` decrease it, and `0.0` results in a flat gray image.
     ///
-    /// The function should take the current image data and return the new data.
+    /// For anything with alpha, alpha is not adjusted.
+    pub fn adjust_contrast(&mut self, factor: f64)
+    where
+        P: TrueColor,
+    {
+        self.map_rgb_with_luts(&[contrast_lut(factor); 3]);
+    }
+
+    /// Takes this image and adjusts its contrast by the given factor. Useful for method chaining.
     ///
-    /// # Note
-    /// This will *not* work for paletted images, nor will it work for conversion to paletted
+    /// See [`Self::adjust_contrast`] for more information.
+    #[must_use]
+    pub fn contrast_adjusted(mut self, factor: f64) -> Self
+    where
+        P: TrueColor,
+    {
+        self.adjust_contrast(factor);
+        self
+    }
+
+    /// Applies gamma correction to this image in place, mapping every normalized color channel
+    /// `c` to `c^(1 / gamma)`. Values above `1.0` brighten the midtones of the image, and values
+    /// between `0.0` and `1.0` darken them. Pure black and pure white are left unchanged.
+    ///
+    /// For anything with alpha, alpha is not adjusted.
+    ///
+    /// # Panics
+    /// * `gamma` is not positive.
+    pub fn adjust_gamma(&mut self, gamma: f64)
+    where
+        P: TrueColor,
+    {
+        self.map_rgb_with_luts(&[gamma_lut(gamma); 3]);
+    }
+
+    /// Takes this image and applies gamma correction to it. Useful for method chaining.
+    ///
+    /// See [`Self::adjust_gamma`] for more information.
+    ///
+    /// # Panics
+    /// * `gamma` is not positive.
+    #[must_use]
+    pub fn gamma_adjusted(mut self, gamma: f64) -> Self
+    where
+        P: TrueColor,
+    {
+        self.adjust_gamma(gamma);
+        self
+    }
+
+    /// Adjusts the color temperature of this image in place, tinting it as if it was lit by a
+    /// light source of the given color temperature in Kelvin instead of daylight (6500 K).
+    ///
+    /// Temperatures below 6500 K warm the image up (towards orange), and temperatures above cool
+    /// it down (towards blue). Temperatures are clamped to the range `1000.0..=40000.0`.
+    ///
+    /// For anything with alpha, alpha is not adjusted.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(1, 1, Rgb::new(128, 128, 128));
+    /// image.adjust_temperature(4000.0);
+    ///
+    /// let pixel = image.pixel(0, 0);
+    /// assert!(pixel.r > pixel.g && pixel.g > pixel.b);
+    /// ```
+    pub fn adjust_temperature(&mut self, kelvin: f64)
+    where
+        P: TrueColor,
+    {
+        let target = kelvin_to_rgb(kelvin);
+        let daylight = kelvin_to_rgb(6500.0);
+        let luts = [0, 1, 2].map(|c| {
+            let scale = target[c] / daylight[c];
+            lut_from_fn(|value| value * scale)
+        });
+
+        self.map_rgb_with_luts(&luts);
+    }
+
+    /// Takes this image and adjusts its color temperature. Useful for method chaining.
+    ///
+    /// See [`Self::adjust_temperature`] for more information.
+    #[must_use]
+    pub fn temperature_adjusted(mut self, kelvin: f64) -> Self
+    where
+        P: TrueColor,
+    {
+        self.adjust_temperature(kelvin);
+        self
+    }
+
+    /// Automatically corrects the white balance of this image in place using the gray world
+    /// assumption, which scales each color channel so that the average color of the image
+    /// becomes a neutral gray.
+    ///
+    /// This works well for photos with a variety of colors, but will wash out images that are
+    /// dominated by a single color.
+    ///
+    /// For anything with alpha, alpha is not adjusted.
+    pub fn auto_white_balance(&mut self)
+    where
+        P: TrueColor,
+    {
+        let histogram = Histogram::from_rgb(self);
+        let means = [0, 1, 2].map(|c| histogram.mean(c));
+        let gray = means.iter().sum::<f64>() / 3.0;
+
+        let luts = means.map(|mean| {
+            if mean <= 0.0 {
+                lut_from_fn(|value| value)
+            } else {
+                let scale = gray / mean;
+                lut_from_fn(|value| value * scale)
+            }
+        });
+
+        self.map_rgb_with_luts(&luts);
+    }
+
+    /// Takes this image and automatically corrects its white balance. Useful for method chaining.
+    ///
+    /// See [`Self::auto_white_balance`] for more information.
+    #[must_use]
+    pub fn auto_white_balanced(mut self) -> Self
+    where
+        P: TrueColor,
+    {
+        self.auto_white_balance();
+        self
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn prepare_hue_matrix(degrees: i32) -> (f64, f64, f64, f64, f64, f64, f64, f64, f64) {
+        let degrees = (degrees % 360) as f64;
+        let radians = degrees.to_radians();
+        let sinv = radians.sin();
+        let cosv = radians.cos();
+
+        (
+            sinv.mul_add(-0.213, cosv.mul_add(0.787, 0.213)),
+            sinv.mul_add(-0.715, cosv.mul_add(-0.715, 0.715)),
+            sinv.mul_add(0.928, cosv.mul_add(-0.072, 0.072)),
+            sinv.mul_add(0.143, cosv.mul_add(-0.213, 0.213)),
+            sinv.mul_add(0.140, cosv.mul_add(0.285, 0.715)),
+            sinv.mul_add(-0.283, cosv.mul_add(-0.072, 0.072)),
+            sinv.mul_add(-0.787, cosv.mul_add(-0.213, 0.213)),
+            sinv.mul_add(0.715, cosv.mul_add(-0.715, 0.715)),
+            sinv.mul_add(0.072, cosv.mul_add(0.928, 0.072)),
+        )
+    }
+
+    /// Hue rotates the image by the specified amount of degrees in place.
+    ///
+    /// The hue is a standard angle degree, that is a value between 0 and 360, although values
+    /// below and above will be wrapped using the modulo operator.
+    ///
+    /// For anything with alpha, alpha is not rotated.
+    pub fn hue_rotate(&mut self, degrees: i32)
+    where
+        P: TrueColor,
+    {
+        let mat = Self::prepare_hue_matrix(degrees);
+
+        self.data.iter_mut().for_each(|p| {
+            let (r, g, b, a) = p.as_rgba_tuple();
+            let (r, g, b) = (f64::from(r), f64::from(g), f64::from(b));
+
+            *p = P::from_rgba_tuple((
+                mat.2.mul_add(b, mat.0.mul_add(r, mat.1 * g)) as u8,
+                mat.5.mul_add(b, mat.3.mul_add(r, mat.4 * g)) as u8,
+                mat.8.mul_add(b, mat.6.mul_add(r, mat.7 * g)) as u8,
+                a,
+            ));
+        });
+    }
+
+    /// Takes this image and hue rotates it by the specified amount of degrees.
+    /// Useful for method chaining.
+    ///
+    /// See [`Self::hue_rotate`] for more information.
+    #[must_use]
+    pub fn hue_rotated(mut self, degrees: i32) -> Self
+    where
+        P: TrueColor,
+    {
+        self.hue_rotate(degrees);
+        self
+    }
+
+    /// Returns the image replaced with the given data. It is up to you to make sure
+    /// the data is the correct size.
+    ///
+    /// The function should take the current image data and return the new data.
+    ///
+    /// # Note
+    /// This will *not* work for paletted images, nor will it work for conversion to paletted
     /// images. For conversion from paletted images, see the [`Self::flatten`] method to flatten
     /// the palette fist. For conversion to paletted images, try quantizing the image.
     pub fn map_data<T: Pixel>(self, f: impl FnOnce(Vec<P>) -> Vec<T>) -> Image<T> {
@@ -795,6 +2071,7 @@ impl<P: Pixel> Image<P> {
             format: self.format,
             overlay: self.overlay,
             palette: None,
+            metadata: self.metadata,
         }
     }
 
@@ -856,6 +2133,63 @@ impl<P: Pixel> Image<P> {
             format: self.format,
             overlay: self.overlay,
             palette: None,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Returns a new image where each pixel is computed by the given function from a square window
+    /// of pixels around the corresponding pixel of this image, which spans `radius` pixels in
+    /// every direction. Pixels outside of the image are read according to the given
+    /// [`EdgeMode`].
+    ///
+    /// This allows writing custom local filters, such as median filters or edge detectors,
+    /// without managing a second image or checking bounds manually.
+    ///
+    /// # Panics
+    /// * `radius` is greater than `i32::MAX / 2`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(5, 5, |x, y| L(if (x, y) == (2, 2) { 255 } else { 0 }));
+    ///
+    /// // A 3x3 maximum filter, which grows bright regions by a pixel
+    /// let dilated = image.map_with_neighborhood(1, EdgeMode::Clamp, |window| {
+    ///     window.pixels().max_by_key(|pixel| pixel.value()).unwrap()
+    /// });
+    ///
+    /// assert_eq!(dilated.pixel(1, 1).value(), 255);
+    /// assert_eq!(dilated.pixel(0, 0).value(), 0);
+    /// ```
+    pub fn map_with_neighborhood<T: Pixel>(
+        &self,
+        radius: u32,
+        edge: EdgeMode<P>,
+        f: impl Fn(&Neighborhood<P>) -> T,
+    ) -> Image<T> {
+        assert!(radius <= i32::MAX as u32 / 2, "radius is too large");
+
+        let data = (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                f(&Neighborhood {
+                    image: self,
+                    x,
+                    y,
+                    radius,
+                    edge,
+                })
+            })
+            .collect();
+
+        Image {
+            width: self.width,
+            height: self.height,
+            data,
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+            metadata: self.metadata.clone(),
         }
     }
 
@@ -890,11 +2224,224 @@ impl<P: Pixel> Image<P> {
         })
     }
 
+    /// Returns the image with each pixel in the image mapped to the given function, processing
+    /// pixels in parallel on the rayon thread pool. This is the parallel version of
+    /// [`Self::map_pixels`].
+    ///
+    /// This only pays off when the function is expensive or the image is large. For cheap
+    /// functions on small images, [`Self::map_pixels`] is usually faster.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::new(256, 256, Rgb::new(255, 0, 0));
+    /// let image = image.par_map_pixels(|Rgb { r, g, b }| Rgb::new(b, r, g));
+    ///
+    /// assert!(image.data.iter().all(|&pixel| pixel == Rgb::new(0, 255, 0)));
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_map_pixels<T: Pixel>(self, f: impl Fn(P) -> T + Send + Sync) -> Image<T> {
+        use rayon::prelude::*;
+
+        self.map_data(|data| data.into_par_iter().map(f).collect())
+    }
+
+    /// Maps each pixel in the image in place, processing pixels in parallel on the rayon thread
+    /// pool. The function takes the x and y coordinates of the pixel followed by a mutable
+    /// reference to it. This is the parallel version of [`Self::map_in_place`].
+    #[cfg(feature = "rayon")]
+    pub fn par_map_in_place(&mut self, f: impl Fn(u32, u32, &mut P) + Send + Sync) {
+        use rayon::prelude::*;
+
+        let width = self.width();
+        self.data
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, p)| f(i as u32 % width, i as u32 / width, p));
+    }
+
+    /// Returns a parallel iterator over the rows of this image, where each row is a slice of
+    /// pixels. This is the parallel version of [`Self::rows`].
+    #[cfg(feature = "rayon")]
+    pub fn par_rows(&self) -> impl rayon::iter::IndexedParallelIterator<Item = &[P]> {
+        use rayon::prelude::*;
+
+        self.data.par_chunks_exact(self.width() as usize)
+    }
+
+    /// Returns a parallel iterator over the rows of this image, where each row is a mutable slice
+    /// of pixels. This is the parallel version of [`Self::rows_mut`].
+    #[cfg(feature = "rayon")]
+    pub fn par_rows_mut(&mut self) -> impl rayon::iter::IndexedParallelIterator<Item = &mut [P]> {
+        use rayon::prelude::*;
+
+        let width = self.width() as usize;
+        self.data.par_chunks_exact_mut(width)
+    }
+
+    /// Splits this image into tiles of the given size, processes each tile with the given function
+    /// and stitches the results back together in place. If the `rayon` feature is enabled, tiles
+    /// are processed in parallel.
+    ///
+    /// Each tile is extended by `overlap` pixels on every side (clamped to the bounds of the
+    /// image), so that filters which depend on surrounding pixels, such as blurs or morphology
+    /// operations, have enough context at the edges of the tile. Only the non-overlapping part of
+    /// each processed tile is written back. Tiles on the right and bottom edges may be smaller
+    /// than the given tile size.
+    ///
+    /// Processing images in tiles keeps the working set of each operation small enough to fit in
+    /// cache, which makes expensive filters faster on very large images. Tiles are processed one
+    /// row of tiles at a time and written back as soon as they are processed, so only the rows
+    /// of the current row of tiles are copied. Tiles keep the palette of this image, but not its
+    /// metadata.
+    ///
+    /// # Panics
+    /// * `tile_width` or `tile_height` is zero.
+    /// * The function returns an image with different dimensions than the tile it was given.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(1000, 1000, Rgb::new(0, 0, 0));
+    /// image.process_tiles(256, 256, 0, |tile| !tile);
+    ///
+    /// assert!(image.data.iter().all(|&pixel| pixel == Rgb::white()));
+    /// ```
+    pub fn process_tiles(
+        &mut self,
+        tile_width: u32,
+        tile_height: u32,
+        overlap: u32,
+        f: impl Fn(Self) -> Self + Send + Sync,
+    ) {
+        assert!(
+            tile_width > 0 && tile_height > 0,
+            "tile dimensions must be non-zero"
+        );
+
+        let (width, height) = self.dimensions();
+        let (format, overlay, palette) = (self.format, self.overlay, self.palette.clone());
+        // Processed tiles may reference their own copy of the palette, so paletted pixels are
+        // rebound to the palette of this image when they are written back
+        // SAFETY: the boxed palette is never moved out of or mutated while tiles are written
+        let rebind = unsafe { self.detached_palette() };
+        // Copies the rows a band of tiles starting at the given y coordinate is read from, since
+        // the rows of neighboring bands are overwritten while they are processed
+        let band_source = |image: &Self, y1: u32| {
+            let oy1 = y1.saturating_sub(overlap);
+            let oy2 = y1.saturating_add(tile_height).saturating_add(overlap);
+            let rows = (oy1 as usize * width as usize)..(oy2.min(height) as usize * width as usize);
+
+            (oy1, image.data[rows].to_vec())
+        };
+
+        let mut source = band_source(self, 0);
+        for y1 in (0..height).step_by(tile_height as usize) {
+            let y2 = y1.saturating_add(tile_height).min(height);
+            let next = (y2 < height).then(|| band_source(self, y2));
+            let (oy1, band) = (source.0, &source.1);
+            let oy2 = oy1 + (band.len() / width as usize) as u32;
+
+            // Split the rows of the band into disjoint columns, one for each tile, so that every
+            // tile is written back as soon as it is processed
+            let mut tiles = (0..width)
+                .step_by(tile_width as usize)
+                .map(|x1| (x1, Vec::with_capacity((y2 - y1) as usize)))
+                .collect::<Vec<_>>();
+            let rows = (y1 as usize * width as usize)..(y2 as usize * width as usize);
+            for row in self.data[rows].chunks_exact_mut(width as usize) {
+                let row = row.chunks_mut(tile_width as usize);
+                for ((_, columns), column) in tiles.iter_mut().zip(row) {
+                    columns.push(column);
+                }
+            }
+
+            parallel::for_each_mut(&mut tiles, |(x1, columns)| {
+                let x1 = *x1;
+                let x2 = x1.saturating_add(tile_width).min(width);
+                let ox1 = x1.saturating_sub(overlap);
+                let ox2 = x2.saturating_add(overlap).min(width);
+
+                let tile = Self {
+                    width: NonZeroU32::new(ox2 - ox1).unwrap(),
+                    height: NonZeroU32::new(oy2 - oy1).unwrap(),
+                    data: band
+                        .chunks_exact(width as usize)
+                        .flat_map(|row| &row[ox1 as usize..ox2 as usize])
+                        .copied()
+                        .collect(),
+                    format,
+                    overlay,
+                    palette: palette.clone(),
+                    metadata: ImageMetadata::default(),
+                };
+                let tile = f(tile);
+                assert_eq!(
+                    tile.dimensions(),
+                    (ox2 - ox1, oy2 - oy1),
+                    "processed tile must have the same dimensions as the original tile"
+                );
+
+                let rows = tile.rows().skip((y1 - oy1) as usize);
+                for (column, row) in columns.iter_mut().zip(rows) {
+                    let row = &row[(x1 - ox1) as usize..(x2 - ox1) as usize];
+                    // SAFETY: the pixels are bound to the palette of this image, which outlives
+                    // them.
+                    unsafe { copy_rebound(rebind, column, row) };
+                }
+            });
+
+            if let Some(next) = next {
+                source = next;
+            }
+        }
+    }
+
     /// Iterates over each row of pixels in the image.
     pub fn rows(&self) -> impl Iterator<Item = &[P]> {
         self.data.chunks_exact(self.width() as usize)
     }
 
+    /// Iterates over each row of pixels in the image, where each row is a mutable slice of pixels.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [P]> {
+        let width = self.width() as usize;
+        self.data.chunks_exact_mut(width)
+    }
+
+    /// Iterates over every pixel in the image along with its coordinates, as `(x, y, pixel)`
+    /// tuples. Pixels are ordered row by row, from left to right.
+    ///
+    /// This avoids the bounds check that [`Self::pixel`] performs for every access when iterating
+    /// over coordinates in a double loop.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(3, 2, |x, y| L((x + y * 3) as u8));
+    ///
+    /// for (x, y, pixel) in image.pixels_with_coords() {
+    ///     assert_eq!(pixel.value(), (x + y * 3) as u8);
+    /// }
+    /// ```
+    pub fn pixels_with_coords(&self) -> impl Iterator<Item = (u32, u32, &P)> {
+        self.rows()
+            .zip(0..)
+            .flat_map(|(row, y)| row.iter().zip(0..).map(move |(pixel, x)| (x, y, pixel)))
+    }
+
+    /// Iterates over every pixel in the image along with its coordinates, as `(x, y, pixel)`
+    /// tuples where each pixel is a mutable reference. Pixels are ordered row by row, from left to
+    /// right.
+    ///
+    /// # See Also
+    /// * [`Self::pixels_with_coords`] for more information.
+    /// * [`Self::map_in_place`], which calls a function with the same arguments on every pixel.
+    pub fn pixels_with_coords_mut(&mut self) -> impl Iterator<Item = (u32, u32, &mut P)> {
+        self.rows_mut()
+            .zip(0..)
+            .flat_map(|(row, y)| row.iter_mut().zip(0..).map(move |(pixel, x)| (x, y, pixel)))
+    }
+
     /// Converts the image into an image with the given pixel type.
     ///
     /// # Note
@@ -908,6 +2455,26 @@ impl<P: Pixel> Image<P> {
         self.map_pixels(T::from)
     }
 
+    /// Converts this image into the given image with a different pixel type, overwriting its
+    /// pixels and dimensions. This reuses the allocation of the given image, which avoids
+    /// allocating a new image every time when converting images repeatedly, for example between
+    /// frames of a video.
+    ///
+    /// # See Also
+    /// * [`Self::convert`] for more information.
+    pub fn convert_into<T: Pixel + From<P>>(&self, target: &mut Image<T>) {
+        target.data.clear();
+        target
+            .data
+            .extend(self.data.iter().map(|&pixel| T::from(pixel)));
+        target.width = self.width;
+        target.height = self.height;
+        target.format = self.format;
+        target.overlay = self.overlay;
+        target.palette = None;
+        target.metadata.clone_from(&self.metadata);
+    }
+
     /// Sets the encoding format of this image. Note that when saving the file,
     /// an encoding format will still have to be explicitly specified.
     /// This is more or less image metadata.
@@ -915,50 +2482,168 @@ impl<P: Pixel> Image<P> {
         self.format = format;
     }
 
-    /// Crops this image in place to the given bounding box.
+    /// Crops this image in place to the given bounding box. This moves the pixels within the
+    /// bounding box to the start of the pixel buffer and does not allocate.
     ///
     /// # Panics
     /// * The width or height of the bounding box is less than 1.
+    /// * The bounding box exceeds the bounds of the image.
+    ///
+    /// # See Also
+    /// * [`Self::try_crop`] for a non-panicking version.
+    /// * [`Self::crop_rect`] to crop to a [`Rect`][crate::Rect] which may exceed the bounds of the
+    ///   image.
     pub fn crop(&mut self, x1: u32, y1: u32, x2: u32, y2: u32) {
-        self.data = self
-            .pixels()
-            .skip(y1 as usize)
-            .zip(y1..y2)
-            .flat_map(|(row, _)| &row[x1 as usize..x2 as usize])
-            .copied()
-            .collect();
+        let width = self.width() as usize;
+        let (x1, x2) = (x1 as usize, x2 as usize);
+        let row_width = x2 - x1;
+        assert!(x2 <= width, "crop region exceeds the width of the image");
+
+        for (i, y) in (y1 as usize..y2 as usize).enumerate() {
+            let start = y * width + x1;
+            self.data
+                .copy_within(start..start + row_width, i * row_width);
+        }
+        self.data.truncate(row_width * (y2 - y1) as usize);
 
-        self.width = NonZeroU32::new(x2 - x1).unwrap();
+        self.width = NonZeroU32::new(row_width as u32).unwrap();
         self.height = NonZeroU32::new(y2 - y1).unwrap();
     }
 
     /// Takes this image and crops it to the given box. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * The width or height of the bounding box is less than 1.
+    /// * The bounding box exceeds the bounds of the image.
     #[must_use]
     pub fn cropped(mut self, x1: u32, y1: u32, x2: u32, y2: u32) -> Self {
         self.crop(x1, y1, x2, y2);
         self
     }
 
-    /// Mirrors, or flips this image horizontally (about the y-axis) in place.
-    pub fn mirror(&mut self) {
-        let width = self.width();
-
-        self.data
-            .chunks_exact_mut(width as usize)
-            .for_each(<[P]>::reverse);
-    }
+    /// Crops this image in place to the given bounding box, or returns an error if the bounding
+    /// box is empty or exceeds the bounds of the image. The image is left untouched on error.
+    ///
+    /// # Errors
+    /// * `OutOfBounds`: The bounding box exceeds the bounds of the image.
+    /// * `EmptyImageError`: The width or height of the bounding box is less than 1.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(16, 16, Rgb::black());
+    /// assert!(image.try_crop(8, 8, 24, 24).is_err());
+    ///
+    /// image.try_crop(4, 4, 12, 12).unwrap();
+    /// assert_eq!(image.dimensions(), (8, 8));
+    /// ```
+    pub fn try_crop(&mut self, x1: u32, y1: u32, x2: u32, y2: u32) -> Result<()> {
+        if x2 > self.width() || y2 > self.height() {
+            return Err(self.out_of_bounds((x1.into(), y1.into(), x2.into(), y2.into())));
+        }
+        if x1 >= x2 || y1 >= y2 {
+            return Err(Error::EmptyImageError);
+        }
 
-    /// Takes this image and flips it horizontally (about the y-axis). Useful for method chaining.
-    #[must_use]
-    pub fn mirrored(mut self) -> Self {
-        self.mirror();
-        self
+        self.crop(x1, y1, x2, y2);
+        Ok(())
     }
 
-    /// Flips this image vertically (about the x-axis) in place.
-    pub fn flip(&mut self) {
-        self.mirror();
-        self.rotate_180();
+    /// Returns the smallest bounding box `(x1, y1, x2, y2)` containing every pixel that matches the
+    /// given predicate, or `None` if no pixel matches. `x2` and `y2` are exclusive, so the bounding
+    /// box can be passed directly to [`Self::crop`].
+    ///
+    /// This is useful for making layout decisions based on the visible content of an image
+    /// without cropping it.
+    ///
+    /// # See Also
+    /// * [`Self::trim`] to crop an image to the bounding box of its content.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(8, 8, Rgba::transparent());
+    /// image.set_pixel(2, 5, Rgba::white());
+    /// image.set_pixel(6, 3, Rgba::new(255, 0, 0, 10));
+    ///
+    /// assert_eq!(image.content_bounds(|pixel| pixel.a > 0), Some((2, 3, 7, 6)));
+    /// assert_eq!(image.content_bounds(|pixel| pixel.a > 128), Some((2, 5, 3, 6)));
+    /// ```
+    pub fn content_bounds(&self, predicate: impl Fn(&P) -> bool) -> Option<(u32, u32, u32, u32)> {
+        self.pixels_with_coords()
+            .filter(|(_, _, pixel)| predicate(pixel))
+            .fold(None, |bounds, (x, y, _)| {
+                Some(match bounds {
+                    Some((x1, y1, x2, y2)) => (x.min(x1), y.min(y1), x2.max(x + 1), y2.max(y + 1)),
+                    None => (x, y, x + 1, y + 1),
+                })
+            })
+    }
+
+    /// Crops away the borders of this image in place, where border pixels are determined by the
+    /// given trim mode. Returns the bounding box `(x1, y1, x2, y2)` of the remaining content
+    /// relative to the original image.
+    ///
+    /// If every pixel of the image is a border pixel, the image is left unchanged and `None` is
+    /// returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(5, 5, Rgba::transparent());
+    /// image.set_pixel(1, 2, Rgba::white());
+    /// image.set_pixel(3, 3, Rgba::white());
+    ///
+    /// assert_eq!(image.trim(TrimMode::Transparent), Some((1, 2, 4, 4)));
+    /// assert_eq!(image.dimensions(), (3, 2));
+    /// ```
+    pub fn trim(&mut self, mode: TrimMode<P>) -> Option<(u32, u32, u32, u32)>
+    where
+        P: TrueColor,
+    {
+        let bounds = self.content_bounds(|&pixel| !mode.is_border(pixel))?;
+        let (x1, y1, x2, y2) = bounds;
+        self.crop(x1, y1, x2, y2);
+
+        Some(bounds)
+    }
+
+    /// Takes this image and crops away its borders, returning the cropped image along with the
+    /// bounding box `(x1, y1, x2, y2)` of the content relative to the original image. Useful for
+    /// method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::trim`] for more information.
+    #[must_use]
+    pub fn trimmed(mut self, mode: TrimMode<P>) -> (Self, Option<(u32, u32, u32, u32)>)
+    where
+        P: TrueColor,
+    {
+        let bounds = self.trim(mode);
+        (self, bounds)
+    }
+
+    /// Mirrors, or flips this image horizontally (about the y-axis) in place.
+    ///
+    /// # See Also
+    /// - [`Self::flip_horizontal_in_place`], which this method is an alias of.
+    pub fn mirror(&mut self) {
+        self.flip_horizontal_in_place();
+    }
+
+    /// Takes this image and flips it horizontally (about the y-axis). Useful for method chaining.
+    #[must_use]
+    pub fn mirrored(mut self) -> Self {
+        self.mirror();
+        self
+    }
+
+    /// Flips this image vertically (about the x-axis) in place.
+    ///
+    /// # See Also
+    /// - [`Self::flip_vertical_in_place`], which this method is an alias of.
+    pub fn flip(&mut self) {
+        self.flip_vertical_in_place();
     }
 
     /// Takes this image and flips it vertically, or about the x-axis. Useful for method chaining.
@@ -968,23 +2653,81 @@ impl<P: Pixel> Image<P> {
         self
     }
 
-    fn rotate_iterator(&self) -> impl Iterator<Item = P> + DoubleEndedIterator + '_ {
-        (0..self.width() as usize).flat_map(move |i| {
-            (0..self.height() as usize)
-                .map(move |j| self.data[j * self.width() as usize + i])
-                .rev()
-        })
+    /// Flips this image horizontally (about the y-axis) by reversing each row in place. This does
+    /// not allocate.
+    pub fn flip_horizontal_in_place(&mut self) {
+        let width = self.width() as usize;
+
+        self.data.chunks_exact_mut(width).for_each(<[P]>::reverse);
+    }
+
+    /// Flips this image vertically (about the x-axis) by swapping rows in place. This does not
+    /// allocate.
+    pub fn flip_vertical_in_place(&mut self) {
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - y - 1) * width);
+            top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+    }
+
+    /// Transposes the pixel data of this image in place, swapping its width and height.
+    ///
+    /// Square images are transposed by swapping pixels across the diagonal. Other images are
+    /// transposed by following the permutation cycles of the pixel indices, which only requires a
+    /// bitmap of visited positions (one bit per pixel) as opposed to a full copy of the image.
+    fn transpose_in_place(&mut self) {
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+
+        if width == height {
+            for y in 0..height {
+                for x in y + 1..width {
+                    self.data.swap(y * width + x, x * width + y);
+                }
+            }
+        } else {
+            // The first and last pixels never move. Every other pixel at index `i` moves to
+            // index `i * height mod (len - 1)`.
+            let last = self.data.len() - 1;
+            let mut visited = vec![0_u64; (last >> 6) + 1];
+
+            for start in 1..last {
+                if visited[start >> 6] & (1 << (start & 63)) != 0 {
+                    continue;
+                }
+
+                let mut i = start;
+                let mut carried = self.data[start];
+                loop {
+                    i = i * height % last;
+                    core::mem::swap(&mut self.data[i], &mut carried);
+                    visited[i >> 6] |= 1 << (i & 63);
+
+                    if i == start {
+                        break;
+                    }
+                }
+            }
+        }
+
+        core::mem::swap(&mut self.width, &mut self.height);
     }
 
     /// Rotates this image by 90 degrees clockwise, or 270 degrees counterclockwise, in place.
     ///
+    /// This is done by transposing the image in place and then mirroring it, so the pixel data is
+    /// never reallocated.
+    ///
     /// # See Also
     /// - [`Self::rotate`] for a version that can take any arbitrary amount of degrees
     /// - [`Self::rotated`] for the above method which does operate in-place - useful for method
     /// chaining
     pub fn rotate_90(&mut self) {
-        self.data = self.rotate_iterator().collect();
-        std::mem::swap(&mut self.width, &mut self.height);
+        self.transpose_in_place();
+        self.flip_horizontal_in_place();
     }
 
     /// Rotates this image by 180 degrees in place.
@@ -999,13 +2742,16 @@ impl<P: Pixel> Image<P> {
 
     /// Rotates this image by 270 degrees clockwise, or 90 degrees counterclockwise, in place.
     ///
+    /// This is done by transposing the image in place and then flipping it, so the pixel data is
+    /// never reallocated.
+    ///
     /// # See Also
     /// - [`Self::rotate`] for a version that can take any arbitrary amount of degrees
     /// - [`Self::rotated`] for the above method which does operate in-place - useful for method
     /// chaining
     pub fn rotate_270(&mut self) {
-        self.data = self.rotate_iterator().rev().collect();
-        std::mem::swap(&mut self.width, &mut self.height);
+        self.transpose_in_place();
+        self.flip_vertical_in_place();
     }
 
     /// Rotates this image in place about its center. There are optimized rotating algorithms for
@@ -1036,13 +2782,274 @@ impl<P: Pixel> Image<P> {
         self
     }
 
+    /// Transforms this image in place so that an image stored with the given orientation becomes
+    /// upright. This does not modify the metadata of the image.
+    ///
+    /// # See Also
+    /// - [`Self::auto_orient`] to apply the orientation stored in the EXIF data of this image.
+    pub fn apply_orientation(&mut self, orientation: Orientation) {
+        match orientation {
+            Orientation::Normal => (),
+            Orientation::MirrorHorizontal => self.flip_horizontal_in_place(),
+            Orientation::Rotate180 => self.rotate_180(),
+            Orientation::MirrorVertical => self.flip_vertical_in_place(),
+            Orientation::Transpose => self.transpose_in_place(),
+            Orientation::Rotate90 => self.rotate_90(),
+            Orientation::Transverse => {
+                self.transpose_in_place();
+                self.rotate_180();
+            }
+            Orientation::Rotate270 => self.rotate_270(),
+        }
+    }
+
+    /// Makes this image upright in place according to the orientation stored in its EXIF data,
+    /// usually set by the camera that took it. The stored orientation is then reset to
+    /// [`Orientation::Normal`], so that the image is not transformed again when it is displayed or
+    /// encoded.
+    ///
+    /// This does nothing if the image has no orientation.
+    ///
+    /// # See Also
+    /// - [`ImageMetadata::orientation`] for the orientation which is applied.
+    /// - [`Self::apply_orientation`] to apply any orientation.
+    pub fn auto_orient(&mut self) {
+        if let Some(orientation) = self.metadata.orientation() {
+            self.apply_orientation(orientation);
+            self.metadata.set_orientation(Orientation::Normal);
+        }
+    }
+
+    /// Takes this image and makes it upright according to the orientation stored in its EXIF
+    /// data. Useful for method chaining.
+    ///
+    /// # See Also
+    /// - [`Self::auto_orient`] for more information.
+    #[must_use]
+    pub fn auto_oriented(mut self) -> Self {
+        self.auto_orient();
+        self
+    }
+
+    /// Rotates this image clockwise by the given amount of degrees about the given pivot point,
+    /// where the center of the pixel at `(x, y)` lies at `(x, y)`. For example, the pivot
+    /// `(-0.5, -0.5)` is the top-left corner of the image. The image is resampled with bilinear
+    /// interpolation, and areas of the canvas not covered by the rotated image are filled with
+    /// `fill`.
+    ///
+    /// The given [`RotationCanvas`] determines whether the canvas keeps its dimensions or is
+    /// resized to fit the whole rotated image. Returns the position of the top-left pixel of the
+    /// new canvas relative to the top-left pixel of the original image, which is always `(0, 0)`
+    /// when the canvas is kept. Adding this offset to the position an image was going to be pasted
+    /// at pastes the rotated image so that its pivot stays in place.
+    ///
+    /// # See Also
+    /// * [`Self::rotate`] for lossless rotations by multiples of 90 degrees about the center.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(64, 32, Rgba::white());
+    ///
+    /// // Rotate about the top-left corner, growing the canvas to the left
+    /// let clear = Rgba::transparent();
+    /// let offset = image.rotate_about(90.0, (-0.5, -0.5), RotationCanvas::Expand, clear);
+    ///
+    /// assert_eq!(image.dimensions(), (32, 64));
+    /// assert_eq!(offset, (-32, 0));
+    /// ```
+    pub fn rotate_about(
+        &mut self,
+        degrees: f64,
+        pivot: (f64, f64),
+        canvas: RotationCanvas,
+        fill: P,
+    ) -> (i64, i64)
+    where
+        P: TrueColor,
+    {
+        self.transform(Affine::rotate(degrees).about(pivot), canvas, fill)
+    }
+
+    /// Takes this image and rotates it clockwise by the given amount of degrees about the given
+    /// pivot point. Useful for method chaining. Returns the rotated image along with the position
+    /// of its top-left pixel relative to the top-left pixel of the original image.
+    ///
+    /// # See Also
+    /// * [`Self::rotate_about`] for more information.
+    #[must_use]
+    pub fn rotated_about(
+        mut self,
+        degrees: f64,
+        pivot: (f64, f64),
+        canvas: RotationCanvas,
+        fill: P,
+    ) -> (Self, (i64, i64))
+    where
+        P: TrueColor,
+    {
+        let offset = self.rotate_about(degrees, pivot, canvas, fill);
+        (self, offset)
+    }
+
+    /// Transforms this image with the given affine transformation, which maps pixel coordinates of
+    /// this image to pixel coordinates of the transformed image, where the center of the pixel at
+    /// `(x, y)` lies at `(x, y)`. The image is resampled with bilinear interpolation, and areas of
+    /// the canvas not covered by the transformed image are filled with `fill`.
+    ///
+    /// The given [`RotationCanvas`] determines whether the canvas keeps its dimensions, is resized
+    /// to fit the whole transformed image, or is given explicitly. Returns the position of the
+    /// top-left pixel of the new canvas relative to the top-left pixel of the original image.
+    ///
+    /// # Panics
+    /// * The transformation is not invertible, for example because it scales by zero.
+    /// * The transformed image is too large.
+    ///
+    /// # See Also
+    /// * [`Self::rotate_about`] for rotations about a pivot point.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(64, 32, Rgba::white());
+    ///
+    /// // Shear the image to the right about its top-left corner, growing the canvas to fit it
+    /// let offset = image.transform(
+    ///     Affine::shear(0.5, 0.0).about((-0.5, -0.5)),
+    ///     RotationCanvas::Expand,
+    ///     Rgba::transparent(),
+    /// );
+    ///
+    /// assert_eq!(image.dimensions(), (80, 32));
+    /// assert_eq!(offset, (0, 0));
+    /// ```
+    pub fn transform(&mut self, affine: Affine, canvas: RotationCanvas, fill: P) -> (i64, i64)
+    where
+        P: TrueColor,
+    {
+        let inverse = affine
+            .inverse()
+            .expect("affine transformation is not invertible");
+        let (w, h) = (f64::from(self.width()), f64::from(self.height()));
+
+        let (origin, (width, height)) = match canvas {
+            RotationCanvas::Keep => ((0, 0), self.dimensions()),
+            RotationCanvas::Expand => {
+                let corners = [
+                    (-0.5, -0.5),
+                    (w - 0.5, -0.5),
+                    (-0.5, h - 0.5),
+                    (w - 0.5, h - 0.5),
+                ]
+                .map(|(x, y)| affine.apply(x, y));
+
+                // Round off floating point error so that exact transformations keep exact
+                // dimensions
+                let snap = |value: f64| ((value + 0.5) * 1e6).round() / 1e6;
+                let (x1, y1, x2, y2) = corners.iter().fold(
+                    (i64::MAX, i64::MAX, i64::MIN, i64::MIN),
+                    |(x1, y1, x2, y2), &(x, y)| {
+                        (
+                            x1.min(snap(x).floor() as i64),
+                            y1.min(snap(y).floor() as i64),
+                            x2.max(snap(x).ceil() as i64),
+                            y2.max(snap(y).ceil() as i64),
+                        )
+                    },
+                );
+                let size = |length: i64| {
+                    u32::try_from(length.max(1)).expect("transformed image is too large")
+                };
+                ((x1, y1), (size(x2 - x1), size(y2 - y1)))
+            }
+            RotationCanvas::Bounds {
+                x,
+                y,
+                width,
+                height,
+            } => ((x, y), (width.max(1), height.max(1))),
+        };
+
+        let source = &*self;
+        let data = parallel::map_rows(height, |y| {
+            (0..width).map(move |x| {
+                // Map the destination pixel back into the source image
+                let (sx, sy) = inverse.apply(
+                    (origin.0 + i64::from(x)) as f64,
+                    (origin.1 + i64::from(y)) as f64,
+                );
+
+                if (-0.5..w - 0.5).contains(&sx) && (-0.5..h - 0.5).contains(&sy) {
+                    source.sample_bilinear(sx, sy)
+                } else {
+                    fill
+                }
+            })
+        });
+
+        self.data = data;
+        self.width = NonZeroU32::new(width).unwrap();
+        self.height = NonZeroU32::new(height).unwrap();
+        origin
+    }
+
+    /// Takes this image and transforms it with the given affine transformation. Useful for method
+    /// chaining. Returns the transformed image along with the position of its top-left pixel
+    /// relative to the top-left pixel of the original image.
+    ///
+    /// # See Also
+    /// * [`Self::transform`] for more information.
+    #[must_use]
+    pub fn transformed(
+        mut self,
+        affine: Affine,
+        canvas: RotationCanvas,
+        fill: P,
+    ) -> (Self, (i64, i64))
+    where
+        P: TrueColor,
+    {
+        let offset = self.transform(affine, canvas, fill);
+        (self, offset)
+    }
+
+    /// Pads this image in place with the given amount of pixels on each side, filling the new
+    /// space with the given pixel. This reuses the existing pixel buffer, only growing it if
+    /// needed.
+    pub fn pad(&mut self, fill: P, top: u32, right: u32, bottom: u32, left: u32) {
+        let (w, h) = (self.width() as usize, self.height() as usize);
+        let new_width = w + (left + right) as usize;
+        let new_height = h + (top + bottom) as usize;
+        let (top, left) = (top as usize, left as usize);
+
+        self.data.resize(new_width * new_height, fill);
+        // Moving the last row first ensures rows are never overwritten before they are moved
+        for y in (0..h).rev() {
+            let start = (y + top) * new_width + left;
+            self.data.copy_within(y * w..(y + 1) * w, start);
+        }
+
+        for (y, row) in self.data.chunks_exact_mut(new_width).enumerate() {
+            if y < top || y >= top + h {
+                row.fill(fill);
+            } else {
+                row[..left].fill(fill);
+                row[left + w..].fill(fill);
+            }
+        }
+
+        self.width = NonZeroU32::new(new_width as u32).unwrap();
+        self.height = NonZeroU32::new(new_height as u32).unwrap();
+    }
+
     /// Takes the image and creates a new one with the specified padding and fill.
+    ///
+    /// # See Also
+    /// * [`Self::pad`] for a version that operates in-place
     #[must_use]
-    pub fn padded(self, fill: P, top: u32, right: u32, bottom: u32, left: u32) -> Self {
-        let (w, h) = self.dimensions();
-        Self::from_fn(w + left + right, h + top + bottom, |x, y| {
-            self.get_pixel(x - left, y - top).copied().unwrap_or(fill)
-        })
+    pub fn padded(mut self, fill: P, top: u32, right: u32, bottom: u32, left: u32) -> Self {
+        self.pad(fill, top, right, bottom, left);
+        self
     }
 
     /// Resizes this image in place to the given dimensions using the given resizing algorithm
@@ -1103,6 +3110,168 @@ impl<P: Pixel> Image<P> {
         self
     }
 
+    /// Resizes this image in place like [`Self::resize`], but resamples colors in linear light
+    /// instead of in gamma-encoded sRGB.
+    ///
+    /// Averaging sRGB values darkens the result, which makes downscaled images with fine, high
+    /// contrast details (such as text or foliage) look darker and muddier than the original.
+    /// Resampling in linear light is physically correct and avoids this, at the cost of
+    /// converting the image to floating point pixels and back. Alpha is not affected.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(2, 1, |x, _| if x == 0 { Rgb::black() } else { Rgb::white() });
+    ///
+    /// let naive = image.clone().resized(1, 1, ResizeAlgorithm::Box);
+    /// let linear = image.resized_linear(1, 1, ResizeAlgorithm::Box);
+    ///
+    /// // Half of the light of a white pixel is much brighter than 50% gray in sRGB
+    /// assert!(naive.pixel(0, 0).r < linear.pixel(0, 0).r);
+    /// ```
+    #[cfg(feature = "resize")]
+    pub fn resize_linear(&mut self, width: u32, height: u32, algorithm: ResizeAlgorithm)
+    where
+        P: TrueColor,
+    {
+        assert_nonzero!(width, height);
+
+        let width = NonZeroU32::new(width).unwrap();
+        let height = NonZeroU32::new(height).unwrap();
+
+        let linear = self
+            .data
+            .iter()
+            .map(|&pixel| pixel.into_rgba().to_linear())
+            .collect::<Vec<_>>();
+        let linear =
+            crate::resize::resize(&linear, self.width, self.height, width, height, algorithm);
+
+        self.data = linear
+            .into_iter()
+            .map(|pixel| P::from_rgba_tuple(Rgba::from_linear(pixel).as_rgba_tuple()))
+            .collect();
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Takes this image and resizes it to the given dimensions in linear light. Useful for method
+    /// chaining.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # See Also
+    /// * [`Self::resize_linear`] for more information.
+    #[must_use]
+    #[cfg(feature = "resize")]
+    pub fn resized_linear(mut self, width: u32, height: u32, algorithm: ResizeAlgorithm) -> Self
+    where
+        P: TrueColor,
+    {
+        self.resize_linear(width, height, algorithm);
+        self
+    }
+
+    /// Averages the pixels in the region `x1..x2`, `y1..y2` of this image in linear light,
+    /// weighting colors by their alpha so that transparent pixels don't darken the result.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn average_region(&self, x1: u32, y1: u32, x2: u32, y2: u32) -> P
+    where
+        P: TrueColor,
+    {
+        let mut color = [0_f32; 3];
+        let mut alpha = 0_f32;
+        for y in y1..y2 {
+            let offset = (y * self.width()) as usize;
+            for pixel in &self.data[offset + x1 as usize..offset + x2 as usize] {
+                let (r, g, b, a) = pixel.as_rgba_tuple();
+                let a = f32::from(a) / 255.;
+                for (c, v) in color.iter_mut().zip([r, g, b]) {
+                    *c += crate::color::srgb_u8_to_linear(v) * a;
+                }
+                alpha += a;
+            }
+        }
+
+        let count = ((x2 - x1) * (y2 - y1)) as f32;
+        let [r, g, b] = if alpha > 0. {
+            color.map(|c| crate::color::linear_to_srgb_u8(c / alpha))
+        } else {
+            [0; 3]
+        };
+        P::from_rgba_tuple((r, g, b, (alpha / count * 255.).round() as u8))
+    }
+
+    /// Returns the average color of this image. Colors are averaged in linear light and weighted
+    /// by their alpha, so the result matches what the image looks like from afar and fully
+    /// transparent pixels don't affect its color. This is useful as an accent color or as the
+    /// simplest placeholder while the image loads.
+    ///
+    /// # See Also
+    /// * [`Self::preview`] for a tiny version of the image instead of a single color.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(2, 1, |x, _| if x == 0 { Rgb::black() } else { Rgb::white() });
+    ///
+    /// // The average of black and white in linear light is brighter than 50% gray in sRGB
+    /// assert_eq!(image.average_color(), Rgb::new(188, 188, 188));
+    /// ```
+    #[must_use]
+    pub fn average_color(&self) -> P
+    where
+        P: TrueColor,
+    {
+        self.average_region(0, 0, self.width(), self.height())
+    }
+
+    /// Creates a tiny `size`x`size` preview of this image for use as a low quality image
+    /// placeholder. Every pixel of the preview is the average of the corresponding region of this
+    /// image, computed in linear light like [`Self::average_color`]. This is much cheaper than a
+    /// full resize, and since every source pixel contributes to exactly one preview pixel, no
+    /// detail is skipped.
+    ///
+    /// The aspect ratio of the image is not preserved. Placeholders are usually stretched to the
+    /// size of the image anyway.
+    ///
+    /// # Panics
+    /// * `size` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::horizontal_gradient(256, 128, Rgb::new(255, 0, 0), Rgb::new(0, 0, 255));
+    /// let preview = image.preview(8);
+    ///
+    /// assert_eq!(preview.dimensions(), (8, 8));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn preview(&self, size: u32) -> Self
+    where
+        P: TrueColor,
+    {
+        assert_ne!(size, 0, "size must be non-zero");
+
+        // The start of the region covered by the given preview pixel along an axis
+        let start =
+            |i: u32, length: u32| (u64::from(i) * u64::from(length) / u64::from(size)) as u32;
+        let (width, height) = self.dimensions();
+
+        Self::from_fn(size, size, |x, y| {
+            let (x1, y1) = (start(x, width), start(y, height));
+            // Always cover at least one pixel when upscaling
+            let x2 = start(x + 1, width).max(x1 + 1).min(width);
+            let y2 = start(y + 1, height).max(y1 + 1).min(height);
+            self.average_region(x1, y1, x2, y2)
+        })
+    }
+
     /// Draws an object or shape onto this image.
     ///
     /// # Example
@@ -1122,6 +3291,16 @@ impl<P: Pixel> Image<P> {
         entity.draw(self);
     }
 
+    /// Draws an object or shape onto this image, letting it borrow any temporary buffers it needs
+    /// from the given scratch instead of allocating them. This is useful when drawing objects such
+    /// as [`crate::morph::Stroke`] onto many images.
+    ///
+    /// # See Also
+    /// * [`crate::scratch`] for more information.
+    pub fn draw_with_scratch(&mut self, entity: &impl Draw<P>, scratch: &mut Scratch<P>) {
+        entity.draw_with_scratch(self, scratch);
+    }
+
     /// Takes this image, draws the given object or shape onto it, and returns it.
     /// Useful for method chaining and drawing multiple objects at once.
     ///
@@ -1136,6 +3315,11 @@ impl<P: Pixel> Image<P> {
     /// Pastes the given image onto this image at the given x and y coordinates.
     /// This is a shorthand for using the [`Self::draw`] method with [`crate::Paste`].
     ///
+    /// Any part of the pasted image that lies outside of this image is clipped.
+    ///
+    /// # See Also
+    /// * [`Self::try_paste`] to return an error instead of clipping the pasted image.
+    ///
     /// # Example
     /// ```no_run
     /// # use ril::prelude::*;
@@ -1151,23 +3335,49 @@ impl<P: Pixel> Image<P> {
         self.draw(&crate::Paste::new(image).with_position(x, y));
     }
 
-    /// Pastes the given image onto this image at the given x and y coordinates,
-    /// masked with the given masking image.
+    /// Pastes the given image onto this image at the given x and y coordinates, or returns an
+    /// error if the pasted image does not lie entirely within this image. This image is left
+    /// untouched on error.
     ///
-    /// Currently, only [`BitPixel`] images are supported for the masking image.
-    ///
-    /// This is a shorthand for using the [`Self::draw`] method with [`crate::Paste`].
+    /// # Errors
+    /// * `OutOfBounds`: The pasted image exceeds the bounds of this image.
     ///
     /// # Example
-    /// ```no_run
+    /// ```
     /// # use ril::prelude::*;
-    /// # fn main() -> ril::Result<()> {
     /// let mut image = Image::new(256, 256, Rgb::white());
-    /// let overlay_image = Image::open("overlay.png")?;
+    /// let overlay_image = Image::new(64, 64, Rgb::black());
+    ///
+    /// image.try_paste(64, 64, &overlay_image).unwrap();
+    /// assert!(image.try_paste(224, 0, &overlay_image).is_err());
+    /// ```
+    pub fn try_paste(&mut self, x: i64, y: i64, image: &Self) -> Result<()> {
+        let (x2, y2) = (x + i64::from(image.width()), y + i64::from(image.height()));
+        if x < 0 || y < 0 || x2 > i64::from(self.width()) || y2 > i64::from(self.height()) {
+            return Err(self.out_of_bounds((x, y, x2, y2)));
+        }
+
+        self.paste(x, y, image);
+        Ok(())
+    }
+
+    /// Pastes the given image onto this image at the given x and y coordinates,
+    /// masked with the given masking image.
+    ///
+    /// Currently, only [`BitPixel`] images are supported for the masking image.
+    ///
+    /// This is a shorthand for using the [`Self::draw`] method with [`crate::Paste`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut image = Image::new(256, 256, Rgb::white());
+    /// let overlay_image = Image::open("overlay.png")?;
     ///
     /// let (w, h) = overlay_image.dimensions();
     /// let mut mask = Image::new(w, h, BitPixel::off());
-    /// mask.draw(&Ellipse::from_bounding_box(0, 0, w, h).with_fill(BitPixel::on()));
+    /// mask.draw(&Ellipse::from_bounding_box(0, 0, w.into(), h.into()).with_fill(BitPixel::on()));
     ///
     /// image.paste_with_mask(64, 64, &overlay_image, &mask);
     /// # Ok(())
@@ -1177,6 +3387,66 @@ impl<P: Pixel> Image<P> {
         self.draw(&crate::Paste::new(image).with_position(x, y).with_mask(mask));
     }
 
+    /// Pastes the given image onto this image at the given x and y coordinates, using the
+    /// luminance values of the given grayscale masking image as the opacity of each pasted pixel.
+    ///
+    /// Unlike [`Self::paste_with_mask`], this allows for soft, anti-aliased or feathered edges.
+    /// The masking image must have the same dimensions as the pasted image.
+    ///
+    /// This is a shorthand for using the [`Self::draw`] method with [`crate::Paste`].
+    ///
+    /// # Panics
+    /// * The masking image has different dimensions from the pasted image.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut image = Image::new(256, 256, Rgb::white());
+    /// let overlay_image = Image::open("overlay.png")?;
+    ///
+    /// let (w, h) = overlay_image.dimensions();
+    /// // Fade the overlay image in from left to right
+    /// let mask = Image::from_fn(w, h, |x, _| L((x * 255 / w) as u8));
+    ///
+    /// image.paste_with_alpha_mask(64, 64, &overlay_image, &mask);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn paste_with_alpha_mask(&mut self, x: i64, y: i64, image: &Self, mask: &Image<L>) {
+        self.draw(
+            &crate::Paste::new(image)
+                .with_position(x, y)
+                .with_alpha_mask(mask),
+        );
+    }
+
+    /// Pastes the given image onto this image at the given x and y coordinates, scaling the alpha
+    /// of every pasted pixel by the given opacity, from `0.0` (fully transparent) to `1.0`
+    /// (opaque).
+    ///
+    /// This is a shorthand for using the [`Self::draw`] method with [`crate::Paste`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut image = Image::new(256, 256, Rgb::white());
+    /// let overlay_image = Image::open("overlay.png")?;
+    ///
+    /// // Paste the overlay image at half opacity
+    /// image.paste_with_opacity(64, 64, &overlay_image, 0.5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn paste_with_opacity(&mut self, x: i64, y: i64, image: &Self, opacity: f32) {
+        self.draw(
+            &crate::Paste::new(image)
+                .with_position(x, y)
+                .with_opacity(opacity),
+        );
+    }
+
     /// Masks the alpha values of this image with the luminance values of the given single-channel
     /// [`L`] image.
     ///
@@ -1209,6 +3479,219 @@ impl<P: Pixel> Image<P> {
             });
     }
 
+    /// Converts every pixel in this image from straight alpha into premultiplied alpha in place.
+    ///
+    /// Filtering or resampling a premultiplied image avoids dark fringes around anti-aliased or
+    /// translucent edges. Make sure to call [`Self::unpremultiply`] before encoding the image or
+    /// drawing onto it, since the rest of this crate assumes straight alpha.
+    ///
+    /// # See Also
+    /// * [`Alpha::premultiplied`] for the per-pixel version of this method.
+    pub fn premultiply(&mut self)
+    where
+        P: Alpha,
+    {
+        self.data
+            .iter_mut()
+            .for_each(|pixel| *pixel = pixel.premultiplied());
+    }
+
+    /// Takes this image and converts it into premultiplied alpha. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::premultiply`] for more information.
+    #[must_use]
+    pub fn premultiplied(mut self) -> Self
+    where
+        P: Alpha,
+    {
+        self.premultiply();
+        self
+    }
+
+    /// Converts every pixel in this image from premultiplied alpha back into straight alpha in
+    /// place. This is the inverse of [`Self::premultiply`].
+    pub fn unpremultiply(&mut self)
+    where
+        P: Alpha,
+    {
+        self.data
+            .iter_mut()
+            .for_each(|pixel| *pixel = pixel.unpremultiplied());
+    }
+
+    /// Takes this image and converts it from premultiplied alpha back into straight alpha. Useful
+    /// for method chaining.
+    #[must_use]
+    pub fn unpremultiplied(mut self) -> Self
+    where
+        P: Alpha,
+    {
+        self.unpremultiply();
+        self
+    }
+
+    /// Converts this image into grayscale using the given weights for the red, green and blue
+    /// channels. The alpha channel is discarded.
+    ///
+    /// Converting into [`L`] with [`Self::convert`] is equivalent to using
+    /// [`GrayscaleWeights::Rec601`], except that it truncates instead of rounding.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::new(1, 1, Rgb::new(0, 255, 0));
+    ///
+    /// assert_eq!(image.clone().grayscale(GrayscaleWeights::Rec709).pixel(0, 0), &L(182));
+    /// assert_eq!(image.grayscale(GrayscaleWeights::Average).pixel(0, 0), &L(85));
+    /// ```
+    #[must_use = "the image is consumed by this method and returns a new image"]
+    pub fn grayscale(self, weights: GrayscaleWeights) -> Image<L>
+    where
+        P: TrueColor,
+    {
+        self.map_pixels(|pixel| weights.luminance(pixel))
+    }
+
+    /// Maps the luminance of every pixel in this image onto a gradient between the two given
+    /// colors in place, where black maps to `dark` and white maps to `light`. Alpha is left
+    /// untouched.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let (dark, light) = (Rgb::new(0, 0, 128), Rgb::new(255, 255, 0));
+    /// let mut image = Image::from_fn(2, 1, |x, _| if x == 0 { Rgb::black() } else { Rgb::white() });
+    /// image.duotone(dark, light);
+    ///
+    /// assert_eq!(image.pixel(0, 0), &dark);
+    /// assert_eq!(image.pixel(1, 0), &light);
+    /// ```
+    pub fn duotone(&mut self, dark: Rgb, light: Rgb)
+    where
+        P: TrueColor,
+    {
+        let mut ramp = [(0, 0, 0); 256];
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        for (i, color) in ramp.iter_mut().enumerate() {
+            let t = i as f32 / 255.0;
+            let lerp = |a: u8, b: u8| {
+                (f32::from(b) - f32::from(a))
+                    .mul_add(t, f32::from(a))
+                    .round() as u8
+            };
+            *color = (
+                lerp(dark.r, light.r),
+                lerp(dark.g, light.g),
+                lerp(dark.b, light.b),
+            );
+        }
+
+        for pixel in &mut self.data {
+            let (r, g, b, a) = pixel.as_rgba_tuple();
+            let L(luminance) = GrayscaleWeights::Rec601.luminance(Rgb::new(r, g, b));
+            let (r, g, b) = ramp[luminance as usize];
+            *pixel = P::from_rgba_tuple((r, g, b, a));
+        }
+    }
+
+    /// Takes this image and maps its luminance onto a gradient between the two given colors.
+    /// Useful for method chaining.
+    ///
+    /// See [`Self::duotone`] for more information.
+    #[must_use]
+    pub fn duotoned(mut self, dark: Rgb, light: Rgb) -> Self
+    where
+        P: TrueColor,
+    {
+        self.duotone(dark, light);
+        self
+    }
+
+    /// Applies a sepia tone to this image in place, giving it the brown tint of old photographs.
+    /// Alpha is left untouched.
+    pub fn sepia(&mut self)
+    where
+        P: TrueColor,
+    {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let channel = |[wr, wg, wb]: [f32; 3], r: f32, g: f32, b: f32| {
+            wb.mul_add(b, wr.mul_add(r, wg * g)).round().min(255.0) as u8
+        };
+
+        for pixel in &mut self.data {
+            let (r, g, b, a) = pixel.as_rgba_tuple();
+            let (r, g, b) = (f32::from(r), f32::from(g), f32::from(b));
+
+            *pixel = P::from_rgba_tuple((
+                channel([0.393, 0.769, 0.189], r, g, b),
+                channel([0.349, 0.686, 0.168], r, g, b),
+                channel([0.272, 0.534, 0.131], r, g, b),
+                a,
+            ));
+        }
+    }
+
+    /// Takes this image and applies a sepia tone to it. Useful for method chaining.
+    ///
+    /// See [`Self::sepia`] for more information.
+    #[must_use]
+    pub fn sepia_toned(mut self) -> Self
+    where
+        P: TrueColor,
+    {
+        self.sepia();
+        self
+    }
+
+    /// Maps the color components of every pixel in this float image into the range `0.0..=1.0`
+    /// in place, using the given tone mapping operator. Alpha is left untouched.
+    ///
+    /// # See Also
+    /// * [`Tonemap`] for the available operators.
+    /// * [`Self::tonemapped`] for a version of this method that takes ownership of the image.
+    pub fn tonemap(&mut self, operator: Tonemap)
+    where
+        P: Pixel<Subpixel = f32>,
+    {
+        self.data.iter_mut().for_each(|pixel| {
+            *pixel = pixel.map_subpixels(|c| operator.apply(c), |a| a);
+        });
+    }
+
+    /// Takes this float image and tone maps it using the given operator. Useful for method
+    /// chaining, for example to convert a high dynamic range image into an 8-bit image:
+    ///
+    /// ```
+    /// # use ril::prelude::*;
+    /// let hdr = Image::new(2, 2, Rgb32F::new(4.0, 1.0, 0.25));
+    /// let image = hdr.tonemapped(Tonemap::Reinhard).convert::<Rgb>();
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(204, 128, 51));
+    /// ```
+    #[must_use]
+    pub fn tonemapped(mut self, operator: Tonemap) -> Self
+    where
+        P: Pixel<Subpixel = f32>,
+    {
+        self.tonemap(operator);
+        self
+    }
+
+    /// Returns the palette paletted pixels written into this image must be bound to, or `None`
+    /// if the pixels of this image are not paletted.
+    ///
+    /// # Safety
+    /// The lifetime of the palette is detached from this image, so that pixels can be rebound to
+    /// it while the image is mutated. It must not be used after this image is dropped or its
+    /// palette is replaced.
+    pub(crate) unsafe fn detached_palette<'p>(&self) -> Option<&'p [P::Color]> {
+        self.palette
+            .as_deref()
+            .filter(|_| P::COLOR_TYPE.is_paletted())
+            .map(|slice| core::slice::from_raw_parts(slice.as_ptr(), slice.len()))
+    }
+
     /// Returns the palette associated with this image as a slice.
     /// If there is no palette, this returns `None`.
     #[must_use]
@@ -1249,6 +3732,104 @@ impl<P: Pixel> Image<P> {
         self.palette.as_mut().unwrap_unchecked()
     }
 
+    /// Returns an iterator over the palette index of every pixel in this paletted image.
+    pub fn palette_indices<'a>(&self) -> impl Iterator<Item = u8> + '_
+    where
+        Self: 'a,
+        P: Paletted<'a>,
+    {
+        self.data.iter().map(|pixel| pixel.palette_index())
+    }
+
+    /// Remaps the palette index of every pixel in this paletted image using the given function,
+    /// for example to swap two colors of the palette. The palette itself is left untouched.
+    ///
+    /// # Panics
+    /// * A remapped index is out of bounds of the palette.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::<PalettedRgb>::from_paletted_pixels(
+    ///     2,
+    ///     vec![Rgb::white(), Rgb::black()],
+    ///     &[0, 1, 0, 1],
+    /// );
+    /// image.remap_palette_indices(|index| 1 - index);
+    ///
+    /// assert_eq!(image.pixel(0, 0).color(), Rgb::black());
+    /// ```
+    pub fn remap_palette_indices<'a>(&mut self, mut f: impl FnMut(u8) -> u8)
+    where
+        Self: 'a,
+        P: Paletted<'a>,
+    {
+        self.data.iter_mut().for_each(|pixel| {
+            let palette = pixel.palette();
+            let index = f(pixel.palette_index());
+            assert!(
+                (index as usize) < palette.len(),
+                "palette index {index} is out of bounds for a palette of {} colors",
+                palette.len(),
+            );
+
+            *pixel = P::from_palette(palette, index);
+        });
+    }
+
+    /// Replaces the palette of this paletted image with the given palette. The palette indices of
+    /// all pixels are kept, i.e. pixels will now resolve to the color at the same index in the new
+    /// palette.
+    ///
+    /// # Panics
+    /// * The new palette is too small for a palette index used by this image.
+    pub fn set_palette<'a>(&mut self, palette: Vec<P::Color>)
+    where
+        Self: 'a,
+        P: Paletted<'a>,
+    {
+        let indices = self.palette_indices().collect::<Vec<_>>();
+        assert!(
+            indices
+                .iter()
+                .all(|&index| (index as usize) < palette.len()),
+            "palette is too small for the palette indices used by this image",
+        );
+
+        let image = Self::from_paletted_pixels(self.width(), palette, indices);
+        // The pixels of the new image reference the heap allocation of the new palette, which
+        // does not move when the box itself is moved into this image.
+        self.data = image.data;
+        self.palette = image.palette;
+    }
+
+    /// Removes unused and duplicate colors from the palette of this paletted image, remapping the
+    /// palette indices of all pixels accordingly. Colors are ordered by their first occurrence in
+    /// the image.
+    #[cfg(feature = "std")]
+    pub fn compact_palette<'a>(&mut self)
+    where
+        Self: 'a,
+        P: Paletted<'a>,
+    {
+        let mut palette = Vec::new();
+        let mut lookup = HashMap::new();
+        let indices = self
+            .data
+            .iter()
+            .map(|pixel| {
+                *lookup.entry(pixel.color()).or_insert_with(|| {
+                    palette.push(pixel.color());
+                    (palette.len() - 1) as u8
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let image = Self::from_paletted_pixels(self.width(), palette, indices);
+        self.data = image.data;
+        self.palette = image.palette;
+    }
+
     /// Maps the palette of this image using the given function. If this image has no palette,
     /// this will do nothing.
     ///
@@ -1279,7 +3860,7 @@ impl<P: Pixel> Image<P> {
                     U::from_raw_parts_paletted(
                         U::COLOR_TYPE,
                         U::BIT_DEPTH,
-                        &[p.palette_index().into() as u8],
+                        &[p.palette_index()],
                         palette.as_deref(),
                     )
                     .expect("could not perform safe conversion of palette references")
@@ -1288,46 +3869,504 @@ impl<P: Pixel> Image<P> {
             format: self.format,
             overlay: self.overlay,
             palette,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Takes this image and flattens this paletted image into an unpaletted image. This is similar
+    /// to [`Self::convert`] but the output type is automatically resolved.
+    #[must_use = "the image is consumed by this method and returns a new image"]
+    pub fn flatten_palette<'a>(self) -> Image<P::Color>
+    where
+        Self: 'a,
+        P: Paletted<'a>,
+    {
+        self.map_pixels(|pixel| pixel.color())
+    }
+
+    /// Quantizes this image using its colors and turns it into its paletted counterpart.
+    /// This currently only works with 8-bit palettes.
+    ///
+    /// This is similar to [`Self::convert`] but the output type is automatically resolved.
+    /// This is also the inverse conversion of [`Self::flatten_palette`].
+    ///
+    /// # Errors
+    /// * The palette could not be created.
+    ///
+    /// # See Also
+    /// * [`Quantizer`] - Implementation of the core quantizer. Use this for more fine-grained
+    /// control over the quantization process, such as adjusting the quantization speed.
+    #[cfg(feature = "std")]
+    #[must_use = "the image is consumed by this method and returns a new image"]
+    pub fn quantize<'p, T>(self, palette_size: u8) -> Image<T>
+    where
+        Self: 'p,
+        P: TrueColor,
+        T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
+    {
+        let width = self.width();
+        let (palette, pixels) = crate::quantize::Quantizer::new()
+            .with_palette_size(palette_size as usize)
+            .quantize(self.data)
+            .expect("unable to quantize image");
+
+        Image::from_paletted_pixels(width, palette, pixels)
+    }
+
+    /// Quantizes this image using its colors with the given quantization method and turns it
+    /// into its paletted counterpart. This currently only works with 8-bit palettes.
+    ///
+    /// # Panics
+    /// * The palette could not be created.
+    ///
+    /// # See Also
+    /// * [`Self::quantize`] - Quantizes using the default method.
+    /// * [`Quantizer`] - Implementation of the core quantizer. Use this for more fine-grained
+    /// control over the quantization process.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let pixels = [Rgb::new(0, 0, 200), Rgb::new(0, 0, 210), Rgb::new(255, 0, 180)];
+    /// let image = Image::from_pixels(3, pixels);
+    ///
+    /// let quantized: Image<PalettedRgb> = image.quantize_with_method(2, QuantizeMethod::MedianCut);
+    /// assert_eq!(
+    ///     quantized.palette(),
+    ///     Some(&[Rgb::new(0, 0, 205), Rgb::new(255, 0, 180)][..]),
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use = "the image is consumed by this method and returns a new image"]
+    pub fn quantize_with_method<'p, T>(self, palette_size: u8, method: QuantizeMethod) -> Image<T>
+    where
+        Self: 'p,
+        P: TrueColor,
+        T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
+    {
+        let width = self.width();
+        let (palette, pixels) = crate::quantize::Quantizer::new()
+            .with_palette_size(palette_size as usize)
+            .with_method(method)
+            .quantize(self.data)
+            .expect("unable to quantize image");
+
+        Image::from_paletted_pixels(width, palette, pixels)
+    }
+
+    /// Quantizes this image using the given [`Quantizer`] and turns it into its paletted
+    /// counterpart. Unlike the other quantization methods, this applies dithering if it is
+    /// enabled on the quantizer.
+    ///
+    /// # Panics
+    /// * The palette could not be created.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// use ril::Quantizer;
+    ///
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgb>::open("photo.png")?;
+    /// let quantizer = Quantizer::new()
+    ///     .with_palette_size(16)
+    ///     .with_dither(Dither::FloydSteinberg);
+    ///
+    /// let quantized: Image<PalettedRgb> = image.quantize_with(&quantizer);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use = "the image is consumed by this method and returns a new image"]
+    pub fn quantize_with<'p, T>(self, quantizer: &Quantizer) -> Image<T>
+    where
+        Self: 'p,
+        P: TrueColor,
+        T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
+    {
+        let (palette, pixels) = quantizer
+            .quantize_image(&self)
+            .expect("unable to quantize image");
+
+        Image::from_paletted_pixels(self.width(), palette, pixels)
+    }
+
+    /// Maps this image to the closest colors of the given palette, optionally dithering it, and
+    /// turns it into its paletted counterpart. Unlike the other quantization methods, this does
+    /// not generate a palette, which is useful to share a palette between multiple images or to
+    /// restrict an image to a prescribed set of colors.
+    ///
+    /// # Panics
+    /// * The palette is empty or has more than 256 colors.
+    ///
+    /// # See Also
+    /// * [`Quantizer::map_to_palette`] - Maps pixels to a palette without creating an image.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::new(4, 4, Rgb::new(128, 128, 128));
+    /// let palette = vec![Rgb::black(), Rgb::white()];
+    ///
+    /// // Dithering approximates gray with a pattern of black and white pixels
+    /// let dithered: Image<PalettedRgb> =
+    ///     image.quantize_to_palette(palette, Some(Dither::FloydSteinberg));
+    /// let white = dithered.data.iter().filter(|p| p.color() == Rgb::white()).count();
+    /// assert_eq!(white, 8);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use = "the image is consumed by this method and returns a new image"]
+    pub fn quantize_to_palette<'p, T>(self, palette: Vec<P>, dither: Option<Dither>) -> Image<T>
+    where
+        Self: 'p,
+        P: TrueColor,
+        T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
+    {
+        let quantizer = Quantizer {
+            dither,
+            ..Quantizer::new()
+        };
+        let pixels = quantizer.map_to_palette(&self, &palette);
+
+        Image::from_paletted_pixels(self.width(), palette, pixels)
+    }
+
+    /// Reduces every color channel of this image to the given amount of bits in place, using the
+    /// given dithering algorithm to hide the resulting banding. The alpha channel is left
+    /// untouched.
+    ///
+    /// # Panics
+    /// * `bits` is not in the range `1..=8`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(4, 4, Rgb::new(128, 128, 128));
+    /// image.dither(1, Dither::Bayer2x2);
+    ///
+    /// // Each channel is either fully on or fully off, and about half of them are on.
+    /// assert!(image.pixels().flatten().all(|p| p.r == 0 || p.r == 255));
+    /// assert_eq!(image.pixels().flatten().filter(|p| p.r == 255).count(), 8);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn dither(&mut self, bits: u8, dither: Dither)
+    where
+        P: TrueColor,
+    {
+        assert!(
+            (1..=8).contains(&bits),
+            "bit depth must be between 1 and 8, got {bits}"
+        );
+
+        let levels = f32::from((1_u16 << bits) - 1);
+        let step = 255.0 / levels;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let quantize = |c: u8| ((f32::from(c) / step).round() * step).round() as u8;
+
+        self.data = crate::quantize::dither_with(
+            &self.data,
+            self.width() as usize,
+            dither,
+            step,
+            |[r, g, b, a]| {
+                let color = [quantize(r), quantize(g), quantize(b), a];
+                (color, P::from_rgba_tuple((color[0], color[1], color[2], a)))
+            },
+        );
+    }
+
+    /// Takes this image and reduces every color channel to the given amount of bits, using the
+    /// given dithering algorithm. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * `bits` is not in the range `1..=8`.
+    ///
+    /// # See Also
+    /// * [`Self::dither`] for more information.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn dithered(mut self, bits: u8, dither: Dither) -> Self
+    where
+        P: TrueColor,
+    {
+        self.dither(bits, dither);
+        self
+    }
+
+    /// Maps the red, green and blue channels of every pixel through the given lookup tables,
+    /// leaving the alpha channel untouched.
+    pub(crate) fn map_rgb_with_luts(&mut self, [r, g, b]: &[[u8; 256]; 3])
+    where
+        P: TrueColor,
+    {
+        for pixel in &mut self.data {
+            let (pr, pg, pb, pa) = pixel.as_rgba_tuple();
+            *pixel = P::from_rgba_tuple((r[pr as usize], g[pg as usize], b[pb as usize], pa));
+        }
+    }
+
+    /// Stretches the color channels of this image in place so that they span the full range of
+    /// values, making washed out images look more vivid.
+    ///
+    /// `clip_percent` is the percentage of the darkest and of the brightest pixels to ignore when
+    /// determining the range of the image, making this robust against a few outlying pixels.
+    /// All channels are stretched by the same amount, which preserves the color balance of the
+    /// image. Use [`Self::auto_levels`] to stretch each channel independently instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(2, 1, |x, _| Rgb::new(100 + x as u8 * 50, 120, 110));
+    /// image.auto_contrast(0.0);
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(0, 102, 51));
+    /// assert_eq!(image.pixel(1, 0), &Rgb::new(255, 102, 51));
+    /// ```
+    pub fn auto_contrast(&mut self, clip_percent: f64)
+    where
+        P: TrueColor,
+    {
+        let histogram = Histogram::from_rgb(self);
+        let fraction = clip_percent / 100.0;
+        let low = (0..3)
+            .map(|c| histogram.percentile(c, fraction))
+            .min()
+            .unwrap_or(0);
+        let high = (0..3)
+            .map(|c| histogram.percentile(c, 1.0 - fraction))
+            .max()
+            .unwrap_or(255);
+
+        let lut = stretch_lut(low, high);
+        self.map_rgb_with_luts(&[lut; 3]);
+    }
+
+    /// Takes this image and stretches its color channels to span the full range of values.
+    /// Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::auto_contrast`] for more information.
+    #[must_use]
+    pub fn auto_contrasted(mut self, clip_percent: f64) -> Self
+    where
+        P: TrueColor,
+    {
+        self.auto_contrast(clip_percent);
+        self
+    }
+
+    /// Stretches each color channel of this image independently in place so that it spans the
+    /// full range of values. This also neutralizes color casts, since the channels are balanced
+    /// against each other.
+    ///
+    /// `clip_percent` is the percentage of the darkest and of the brightest values of each channel
+    /// to ignore when determining its range.
+    pub fn auto_levels(&mut self, clip_percent: f64)
+    where
+        P: TrueColor,
+    {
+        let histogram = Histogram::from_rgb(self);
+        let fraction = clip_percent / 100.0;
+        let luts = [0, 1, 2].map(|c| {
+            stretch_lut(
+                histogram.percentile(c, fraction),
+                histogram.percentile(c, 1.0 - fraction),
+            )
+        });
+
+        self.map_rgb_with_luts(&luts);
+    }
+
+    /// Takes this image and stretches each of its color channels independently to span the full
+    /// range of values. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::auto_levels`] for more information.
+    #[must_use]
+    pub fn auto_leveled(mut self, clip_percent: f64) -> Self
+    where
+        P: TrueColor,
+    {
+        self.auto_levels(clip_percent);
+        self
+    }
+
+    /// Equalizes the histogram of this image in place, spreading its most frequent values over the
+    /// full range of values. This enhances the contrast of images with details hidden in a narrow
+    /// range of values, such as underexposed photos, at the cost of exaggerating noise.
+    ///
+    /// The red, green and blue channels are counted together and mapped through the same lookup
+    /// table, which preserves the color balance of the image. The alpha channel is left untouched.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(4, 1, |x, _| L(100 + x as u8));
+    /// image.equalize();
+    ///
+    /// assert_eq!(image.pixel(0, 0), &L(0));
+    /// assert_eq!(image.pixel(1, 0), &L(85));
+    /// assert_eq!(image.pixel(3, 0), &L(255));
+    /// ```
+    #[allow(clippy::cast_precision_loss)]
+    pub fn equalize(&mut self)
+    where
+        P: TrueColor,
+    {
+        let histogram = Histogram::from_rgb(self);
+        let mut cdf = [0_u64; 256];
+        let mut total = 0;
+        for (value, count) in cdf.iter_mut().enumerate() {
+            total += histogram
+                .channels()
+                .map(|bins| u64::from(bins[value]))
+                .sum::<u64>();
+            *count = total;
+        }
+
+        // The darkest value of the image is mapped to zero, since values below it are never used
+        let first = cdf.iter().copied().find(|&count| count > 0).unwrap_or(0);
+        if total <= first {
+            return;
         }
+
+        let scale = 255.0 / (total - first) as f64;
+        let lut = lut_from_fn(|value| cdf[value as usize].saturating_sub(first) as f64 * scale);
+        self.map_rgb_with_luts(&[lut; 3]);
     }
 
-    /// Takes this image and flattens this paletted image into an unpaletted image. This is similar
-    /// to [`Self::convert`] but the output type is automatically resolved.
-    #[must_use = "the image is consumed by this method and returns a new image"]
-    pub fn flatten_palette<'a>(self) -> Image<P::Color>
+    /// Takes this image and equalizes its histogram. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::equalize`] for more information.
+    #[must_use]
+    pub fn equalized(mut self) -> Self
     where
-        Self: 'a,
-        P: Paletted<'a>,
+        P: TrueColor,
     {
-        self.map_pixels(|pixel| pixel.color())
+        self.equalize();
+        self
     }
 
-    /// Quantizes this image using its colors and turns it into its paletted counterpart.
-    /// This currently only works with 8-bit palettes.
+    /// Linearly stretches the color channels of this image in place so that its darkest value
+    /// becomes zero and its brightest value becomes the largest value of the pixel type, which is
+    /// `255` for 8-bit, `65535` for 16-bit and `1.0` for floating-point pixel types.
     ///
-    /// This is similar to [`Self::convert`] but the output type is automatically resolved.
-    /// This is also the inverse conversion of [`Self::flatten_palette`].
+    /// Unlike [`Self::auto_contrast`], this works with pixel types of any precision without
+    /// quantizing them to 8 bits, which makes it suitable for 16-bit images and for the results of
+    /// computations stored in floating-point images. All color channels are stretched by the same
+    /// amount and the alpha channel is left untouched.
     ///
-    /// # Errors
-    /// * The palette could not be created.
+    /// # Panics
+    /// * The pixel type is paletted. Use [`Self::flatten_palette`] first.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::from_fn(2, 1, |x, _| L32F(0.25 + x as f32 * 0.25));
+    /// image.normalize();
+    ///
+    /// assert_eq!(image.pixel(0, 0), &L32F(0.0));
+    /// assert_eq!(image.pixel(1, 0), &L32F(1.0));
+    /// ```
+    pub fn normalize(&mut self)
+    where
+        P::Subpixel: ToPrimitive + num_traits::NumCast,
+    {
+        assert!(
+            !P::COLOR_TYPE.is_paletted(),
+            "cannot normalize paletted images, flatten their palettes first"
+        );
+
+        let channels = P::NUM_CHANNELS.saturating_sub(usize::from(P::COLOR_TYPE.has_alpha()));
+        let (mut low, mut high) = (f64::INFINITY, f64::NEG_INFINITY);
+        for pixel in &self.data {
+            for value in (0..channels).filter_map(|i| pixel.channel(i).to_f64()) {
+                low = low.min(value);
+                high = high.max(value);
+            }
+        }
+        if high <= low {
+            return;
+        }
+
+        let integer = P::BIT_DEPTH <= 16;
+        let full = if integer {
+            f64::from((1_u32 << P::BIT_DEPTH) - 1)
+        } else {
+            1.0
+        };
+        let scale = full / (high - low);
+        let stretch = |subpixel: P::Subpixel| {
+            subpixel
+                .to_f64()
+                .map(|value| {
+                    let value = (value - low) * scale;
+                    if integer {
+                        value.round()
+                    } else {
+                        value
+                    }
+                })
+                .and_then(num_traits::NumCast::from)
+                .unwrap_or(subpixel)
+        };
+
+        for pixel in &mut self.data {
+            *pixel = pixel.map_subpixels(stretch, |alpha| alpha);
+        }
+    }
+
+    /// Takes this image and linearly stretches its color channels to span the full range of values
+    /// of its pixel type. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * The pixel type is paletted.
     ///
     /// # See Also
-    /// * [`Quantizer`] - Implementation of the core quantizer. Use this for more fine-grained
-    /// control over the quantization process, such as adjusting the quantization speed.
-    #[must_use = "the image is consumed by this method and returns a new image"]
-    pub fn quantize<'p, T>(self, palette_size: u8) -> Image<T>
+    /// * [`Self::normalize`] for more information.
+    #[must_use]
+    pub fn normalized(mut self) -> Self
     where
-        Self: 'p,
-        P: TrueColor,
-        T: Pixel<Color = P> + Paletted<'p, Subpixel = u8>,
+        P::Subpixel: ToPrimitive + num_traits::NumCast,
     {
-        let width = self.width();
-        let (palette, pixels) = crate::quantize::Quantizer::new()
-            .with_palette_size(palette_size as usize)
-            .quantize(self.data)
-            .expect("unable to quantize image");
+        self.normalize();
+        self
+    }
+}
 
-        Image::from_paletted_pixels(width, palette, pixels)
+impl Image<Rgb32F> {
+    /// Tone maps this high dynamic range image in linear light into the displayable range using
+    /// the given operator, then encodes it into an 8-bit sRGB image. This is the usual way to save
+    /// HDR or EXR images as PNG or JPEG.
+    ///
+    /// Unlike [`Self::tonemapped`] followed by [`Self::convert`], this applies the sRGB transfer
+    /// function, so midtones don't look too dark.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let hdr = Image::new(2, 2, Rgb32F::new(4.0, 0.18, 0.0));
+    /// let image = hdr.to_sdr(Tonemap::Aces);
+    ///
+    /// assert_eq!(image.pixel(0, 0).b, 0);
+    /// assert!(image.pixel(0, 0).r > 240);
+    /// ```
+    #[must_use]
+    pub fn to_sdr(&self, operator: Tonemap) -> Image<Rgb> {
+        self.clone()
+            .map_pixels(|pixel| Rgb::from_linear(pixel.map_subpixels(|c| operator.apply(c), |a| a)))
+    }
+}
+
+impl Image<Rgba32F> {
+    /// Tone maps this high dynamic range image in linear light into the displayable range using
+    /// the given operator, then encodes it into an 8-bit sRGB image. Alpha is left untouched.
+    #[must_use]
+    pub fn to_sdr(&self, operator: Tonemap) -> Image<Rgba> {
+        self.clone().map_pixels(|pixel| {
+            Rgba::from_linear(pixel.map_subpixels(|c| operator.apply(c), |a| a))
+        })
     }
 }
 
@@ -1406,12 +4445,28 @@ impl Image<Rgba> {
     /// channel.
     /// * [`Self::split_rgb_and_alpha`] - If you need to operate on the entire `Image<Rgb>`
     /// (and `Image<L>`).
+    /// * [`Self::map_rgb_in_place`] for a version that operates in-place
     #[must_use]
-    pub fn map_rgb_pixels(self, mut f: impl FnMut(Rgb) -> Rgb) -> Self {
-        self.map_pixels(|Rgba { r, g, b, a }| {
-            let Rgb { r, g, b } = f(Rgb { r, g, b });
-            Rgba { r, g, b, a }
-        })
+    pub fn map_rgb_pixels(mut self, f: impl FnMut(Rgb) -> Rgb) -> Self {
+        self.map_rgb_in_place(f);
+        self
+    }
+
+    /// Performs the given operation `f` on every pixel in this image in place, ignoring the alpha
+    /// channel. The alpha channel is left untouched.
+    ///
+    /// # See Also
+    /// * [`Self::map_rgb_pixels`] for more information.
+    pub fn map_rgb_in_place(&mut self, mut f: impl FnMut(Rgb) -> Rgb) {
+        for pixel in &mut self.data {
+            let Rgb { r, g, b } = f(Rgb::new(pixel.r, pixel.g, pixel.b));
+            *pixel = Rgba {
+                r,
+                g,
+                b,
+                a: pixel.a,
+            };
+        }
     }
 
     /// Performs the given operation `f` on every pixel in the alpha channel of this image.
@@ -1421,14 +4476,19 @@ impl Image<Rgba> {
     /// * [`Self::map_rgb_pixels`] - Performs the given operation on every pixel in the RGB channels.
     /// * [`Self::split_rgb_and_alpha`] - If you need to operate on the entire `Image<L>`
     /// (and `Image<Rgb>`).
+    /// * [`Self::map_alpha_in_place`] for a version that operates in-place
     #[must_use]
-    pub fn map_alpha_pixels(self, mut f: impl FnMut(L) -> L) -> Self {
-        self.map_pixels(|Rgba { r, g, b, a }| Rgba {
-            r,
-            g,
-            b,
-            a: f(L(a)).value(),
-        })
+    pub fn map_alpha_pixels(mut self, f: impl FnMut(L) -> L) -> Self {
+        self.map_alpha_in_place(f);
+        self
+    }
+
+    /// Performs the given operation `f` on every pixel in the alpha channel of this image in
+    /// place. The RGB channels are left untouched.
+    pub fn map_alpha_in_place(&mut self, mut f: impl FnMut(L) -> L) {
+        for pixel in &mut self.data {
+            pixel.a = f(L(pixel.a)).value();
+        }
     }
 
     /// Creates a stroke around this image. This is a shorthand for using the
@@ -1438,13 +4498,292 @@ impl Image<Rgba> {
         self.draw(&crate::morph::Stroke::new(&src, size, color).with_threshold(threshold));
     }
 
+    /// Adds padding to this image in place with enough space to fit the stroke, and adds stroke
+    /// to it.
+    pub fn pad_stroke(&mut self, size: u32, color: Rgba, threshold: u8) {
+        self.pad(Rgba::transparent(), size, size, size, size);
+        self.stroke(size, color, threshold);
+    }
+
     /// Adds padding to this image with enough space to fit
     /// the stroke, and adds stroke to it.
+    ///
+    /// # See Also
+    /// * [`Self::pad_stroke`] for a version that operates in-place
+    #[must_use]
+    pub fn padded_stroke(mut self, size: u32, color: Rgba, threshold: u8) -> Self {
+        self.pad_stroke(size, color, threshold);
+        self
+    }
+}
+
+impl Image<Cmyk> {
+    /// Converts this CMYK image into an RGB image using the given ICC profile, which describes
+    /// the CMYK color space of this image. The resulting image is in the sRGB color space.
+    ///
+    /// This is far more accurate than the naive conversion performed by [`Image::convert`], since
+    /// CMYK colors depend heavily on the inks and paper they were intended for.
+    ///
+    /// # Errors
+    /// * The ICC profile is invalid or is not a CMYK profile.
+    #[cfg(feature = "icc")]
+    pub fn to_rgb_with_icc_profile(&self, profile: &[u8]) -> Result<Image<Rgb>> {
+        use lcms2::{Intent, PixelFormat, Profile, Transform};
+
+        let input = Profile::new_icc(profile)?;
+        let output = Profile::new_srgb();
+        let transform = Transform::new(
+            &input,
+            PixelFormat::CMYK_8,
+            &output,
+            PixelFormat::RGB_8,
+            Intent::Perceptual,
+        )?;
+
+        let src = self.data.iter().map(Pixel::as_bytes).collect::<Vec<_>>();
+        let mut dst = vec![[0_u8; 3]; src.len()];
+        transform.transform_pixels(&src, &mut dst);
+
+        Ok(Image {
+            width: self.width,
+            height: self.height,
+            data: dst.into_iter().map(|[r, g, b]| Rgb::new(r, g, b)).collect(),
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+        })
+    }
+}
+
+macro_rules! impl_linear_conversions {
+    ($($srgb:ident => $linear:ident),+) => {
+        $(
+            impl Image<$srgb> {
+                /// Converts this image from gamma-encoded sRGB into linear light, which is the
+                /// space physically correct color math such as blending or resampling should be
+                /// performed in. Alpha is left untouched.
+                ///
+                /// This uses a lookup table, so it is cheap compared to converting each channel
+                /// with [`crate::color::srgb_to_linear`].
+                #[must_use]
+                pub fn to_linear(&self) -> Image<$linear> {
+                    Image {
+                        width: self.width,
+                        height: self.height,
+                        data: self.data.iter().map(|pixel| pixel.to_linear()).collect(),
+                        format: self.format,
+                        overlay: self.overlay,
+                        palette: None,
+                        metadata: self.metadata.clone(),
+                    }
+                }
+            }
+
+            impl Image<$linear> {
+                /// Converts this image from linear light into gamma-encoded sRGB, clamping every
+                /// channel into the range `0.0..=1.0` first. Alpha is left untouched.
+                #[must_use]
+                pub fn to_srgb(&self) -> Image<$srgb> {
+                    Image {
+                        width: self.width,
+                        height: self.height,
+                        data: self.data.iter().map(|&pixel| $srgb::from_linear(pixel)).collect(),
+                        format: self.format,
+                        overlay: self.overlay,
+                        palette: None,
+                        metadata: self.metadata.clone(),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_linear_conversions!(L => L32F, Rgb => Rgb32F, Rgba => Rgba32F);
+
+impl Image<L> {
+    fn zip_band(&self, other: &Self, f: impl Fn(u8, u8) -> u8) -> Self {
+        assert_eq!(
+            self.dimensions(),
+            other.dimensions(),
+            "bands must have the same dimensions"
+        );
+
+        Self {
+            width: self.width,
+            height: self.height,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&L(a), &L(b))| L(f(a, b)))
+                .collect(),
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Adds the values of the given band to the values of this band, saturating at `255`.
+    ///
+    /// # Panics
+    /// * The bands have different dimensions.
+    #[must_use]
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        self.zip_band(other, u8::saturating_add)
+    }
+
+    /// Subtracts the values of the given band from the values of this band, saturating at `0`.
+    ///
+    /// # Panics
+    /// * The bands have different dimensions.
+    #[must_use]
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        self.zip_band(other, u8::saturating_sub)
+    }
+
+    /// Multiplies the values of this band with the values of the given band, where `255`
+    /// represents `1.0`. This is useful for applying a mask to a band.
+    ///
+    /// # Panics
+    /// * The bands have different dimensions.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let (r, g, b) = Image::new(1, 1, Rgb::new(200, 100, 50)).bands();
+    /// let mask = Image::new(1, 1, L(128));
+    ///
+    /// let image = Image::<Rgb>::from_bands((r.multiply(&mask), g, b.saturating_add(&mask)));
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(100, 100, 178));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn multiply(&self, other: &Self) -> Self {
+        self.zip_band(other, |a, b| {
+            ((u16::from(a) * u16::from(b) + 127) / 255) as u8
+        })
+    }
+
+    /// Turns this image into a binary image, where pixels brighter than the given threshold are
+    /// on and all other pixels are off.
+    #[must_use]
+    pub fn threshold(&self, threshold: u8) -> Image<BitPixel> {
+        self.clone()
+            .map_pixels(|L(value)| BitPixel(value > threshold))
+    }
+
+    /// Computes the optimal global threshold of this image using Otsu's method, which picks the
+    /// threshold that best separates the histogram into a dark and a bright class.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn otsu_threshold(&self) -> u8 {
+        let histogram = self.luminance_histogram();
+        let bins = histogram.channel(0).unwrap_or(&[]);
+        let total = histogram.pixel_count() as f64;
+        let sum = histogram.mean(0) * total;
+
+        let (mut best, mut best_variance) = (0, -1.0);
+        let (mut weight, mut partial_sum) = (0.0, 0.0);
+        for (value, &count) in bins.iter().enumerate() {
+            weight += f64::from(count);
+            partial_sum += value as f64 * f64::from(count);
+            if weight == 0.0 || weight == total {
+                continue;
+            }
+
+            let dark_mean = partial_sum / weight;
+            let bright_mean = (sum - partial_sum) / (total - weight);
+            let variance = weight * (total - weight) * (dark_mean - bright_mean).powi(2);
+            if variance > best_variance {
+                best = value;
+                best_variance = variance;
+            }
+        }
+
+        best as u8
+    }
+
+    /// Turns this image into a binary image using the threshold computed by Otsu's method.
+    ///
+    /// This works well for images with a bimodal histogram, such as scanned documents or objects
+    /// on a plain background, but not for unevenly lit images. Use [`Self::threshold_adaptive`]
+    /// for those instead.
+    ///
+    /// # See Also
+    /// * [`Self::otsu_threshold`] for computing the threshold itself.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(4, 1, |x, _| L([20, 30, 200, 210][x as usize]));
+    /// let binary = image.threshold_otsu();
+    ///
+    /// assert_eq!(binary.data, [BitPixel::off(), BitPixel::off(), BitPixel::on(), BitPixel::on()]);
+    /// ```
+    #[must_use]
+    pub fn threshold_otsu(&self) -> Image<BitPixel> {
+        self.threshold(self.otsu_threshold())
+    }
+
+    /// Turns this image into a binary image using a threshold which adapts to the local
+    /// brightness of the image. A pixel is on if it is brighter than the mean of the
+    /// `block_size` by `block_size` area around it, minus the constant `c`.
+    ///
+    /// Unlike global thresholds, this handles uneven lighting, such as shadows across a
+    /// photographed document. Larger blocks are less sensitive to fine details, and larger values
+    /// of `c` turn fewer pixels on in flat areas.
+    ///
+    /// # Panics
+    /// * `block_size` is zero.
     #[must_use]
-    pub fn padded_stroke(self, size: u32, color: Rgba, threshold: u8) -> Self {
-        let mut out = self.padded(Rgba::transparent(), size, size, size, size);
-        out.stroke(size, color, threshold);
-        out
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn threshold_adaptive(&self, block_size: u32, c: i16) -> Image<BitPixel> {
+        assert_ne!(block_size, 0, "block size must be greater than zero");
+
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        // Summed area table with an extra leading row and column of zeroes
+        let mut integral = vec![0_u64; (width + 1) * (height + 1)];
+        for y in 0..height {
+            let mut row_sum = 0;
+            for x in 0..width {
+                row_sum += u64::from(self.data[y * width + x].0);
+                integral[(y + 1) * (width + 1) + x + 1] =
+                    integral[y * (width + 1) + x + 1] + row_sum;
+            }
+        }
+
+        let radius = (block_size / 2) as usize;
+        let data = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, &L(value))| {
+                let (x, y) = (i % width, i / width);
+                let (x1, y1) = (x.saturating_sub(radius), y.saturating_sub(radius));
+                let (x2, y2) = ((x + radius + 1).min(width), (y + radius + 1).min(height));
+
+                let sum = integral[y2 * (width + 1) + x2] + integral[y1 * (width + 1) + x1]
+                    - integral[y1 * (width + 1) + x2]
+                    - integral[y2 * (width + 1) + x1];
+                let area = ((x2 - x1) * (y2 - y1)) as u64;
+
+                // Compare `value > sum / area - c` without dividing
+                (i64::from(value) + i64::from(c)) * area as i64 > sum as i64
+            })
+            .map(BitPixel)
+            .collect();
+
+        Image {
+            width: self.width,
+            height: self.height,
+            data,
+            format: self.format,
+            overlay: self.overlay,
+            palette: None,
+            metadata: self.metadata.clone(),
+        }
     }
 }
 
@@ -1460,6 +4799,7 @@ impl<'a> From<Image<PalettedRgba<'a>>> for Image<PalettedRgb<'a>> {
     }
 }
 
+#[cfg(feature = "std")]
 macro_rules! impl_cast_quantize {
     ($t:ty: $p:ty) => {
         impl From<Image<$t>> for Image<$p> {
@@ -1476,7 +4816,9 @@ macro_rules! impl_cast_quantize {
     };
 }
 
+#[cfg(feature = "std")]
 impl_cast_quantize!(Rgb: PalettedRgb<'_>);
+#[cfg(feature = "std")]
 impl_cast_quantize!(Rgba: PalettedRgba<'_>);
 
 macro_rules! impl_cast {
@@ -1496,6 +4838,25 @@ impl_cast!(L: BitPixel Rgb Rgba Dynamic PalettedRgb<'_> PalettedRgba<'_>);
 impl_cast!(Rgb: BitPixel L Rgba Dynamic PalettedRgb<'_> PalettedRgba<'_>);
 impl_cast!(Rgba: BitPixel L Rgb Dynamic PalettedRgb<'_> PalettedRgba<'_>);
 impl_cast!(Dynamic: BitPixel L Rgb Rgba PalettedRgb<'_> PalettedRgba<'_>);
+impl_cast!(L: L32F Rgb32F Rgba32F);
+impl_cast!(Rgb: L32F Rgb32F Rgba32F);
+impl_cast!(Rgba: L32F Rgb32F Rgba32F);
+impl_cast!(L32F: L Rgb Rgba Rgb32F Rgba32F);
+impl_cast!(Rgb32F: L Rgb Rgba L32F Rgba32F);
+impl_cast!(Rgba32F: L Rgb Rgba L32F Rgb32F);
+impl_cast!(L16: L Rgb Rgba L32F Rgb16 Rgba16);
+impl_cast!(Rgb16: L Rgb Rgba Rgb32F L16 Rgba16);
+impl_cast!(Rgba16: L Rgb Rgba Rgba32F L16 Rgb16);
+impl_cast!(L: L16 Rgb16 Rgba16);
+impl_cast!(Rgb: L16 Rgb16 Rgba16);
+impl_cast!(Rgba: L16 Rgb16 Rgba16);
+impl_cast!(L32F: L16);
+impl_cast!(Rgb32F: Rgb16);
+impl_cast!(Rgba32F: Rgba16);
+impl_cast!(Cmyk: L Rgb Rgba);
+impl_cast!(L: Cmyk);
+impl_cast!(Rgb: Cmyk);
+impl_cast!(Rgba: Cmyk);
 
 /// Represents an image with multiple channels, called bands.
 ///
@@ -1509,6 +4870,133 @@ pub trait Banded<T> {
 
     /// Creates a new image from the given bands.
     fn from_bands(bands: T) -> Self;
+
+    /// Reorders the bands of this image according to the given pattern, where each character
+    /// selects the band to place at that position:
+    ///
+    /// * `r`, `g`, `b` and `a` select the first, second, third and fourth band respectively.
+    /// * `0` and `1` fill the band with zeroes and with the maximum value respectively.
+    ///
+    /// Characters are case-insensitive.
+    ///
+    /// # Panics
+    /// * The pattern does not have exactly one character per band.
+    /// * The pattern selects a band that does not exist, or contains an unknown character.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::new(1, 1, Rgba::new(10, 20, 30, 40));
+    ///
+    /// assert_eq!(image.swizzle("bgra").pixel(0, 0), &Rgba::new(30, 20, 10, 40));
+    /// assert_eq!(image.swizzle("rrr1").pixel(0, 0), &Rgba::new(10, 10, 10, 255));
+    /// ```
+    #[must_use]
+    fn swizzle(&self, pattern: &str) -> Self
+    where
+        Self: Sized,
+        T: BandTuple,
+    {
+        assert_eq!(
+            pattern.chars().count(),
+            T::LEN,
+            "swizzle pattern {pattern:?} must have exactly one character per band"
+        );
+
+        let first = self.band(0);
+        let bands = pattern
+            .chars()
+            .map(|c| match c.to_ascii_lowercase() {
+                'r' => self.band(0),
+                'g' => self.band(1),
+                'b' => self.band(2),
+                'a' => self.band(3),
+                '0' => Image::new(first.width(), first.height(), L(0)),
+                '1' => Image::new(first.width(), first.height(), L(255)),
+                c => panic!("unknown character {c:?} in swizzle pattern {pattern:?}"),
+            })
+            .collect();
+
+        Self::from_bands(T::from_vec(bands))
+    }
+
+    /// Applies a linear transform to every band of this image, mapping each value `v` of the
+    /// band at index `i` to `v * scales[i] + offsets[i]`, clamped to the range `0..=255`.
+    ///
+    /// # Panics
+    /// * `scales` or `offsets` do not have exactly one value per band.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::new(1, 1, Rgb::new(100, 100, 100));
+    /// let image = image.scale_bands(&[2.0, 1.0, 0.5], &[0.0, 10.0, 0.0]);
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(200, 110, 50));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn scale_bands(&self, scales: &[f32], offsets: &[f32]) -> Self
+    where
+        Self: Sized,
+        T: BandTuple,
+    {
+        assert!(
+            scales.len() == T::LEN && offsets.len() == T::LEN,
+            "expected {} scales and offsets, one for each band",
+            T::LEN,
+        );
+
+        let bands = scales
+            .iter()
+            .zip(offsets)
+            .enumerate()
+            .map(|(i, (&scale, &offset))| {
+                self.band(i).map_pixels(|L(value)| {
+                    L(f32::from(value)
+                        .mul_add(scale, offset)
+                        .round()
+                        .clamp(0.0, 255.0) as u8)
+                })
+            })
+            .collect();
+
+        Self::from_bands(T::from_vec(bands))
+    }
+}
+
+/// A tuple of bands, which is what [`Banded`] images are split into and created from.
+pub trait BandTuple: Sized {
+    /// The amount of bands in this tuple.
+    const LEN: usize;
+
+    /// Creates this tuple from a vector of exactly [`Self::LEN`] bands.
+    ///
+    /// # Panics
+    /// * The vector does not have exactly [`Self::LEN`] bands.
+    fn from_vec(bands: Vec<Band>) -> Self;
+}
+
+impl BandTuple for (Band, Band, Band) {
+    const LEN: usize = 3;
+
+    fn from_vec(bands: Vec<Band>) -> Self {
+        assert_eq!(bands.len(), 3, "expected exactly 3 bands");
+        let mut bands = bands.into_iter();
+        let mut next = || bands.next().unwrap();
+        (next(), next(), next())
+    }
+}
+
+impl BandTuple for (Band, Band, Band, Band) {
+    const LEN: usize = 4;
+
+    fn from_vec(bands: Vec<Band>) -> Self {
+        assert_eq!(bands.len(), 4, "expected exactly 4 bands");
+        let mut bands = bands.into_iter();
+        let mut next = || bands.next().unwrap();
+        (next(), next(), next(), next())
+    }
 }
 
 type Band = Image<L>;
@@ -1524,6 +5012,7 @@ macro_rules! map_idx {
             format: $image.format,
             overlay: $image.overlay,
             palette: None,
+            metadata: $image.metadata.clone(),
         }
     }};
 }
@@ -1596,7 +5085,7 @@ impl Banded<(Band, Band, Band, Band)> for Image<Rgba> {
     }
 }
 
-impl<P: Pixel> std::ops::Not for Image<P> {
+impl<P: Pixel> core::ops::Not for Image<P> {
     type Output = Self;
 
     fn not(self) -> Self::Output {
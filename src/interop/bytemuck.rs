@@ -0,0 +1,37 @@
+//! Implements [`Pod`] and [`Zeroable`] from [`bytemuck`](https://docs.rs/bytemuck) for pixel
+//! types, which allows safely casting slices of pixels such as [`Image::data`] to and from slices
+//! of bytes without copying them.
+//!
+//! # Example
+//! ```
+//! use ril::prelude::*;
+//!
+//! let mut image = Image::new(2, 2, Rgb::new(255, 128, 0));
+//! let bytes: &[u8] = bytemuck::cast_slice(&image.data);
+//! assert_eq!(bytes.len(), 12);
+//! assert_eq!(&bytes[..3], [255, 128, 0]);
+//!
+//! // Casting the other way around writes to the pixels directly
+//! let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut image.data);
+//! bytes[3..6].copy_from_slice(&[0, 0, 255]);
+//! assert_eq!(image.pixel(1, 0), &Rgb::new(0, 0, 255));
+//! ```
+//!
+//! [`Image::data`]: crate::Image::data
+
+use crate::{Cmyk, Rgb, Rgb16, Rgb32F, Rgba, Rgba16, Rgba32F, L, L16, L32F};
+use bytemuck::{Pod, Zeroable};
+
+macro_rules! impl_pod {
+    ($($pixel:ty),+) => {
+        $(
+            // SAFETY: the pixel type is `repr(C)` or `repr(transparent)` and only consists of
+            // fields of the same primitive type, so it has no padding and every bit pattern is
+            // valid.
+            unsafe impl Zeroable for $pixel {}
+            unsafe impl Pod for $pixel {}
+        )+
+    };
+}
+
+impl_pod!(L, Rgb, Rgba, Cmyk, L16, Rgb16, Rgba16, L32F, Rgb32F, Rgba32F);
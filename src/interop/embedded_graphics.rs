@@ -0,0 +1,87 @@
+//! Implements the [`DrawTarget`] trait of
+//! [`embedded-graphics`](https://docs.rs/embedded-graphics) for images, so that its primitives,
+//! fonts and widgets can be rendered straight into an image and then encoded.
+//!
+//! The color of each image is fixed by its pixel type:
+//!
+//! | Pixel type   | `embedded-graphics` color |
+//! |--------------|---------------------------|
+//! | [`BitPixel`] | `BinaryColor`             |
+//! | [`L`]        | `Gray8`                   |
+//! | [`Rgb`]      | `Rgb888`                  |
+//! | [`Rgba`]     | `Rgb888`, always opaque   |
+//!
+//! Pixels outside of the image are ignored.
+//!
+//! # Example
+//! ```ignore
+//! use embedded_graphics::{
+//!     pixelcolor::Rgb888,
+//!     prelude::*,
+//!     primitives::{Circle, PrimitiveStyle},
+//! };
+//! use ril::prelude::*;
+//!
+//! let mut image = Image::new(64, 64, Rgb::black());
+//! Circle::new(Point::new(8, 8), 48)
+//!     .into_styled(PrimitiveStyle::with_fill(Rgb888::RED))
+//!     .draw(&mut image)?;
+//!
+//! image.save(ImageFormat::Png, "circle.png")?;
+//! ```
+
+use crate::{BitPixel, Image, Pixel, Rgb, Rgba, L};
+use core::convert::Infallible;
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{BinaryColor, Gray8, GrayColor, Rgb888, RgbColor},
+    Pixel as EgPixel,
+};
+
+impl<P: Pixel> OriginDimensions for Image<P> {
+    fn size(&self) -> Size {
+        Size::new(self.width(), self.height())
+    }
+}
+
+macro_rules! impl_draw_target {
+    ($($pixel:ty => $color:ty, |$c:ident| $convert:expr;)+) => {
+        $(
+            impl DrawTarget for Image<$pixel> {
+                type Color = $color;
+                type Error = Infallible;
+
+                fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+                where
+                    I: IntoIterator<Item = EgPixel<Self::Color>>,
+                {
+                    let (width, height) = self.dimensions();
+
+                    for EgPixel(point, $c) in pixels {
+                        if let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y)) {
+                            if x < width && y < height {
+                                self.set_pixel(x, y, $convert);
+                            }
+                        }
+                    }
+
+                    Ok(())
+                }
+
+                fn clear(&mut self, $c: Self::Color) -> Result<(), Self::Error> {
+                    self.data.fill($convert);
+
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_draw_target! {
+    BitPixel => BinaryColor, |color| BitPixel(color.is_on());
+    L => Gray8, |color| L(color.luma());
+    Rgb => Rgb888, |color| Rgb::new(color.r(), color.g(), color.b());
+    Rgba => Rgb888, |color| Rgba::new(color.r(), color.g(), color.b(), 255);
+}
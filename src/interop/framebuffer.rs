@@ -0,0 +1,100 @@
+//! Conversions into the `u32` pixel buffers of windowing libraries such as
+//! [`softbuffer`](https://docs.rs/softbuffer) and [`minifb`](https://docs.rs/minifb).
+
+use crate::{Image, TrueColor};
+use alloc::vec::Vec;
+
+/// The layout of each `u32` pixel in a framebuffer, from the most significant to the least
+/// significant byte.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FramebufferFormat {
+    /// A zero byte followed by red, green and blue, also known as `0RGB`. This is the layout
+    /// expected by `softbuffer` and `minifb`. Alpha is discarded.
+    Xrgb,
+    /// Alpha followed by red, green and blue, also known as `ARGB`.
+    Argb,
+}
+
+impl FramebufferFormat {
+    /// Packs the given RGBA components into a single pixel of this format.
+    const fn pack(self, (r, g, b, a): (u8, u8, u8, u8)) -> u32 {
+        let rgb = (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        match self {
+            Self::Xrgb => rgb,
+            Self::Argb => (a as u32) << 24 | rgb,
+        }
+    }
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Packs the pixels of this image into a buffer of `u32` pixels in the given format, with
+    /// rows directly following each other. This can be copied into the buffer of a window
+    /// created with `softbuffer` or `minifb` if the window has the same size as this image.
+    ///
+    /// # See Also
+    /// * [`Self::write_framebuffer`] for writing into an existing buffer with padded rows.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::new(2, 2, Rgba::new(255, 128, 0, 64));
+    ///
+    /// assert_eq!(image.to_framebuffer(FramebufferFormat::Xrgb), [0x00ff_8000; 4]);
+    /// assert_eq!(image.to_framebuffer(FramebufferFormat::Argb), [0x40ff_8000; 4]);
+    /// ```
+    #[must_use]
+    pub fn to_framebuffer(&self, format: FramebufferFormat) -> Vec<u32> {
+        self.data
+            .iter()
+            .map(|pixel| format.pack(pixel.as_rgba_tuple()))
+            .collect()
+    }
+
+    /// Packs the pixels of this image into the given buffer of `u32` pixels in the given format,
+    /// where each row starts `stride` pixels after the start of the previous row. Pixels in the
+    /// padding after each row are left untouched.
+    ///
+    /// This is useful for drawing the image into the top-left corner of a window larger than the
+    /// image, in which case the stride is the width of the window.
+    ///
+    /// # Panics
+    /// * The stride is less than the width of this image.
+    /// * The buffer is too small to hold every row of this image.
+    pub fn write_framebuffer(&self, format: FramebufferFormat, buffer: &mut [u32], stride: usize) {
+        let width = self.width() as usize;
+        assert!(
+            stride >= width,
+            "stride {stride} must not be less than the image width {width}",
+        );
+        assert!(
+            buffer.len() >= stride * (self.height() as usize - 1) + width,
+            "buffer is too small to hold an image with dimensions {}x{} and stride {stride}",
+            self.width(),
+            self.height(),
+        );
+
+        for (row, out) in self.data.chunks_exact(width).zip(buffer.chunks_mut(stride)) {
+            for (pixel, out) in row.iter().zip(out) {
+                *out = format.pack(pixel.as_rgba_tuple());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rgb;
+
+    #[test]
+    fn test_write_framebuffer_with_padding() {
+        let image = Image::from_fn(2, 2, |x, y| Rgb::new(x as u8, y as u8, 1));
+        let mut buffer = vec![u32::MAX; 3 * 2 - 1];
+        image.write_framebuffer(FramebufferFormat::Argb, &mut buffer, 3);
+
+        assert_eq!(
+            buffer,
+            [0xff00_0001, 0xff01_0001, u32::MAX, 0xff00_0101, 0xff01_0101]
+        );
+    }
+}
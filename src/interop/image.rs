@@ -0,0 +1,100 @@
+//! Conversions to and from the buffers of the [`image`](https://docs.rs/image) crate.
+//!
+//! Every conversion copies the pixel data. Converting from the `image` crate fails with
+//! [`Error::EmptyImageError`] if the image has a width or height of zero, since images of this
+//! crate cannot be empty.
+
+use crate::{Error, Image, Result, Rgb, Rgb32F, Rgba, Rgba32F, L, L32F};
+use ::image::{DynamicImage, ImageBuffer, Luma};
+use std::ops::Deref;
+
+macro_rules! impl_buffer_conversions {
+    ($(
+        $pixel:ty => $buffer:ty, $subpixel:ty;
+        |$p:ident| $to:expr;
+        |$c:ident| $from:expr;
+    )+) => {
+        $(
+            impl From<Image<$pixel>> for ImageBuffer<$buffer, Vec<$subpixel>> {
+                fn from(image: Image<$pixel>) -> Self {
+                    let (width, height) = image.dimensions();
+                    let data = image.data.into_iter().flat_map(|$p| $to).collect();
+
+                    Self::from_raw(width, height, data).expect("buffer should fit the dimensions")
+                }
+            }
+
+            impl<C> TryFrom<ImageBuffer<$buffer, C>> for Image<$pixel>
+            where
+                C: Deref<Target = [$subpixel]>,
+            {
+                type Error = Error;
+
+                fn try_from(buffer: ImageBuffer<$buffer, C>) -> Result<Self> {
+                    if buffer.width() == 0 || buffer.height() == 0 {
+                        return Err(Error::EmptyImageError);
+                    }
+                    let data = buffer.pixels().map(|&$c| $from).collect::<Vec<_>>();
+
+                    Ok(Image::from_pixels(buffer.width(), data))
+                }
+            }
+        )+
+    };
+}
+
+impl_buffer_conversions! {
+    L => Luma<u8>, u8;
+    |p| [p.0];
+    |c| L(c.0[0]);
+
+    Rgb => ::image::Rgb<u8>, u8;
+    |p| [p.r, p.g, p.b];
+    |c| Rgb::new(c.0[0], c.0[1], c.0[2]);
+
+    Rgba => ::image::Rgba<u8>, u8;
+    |p| [p.r, p.g, p.b, p.a];
+    |c| Rgba::new(c.0[0], c.0[1], c.0[2], c.0[3]);
+
+    L32F => Luma<f32>, f32;
+    |p| [p.0];
+    |c| L32F(c.0[0]);
+
+    Rgb32F => ::image::Rgb<f32>, f32;
+    |p| [p.r, p.g, p.b];
+    |c| Rgb32F::new(c.0[0], c.0[1], c.0[2]);
+
+    Rgba32F => ::image::Rgba<f32>, f32;
+    |p| [p.r, p.g, p.b, p.a];
+    |c| Rgba32F::new(c.0[0], c.0[1], c.0[2], c.0[3]);
+}
+
+macro_rules! impl_dynamic_conversions {
+    ($($pixel:ty => $variant:ident, $into:ident;)+) => {
+        $(
+            impl From<Image<$pixel>> for DynamicImage {
+                fn from(image: Image<$pixel>) -> Self {
+                    Self::$variant(image.into())
+                }
+            }
+
+            /// Converts the image into this pixel type first if it has a different color type or
+            /// bit depth, which may be lossy.
+            impl TryFrom<DynamicImage> for Image<$pixel> {
+                type Error = Error;
+
+                fn try_from(image: DynamicImage) -> Result<Self> {
+                    Self::try_from(image.$into())
+                }
+            }
+        )+
+    };
+}
+
+impl_dynamic_conversions! {
+    L => ImageLuma8, into_luma8;
+    Rgb => ImageRgb8, into_rgb8;
+    Rgba => ImageRgba8, into_rgba8;
+    Rgb32F => ImageRgb32F, into_rgb32f;
+    Rgba32F => ImageRgba32F, into_rgba32f;
+}
@@ -0,0 +1,60 @@
+//! Conversions to and from the pixel buffers of the browser's
+//! [`ImageData`](https://developer.mozilla.org/en-US/docs/Web/API/ImageData).
+
+use crate::{Error, Image, Pixel, Result, Rgba};
+use alloc::vec::Vec;
+
+impl Image<Rgba> {
+    /// Creates an image from a buffer of RGBA bytes with the layout of the browser's `ImageData`,
+    /// which stores the four unpremultiplied 8-bit components of every pixel in row-major order.
+    ///
+    /// This is useful for processing the contents of a `<canvas>` element, for example the data
+    /// returned by `CanvasRenderingContext2D.getImageData()`.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The width or height is zero.
+    /// * `IncompatibleImageData`: The length of the buffer is not `width * height * 4`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let data = [255, 0, 0, 255, 0, 0, 255, 128];
+    /// let image = Image::from_image_data(2, 1, data)?;
+    /// assert_eq!(image.pixel(1, 0), &Rgba::new(0, 0, 255, 128));
+    /// assert_eq!(image.to_image_data(), data);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_image_data(width: u32, height: u32, data: impl AsRef<[u8]>) -> Result<Self> {
+        let data = data.as_ref();
+        if width == 0 || height == 0 {
+            return Err(Error::EmptyImageError);
+        }
+        if data.len() != width as usize * height as usize * 4 {
+            return Err(Error::IncompatibleImageData {
+                width,
+                height,
+                received: data.len() / 4,
+            });
+        }
+
+        Ok(Self::from_pixels(
+            width,
+            data.chunks_exact(4)
+                .map(Rgba::from_bytes)
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Copies this image into a buffer of RGBA bytes with the layout of the browser's
+    /// `ImageData`, which can be drawn onto a `<canvas>` element with
+    /// `CanvasRenderingContext2D.putImageData()`.
+    ///
+    /// # See Also
+    /// * [`Self::from_image_data`] for more information on the layout.
+    #[must_use]
+    pub fn to_image_data(&self) -> Vec<u8> {
+        self.data.iter().flat_map(Pixel::as_bytes).collect()
+    }
+}
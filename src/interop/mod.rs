@@ -0,0 +1,20 @@
+//! Conversions between the types of this crate and the types of other crates.
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics;
+mod framebuffer;
+#[cfg(feature = "image")]
+mod image;
+mod image_data;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+mod raw;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "wgpu")]
+mod wgpu;
+
+pub use framebuffer::FramebufferFormat;
+pub use raw::RawDescriptor;
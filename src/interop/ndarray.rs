@@ -0,0 +1,92 @@
+//! Conversions to and from the arrays of the [`ndarray`](https://docs.rs/ndarray) crate.
+
+use crate::{Error, Image, Result, Rgb, Rgb32F, Rgba, Rgba32F, L, L32F};
+use ndarray::{Array3, ArrayBase, Data, Ix3};
+
+macro_rules! impl_ndarray_conversions {
+    ($(
+        $pixel:ty => $subpixel:ty, $channels:literal;
+        |$p:ident| $to:expr;
+        |$c:ident| $from:expr;
+    )+) => {
+        $(
+            impl Image<$pixel> {
+                #[doc = concat!(
+                    "Copies this image into a 3-dimensional array in height, width, channel (HWC) ",
+                    "order, with a shape of `(height, width, ", $channels, ")`.",
+                )]
+                ///
+                /// This is the layout most scientific and machine learning libraries expect, and
+                /// can be indexed with `array[[y, x, channel]]`.
+                #[must_use]
+                pub fn to_ndarray(&self) -> Array3<$subpixel> {
+                    let (width, height) = self.dimensions();
+                    let data = self.data.iter().flat_map(|&$p| $to).collect();
+
+                    Array3::from_shape_vec((height as usize, width as usize, $channels), data)
+                        .expect("data should fit the shape")
+                }
+
+                #[doc = concat!(
+                    "Creates an image from a 3-dimensional array in height, width, channel (HWC) ",
+                    "order, with a shape of `(height, width, ", $channels, ")`. The array may be ",
+                    "in any memory layout.",
+                )]
+                ///
+                /// # Errors
+                /// * `EmptyImageError`: The array has a width or height of zero.
+                /// * `IncompatibleImageData`: The array has the wrong amount of channels.
+                pub fn from_ndarray<S>(array: &ArrayBase<S, Ix3>) -> Result<Self>
+                where
+                    S: Data<Elem = $subpixel>,
+                {
+                    let (height, width, channels) = array.dim();
+                    if width == 0 || height == 0 {
+                        return Err(Error::EmptyImageError);
+                    }
+                    if channels != $channels {
+                        return Err(Error::IncompatibleImageData {
+                            width: width as u32,
+                            height: height as u32,
+                            received: array.len(),
+                        });
+                    }
+
+                    let values = array.iter().copied().collect::<Vec<_>>();
+                    let data = values
+                        .chunks_exact($channels)
+                        .map(|$c| $from)
+                        .collect::<Vec<_>>();
+
+                    Ok(Self::from_pixels(width as u32, data))
+                }
+            }
+        )+
+    };
+}
+
+impl_ndarray_conversions! {
+    L => u8, 1;
+    |p| [p.0];
+    |c| L(c[0]);
+
+    Rgb => u8, 3;
+    |p| [p.r, p.g, p.b];
+    |c| Rgb::new(c[0], c[1], c[2]);
+
+    Rgba => u8, 4;
+    |p| [p.r, p.g, p.b, p.a];
+    |c| Rgba::new(c[0], c[1], c[2], c[3]);
+
+    L32F => f32, 1;
+    |p| [p.0];
+    |c| L32F(c[0]);
+
+    Rgb32F => f32, 3;
+    |p| [p.r, p.g, p.b];
+    |c| Rgb32F::new(c[0], c[1], c[2]);
+
+    Rgba32F => f32, 4;
+    |p| [p.r, p.g, p.b, p.a];
+    |c| Rgba32F::new(c[0], c[1], c[2], c[3]);
+}
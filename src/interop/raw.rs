@@ -0,0 +1,223 @@
+//! Raw access to the pixel buffers of images, for interop with C libraries, GPU upload paths and
+//! capture APIs.
+
+use crate::{
+    Cmyk, ColorType, Error, Image, ImageFormat, ImageMetadata, OverlayMode, Pixel, Result, Rgb,
+    Rgb16, Rgb32F, Rgba, Rgba16, Rgba32F, L, L16, L32F,
+};
+use alloc::vec::Vec;
+use core::{
+    mem::{size_of, ManuallyDrop},
+    num::NonZeroU32,
+};
+
+/// Describes the memory layout of the pixel buffer of an image, created with
+/// [`Image::as_raw_descriptor`].
+///
+/// Pixels are stored contiguously in row-major order, without any padding between rows. Channels
+/// are stored in the order of the fields of the pixel type, for example red, green, blue then
+/// alpha for [`Rgba`]. Channels with a bit depth of 8 are `u8` values, channels with a bit depth
+/// of 16 are `u16` values and channels with a bit depth of 32 are `f32` values, both in native
+/// endianness.
+///
+/// The pointer is only valid for as long as the image it was created from is neither mutated nor
+/// dropped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RawDescriptor {
+    /// A pointer to the first byte of the first pixel.
+    pub ptr: *const u8,
+    /// The length of the buffer, in bytes.
+    pub len: usize,
+    /// The width of the image, in pixels.
+    pub width: u32,
+    /// The height of the image, in pixels.
+    pub height: u32,
+    /// The distance between the starts of two consecutive rows, in bytes.
+    pub stride: usize,
+    /// The size of a single pixel, in bytes.
+    pub bytes_per_pixel: usize,
+    /// The color type of the pixels.
+    pub color_type: ColorType,
+    /// The bit depth of a single channel.
+    pub bit_depth: u8,
+}
+
+macro_rules! impl_raw_descriptor {
+    ($($pixel:ty),+) => {
+        $(
+            impl Image<$pixel> {
+                /// Returns a descriptor of the memory layout of the pixel buffer of this image,
+                /// which can be handed to C libraries or GPU APIs without copying the pixels.
+                ///
+                /// # See Also
+                /// * [`RawDescriptor`] for more information on the layout.
+                /// * [`Self::from_raw_parts`] for the reverse operation.
+                #[must_use]
+                pub fn as_raw_descriptor(&self) -> RawDescriptor {
+                    let bytes_per_pixel = size_of::<$pixel>();
+
+                    RawDescriptor {
+                        ptr: self.data.as_ptr().cast(),
+                        len: self.data.len() * bytes_per_pixel,
+                        width: self.width(),
+                        height: self.height(),
+                        stride: self.width() as usize * bytes_per_pixel,
+                        bytes_per_pixel,
+                        color_type: <$pixel>::COLOR_TYPE,
+                        bit_depth: <$pixel>::BIT_DEPTH,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_raw_descriptor!(L, Rgb, Rgba, L16, Rgb16, Rgba16, L32F, Rgb32F, Rgba32F, Cmyk);
+
+macro_rules! impl_raw_buf {
+    ($($pixel:ty),+) => {
+        $(
+            impl Image<$pixel> {
+                #[doc = concat!(
+                    "Creates an image from a buffer of packed [`", stringify!($pixel), "`] ",
+                    "pixels in row-major order, reusing the allocation of the buffer instead of ",
+                    "copying every pixel.",
+                )]
+                ///
+                /// The buffer is only copied if its capacity is not a multiple of the size of a
+                /// pixel, since the allocation could not be freed correctly otherwise.
+                ///
+                /// # Errors
+                /// * `EmptyImageError`: The width or height is zero.
+                /// * `IncompatibleImageData`: The length of the buffer does not match the
+                ///   dimensions.
+                ///
+                /// # See Also
+                /// * [`Self::into_raw_bytes`] for the reverse operation.
+                pub fn from_raw_buf(width: u32, height: u32, buf: Vec<u8>) -> Result<Self> {
+                    let size = size_of::<$pixel>();
+                    if width == 0 || height == 0 {
+                        return Err(Error::EmptyImageError);
+                    }
+                    if buf.len() != width as usize * height as usize * size {
+                        return Err(Error::IncompatibleImageData {
+                            width,
+                            height,
+                            received: buf.len() / size,
+                        });
+                    }
+                    if buf.capacity() % size != 0 {
+                        let pixels = buf.chunks_exact(size).map(<$pixel>::from_bytes);
+                        return Ok(Self::from_pixels(width, pixels.collect::<Vec<_>>()));
+                    }
+
+                    let mut buf = ManuallyDrop::new(buf);
+                    // SAFETY: the pixel type only consists of `u8` fields, so it has an alignment
+                    // of 1 and every bit pattern is valid. The capacity of the buffer is a
+                    // multiple of the size of a pixel, so the allocation has the same layout.
+                    Ok(unsafe {
+                        Self::from_raw_parts(
+                            width,
+                            height,
+                            buf.as_mut_ptr().cast(),
+                            buf.capacity() / size,
+                        )
+                    })
+                }
+
+                /// Consumes this image and returns the packed bytes of its pixels in row-major
+                /// order, reusing the allocation of the pixels instead of copying them.
+                #[must_use]
+                pub fn into_raw_bytes(self) -> Vec<u8> {
+                    let size = size_of::<$pixel>();
+                    let mut data = ManuallyDrop::new(self.data);
+
+                    // SAFETY: the pixel type only consists of `u8` fields, so its bytes can be
+                    // reinterpreted as a buffer of bytes with the same allocation layout.
+                    unsafe {
+                        Vec::from_raw_parts(
+                            data.as_mut_ptr().cast(),
+                            data.len() * size,
+                            data.capacity() * size,
+                        )
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_raw_buf!(L, Rgb, Rgba, Cmyk);
+
+impl<P: Pixel> Image<P> {
+    /// Creates an image which takes ownership of the given pixel buffer without copying it.
+    ///
+    /// The buffer holds `width * height` pixels in row-major order, and `capacity` is the amount
+    /// of pixels it was allocated with.
+    ///
+    /// # Safety
+    /// The same requirements as [`Vec::from_raw_parts`] apply. Most importantly, the buffer must
+    /// have been allocated by Rust's global allocator for `capacity` elements of `P`, for example
+    /// by a [`Vec<P>`] whose ownership was given up. Buffers allocated by C libraries must be
+    /// copied instead.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    /// * The capacity is less than `width * height`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut pixels = core::mem::ManuallyDrop::new(vec![Rgb::new(255, 0, 0); 4]);
+    /// let capacity = pixels.capacity();
+    ///
+    /// // SAFETY: the buffer was allocated by a `Vec<Rgb>` which is never dropped
+    /// let image = unsafe { Image::from_raw_parts(2, 2, pixels.as_mut_ptr(), capacity) };
+    /// assert_eq!(image.pixel(1, 1), &Rgb::new(255, 0, 0));
+    ///
+    /// let descriptor = image.as_raw_descriptor();
+    /// assert_eq!((descriptor.len, descriptor.stride), (12, 6));
+    /// ```
+    #[must_use]
+    pub unsafe fn from_raw_parts(width: u32, height: u32, ptr: *mut P, capacity: usize) -> Self {
+        let len = width as usize * height as usize;
+        assert!(
+            capacity >= len,
+            "capacity {capacity} is too small for an image with dimensions {width}x{height}",
+        );
+
+        Self {
+            width: NonZeroU32::new(width).expect("width must be non-zero"),
+            height: NonZeroU32::new(height).expect("height must be non-zero"),
+            data: Vec::from_raw_parts(ptr, len, capacity),
+            format: ImageFormat::default(),
+            overlay: OverlayMode::default(),
+            palette: None,
+            metadata: ImageMetadata::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_buf_reuses_allocation() {
+        let buf = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let ptr = buf.as_ptr();
+
+        let image = Image::<Rgb>::from_raw_buf(2, 2, buf).unwrap();
+        assert_eq!(image.pixel(1, 1), &Rgb::new(10, 11, 12));
+        assert_eq!(image.as_raw_descriptor().ptr, ptr);
+
+        let bytes = image.into_raw_bytes();
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(bytes, (1..=12).collect::<Vec<_>>());
+
+        assert!(matches!(
+            Image::<Rgb>::from_raw_buf(2, 2, vec![0; 11]),
+            Err(Error::IncompatibleImageData { received: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,273 @@
+//! Support for serializing and deserializing images and pixels with
+//! [`serde`](https://docs.rs/serde).
+//!
+//! Pixels and colors serialize as structured values, for example an [`Rgb`] pixel serializes as
+//! `{"r": 255, "g": 128, "b": 0}`. Use the [`hex`] module to serialize them as hex strings
+//! instead.
+//!
+//! Images serialize as their width and height along with the raw bytes of their pixels. In
+//! human-readable formats such as JSON, the bytes are encoded as a base64 string. Use the [`png`]
+//! module to store the pixels as an encoded PNG image instead, which is usually much smaller.
+//!
+//! # Example
+//! ```
+//! use ril::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Sprite {
+//!     #[serde(with = "ril::interop::serde::hex")]
+//!     tint: Rgba,
+//!     mask: Image<L>,
+//! }
+//!
+//! # fn main() -> serde_json::Result<()> {
+//! let sprite = Sprite {
+//!     tint: Rgba::new(255, 128, 0, 255),
+//!     mask: Image::new(2, 1, L(255)),
+//! };
+//! let json = serde_json::to_string(&sprite)?;
+//! assert_eq!(
+//!     json,
+//!     r##"{"tint":"#ff8000","mask":{"width":2,"height":1,"data":"//8="}}"##
+//! );
+//!
+//! let sprite: Sprite = serde_json::from_str(&json)?;
+//! assert_eq!(sprite.mask.data, [L(255); 2]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{
+    de::{self, Deserializer, SeqAccess, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::{
+    BitPixel, Cmyk, Error, Image, Pixel, Result, Rgb, Rgb16, Rgb32F, Rgba, Rgba16, Rgba32F, L, L16,
+    L32F,
+};
+
+/// The payload of a serialized image, which is a base64 string in human-readable formats and raw
+/// bytes otherwise.
+struct Payload(Vec<u8>);
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+struct PayloadVisitor;
+
+impl<'de> Visitor<'de> for PayloadVisitor {
+    type Value = Payload;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a base64 string or a byte array")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Payload, E> {
+        STANDARD.decode(v).map(Payload).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Payload, E> {
+        Ok(Payload(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<Payload, E> {
+        Ok(Payload(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Payload, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        Ok(Payload(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(PayloadVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(PayloadVisitor)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Image")]
+struct SerializedImage {
+    width: u32,
+    height: u32,
+    data: Payload,
+}
+
+/// Creates an image from the raw bytes of its pixels.
+fn image_from_raw<P: Pixel>(width: u32, height: u32, data: &[u8]) -> Result<Image<P>> {
+    let size = P::default().as_bytes().as_ref().len();
+    let pixels = width as usize * height as usize;
+
+    if pixels == 0 {
+        return Err(Error::EmptyImageError);
+    }
+    if data.len() != pixels * size {
+        return Err(Error::IncompatibleImageData {
+            width,
+            height,
+            received: data.len() / size,
+        });
+    }
+
+    Ok(Image::from_pixels(
+        width,
+        data.chunks_exact(size)
+            .map(P::from_bytes)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+macro_rules! impl_serde {
+    ($($pixel:ty),+) => {
+        $(
+            impl Serialize for Image<$pixel> {
+                fn serialize<S: Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> std::result::Result<S::Ok, S::Error> {
+                    SerializedImage {
+                        width: self.width(),
+                        height: self.height(),
+                        data: Payload(self.data.iter().flat_map(Pixel::as_bytes).collect()),
+                    }
+                    .serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for Image<$pixel> {
+                fn deserialize<D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> std::result::Result<Self, D::Error> {
+                    let SerializedImage {
+                        width,
+                        height,
+                        data: Payload(data),
+                    } = SerializedImage::deserialize(deserializer)?;
+
+                    image_from_raw(width, height, &data).map_err(de::Error::custom)
+                }
+            }
+        )+
+    };
+}
+
+impl_serde!(BitPixel, L, Rgb, Rgba, L16, Rgb16, Rgba16, L32F, Rgb32F, Rgba32F, Cmyk);
+
+/// Serializes true color pixels as hex strings, for use with `#[serde(with = "...")]`.
+///
+/// Colors are serialized as `"#rrggbb"`, or as `"#rrggbbaa"` if they are not fully opaque. Any
+/// format accepted by [`Rgba::from_css`] can be deserialized.
+pub mod hex {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::{Rgba, TrueColor};
+
+    /// Serializes the given pixel as a hex string.
+    ///
+    /// # Errors
+    /// * The serializer failed to serialize the string.
+    pub fn serialize<P: TrueColor, S: Serializer>(
+        pixel: &P,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&Rgba::from_rgba_tuple(pixel.as_rgba_tuple()).to_hex())
+    }
+
+    /// Deserializes a pixel from a hex string, or any other CSS color string accepted by
+    /// [`Rgba::from_css`].
+    ///
+    /// # Errors
+    /// * The value is not a string.
+    /// * The string is a malformed hex code or CSS color.
+    pub fn deserialize<'de, P: TrueColor, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<P, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        Rgba::from_css(&hex)
+            .map(|pixel| P::from_rgba_tuple(pixel.as_rgba_tuple()))
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Serializes images with their pixels encoded as a PNG image, for use with
+/// `#[serde(with = "...")]`.
+///
+/// This is lossless and usually much smaller than the raw pixel data, at the cost of encoding and
+/// decoding the image.
+#[cfg(feature = "png")]
+pub mod png {
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Payload, SerializedImage};
+    use crate::{Image, ImageFormat, Pixel};
+
+    /// Serializes the given image with its pixels encoded as a PNG image.
+    ///
+    /// # Errors
+    /// * The image could not be encoded.
+    /// * The serializer failed to serialize the image.
+    pub fn serialize<P: Pixel, S: Serializer>(
+        image: &Image<P>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut data = Vec::new();
+        image
+            .encode(ImageFormat::Png, &mut data)
+            .map_err(ser::Error::custom)?;
+
+        SerializedImage {
+            width: image.width(),
+            height: image.height(),
+            data: Payload(data),
+        }
+        .serialize(serializer)
+    }
+
+    /// Deserializes an image with its pixels encoded as a PNG image.
+    ///
+    /// # Errors
+    /// * The image could not be decoded.
+    /// * The dimensions of the decoded image do not match the serialized dimensions.
+    pub fn deserialize<'de, P: Pixel, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Image<P>, D::Error> {
+        let SerializedImage {
+            width,
+            height,
+            data: Payload(data),
+        } = SerializedImage::deserialize(deserializer)?;
+        let image = Image::from_bytes(ImageFormat::Png, data).map_err(de::Error::custom)?;
+
+        if image.dimensions() != (width, height) {
+            return Err(de::Error::custom(format!(
+                "expected an image with dimensions {width}x{height}, found {}x{}",
+                image.width(),
+                image.height(),
+            )));
+        }
+
+        Ok(image)
+    }
+}
@@ -0,0 +1,208 @@
+//! Uploading images to and reading images back from the textures of
+//! [`wgpu`](https://docs.rs/wgpu).
+//!
+//! The texture format is fixed by the pixel type of the image:
+//!
+//! | Pixel type  | Texture format    |
+//! |-------------|-------------------|
+//! | [`L`]       | `R8Unorm`         |
+//! | [`Rgb`]     | `Rgba8UnormSrgb`  |
+//! | [`Rgba`]    | `Rgba8UnormSrgb`  |
+//! | [`L32F`]    | `R32Float`        |
+//! | [`Rgba32F`] | `Rgba32Float`     |
+//!
+//! Since there are no three-channel texture formats, [`Rgb`] images are uploaded with an opaque
+//! alpha channel, which is discarded again when reading them back.
+
+use crate::{Error, Image, Pixel, Result, Rgb, Rgba, Rgba32F, L, L32F};
+use std::sync::mpsc;
+
+/// Creates a 2D texture with the given dimensions and format and uploads the given bytes to it.
+fn upload(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    (width, height): (u32, u32),
+    format: wgpu::TextureFormat,
+    bytes_per_pixel: u32,
+    data: &[u8],
+) -> wgpu::Texture {
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    queue.write_texture(
+        texture.as_image_copy(),
+        data,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width * bytes_per_pixel),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    texture
+}
+
+/// Copies the contents of the given texture into a buffer and reads it back, with the padding
+/// wgpu requires at the end of each row removed.
+fn read_back(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    format: wgpu::TextureFormat,
+    bytes_per_pixel: u32,
+) -> Result<Vec<u8>> {
+    if texture.format() != format {
+        return Err(Error::UnsupportedColorType);
+    }
+    if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+        return Err(Error::GpuError(
+            "texture must have the COPY_SRC usage to be read back".to_string(),
+        ));
+    }
+
+    let (width, height) = (texture.width(), texture.height());
+    let row = width * bytes_per_pixel;
+    // Rows of texture to buffer copies must be aligned to a multiple of 256 bytes
+    let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_row = row + (alignment - row % alignment) % alignment;
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: u64::from(padded_row) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait).panic_on_timeout();
+    receiver
+        .recv()
+        .map_err(|err| Error::GpuError(err.to_string()))??;
+
+    let data = slice
+        .get_mapped_range()
+        .chunks_exact(padded_row as usize)
+        .flat_map(|padded| &padded[..row as usize])
+        .copied()
+        .collect();
+    buffer.unmap();
+
+    Ok(data)
+}
+
+macro_rules! impl_wgpu_conversions {
+    ($(
+        $pixel:ty => $format:ident, $bytes_per_pixel:literal;
+        |$p:ident| $to:expr;
+        |$c:ident| $from:expr;
+    )+) => {
+        $(
+            impl Image<$pixel> {
+                #[doc = concat!(
+                    "Creates a 2D texture with the `", stringify!($format), "` format and uploads ",
+                    "this image to it.",
+                )]
+                ///
+                /// The texture can be sampled in shaders, and has the `COPY_SRC` and `COPY_DST`
+                /// usages so that it can be read back or overwritten.
+                #[must_use]
+                pub fn to_wgpu_texture(
+                    &self,
+                    device: &wgpu::Device,
+                    queue: &wgpu::Queue,
+                ) -> wgpu::Texture {
+                    let data = self.data.iter().flat_map(|&$p| $to).collect::<Vec<u8>>();
+
+                    upload(
+                        device,
+                        queue,
+                        self.dimensions(),
+                        wgpu::TextureFormat::$format,
+                        $bytes_per_pixel,
+                        &data,
+                    )
+                }
+
+                #[doc = concat!(
+                    "Reads an image back from a 2D texture with the `", stringify!($format),
+                    "` format. This blocks until the GPU has finished all submitted work.",
+                )]
+                ///
+                /// # Errors
+                /// * `UnsupportedColorType`: The texture has a different format.
+                /// * `GpuError`: The texture does not have the `COPY_SRC` usage, or could not be
+                ///   mapped.
+                pub fn from_wgpu_texture(
+                    device: &wgpu::Device,
+                    queue: &wgpu::Queue,
+                    texture: &wgpu::Texture,
+                ) -> Result<Self> {
+                    let data = read_back(
+                        device,
+                        queue,
+                        texture,
+                        wgpu::TextureFormat::$format,
+                        $bytes_per_pixel,
+                    )?;
+                    let pixels = data
+                        .chunks_exact($bytes_per_pixel)
+                        .map(|$c| $from)
+                        .collect::<Vec<_>>();
+
+                    Ok(Self::from_pixels(texture.width(), pixels))
+                }
+            }
+        )+
+    };
+}
+
+impl_wgpu_conversions! {
+    L => R8Unorm, 1;
+        |p| p.as_bytes();
+        |c| L::from_bytes(c);
+    Rgb => Rgba8UnormSrgb, 4;
+        |p| [p.r, p.g, p.b, 255];
+        |c| Rgb::new(c[0], c[1], c[2]);
+    Rgba => Rgba8UnormSrgb, 4;
+        |p| p.as_bytes();
+        |c| Rgba::from_bytes(c);
+    L32F => R32Float, 4;
+        |p| p.as_bytes();
+        |c| L32F::from_bytes(c);
+    Rgba32F => Rgba32Float, 16;
+        |p| p.as_bytes();
+        |c| Rgba32F::from_bytes(c);
+}
@@ -0,0 +1,302 @@
+//! Implements a layer compositor which flattens an ordered stack of layers into a single image.
+
+use crate::{image::rebind_pixel, Draw, Image, OverlayMode, Pixel, L};
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use core::ops::DerefMut;
+
+/// A drawable object, type-erased into a function which draws it onto an image.
+type DrawFn<'a, P> = Box<dyn Fn(&mut Image<P>) + 'a>;
+
+/// The content of a [`Layer`].
+enum LayerContent<'a, P: Pixel> {
+    /// An image, either borrowed or owned.
+    Image(Cow<'a, Image<P>>),
+    /// A drawable object.
+    Draw(DrawFn<'a, P>),
+}
+
+/// A single layer of a [`LayerStack`].
+///
+/// A layer either holds an image, which is pasted at the layer's position, or any drawable object,
+/// which is drawn using its own coordinates. Layers can additionally be given an opacity and a
+/// grayscale mask.
+pub struct Layer<'a, P: Pixel> {
+    content: LayerContent<'a, P>,
+    /// The position of the top-left corner of this layer. For image layers this is where the image
+    /// is placed; for drawable layers this only determines where the mask is placed, since
+    /// drawable objects carry their own coordinates. Defaults to `(0, 0)`.
+    pub position: (i64, i64),
+    /// The opacity of this layer, where `0` is fully transparent and `255` is fully opaque.
+    /// Defaults to `255`.
+    pub opacity: u8,
+    /// How the pixels of an image layer are blended with the layers below it. This is ignored for
+    /// drawable layers, which use their own overlay modes. Defaults to [`OverlayMode::Merge`].
+    pub overlay: OverlayMode,
+    /// A grayscale mask placed at the layer's position, whose luminance values modulate the
+    /// opacity of this layer. Pixels outside of the mask are not drawn.
+    pub mask: Option<&'a Image<L>>,
+    /// Whether this layer is visible. Hidden layers are skipped when compositing. Defaults to
+    /// `true`.
+    pub visible: bool,
+}
+
+impl<'a, P: Pixel> Layer<'a, P> {
+    fn from_content(content: LayerContent<'a, P>) -> Self {
+        Self {
+            content,
+            position: (0, 0),
+            opacity: u8::MAX,
+            overlay: OverlayMode::Merge,
+            mask: None,
+            visible: true,
+        }
+    }
+
+    /// Creates a new layer from a reference to the given image.
+    #[must_use]
+    pub fn image(image: &'a Image<P>) -> Self {
+        Self::from_content(LayerContent::Image(Cow::Borrowed(image)))
+    }
+
+    /// Creates a new layer which takes ownership of the given image.
+    #[must_use]
+    pub fn owned_image(image: Image<P>) -> Self {
+        Self::from_content(LayerContent::Image(Cow::Owned(image)))
+    }
+
+    /// Creates a new layer from any drawable object, for example a shape or text layout.
+    #[must_use]
+    pub fn draw(entity: impl Draw<P> + 'a) -> Self {
+        Self::from_content(LayerContent::Draw(Box::new(move |image| {
+            image.draw(&entity)
+        })))
+    }
+
+    /// Creates a new layer from a group of layers. The group is flattened into a single image with
+    /// its own background first, so opacity and masks of this layer apply to the group as a
+    /// whole.
+    #[must_use]
+    pub fn group(stack: LayerStack<'a, P>) -> Self {
+        Self::owned_image(stack.flatten())
+    }
+
+    /// Sets the position of the top-left corner of this layer.
+    #[must_use]
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the opacity of this layer, where `0` is fully transparent and `255` is fully opaque.
+    #[must_use]
+    pub const fn with_opacity(mut self, opacity: u8) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets how the pixels of this layer are blended with the layers below it.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = mode;
+        self
+    }
+
+    /// Sets the grayscale mask of this layer.
+    #[must_use]
+    pub const fn with_mask(mut self, mask: &'a Image<L>) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Sets whether this layer is visible.
+    #[must_use]
+    pub const fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Returns the effective opacity of this layer at the given coordinates relative to the
+    /// position of this layer.
+    #[allow(clippy::cast_lossless)]
+    fn alpha_at(&self, x: i64, y: i64) -> u8 {
+        match self.mask {
+            Some(mask) => {
+                if x < 0 || y < 0 {
+                    return 0;
+                }
+                mask.get_pixel(x as u32, y as u32)
+                    .map_or(0, |&L(m)| (m as u16 * self.opacity as u16 / 255) as u8)
+            }
+            None => self.opacity,
+        }
+    }
+
+    fn composite(&self, image: &mut Image<P>) {
+        if !self.visible || self.opacity == 0 {
+            return;
+        }
+
+        let (px, py) = self.position;
+        match &self.content {
+            LayerContent::Image(src) => {
+                let (w, h) = src.dimensions();
+
+                for (y, i) in (py..py + h as i64).zip(0..) {
+                    if y < 0 {
+                        continue;
+                    }
+                    for (x, j) in (px..px + w as i64).zip(0..) {
+                        if x < 0 {
+                            continue;
+                        }
+
+                        let alpha = self.alpha_at(j as i64, i as i64);
+                        let pixel = *src.pixel(j, i);
+                        match alpha {
+                            0 => (),
+                            u8::MAX => {
+                                image.overlay_pixel_with_mode(
+                                    x as u32,
+                                    y as u32,
+                                    pixel,
+                                    self.overlay,
+                                );
+                            }
+                            _ => image.overlay_pixel_with_alpha(
+                                x as u32,
+                                y as u32,
+                                pixel,
+                                self.overlay,
+                                alpha,
+                            ),
+                        }
+                    }
+                }
+            }
+            LayerContent::Draw(f) => {
+                if self.opacity == u8::MAX && self.mask.is_none() {
+                    f(image);
+                    return;
+                }
+
+                let mut drawn = image.clone();
+                f(&mut drawn);
+
+                // SAFETY: pixels are only rebound right before they are written into the image
+                let palette = unsafe { image.detached_palette() };
+                let width = image.width();
+                for (i, (pixel, src)) in image.data.iter_mut().zip(drawn.data).enumerate() {
+                    let x = (i as u32 % width) as i64 - px;
+                    let y = (i as u32 / width) as i64 - py;
+                    let src = unsafe { rebind_pixel(palette, src) };
+
+                    match self.alpha_at(x, y) {
+                        0 => (),
+                        u8::MAX => *pixel = src,
+                        alpha => *pixel = pixel.merge_with_alpha(src, alpha),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An ordered stack of layers which can be flattened into a single image.
+///
+/// Layers are composited from bottom to top, i.e. in the order they were added.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let logo = Image::new(32, 32, Rgba::new(255, 0, 0, 255));
+///
+/// let image = LayerStack::new(128, 128, Rgba::white())
+///     .with_layer(Layer::draw(Rectangle::from_bounding_box(0, 0, 128, 64).with_fill(Rgba::black())))
+///     .with_layer(Layer::image(&logo).with_position(48, 48).with_opacity(128))
+///     .flatten();
+///
+/// assert_eq!(image.dimensions(), (128, 128));
+/// ```
+pub struct LayerStack<'a, P: Pixel> {
+    width: u32,
+    height: u32,
+    background: P,
+    layers: Vec<Layer<'a, P>>,
+}
+
+impl<'a, P: Pixel> LayerStack<'a, P> {
+    /// Creates a new empty layer stack with the given dimensions and background color.
+    #[must_use]
+    pub const fn new(width: u32, height: u32, background: P) -> Self {
+        Self {
+            width,
+            height,
+            background,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Returns the dimensions of the image this layer stack flattens into.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Adds a layer on top of all other layers in this stack.
+    pub fn push_layer(&mut self, layer: Layer<'a, P>) {
+        self.layers.push(layer);
+    }
+
+    /// Takes this layer stack and adds a layer on top of all other layers. Useful for method
+    /// chaining.
+    #[must_use]
+    pub fn with_layer(mut self, layer: Layer<'a, P>) -> Self {
+        self.push_layer(layer);
+        self
+    }
+
+    /// Returns a slice of the layers in this stack, ordered from bottom to top.
+    #[must_use]
+    pub fn layers(&self) -> &[Layer<'a, P>] {
+        &self.layers
+    }
+
+    /// Returns a mutable reference to the layers in this stack, ordered from bottom to top. This
+    /// can be used to reorder, insert, or remove layers.
+    pub fn layers_mut(&mut self) -> &mut Vec<Layer<'a, P>> {
+        &mut self.layers
+    }
+
+    /// Returns the number of layers in this stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns whether there are no layers in this stack.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Composites all layers on top of the background and returns the resulting image.
+    ///
+    /// # Panics
+    /// * The width or height of this layer stack is zero.
+    #[must_use]
+    pub fn flatten(&self) -> Image<P> {
+        Image::new(self.width, self.height, self.background).with(self)
+    }
+}
+
+impl<'a, P: Pixel> Draw<P> for LayerStack<'a, P> {
+    /// Composites all layers of this stack directly onto the given image, disregarding the
+    /// background of this stack.
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let image = &mut *image;
+
+        for layer in &self.layers {
+            layer.composite(image);
+        }
+    }
+}
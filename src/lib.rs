@@ -37,17 +37,32 @@
 //! | PNG and APNG  | `png`   | `png`                          | no       |
 //! | JPEG          | `jpeg`  | `jpeg-decoder`, `jpeg-encoder` | no       |
 //! | GIF           | `gif`   | `gif`                          | no       |
+//! | TIFF          | `tiff`  | `tiff`                         | no       |
 //! | WebP          | `webp`  | `libwebp-sys2`                 | no       |
 //!
 //! Other features:
 //!
-//! | Description                                               | Feature    | Dependencies        | Default? |
-//! |-----------------------------------------------------------|------------|---------------------|----------|
-//! | Font/Text Rendering                                       | `text`     | `fontdue`           | yes      |
-//! | Image Resizing                                            | `resize`   | `fast_image_resize` | yes      |
-//! | Color Quantization (using NeuQuant)                       | `quantize` | `color_quant`       | yes      |
-//! | Gradients                                                 | `gradient` | `colorgrad`         | yes      |
-//! | Enable all features,<br/> including all encoding features | `all`      |                     | no       |
+//! | Description                                               | Feature             | Dependencies             | Default? |
+//! |-----------------------------------------------------------|---------------------|--------------------------|----------|
+//! | Standard library support, required for I/O and encoding   | `std`               |                          | yes      |
+//! | Font/Text Rendering                                       | `text`              | `fontdue`                | yes      |
+//! | Bidi reordering and Arabic letter joining in text layouts | `arabic-shaping`    | `unicode-bidi`           | no       |
+//! | Image Resizing                                            | `resize`            | `fast_image_resize`      | yes      |
+//! | Color Quantization (using NeuQuant)                       | `quantize`          | `color_quant`            | yes      |
+//! | Gradients                                                 | `gradient`          | `colorgrad`              | yes      |
+//! | ICC Color Profiles (for CMYK images)                      | `icc`               | `lcms2`                  | no       |
+//! | Parallel Processing and batch processing of files         | `rayon`             | `rayon`                  | no       |
+//! | QR codes and barcodes                                     | `barcode`           | `qrcode`                 | no       |
+//! | Asynchronous encoding and decoding with `tokio`           | `tokio`             | `tokio`                  | no       |
+//! | SIMD-accelerated alpha blending (on `x86_64`)             | `simd`              |                          | no       |
+//! | Conversions to and from `image` crate buffers             | `image`             | `image`                  | no       |
+//! | Conversions to and from `ndarray` arrays                  | `ndarray`           | `ndarray`                | no       |
+//! | Serializing images and pixels with `serde`                | `serde`             | `serde`, `base64`        | no       |
+//! | Drawing with `embedded-graphics`                          | `embedded-graphics` | `embedded-graphics-core` | no       |
+//! | Uploading images to and reading from `wgpu` textures      | `wgpu`              | `wgpu`                   | no       |
+//! | Zero-copy casts of pixels with `bytemuck`                 | `bytemuck`          | `bytemuck`               | no       |
+//! | C-compatible interface for other languages                | `ffi`               |                          | no       |
+//! | Enable all features,<br/> including all encoding features | `all`               |                          | no       |
 //!
 //! ### WebP Support limitations
 //! WebP support uses `libwebp`, which is a native library. This means that if you try to use the
@@ -57,6 +72,25 @@
 //! For ease of use, the `all-pure` feature is provided, which is the equivalent of `all` minus the
 //! `webp` feature.
 //!
+//! ### WebAssembly
+//! RIL compiles on `wasm32-unknown-unknown` with the `all-pure` feature. Since there is no
+//! filesystem on this target, methods which read from or write to paths such as [`Image::open`]
+//! and [`Image::save`] are not available there - use [`Image::from_bytes`] and [`Image::encode`]
+//! instead. [`Image::from_image_data`] and [`Image::to_image_data`] convert images to and from the
+//! pixel buffers of the browser's `ImageData`, so images can be drawn onto a `<canvas>`.
+//!
+//! ### `no_std` Support
+//! RIL can be used without the standard library by disabling default features, in which case it
+//! only depends on `alloc`. Creating, drawing on and processing images is still supported, but
+//! reading, writing, encoding and decoding images as well as image sequences and quantization
+//! require the `std` feature. Features that depend on other crates which require the standard
+//! library, such as `png` or `text`, enable `std` automatically.
+//!
+//! ```toml
+//! [dependencies]
+//! ril = { version = "0", default-features = false }
+//! ```
+//!
 //! # Getting Started
 //! Import the prelude which brings commonly used types and crucial traits into scope:
 //!
@@ -251,6 +285,7 @@
 //!
 //! TODO: finish guide
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(
     clippy::cast_possible_truncation,
     clippy::cast_sign_loss,
@@ -260,24 +295,68 @@
     clippy::doc_markdown
 )]
 
+extern crate alloc;
+
+#[cfg(feature = "barcode")]
+pub mod barcode;
+#[cfg(all(
+    feature = "rayon",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+pub mod batch;
+pub mod blurhash;
+pub mod color;
+#[cfg(feature = "text")]
+mod colr;
+mod css;
 pub mod draw;
+pub mod effects;
+#[cfg(feature = "std")]
 pub mod encode;
 pub mod encodings;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod fill;
 pub mod filter;
 mod format;
+pub mod generate;
 #[cfg(feature = "gradient")]
 pub mod gradient;
+pub mod hash;
+pub mod histogram;
 mod image;
+pub mod interop;
+pub mod layer;
+pub mod lut;
+pub mod metadata;
 pub mod morph;
+pub mod nine_slice;
+mod parallel;
+pub mod pipeline;
 pub mod pixel;
+#[cfg(feature = "std")]
 pub mod quantize;
+mod raster;
+pub mod rect;
 #[cfg(feature = "resize")]
 mod resize;
+pub mod scratch;
+#[cfg(feature = "std")]
 pub mod sequence;
+#[cfg(feature = "arabic-shaping")]
+mod shaping;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+pub mod template;
+#[cfg(feature = "std")]
+pub mod terminal;
 #[cfg(feature = "text")]
 pub mod text;
+pub mod thumbhash;
+pub mod transform;
+pub mod view;
+pub mod watermark;
 
 macro_rules! inline_doc {
     ($($token:item)*) => {
@@ -286,9 +365,23 @@ macro_rules! inline_doc {
 }
 
 inline_doc! {
-    pub use crate::image::{Banded, Image, OverlayMode};
-    pub use draw::{Border, BorderPosition, Draw, Ellipse, Line, Paste, Polygon, Rectangle};
-    pub use encode::{Decoder, Encoder, EncoderMetadata, SingleFrameIterator, FrameIterator};
+    #[cfg(feature = "barcode")]
+    pub use barcode::{Barcode, QrCode, QrErrorCorrection};
+    pub use crate::image::{
+        BandTuple, Banded, BlendMode, EdgeMode, Image, ImageBuilder, Neighborhood, OverlayMode,
+        RotationCanvas, TrimMode,
+    };
+    pub use color::{Hsl, Hsv, Lab, Oklab, Oklch};
+    pub use draw::{
+        Bezier, Border, BorderPosition, Clip, ClipRegion, Draw, DrawExt, DrawTarget, Ellipse, FillRule,
+        FloodFill, Line, LineCap, LineJoin, Masked, Paste, Path, PathStroke, Polygon, Polyline,
+        Rectangle,
+    };
+    pub use effects::{Aberration, MirrorMode, Vignette};
+    #[cfg(feature = "std")]
+    pub use encode::{
+        Decoder, Encoder, EncoderMetadata, SingleFrameIterator, FrameIterator, RowSink,
+    };
     pub use encodings::ColorType;
     pub use error::{Error, Result};
     #[cfg(feature = "gradient")]
@@ -296,24 +389,50 @@ inline_doc! {
         BlendMode as GradientBlendMode,
         Interpolation as GradientInterpolation,
         GradientPosition,
+        GradientRepeat,
         LinearGradient,
         RadialGradient,
         ConicGradient,
         RadialGradientCover,
     };
-    pub use fill::{Fill, IntoFill};
+    pub use fill::{Fill, IntoFill, Pattern};
+    pub use filter::{BoxBlur, Convolution, GaussianBlur, Sharpen, Sobel};
     pub use format::ImageFormat;
+    pub use generate::PlaceholderStyle;
+    pub use hash::ImageHash;
+    pub use histogram::Histogram;
+    pub use interop::{FramebufferFormat, RawDescriptor};
+    pub use layer::{Layer, LayerStack};
+    pub use lut::{CubeLut, Lut};
+    pub use metadata::{ImageMetadata, Orientation};
+    pub use nine_slice::NineSlice;
+    pub use pipeline::Pipeline;
     pub use pixel::{
-        Alpha, BitPixel, Dynamic, DynamicSubpixel, Paletted, PalettedRgb, PalettedRgba, Pixel, Rgb,
-        Rgba, TrueColor, L,
+        Alpha, BitPixel, Cmyk, Dynamic, DynamicSubpixel, GrayscaleWeights, Modulate, Paletted,
+        PalettedRgb, PalettedRgba, Pixel, Rgb, Rgb16, Rgb32F, Rgba, Rgba16, Rgba32F, Tonemap,
+        TrueColor, L, L16, L32F,
     };
-    pub use quantize::Quantizer;
+    #[cfg(feature = "std")]
+    pub use quantize::{Dither, QuantizeMethod, Quantizer};
+    pub use rect::{OutOfBounds, Rect};
     #[cfg(feature = "resize")]
     pub use resize::FilterType as ResizeAlgorithm;
-    pub use sequence::{DisposalMethod, Frame, ImageSequence, LoopCount};
+    pub use scratch::Scratch;
+    #[cfg(feature = "std")]
+    pub use sequence::{
+        BlendMethod, DisposalMethod, FitMode, Frame, ImageSequence, LazySequence, LoopCount,
+        OverlaySource,
+    };
+    pub use template::{MatchMethod, TemplateMatch};
+    #[cfg(feature = "std")]
+    pub use terminal::{TerminalOptions, TerminalProtocol};
+    pub use transform::Affine;
+    pub use view::{ImageView, ImageViewMut};
+    pub use watermark::{Watermark, WatermarkAnchor, WatermarkPlacement};
     #[cfg(feature = "text")]
     pub use text::{
-        Font, HorizontalAnchor, TextLayout, TextSegment, VerticalAnchor, WrapStyle,
+        Font, GlyphCache, GlyphInfo, HorizontalAnchor, Overflow, TextLayout, TextSegment,
+        VerticalAnchor, WrapStyle,
     };
 }
 
@@ -331,20 +450,38 @@ inline_doc! {
 /// ```
 pub mod prelude {
     pub use super::{
-        Alpha, Banded, BitPixel, Border, BorderPosition, ColorType, Decoder, DisposalMethod, Draw,
-        Dynamic, DynamicSubpixel, Ellipse, Encoder, EncoderMetadata, Fill, Frame, FrameIterator,
-        Image, ImageFormat, ImageSequence, IntoFill, Line, LoopCount, OverlayMode, Paletted,
-        PalettedRgb, PalettedRgba, Paste, Pixel, Polygon, Rectangle, Rgb, Rgba,
-        SingleFrameIterator, TrueColor, L,
+        Aberration, Affine, Alpha, Banded, Bezier, BitPixel, BlendMode, Border, BorderPosition,
+        BoxBlur, Clip, ClipRegion, Cmyk, ColorType, Convolution, CubeLut, Draw, DrawExt,
+        DrawTarget, Dynamic, DynamicSubpixel, EdgeMode, Ellipse, Fill, FillRule, FloodFill,
+        FramebufferFormat, GaussianBlur, GrayscaleWeights, Histogram, Hsl, Hsv, Image,
+        ImageBuilder, ImageFormat, ImageHash, ImageMetadata, ImageView, ImageViewMut, IntoFill,
+        Lab, Layer, LayerStack, Line, LineCap, LineJoin, Lut, Masked, MatchMethod, MirrorMode,
+        Modulate, Neighborhood, NineSlice, Oklab, Oklch, Orientation, OutOfBounds, OverlayMode,
+        Paletted, PalettedRgb, PalettedRgba, Paste, Path, PathStroke, Pattern, Pipeline, Pixel,
+        PlaceholderStyle, Polygon, Polyline, Rect, Rectangle, Rgb, Rgb16, Rgb32F, Rgba, Rgba16,
+        Rgba32F, RotationCanvas, Scratch, Sharpen, Sobel, TemplateMatch, Tonemap, TrimMode,
+        TrueColor, Vignette, Watermark, WatermarkAnchor, WatermarkPlacement, L, L16, L32F,
+    };
+
+    #[cfg(feature = "std")]
+    pub use super::{
+        BlendMethod, Decoder, DisposalMethod, Dither, Encoder, EncoderMetadata, FitMode, Frame,
+        FrameIterator, ImageSequence, LazySequence, LoopCount, QuantizeMethod, RowSink,
+        SingleFrameIterator, TerminalOptions, TerminalProtocol,
     };
 
     #[cfg(feature = "resize")]
     pub use super::ResizeAlgorithm;
+    #[cfg(feature = "barcode")]
+    pub use super::{Barcode, QrCode, QrErrorCorrection};
     #[cfg(feature = "gradient")]
     pub use super::{
-        ConicGradient, GradientBlendMode, GradientInterpolation, GradientPosition, LinearGradient,
-        RadialGradient, RadialGradientCover,
+        ConicGradient, GradientBlendMode, GradientInterpolation, GradientPosition, GradientRepeat,
+        LinearGradient, RadialGradient, RadialGradientCover,
     };
     #[cfg(feature = "text")]
-    pub use super::{Font, HorizontalAnchor, TextLayout, TextSegment, VerticalAnchor, WrapStyle};
+    pub use super::{
+        Font, GlyphCache, GlyphInfo, HorizontalAnchor, Overflow, TextLayout, TextSegment,
+        VerticalAnchor, WrapStyle,
+    };
 }
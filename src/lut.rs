@@ -0,0 +1,553 @@
+//! Lookup tables for adjusting the colors of images.
+
+use crate::{Error, Image, Result, TrueColor};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+use std::path::Path;
+
+/// A per-channel lookup table, which maps every possible value of each channel of a pixel to a
+/// new value.
+///
+/// Lookup tables generalize most color adjustments, such as brightness, contrast, gamma and
+/// curves, and can be composed with [`Lut::then`] to apply multiple adjustments in a single pass.
+/// They are applied to images with [`Image::apply_lut`].
+///
+/// # Example
+/// Applying an S-shaped curve to increase contrast, while warming up the image:
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::<Rgb>::open("photo.jpg")?;
+/// let lut = Lut::from_curve(&[(0, 0), (64, 48), (192, 208), (255, 255)])
+///     .then(&Lut::identity().with_red(Lut::curve_table(&[(0, 0), (128, 140), (255, 255)])));
+///
+/// image.apply_lut(&lut);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lut {
+    /// The table for the red channel.
+    pub red: [u8; 256],
+    /// The table for the green channel.
+    pub green: [u8; 256],
+    /// The table for the blue channel.
+    pub blue: [u8; 256],
+    /// The table for the alpha channel. This is the identity unless explicitly set.
+    pub alpha: [u8; 256],
+}
+
+impl Default for Lut {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Lut {
+    /// Creates a lookup table which maps every value to itself.
+    #[must_use]
+    pub fn identity() -> Self {
+        Self::from_fn(|value| value)
+    }
+
+    /// Creates a lookup table which uses the given table for the red, green and blue channels.
+    #[must_use]
+    pub const fn from_table(table: [u8; 256]) -> Self {
+        Self {
+            red: table,
+            green: table,
+            blue: table,
+            alpha: identity_table(),
+        }
+    }
+
+    /// Creates a lookup table which maps the red, green and blue channels using the given
+    /// function.
+    #[must_use]
+    pub fn from_fn(f: impl Fn(u8) -> u8) -> Self {
+        let mut table = [0; 256];
+        for (value, entry) in (0..=255).zip(table.iter_mut()) {
+            *entry = f(value);
+        }
+
+        Self::from_table(table)
+    }
+
+    /// Creates a lookup table which maps the red, green and blue channels along a smooth curve
+    /// through the given `(input, output)` control points.
+    ///
+    /// See [`Self::curve_table`] for more information on how the curve is formed.
+    #[must_use]
+    pub fn from_curve(points: &[(u8, u8)]) -> Self {
+        Self::from_table(Self::curve_table(points))
+    }
+
+    /// Samples a smooth curve through the given `(input, output)` control points into a table.
+    ///
+    /// The curve is interpolated using monotone cubic interpolation, so it never overshoots
+    /// between two control points. Values before the first or after the last control point are
+    /// mapped to the output of that control point. If multiple control points have the same
+    /// input, only the last one is used. No control points result in the identity table.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn curve_table(points: &[(u8, u8)]) -> [u8; 256] {
+        let mut sorted = points.to_vec();
+        sorted.sort_by_key(|&(x, _)| x);
+        sorted.reverse();
+        sorted.dedup_by_key(|&mut (x, _)| x);
+        sorted.reverse();
+
+        let (xs, ys): (Vec<f64>, Vec<f64>) = sorted
+            .into_iter()
+            .map(|(x, y)| (f64::from(x), f64::from(y)))
+            .unzip();
+
+        match xs.len() {
+            0 => return identity_table(),
+            1 => return [ys[0] as u8; 256],
+            _ => (),
+        }
+
+        let tangents = monotone_tangents(&xs, &ys);
+        let mut table = [0; 256];
+        let mut k = 0;
+
+        for (x, entry) in table.iter_mut().enumerate() {
+            let x = x as f64;
+            let y = if x <= xs[0] {
+                ys[0]
+            } else if x >= xs[xs.len() - 1] {
+                ys[ys.len() - 1]
+            } else {
+                while x > xs[k + 1] {
+                    k += 1;
+                }
+
+                let h = xs[k + 1] - xs[k];
+                let t = (x - xs[k]) / h;
+                let (t2, t3) = (t * t, t * t * t);
+
+                (2.0 * t3 - 3.0 * t2 + 1.0) * ys[k]
+                    + (t3 - 2.0 * t2 + t) * h * tangents[k]
+                    + (-2.0 * t3 + 3.0 * t2) * ys[k + 1]
+                    + (t3 - t2) * h * tangents[k + 1]
+            };
+
+            *entry = y.round().clamp(0.0, 255.0) as u8;
+        }
+
+        table
+    }
+
+    /// Sets the table for the red channel.
+    #[must_use]
+    pub const fn with_red(mut self, table: [u8; 256]) -> Self {
+        self.red = table;
+        self
+    }
+
+    /// Sets the table for the green channel.
+    #[must_use]
+    pub const fn with_green(mut self, table: [u8; 256]) -> Self {
+        self.green = table;
+        self
+    }
+
+    /// Sets the table for the blue channel.
+    #[must_use]
+    pub const fn with_blue(mut self, table: [u8; 256]) -> Self {
+        self.blue = table;
+        self
+    }
+
+    /// Sets the table for the alpha channel.
+    #[must_use]
+    pub const fn with_alpha(mut self, table: [u8; 256]) -> Self {
+        self.alpha = table;
+        self
+    }
+
+    /// Returns a lookup table which applies this lookup table, then the given one.
+    #[must_use]
+    pub fn then(&self, other: &Self) -> Self {
+        let compose = |first: &[u8; 256], second: &[u8; 256]| first.map(|v| second[v as usize]);
+
+        Self {
+            red: compose(&self.red, &other.red),
+            green: compose(&self.green, &other.green),
+            blue: compose(&self.blue, &other.blue),
+            alpha: compose(&self.alpha, &other.alpha),
+        }
+    }
+
+    /// Maps the given pixel through this lookup table.
+    #[must_use]
+    pub fn apply<P: TrueColor>(&self, pixel: P) -> P {
+        let (r, g, b, a) = pixel.as_rgba_tuple();
+
+        P::from_rgba_tuple((
+            self.red[r as usize],
+            self.green[g as usize],
+            self.blue[b as usize],
+            self.alpha[a as usize],
+        ))
+    }
+}
+
+/// A 3D lookup table, which maps every color to a new color by interpolating between a lattice
+/// of sampled colors. 3D lookup tables can express adjustments that depend on all channels at
+/// once, which makes them the standard format for color grading presets.
+///
+/// 3D lookup tables are usually loaded from Adobe/Resolve `.cube` files using [`Self::open`] or
+/// [`Self::parse`], and are applied to images with [`Image::apply_cube_lut`].
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let lut = CubeLut::open("teal_and_orange.cube")?;
+/// let image = Image::<Rgb>::open("photo.jpg")?.cube_lut_applied(&lut);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubeLut {
+    /// The title of this lookup table, if any.
+    pub title: Option<String>,
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    /// The sampled colors, with the red index changing fastest and the blue index slowest.
+    table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Creates a 3D lookup table from the given sampled colors, where each color is normalized to
+    /// the range `0.0..=1.0`. The table must have `size * size * size` entries, ordered with the
+    /// red index changing fastest and the blue index changing slowest.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The size is below 2, or the table has the wrong amount of entries.
+    pub fn new(size: usize, table: Vec<[f32; 3]>) -> Result<Self> {
+        if size < 2 {
+            return Err(Error::DecodingError(
+                "3D LUT size must be at least 2".to_string(),
+            ));
+        }
+        if table.len() != size * size * size {
+            return Err(Error::DecodingError(format!(
+                "3D LUT of size {size} must have {} entries, received {} instead",
+                size * size * size,
+                table.len(),
+            )));
+        }
+
+        Ok(Self {
+            title: None,
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            table,
+        })
+    }
+
+    /// Parses a 3D lookup table from the contents of a `.cube` file.
+    ///
+    /// 1D lookup tables (`LUT_1D_SIZE`) are not supported, use [`Lut`] for those instead.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The contents are not a valid 3D `.cube` lookup table.
+    pub fn parse(source: &str) -> Result<Self> {
+        let invalid = |line: usize, msg: &str| {
+            Error::DecodingError(format!("invalid .cube file on line {line}: {msg}"))
+        };
+        let parse_floats = |line: usize, parts: &[&str]| -> Result<[f32; 3]> {
+            match parts {
+                [r, g, b] => {
+                    let mut out = [0.0; 3];
+                    for (out, part) in out.iter_mut().zip([r, g, b]) {
+                        *out = part
+                            .parse()
+                            .map_err(|_| invalid(line, "expected a number"))?;
+                    }
+                    Ok(out)
+                }
+                _ => Err(invalid(line, "expected three numbers")),
+            }
+        };
+
+        let mut title = None;
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            match parts[0] {
+                "TITLE" => {
+                    let rest = line["TITLE".len()..].trim();
+                    title = Some(rest.trim_matches('"').to_string());
+                }
+                "LUT_3D_SIZE" => {
+                    size = Some(
+                        parts
+                            .get(1)
+                            .and_then(|size| size.parse::<usize>().ok())
+                            .ok_or_else(|| invalid(line_number, "expected a size"))?,
+                    );
+                }
+                "LUT_1D_SIZE" => {
+                    return Err(invalid(line_number, "1D lookup tables are not supported"));
+                }
+                "DOMAIN_MIN" => domain_min = parse_floats(line_number, &parts[1..])?,
+                "DOMAIN_MAX" => domain_max = parse_floats(line_number, &parts[1..])?,
+                // Unknown keywords are reserved for other applications
+                keyword if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => (),
+                _ => table.push(parse_floats(line_number, &parts)?),
+            }
+        }
+
+        let size = size.ok_or_else(|| {
+            Error::DecodingError("invalid .cube file: missing LUT_3D_SIZE".to_string())
+        })?;
+        if domain_min
+            .iter()
+            .zip(domain_max)
+            .any(|(&min, max)| min >= max)
+        {
+            return Err(Error::DecodingError(
+                "invalid .cube file: DOMAIN_MIN must be less than DOMAIN_MAX".to_string(),
+            ));
+        }
+
+        let mut lut = Self::new(size, table)?;
+        lut.title = title;
+        lut.domain_min = domain_min;
+        lut.domain_max = domain_max;
+        Ok(lut)
+    }
+
+    /// Opens and parses the `.cube` file at the given path.
+    ///
+    /// # Errors
+    /// * `IoError`: The file could not be read.
+    /// * `DecodingError`: The file is not a valid 3D `.cube` lookup table.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .map_err(Error::IoError)
+            .and_then(|source| Self::parse(&source))
+            .map_err(|err| err.with_path(path))
+    }
+
+    /// Returns the amount of samples along each axis of this lookup table.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    fn sample(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.table[(b * self.size + g) * self.size + r]
+    }
+
+    /// Maps the given pixel through this lookup table using trilinear interpolation. Alpha is
+    /// left untouched.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn apply<P: TrueColor>(&self, pixel: P) -> P {
+        let (r, g, b, a) = pixel.as_rgba_tuple();
+        let max_index = (self.size - 1) as f32;
+
+        // Position of the color within the lattice, and the lower corner of its cell
+        let mut position = [0.0; 3];
+        let mut base = [0; 3];
+        for (c, value) in [r, g, b].into_iter().enumerate() {
+            let (min, max) = (self.domain_min[c], self.domain_max[c]);
+            let normalized = ((f32::from(value) / 255.0 - min) / (max - min)).clamp(0.0, 1.0);
+            let scaled = normalized * max_index;
+            // Clamp so that the upper corner of the cell is still inside the lattice
+            base[c] = (scaled.floor() as usize).min(self.size - 2);
+            position[c] = scaled - base[c] as f32;
+        }
+
+        let [x, y, z] = position;
+        let [r0, g0, b0] = base;
+        let lerp =
+            |a: [f32; 3], b: [f32; 3], t: f32| [0, 1, 2].map(|c| (b[c] - a[c]).mul_add(t, a[c]));
+
+        let c00 = lerp(self.sample(r0, g0, b0), self.sample(r0 + 1, g0, b0), x);
+        let c10 = lerp(
+            self.sample(r0, g0 + 1, b0),
+            self.sample(r0 + 1, g0 + 1, b0),
+            x,
+        );
+        let c01 = lerp(
+            self.sample(r0, g0, b0 + 1),
+            self.sample(r0 + 1, g0, b0 + 1),
+            x,
+        );
+        let c11 = lerp(
+            self.sample(r0, g0 + 1, b0 + 1),
+            self.sample(r0 + 1, g0 + 1, b0 + 1),
+            x,
+        );
+        let color = lerp(lerp(c00, c10, y), lerp(c01, c11, y), z);
+
+        let [r, g, b] = color.map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+        P::from_rgba_tuple((r, g, b, a))
+    }
+}
+
+const fn identity_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = i as u8;
+        i += 1;
+    }
+
+    table
+}
+
+/// Computes the tangents of a monotone cubic spline through the given points using the
+/// Fritsch-Carlson method.
+fn monotone_tangents(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    let secants = (0..n - 1)
+        .map(|k| (ys[k + 1] - ys[k]) / (xs[k + 1] - xs[k]))
+        .collect::<Vec<_>>();
+
+    let mut tangents = Vec::with_capacity(n);
+    tangents.push(secants[0]);
+    for k in 1..n - 1 {
+        let (before, after) = (secants[k - 1], secants[k]);
+        // Local extrema get a flat tangent so that the curve does not overshoot
+        tangents.push(if before * after <= 0.0 {
+            0.0
+        } else {
+            (before + after) / 2.0
+        });
+    }
+    tangents.push(secants[n - 2]);
+
+    for k in 0..n - 1 {
+        if secants[k] == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+
+        let a = tangents[k] / secants[k];
+        let b = tangents[k + 1] / secants[k];
+        let magnitude = a.hypot(b);
+        if magnitude > 3.0 {
+            let scale = 3.0 / magnitude;
+            tangents[k] = scale * a * secants[k];
+            tangents[k + 1] = scale * b * secants[k];
+        }
+    }
+
+    tangents
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Maps every pixel of this image through the given lookup table in place.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut image = Image::new(1, 1, Rgb::new(0, 128, 255));
+    /// image.apply_lut(&Lut::from_fn(|value| 255 - value));
+    ///
+    /// assert_eq!(image.pixel(0, 0), &Rgb::new(255, 127, 0));
+    /// ```
+    pub fn apply_lut(&mut self, lut: &Lut) {
+        for pixel in &mut self.data {
+            *pixel = lut.apply(*pixel);
+        }
+    }
+
+    /// Takes this image and maps every pixel through the given lookup table. Useful for method
+    /// chaining.
+    #[must_use]
+    pub fn lut_applied(mut self, lut: &Lut) -> Self {
+        self.apply_lut(lut);
+        self
+    }
+
+    /// Maps every pixel of this image through the given 3D lookup table in place, using
+    /// trilinear interpolation. Alpha is left untouched.
+    pub fn apply_cube_lut(&mut self, lut: &CubeLut) {
+        for pixel in &mut self.data {
+            *pixel = lut.apply(*pixel);
+        }
+    }
+
+    /// Takes this image and maps every pixel through the given 3D lookup table. Useful for method
+    /// chaining.
+    #[must_use]
+    pub fn cube_lut_applied(mut self, lut: &CubeLut) -> Self {
+        self.apply_cube_lut(lut);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CubeLut, Lut};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_curve_table() {
+        let points = [(0, 10), (64, 40), (192, 220), (255, 240)];
+        let table = Lut::curve_table(&points);
+
+        for (x, y) in points {
+            assert_eq!(table[x as usize], y);
+        }
+        assert!(table.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        assert_eq!(Lut::curve_table(&[(100, 50), (200, 100)])[..100], [50; 100]);
+        assert_eq!(Lut::curve_table(&[]), Lut::identity().red);
+    }
+
+    #[test]
+    fn test_cube_lut() {
+        // Swaps the red and blue channels
+        let source = "TITLE \"swap\"\n# comment\nLUT_3D_SIZE 2\n\n\
+            0 0 0\n0 0 1\n0 1 0\n0 1 1\n1 0 0\n1 0 1\n1 1 0\n1 1 1\n";
+        let lut = CubeLut::parse(source).unwrap();
+        assert_eq!(lut.title.as_deref(), Some("swap"));
+        assert_eq!(
+            lut.apply(Rgba::new(255, 128, 0, 7)),
+            Rgba::new(0, 128, 255, 7)
+        );
+
+        assert!(CubeLut::parse("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(CubeLut::parse("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").is_err());
+    }
+}
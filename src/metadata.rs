@@ -0,0 +1,312 @@
+//! Ancillary metadata of images, such as text, color profiles and physical resolution.
+
+use alloc::{string::String, vec::Vec};
+
+/// Metadata attached to an [`Image`][crate::Image] which does not affect its pixels, such as
+/// copyright notices, color profiles and physical resolution.
+///
+/// Decoders populate the metadata they understand, and it is kept through edits that produce new
+/// images from existing ones. Encoders write whatever metadata their format can store and ignore
+/// the rest. Currently, the following metadata is supported:
+///
+/// | Metadata           | PNG chunks                 | JPEG segments |
+/// |--------------------|----------------------------|---------------|
+/// | Text               | `tEXt`, `zTXt` and `iTXt`  |               |
+/// | ICC color profile  | `iCCP`                     |               |
+/// | Gamma              | `gAMA`                     |               |
+/// | Physical dimension | `pHYs`                     |               |
+/// | EXIF               | `eXIf`                     | `APP1`        |
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let image = Image::<Rgb>::open("photo.png")?;
+/// if let Some(author) = image.metadata().text("Author") {
+///     println!("photo by {author}");
+/// }
+///
+/// // The metadata is kept when the image is edited and saved again
+/// let metadata = image.metadata().clone().with_text("Copyright", "CC-BY 4.0");
+/// let image = image.with_metadata(metadata).flipped();
+/// image.save(ImageFormat::Png, "flipped.png")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ImageMetadata {
+    text: Vec<(String, String)>,
+    icc_profile: Option<Vec<u8>>,
+    /// The gamma scaled by 100,000, as stored by PNG.
+    gamma: Option<u32>,
+    pixels_per_meter: Option<(u32, u32)>,
+    exif: Option<Vec<u8>>,
+}
+
+impl ImageMetadata {
+    /// Creates new, empty metadata.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            text: Vec::new(),
+            icc_profile: None,
+            gamma: None,
+            pixels_per_meter: None,
+            exif: None,
+        }
+    }
+
+    /// Returns true if there is no metadata.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+            && self.icc_profile.is_none()
+            && self.gamma.is_none()
+            && self.pixels_per_meter.is_none()
+            && self.exif.is_none()
+    }
+
+    /// Returns the text stored under the given keyword, such as `Title`, `Author` or `Copyright`.
+    /// If the keyword occurs more than once, the first text is returned.
+    #[must_use]
+    pub fn text(&self, keyword: &str) -> Option<&str> {
+        self.text
+            .iter()
+            .find(|(k, _)| k == keyword)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Returns an iterator over every keyword and text pair, in the order they were added.
+    pub fn texts(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.text
+            .iter()
+            .map(|(k, text)| (k.as_str(), text.as_str()))
+    }
+
+    /// Adds the given text under the given keyword. Existing text under the same keyword is kept.
+    ///
+    /// PNG requires keywords to be between 1 and 79 characters long and to only contain Latin-1
+    /// characters, otherwise encoding will fail. Text may contain any characters.
+    pub fn add_text(&mut self, keyword: impl Into<String>, text: impl Into<String>) {
+        self.text.push((keyword.into(), text.into()));
+    }
+
+    /// Takes this metadata and adds the given text under the given keyword.
+    ///
+    /// # See Also
+    /// - [`Self::add_text`] for more information.
+    #[must_use]
+    pub fn with_text(mut self, keyword: impl Into<String>, text: impl Into<String>) -> Self {
+        self.add_text(keyword, text);
+        self
+    }
+
+    /// Returns the embedded ICC color profile, if any.
+    #[must_use]
+    pub fn icc_profile(&self) -> Option<&[u8]> {
+        self.icc_profile.as_deref()
+    }
+
+    /// Sets the embedded ICC color profile. The profile is stored as-is and is not validated.
+    #[must_use]
+    pub fn with_icc_profile(mut self, profile: impl Into<Vec<u8>>) -> Self {
+        self.icc_profile = Some(profile.into());
+        self
+    }
+
+    /// Returns the gamma the image was encoded with, e.g. `1.0 / 2.2`, if it is known.
+    #[must_use]
+    pub fn gamma(&self) -> Option<f32> {
+        self.gamma.map(|gamma| gamma as f32 / 100_000.0)
+    }
+
+    /// Sets the gamma the image was encoded with, e.g. `1.0 / 2.2`. This is rounded to five
+    /// decimal places.
+    #[must_use]
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = Some((gamma * 100_000.0 + 0.5) as u32);
+        self
+    }
+
+    /// Returns the horizontal and vertical physical resolution of the image in pixels per meter,
+    /// if it is known.
+    #[must_use]
+    pub const fn pixels_per_meter(&self) -> Option<(u32, u32)> {
+        self.pixels_per_meter
+    }
+
+    /// Sets the horizontal and vertical physical resolution of the image in pixels per meter.
+    #[must_use]
+    pub const fn with_pixels_per_meter(mut self, x: u32, y: u32) -> Self {
+        self.pixels_per_meter = Some((x, y));
+        self
+    }
+
+    /// Returns the horizontal and vertical physical resolution of the image in dots (pixels) per
+    /// inch, rounded to the nearest integer, if it is known.
+    #[must_use]
+    pub fn dpi(&self) -> Option<(u32, u32)> {
+        // There are 0.0254 meters in an inch
+        let to_dpi = |ppm: u32| ((u64::from(ppm) * 254 + 5000) / 10_000) as u32;
+
+        self.pixels_per_meter.map(|(x, y)| (to_dpi(x), to_dpi(y)))
+    }
+
+    /// Sets the horizontal and vertical physical resolution of the image in dots (pixels) per
+    /// inch. This is stored in pixels per meter, rounded to the nearest integer.
+    #[must_use]
+    pub fn with_dpi(mut self, x: u32, y: u32) -> Self {
+        let to_ppm = |dpi: u32| ((u64::from(dpi) * 10_000 + 127) / 254) as u32;
+
+        self.pixels_per_meter = Some((to_ppm(x), to_ppm(y)));
+        self
+    }
+
+    /// Returns the raw EXIF data, starting at its TIFF header, if any.
+    #[must_use]
+    pub fn exif(&self) -> Option<&[u8]> {
+        self.exif.as_deref()
+    }
+
+    /// Sets the raw EXIF data, which must start at its TIFF header (`II*\0` or `MM\0*`). The data
+    /// is stored as-is and is not validated.
+    #[must_use]
+    pub fn with_exif(mut self, exif: impl Into<Vec<u8>>) -> Self {
+        self.exif = Some(exif.into());
+        self
+    }
+
+    /// Returns the orientation stored in the EXIF data, if any.
+    ///
+    /// # See Also
+    /// - [`Image::auto_orient`][crate::Image::auto_orient] to make an image upright according to
+    ///   this orientation.
+    #[must_use]
+    pub fn orientation(&self) -> Option<Orientation> {
+        let exif = self.exif.as_deref()?;
+        let (offset, big_endian) = exif_orientation_offset(exif)?;
+
+        Orientation::from_exif(read_u16(exif, offset, big_endian)?)
+    }
+
+    /// Sets the orientation stored in the EXIF data. This does nothing if the EXIF data does not
+    /// already have an orientation.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        let exif = match self.exif.as_deref_mut() {
+            Some(exif) => exif,
+            None => return,
+        };
+
+        if let Some((offset, big_endian)) = exif_orientation_offset(exif) {
+            let value = orientation as u16;
+            let bytes = if big_endian {
+                value.to_be_bytes()
+            } else {
+                value.to_le_bytes()
+            };
+            exif[offset..offset + 2].copy_from_slice(&bytes);
+        }
+    }
+}
+
+/// The orientation of an image, as stored in its EXIF data. Cameras usually store images in the
+/// orientation of their sensor and use this to describe how the image must be transformed to be
+/// displayed upright.
+///
+/// Each variant is named after the transformation which makes the image upright, and its
+/// discriminant is the value EXIF uses for it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// The image is already upright.
+    #[default]
+    Normal = 1,
+    /// The image must be mirrored horizontally (about the y-axis).
+    MirrorHorizontal = 2,
+    /// The image must be rotated by 180 degrees.
+    Rotate180 = 3,
+    /// The image must be flipped vertically (about the x-axis).
+    MirrorVertical = 4,
+    /// The image must be transposed, or flipped about its top-left to bottom-right diagonal.
+    Transpose = 5,
+    /// The image must be rotated by 90 degrees clockwise.
+    Rotate90 = 6,
+    /// The image must be transversed, or flipped about its top-right to bottom-left diagonal.
+    Transverse = 7,
+    /// The image must be rotated by 270 degrees clockwise, or 90 degrees counterclockwise.
+    Rotate270 = 8,
+}
+
+impl Orientation {
+    /// Returns the orientation for the given EXIF orientation value, or `None` if the value is not
+    /// between 1 and 8.
+    #[must_use]
+    pub const fn from_exif(value: u16) -> Option<Self> {
+        Some(match value {
+            1 => Self::Normal,
+            2 => Self::MirrorHorizontal,
+            3 => Self::Rotate180,
+            4 => Self::MirrorVertical,
+            5 => Self::Transpose,
+            6 => Self::Rotate90,
+            7 => Self::Transverse,
+            8 => Self::Rotate270,
+            _ => return None,
+        })
+    }
+
+    /// Returns true if the width and height of the image are swapped by this orientation.
+    #[must_use]
+    pub const fn swaps_dimensions(self) -> bool {
+        matches!(
+            self,
+            Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270
+        )
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize, big_endian: bool) -> Option<u16> {
+    let bytes = [*data.get(offset)?, *data.get(offset + 1)?];
+
+    Some(if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    })
+}
+
+fn read_u32(data: &[u8], offset: usize, big_endian: bool) -> Option<u32> {
+    let bytes = [
+        *data.get(offset)?,
+        *data.get(offset + 1)?,
+        *data.get(offset + 2)?,
+        *data.get(offset + 3)?,
+    ];
+
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+/// Finds the orientation tag in the first IFD of the given EXIF data, returning the offset of its
+/// value and whether the data is big-endian.
+fn exif_orientation_offset(exif: &[u8]) -> Option<(usize, bool)> {
+    const ORIENTATION_TAG: u16 = 0x0112;
+    const SHORT_TYPE: u16 = 3;
+
+    let big_endian = match exif.get(0..4)? {
+        b"II*\0" => false,
+        b"MM\0*" => true,
+        _ => return None,
+    };
+    let ifd = read_u32(exif, 4, big_endian)? as usize;
+    let entries = read_u16(exif, ifd, big_endian)?;
+
+    (0..entries as usize)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|&entry| read_u16(exif, entry, big_endian) == Some(ORIENTATION_TAG))
+        .filter(|&entry| read_u16(exif, entry + 2, big_endian) == Some(SHORT_TYPE))
+        .map(|entry| (entry + 8, big_endian))
+        .filter(|&(offset, _)| offset + 2 <= exif.len())
+}
@@ -1,9 +1,14 @@
 //! Implements morphological transformations e.g. dilate and erode
 
-use std::ops::DerefMut;
+use alloc::{vec, vec::Vec};
+use core::ops::DerefMut;
 
-use crate::pixel::{BitPixel, Pixel, L};
-use crate::{Banded, Draw, Image, Rgba};
+use crate::fill::{Fill, IntoFill};
+use crate::filter::GaussianBlur;
+use crate::pixel::{BitPixel, Pixel, TrueColor, L, L32F};
+use crate::{parallel, Banded, Draw, Image, OverlayMode, Paste, Rgba, Scratch};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 /// Useful kernel shapes for morphology operations
 #[derive(Copy, Clone, Debug)]
@@ -16,6 +21,9 @@ pub enum KernelShape {
     Ellipse,
     /// Ellipse shaped kernel, with anti alias
     EllipseAa,
+    /// Diamond shaped kernel, i.e. a square rotated by 45 degrees touching the middle of every
+    /// edge of the kernel
+    Diamond,
 }
 
 pub struct KernelImage {
@@ -102,10 +110,66 @@ impl KernelImage {
             }
             KernelShape::Ellipse => kernel.draw_ellipse(false),
             KernelShape::EllipseAa => kernel.draw_ellipse(true),
+            KernelShape::Diamond => {
+                let (cx, cy) = ((width - 1) as f32 / 2.0, (height - 1) as f32 / 2.0);
+                let (a, b) = (width as f32 / 2.0, height as f32 / 2.0);
+                for y in 0..height {
+                    for x in 0..width {
+                        if (x as f32 - cx).abs() / a + (y as f32 - cy).abs() / b <= 1.0 {
+                            *kernel.pixel_mut(x, y) = 1.0;
+                        }
+                    }
+                }
+            }
         };
         kernel
     }
 
+    /// Creates a kernel image from the given image, where the luminance of every pixel is its
+    /// weight, e.g. white cells have a weight of `1.0` and black cells are skipped. This allows
+    /// arbitrary structuring elements, such as directional or star shaped kernels, to be drawn
+    /// with the usual drawing operations.
+    #[must_use]
+    pub fn from_image(image: &Image<L>) -> Self {
+        Self {
+            data: image
+                .data
+                .iter()
+                .map(|&L(value)| f32::from(value) / u8::MAX as f32)
+                .collect(),
+            width: image.width(),
+            height: image.height(),
+        }
+    }
+
+    /// Creates a kernel image from the given weights in row-major order, with rows of the given
+    /// width. Weights are usually in the range `0.0..=1.0`, where cells with a weight of `0.0` are
+    /// skipped.
+    ///
+    /// # Panics
+    /// * The width is zero, or the number of weights is not a multiple of the width.
+    ///
+    /// # Example
+    /// ```
+    /// use ril::morph::KernelImage;
+    ///
+    /// // A horizontal line with a faded center
+    /// let kernel = KernelImage::from_weights(3, &[1.0, 0.5, 1.0]);
+    /// assert_eq!(kernel.dimensions(), (3, 1));
+    /// ```
+    #[must_use]
+    pub fn from_weights(width: u32, weights: &[f32]) -> Self {
+        assert!(
+            width > 0 && weights.chunks_exact(width as usize).remainder().is_empty(),
+            "width must be non-zero and divide the number of weights"
+        );
+        Self {
+            data: weights.to_vec(),
+            width,
+            height: (weights.len() / width as usize) as u32,
+        }
+    }
+
     fn draw_ellipse(&mut self, anti_alias: bool) {
         let (ox, oy) = (self.width / 2, self.height / 2);
         let (px, py) = (1 - self.width % 2, 1 - self.height % 2);
@@ -169,6 +233,208 @@ impl KernelImage {
     pub const fn dimensions(&self) -> (u32, u32) {
         (self.width, self.height)
     }
+
+    /// Returns the offsets and weights of the cells of this kernel that satisfy the given
+    /// predicate, relative to the given anchor, e.g. (0.5, 0.5) for the center of the kernel.
+    /// Morphology operations only visit these cells, since most cells of shapes such as crosses
+    /// and ellipses have no weight.
+    fn active_points(
+        &self,
+        (ax, ay): (f64, f64),
+        predicate: impl Fn(f32) -> bool,
+    ) -> Vec<(i64, i64, f32)> {
+        let (ax, ay) = (
+            (self.width as f64 * ax) as u32,
+            (self.height as f64 * ay) as u32,
+        );
+        (0..self.height)
+            .flat_map(|ky| (0..self.width).map(move |kx| (kx, ky)))
+            .zip(&self.data)
+            .filter(|&(_, &k)| predicate(k))
+            .map(|((kx, ky), &k)| {
+                (
+                    i64::from(kx) - i64::from(ax),
+                    i64::from(ky) - i64::from(ay),
+                    k,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Writes the given rows of pixels onto the image with the top-left corner at the given position,
+/// clipping anything that falls outside of the image. Pixels are replaced unless an overlay mode is
+/// given.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn place<P: Pixel>(
+    image: &mut Image<P>,
+    (x1, y1): (i64, i64),
+    width: u32,
+    pixels: &[P],
+    overlay: Option<OverlayMode>,
+) {
+    let (image_width, image_height) = (i64::from(image.width()), i64::from(image.height()));
+
+    for (y, row) in (y1..).zip(pixels.chunks_exact(width as usize)) {
+        if y < 0 || y >= image_height {
+            continue;
+        }
+        for (x, &pixel) in (x1..).zip(row) {
+            if x >= 0 && x < image_width {
+                match overlay {
+                    Some(mode) => image.overlay_pixel_with_mode(x as u32, y as u32, pixel, mode),
+                    None => *image.pixel_mut(x as u32, y as u32) = pixel,
+                }
+            }
+        }
+    }
+}
+
+/// Returns the offsets `(dx1, dy1, dx2, dy2)` of the corners of the given kernel points if they
+/// fill a rectangle with a weight of `1.0`, in which case the kernel is separable.
+fn rectangle(points: &[(i64, i64, f32)]) -> Option<(i64, i64, i64, i64)> {
+    let (&(x, y, _), rest) = points.split_first()?;
+    let (mut x1, mut y1, mut x2, mut y2) = (x, y, x, y);
+    for &(x, y, _) in rest {
+        x1 = x1.min(x);
+        y1 = y1.min(y);
+        x2 = x2.max(x);
+        y2 = y2.max(y);
+    }
+
+    // Points are distinct cells, so the rectangle is full if it has as many cells as points
+    let full = (x2 - x1 + 1) * (y2 - y1 + 1) == points.len() as i64;
+    if full && points.iter().all(|&(_, _, k)| k == 1.0) {
+        Some((x1, y1, x2, y2))
+    } else {
+        None
+    }
+}
+
+/// Returns the transpose of the given rows of pixels with the given width.
+fn transpose<P: Copy + Send + Sync>(data: &[P], width: usize) -> Vec<P> {
+    let height = data.len() / width;
+    parallel::map_rows(width as u32, |x| {
+        (0..height).map(move |y| data[y * width + x as usize])
+    })
+}
+
+/// Folds every window of `size` consecutive values with `f` into `out`, which has room for
+/// `values.len() - size + 1` windows. This uses the van Herk/Gil-Werman algorithm, which takes
+/// three applications of `f` per value regardless of the window size, but requires `f` to be
+/// idempotent, such as a maximum or minimum.
+fn fold_windows<P: Copy>(values: &[P], size: usize, f: &impl Fn(P, P) -> P, out: &mut [P]) {
+    // Folds of every block of `size` values, from the start of the block and from its end
+    let mut prefix = values.to_vec();
+    let mut suffix = values.to_vec();
+    for i in 1..values.len() {
+        if i % size != 0 {
+            prefix[i] = f(prefix[i - 1], values[i]);
+        }
+    }
+    for i in (0..values.len().saturating_sub(1)).rev() {
+        if (i + 1) % size != 0 {
+            suffix[i] = f(suffix[i + 1], values[i]);
+        }
+    }
+
+    // Every window spans the end of one block and the start of the next
+    for (i, value) in out.iter_mut().enumerate() {
+        *value = f(suffix[i], prefix[i + size - 1]);
+    }
+}
+
+/// Applies a morphology operation with a rectangular kernel spanning the given offsets as a
+/// horizontal pass followed by a vertical pass, which takes the same time regardless of the size
+/// of the kernel. Produces the same result as [`morph_pixels`].
+fn morph_separable<P: Pixel>(
+    src: &[P],
+    width: u32,
+    (dx1, dy1, dx2, dy2): (i64, i64, i64, i64),
+    out: &mut [P],
+    init: P,
+    f: impl Fn(P, P) -> P + Send + Sync,
+) {
+    let (w, h) = (width as usize, src.len() / width as usize);
+    // Offsets before the start of a line are skipped, while offsets past its end count as `end`
+    let extend = |line: &[P], lo: i64, hi: i64, end: P| -> Vec<P> {
+        (lo..line.len() as i64 + hi)
+            .map(|i| match usize::try_from(i) {
+                Ok(i) => line.get(i).copied().unwrap_or(end),
+                Err(_) => init,
+            })
+            .collect()
+    };
+
+    let mut rows = vec![init; src.len()];
+    parallel::for_each_row_mut(&mut rows, w, |y, row| {
+        let line = &src[y as usize * w..][..w];
+        let values = extend(line, dx1, dx2, P::default());
+        fold_windows(&values, (dx2 - dx1 + 1) as usize, &f, row);
+    });
+
+    let columns = transpose(&rows, w);
+    let mut result = vec![init; src.len()];
+    parallel::for_each_row_mut(&mut result, h, |x, column| {
+        // Windows entirely left of the source only contain skipped offsets, even past its bottom
+        let end = if i64::from(x) + dx2 >= 0 {
+            P::default()
+        } else {
+            init
+        };
+        let line = &columns[x as usize * h..][..h];
+        let values = extend(line, dy1, dy2, end);
+        fold_windows(&values, (dy2 - dy1 + 1) as usize, &f, column);
+    });
+    out.copy_from_slice(&transpose(&result, h));
+}
+
+/// Writes the result of a morphology operation on the given rows of pixels with the given width
+/// into `out`, folding the weighted pixels under every given kernel point into `init` with `f`.
+/// Points that fall past the bottom or right edge of the source count as [`Pixel::default`].
+///
+/// Rectangular kernels are applied in two separable passes. Other kernels visit every point for
+/// every pixel.
+fn morph_pixels<P: Pixel>(
+    src: &[P],
+    width: u32,
+    points: &[(i64, i64, f32)],
+    out: &mut [P],
+    init: P,
+    f: impl Fn(P, P) -> P + Send + Sync,
+) {
+    if let Some(bounds) = rectangle(points) {
+        return morph_separable(src, width, bounds, out, init, f);
+    }
+    let (w, h) = (i64::from(width), (src.len() / width as usize) as i64);
+
+    parallel::for_each_row_mut(out, width as usize, |y, row| {
+        for (x, pixel) in (0..).zip(row) {
+            let mut m = init;
+            for &(dx, dy, k) in points {
+                let (sx, sy) = (x + dx, i64::from(y) + dy);
+                if sx >= 0 && sy >= 0 {
+                    let p = if sx < w && sy < h {
+                        src[(sy * w + sx) as usize] * k
+                    } else {
+                        P::default()
+                    };
+                    m = f(m, p);
+                }
+            }
+            *pixel = m;
+        }
+    });
+}
+
+/// Dilates the given rows of pixels into `out` using the given kernel points.
+fn dilate_pixels<P: Pixel>(src: &[P], width: u32, points: &[(i64, i64, f32)], out: &mut [P]) {
+    morph_pixels(src, width, points, out, P::default(), P::max);
+}
+
+/// Erodes the given rows of pixels into `out` using the given kernel points.
+fn erode_pixels<P: Pixel>(src: &[P], width: u32, points: &[(i64, i64, f32)], out: &mut [P]) {
+    morph_pixels(src, width, points, out, !P::default(), P::min);
 }
 
 /// Configuration options regarding behavior of dilation
@@ -179,9 +445,11 @@ pub struct Dilation<'src, 'ker, P: Pixel> {
     /// A reference to the kernel image
     kernel: &'ker KernelImage,
     /// The position to place the dilated image
-    position: (u32, u32),
+    position: (i64, i64),
     /// The position of the anchor within the kernel, e.g., (0.5, 0.5) anchors to the center of the kernel
     anchor: (f64, f64),
+    /// The overlay mode used to place the result, or None to replace the pixels of the image
+    overlay: Option<OverlayMode>,
 }
 
 impl<'src, 'ker, P: Pixel> Dilation<'src, 'ker, P> {
@@ -193,12 +461,14 @@ impl<'src, 'ker, P: Pixel> Dilation<'src, 'ker, P> {
             kernel,
             position: (0, 0),
             anchor: (0.5, 0.5),
+            overlay: None,
         }
     }
 
-    /// Sets the position to place the dilated image
+    /// Sets the position to place the dilated image. The position may be negative, in which case
+    /// the dilated image is clipped.
     #[must_use]
-    pub const fn with_position(mut self, x: u32, y: u32) -> Self {
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
         self.position = (x, y);
         self
     }
@@ -209,46 +479,52 @@ impl<'src, 'ker, P: Pixel> Dilation<'src, 'ker, P> {
         self.anchor = (x, y);
         self
     }
+
+    /// Sets the overlay mode used to place the result onto the image. By default, the pixels of
+    /// the image are replaced.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Writes the dilated pixels of the source image into the given buffer, which must have
+    /// the same length as the source image.
+    fn dilate(&self, pixels: &mut [P]) {
+        // Cells without weight can never raise the maximum, so they are skipped
+        let points = self.kernel.active_points(self.anchor, |k| k != 0.0);
+        dilate_pixels(&self.src.data, self.src.width(), &points, pixels);
+    }
 }
 
 impl<'src, 'ker, P: Pixel> Draw<P> for Dilation<'src, 'ker, P> {
     fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
-        let src = self.src;
-        let kernel = self.kernel;
-
-        let (w, h) = src.dimensions();
-        let (kw, kh) = kernel.dimensions();
-        let (ax, ay) = self.anchor;
-        let (ax, ay) = ((kw as f64 * ax) as u32, (kh as f64 * ay) as u32);
-        let (x1, y1) = self.position;
-        let (x2, y2) = (x1 + w, y1 + h);
-
-        let d = |x: u32, y: u32| -> P {
-            let mut m: P = P::default();
-            for ky in 0..kh {
-                for kx in 0..kw {
-                    if let Some(k) = kernel.get_pixel(kx, ky).copied() {
-                        if let (Some(sx), Some(sy)) =
-                            ((x + kx).checked_sub(ax), (y + ky).checked_sub(ay))
-                        {
-                            m = m.max(
-                                src.get_pixel(sx, sy)
-                                    .copied()
-                                    .map(|p| p * k)
-                                    .unwrap_or(P::default()),
-                            );
-                        }
-                    }
-                }
-            }
-            m
-        };
+        let mut pixels = vec![P::default(); self.src.data.len()];
+        self.dilate(&mut pixels);
+        place(
+            &mut image,
+            self.position,
+            self.src.width(),
+            &pixels,
+            self.overlay,
+        );
+    }
 
-        for (y, i) in (y1..y2).zip(0..) {
-            for (x, j) in (x1..x2).zip(0..) {
-                *image.pixel_mut(x, y) = d(j, i);
-            }
-        }
+    fn draw_with_scratch<I: DerefMut<Target = Image<P>>>(
+        &self,
+        mut image: I,
+        scratch: &mut Scratch<P>,
+    ) {
+        let mut pixels = scratch.take(self.src.data.len(), P::default());
+        self.dilate(&mut pixels);
+        place(
+            &mut image,
+            self.position,
+            self.src.width(),
+            &pixels,
+            self.overlay,
+        );
+        scratch.recycle(pixels);
     }
 }
 
@@ -260,9 +536,11 @@ pub struct Erosion<'src, 'ker, P: Pixel> {
     /// A reference to the kernel image
     kernel: &'ker KernelImage,
     /// The position to place the eroded image
-    position: (u32, u32),
+    position: (i64, i64),
     /// The position of the anchor within the kernel, e.g., (0.5, 0.5) anchors to the center of the kernel
     anchor: (f64, f64),
+    /// The overlay mode used to place the result, or None to replace the pixels of the image
+    overlay: Option<OverlayMode>,
 }
 
 impl<'src, 'ker, P: Pixel> Erosion<'src, 'ker, P> {
@@ -274,12 +552,14 @@ impl<'src, 'ker, P: Pixel> Erosion<'src, 'ker, P> {
             kernel,
             position: (0, 0),
             anchor: (0.5, 0.5),
+            overlay: None,
         }
     }
 
-    /// Sets the position to place the dilated image
+    /// Sets the position to place the dilated image. The position may be negative, in which case
+    /// the dilated image is clipped.
     #[must_use]
-    pub const fn with_position(mut self, x: u32, y: u32) -> Self {
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
         self.position = (x, y);
         self
     }
@@ -290,110 +570,929 @@ impl<'src, 'ker, P: Pixel> Erosion<'src, 'ker, P> {
         self.anchor = (x, y);
         self
     }
+
+    /// Sets the overlay mode used to place the result onto the image. By default, the pixels of
+    /// the image are replaced.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Writes the eroded pixels of the source image into the given buffer, which must have
+    /// the same length as the source image.
+    fn erode(&self, pixels: &mut [P]) {
+        let points = self.kernel.active_points(self.anchor, |k| k > 0.0);
+        erode_pixels(&self.src.data, self.src.width(), &points, pixels);
+    }
 }
 
 impl<'src, 'ker, P: Pixel> Draw<P> for Erosion<'src, 'ker, P> {
     fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
-        let src = self.src;
-        let kernel = self.kernel;
+        let mut pixels = vec![P::default(); self.src.data.len()];
+        self.erode(&mut pixels);
+        place(
+            &mut image,
+            self.position,
+            self.src.width(),
+            &pixels,
+            self.overlay,
+        );
+    }
 
-        let (w, h) = src.dimensions();
-        let (kw, kh) = kernel.dimensions();
-        let (ax, ay) = self.anchor;
-        let (ax, ay) = ((kw as f64 * ax) as u32, (kh as f64 * ay) as u32);
-        let (x1, y1) = self.position;
-        let (x2, y2) = (x1 + w, y1 + h);
-
-        let d = |x: u32, y: u32| -> P {
-            let mut m: P = !P::default();
-            for ky in 0..kh {
-                for kx in 0..kw {
-                    if let Some(k) = kernel.get_pixel(kx, ky).copied() {
-                        if !(k > 0.0) {
-                            continue;
-                        }
-                        if let (Some(sx), Some(sy)) =
-                            ((x + kx).checked_sub(ax), (y + ky).checked_sub(ay))
-                        {
-                            m = m.min(
-                                src.get_pixel(sx, sy)
-                                    .copied()
-                                    .map(|p| p * k)
-                                    .unwrap_or(P::default()),
-                            );
-                        }
-                    }
+    fn draw_with_scratch<I: DerefMut<Target = Image<P>>>(
+        &self,
+        mut image: I,
+        scratch: &mut Scratch<P>,
+    ) {
+        let mut pixels = scratch.take(self.src.data.len(), P::default());
+        self.erode(&mut pixels);
+        place(
+            &mut image,
+            self.position,
+            self.src.width(),
+            &pixels,
+            self.overlay,
+        );
+        scratch.recycle(pixels);
+    }
+}
+
+/// A morphology operation that is composed of dilations and erosions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MorphOperation {
+    /// An erosion followed by a dilation, which removes small bright spots and thin protrusions
+    /// while keeping the shape of larger objects.
+    Opening,
+    /// A dilation followed by an erosion, which fills small dark holes and gaps while keeping the
+    /// shape of larger objects.
+    Closing,
+    /// The difference between the dilation and the erosion, which outlines the edges of objects.
+    Gradient,
+    /// The difference between the image and its opening, which extracts bright details smaller
+    /// than the kernel.
+    TopHat,
+    /// The difference between the closing of the image and the image, which extracts dark details
+    /// smaller than the kernel.
+    BlackHat,
+}
+
+/// Applies a compound [`MorphOperation`], such as an opening or a morphological gradient, to an
+/// image in a single draw.
+///
+/// The second pass of openings and closings uses the kernel reflected around its anchor, so that
+/// they are correct for asymmetric kernels and anchors as well. Differences are computed per
+/// channel, while the alpha channel, if any, is kept from the source image.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// use ril::morph::{KernelImage, KernelShape, MorphOperation, Morphology};
+///
+/// # fn main() -> ril::Result<()> {
+/// let scan = Image::<L>::open("scan.png")?;
+/// let kernel = KernelImage::from_shape(KernelShape::Ellipse, 5, 5);
+///
+/// // Remove specks of noise smaller than the kernel
+/// let mut image = Image::new(scan.width(), scan.height(), L(0));
+/// image.draw(&Morphology::new(&scan, &kernel, MorphOperation::Opening));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Morphology<'src, 'ker, P: Pixel> {
+    /// A reference to the source image
+    src: &'src Image<P>,
+    /// A reference to the kernel image
+    kernel: &'ker KernelImage,
+    /// The operation to apply
+    operation: MorphOperation,
+    /// The position to place the resulting image
+    position: (i64, i64),
+    /// The position of the anchor within the kernel, e.g., (0.5, 0.5) anchors to the center of the kernel
+    anchor: (f64, f64),
+    /// The overlay mode used to place the result, or None to replace the pixels of the image
+    overlay: Option<OverlayMode>,
+}
+
+impl<'src, 'ker, P: Pixel> Morphology<'src, 'ker, P> {
+    /// Creates a new [`Morphology`] applying the given operation, with default settings.
+    #[must_use]
+    pub fn new(src: &'src Image<P>, kernel: &'ker KernelImage, operation: MorphOperation) -> Self {
+        Self {
+            src,
+            kernel,
+            operation,
+            position: (0, 0),
+            anchor: (0.5, 0.5),
+            overlay: None,
+        }
+    }
+
+    /// Sets the position to place the resulting image. The position may be negative, in which
+    /// case the result is clipped.
+    #[must_use]
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the anchor of the kernel, e.g. (0.5, 0.5) anchors to the center of the kernel.
+    #[must_use]
+    pub const fn with_anchor(mut self, x: f64, y: f64) -> Self {
+        self.anchor = (x, y);
+        self
+    }
+
+    /// Sets the overlay mode used to place the result onto the image. By default, the pixels of
+    /// the image are replaced.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+}
+
+impl<'src, 'ker, P: TrueColor> Morphology<'src, 'ker, P> {
+    /// Writes the result of the operation into the given buffer, which must have the same length
+    /// as the source image. The intermediate result is borrowed from the given scratch.
+    fn apply(&self, pixels: &mut [P], scratch: &mut Scratch<P>) {
+        let src = &self.src.data;
+        let width = self.src.width();
+        let dilation = self.kernel.active_points(self.anchor, |k| k != 0.0);
+        let erosion = self.kernel.active_points(self.anchor, |k| k > 0.0);
+        let reflect = |points: Vec<(i64, i64, f32)>| {
+            points
+                .into_iter()
+                .map(|(dx, dy, k)| (-dx, -dy, k))
+                .collect::<Vec<_>>()
+        };
+
+        let mut temp = scratch.take(src.len(), P::default());
+        match self.operation {
+            MorphOperation::Opening => {
+                erode_pixels(src, width, &erosion, &mut temp);
+                dilate_pixels(&temp, width, &reflect(dilation), pixels);
+            }
+            MorphOperation::Closing => {
+                dilate_pixels(src, width, &dilation, &mut temp);
+                erode_pixels(&temp, width, &reflect(erosion), pixels);
+            }
+            MorphOperation::Gradient => {
+                dilate_pixels(src, width, &dilation, pixels);
+                erode_pixels(src, width, &erosion, &mut temp);
+                for ((pixel, &eroded), &source) in pixels.iter_mut().zip(&temp).zip(src) {
+                    *pixel = difference(*pixel, eroded, source);
+                }
+            }
+            MorphOperation::TopHat => {
+                erode_pixels(src, width, &erosion, pixels);
+                dilate_pixels(pixels, width, &reflect(dilation), &mut temp);
+                for ((pixel, &opened), &source) in pixels.iter_mut().zip(&temp).zip(src) {
+                    *pixel = difference(source, opened, source);
+                }
+            }
+            MorphOperation::BlackHat => {
+                dilate_pixels(src, width, &dilation, pixels);
+                erode_pixels(pixels, width, &reflect(erosion), &mut temp);
+                for ((pixel, &closed), &source) in pixels.iter_mut().zip(&temp).zip(src) {
+                    *pixel = difference(closed, source, source);
                 }
             }
-            m
+        }
+        scratch.recycle(temp);
+    }
+}
+
+/// Returns the per-channel difference `a - b`, clamped at zero, with the alpha of `alpha`.
+fn difference<P: TrueColor>(a: P, b: P, alpha: P) -> P {
+    let (a, b) = (a.as_rgba_tuple(), b.as_rgba_tuple());
+    P::from_rgba_tuple((
+        a.0.saturating_sub(b.0),
+        a.1.saturating_sub(b.1),
+        a.2.saturating_sub(b.2),
+        alpha.as_rgba_tuple().3,
+    ))
+}
+
+impl<'src, 'ker, P: TrueColor> Draw<P> for Morphology<'src, 'ker, P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, image: I) {
+        self.draw_with_scratch(image, &mut Scratch::new());
+    }
+
+    fn draw_with_scratch<I: DerefMut<Target = Image<P>>>(
+        &self,
+        mut image: I,
+        scratch: &mut Scratch<P>,
+    ) {
+        let mut pixels = scratch.take(self.src.data.len(), P::default());
+        self.apply(&mut pixels, scratch);
+        place(
+            &mut image,
+            self.position,
+            self.src.width(),
+            &pixels,
+            self.overlay,
+        );
+        scratch.recycle(pixels);
+    }
+}
+
+/// Applies the hit-or-miss transform to a binary image, which turns on exactly the pixels where
+/// a pattern of foreground and background cells matches the image, such as corners, endpoints of
+/// lines or isolated pixels.
+///
+/// The pattern is given as a tri-state kernel, where cells with a positive weight must be on,
+/// cells with a negative weight must be off and cells with a weight of `0.0` are ignored.
+/// Alternatively, the background cells can be given as a separate kernel with
+/// [`with_background`][Self::with_background]. Pixels outside of the source image are considered
+/// to be off.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// use ril::morph::{HitOrMiss, KernelImage};
+///
+/// // Matches the top-left corners of shapes
+/// let corner = KernelImage::from_weights(3, &[
+///     0.0, -1.0, 0.0,
+///     -1.0, 1.0, 1.0,
+///     0.0, 1.0, 0.0,
+/// ]);
+///
+/// let mut square = Image::new(8, 8, BitPixel::off());
+/// for (x, y) in (2..6).flat_map(|y| (2..6).map(move |x| (x, y))) {
+///     *square.pixel_mut(x, y) = BitPixel::on();
+/// }
+///
+/// let mut corners = Image::new(8, 8, BitPixel::off());
+/// corners.draw(&HitOrMiss::new(&square, &corner));
+/// assert!(corners.pixel(2, 2).value());
+/// assert_eq!(corners.data.iter().filter(|pixel| pixel.value()).count(), 1);
+/// ```
+#[derive(Clone)]
+pub struct HitOrMiss<'src, 'ker> {
+    /// A reference to the source image
+    src: &'src Image<BitPixel>,
+    /// A reference to the kernel image of the pattern
+    kernel: &'ker KernelImage,
+    /// A reference to the kernel image of the background cells, or None to use the cells of
+    /// `kernel` with a negative weight
+    background: Option<&'ker KernelImage>,
+    /// The position to place the resulting image
+    position: (i64, i64),
+    /// The position of the anchor within the kernel, e.g., (0.5, 0.5) anchors to the center of the kernel
+    anchor: (f64, f64),
+    /// The overlay mode used to place the result, or None to replace the pixels of the image
+    overlay: Option<OverlayMode>,
+}
+
+impl<'src, 'ker> HitOrMiss<'src, 'ker> {
+    /// Creates a new [`HitOrMiss`] matching the given tri-state kernel, with default settings.
+    #[must_use]
+    pub const fn new(src: &'src Image<BitPixel>, kernel: &'ker KernelImage) -> Self {
+        Self {
+            src,
+            kernel,
+            background: None,
+            position: (0, 0),
+            anchor: (0.5, 0.5),
+            overlay: None,
+        }
+    }
+
+    /// Sets a separate kernel for the cells that must be off, in which case the cells of both
+    /// kernels with a positive weight are used. The kernels should have the same dimensions, since
+    /// the anchor is resolved against each of them.
+    #[must_use]
+    pub const fn with_background(mut self, kernel: &'ker KernelImage) -> Self {
+        self.background = Some(kernel);
+        self
+    }
+
+    /// Sets the position to place the resulting image. The position may be negative, in which
+    /// case the result is clipped.
+    #[must_use]
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets the anchor of the kernel, e.g. (0.5, 0.5) anchors to the center of the kernel.
+    #[must_use]
+    pub const fn with_anchor(mut self, x: f64, y: f64) -> Self {
+        self.anchor = (x, y);
+        self
+    }
+
+    /// Sets the overlay mode used to place the result onto the image. By default, the pixels of
+    /// the image are replaced.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Writes the matches of the pattern into the given buffer, which must have the same length
+    /// as the source image.
+    fn apply(&self, pixels: &mut [BitPixel]) {
+        let hits = self.kernel.active_points(self.anchor, |k| k > 0.0);
+        let misses = match self.background {
+            Some(background) => background.active_points(self.anchor, |k| k > 0.0),
+            None => self.kernel.active_points(self.anchor, |k| k < 0.0),
+        };
+
+        let (width, height) = self.src.dimensions();
+        let (w, h) = (i64::from(width), i64::from(height));
+        let data = &self.src.data;
+        let on =
+            |x: i64, y: i64| x >= 0 && y >= 0 && x < w && y < h && data[(y * w + x) as usize].0;
+
+        parallel::for_each_row_mut(pixels, width as usize, |y, row| {
+            let y = i64::from(y);
+            for (x, pixel) in (0..).zip(row.iter_mut()) {
+                *pixel = BitPixel(
+                    hits.iter().all(|&(dx, dy, _)| on(x + dx, y + dy))
+                        && !misses.iter().any(|&(dx, dy, _)| on(x + dx, y + dy)),
+                );
+            }
+        });
+    }
+}
+
+impl<'src, 'ker> Draw<BitPixel> for HitOrMiss<'src, 'ker> {
+    fn draw<I: DerefMut<Target = Image<BitPixel>>>(&self, image: I) {
+        self.draw_with_scratch(image, &mut Scratch::new());
+    }
+
+    fn draw_with_scratch<I: DerefMut<Target = Image<BitPixel>>>(
+        &self,
+        mut image: I,
+        scratch: &mut Scratch<BitPixel>,
+    ) {
+        let mut pixels = scratch.take(self.src.data.len(), BitPixel::off());
+        self.apply(&mut pixels);
+        place(
+            &mut image,
+            self.position,
+            self.src.width(),
+            &pixels,
+            self.overlay,
+        );
+        scratch.recycle(pixels);
+    }
+}
+
+/// The metric used to measure distances in [`Image::distance_transform`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DistanceMetric {
+    /// The sum of the horizontal and vertical distance, i.e. the number of steps between pixels
+    /// when only moving horizontally or vertically. Also known as the L1 or taxicab distance.
+    Manhattan,
+    /// The straight-line distance. Also known as the L2 distance.
+    Euclidean,
+    /// The larger of the horizontal and vertical distance, i.e. the number of steps between pixels
+    /// when also moving diagonally. Also known as the L∞ or chessboard distance.
+    Chebyshev,
+}
+
+/// Replaces every value with the smallest of it and its already visited neighbors at the given
+/// offsets plus one, visiting pixels in row-major order if `forward`, otherwise in reverse.
+fn chamfer_pass(distances: &mut [f32], width: usize, offsets: &[(i64, i64)], forward: bool) {
+    let (w, h) = (width as i64, (distances.len() / width) as i64);
+    let sign = if forward { 1 } else { -1 };
+    let coords = (0..h).flat_map(|y| (0..w).map(move |x| (x, y)));
+    let visit = |(x, y): (i64, i64)| {
+        for &(dx, dy) in offsets {
+            let (nx, ny) = (x - sign * dx, y - sign * dy);
+            if nx >= 0 && nx < w && ny >= 0 && ny < h {
+                let neighbor = distances[(ny * w + nx) as usize] + 1.0;
+                let distance = &mut distances[(y * w + x) as usize];
+                *distance = distance.min(neighbor);
+            }
+        }
+    };
+    if forward {
+        coords.for_each(visit);
+    } else {
+        coords.rev().for_each(visit);
+    }
+}
+
+/// Computes the squared euclidean distance transform of the given line of squared distances in
+/// place, using the lower envelope of the parabolas rooted at every finite value.
+fn squared_distances(values: &mut [f32]) {
+    // The roots of the parabolas in the envelope, and the position from which each one is lowest
+    let mut roots: Vec<usize> = Vec::with_capacity(values.len());
+    let mut starts: Vec<f64> = Vec::with_capacity(values.len());
+    let parabola = |q: usize, value: f32| f64::from(value) + (q * q) as f64;
+
+    for (q, &value) in values.iter().enumerate().filter(|(_, v)| v.is_finite()) {
+        let mut start = f64::NEG_INFINITY;
+        while let Some(&root) = roots.last() {
+            start = (parabola(q, value) - parabola(root, values[root])) / (2 * (q - root)) as f64;
+            if start > *starts.last().unwrap() {
+                break;
+            }
+            roots.pop();
+            starts.pop();
+            start = f64::NEG_INFINITY;
+        }
+        roots.push(q);
+        starts.push(start);
+    }
+    if roots.is_empty() {
+        return;
+    }
+
+    let envelope = roots
+        .iter()
+        .map(|&root| (root, values[root]))
+        .collect::<Vec<_>>();
+    let mut k = 0;
+    for (q, value) in values.iter_mut().enumerate() {
+        while k + 1 < starts.len() && starts[k + 1] < q as f64 {
+            k += 1;
+        }
+        let (root, offset) = envelope[k];
+        let distance = q as f32 - root as f32;
+        *value = distance.mul_add(distance, offset);
+    }
+}
+
+impl Image<BitPixel> {
+    /// Computes the distance of every pixel in this image to the nearest pixel that is off, i.e.
+    /// part of the background, using the given metric. Background pixels have a distance of
+    /// `0.0`, and if the image has no background at all, every distance is infinite.
+    ///
+    /// Distances are exact for every metric and take linear time in the number of pixels. They
+    /// are returned as raw values rather than normalized to `0.0..=1.0`, for example to threshold
+    /// them into strokes or to map them into feathered masks and glows.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// use ril::morph::DistanceMetric;
+    ///
+    /// let mut image = Image::new(5, 1, BitPixel::on());
+    /// *image.pixel_mut(0, 0) = BitPixel::off();
+    ///
+    /// let distances = image.distance_transform(DistanceMetric::Euclidean);
+    /// assert_eq!(distances.pixel(4, 0).0, 4.0);
+    /// ```
+    #[must_use]
+    pub fn distance_transform(&self, metric: DistanceMetric) -> Image<L32F> {
+        let width = self.width() as usize;
+        let mut distances = self
+            .data
+            .iter()
+            .map(|&BitPixel(on)| if on { f32::INFINITY } else { 0.0 })
+            .collect::<Vec<_>>();
+
+        match metric {
+            DistanceMetric::Manhattan | DistanceMetric::Chebyshev => {
+                // Two raster scans over the neighbors that are one step away are exact for both
+                let offsets: &[(i64, i64)] = match metric {
+                    DistanceMetric::Manhattan => &[(1, 0), (0, 1)],
+                    _ => &[(1, 0), (-1, 1), (0, 1), (1, 1)],
+                };
+                chamfer_pass(&mut distances, width, offsets, true);
+                chamfer_pass(&mut distances, width, offsets, false);
+            }
+            DistanceMetric::Euclidean => {
+                // The squared distance is separable into a pass over columns and one over rows
+                let height = distances.len() / width;
+                let mut columns = transpose(&distances, width);
+                parallel::for_each_row_mut(&mut columns, height, |_, column| {
+                    squared_distances(column);
+                });
+                distances = transpose(&columns, height);
+                parallel::for_each_row_mut(&mut distances, width, |_, row| {
+                    squared_distances(row);
+                    for distance in row {
+                        *distance = distance.sqrt();
+                    }
+                });
+            }
+        }
+
+        let pixels = distances.into_iter().map(L32F).collect::<Vec<_>>();
+        Image::from_pixels(self.width(), pixels)
+    }
+
+    /// Thins the foreground of this image down to a skeleton that is one pixel wide, using the
+    /// Zhang-Suen algorithm. The skeleton keeps the connectivity and the topology of the shapes,
+    /// e.g. the strokes of a glyph or signature become their center lines and holes are kept.
+    /// Pixels outside of the image are considered to be off.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// // A thick horizontal bar thins down to a line
+    /// let mut image = Image::new(9, 5, BitPixel::off());
+    /// for y in 1..4 {
+    ///     for x in 1..8 {
+    ///         *image.pixel_mut(x, y) = BitPixel::on();
+    ///     }
+    /// }
+    ///
+    /// let skeleton = image.skeletonize();
+    /// assert!((0..9).all(|x| !skeleton.pixel(x, 1).value() && !skeleton.pixel(x, 3).value()));
+    /// ```
+    #[must_use]
+    pub fn skeletonize(&self) -> Self {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let mut on = self.data.iter().map(|&BitPixel(on)| on).collect::<Vec<_>>();
+        let mut remaining = (0..on.len()).filter(|&i| on[i]).collect::<Vec<_>>();
+        let mut removed = Vec::new();
+
+        // The neighbors of a pixel, clockwise from the one above it
+        let neighbors = |on: &[bool], i: usize| {
+            let (x, y) = (i % width, i / width);
+            let get = |dx: isize, dy: isize| {
+                let (x, y) = (x as isize + dx, y as isize + dy);
+                x >= 0
+                    && y >= 0
+                    && (x as usize) < width
+                    && (y as usize) < height
+                    && on[y as usize * width + x as usize]
+            };
+            [
+                get(0, -1),
+                get(1, -1),
+                get(1, 0),
+                get(1, 1),
+                get(0, 1),
+                get(-1, 1),
+                get(-1, 0),
+                get(-1, -1),
+            ]
         };
 
-        for (y, i) in (y1..y2).zip(0..) {
-            for (x, j) in (x1..x2).zip(0..) {
-                *image.pixel_mut(x, y) = d(j, i);
+        loop {
+            let mut changed = false;
+            for second in [false, true] {
+                removed.clear();
+                for &i in &remaining {
+                    let [n, ne, e, se, s, sw, w, nw] = neighbors(&on, i);
+                    let ring = [n, ne, e, se, s, sw, w, nw, n];
+                    let count = ring[..8].iter().filter(|&&on| on).count();
+                    let transitions = ring.windows(2).filter(|pair| !pair[0] && pair[1]).count();
+                    // The first subiteration removes south-east boundary pixels and north-west
+                    // corners, the second one north-west boundary pixels and south-east corners
+                    let boundary = if second {
+                        !(n && w && (e || s))
+                    } else {
+                        !(e && s && (n || w))
+                    };
+
+                    if (2..=6).contains(&count) && transitions == 1 && boundary {
+                        removed.push(i);
+                    }
+                }
+
+                for &i in &removed {
+                    on[i] = false;
+                }
+                changed |= !removed.is_empty();
+                remaining.retain(|&i| on[i]);
+            }
+
+            if !changed {
+                break;
             }
         }
+
+        let pixels = on.into_iter().map(BitPixel).collect::<Vec<_>>();
+        Image::from_pixels(self.width(), pixels)
     }
 }
 
+/// Where a [`Stroke`] is placed relative to the edge of the reference image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StrokePlacement {
+    /// The stroke grows outward from the edge and is drawn underneath the reference image by
+    /// default. This is the default placement.
+    Outside,
+    /// The stroke grows inward from the edge and is drawn over the reference image by default.
+    Inside,
+    /// The stroke straddles the edge, with half of its size on either side, and is drawn over the
+    /// reference image by default.
+    Center,
+}
+
 /// Creates stroke around an [`Image<Rgba>`].
+///
+/// The stroke can be filled with anything that implements [`IntoFill`], such as a solid color,
+/// a gradient or a [`Pattern`][crate::Pattern]. Fills span the bounding box of the reference image.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// use ril::morph::{Stroke, StrokePlacement};
+///
+/// # fn main() -> ril::Result<()> {
+/// let logo = Image::<Rgba>::open("logo.png")?;
+/// let mut image = Image::new(logo.width(), logo.height(), Rgba::transparent());
+/// image.paste(0, 0, &logo);
+/// image.draw(&Stroke::new(&logo, 4, Rgba::black()).with_placement(StrokePlacement::Inside));
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Clone)]
-pub struct Stroke {
+pub struct Stroke<F: IntoFill<Pixel = Rgba>> {
     /// The alpha channel of a reference image.
     pub alpha: Image<L>,
+    /// The position of the top-left corner of the reference image. This may be negative, in which
+    /// case the stroke is clipped.
+    pub position: (i64, i64),
     /// Stroke size in pixels
     pub size: u32,
-    /// Stroke fill color
-    pub color: Rgba,
+    /// Stroke fill, e.g. a color or gradient
+    pub fill: F::Fill,
     /// The alpha threshold above which the pixel is considered filled
     pub threshold: u8,
+    /// Where the stroke is placed relative to the edge of the reference image. Defaults to
+    /// [`StrokePlacement::Outside`].
+    pub placement: StrokePlacement,
+    /// The overlay mode of the stroke, or None to draw outside strokes underneath the existing
+    /// pixels of the image and other strokes over them with the overlay mode of the image
+    pub overlay: Option<OverlayMode>,
 }
 
-impl Stroke {
+impl<F: IntoFill<Pixel = Rgba>> Stroke<F> {
     /// Creates a new image stroke, with the position default to `(0, 0)` and threshold default to `0`
     #[must_use]
-    pub fn new(image: &Image<Rgba>, size: u32, color: Rgba) -> Self {
+    pub fn new(image: &Image<Rgba>, size: u32, fill: F) -> Self {
         Self {
             alpha: image.band(3),
+            position: (0, 0),
             size: size.max(1),
-            color,
+            fill: fill.into_fill(),
             threshold: 0,
+            placement: StrokePlacement::Outside,
+            overlay: None,
         }
     }
 
+    /// Sets the position of the top-left corner of the reference image.
+    #[must_use]
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = (x, y);
+        self
+    }
+
     /// Sets the alpha threshold value above which pixels are considered filled.
     #[must_use]
     pub const fn with_threshold(mut self, threshold: u8) -> Self {
         self.threshold = threshold;
         self
     }
+
+    /// Sets where the stroke is placed relative to the edge of the reference image.
+    #[must_use]
+    pub const fn with_placement(mut self, placement: StrokePlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Sets the overlay mode of the stroke. By default, outside strokes are drawn underneath the
+    /// existing pixels of the image.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
 }
 
-impl Draw<Rgba> for Stroke {
-    fn draw<I: DerefMut<Target = Image<Rgba>>>(&self, mut image: I) {
+/// Returns the kernel that grows a shape by a stroke of the given size.
+fn stroke_kernel(size: u32) -> KernelImage {
+    let k_size = size.max(1) * 2 - 1;
+    // Antialiased ellipses need at least three pixels, smaller strokes only cover the edge pixels
+    if k_size < 3 {
+        KernelImage::from_shape(KernelShape::Rect, 1, 1)
+    } else {
+        KernelImage::from_shape(KernelShape::EllipseAa, k_size, k_size)
+    }
+}
+
+impl<F: IntoFill<Pixel = Rgba>> Draw<Rgba> for Stroke<F> {
+    fn draw<I: DerefMut<Target = Image<Rgba>>>(&self, image: I) {
+        self.draw_with_scratch(image, &mut Scratch::new());
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn draw_with_scratch<I: DerefMut<Target = Image<Rgba>>>(
+        &self,
+        mut image: I,
+        scratch: &mut Scratch<Rgba>,
+    ) {
         let (w, h) = self.alpha.dimensions();
-        let src_alpha = self
-            .alpha
-            .clone()
-            .map_pixels(|p| L(if p.0 > self.threshold { 255 } else { 0 }));
+        // Filled pixels are marked in the alpha channel of an RGBA buffer, so that every temporary
+        // buffer can be borrowed from the same scratch
+        let mut src_alpha = scratch.take_image(w, h, Rgba::transparent());
+        for (pixel, &L(alpha)) in src_alpha.data.iter_mut().zip(&self.alpha.data) {
+            pixel.a = if alpha > self.threshold { 255 } else { 0 };
+        }
 
-        let k_size = self.size * 2 - 1;
-        let kernel = KernelImage::from_shape(KernelShape::EllipseAa, k_size, k_size);
+        let kernel = match self.placement {
+            StrokePlacement::Center => stroke_kernel(self.size - self.size / 2),
+            _ => stroke_kernel(self.size),
+        };
 
-        let mut stroke_alpha = Image::new(w, h, L(0));
+        // The outer part of the stroke is the dilated shape, and the inner part is the dilated
+        // background. Each part is limited to the uncovered and covered portion of every pixel
+        // respectively, and the outer part is left out of the interior of the shape entirely, so
+        // that it does not show through semi-transparent pixels.
+        let mut outer = scratch.take(src_alpha.data.len(), Rgba::transparent());
+        let mut inner = scratch.take(src_alpha.data.len(), Rgba::transparent());
+        if self.placement != StrokePlacement::Inside {
+            Dilation::new(&src_alpha, &kernel).dilate(&mut outer);
+        }
+        if self.placement != StrokePlacement::Outside {
+            for pixel in &mut src_alpha.data {
+                pixel.a = u8::MAX - pixel.a;
+            }
+            Dilation::new(&src_alpha, &kernel).dilate(&mut inner);
+        }
 
-        stroke_alpha.draw(&Dilation::new(&src_alpha, &kernel));
+        let (x1, y1) = self.position;
+        let clamp = |value: i64| value.clamp(0, i64::from(u32::MAX)) as u32;
+        let fill = self.fill.clone().with_bounding_box((
+            clamp(x1),
+            clamp(y1),
+            clamp(x1 + i64::from(w)),
+            clamp(y1 + i64::from(h)),
+        ));
+        let overlay = match self.placement {
+            StrokePlacement::Outside => self.overlay,
+            _ => Some(self.overlay.unwrap_or(image.overlay)),
+        };
 
-        let Rgba { r, g, b, a } = self.color;
-        let stroke_rgba = stroke_alpha
-            .map_pixels(|L(k)| Rgba::new(r, g, b, (a as f32 * k as f32 / u8::MAX as f32) as u8));
+        let (w, h) = (i64::from(w), i64::from(h));
+        let filled = |x: i64, y: i64| {
+            (0..w).contains(&x)
+                && (0..h).contains(&y)
+                && self.alpha.data[(y * w + x) as usize].0 > self.threshold
+        };
+        let interior = |x: i64, y: i64| {
+            [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+                .iter()
+                .all(|&(dx, dy)| filled(x + dx, y + dy))
+        };
 
-        for y in 0..h {
-            for x in 0..w {
-                if let Some(pixel) = stroke_rgba.get_pixel(x, y) {
-                    image.underlay_pixel(x as u32, y as u32, *pixel);
-                }
+        let coords = (0..h).flat_map(|y| (0..w).map(move |x| (x, y)));
+        for (((x, y), &L(covered)), (outer, inner)) in
+            coords.zip(&self.alpha.data).zip(outer.iter().zip(&inner))
+        {
+            let outer = if interior(x, y) { 0.0 } else { outer.a as f32 };
+            let coverage = (outer * (u8::MAX - covered) as f32 + inner.a as f32 * covered as f32)
+                / u8::MAX as f32;
+            let (x, y) = (x1 + x, y1 + y);
+            if x < 0 || y < 0 || coverage == 0.0 {
+                continue;
+            }
+
+            let mut pixel = fill.get_pixel(x as u32, y as u32);
+            pixel.a = (pixel.a as f32 * coverage / u8::MAX as f32) as u8;
+            match overlay {
+                Some(mode) => image.overlay_pixel_with_mode(x as u32, y as u32, pixel, mode),
+                None => image.underlay_pixel(x as u32, y as u32, pixel),
+            }
+        }
+
+        scratch.recycle_image(src_alpha);
+        scratch.recycle(outer);
+        scratch.recycle(inner);
+    }
+}
+
+/// Draws a soft drop shadow beneath an [`Image<Rgba>`], and then the image itself on top of it,
+/// in a single draw.
+///
+/// The shadow is the alpha channel of the image, grown by the spread, blurred by the blur radius
+/// and filled with the shadow color. It is drawn at the position of the image moved by the offset.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// use ril::morph::DropShadow;
+///
+/// # fn main() -> ril::Result<()> {
+/// let logo = Image::<Rgba>::open("logo.png")?;
+/// let mut image = Image::new(512, 512, Rgba::white());
+/// image.draw(
+///     &DropShadow::new(&logo, (6, 6), 8, Rgba::new(0, 0, 0, 128))
+///         .with_position(64, 64)
+///         .with_spread(2),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DropShadow<'img> {
+    /// The image casting the shadow
+    pub image: &'img Image<Rgba>,
+    /// The position of the top-left corner of the image. This may be negative, in which case the
+    /// image and its shadow are clipped.
+    pub position: (i64, i64),
+    /// The offset of the shadow from the image in pixels
+    pub offset: (i64, i64),
+    /// How far the shadow is blurred in every direction in pixels, or `0` for a hard shadow
+    pub blur: u32,
+    /// How far the shape of the shadow is grown in every direction in pixels before blurring
+    pub spread: u32,
+    /// The color of the shadow. Its alpha is scaled by the alpha of the image.
+    pub color: Rgba,
+    /// The overlay mode of the shadow and the image, or None to use the overlay mode of the image
+    /// that is drawn onto
+    pub overlay: Option<OverlayMode>,
+}
+
+impl<'img> DropShadow<'img> {
+    /// Creates a new drop shadow of the given image with the given offset, blur radius and color.
+    /// The position defaults to `(0, 0)` and the spread defaults to `0`.
+    #[must_use]
+    pub const fn new(image: &'img Image<Rgba>, offset: (i64, i64), blur: u32, color: Rgba) -> Self {
+        Self {
+            image,
+            position: (0, 0),
+            offset,
+            blur,
+            spread: 0,
+            color,
+            overlay: None,
+        }
+    }
+
+    /// Sets the position of the top-left corner of the image.
+    #[must_use]
+    pub const fn with_position(mut self, x: i64, y: i64) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Sets how far the shape of the shadow is grown in every direction before blurring.
+    #[must_use]
+    pub const fn with_spread(mut self, spread: u32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Sets the overlay mode of the shadow and the image.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+
+    /// Returns the shadow as a mask, which extends past the image by the spread and the blur
+    /// radius on every side.
+    fn mask(&self) -> Image<L> {
+        let (w, h) = self.image.dimensions();
+        let margin = self.spread + self.blur;
+        let mut mask = Image::new(w + margin * 2, h + margin * 2, L(0));
+        mask.paste(i64::from(margin), i64::from(margin), &self.image.band(3));
+
+        if self.spread > 0 {
+            // Unlike strokes, the spread grows the shadow by whole pixels, since the blur softens
+            // its edges afterwards
+            let size = self.spread * 2 + 1;
+            let kernel = KernelImage::from_shape(KernelShape::Ellipse, size, size);
+            let mut spread = vec![L(0); mask.data.len()];
+            Dilation::new(&mask, &kernel).dilate(&mut spread);
+            mask.data = spread;
+        }
+        // The gaussian kernel reaches three standard deviations in every direction
+        mask.draw(&GaussianBlur::new(f64::from(self.blur) / 3.0));
+        mask
+    }
+}
+
+impl<'img> Draw<Rgba> for DropShadow<'img> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn draw<I: DerefMut<Target = Image<Rgba>>>(&self, mut image: I) {
+        let overlay = self.overlay.unwrap_or(image.overlay);
+        let margin = i64::from(self.spread + self.blur);
+        let (x1, y1) = (
+            self.position.0 + self.offset.0 - margin,
+            self.position.1 + self.offset.1 - margin,
+        );
+
+        for (x, y, &L(coverage)) in self.mask().pixels_with_coords() {
+            let (x, y) = (x1 + i64::from(x), y1 + i64::from(y));
+            if x < 0 || y < 0 || coverage == 0 {
+                continue;
             }
+
+            let a = (u32::from(self.color.a) * u32::from(coverage) / 255) as u8;
+            image.overlay_pixel_with_mode(x as u32, y as u32, Rgba { a, ..self.color }, overlay);
         }
+
+        image.draw(
+            &Paste::new(self.image)
+                .with_position(self.position.0, self.position.1)
+                .with_overlay_mode(overlay),
+        );
     }
 }
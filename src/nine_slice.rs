@@ -0,0 +1,172 @@
+//! Scales border images such as UI frames while preserving their corners.
+
+use crate::{Image, Pixel};
+use alloc::vec::Vec;
+
+/// A border image divided into nine slices by four insets, created with [`Image::nine_slice`].
+///
+/// When scaled with [`Self::scale_to`], the four corners are copied as-is, the top and bottom
+/// edges are only stretched horizontally, the left and right edges are only stretched vertically,
+/// and the center is stretched in both directions. This is the standard way to scale UI frames,
+/// speech bubbles and panels without distorting their borders.
+///
+/// Stretching uses nearest-neighbor sampling, which keeps pixel art and hard edges crisp.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// // A 12x12 frame with a 4 pixel black border around a white center
+/// let frame = Image::from_fn(12, 12, |x, y| {
+///     if (4..8).contains(&x) && (4..8).contains(&y) {
+///         Rgb::white()
+///     } else {
+///         Rgb::black()
+///     }
+/// });
+///
+/// let panel = frame.nine_slice((4, 4, 4, 4)).scale_to(64, 32);
+/// assert_eq!(panel.dimensions(), (64, 32));
+/// assert_eq!(panel.pixel(3, 3), &Rgb::black());
+/// assert_eq!(panel.pixel(32, 16), &Rgb::white());
+/// ```
+#[derive(Clone)]
+pub struct NineSlice<'a, P: Pixel> {
+    /// A reference to the border image.
+    pub image: &'a Image<P>,
+    /// The insets of the slices from each edge of the image, in the order top, right, bottom,
+    /// left.
+    pub insets: (u32, u32, u32, u32),
+}
+
+/// Maps every coordinate along one axis of the scaled image to a coordinate in the source image,
+/// keeping `start` and `end` pixels at either end unscaled.
+fn map_axis(target: u32, size: u32, start: u32, end: u32) -> Vec<u32> {
+    // If the target is too small to fit both insets, shrink them proportionally, rounding to the
+    // nearest pixel so that neither side disappears before the other
+    let insets = u64::from(start) + u64::from(end);
+    let (start, end) = if insets > u64::from(target) {
+        let shrunk = ((u64::from(start) * u64::from(target) + insets / 2) / insets) as u32;
+        (shrunk, target - shrunk)
+    } else {
+        (start, end)
+    };
+    let (center, target_center) = (
+        u64::from(size - start - end),
+        u64::from(target - start - end),
+    );
+
+    (0..target)
+        .map(|i| {
+            if i < start {
+                i
+            } else if i >= target - end {
+                size - (target - i)
+            } else {
+                start + (u64::from(i - start) * center / target_center) as u32
+            }
+        })
+        .collect()
+}
+
+impl<'a, P: Pixel> NineSlice<'a, P> {
+    /// Creates a new nine-slice from the given image and insets, in the order top, right,
+    /// bottom, left.
+    ///
+    /// # Panics
+    /// * The horizontal insets are at least as wide as the image.
+    /// * The vertical insets are at least as tall as the image.
+    #[must_use]
+    pub fn new(image: &'a Image<P>, insets: (u32, u32, u32, u32)) -> Self {
+        let (top, right, bottom, left) = insets;
+        assert!(
+            u64::from(left) + u64::from(right) < u64::from(image.width())
+                && u64::from(top) + u64::from(bottom) < u64::from(image.height()),
+            "insets {:?} must leave a non-empty center in an image with dimensions {:?}",
+            insets,
+            image.dimensions(),
+        );
+
+        Self { image, insets }
+    }
+
+    /// Scales the image to the given dimensions, preserving the corners and only stretching the
+    /// edges and center.
+    ///
+    /// If the given dimensions are smaller than the insets, the corners are shrunk
+    /// proportionally.
+    ///
+    /// # Panics
+    /// * The width or height is zero.
+    #[must_use]
+    pub fn scale_to(&self, width: u32, height: u32) -> Image<P> {
+        let (top, right, bottom, left) = self.insets;
+        let columns = map_axis(width, self.image.width(), left, right);
+        let rows = map_axis(height, self.image.height(), top, bottom);
+
+        let data = rows
+            .iter()
+            .flat_map(|&y| columns.iter().map(move |&x| *self.image.pixel(x, y)))
+            .collect::<Vec<_>>();
+
+        Image::from_pixels(width, data).with_overlay_mode(self.image.overlay_mode())
+    }
+}
+
+impl<P: Pixel> Image<P> {
+    /// Divides this image into nine slices with the given insets, in the order top, right, bottom,
+    /// left. Call [`NineSlice::scale_to`] on the result to scale the image while preserving its
+    /// corners.
+    ///
+    /// # Panics
+    /// * The horizontal insets are at least as wide as the image.
+    /// * The vertical insets are at least as tall as the image.
+    ///
+    /// # See Also
+    /// * [`NineSlice`] for more information.
+    #[must_use]
+    pub fn nine_slice(&self, insets: (u32, u32, u32, u32)) -> NineSlice<'_, P> {
+        NineSlice::new(self, insets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::L;
+
+    #[test]
+    fn test_scale_preserves_corners() {
+        let image = Image::from_fn(6, 5, |x, y| L((y * 6 + x) as u8));
+        let scaled = image.nine_slice((1, 2, 1, 1)).scale_to(10, 7);
+
+        assert_eq!(scaled.dimensions(), (10, 7));
+        // Corners
+        assert_eq!(scaled.pixel(0, 0).value(), 0);
+        assert_eq!(scaled.pixel(8, 0).value(), 4);
+        assert_eq!(scaled.pixel(9, 6).value(), 29);
+        assert_eq!(scaled.pixel(0, 6).value(), 24);
+        // Edges are only stretched along one axis
+        assert!((1..8).all(|x| (1..=3).contains(&scaled.pixel(x, 0).value())));
+        assert!((1..6).all(|y| scaled.pixel(9, y).value() % 6 == 5));
+
+        // Shrinking below the insets shrinks the corners, keeping a part of each of them
+        let shrunk = image.nine_slice((1, 2, 1, 1)).scale_to(2, 2);
+        assert_eq!(
+            shrunk.data.iter().map(|p| p.value()).collect::<Vec<_>>(),
+            [0, 5, 24, 29]
+        );
+        let shrunk = image.nine_slice((1, 2, 1, 1)).scale_to(3, 1);
+        assert_eq!(
+            shrunk.data.iter().map(|p| p.value()).collect::<Vec<_>>(),
+            [0, 4, 5]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must leave a non-empty center")]
+    fn test_overflowing_insets() {
+        // The sum of the insets overflows, which must not wrap around into a valid width
+        let image = Image::new(6, 5, L(0));
+        let _ = image.nine_slice((0, u32::MAX, 0, 2));
+    }
+}
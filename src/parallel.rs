@@ -0,0 +1,79 @@
+//! Helpers for running work in parallel when the `rayon` feature is enabled, falling back to
+//! running it sequentially otherwise.
+
+use alloc::vec::Vec;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Maps every item of the given vector, in parallel if the `rayon` feature is enabled.
+#[cfg(feature = "resize")]
+pub(crate) fn map_vec<T, U>(items: Vec<T>, f: impl Fn(T) -> U + Send + Sync) -> Vec<U>
+where
+    T: Send,
+    U: Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        items.into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        items.into_iter().map(f).collect()
+    }
+}
+
+/// Computes every row of an area with the given height and concatenates them, in parallel if the
+/// `rayon` feature is enabled.
+pub(crate) fn map_rows<T, I>(height: u32, f: impl Fn(u32) -> I + Send + Sync) -> Vec<T>
+where
+    T: Send,
+    I: IntoIterator<Item = T>,
+{
+    #[cfg(feature = "rayon")]
+    {
+        (0..height)
+            .into_par_iter()
+            .map(|y| f(y).into_iter().collect::<Vec<_>>())
+            .flatten_iter()
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        (0..height).flat_map(f).collect()
+    }
+}
+
+/// Calls the given function on every item of the given slice, in parallel if the `rayon` feature
+/// is enabled.
+pub(crate) fn for_each_mut<T: Send>(items: &mut [T], f: impl Fn(&mut T) + Send + Sync) {
+    #[cfg(feature = "rayon")]
+    {
+        items.par_iter_mut().for_each(f);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        items.iter_mut().for_each(f);
+    }
+}
+
+/// Calls the given function on every row of the given buffer of rows with the given width, along
+/// with the index of the row, in parallel if the `rayon` feature is enabled.
+pub(crate) fn for_each_row_mut<T: Send>(
+    items: &mut [T],
+    width: usize,
+    f: impl Fn(u32, &mut [T]) + Send + Sync,
+) {
+    #[cfg(feature = "rayon")]
+    {
+        items
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| f(y as u32, row));
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        for (y, row) in items.chunks_mut(width).enumerate() {
+            f(y as u32, row);
+        }
+    }
+}
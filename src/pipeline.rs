@@ -0,0 +1,241 @@
+//! Pipelines of point operations which are applied in a single pass.
+
+use crate::{
+    image::{brightness_lut, contrast_lut, gamma_lut, posterize_lut, solarize_lut},
+    parallel, Image, Lut, OverlayMode, TrueColor,
+};
+use alloc::{boxed::Box, vec::Vec};
+
+#[cfg(feature = "std")]
+use crate::ImageSequence;
+
+/// A single step of a [`Pipeline`].
+enum Step<P> {
+    Lut(Box<Lut>),
+    Map(Box<dyn Fn(P) -> P + Send + Sync>),
+}
+
+/// A chain of point operations, which are operations that map every pixel independently of its
+/// position and of other pixels, such as brightness, contrast or inversion.
+///
+/// Chaining methods such as [`Image::brightness_adjusted`] walks over the whole image once per
+/// operation. A pipeline instead records the operations and applies all of them to each pixel in
+/// a single pass, and consecutive lookup table based operations are composed into a single lookup
+/// table when they are recorded. Pipelines are applied to images with [`Image::apply_pipeline`],
+/// or to single pixels with [`Pipeline::apply`].
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let pipeline = Pipeline::new()
+///     .adjust_brightness(20)
+///     .adjust_contrast(1.2)
+///     .apply_lut(&Lut::from_curve(&[(0, 0), (128, 150), (255, 255)]))
+///     .invert()
+///     .overlay(Rgba::new(255, 0, 0, 64), OverlayMode::Merge);
+///
+/// let image = Image::<Rgba>::open("sample.png")?.pipeline_applied(&pipeline);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Pipeline<P: TrueColor> {
+    steps: Vec<Step<P>>,
+}
+
+impl<P: TrueColor> Default for Pipeline<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: TrueColor> Pipeline<P> {
+    /// Creates a new, empty pipeline which leaves every pixel unchanged.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Returns the amount of passes over each pixel this pipeline makes when applied, after
+    /// consecutive lookup tables have been composed.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns whether this pipeline has no operations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    fn push_lut(mut self, lut: Lut) -> Self {
+        if let Some(Step::Lut(last)) = self.steps.last_mut() {
+            **last = last.then(&lut);
+        } else {
+            self.steps.push(Step::Lut(Box::new(lut)));
+        }
+        self
+    }
+
+    /// Adds the given delta to every color channel, clamping the result.
+    ///
+    /// See [`Image::adjust_brightness`] for more information.
+    #[must_use]
+    pub fn adjust_brightness(self, delta: i16) -> Self {
+        self.push_lut(Lut::from_table(brightness_lut(delta)))
+    }
+
+    /// Scales the distance of every color channel from the midpoint by the given factor.
+    ///
+    /// See [`Image::adjust_contrast`] for more information.
+    #[must_use]
+    pub fn adjust_contrast(self, factor: f64) -> Self {
+        self.push_lut(Lut::from_table(contrast_lut(factor)))
+    }
+
+    /// Applies gamma correction to every color channel.
+    ///
+    /// See [`Image::adjust_gamma`] for more information.
+    ///
+    /// # Panics
+    /// * `gamma` is not positive.
+    #[must_use]
+    pub fn adjust_gamma(self, gamma: f64) -> Self {
+        self.push_lut(Lut::from_table(gamma_lut(gamma)))
+    }
+
+    /// Inverts every color channel with a value of at least the given threshold.
+    ///
+    /// See [`Image::solarize`] for more information.
+    #[must_use]
+    pub fn solarize(self, threshold: u8) -> Self {
+        self.push_lut(Lut::from_table(solarize_lut(threshold)))
+    }
+
+    /// Reduces every color channel to the given amount of evenly spaced levels.
+    ///
+    /// See [`Image::posterize`] for more information.
+    ///
+    /// # Panics
+    /// * `levels` is less than 2.
+    #[must_use]
+    pub fn posterize(self, levels: u8) -> Self {
+        self.push_lut(Lut::from_table(posterize_lut(levels)))
+    }
+
+    /// Maps every pixel through the given lookup table.
+    #[must_use]
+    pub fn apply_lut(self, lut: &Lut) -> Self {
+        self.push_lut(lut.clone())
+    }
+
+    /// Inverts every pixel, the same way [`Image::invert`] does.
+    #[must_use]
+    pub fn invert(self) -> Self {
+        self.map(|pixel| !pixel)
+    }
+
+    /// Overlays the given pixel on top of every pixel using the given overlay mode.
+    #[must_use]
+    pub fn overlay(self, pixel: P, mode: OverlayMode) -> Self
+    where
+        P: 'static,
+    {
+        self.map(move |base| base.overlay(pixel, mode))
+    }
+
+    /// Maps every pixel with the given function.
+    #[must_use]
+    pub fn map(mut self, f: impl Fn(P) -> P + Send + Sync + 'static) -> Self {
+        self.steps.push(Step::Map(Box::new(f)));
+        self
+    }
+
+    /// Applies every operation of this pipeline to the given pixel, in order.
+    #[must_use]
+    pub fn apply(&self, pixel: P) -> P {
+        self.steps.iter().fold(pixel, |pixel, step| match step {
+            Step::Lut(lut) => lut.apply(pixel),
+            Step::Map(f) => f(pixel),
+        })
+    }
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Applies the given pipeline to every pixel of this image in place, in a single pass. If the
+    /// `rayon` feature is enabled, pixels are processed in parallel.
+    ///
+    /// See [`Pipeline`] for more information.
+    pub fn apply_pipeline(&mut self, pipeline: &Pipeline<P>) {
+        if pipeline.is_empty() {
+            return;
+        }
+
+        parallel::for_each_mut(&mut self.data, |pixel| *pixel = pipeline.apply(*pixel));
+    }
+
+    /// Takes this image and applies the given pipeline to it. Useful for method chaining.
+    ///
+    /// See [`Self::apply_pipeline`] for more information.
+    #[must_use]
+    pub fn pipeline_applied(mut self, pipeline: &Pipeline<P>) -> Self {
+        self.apply_pipeline(pipeline);
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: TrueColor> ImageSequence<P> {
+    /// Applies the given pipeline to every frame of this image sequence in place.
+    ///
+    /// See [`Image::apply_pipeline`] for more information.
+    pub fn apply_pipeline(&mut self, pipeline: &Pipeline<P>) {
+        self.map_frames_in_place(|image| image.apply_pipeline(pipeline));
+    }
+
+    /// Takes this image sequence and applies the given pipeline to every frame. Useful for method
+    /// chaining.
+    ///
+    /// See [`Image::apply_pipeline`] for more information.
+    #[must_use]
+    pub fn pipeline_applied(mut self, pipeline: &Pipeline<P>) -> Self {
+        self.apply_pipeline(pipeline);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pixel, Rgba};
+
+    #[test]
+    fn test_fused_pipeline() {
+        let image = Image::from_fn(16, 16, |x, y| {
+            Rgba::new(x as u8 * 16, y as u8 * 16, 77, 200)
+        });
+
+        let pipeline = Pipeline::new()
+            .adjust_brightness(-20)
+            .adjust_contrast(1.5)
+            .posterize(6)
+            .invert()
+            .adjust_gamma(0.8)
+            .overlay(Rgba::new(0, 0, 255, 100), OverlayMode::Merge);
+        assert_eq!(pipeline.len(), 4);
+
+        let expected = image
+            .clone()
+            .brightness_adjusted(-20)
+            .contrast_adjusted(1.5)
+            .posterized(6);
+        let mut expected = !expected;
+        expected.adjust_gamma(0.8);
+        expected.map_in_place(|_, _, pixel| {
+            *pixel = pixel.overlay(Rgba::new(0, 0, 255, 100), OverlayMode::Merge);
+        });
+
+        assert_eq!(image.pipeline_applied(&pipeline).data, expected.data);
+    }
+}
@@ -1,44 +1,139 @@
 //! Encloses pixel-related traits and pixel type implementations.
 
 use crate::{
+    color::{linear_to_srgb_u8, srgb_u8_to_linear},
     encodings::ColorType,
-    image::OverlayMode,
+    image::{BlendMode, OverlayMode},
     Error::{DecodingError, InvalidHexCode, InvalidPaletteIndex, UnsupportedColorType},
     Result,
 };
-use std::{
+use alloc::{
     borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
     fmt::{self, Debug, Formatter},
     hash::Hash,
     ops::{Mul, Not},
+    str::FromStr,
 };
-
-mod sealed {
-    use super::{BitPixel, Dynamic, NoOp, PalettedRgb, PalettedRgba, Rgb, Rgba, L};
-    pub trait Sealed {}
-
-    macro_rules! sealed {
-        ($($t:ty)+) => {
-            $(impl Sealed for $t {})+
-        };
-    }
-
-    pub trait MaybeSealed {
-        const SEALED: bool = false;
-    }
-
-    impl<P: Sealed> MaybeSealed for P {
-        const SEALED: bool = true;
-    }
-
-    sealed!(NoOp BitPixel L Rgb Rgba Dynamic PalettedRgb<'_> PalettedRgba<'_>);
-}
-
-pub use sealed::MaybeSealed;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 /// Represents any type of pixel in an image.
 ///
 /// Generally speaking, the values enclosed inside of each pixel are designed to be immutable.
+///
+/// # Custom pixel types
+/// This trait can be implemented by pixel types defined outside of this crate, for example to
+/// store YCbCr colors or channels of scientific data. Images of custom pixels can be created,
+/// drawn on and resized like any other image.
+///
+/// Custom pixel types should use [`ColorType::Custom`] with the number of channels they hold as
+/// their color type, and [`Self::as_bytes`] should return one [`Self::BIT_DEPTH`]-bit value per
+/// channel. Encoders cannot store custom pixels, so images of them are converted into RGBA using
+/// [`Self::as_rgba`] when they are encoded.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// # use std::ops::{Mul, Not};
+/// #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+/// struct YCbCr {
+///     y: u8,
+///     cb: u8,
+///     cr: u8,
+/// }
+///
+/// impl Pixel for YCbCr {
+///     const COLOR_TYPE: ColorType = ColorType::Custom(3);
+///     const BIT_DEPTH: u8 = 8;
+///
+///     type Subpixel = u8;
+///     type Color = Self;
+///     type Data = [u8; 3];
+///
+///     fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+///     where
+///         F: Fn(u8) -> u8,
+///         A: Fn(u8) -> u8,
+///     {
+///         Self { y: f(self.y), cb: f(self.cb), cr: f(self.cr) }
+///     }
+///
+///     fn channel(&self, index: usize) -> u8 {
+///         [self.y, self.cb, self.cr][index]
+///     }
+///
+///     fn from_bytes(bytes: &[u8]) -> Self {
+///         Self { y: bytes[0], cb: bytes[1], cr: bytes[2] }
+///     }
+///
+///     fn as_bytes(&self) -> [u8; 3] {
+///         [self.y, self.cb, self.cr]
+///     }
+///
+///     fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+///         let mix = |a: u8, b: u8| {
+///             ((a as u32 * (255 - alpha as u32) + b as u32 * alpha as u32) / 255) as u8
+///         };
+///         Self { y: mix(self.y, other.y), cb: mix(self.cb, other.cb), cr: mix(self.cr, other.cr) }
+///     }
+///
+///     fn max(self, other: Self) -> Self {
+///         Self { y: self.y.max(other.y), cb: self.cb.max(other.cb), cr: self.cr.max(other.cr) }
+///     }
+///
+///     fn min(self, other: Self) -> Self {
+///         Self { y: self.y.min(other.y), cb: self.cb.min(other.cb), cr: self.cr.min(other.cr) }
+///     }
+///
+///     fn as_rgb(&self) -> Rgb {
+///         let (y, cb, cr) = (self.y as f32, self.cb as f32 - 128., self.cr as f32 - 128.);
+///         Rgb::new(
+///             (y + 1.402 * cr).round().clamp(0., 255.) as u8,
+///             (y - 0.344_136 * cb - 0.714_136 * cr).round().clamp(0., 255.) as u8,
+///             (y + 1.772 * cb).round().clamp(0., 255.) as u8,
+///         )
+///     }
+///
+///     fn as_rgba(&self) -> Rgba {
+///         Rgba::from(self.as_rgb())
+///     }
+/// }
+///
+/// impl Not for YCbCr {
+///     type Output = Self;
+///
+///     fn not(self) -> Self {
+///         Self { y: !self.y, ..self }
+///     }
+/// }
+///
+/// impl Mul for YCbCr {
+///     type Output = Self;
+///
+///     fn mul(self, other: Self) -> Self {
+///         Self { y: (self.y as u16 * other.y as u16 / 255) as u8, ..self }
+///     }
+/// }
+///
+/// impl Mul<f32> for YCbCr {
+///     type Output = Self;
+///
+///     fn mul(self, factor: f32) -> Self {
+///         Self { y: (self.y as f32 * factor).clamp(0., 255.) as u8, ..self }
+///     }
+/// }
+///
+/// let white = YCbCr { y: 255, cb: 128, cr: 128 };
+/// let mut image = Image::new(4, 4, YCbCr::default());
+/// image.draw(&Rectangle::at(1, 1).with_size(2, 2).with_fill(white));
+///
+/// assert_eq!(image.pixel(1, 1).as_rgb(), Rgb::white());
+/// ```
 pub trait Pixel:
     Copy
     + Clone
@@ -48,9 +143,10 @@ pub trait Pixel:
     + Eq
     + Hash
     + Not<Output = Self>
-    + MaybeSealed
     + Mul<Self, Output = Self>
     + Mul<f32, Output = Self>
+    + Send
+    + Sync
 {
     /// The color type of the pixel.
     const COLOR_TYPE: ColorType;
@@ -58,8 +154,23 @@ pub trait Pixel:
     /// The bit depth of the pixel.
     const BIT_DEPTH: u8;
 
+    /// The number of channels of the pixel, including alpha. This is the number of indices that
+    /// can be passed to [`Self::channel`].
+    ///
+    /// This defaults to the number of channels of [`Self::COLOR_TYPE`]. Since the number of
+    /// channels of [`Dynamic`] pixels is only known at runtime, it is `0` for them.
+    const NUM_CHANNELS: usize = Self::COLOR_TYPE.channels();
+
+    /// The index of the channel which holds a hue angle in degrees, if there is one. Hue wraps
+    /// around at 360 degrees, so it is resampled as an angle rather than as a number when resizing
+    /// floating-point pixels. Otherwise, red hues on either side of 0 degrees would be averaged
+    /// into cyan.
+    ///
+    /// This defaults to `None`.
+    const HUE_CHANNEL: Option<usize> = None;
+
     /// The type of a single component in the pixel.
-    type Subpixel: Copy + Into<usize>;
+    type Subpixel: Copy;
 
     /// The resolved color type of the palette. This is `Self` for non-paletted pixels.
     type Color: Pixel;
@@ -95,6 +206,40 @@ pub trait Pixel:
         F: Fn(Self::Subpixel) -> Self::Subpixel,
         A: Fn(Self::Subpixel) -> Self::Subpixel;
 
+    /// Returns the channel of the pixel at the given index. Channels are in the same order as they
+    /// are in [`Self::as_bytes`], so alpha, if any, is always the last channel.
+    ///
+    /// Together with [`Self::NUM_CHANNELS`] and [`Self::map_channels`], this allows filters to be
+    /// written generically over all pixel types.
+    ///
+    /// # Panics
+    /// * The index is not less than [`Self::NUM_CHANNELS`].
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// fn channel_sum<P: Pixel<Subpixel = u8>>(pixel: P) -> u32 {
+    ///     (0..P::NUM_CHANNELS).map(|i| u32::from(pixel.channel(i))).sum()
+    /// }
+    ///
+    /// assert_eq!(channel_sum(Rgb::new(255, 128, 0)), 383);
+    /// assert_eq!(channel_sum(L(64)), 64);
+    /// ```
+    fn channel(&self, index: usize) -> Self::Subpixel;
+
+    /// Maps every channel of the pixel, including alpha, with the given function and returns a new
+    /// pixel with the mapped channels.
+    ///
+    /// # See Also
+    /// * [`Self::map_subpixels`] for mapping alpha separately.
+    #[must_use]
+    fn map_channels<F>(self, f: F) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        self.map_subpixels(&f, &f)
+    }
+
     /// Creates this pixel from the given color type, bit depth, and data. This may require a lossy
     /// conversion.
     ///
@@ -139,11 +284,9 @@ pub trait Pixel:
     fn from_arbitrary_palette<P: Pixel>(palette: &[P], index: usize) -> Result<Self> {
         let pixel = palette.get(index).ok_or(InvalidPaletteIndex)?;
 
-        if P::SEALED && P::COLOR_TYPE == ColorType::Dynamic {
-            // SAFETY: upheld by the `Sealed` trait
-            Ok(Self::from_dynamic(unsafe { *(pixel as *const P).cast() }))
-        } else {
-            Self::from_raw_parts(P::COLOR_TYPE, P::BIT_DEPTH, pixel.as_bytes().as_ref())
+        match pixel.as_dynamic() {
+            Some(dynamic) => Ok(Self::from_dynamic(dynamic)),
+            None => Self::from_raw_parts(P::COLOR_TYPE, P::BIT_DEPTH, pixel.as_bytes().as_ref()),
         }
     }
 
@@ -160,15 +303,42 @@ pub trait Pixel:
         other
     }
 
+    /// Merges this pixel with the given overlay pixel like [`Self::merge`], but blends colors in
+    /// linear light instead of in gamma-encoded sRGB. Pixel types that do not store sRGB colors
+    /// with alpha merge the same way as [`Self::merge`].
+    #[must_use]
+    fn merge_linear(self, other: Self) -> Self {
+        self.merge(other)
+    }
+
+    /// Merges this pixel with the given overlay pixel like [`Self::merge_with_alpha`], but blends
+    /// colors in linear light like [`Self::merge_linear`]. This is used in anti-aliasing with
+    /// [`OverlayMode::MergeLinear`].
+    #[must_use]
+    fn merge_linear_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge_with_alpha(other, alpha)
+    }
+
     /// Overlays this pixel with the given overlay pixel, abiding by the given overlay mode.
     #[must_use]
     fn overlay(self, other: Self, mode: OverlayMode) -> Self {
         match mode {
             OverlayMode::Replace => other,
             OverlayMode::Merge => self.merge(other),
+            OverlayMode::MergeLinear => self.merge_linear(other),
+            OverlayMode::Blend(mode) => self.blend(other, mode),
         }
     }
 
+    /// Blends this pixel with the given overlay pixel using the given blend mode, taking into
+    /// account alpha. Pixel types that do not support blend modes merge the same way as
+    /// [`Self::merge`].
+    #[allow(unused_variables)]
+    #[must_use]
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        self.merge(other)
+    }
+
     /// Merges this pixel with the given overlay pixel, where the alpha of the overlay pixel is
     /// known. This is used in anti-aliasing.
     #[must_use]
@@ -191,6 +361,31 @@ pub trait Pixel:
         match mode {
             OverlayMode::Replace => other,
             OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::MergeLinear => self.merge_linear_with_alpha(other, alpha),
+            OverlayMode::Blend(blend) => self.merge_with_alpha(self.blend(other, blend), alpha),
+        }
+    }
+
+    /// Overlays every pixel of `dest` with the pixel at the same index of `src`, abiding by the
+    /// given overlay mode. This is equivalent to calling [`Self::overlay`] on every pair of
+    /// pixels, but may be accelerated for some pixel types when blending whole rows at once.
+    ///
+    /// Only `dest.len().min(src.len())` pixels are overlaid.
+    fn overlay_row(dest: &mut [Self], src: &[Self], mode: OverlayMode) {
+        for (target, &pixel) in dest.iter_mut().zip(src) {
+            *target = target.overlay(pixel, mode);
+        }
+    }
+
+    /// Overlays every pixel of `dest` with the pixel at the same index of `src` and the alpha at
+    /// the same index of `alpha`, abiding by the given overlay mode. This is equivalent to
+    /// calling [`Self::overlay_with_alpha`] on every pair of pixels, but may be accelerated for
+    /// some pixel types when blending whole rows at once.
+    ///
+    /// Only as many pixels as the shortest of the three slices are overlaid.
+    fn overlay_row_with_alpha(dest: &mut [Self], src: &[Self], mode: OverlayMode, alpha: &[u8]) {
+        for ((target, &pixel), &alpha) in dest.iter_mut().zip(src).zip(alpha) {
+            *target = target.overlay_with_alpha(pixel, mode, alpha);
         }
     }
 
@@ -202,6 +397,12 @@ pub trait Pixel:
         panic!("cannot convert from dynamic pixel for this pixel type");
     }
 
+    /// Returns this pixel as a [`Dynamic`] pixel if it is one, otherwise `None`. This is used to
+    /// resolve palettes of dynamic pixels at runtime.
+    fn as_dynamic(&self) -> Option<Dynamic> {
+        None
+    }
+
     /// Returns this pixel as RGB despite its type. This can panic on some pixel types, you must
     /// be sure this pixel is able to be converted into RGB before using this.
     ///
@@ -237,6 +438,26 @@ pub trait Alpha: Pixel {
     /// Clones this pixel with the given alpha value.
     #[must_use]
     fn with_alpha(self, alpha: u8) -> Self;
+
+    /// Returns this pixel with its color channels multiplied by its alpha value, i.e. converts this
+    /// pixel from straight alpha into premultiplied alpha.
+    ///
+    /// Premultiplied pixels are suitable for filtering and interpolation, since fully or partially
+    /// transparent pixels no longer contribute their (invisible) color to their neighbors.
+    ///
+    /// # See Also
+    /// * [`Self::unpremultiplied`] for the inverse of this method.
+    #[must_use]
+    fn premultiplied(self) -> Self;
+
+    /// Returns this pixel with its color channels divided by its alpha value, i.e. converts this
+    /// pixel from premultiplied alpha back into straight alpha. Fully transparent pixels become
+    /// transparent black.
+    ///
+    /// # See Also
+    /// * [`Self::premultiplied`] for the inverse of this method.
+    #[must_use]
+    fn unpremultiplied(self) -> Self;
 }
 
 /// Represents a pixel that can be modulated, i.e. transformed in hue, saturation, and brightness.
@@ -265,6 +486,7 @@ impl From<NoOpSubpixel> for usize {
 impl Pixel for NoOp {
     const COLOR_TYPE: ColorType = ColorType::L;
     const BIT_DEPTH: u8 = 0;
+    const NUM_CHANNELS: usize = 0;
 
     type Subpixel = NoOpSubpixel;
     type Color = Self;
@@ -278,6 +500,10 @@ impl Pixel for NoOp {
         Self
     }
 
+    fn channel(&self, _index: usize) -> Self::Subpixel {
+        panic!("no-op pixels have no channels")
+    }
+
     fn from_bytes(_bytes: &[u8]) -> Self {
         Self
     }
@@ -341,6 +567,14 @@ impl Alpha for NoOp {
     fn with_alpha(self, _alpha: u8) -> Self {
         Self
     }
+
+    fn premultiplied(self) -> Self {
+        Self
+    }
+
+    fn unpremultiplied(self) -> Self {
+        Self
+    }
 }
 
 impl TrueColor for NoOp {
@@ -379,6 +613,7 @@ macro_rules! propagate_palette {
 
 /// Represents a single-bit pixel that represents either a pixel that is on or off.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitPixel(
     /// Whether the pixel is on.
     pub bool,
@@ -438,6 +673,10 @@ impl Pixel for BitPixel {
         Self(f(self.0))
     }
 
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.0][index]
+    }
+
     fn from_raw_parts_paletted<P: Pixel>(
         color_type: ColorType,
         bit_depth: u8,
@@ -536,30 +775,35 @@ macro_rules! scale_subpixels {
                     let scale = $src_depth / $target_depth;
                     $data.iter().map(|n| *n / scale).collect::<Vec<_>>()
                 })
-            } else if $src_depth < $target_depth {
-                let scale = $target_depth as usize / $src_depth as usize;
-                let mut result = Vec::with_capacity($data.len() * scale);
-
-                for n in $data {
-                    result.extend((*n as usize * scale).to_be_bytes());
+            } else if $target_depth == 16 {
+                // Components are rescaled so that the maximum value of the source depth maps to
+                // the maximum 16-bit value, and are stored big-endian
+                let max = (1_u32 << $src_depth) - 1;
+                let mut result = Vec::with_capacity($data.len() * 2);
+
+                for &n in $data.iter() {
+                    result.extend(((u32::from(n) * 65_535 / max) as u16).to_be_bytes());
                 }
 
                 Cow::from(result)
+            } else if $src_depth == 16 {
+                let max = (1_u32 << $target_depth) - 1;
+
+                Cow::from(
+                    $data
+                        .chunks_exact(2)
+                        .map(|c| {
+                            let n = u32::from(u16::from_be_bytes([c[0], c[1]]));
+                            // Rounded (n * max) / 65535
+                            ((n * max + 32_767) / 65_535) as u8
+                        })
+                        .collect::<Vec<_>>(),
+                )
             } else {
-                let scale = $src_depth as usize / $target_depth as usize;
-                let mut result = Vec::with_capacity($data.len() / scale);
-
-                for chunk in $data.chunks_exact(scale) {
-                    let sum = chunk
-                        .iter()
-                        .rev()
-                        .enumerate()
-                        .map(|(i, &x)| (x as usize) << (8 * i))
-                        .sum::<usize>();
-                    result.push((sum / scale) as u8);
-                }
-
-                Cow::from(result)
+                return Err(DecodingError(format!(
+                    "cannot scale components from a depth of {} to {}",
+                    $src_depth, $target_depth
+                )));
             }
         }
     }};
@@ -570,7 +814,7 @@ macro_rules! propagate_data {
         if $data.len() < $expected {
             return Err(DecodingError(format!(
                 "malformed pixel data for {}: expected at least {} component(s) but received {}",
-                std::any::type_name::<Self>(),
+                core::any::type_name::<Self>(),
                 $expected,
                 $data.len(),
             )));
@@ -587,6 +831,8 @@ macro_rules! propagate_data {
 /// This can be thought of as the "unit channel" as this represents only
 /// a single channel in which other pixel types can be composed of.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
 pub struct L(
     /// The luminance value of the pixel, between 0 and 255.
     pub u8,
@@ -608,6 +854,10 @@ impl Pixel for L {
         Self(f(self.0))
     }
 
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.0][index]
+    }
+
     fn from_raw_parts_paletted<P: Pixel>(
         color_type: ColorType,
         bit_depth: u8,
@@ -623,6 +873,10 @@ impl Pixel for L {
             // Currently, losing alpha implicitly is allowed, but I may change my mind about this
             // in the future.
             ColorType::L | ColorType::LA => Ok(Self(data[0])),
+            ColorType::Cmyk => {
+                propagate_data!(data, 4);
+                Ok(Cmyk::from_bytes(&data).into())
+            }
             _ => Err(UnsupportedColorType),
         }
     }
@@ -648,6 +902,14 @@ impl Pixel for L {
         Self((l * 255.) as u8)
     }
 
+    fn merge_linear_with_alpha(self, other: Self, alpha: u8) -> Self {
+        Self(lerp_linear(self.0, other.0, subpixel_to_f32(alpha)))
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        L32F::from(self).blend(other.into(), mode).into()
+    }
+
     fn from_dynamic(dynamic: Dynamic) -> Self {
         match dynamic {
             Dynamic::L(value) => value,
@@ -702,10 +964,24 @@ impl L {
     pub const fn value(&self) -> u8 {
         self.0
     }
+
+    /// Converts this pixel from gamma-encoded sRGB into linear light.
+    #[must_use]
+    pub fn to_linear(self) -> L32F {
+        L32F(srgb_u8_to_linear(self.0))
+    }
+
+    /// Converts the given pixel in linear light into gamma-encoded sRGB.
+    #[must_use]
+    pub fn from_linear(L32F(l): L32F) -> Self {
+        Self(linear_to_srgb_u8(l))
+    }
 }
 
 /// Represents an RGB pixel.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
 pub struct Rgb {
     /// The red component of the pixel.
     pub r: u8,
@@ -735,6 +1011,10 @@ impl Pixel for Rgb {
         }
     }
 
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.r, self.g, self.b][index]
+    }
+
     fn from_raw_parts_paletted<P: Pixel>(
         color_type: ColorType,
         bit_depth: u8,
@@ -761,6 +1041,10 @@ impl Pixel for Rgb {
                     b: data[0],
                 })
             }
+            ColorType::Cmyk => {
+                propagate_data!(data, 4);
+                Ok(Cmyk::from_bytes(&data).into())
+            }
             _ => Err(UnsupportedColorType),
         }
     }
@@ -783,6 +1067,43 @@ impl Pixel for Rgb {
             .into()
     }
 
+    fn merge_linear_with_alpha(self, other: Self, alpha: u8) -> Self {
+        let alpha = subpixel_to_f32(alpha);
+
+        Self {
+            r: lerp_linear(self.r, other.r, alpha),
+            g: lerp_linear(self.g, other.g, alpha),
+            b: lerp_linear(self.b, other.b, alpha),
+        }
+    }
+
+    fn overlay_row(dest: &mut [Self], src: &[Self], mode: OverlayMode) {
+        // Pixels without alpha are opaque, so merging them replaces the base pixel
+        if let OverlayMode::Replace | OverlayMode::Merge | OverlayMode::MergeLinear = mode {
+            let len = dest.len().min(src.len());
+            return dest[..len].copy_from_slice(&src[..len]);
+        }
+
+        for (target, &pixel) in dest.iter_mut().zip(src) {
+            *target = target.overlay(pixel, mode);
+        }
+    }
+
+    fn overlay_row_with_alpha(dest: &mut [Self], src: &[Self], mode: OverlayMode, alpha: &[u8]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if let OverlayMode::Merge = mode {
+            return crate::simd::merge_rgb_row(dest, src, alpha);
+        }
+
+        for ((target, &pixel), &alpha) in dest.iter_mut().zip(src).zip(alpha) {
+            *target = target.overlay_with_alpha(pixel, mode, alpha);
+        }
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        Rgb32F::from(self).blend(other.into(), mode).into()
+    }
+
     fn from_dynamic(dynamic: Dynamic) -> Self {
         match dynamic {
             Dynamic::Rgb(value) => value,
@@ -855,6 +1176,26 @@ impl Rgb {
         Self { r, g, b }
     }
 
+    /// Converts this pixel from gamma-encoded sRGB into linear light.
+    #[must_use]
+    pub fn to_linear(self) -> Rgb32F {
+        Rgb32F::new(
+            srgb_u8_to_linear(self.r),
+            srgb_u8_to_linear(self.g),
+            srgb_u8_to_linear(self.b),
+        )
+    }
+
+    /// Converts the given pixel in linear light into gamma-encoded sRGB.
+    #[must_use]
+    pub fn from_linear(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        Self::new(
+            linear_to_srgb_u8(r),
+            linear_to_srgb_u8(g),
+            linear_to_srgb_u8(b),
+        )
+    }
+
     /// Parses an RGB pixel from a hex value.
     ///
     /// The hex value can be in one of the following formats:
@@ -900,6 +1241,30 @@ impl Rgb {
         })
     }
 
+    /// Parses an RGB pixel from a CSS color string. The alpha component of the color, if any, is
+    /// discarded.
+    ///
+    /// # See Also
+    /// * [`Rgba::from_css`] for the accepted formats.
+    ///
+    /// # Errors
+    /// * Received a malformed or unknown color.
+    pub fn from_css(color: impl AsRef<str>) -> Result<Self> {
+        crate::css::parse_color(color.as_ref()).map(Self::from)
+    }
+
+    /// Formats this pixel as a lowercase hex code in the form `#rrggbb`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// assert_eq!(Rgb::new(255, 128, 0).to_hex(), "#ff8000");
+    /// ```
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
     /// Creates a completely black pixel.
     #[must_use]
     pub const fn black() -> Self {
@@ -915,6 +1280,8 @@ impl Rgb {
 
 /// Represents an RGBA pixel.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
 pub struct Rgba {
     /// The red component of the pixel.
     pub r: u8,
@@ -947,6 +1314,10 @@ impl Pixel for Rgba {
         }
     }
 
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.r, self.g, self.b, self.a][index]
+    }
+
     fn from_raw_parts_paletted<P: Pixel>(
         color_type: ColorType,
         bit_depth: u8,
@@ -993,6 +1364,10 @@ impl Pixel for Rgba {
                     a: data[1],
                 })
             }
+            ColorType::Cmyk => {
+                propagate_data!(data, 4);
+                Ok(Cmyk::from_bytes(&data).into())
+            }
             _ => Err(UnsupportedColorType),
         }
     }
@@ -1010,7 +1385,6 @@ impl Pixel for Rgba {
         [self.r, self.g, self.b, self.a]
     }
 
-    // TODO: SIMD could speed this up significantly
     #[allow(clippy::cast_lossless)]
     fn merge(self, other: Self) -> Self {
         // Optimize for common cases
@@ -1034,8 +1408,14 @@ impl Pixel for Rgba {
             other.a as f32 / 255.,
         );
 
+        // Source-over compositing is performed in premultiplied space, i.e. each color channel
+        // is weighted by its alpha before blending and divided by the resulting alpha afterwards.
+        // Blending straight-alpha colors directly causes dark fringes around translucent edges.
         let a_diff = 1. - overlay_a;
         let a = a_diff.mul_add(base_a, overlay_a);
+        if a <= 0. {
+            return Self::transparent();
+        }
 
         let a_ratio = a_diff * base_a;
         let r = a_ratio.mul_add(base_r, overlay_a * overlay_r) / a;
@@ -1055,10 +1435,67 @@ impl Pixel for Rgba {
         self.merge(other.with_alpha((other.a as f32 * (alpha as f32 / 255.)) as u8))
     }
 
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        if other.a == 0 {
+            return self;
+        }
+
+        Rgba32F::from(self).blend(other.into(), mode).into()
+    }
+
+    fn merge_linear(self, other: Self) -> Self {
+        // Optimize for common cases
+        if other.a == 255 {
+            return other;
+        } else if other.a == 0 {
+            return self;
+        }
+
+        Self::from_linear(self.to_linear().merge(other.to_linear()))
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn merge_linear_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge_linear(other.with_alpha((other.a as f32 * (alpha as f32 / 255.)) as u8))
+    }
+
+    #[allow(clippy::cast_lossless)]
     fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
         match mode {
             OverlayMode::Replace => other.with_alpha(alpha),
             OverlayMode::Merge => self.merge_with_alpha(other, alpha),
+            OverlayMode::MergeLinear => self.merge_linear_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend(
+                other.with_alpha((other.a as f32 * (alpha as f32 / 255.)) as u8),
+                mode,
+            ),
+        }
+    }
+
+    fn overlay_row(dest: &mut [Self], src: &[Self], mode: OverlayMode) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if mode == OverlayMode::Merge {
+            let len = dest.len().min(src.len());
+            return crate::simd::merge_rgba_row(&mut dest[..len], |i| src[i]);
+        }
+
+        for (target, &pixel) in dest.iter_mut().zip(src) {
+            *target = target.overlay(pixel, mode);
+        }
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn overlay_row_with_alpha(dest: &mut [Self], src: &[Self], mode: OverlayMode, alpha: &[u8]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if mode == OverlayMode::Merge {
+            let len = dest.len().min(src.len()).min(alpha.len());
+            return crate::simd::merge_rgba_row(&mut dest[..len], |i| {
+                src[i].with_alpha((src[i].a as f32 * (alpha[i] as f32 / 255.)) as u8)
+            });
+        }
+
+        for ((target, &pixel), &alpha) in dest.iter_mut().zip(src).zip(alpha) {
+            *target = target.overlay_with_alpha(pixel, mode, alpha);
         }
     }
 
@@ -1101,6 +1538,38 @@ impl Alpha for Rgba {
         self.a = alpha;
         self
     }
+
+    #[allow(clippy::cast_lossless)]
+    fn premultiplied(self) -> Self {
+        let a = self.a as u16;
+        // Rounded (c * a) / 255
+        let mul = |c: u8| ((c as u16 * a + 127) / 255) as u8;
+
+        Self {
+            r: mul(self.r),
+            g: mul(self.g),
+            b: mul(self.b),
+            a: self.a,
+        }
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn unpremultiplied(self) -> Self {
+        if self.a == 0 {
+            return Self::transparent();
+        }
+
+        let a = self.a as u16;
+        // Rounded (c * 255) / a, saturating in case the pixel was not actually premultiplied
+        let div = |c: u8| ((c as u16 * 255 + a / 2) / a).min(255) as u8;
+
+        Self {
+            r: div(self.r),
+            g: div(self.g),
+            b: div(self.b),
+            a: self.a,
+        }
+    }
 }
 
 impl Rgba {
@@ -1116,6 +1585,30 @@ impl Rgba {
         Self::new(r, g, b, 255)
     }
 
+    /// Converts the color channels of this pixel from gamma-encoded sRGB into linear light. Alpha
+    /// is left untouched.
+    #[must_use]
+    pub fn to_linear(self) -> Rgba32F {
+        Rgba32F::new(
+            srgb_u8_to_linear(self.r),
+            srgb_u8_to_linear(self.g),
+            srgb_u8_to_linear(self.b),
+            subpixel_to_f32(self.a),
+        )
+    }
+
+    /// Converts the color channels of the given pixel in linear light into gamma-encoded sRGB.
+    /// Alpha is left untouched.
+    #[must_use]
+    pub fn from_linear(Rgba32F { r, g, b, a }: Rgba32F) -> Self {
+        Self::new(
+            linear_to_srgb_u8(r),
+            linear_to_srgb_u8(g),
+            linear_to_srgb_u8(b),
+            subpixel_from_f32(a),
+        )
+    }
+
     /// Parses an RGBA pixel from a hex value.
     ///
     /// The hex value can be in one of the following formats:
@@ -1160,6 +1653,53 @@ impl Rgba {
         }
     }
 
+    /// Parses an RGBA pixel from a CSS color string, ignoring case and surrounding whitespace.
+    ///
+    /// The color can be in one of the following formats:
+    /// - A hex code, see [`Self::from_hex`]
+    /// - `rgb(r, g, b)` or `rgba(r, g, b, a)`, where each component is a number between 0 and 255
+    ///   or a percentage, and the alpha is a number between 0 and 1 or a percentage
+    /// - `hsl(h, s, l)` or `hsla(h, s, l, a)`, where the hue is in degrees and the saturation and
+    ///   lightness are percentages
+    /// - A CSS named color such as `rebeccapurple`, or `transparent`
+    ///
+    /// Functions also accept the space-separated syntax, such as `rgb(255 0 0 / 50%)`.
+    ///
+    /// # Errors
+    /// * Received a malformed or unknown color.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// assert_eq!(Rgba::from_css("orange")?, Rgba::new(255, 165, 0, 255));
+    /// assert_eq!(Rgba::from_css("rgba(255, 0, 0, 0.5)")?, Rgba::new(255, 0, 0, 128));
+    /// assert_eq!(Rgba::from_css("hsl(240, 100%, 50%)")?, Rgba::new(0, 0, 255, 255));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_css(color: impl AsRef<str>) -> Result<Self> {
+        crate::css::parse_color(color.as_ref())
+    }
+
+    /// Formats this pixel as a lowercase hex code in the form `#rrggbb`, or `#rrggbbaa` if the
+    /// pixel is not fully opaque.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// assert_eq!(Rgba::new(255, 128, 0, 255).to_hex(), "#ff8000");
+    /// assert_eq!(Rgba::new(255, 128, 0, 128).to_hex(), "#ff800080");
+    /// ```
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
     /// Creates a completely transparent pixel.
     #[must_use]
     pub const fn transparent() -> Self {
@@ -1218,63 +1758,1908 @@ impl Mul<Rgba> for Rgba {
     }
 }
 
-/// Represents a subpixel of a dynamic pixel.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum DynamicSubpixel {
-    /// A u8 subpixel.
-    U8(u8),
-    /// A boolean subpixel.
-    Bool(bool),
+/// Reads up to four native-endian `f32` components from the given bytes, returning them along
+/// with the number of components read.
+fn f32_components(data: &[u8]) -> ([f32; 4], usize) {
+    let mut components = [0.0; 4];
+    let mut len = 0;
+
+    for (component, chunk) in components.iter_mut().zip(data.chunks_exact(4)) {
+        *component = f32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        len += 1;
+    }
+
+    (components, len)
 }
 
-macro_rules! impl_num_op {
-    ($err:literal; $self:expr, $other:expr; $a:ident, $b:ident; $out:expr) => {{
-        match ($self, $other) {
-            (DynamicSubpixel::U8($a), DynamicSubpixel::U8($b)) => DynamicSubpixel::U8($out),
-            (DynamicSubpixel::Bool(_), DynamicSubpixel::Bool(_)) => panic!(
-                "cannot {} DynamicPixel boolean variants. You should try converting to a \
-                    concrete pixel type so these runtime panics are not triggered.",
-                $err,
-            ),
-            _ => panic!(
-                "cannot {} different or incompatible DynamicSubpixel variants. You should \
-                    try converting to a concrete pixel type so these runtime panics are not \
-                    triggered.",
-                $err,
-            ),
-        }
-    }};
+/// Converts an 8-bit subpixel into a float subpixel in the range `0.0..=1.0`.
+#[allow(clippy::cast_lossless)]
+fn subpixel_to_f32(value: u8) -> f32 {
+    value as f32 / 255.
 }
 
-impl std::ops::Add for DynamicSubpixel {
-    type Output = Self;
+/// Interpolates from the given base subpixel to the given overlay subpixel by the given alpha, in
+/// linear light instead of in sRGB.
+fn lerp_linear(base: u8, overlay: u8, alpha: f32) -> u8 {
+    let (base, overlay) = (srgb_u8_to_linear(base), srgb_u8_to_linear(overlay));
 
-    fn add(self, other: Self) -> Self {
-        impl_num_op!("add"; self, other; a, b; a + b)
-    }
+    linear_to_srgb_u8(alpha.mul_add(overlay - base, base))
 }
 
-impl std::ops::AddAssign for DynamicSubpixel {
-    fn add_assign(&mut self, other: Self) {
-        *self = *self + other;
-    }
+/// Converts a float subpixel into an 8-bit subpixel, clamping it into the range `0.0..=1.0`
+/// first. `NaN` is treated as `0.0`.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+fn subpixel_from_f32(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
 }
 
-impl std::ops::Sub for DynamicSubpixel {
-    type Output = Self;
+/// Represents an L, or luminance pixel that is stored as a single 32-bit float.
+///
+/// Float pixels are intended for intermediate or high dynamic range computations, where chaining
+/// 8-bit operations would otherwise accumulate rounding errors. Values are nominally in the range
+/// `0.0..=1.0`, but may fall outside of it; they are only clamped when converting back to an
+/// 8-bit pixel type. See [`Tonemap`] for mapping high dynamic range values into this range.
+///
+/// Float pixels are compared and hashed bitwise, so that they can satisfy [`Eq`] and [`Hash`].
+///
+/// Images of float pixels cannot be encoded directly, convert them into an 8-bit pixel type such
+/// as [`L`] using [`Image::convert`][crate::Image::convert] first.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
+pub struct L32F(
+    /// The luminance value of the pixel, nominally between 0.0 and 1.0.
+    pub f32,
+);
 
-    fn sub(self, other: Self) -> Self {
-        impl_num_op!("subtract"; self, other; a, b; a - b)
+impl PartialEq for L32F {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
     }
 }
 
-impl std::ops::SubAssign for DynamicSubpixel {
+impl Eq for L32F {}
+
+impl Hash for L32F {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl Pixel for L32F {
+    const COLOR_TYPE: ColorType = ColorType::L;
+    const BIT_DEPTH: u8 = 32;
+
+    type Subpixel = f32;
+    type Color = Self;
+    type Data = [u8; 4];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self(f(self.0))
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.0][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        if bit_depth != Self::BIT_DEPTH {
+            return L::from_raw_parts_paletted(color_type, bit_depth, data, palette).map(Self::from);
+        }
+
+        let (data, len) = f32_components(data);
+        match color_type {
+            ColorType::L | ColorType::LA => {
+                propagate_data!(data[..len], 1);
+                Ok(Self(data[0]))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(f32_components(bytes).0[0])
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        self.0.to_ne_bytes()
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        let alpha = subpixel_to_f32(alpha);
+
+        Self((other.0 - self.0).mul_add(alpha, self.0))
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        Self(mode.apply(self.0, other.0))
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        L::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self(self.0.max(other.0))
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self(self.0.min(other.0))
+    }
+
+    force_into_impl!();
+}
+
+impl Not for L32F {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(1. - self.0)
+    }
+}
+
+impl Mul<f32> for L32F {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Mul<L32F> for L32F {
+    type Output = Self;
+    fn mul(self, rhs: L32F) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl L32F {
+    /// Creates a new float L pixel with the given luminance value.
+    #[must_use]
+    pub const fn new(l: f32) -> Self {
+        Self(l)
+    }
+
+    /// Returns the luminance value of the pixel.
+    #[must_use]
+    pub const fn value(&self) -> f32 {
+        self.0
+    }
+}
+
+/// Represents an RGB pixel where each component is stored as a 32-bit float.
+///
+/// See [`L32F`] for more information on float pixels.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Rgb32F {
+    /// The red component of the pixel.
+    pub r: f32,
+    /// The green component of the pixel.
+    pub g: f32,
+    /// The blue component of the pixel.
+    pub b: f32,
+}
+
+impl PartialEq for Rgb32F {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Rgb32F {}
+
+impl Hash for Rgb32F {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Pixel for Rgb32F {
+    const COLOR_TYPE: ColorType = ColorType::Rgb;
+    const BIT_DEPTH: u8 = 32;
+
+    type Subpixel = f32;
+    type Color = Self;
+    type Data = [u8; 12];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+        }
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.r, self.g, self.b][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        if bit_depth != Self::BIT_DEPTH {
+            return Rgb::from_raw_parts_paletted(color_type, bit_depth, data, palette)
+                .map(Self::from);
+        }
+
+        let (data, len) = f32_components(data);
+        match color_type {
+            ColorType::Rgb | ColorType::Rgba => {
+                propagate_data!(data[..len], 3);
+                Ok(Self::new(data[0], data[1], data[2]))
+            }
+            ColorType::L | ColorType::LA => {
+                propagate_data!(data[..len], 1);
+                Ok(Self::new(data[0], data[0], data[0]))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (data, _) = f32_components(bytes);
+
+        Self::new(data[0], data[1], data[2])
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let mut bytes = [0; 12];
+        for (chunk, c) in bytes.chunks_exact_mut(4).zip([self.r, self.g, self.b]) {
+            chunk.copy_from_slice(&c.to_ne_bytes());
+        }
+
+        bytes
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        let alpha = subpixel_to_f32(alpha);
+
+        Self {
+            r: (other.r - self.r).mul_add(alpha, self.r),
+            g: (other.g - self.g).mul_add(alpha, self.g),
+            b: (other.b - self.b).mul_add(alpha, self.b),
+        }
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        Self {
+            r: mode.apply(self.r, other.r),
+            g: mode.apply(self.g, other.g),
+            b: mode.apply(self.b, other.b),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        Rgb::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            r: self.r.max(other.r),
+            g: self.g.max(other.g),
+            b: self.b.max(other.b),
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self {
+            r: self.r.min(other.r),
+            g: self.g.min(other.g),
+            b: self.b.min(other.b),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Not for Rgb32F {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: 1. - self.r,
+            g: 1. - self.g,
+            b: 1. - self.b,
+        }
+    }
+}
+
+impl Mul<f32> for Rgb32F {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self {
+            r: self.r * rhs,
+            g: self.g * rhs,
+            b: self.b * rhs,
+        }
+    }
+}
+
+impl Mul<Rgb32F> for Rgb32F {
+    type Output = Self;
+    fn mul(self, rhs: Rgb32F) -> Self::Output {
+        Self {
+            r: self.r * rhs.r,
+            g: self.g * rhs.g,
+            b: self.b * rhs.b,
+        }
+    }
+}
+
+impl Rgb32F {
+    /// Creates a new float RGB pixel.
+    #[must_use]
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a completely black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0., 0., 0.)
+    }
+
+    /// Creates a completely white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(1., 1., 1.)
+    }
+}
+
+/// Represents an RGBA pixel where each component is stored as a 32-bit float.
+///
+/// Unlike [`Rgba`], the alpha component is also a float in the range `0.0..=1.0`. See [`L32F`]
+/// for more information on float pixels.
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Rgba32F {
+    /// The red component of the pixel.
+    pub r: f32,
+    /// The green component of the pixel.
+    pub g: f32,
+    /// The blue component of the pixel.
+    pub b: f32,
+    /// The alpha component of the pixel.
+    pub a: f32,
+}
+
+impl PartialEq for Rgba32F {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Rgba32F {}
+
+impl Hash for Rgba32F {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Pixel for Rgba32F {
+    const COLOR_TYPE: ColorType = ColorType::Rgba;
+    const BIT_DEPTH: u8 = 32;
+
+    type Subpixel = f32;
+    type Color = Self;
+    type Data = [u8; 16];
+
+    fn map_subpixels<F, A>(self, f: F, a: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+            a: a(self.a),
+        }
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.r, self.g, self.b, self.a][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        if bit_depth != Self::BIT_DEPTH {
+            return Rgba::from_raw_parts_paletted(color_type, bit_depth, data, palette)
+                .map(Self::from);
+        }
+
+        let (data, len) = f32_components(data);
+        match color_type {
+            ColorType::Rgb => {
+                propagate_data!(data[..len], 3);
+                Ok(Self::new(data[0], data[1], data[2], 1.))
+            }
+            ColorType::Rgba => {
+                propagate_data!(data[..len], 4);
+                Ok(Self::new(data[0], data[1], data[2], data[3]))
+            }
+            ColorType::L => {
+                propagate_data!(data[..len], 1);
+                Ok(Self::new(data[0], data[0], data[0], 1.))
+            }
+            ColorType::LA => {
+                propagate_data!(data[..len], 2);
+                Ok(Self::new(data[0], data[0], data[0], data[1]))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (data, _) = f32_components(bytes);
+
+        Self::new(data[0], data[1], data[2], data[3])
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let mut bytes = [0; 16];
+        for (chunk, c) in bytes
+            .chunks_exact_mut(4)
+            .zip([self.r, self.g, self.b, self.a])
+        {
+            chunk.copy_from_slice(&c.to_ne_bytes());
+        }
+
+        bytes
+    }
+
+    fn merge(self, other: Self) -> Self {
+        if other.a >= 1. {
+            return other;
+        } else if other.a <= 0. {
+            return self;
+        }
+
+        // See `Rgba::merge`, which this is equivalent to without the 8-bit quantization.
+        let a_diff = 1. - other.a;
+        let a = a_diff.mul_add(self.a, other.a);
+        if a <= 0. {
+            return Self::transparent();
+        }
+
+        let a_ratio = a_diff * self.a;
+        Self {
+            r: a_ratio.mul_add(self.r, other.a * other.r) / a,
+            g: a_ratio.mul_add(self.g, other.a * other.g) / a,
+            b: a_ratio.mul_add(self.b, other.a * other.b) / a,
+            a,
+        }
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge(Self {
+            a: other.a * subpixel_to_f32(alpha),
+            ..other
+        })
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        if other.a <= 0. {
+            return self;
+        }
+
+        // The blended color is weighted by the alpha of both pixels, while the remaining parts
+        // of each pixel are composited as with `merge`. See the W3C Compositing and Blending spec.
+        let a = (1. - other.a).mul_add(self.a, other.a);
+        if a <= 0. {
+            return Self::transparent();
+        }
+
+        let (base, overlay, both) = (
+            self.a * (1. - other.a),
+            other.a * (1. - self.a),
+            self.a * other.a,
+        );
+        let mix = |b: f32, o: f32| both.mul_add(mode.apply(b, o), base.mul_add(b, overlay * o)) / a;
+
+        Self {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a,
+        }
+    }
+
+    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
+        match mode {
+            OverlayMode::Replace => other.with_alpha(alpha),
+            OverlayMode::Merge | OverlayMode::MergeLinear => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend(
+                Self {
+                    a: other.a * subpixel_to_f32(alpha),
+                    ..other
+                },
+                mode,
+            ),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        Rgba::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            r: self.r.max(other.r),
+            g: self.g.max(other.g),
+            b: self.b.max(other.b),
+            a: self.a.max(other.a),
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self {
+            r: self.r.min(other.r),
+            g: self.g.min(other.g),
+            b: self.b.min(other.b),
+            a: self.a.min(other.a),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Alpha for Rgba32F {
+    fn alpha(&self) -> u8 {
+        subpixel_from_f32(self.a)
+    }
+
+    fn with_alpha(mut self, alpha: u8) -> Self {
+        self.a = subpixel_to_f32(alpha);
+        self
+    }
+
+    fn premultiplied(self) -> Self {
+        Self {
+            r: self.r * self.a,
+            g: self.g * self.a,
+            b: self.b * self.a,
+            a: self.a,
+        }
+    }
+
+    fn unpremultiplied(self) -> Self {
+        if self.a <= 0. {
+            return Self::transparent();
+        }
+
+        Self {
+            r: self.r / self.a,
+            g: self.g / self.a,
+            b: self.b / self.a,
+            a: self.a,
+        }
+    }
+}
+
+impl Not for Rgba32F {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: 1. - self.r,
+            g: 1. - self.g,
+            b: 1. - self.b,
+            a: 1. - self.a,
+        }
+    }
+}
+
+impl Mul<f32> for Rgba32F {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self {
+            r: self.r * rhs,
+            g: self.g * rhs,
+            b: self.b * rhs,
+            a: self.a * rhs,
+        }
+    }
+}
+
+impl Mul<Rgba32F> for Rgba32F {
+    type Output = Self;
+    fn mul(self, rhs: Rgba32F) -> Self::Output {
+        Self {
+            r: self.r * rhs.r,
+            g: self.g * rhs.g,
+            b: self.b * rhs.b,
+            a: self.a * rhs.a,
+        }
+    }
+}
+
+impl Rgba32F {
+    /// Creates a new float RGBA pixel.
+    #[must_use]
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates an opaque pixel from a float RGB pixel.
+    #[must_use]
+    pub const fn from_rgb(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        Self::new(r, g, b, 1.)
+    }
+
+    /// Creates a completely transparent pixel.
+    #[must_use]
+    pub const fn transparent() -> Self {
+        Self::new(0., 0., 0., 0.)
+    }
+
+    /// Creates an opaque black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0., 0., 0., 1.)
+    }
+
+    /// Creates an opaque white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(1., 1., 1., 1.)
+    }
+}
+
+/// An operator used to map the color components of float pixels, which may be of high dynamic
+/// range, into the displayable range `0.0..=1.0`. Alpha is never tone mapped.
+///
+/// # See Also
+/// * [`Image::tonemap`][crate::Image::tonemap] for tone mapping a whole image.
+/// * [`Image::to_sdr`][crate::Image::to_sdr] for tone mapping a high dynamic range image into an
+///   8-bit image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tonemap {
+    /// Simply clamps each component into the range `0.0..=1.0`. Any detail in highlights brighter
+    /// than `1.0` is lost.
+    Clamp,
+    /// The Reinhard operator, `c / (1 + c)`. This compresses highlights smoothly, but also
+    /// darkens midtones.
+    Reinhard,
+    /// A fitted approximation of the ACES filmic curve, which gives more contrast than
+    /// [`Reinhard`][Self::Reinhard] while still rolling off highlights.
+    Aces,
+    /// Simulates the response of film exposed for the given exposure, `1 - exp(-exposure * c)`.
+    /// Higher exposures brighten the image, and `1.0` is a good starting point.
+    Exposure(f32),
+}
+
+impl Tonemap {
+    /// Applies this operator to a single component.
+    #[must_use]
+    pub fn apply(self, value: f32) -> f32 {
+        let value = value.max(0.);
+
+        match self {
+            Self::Clamp => value.min(1.),
+            Self::Reinhard => value / (1. + value),
+            // Krzysztof Narkowicz's fit of the ACES reference rendering transform
+            Self::Aces => {
+                let numerator = value * 2.51f32.mul_add(value, 0.03);
+                let denominator = 2.43f32.mul_add(value, 0.59).mul_add(value, 0.14);
+
+                (numerator / denominator).min(1.)
+            }
+            Self::Exposure(exposure) => 1. - (-exposure * value).exp(),
+        }
+    }
+}
+
+/// The weights of the red, green and blue channels used to compute the luminance of a color when
+/// converting it to grayscale.
+///
+/// # See Also
+/// * [`Image::grayscale`][crate::Image::grayscale] for converting a whole image to grayscale.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GrayscaleWeights {
+    /// The ITU-R BT.601 luma coefficients, `0.299 R + 0.587 G + 0.114 B`. These are the weights
+    /// used by the implicit conversion into [`L`], and are the common choice for SD video and
+    /// JPEG.
+    Rec601,
+    /// The ITU-R BT.709 luma coefficients, `0.2126 R + 0.7152 G + 0.0722 B`, which match the
+    /// primaries of sRGB and HD video.
+    Rec709,
+    /// The unweighted average of the red, green and blue channels.
+    Average,
+    /// Custom weights for the red, green and blue channels. These should usually add up to `1.0`.
+    Custom([f32; 3]),
+}
+
+impl GrayscaleWeights {
+    /// Returns the weights of the red, green and blue channels.
+    #[must_use]
+    pub const fn weights(self) -> [f32; 3] {
+        match self {
+            Self::Rec601 => [0.299, 0.587, 0.114],
+            Self::Rec709 => [0.2126, 0.7152, 0.0722],
+            Self::Average => [1. / 3.; 3],
+            Self::Custom(weights) => weights,
+        }
+    }
+
+    /// Computes the luminance of the given color using these weights, rounding to the nearest
+    /// value.
+    #[must_use]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn luminance<P: TrueColor>(self, pixel: P) -> L {
+        let (r, g, b) = pixel.as_rgb_tuple();
+        let [wr, wg, wb] = self.weights();
+        let value = f32::from(b).mul_add(wb, f32::from(r).mul_add(wr, f32::from(g) * wg));
+
+        L(value.round().clamp(0., 255.) as u8)
+    }
+}
+
+impl From<L> for L32F {
+    fn from(L(l): L) -> Self {
+        Self(subpixel_to_f32(l))
+    }
+}
+
+impl From<L32F> for L {
+    fn from(L32F(l): L32F) -> Self {
+        Self(subpixel_from_f32(l))
+    }
+}
+
+impl From<Rgb> for Rgb32F {
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        Self::new(subpixel_to_f32(r), subpixel_to_f32(g), subpixel_to_f32(b))
+    }
+}
+
+impl From<Rgb32F> for Rgb {
+    fn from(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        Self::new(subpixel_from_f32(r), subpixel_from_f32(g), subpixel_from_f32(b))
+    }
+}
+
+impl From<Rgba> for Rgba32F {
+    fn from(Rgba { r, g, b, a }: Rgba) -> Self {
+        Self::new(
+            subpixel_to_f32(r),
+            subpixel_to_f32(g),
+            subpixel_to_f32(b),
+            subpixel_to_f32(a),
+        )
+    }
+}
+
+impl From<Rgba32F> for Rgba {
+    fn from(Rgba32F { r, g, b, a }: Rgba32F) -> Self {
+        Self::new(
+            subpixel_from_f32(r),
+            subpixel_from_f32(g),
+            subpixel_from_f32(b),
+            subpixel_from_f32(a),
+        )
+    }
+}
+
+impl From<Rgb32F> for L32F {
+    fn from(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        Self(b.mul_add(0.114, r.mul_add(0.299, g * 0.587)))
+    }
+}
+
+impl From<Rgba32F> for L32F {
+    fn from(rgba: Rgba32F) -> Self {
+        Rgb32F::from(rgba).into()
+    }
+}
+
+impl From<L32F> for Rgb32F {
+    fn from(L32F(l): L32F) -> Self {
+        Self::new(l, l, l)
+    }
+}
+
+impl From<L32F> for Rgba32F {
+    fn from(L32F(l): L32F) -> Self {
+        Self::new(l, l, l, 1.)
+    }
+}
+
+impl From<Rgba32F> for Rgb32F {
+    fn from(Rgba32F { r, g, b, .. }: Rgba32F) -> Self {
+        Self::new(r, g, b)
+    }
+}
+
+impl From<Rgb32F> for Rgba32F {
+    fn from(rgb: Rgb32F) -> Self {
+        Self::from_rgb(rgb)
+    }
+}
+
+// Conversions between a float or 16-bit pixel and an 8-bit pixel of a different color type are
+// performed through the counterpart of the 8-bit pixel with the same precision.
+macro_rules! impl_cast_via {
+    ($t:ident: $($f:ident => $via:ident),+) => {
+        $(
+            impl From<$f> for $t {
+                fn from(pixel: $f) -> Self {
+                    $via::from(pixel).into()
+                }
+            }
+
+            impl From<$t> for $f {
+                fn from(pixel: $t) -> Self {
+                    $via::from(pixel).into()
+                }
+            }
+        )+
+    };
+}
+
+impl_cast_via!(L32F: Rgb => Rgb32F, Rgba => Rgba32F);
+impl_cast_via!(Rgb32F: L => L32F, Rgba => Rgba32F);
+impl_cast_via!(Rgba32F: L => L32F, Rgb => Rgb32F);
+
+/// Reads up to four big-endian `u16` components from the given bytes, returning them along with
+/// the number of components read.
+fn u16_components(data: &[u8]) -> ([u16; 4], usize) {
+    let mut components = [0; 4];
+    let mut len = 0;
+
+    for (component, chunk) in components.iter_mut().zip(data.chunks_exact(2)) {
+        *component = u16::from_be_bytes([chunk[0], chunk[1]]);
+        len += 1;
+    }
+
+    (components, len)
+}
+
+/// Converts an 8-bit subpixel into a 16-bit subpixel. This is lossless, i.e. converting the
+/// result back with [`subpixel_from_u16`] yields the original subpixel.
+const fn subpixel_to_u16(value: u8) -> u16 {
+    value as u16 * 257
+}
+
+/// Converts a 16-bit subpixel into an 8-bit subpixel, rounding to the nearest value.
+const fn subpixel_from_u16(value: u16) -> u8 {
+    ((value as u32 * 255 + 32_767) / 65_535) as u8
+}
+
+/// Converts a 16-bit subpixel into a float subpixel in the range `0.0..=1.0`.
+fn subpixel16_to_f32(value: u16) -> f32 {
+    f32::from(value) / 65_535.
+}
+
+/// Converts a float subpixel into a 16-bit subpixel, clamping it into the range `0.0..=1.0`
+/// first. `NaN` is treated as `0.0`.
+fn subpixel16_from_f32(value: f32) -> u16 {
+    (value.clamp(0., 1.) * 65_535.).round() as u16
+}
+
+/// Blends two 16-bit subpixels with the given 8-bit alpha, rounding to the nearest value.
+fn mix_u16(base: u16, overlay: u16, alpha: u8) -> u16 {
+    let alpha = u32::from(alpha);
+
+    ((u32::from(base) * (255 - alpha) + u32::from(overlay) * alpha + 127) / 255) as u16
+}
+
+/// Represents an L, or luminance pixel that is stored as a single 16-bit integer.
+///
+/// 16-bit pixels keep the full precision of high bit-depth images such as 16-bit PNGs, which are
+/// otherwise reduced to 8 bits per channel when decoded. Unlike 8-bit pixels, their bytes as
+/// returned by [`Pixel::as_bytes`] are big-endian, which is the order image formats store them
+/// in.
+///
+/// Only PNG can store 16-bit pixels. Images encoded into any other format are converted into
+/// [`Rgba`] first.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
+pub struct L16(
+    /// The luminance value of the pixel, between 0 and 65535.
+    pub u16,
+);
+
+impl Pixel for L16 {
+    const COLOR_TYPE: ColorType = ColorType::L;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = [u8; 2];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self(f(self.0))
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.0][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        if bit_depth != Self::BIT_DEPTH {
+            return L::from_raw_parts_paletted(color_type, bit_depth, data, palette)
+                .map(Self::from);
+        }
+
+        let (data, len) = u16_components(data);
+        match color_type {
+            ColorType::L | ColorType::LA => {
+                propagate_data!(data[..len], 1);
+                Ok(Self(data[0]))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(u16_components(bytes).0[0])
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        self.0.to_be_bytes()
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        Self(mix_u16(self.0, other.0, alpha))
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        L32F::from(self).blend(other.into(), mode).into()
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        L::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self(self.0.max(other.0))
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self(self.0.min(other.0))
+    }
+
+    force_into_impl!();
+}
+
+impl Not for L16 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(!self.0)
+    }
+}
+
+impl Mul<f32> for L16 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self((f32::from(self.0) * rhs).clamp(0.0, f32::from(u16::MAX)) as u16)
+    }
+}
+
+impl Mul<L16> for L16 {
+    type Output = Self;
+    fn mul(self, rhs: L16) -> Self::Output {
+        Self((u32::from(self.0) * u32::from(rhs.0) / 65_535) as u16)
+    }
+}
+
+impl L16 {
+    /// Creates a new 16-bit L pixel with the given luminance value.
+    #[must_use]
+    pub const fn new(l: u16) -> Self {
+        Self(l)
+    }
+
+    /// Returns the luminance value of the pixel.
+    #[must_use]
+    pub const fn value(&self) -> u16 {
+        self.0
+    }
+}
+
+/// Represents an RGB pixel where each component is stored as a 16-bit integer.
+///
+/// See [`L16`] for more information on 16-bit pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Rgb16 {
+    /// The red component of the pixel.
+    pub r: u16,
+    /// The green component of the pixel.
+    pub g: u16,
+    /// The blue component of the pixel.
+    pub b: u16,
+}
+
+impl Pixel for Rgb16 {
+    const COLOR_TYPE: ColorType = ColorType::Rgb;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = [u8; 6];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+        }
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.r, self.g, self.b][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        if bit_depth != Self::BIT_DEPTH {
+            return Rgb::from_raw_parts_paletted(color_type, bit_depth, data, palette)
+                .map(Self::from);
+        }
+
+        let (data, len) = u16_components(data);
+        match color_type {
+            ColorType::Rgb | ColorType::Rgba => {
+                propagate_data!(data[..len], 3);
+                Ok(Self::new(data[0], data[1], data[2]))
+            }
+            ColorType::L | ColorType::LA => {
+                propagate_data!(data[..len], 1);
+                Ok(Self::new(data[0], data[0], data[0]))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (data, _) = u16_components(bytes);
+
+        Self::new(data[0], data[1], data[2])
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let mut bytes = [0; 6];
+        for (chunk, c) in bytes.chunks_exact_mut(2).zip([self.r, self.g, self.b]) {
+            chunk.copy_from_slice(&c.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        Self {
+            r: mix_u16(self.r, other.r, alpha),
+            g: mix_u16(self.g, other.g, alpha),
+            b: mix_u16(self.b, other.b, alpha),
+        }
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        Rgb32F::from(self).blend(other.into(), mode).into()
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        Rgb::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            r: self.r.max(other.r),
+            g: self.g.max(other.g),
+            b: self.b.max(other.b),
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self {
+            r: self.r.min(other.r),
+            g: self.g.min(other.g),
+            b: self.b.min(other.b),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Not for Rgb16 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: !self.r,
+            g: !self.g,
+            b: !self.b,
+        }
+    }
+}
+
+impl Mul<f32> for Rgb16 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        let max = f32::from(u16::MAX);
+        Self {
+            r: (f32::from(self.r) * rhs).clamp(0.0, max) as u16,
+            g: (f32::from(self.g) * rhs).clamp(0.0, max) as u16,
+            b: (f32::from(self.b) * rhs).clamp(0.0, max) as u16,
+        }
+    }
+}
+
+impl Mul<Rgb16> for Rgb16 {
+    type Output = Self;
+    fn mul(self, rhs: Rgb16) -> Self::Output {
+        let mul = |a: u16, b: u16| (u32::from(a) * u32::from(b) / 65_535) as u16;
+        Self {
+            r: mul(self.r, rhs.r),
+            g: mul(self.g, rhs.g),
+            b: mul(self.b, rhs.b),
+        }
+    }
+}
+
+impl Rgb16 {
+    /// Creates a new 16-bit RGB pixel.
+    #[must_use]
+    pub const fn new(r: u16, g: u16, b: u16) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a completely black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    /// Creates a completely white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(u16::MAX, u16::MAX, u16::MAX)
+    }
+}
+
+/// Represents an RGBA pixel where each component, including alpha, is stored as a 16-bit
+/// integer.
+///
+/// See [`L16`] for more information on 16-bit pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Rgba16 {
+    /// The red component of the pixel.
+    pub r: u16,
+    /// The green component of the pixel.
+    pub g: u16,
+    /// The blue component of the pixel.
+    pub b: u16,
+    /// The alpha component of the pixel.
+    pub a: u16,
+}
+
+impl Pixel for Rgba16 {
+    const COLOR_TYPE: ColorType = ColorType::Rgba;
+    const BIT_DEPTH: u8 = 16;
+
+    type Subpixel = u16;
+    type Color = Self;
+    type Data = [u8; 8];
+
+    fn map_subpixels<F, A>(self, f: F, a: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            r: f(self.r),
+            g: f(self.g),
+            b: f(self.b),
+            a: a(self.a),
+        }
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.r, self.g, self.b, self.a][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        if bit_depth != Self::BIT_DEPTH {
+            return Rgba::from_raw_parts_paletted(color_type, bit_depth, data, palette)
+                .map(Self::from);
+        }
+
+        let (data, len) = u16_components(data);
+        match color_type {
+            ColorType::Rgb => {
+                propagate_data!(data[..len], 3);
+                Ok(Self::new(data[0], data[1], data[2], u16::MAX))
+            }
+            ColorType::Rgba => {
+                propagate_data!(data[..len], 4);
+                Ok(Self::new(data[0], data[1], data[2], data[3]))
+            }
+            ColorType::L => {
+                propagate_data!(data[..len], 1);
+                Ok(Self::new(data[0], data[0], data[0], u16::MAX))
+            }
+            ColorType::LA => {
+                propagate_data!(data[..len], 2);
+                Ok(Self::new(data[0], data[0], data[0], data[1]))
+            }
+            _ => Err(UnsupportedColorType),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let (data, _) = u16_components(bytes);
+
+        Self::new(data[0], data[1], data[2], data[3])
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        let mut bytes = [0; 8];
+        for (chunk, c) in bytes
+            .chunks_exact_mut(2)
+            .zip([self.r, self.g, self.b, self.a])
+        {
+            chunk.copy_from_slice(&c.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    fn merge(self, other: Self) -> Self {
+        if other.a == u16::MAX {
+            return other;
+        } else if other.a == 0 {
+            return self;
+        }
+
+        Rgba32F::from(self).merge(other.into()).into()
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        self.merge(Self {
+            a: ((u32::from(other.a) * u32::from(alpha) + 127) / 255) as u16,
+            ..other
+        })
+    }
+
+    fn blend(self, other: Self, mode: BlendMode) -> Self {
+        if other.a == 0 {
+            return self;
+        }
+
+        Rgba32F::from(self).blend(other.into(), mode).into()
+    }
+
+    fn overlay_with_alpha(self, other: Self, mode: OverlayMode, alpha: u8) -> Self {
+        match mode {
+            OverlayMode::Replace => other.with_alpha(alpha),
+            OverlayMode::Merge | OverlayMode::MergeLinear => self.merge_with_alpha(other, alpha),
+            OverlayMode::Blend(mode) => self.blend(
+                Self {
+                    a: ((u32::from(other.a) * u32::from(alpha) + 127) / 255) as u16,
+                    ..other
+                },
+                mode,
+            ),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        Rgba::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            r: self.r.max(other.r),
+            g: self.g.max(other.g),
+            b: self.b.max(other.b),
+            a: self.a.max(other.a),
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self {
+            r: self.r.min(other.r),
+            g: self.g.min(other.g),
+            b: self.b.min(other.b),
+            a: self.a.min(other.a),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Alpha for Rgba16 {
+    fn alpha(&self) -> u8 {
+        subpixel_from_u16(self.a)
+    }
+
+    fn with_alpha(mut self, alpha: u8) -> Self {
+        self.a = subpixel_to_u16(alpha);
+        self
+    }
+
+    fn premultiplied(self) -> Self {
+        let a = u32::from(self.a);
+        // Rounded (c * a) / 65535
+        let mul = |c: u16| ((u32::from(c) * a + 32_767) / 65_535) as u16;
+
+        Self {
+            r: mul(self.r),
+            g: mul(self.g),
+            b: mul(self.b),
+            a: self.a,
+        }
+    }
+
+    fn unpremultiplied(self) -> Self {
+        if self.a == 0 {
+            return Self::transparent();
+        }
+
+        let a = u32::from(self.a);
+        // Rounded (c * 65535) / a, saturating in case the pixel was not actually premultiplied
+        let div = |c: u16| ((u32::from(c) * 65_535 + a / 2) / a).min(65_535) as u16;
+
+        Self {
+            r: div(self.r),
+            g: div(self.g),
+            b: div(self.b),
+            a: self.a,
+        }
+    }
+}
+
+impl Not for Rgba16 {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            r: !self.r,
+            g: !self.g,
+            b: !self.b,
+            a: !self.a,
+        }
+    }
+}
+
+impl Mul<f32> for Rgba16 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        let max = f32::from(u16::MAX);
+        Self {
+            r: (f32::from(self.r) * rhs).clamp(0.0, max) as u16,
+            g: (f32::from(self.g) * rhs).clamp(0.0, max) as u16,
+            b: (f32::from(self.b) * rhs).clamp(0.0, max) as u16,
+            a: (f32::from(self.a) * rhs).clamp(0.0, max) as u16,
+        }
+    }
+}
+
+impl Mul<Rgba16> for Rgba16 {
+    type Output = Self;
+    fn mul(self, rhs: Rgba16) -> Self::Output {
+        let mul = |a: u16, b: u16| (u32::from(a) * u32::from(b) / 65_535) as u16;
+        Self {
+            r: mul(self.r, rhs.r),
+            g: mul(self.g, rhs.g),
+            b: mul(self.b, rhs.b),
+            a: mul(self.a, rhs.a),
+        }
+    }
+}
+
+impl Rgba16 {
+    /// Creates a new 16-bit RGBA pixel.
+    #[must_use]
+    pub const fn new(r: u16, g: u16, b: u16, a: u16) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates an opaque pixel from a 16-bit RGB pixel.
+    #[must_use]
+    pub const fn from_rgb(Rgb16 { r, g, b }: Rgb16) -> Self {
+        Self::new(r, g, b, u16::MAX)
+    }
+
+    /// Creates a completely transparent pixel.
+    #[must_use]
+    pub const fn transparent() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    /// Creates an opaque black pixel.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 0, 0, u16::MAX)
+    }
+
+    /// Creates an opaque white pixel.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(u16::MAX, u16::MAX, u16::MAX, u16::MAX)
+    }
+}
+
+impl From<L> for L16 {
+    fn from(L(l): L) -> Self {
+        Self(subpixel_to_u16(l))
+    }
+}
+
+impl From<L16> for L {
+    fn from(L16(l): L16) -> Self {
+        Self(subpixel_from_u16(l))
+    }
+}
+
+impl From<Rgb> for Rgb16 {
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        Self::new(subpixel_to_u16(r), subpixel_to_u16(g), subpixel_to_u16(b))
+    }
+}
+
+impl From<Rgb16> for Rgb {
+    fn from(Rgb16 { r, g, b }: Rgb16) -> Self {
+        Self::new(
+            subpixel_from_u16(r),
+            subpixel_from_u16(g),
+            subpixel_from_u16(b),
+        )
+    }
+}
+
+impl From<Rgba> for Rgba16 {
+    fn from(Rgba { r, g, b, a }: Rgba) -> Self {
+        Self::new(
+            subpixel_to_u16(r),
+            subpixel_to_u16(g),
+            subpixel_to_u16(b),
+            subpixel_to_u16(a),
+        )
+    }
+}
+
+impl From<Rgba16> for Rgba {
+    fn from(Rgba16 { r, g, b, a }: Rgba16) -> Self {
+        Self::new(
+            subpixel_from_u16(r),
+            subpixel_from_u16(g),
+            subpixel_from_u16(b),
+            subpixel_from_u16(a),
+        )
+    }
+}
+
+impl From<Rgb16> for L16 {
+    fn from(Rgb16 { r, g, b }: Rgb16) -> Self {
+        let l = f32::from(b).mul_add(0.114, f32::from(r).mul_add(0.299, f32::from(g) * 0.587));
+
+        Self(l.round() as u16)
+    }
+}
+
+impl From<Rgba16> for L16 {
+    fn from(rgba: Rgba16) -> Self {
+        Rgb16::from(rgba).into()
+    }
+}
+
+impl From<L16> for Rgb16 {
+    fn from(L16(l): L16) -> Self {
+        Self::new(l, l, l)
+    }
+}
+
+impl From<L16> for Rgba16 {
+    fn from(L16(l): L16) -> Self {
+        Self::new(l, l, l, u16::MAX)
+    }
+}
+
+impl From<Rgba16> for Rgb16 {
+    fn from(Rgba16 { r, g, b, .. }: Rgba16) -> Self {
+        Self::new(r, g, b)
+    }
+}
+
+impl From<Rgb16> for Rgba16 {
+    fn from(rgb: Rgb16) -> Self {
+        Self::from_rgb(rgb)
+    }
+}
+
+impl From<L16> for L32F {
+    fn from(L16(l): L16) -> Self {
+        Self(subpixel16_to_f32(l))
+    }
+}
+
+impl From<L32F> for L16 {
+    fn from(L32F(l): L32F) -> Self {
+        Self(subpixel16_from_f32(l))
+    }
+}
+
+impl From<Rgb16> for Rgb32F {
+    fn from(Rgb16 { r, g, b }: Rgb16) -> Self {
+        Self::new(
+            subpixel16_to_f32(r),
+            subpixel16_to_f32(g),
+            subpixel16_to_f32(b),
+        )
+    }
+}
+
+impl From<Rgb32F> for Rgb16 {
+    fn from(Rgb32F { r, g, b }: Rgb32F) -> Self {
+        Self::new(
+            subpixel16_from_f32(r),
+            subpixel16_from_f32(g),
+            subpixel16_from_f32(b),
+        )
+    }
+}
+
+impl From<Rgba16> for Rgba32F {
+    fn from(Rgba16 { r, g, b, a }: Rgba16) -> Self {
+        Self::new(
+            subpixel16_to_f32(r),
+            subpixel16_to_f32(g),
+            subpixel16_to_f32(b),
+            subpixel16_to_f32(a),
+        )
+    }
+}
+
+impl From<Rgba32F> for Rgba16 {
+    fn from(Rgba32F { r, g, b, a }: Rgba32F) -> Self {
+        Self::new(
+            subpixel16_from_f32(r),
+            subpixel16_from_f32(g),
+            subpixel16_from_f32(b),
+            subpixel16_from_f32(a),
+        )
+    }
+}
+
+impl_cast_via!(L16: Rgb => Rgb16, Rgba => Rgba16);
+impl_cast_via!(Rgb16: L => L16, Rgba => Rgba16);
+impl_cast_via!(Rgba16: L => L16, Rgb => Rgb16);
+
+/// Represents a CMYK pixel, holding cyan, magenta, yellow, and key (black) ink components. CMYK
+/// is typically used for print, and is most commonly found in JPEG images.
+///
+/// Conversions between CMYK and RGB pixels are naive, i.e. they do not take any color profile
+/// into account. Since CMYK colors depend heavily on the inks and paper used, this can result in
+/// noticeably different colors. For accurate results, convert a CMYK image using its ICC profile
+/// instead, using `Image::to_rgb_with_icc_profile` which requires the `icc` feature.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Cmyk {
+    /// The cyan component of the pixel.
+    pub c: u8,
+    /// The magenta component of the pixel.
+    pub m: u8,
+    /// The yellow component of the pixel.
+    pub y: u8,
+    /// The key, or black component of the pixel.
+    pub k: u8,
+}
+
+impl Pixel for Cmyk {
+    const COLOR_TYPE: ColorType = ColorType::Cmyk;
+    const BIT_DEPTH: u8 = 8;
+
+    type Subpixel = u8;
+    type Color = Self;
+    type Data = [u8; 4];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+        A: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        Self {
+            c: f(self.c),
+            m: f(self.m),
+            y: f(self.y),
+            k: f(self.k),
+        }
+    }
+
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        [self.c, self.m, self.y, self.k][index]
+    }
+
+    fn from_raw_parts_paletted<P: Pixel>(
+        color_type: ColorType,
+        bit_depth: u8,
+        data: &[u8],
+        palette: Option<&[P]>,
+    ) -> Result<Self> {
+        propagate_palette!(palette, data);
+        let data = scale_subpixels!(bit_depth, Self::BIT_DEPTH, data);
+
+        match color_type {
+            ColorType::Cmyk => {
+                propagate_data!(data, 4);
+                Ok(Self::from_bytes(&data))
+            }
+            _ => Rgb::from_raw_parts(color_type, Self::BIT_DEPTH, &data).map(Self::from),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            c: bytes[0],
+            m: bytes[1],
+            y: bytes[2],
+            k: bytes[3],
+        }
+    }
+
+    fn as_bytes(&self) -> Self::Data {
+        [self.c, self.m, self.y, self.k]
+    }
+
+    #[allow(clippy::cast_lossless)]
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        let alpha = alpha as f32 / 255.;
+        let lerp = |a: u8, b: u8| (b as f32 - a as f32).mul_add(alpha, a as f32).round() as u8;
+
+        Self {
+            c: lerp(self.c, other.c),
+            m: lerp(self.m, other.m),
+            y: lerp(self.y, other.y),
+            k: lerp(self.k, other.k),
+        }
+    }
+
+    fn from_dynamic(dynamic: Dynamic) -> Self {
+        Rgb::from_dynamic(dynamic).into()
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            c: self.c.max(other.c),
+            m: self.m.max(other.m),
+            y: self.y.max(other.y),
+            k: self.k.max(other.k),
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self {
+            c: self.c.min(other.c),
+            m: self.m.min(other.m),
+            y: self.y.min(other.y),
+            k: self.k.min(other.k),
+        }
+    }
+
+    force_into_impl!();
+}
+
+impl Not for Cmyk {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self {
+            c: !self.c,
+            m: !self.m,
+            y: !self.y,
+            k: !self.k,
+        }
+    }
+}
+
+impl Mul<f32> for Cmyk {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        let max = u8::MAX as f32;
+        Self {
+            c: ((self.c as f32 * rhs).clamp(0.0, max)) as u8,
+            m: ((self.m as f32 * rhs).clamp(0.0, max)) as u8,
+            y: ((self.y as f32 * rhs).clamp(0.0, max)) as u8,
+            k: ((self.k as f32 * rhs).clamp(0.0, max)) as u8,
+        }
+    }
+}
+
+impl Mul<Cmyk> for Cmyk {
+    type Output = Self;
+    fn mul(self, rhs: Cmyk) -> Self::Output {
+        let max = u8::MAX as f32;
+        Self {
+            c: (self.c as f32 * rhs.c as f32 / max) as u8,
+            m: (self.m as f32 * rhs.m as f32 / max) as u8,
+            y: (self.y as f32 * rhs.y as f32 / max) as u8,
+            k: (self.k as f32 * rhs.k as f32 / max) as u8,
+        }
+    }
+}
+
+impl Cmyk {
+    /// Creates a new CMYK pixel.
+    #[must_use]
+    pub const fn new(c: u8, m: u8, y: u8, k: u8) -> Self {
+        Self { c, m, y, k }
+    }
+
+    /// Creates a pixel with no ink, i.e. white.
+    #[must_use]
+    pub const fn white() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    /// Creates a pixel with only black ink.
+    #[must_use]
+    pub const fn black() -> Self {
+        Self::new(0, 0, 0, 255)
+    }
+}
+
+impl From<Cmyk> for Rgb {
+    #[allow(clippy::cast_lossless)]
+    fn from(Cmyk { c, m, y, k }: Cmyk) -> Self {
+        let k = 255 - k as u16;
+        // Rounded (255 - ink) * (255 - k) / 255
+        let channel = |ink: u8| (((255 - ink as u16) * k + 127) / 255) as u8;
+
+        Self::new(channel(c), channel(m), channel(y))
+    }
+}
+
+impl From<Rgb> for Cmyk {
+    #[allow(clippy::cast_lossless)]
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        let max = r.max(g).max(b);
+        if max == 0 {
+            return Self::black();
+        }
+
+        let max = max as u16;
+        // Rounded (max - channel) * 255 / max
+        let ink = |channel: u8| (((max - channel as u16) * 255 + max / 2) / max) as u8;
+
+        Self::new(ink(r), ink(g), ink(b), 255 - max as u8)
+    }
+}
+
+impl From<Cmyk> for Rgba {
+    fn from(cmyk: Cmyk) -> Self {
+        Self::from_rgb(cmyk.into())
+    }
+}
+
+impl From<Rgba> for Cmyk {
+    fn from(rgba: Rgba) -> Self {
+        Rgb::from(rgba).into()
+    }
+}
+
+impl From<Cmyk> for L {
+    fn from(cmyk: Cmyk) -> Self {
+        Rgb::from(cmyk).into()
+    }
+}
+
+impl From<L> for Cmyk {
+    fn from(L(l): L) -> Self {
+        Self::new(0, 0, 0, 255 - l)
+    }
+}
+
+/// Represents a subpixel of a dynamic pixel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DynamicSubpixel {
+    /// A u8 subpixel.
+    U8(u8),
+    /// A boolean subpixel.
+    Bool(bool),
+}
+
+macro_rules! impl_num_op {
+    ($err:literal; $self:expr, $other:expr; $a:ident, $b:ident; $out:expr) => {{
+        match ($self, $other) {
+            (DynamicSubpixel::U8($a), DynamicSubpixel::U8($b)) => DynamicSubpixel::U8($out),
+            (DynamicSubpixel::Bool(_), DynamicSubpixel::Bool(_)) => panic!(
+                "cannot {} DynamicPixel boolean variants. You should try converting to a \
+                    concrete pixel type so these runtime panics are not triggered.",
+                $err,
+            ),
+            _ => panic!(
+                "cannot {} different or incompatible DynamicSubpixel variants. You should \
+                    try converting to a concrete pixel type so these runtime panics are not \
+                    triggered.",
+                $err,
+            ),
+        }
+    }};
+}
+
+impl core::ops::Add for DynamicSubpixel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        impl_num_op!("add"; self, other; a, b; a + b)
+    }
+}
+
+impl core::ops::AddAssign for DynamicSubpixel {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl core::ops::Sub for DynamicSubpixel {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        impl_num_op!("subtract"; self, other; a, b; a - b)
+    }
+}
+
+impl core::ops::SubAssign for DynamicSubpixel {
     fn sub_assign(&mut self, other: Self) {
         *self = *self - other;
     }
 }
 
-impl std::ops::Mul for DynamicSubpixel {
+impl core::ops::Mul for DynamicSubpixel {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
@@ -1282,13 +3667,13 @@ impl std::ops::Mul for DynamicSubpixel {
     }
 }
 
-impl std::ops::MulAssign for DynamicSubpixel {
+impl core::ops::MulAssign for DynamicSubpixel {
     fn mul_assign(&mut self, other: Self) {
         *self = *self * other;
     }
 }
 
-impl std::ops::Div for DynamicSubpixel {
+impl core::ops::Div for DynamicSubpixel {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
@@ -1296,20 +3681,20 @@ impl std::ops::Div for DynamicSubpixel {
     }
 }
 
-impl std::ops::DivAssign for DynamicSubpixel {
+impl core::ops::DivAssign for DynamicSubpixel {
     fn div_assign(&mut self, other: Self) {
         *self = *self / other;
     }
 }
 
-impl std::ops::Rem for DynamicSubpixel {
+impl core::ops::Rem for DynamicSubpixel {
     type Output = Self;
     fn rem(self, other: Self) -> Self {
         impl_num_op!("remainder"; self, other; a, b; a % b)
     }
 }
 
-impl std::ops::RemAssign for DynamicSubpixel {
+impl core::ops::RemAssign for DynamicSubpixel {
     fn rem_assign(&mut self, other: Self) {
         *self = *self % other;
     }
@@ -1344,6 +3729,7 @@ impl From<DynamicSubpixel> for usize {
 
 /// Represents a pixel type that is dynamically resolved.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dynamic {
     BitPixel(BitPixel),
     L(L),
@@ -1405,6 +3791,15 @@ impl Pixel for Dynamic {
         }
     }
 
+    fn channel(&self, index: usize) -> Self::Subpixel {
+        match self {
+            Self::BitPixel(pixel) => DynamicSubpixel::Bool(pixel.channel(index)),
+            Self::L(pixel) => DynamicSubpixel::U8(pixel.channel(index)),
+            Self::Rgb(pixel) => DynamicSubpixel::U8(pixel.channel(index)),
+            Self::Rgba(pixel) => DynamicSubpixel::U8(pixel.channel(index)),
+        }
+    }
+
     fn from_raw_parts_paletted<P: Pixel>(
         color_type: ColorType,
         bit_depth: u8,
@@ -1441,6 +3836,10 @@ impl Pixel for Dynamic {
                         a: data[3],
                     })
                 }
+                ColorType::Cmyk => {
+                    propagate_data!(data, 4);
+                    Self::Rgb(Cmyk::from_bytes(&data).into())
+                }
                 _ => return Err(UnsupportedColorType),
             }
         })
@@ -1482,6 +3881,10 @@ impl Pixel for Dynamic {
         dynamic
     }
 
+    fn as_dynamic(&self) -> Option<Dynamic> {
+        Some(*self)
+    }
+
     fn max(self, other: Self) -> Self {
         match (self, other) {
             (Self::BitPixel(pixel), Self::BitPixel(other)) => Self::BitPixel(pixel.max(other)),
@@ -1557,6 +3960,20 @@ impl Alpha for Dynamic {
             pixel => pixel,
         }
     }
+
+    fn premultiplied(self) -> Self {
+        match self {
+            Self::Rgba(pixel) => Self::Rgba(pixel.premultiplied()),
+            pixel => pixel,
+        }
+    }
+
+    fn unpremultiplied(self) -> Self {
+        match self {
+            Self::Rgba(pixel) => Self::Rgba(pixel.unpremultiplied()),
+            pixel => pixel,
+        }
+    }
 }
 
 impl Dynamic {
@@ -1678,6 +4095,24 @@ impl From<Rgb> for Rgba {
     }
 }
 
+impl FromStr for Rgb {
+    type Err = crate::Error;
+
+    /// Parses an RGB pixel from a CSS color string. See [`Rgb::from_css`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_css(s)
+    }
+}
+
+impl FromStr for Rgba {
+    type Err = crate::Error;
+
+    /// Parses an RGBA pixel from a CSS color string. See [`Rgba::from_css`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_css(s)
+    }
+}
+
 /// A trait representing all pixels that can be safely represented as either RGB or RGBA true color.
 pub trait TrueColor: Pixel {
     /// Returns the pixel as an (r, g, b) tuple.
@@ -1743,11 +4178,11 @@ where
             let length = palette.len();
             let ptr = palette.as_ptr().cast::<$t>();
             // SAFETY: upheld by the caller
-            let palette = std::slice::from_raw_parts(ptr, length);
+            let palette = core::slice::from_raw_parts(ptr, length);
 
             // SAFETY: mostly upheld by the caller, but transmute_copy can be used since all Pixels
             // implement Copy.
-            Ok(std::mem::transmute_copy(&<$out>::from_palette(
+            Ok(core::mem::transmute_copy(&<$out>::from_palette(
                 palette,
                 index.into() as u8,
             )))
@@ -1761,11 +4196,12 @@ where
     }
 }
 
-/// A trait representing a paletted pixel. [`Pixel::Subpixel`] is the type of the palette index.
+/// A trait representing a paletted pixel. [`Pixel::Subpixel`] is the type of the palette index,
+/// which is always a `u8`.
 ///
 /// The generic lifetime parameter `'p` represents the lifetime of a palette the type will hold a
 /// reference to.
-pub trait Paletted<'p>: Pixel
+pub trait Paletted<'p>: Pixel<Subpixel = u8>
 where
     Self: 'p,
 {
@@ -1790,7 +4226,7 @@ where
     fn color(&self) -> Self::Color {
         *self
             .palette()
-            .get(self.palette_index().into())
+            .get(self.palette_index() as usize)
             .expect("invalid palette index")
     }
 
@@ -1803,7 +4239,7 @@ where
     /// # Safety
     /// * The palette index must be valid.
     unsafe fn color_unchecked(&self) -> Self::Color {
-        *self.palette().get_unchecked(self.palette_index().into())
+        *self.palette().get_unchecked(self.palette_index() as usize)
     }
 }
 
@@ -1903,6 +4339,10 @@ macro_rules! impl_palette8 {
                 try_palette!(self, "mapped", |color| color == target)
             }
 
+            fn channel(&self, index: usize) -> Self::Subpixel {
+                [self.index][index]
+            }
+
             fn from_raw_parts_paletted<P: Pixel>(
                 _color_type: ColorType,
                 _bit_depth: u8,
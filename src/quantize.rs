@@ -1,12 +1,163 @@
 //! Quantizes unpaletted pixel data to paletted data by quantizing the colors into a palette.
 
 #[cfg_attr(not(feature = "quantize"), allow(unused_imports))]
-use crate::{Pixel, TrueColor};
-use std::collections::HashMap;
+use crate::{Image, Pixel, TrueColor};
+use std::collections::{HashMap, HashSet};
 
 #[cfg(feature = "quantize")]
 use color_quant::NeuQuant;
 
+/// The algorithm used to reduce the colors of an image into a palette.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum QuantizeMethod {
+    /// Recursively splits the color space along the channel with the widest range until there
+    /// are as many boxes as desired colors, then averages the colors inside each box.
+    ///
+    /// This is fast and deterministic, but may produce less accurate colors than the other
+    /// methods for images with smooth gradients.
+    MedianCut,
+    /// Uses the NeuQuant neural network algorithm, which produces high quality palettes. This is
+    /// the default method.
+    ///
+    /// This requires the `quantize` feature. If it is disabled, lossless quantization is performed
+    /// instead, which fails if there are more unique colors than the palette size.
+    #[default]
+    NeuQuant,
+    /// Refines a median cut palette using k-means clustering. This usually produces the most
+    /// accurate colors, but is the slowest method. The amount of refinement iterations is
+    /// controlled by [`Quantizer::quality`].
+    KMeans,
+}
+
+/// A dithering algorithm, used to hide banding when reducing the amount of colors in an image by
+/// trading it for noise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Dither {
+    /// Floyd-Steinberg error diffusion, which spreads the entire quantization error of each pixel
+    /// to its neighbors. This is the most commonly used dithering algorithm.
+    FloydSteinberg,
+    /// Atkinson error diffusion, which only spreads three quarters of the quantization error of
+    /// each pixel. This preserves contrast better than Floyd-Steinberg, at the cost of detail in
+    /// very light and very dark areas.
+    Atkinson,
+    /// Ordered dithering using a 2x2 Bayer threshold matrix.
+    Bayer2x2,
+    /// Ordered dithering using a 4x4 Bayer threshold matrix.
+    Bayer4x4,
+    /// Ordered dithering using an 8x8 Bayer threshold matrix.
+    Bayer8x8,
+}
+
+impl Dither {
+    /// Returns the `log2` of the size of the Bayer matrix, or `None` if this is an error diffusion
+    /// algorithm.
+    const fn bayer_order(self) -> Option<u32> {
+        match self {
+            Self::Bayer2x2 => Some(1),
+            Self::Bayer4x4 => Some(2),
+            Self::Bayer8x8 => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Returns the neighbors to diffuse the error to as `(dx, dy, weight)`.
+    const fn diffusion_kernel(self) -> &'static [(i32, i32, f32)] {
+        match self {
+            Self::FloydSteinberg => &[
+                (1, 0, 7.0 / 16.0),
+                (-1, 1, 3.0 / 16.0),
+                (0, 1, 5.0 / 16.0),
+                (1, 1, 1.0 / 16.0),
+            ],
+            Self::Atkinson => &[
+                (1, 0, 1.0 / 8.0),
+                (2, 0, 1.0 / 8.0),
+                (-1, 1, 1.0 / 8.0),
+                (0, 1, 1.0 / 8.0),
+                (1, 1, 1.0 / 8.0),
+                (0, 2, 1.0 / 8.0),
+            ],
+            _ => &[],
+        }
+    }
+}
+
+/// Returns the threshold of the Bayer matrix of the given order at the given position, in the
+/// range `-0.5..0.5`.
+#[allow(clippy::cast_precision_loss)]
+fn bayer_threshold(order: u32, x: usize, y: usize) -> f32 {
+    let mut value = 0;
+    for i in 0..order {
+        let (bx, by) = ((x >> i) & 1, (y >> i) & 1);
+        // The 2x2 matrix is [[0, 2], [3, 1]]
+        value |= (((bx ^ by) << 1) | by) << (2 * (order - 1 - i));
+    }
+
+    (value as f32 + 0.5) / (1 << (2 * order)) as f32 - 0.5
+}
+
+/// Dithers the given pixels of an image with the given width. `nearest` receives the desired
+/// color of each pixel and returns the color that is actually used, along with the value to
+/// output for that pixel.
+///
+/// `spread` is the approximate distance between two adjacent output colors, which is used to
+/// scale the thresholds of ordered dithering.
+pub(crate) fn dither_with<P: TrueColor, T>(
+    pixels: &[P],
+    width: usize,
+    dither: Dither,
+    spread: f32,
+    mut nearest: impl FnMut([u8; 4]) -> ([u8; 4], T),
+) -> Vec<T> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let round = |color: [f32; 4]| color.map(|c| c.round().clamp(0.0, 255.0) as u8);
+    let components = |pixel: &P| {
+        let (r, g, b, a) = pixel.as_rgba_tuple();
+        [r, g, b, a].map(f32::from)
+    };
+
+    if let Some(order) = dither.bayer_order() {
+        return pixels
+            .iter()
+            .enumerate()
+            .map(|(i, pixel)| {
+                let offset = bayer_threshold(order, i % width, i / width) * spread;
+                let [r, g, b, a] = components(pixel);
+                nearest(round([r + offset, g + offset, b + offset, a])).1
+            })
+            .collect();
+    }
+
+    let kernel = dither.diffusion_kernel();
+    let mut errors = vec![[0.0_f32; 4]; pixels.len()];
+    let mut output = Vec::with_capacity(pixels.len());
+
+    for (i, pixel) in pixels.iter().enumerate() {
+        let mut desired = components(pixel);
+        for (c, e) in desired.iter_mut().zip(errors[i]) {
+            *c += e;
+        }
+
+        let (actual, value) = nearest(round(desired));
+        output.push(value);
+
+        let (x, y) = ((i % width) as i32, (i / width) as i32);
+        for &(dx, dy, weight) in kernel {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || nx >= width as i32 {
+                continue;
+            }
+            if let Some(error) = errors.get_mut(ny as usize * width + nx as usize) {
+                for ((e, d), a) in error.iter_mut().zip(desired).zip(actual) {
+                    *e += (d - f32::from(a)) * weight;
+                }
+            }
+        }
+    }
+
+    output
+}
+
 /// Configuration options regarding behavior of quantization.
 #[derive(Clone, Debug)]
 pub struct Quantizer {
@@ -29,6 +180,14 @@ pub struct Quantizer {
     /// image, where `n` is the amount of pixels in the image. This isn't very significant, but
     /// it's worth noting.
     pub fallback_to_lossless: bool,
+    /// The algorithm used for lossy quantization. Defaults to [`QuantizeMethod::NeuQuant`].
+    pub method: QuantizeMethod,
+    /// The dithering algorithm used when mapping pixels to the palette, or `None` to map each
+    /// pixel to its closest color. Defaults to `None`.
+    ///
+    /// Dithering requires the dimensions of the image, so it is only applied by
+    /// [`Self::quantize_image`]. It is also skipped if every color fits in the palette.
+    pub dither: Option<Dither>,
 }
 
 impl Default for Quantizer {
@@ -38,6 +197,8 @@ impl Default for Quantizer {
             gif_optimization: false,
             quality: 20,
             fallback_to_lossless: true,
+            method: QuantizeMethod::NeuQuant,
+            dither: None,
         }
     }
 }
@@ -88,30 +249,162 @@ impl Quantizer {
         self
     }
 
+    /// Sets the algorithm used for lossy quantization.
+    #[must_use]
+    pub const fn with_method(mut self, method: QuantizeMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets the dithering algorithm used when mapping pixels of an image to the palette.
+    ///
+    /// Dithering requires the dimensions of the image, so it is only applied by
+    /// [`Self::quantize_image`].
+    #[must_use]
+    pub const fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = Some(dither);
+        self
+    }
+
     /// Quantizes the given pixels to a palette of the given size. Returns `(palette, image_data)`.
     ///
     /// # Behavior
-    /// * If the `quantize` feature is disabled (enabled by default), this function will simply
-    /// run the [`quantize_simple`] function (lossless) and return `Err` if there are more unique
-    /// colors than the palette size.
-    /// * Otherwise, this function will always favor a lossy quantization algorithm unless the
-    /// `fallback_to_lossless` option is set to `true` (which is the default) and the amount of
-    /// unique colors in the image is less than the desired palette size.
+    /// * This function will always favor the lossy quantization algorithm given by `method`
+    ///   unless the `fallback_to_lossless` option is set to `true` (which is the default) and the
+    ///   amount of unique colors in the image is less than the desired palette size.
+    /// * If the `quantize` feature is disabled (enabled by default) and the method is
+    ///   [`QuantizeMethod::NeuQuant`], this function will simply run the [`quantize_simple`]
+    ///   function (lossless) and return `Err` if there are more unique colors than the palette
+    ///   size.
     ///
     /// # Errors
-    /// * There are more unique colors than the palette size, and the `quantize` feature is disabled
+    /// * There are more unique colors than the palette size, the method is
+    ///   [`QuantizeMethod::NeuQuant`] and the `quantize` feature is disabled
     pub fn quantize<P: TrueColor>(
         &self,
         pixels: impl AsRef<[P]>,
     ) -> crate::Result<(Vec<P>, Vec<u8>)> {
-        #[cfg(feature = "quantize")]
-        {
-            quantize_lossy(pixels, self)
+        match self.method {
+            QuantizeMethod::MedianCut => quantize_median_cut(pixels, self),
+            QuantizeMethod::KMeans => quantize_kmeans(pixels, self),
+            #[cfg(feature = "quantize")]
+            QuantizeMethod::NeuQuant => quantize_lossy(pixels, self),
+            #[cfg(not(feature = "quantize"))]
+            QuantizeMethod::NeuQuant => quantize_simple(pixels, self),
         }
-        #[cfg(not(feature = "quantize"))]
+    }
+
+    /// Quantizes the pixels of the given image to a palette of the given size, applying
+    /// dithering if it is enabled. Returns `(palette, image_data)`.
+    ///
+    /// # Errors
+    /// * See [`Self::quantize`]
+    pub fn quantize_image<P: TrueColor>(
+        &self,
+        image: &Image<P>,
+    ) -> crate::Result<(Vec<P>, Vec<u8>)> {
+        let (palette, indices) = self.quantize(&image.data)?;
+        let dither = match self.dither {
+            Some(dither) => dither,
+            None => return Ok((palette, indices)),
+        };
+
+        // Every color is already represented exactly, so there is no error to diffuse
+        if image
+            .data
+            .iter()
+            .zip(&indices)
+            .all(|(pixel, &index)| palette[index as usize] == *pixel)
         {
-            quantize_simple(pixels, self)
+            return Ok((palette, indices));
+        }
+
+        let indices = map_to_palette(&image.data, image.width() as usize, &palette, Some(dither));
+        Ok((palette, indices))
+    }
+
+    /// Maps the pixels of the given image to the closest colors of the given palette, applying
+    /// dithering if it is enabled. Returns the palette index of every pixel.
+    ///
+    /// Unlike [`Self::quantize_image`], this does not generate a palette, which is useful to share
+    /// a palette between multiple images, such as the frames of an animation, or to restrict an
+    /// image to a prescribed set of colors. All other options of this quantizer are ignored.
+    ///
+    /// # Panics
+    /// * The palette is empty or has more than 256 colors.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// use ril::Quantizer;
+    ///
+    /// let image = Image::from_fn(4, 1, |x, _| Rgb::new(x as u8 * 85, 0, 0));
+    /// let indices = Quantizer::new().map_to_palette(&image, &[Rgb::black(), Rgb::new(255, 0, 0)]);
+    ///
+    /// assert_eq!(indices, [0, 0, 1, 1]);
+    /// ```
+    #[must_use]
+    pub fn map_to_palette<P: TrueColor>(&self, image: &Image<P>, palette: &[P]) -> Vec<u8> {
+        assert!(
+            (1..=256).contains(&palette.len()),
+            "palette must have between 1 and 256 colors, got {}",
+            palette.len()
+        );
+
+        map_to_palette(&image.data, image.width() as usize, palette, self.dither)
+    }
+}
+
+/// Maps the given pixels of an image with the given width to the indices of their closest colors
+/// in the given palette, optionally dithering them.
+fn map_to_palette<P: TrueColor>(
+    pixels: &[P],
+    width: usize,
+    palette: &[P],
+    dither: Option<Dither>,
+) -> Vec<u8> {
+    let colors = palette
+        .iter()
+        .map(|pixel| {
+            let (r, g, b, a) = pixel.as_rgba_tuple();
+            [r, g, b, a]
+        })
+        .collect::<Vec<_>>();
+
+    let mut cache = HashMap::new();
+    #[allow(clippy::cast_possible_truncation)]
+    let mut nearest = |color: [u8; 4]| {
+        let index = *cache.entry(color).or_insert_with(|| {
+            colors
+                .iter()
+                .map(|&c| {
+                    c.iter()
+                        .zip(color)
+                        .map(|(&a, b)| (i32::from(a) - i32::from(b)).pow(2))
+                        .sum::<i32>()
+                })
+                .enumerate()
+                .min_by_key(|&(_, distance)| distance)
+                .map_or(0, |(i, _)| i)
+        });
+
+        (colors[index], index as u8)
+    };
+
+    match dither {
+        Some(dither) => {
+            #[allow(clippy::cast_precision_loss)]
+            let spread = 255.0 / (colors.len() as f32).cbrt().max(1.0);
+
+            dither_with(pixels, width, dither, spread, nearest)
         }
+        None => pixels
+            .iter()
+            .map(|pixel| {
+                let (r, g, b, a) = pixel.as_rgba_tuple();
+                nearest([r, g, b, a]).1
+            })
+            .collect(),
     }
 }
 
@@ -171,11 +464,8 @@ pub fn quantize_lossy<P: TrueColor>(
     config: &Quantizer,
 ) -> crate::Result<(Vec<P>, Vec<u8>)> {
     let pixels = pixels.as_ref();
-    if config.fallback_to_lossless {
-        let count = pixels.windows(2).filter(|win| win[0] != win[1]).count() + 1;
-        if count <= config.palette_size {
-            return quantize_simple::<P>(pixels, config);
-        }
+    if config.fallback_to_lossless && fits_palette(pixels, config.palette_size) {
+        return quantize_simple::<P>(pixels, config);
     }
 
     let pixels = pixels
@@ -200,10 +490,238 @@ pub fn quantize_lossy<P: TrueColor>(
     ))
 }
 
+/// Returns whether the amount of unique colors in `pixels` does not exceed `palette_size`.
+fn fits_palette<P: TrueColor>(pixels: &[P], palette_size: usize) -> bool {
+    let mut seen = HashSet::with_capacity(palette_size + 1);
+    pixels.iter().all(|pixel| {
+        seen.insert(pixel.as_rgba_tuple());
+        seen.len() <= palette_size
+    })
+}
+
+/// Returns every unique color in `pixels` along with the amount of times it occurs.
+fn histogram<P: TrueColor>(pixels: &[P]) -> Vec<([u8; 4], u32)> {
+    let mut counts = HashMap::new();
+    for pixel in pixels {
+        let (r, g, b, a) = pixel.as_rgba_tuple();
+        *counts.entry([r, g, b, a]).or_insert(0_u32) += 1;
+    }
+
+    let mut histogram = counts.into_iter().collect::<Vec<_>>();
+    // HashMap iteration order is random, sort for deterministic results
+    histogram.sort_unstable();
+    histogram
+}
+
+fn squared_distance(a: [u8; 4], b: [f64; 4]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(&x, y)| (f64::from(x) - y).powi(2))
+        .sum()
+}
+
+/// Returns the weighted mean of the given colors.
+fn mean_color(colors: &[([u8; 4], u32)]) -> [f64; 4] {
+    let mut sum = [0.0; 4];
+    let mut total = 0.0;
+    for &(color, count) in colors {
+        let count = f64::from(count);
+        for (s, c) in sum.iter_mut().zip(color) {
+            *s += f64::from(c) * count;
+        }
+        total += count;
+    }
+
+    sum.map(|s| s / total.max(1.0))
+}
+
+/// Turns the given colors into a palette, remapping each pixel to the index of its color.
+fn into_palette<P: TrueColor>(
+    pixels: &[P],
+    centers: &[[f64; 4]],
+    lookup: &HashMap<[u8; 4], u8>,
+    config: &Quantizer,
+) -> (Vec<P>, Vec<u8>) {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let palette = centers
+        .iter()
+        .map(|center| {
+            let [r, g, b, mut a] = center.map(|c| c.round().clamp(0.0, 255.0) as u8);
+            if config.gif_optimization {
+                // See `quantize_simple` for why this is done
+                a = if a == 0 { 0 } else { 255 };
+            }
+            P::from_rgba_tuple((r, g, b, a))
+        })
+        .collect();
+
+    let indices = pixels
+        .iter()
+        .map(|pixel| {
+            let (r, g, b, a) = pixel.as_rgba_tuple();
+            lookup[&[r, g, b, a]]
+        })
+        .collect();
+
+    (palette, indices)
+}
+
+/// Splits the histogram into at most `palette_size` boxes using the median cut algorithm.
+fn median_cut_boxes(
+    histogram: Vec<([u8; 4], u32)>,
+    palette_size: usize,
+) -> Vec<Vec<([u8; 4], u32)>> {
+    // Returns the channel with the widest range along with that range
+    fn widest_channel(colors: &[([u8; 4], u32)]) -> (usize, u8) {
+        (0..4)
+            .map(|channel| {
+                let (min, max) = colors.iter().fold((255, 0), |(min, max), (color, _)| {
+                    (color[channel].min(min), color[channel].max(max))
+                });
+                (channel, max.saturating_sub(min))
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap_or((0, 0))
+    }
+
+    let mut boxes = vec![histogram];
+    while boxes.len() < palette_size {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(i, colors)| (i, widest_channel(colors)))
+            .max_by_key(|&(_, (_, range))| range);
+
+        // Every box only contains a single color, so they cannot be split any further
+        let (index, (channel, _)) = match widest {
+            Some(widest) => widest,
+            None => break,
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|(color, _)| color[channel]);
+
+        // Split at the weighted median so that each half represents a similar amount of pixels
+        let total = colors
+            .iter()
+            .map(|&(_, count)| u64::from(count))
+            .sum::<u64>();
+        let mut acc = 0;
+        let split = colors
+            .iter()
+            .position(|&(_, count)| {
+                acc += u64::from(count);
+                acc * 2 >= total
+            })
+            .map_or(1, |i| i + 1)
+            .clamp(1, colors.len() - 1);
+
+        let other = colors.split_off(split);
+        boxes.push(colors);
+        boxes.push(other);
+    }
+
+    boxes
+}
+
+/// Quantizes an image using the median cut algorithm. Returns `(palette, image_data)`.
+///
+/// # Errors
+/// * This function currently never fails, but returns `Result` for consistency with the other
+///   quantizers.
+pub fn quantize_median_cut<P: TrueColor>(
+    pixels: impl AsRef<[P]>,
+    config: &Quantizer,
+) -> crate::Result<(Vec<P>, Vec<u8>)> {
+    let pixels = pixels.as_ref();
+    if config.fallback_to_lossless && fits_palette(pixels, config.palette_size) {
+        return quantize_simple::<P>(pixels, config);
+    }
+
+    let boxes = median_cut_boxes(histogram(pixels), config.palette_size.clamp(1, 256));
+    let centers = boxes
+        .iter()
+        .map(|colors| mean_color(colors))
+        .collect::<Vec<_>>();
+
+    #[allow(clippy::cast_possible_truncation)]
+    let lookup = boxes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, colors)| colors.iter().map(move |&(color, _)| (color, i as u8)))
+        .collect();
+
+    Ok(into_palette(pixels, &centers, &lookup, config))
+}
+
+/// Quantizes an image using k-means clustering, starting from a median cut palette. The amount
+/// of iterations is bounded by the `quality` of the quantizer. Returns `(palette, image_data)`.
+///
+/// # Errors
+/// * This function currently never fails, but returns `Result` for consistency with the other
+///   quantizers.
+pub fn quantize_kmeans<P: TrueColor>(
+    pixels: impl AsRef<[P]>,
+    config: &Quantizer,
+) -> crate::Result<(Vec<P>, Vec<u8>)> {
+    let pixels = pixels.as_ref();
+    if config.fallback_to_lossless && fits_palette(pixels, config.palette_size) {
+        return quantize_simple::<P>(pixels, config);
+    }
+
+    let histogram = histogram(pixels);
+    let mut centers = median_cut_boxes(histogram.clone(), config.palette_size.clamp(1, 256))
+        .iter()
+        .map(|colors| mean_color(colors))
+        .collect::<Vec<_>>();
+
+    let nearest = |centers: &[[f64; 4]], color: [u8; 4]| {
+        centers
+            .iter()
+            .map(|&center| squared_distance(color, center))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map_or(0, |(i, _)| i)
+    };
+
+    let mut assignments = vec![usize::MAX; histogram.len()];
+    for _ in 0..config.quality.max(1) {
+        let mut changed = false;
+        for (assignment, &(color, _)) in assignments.iter_mut().zip(&histogram) {
+            let index = nearest(&centers, color);
+            changed |= *assignment != index;
+            *assignment = index;
+        }
+        if !changed {
+            break;
+        }
+
+        let mut clusters = vec![Vec::new(); centers.len()];
+        for (&assignment, &entry) in assignments.iter().zip(&histogram) {
+            clusters[assignment].push(entry);
+        }
+        for (center, cluster) in centers.iter_mut().zip(clusters) {
+            // Empty clusters keep their previous center
+            if !cluster.is_empty() {
+                *center = mean_color(&cluster);
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let lookup = histogram
+        .iter()
+        .map(|&(color, _)| (color, nearest(&centers, color) as u8))
+        .collect();
+
+    Ok(into_palette(pixels, &centers, &lookup, config))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
-    use crate::quantize::Quantizer;
+    use crate::quantize::{Dither, QuantizeMethod, Quantizer};
 
     #[test]
     fn test_quantization() {
@@ -220,4 +738,56 @@ mod tests {
         );
         assert_eq!(pixels, &[0, 0, 1]);
     }
+
+    #[test]
+    fn test_lossy_methods() {
+        let sample = (0..=255)
+            .map(|i| Rgb::new(i, 0, 0))
+            .chain((0..=255).map(|i| Rgb::new(0, 0, i)))
+            .collect::<Vec<_>>();
+
+        for method in [QuantizeMethod::MedianCut, QuantizeMethod::KMeans] {
+            let (palette, pixels) = Quantizer::new()
+                .with_palette_size(16)
+                .with_method(method)
+                .quantize(&sample)
+                .unwrap();
+
+            assert!(palette.len() <= 16);
+            assert_eq!(pixels.len(), sample.len());
+            for (pixel, &index) in sample.iter().zip(&pixels) {
+                let color = palette[index as usize];
+                assert!((i32::from(color.r) - i32::from(pixel.r)).abs() <= 32);
+                assert!((i32::from(color.b) - i32::from(pixel.b)).abs() <= 32);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dithering() {
+        let image = Image::from_fn(16, 16, |x, _| L(if x % 2 == 0 { 64 } else { 128 }))
+            .map_pixels(Rgb::from);
+        let quantizer = Quantizer::new()
+            .with_palette_size(2)
+            .with_method(QuantizeMethod::MedianCut)
+            .with_dither(Dither::FloydSteinberg);
+
+        // Colors that fit in the palette are never dithered
+        let (_, pixels) = quantizer.quantize_image(&image).unwrap();
+        assert!(pixels.chunks(2).all(|pair| pair[0] != pair[1]));
+
+        // Dithering preserves the average brightness of the image
+        let gray = Image::from_fn(16, 16, |x, y| match (x, y) {
+            (0, 0) => Rgb::black(),
+            (1, 0) => Rgb::white(),
+            _ => Rgb::new(96, 96, 96),
+        });
+        let (palette, pixels) = quantizer.quantize_image(&gray).unwrap();
+        let mean = pixels
+            .iter()
+            .map(|&i| f64::from(palette[i as usize].r))
+            .sum::<f64>()
+            / pixels.len() as f64;
+        assert!((mean - 96.0).abs() < 4.0);
+    }
 }
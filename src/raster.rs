@@ -0,0 +1,386 @@
+//! Implements a scanline rasterizer with anti-aliasing for arbitrary contours, along with a stroker
+//! which converts polylines into contours.
+//!
+//! Coordinates used here follow the same convention as the rest of the drawing API, where integer
+//! coordinates lie on the center of a pixel.
+
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+
+use crate::draw::{FillRule, LineCap, LineJoin};
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The number of sub-scanlines sampled per row of pixels. Horizontal coverage is computed exactly.
+const SUBSAMPLES: usize = 8;
+
+/// A point with sub-pixel precision.
+pub(crate) type Point = (f32, f32);
+
+impl FillRule {
+    #[inline]
+    const fn is_inside(self, winding: i32) -> bool {
+        match self {
+            Self::EvenOdd => winding % 2 != 0,
+            Self::NonZero => winding != 0,
+        }
+    }
+}
+
+/// An edge of a contour, stored such that `y1 < y2`.
+struct Edge {
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    /// `1` if the edge originally pointed downwards, `-1` otherwise.
+    direction: i32,
+}
+
+/// Adds the horizontal span `[start, end)` to the given coverage row, weighted by `weight`.
+fn accumulate_span(coverage: &mut [f32], start: f32, end: f32, weight: f32) {
+    let width = coverage.len() as f32;
+    let (start, end) = (start.max(0.0), end.min(width));
+    if start >= end {
+        return;
+    }
+
+    let (i, j) = (start as usize, end as usize);
+    if i == j {
+        coverage[i] += (end - start) * weight;
+        return;
+    }
+
+    coverage[i] += (i as f32 + 1.0 - start) * weight;
+    for value in &mut coverage[i + 1..j] {
+        *value += weight;
+    }
+    if j < coverage.len() {
+        coverage[j] += (end - j as f32) * weight;
+    }
+}
+
+/// Rasterizes the area enclosed by the given contours. Each contour is implicitly closed.
+///
+/// `plot` is called once for every pixel within `(0, 0, width, height)` that is at least partially
+/// covered, along with its coverage where `255` is fully covered.
+pub(crate) fn rasterize(
+    contours: &[Vec<Point>],
+    rule: FillRule,
+    (width, height): (u32, u32),
+    mut plot: impl FnMut(u32, u32, u8),
+) {
+    // Shift coordinates by half a pixel so that pixel `x` spans `[x, x + 1)`.
+    let mut edges = Vec::new();
+    for contour in contours {
+        let points = contour.iter().map(|&(x, y)| (x + 0.5, y + 0.5));
+
+        for ((x1, y1), (x2, y2)) in points.clone().zip(points.cycle().skip(1)) {
+            #[allow(clippy::float_cmp)]
+            if y1 == y2 {
+                continue;
+            }
+
+            edges.push(if y1 < y2 {
+                Edge {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    direction: 1,
+                }
+            } else {
+                Edge {
+                    x1: x2,
+                    y1: y2,
+                    x2: x1,
+                    y2: y1,
+                    direction: -1,
+                }
+            });
+        }
+    }
+
+    if edges.is_empty() || width == 0 || height == 0 {
+        return;
+    }
+
+    let (mut y_min, mut y_max) = (f32::INFINITY, f32::NEG_INFINITY);
+    let (mut x_min, mut x_max) = (f32::INFINITY, f32::NEG_INFINITY);
+    for edge in &edges {
+        y_min = y_min.min(edge.y1);
+        y_max = y_max.max(edge.y2);
+        x_min = x_min.min(edge.x1.min(edge.x2));
+        x_max = x_max.max(edge.x1.max(edge.x2));
+    }
+
+    let y_range = (y_min.max(0.0) as u32)..(y_max.ceil().max(0.0) as u32).min(height);
+    let x_range = (x_min.max(0.0) as usize)..(x_max.ceil().max(0.0) as usize).min(width as usize);
+    if x_range.is_empty() {
+        return;
+    }
+
+    let mut coverage = vec![0.0_f32; width as usize];
+    let mut crossings = Vec::new();
+    let weight = 1.0 / SUBSAMPLES as f32;
+
+    for y in y_range {
+        for sample in 0..SUBSAMPLES {
+            let sy = y as f32 + (sample as f32 + 0.5) * weight;
+
+            crossings.extend(edges.iter().filter(|e| e.y1 <= sy && sy < e.y2).map(|e| {
+                let t = (sy - e.y1) / (e.y2 - e.y1);
+                (t.mul_add(e.x2 - e.x1, e.x1), e.direction)
+            }));
+            crossings.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+            let mut winding = 0;
+            let mut start = 0.0;
+            for &(x, direction) in &crossings {
+                let was_inside = rule.is_inside(winding);
+                winding += direction;
+
+                match (was_inside, rule.is_inside(winding)) {
+                    (false, true) => start = x,
+                    (true, false) => accumulate_span(&mut coverage, start, x, weight),
+                    _ => (),
+                }
+            }
+            crossings.clear();
+        }
+
+        for x in x_range.clone() {
+            let value = core::mem::take(&mut coverage[x]);
+            let alpha = (value.min(1.0) * 255.0).round() as u8;
+
+            if alpha > 0 {
+                plot(x as u32, y, alpha);
+            }
+        }
+    }
+}
+
+/// Returns twice the signed area of the given contour.
+fn signed_area(contour: &[Point]) -> f32 {
+    contour
+        .iter()
+        .zip(contour.iter().cycle().skip(1))
+        .map(|(&(x1, y1), &(x2, y2))| x1.mul_add(y2, -x2 * y1))
+        .sum()
+}
+
+/// Approximates a circle with the given center and radius as a contour.
+fn circle(center: Point, radius: f32) -> Vec<Point> {
+    ellipse(center, (radius, radius), 0.0)
+}
+
+/// Approximates an ellipse with the given center and radii, rotated clockwise by `rotation`
+/// radians around its center, as a contour.
+pub(crate) fn ellipse((cx, cy): Point, (a, b): (f32, f32), rotation: f32) -> Vec<Point> {
+    let n = ((core::f32::consts::TAU * a.max(b)).ceil() as usize).clamp(8, 1024);
+    let step = core::f32::consts::TAU / n as f32;
+    let (rsin, rcos) = rotation.sin_cos();
+
+    (0..n)
+        .map(|i| {
+            let (sin, cos) = (step * i as f32).sin_cos();
+            let (x, y) = (a * cos, b * sin);
+
+            (
+                x.mul_add(rcos, y.mul_add(-rsin, cx)),
+                x.mul_add(rsin, y.mul_add(rcos, cy)),
+            )
+        })
+        .collect()
+}
+
+/// Approximates an arc around the given center, from `start` to `end` in radians, and appends it
+/// to the given contour. Angles are measured clockwise from the positive x-axis since the y-axis
+/// points downwards.
+fn push_arc(contour: &mut Vec<Point>, (cx, cy): Point, radius: f32, start: f32, end: f32) {
+    let n = ((radius * (end - start).abs()).ceil() as usize).clamp(1, 512);
+    let step = (end - start) / n as f32;
+
+    contour.extend((0..=n).map(|i| {
+        let (sin, cos) = step.mul_add(i as f32, start).sin_cos();
+        (radius.mul_add(cos, cx), radius.mul_add(sin, cy))
+    }));
+}
+
+/// Scales down the given corner radii of a rectangle with the given size such that the radii of
+/// adjacent corners never exceed the length of the side between them. Radii are ordered top-left,
+/// top-right, bottom-right, bottom-left.
+pub(crate) fn fit_radii(radii: [f32; 4], (width, height): (f32, f32)) -> [f32; 4] {
+    let [tl, tr, br, bl] = radii;
+    let scale = [
+        (width, tl + tr),
+        (height, tr + br),
+        (width, br + bl),
+        (height, bl + tl),
+    ]
+    .iter()
+    .filter(|(_, sum)| *sum > 0.0)
+    .fold(1.0_f32, |scale, (length, sum)| scale.min(length / sum));
+
+    radii.map(|r| r * scale)
+}
+
+/// Creates the contour of a rectangle spanning `(x1, y1, x2, y2)` with rounded corners. Radii are
+/// ordered top-left, top-right, bottom-right, bottom-left and should be fitted with [`fit_radii`].
+pub(crate) fn rounded_rectangle(
+    (x1, y1, x2, y2): (f32, f32, f32, f32),
+    [tl, tr, br, bl]: [f32; 4],
+) -> Vec<Point> {
+    use core::f32::consts::{FRAC_PI_2, PI};
+
+    let mut contour = Vec::new();
+    push_arc(&mut contour, (x1 + tl, y1 + tl), tl, PI, PI + FRAC_PI_2);
+    push_arc(&mut contour, (x2 - tr, y1 + tr), tr, -FRAC_PI_2, 0.0);
+    push_arc(&mut contour, (x2 - br, y2 - br), br, 0.0, FRAC_PI_2);
+    push_arc(&mut contour, (x1 + bl, y2 - bl), bl, FRAC_PI_2, PI);
+    contour
+}
+
+/// Converts a polyline into a set of contours which, when rasterized with [`FillRule::NonZero`],
+/// cover the stroke of the polyline with the given thickness.
+///
+/// The stroke is built from a contour for every segment, join, and cap, all with the same
+/// orientation so that overlapping regions do not cancel each other out.
+pub(crate) fn stroke(
+    points: &[Point],
+    closed: bool,
+    thickness: f32,
+    join: LineJoin,
+    cap: LineCap,
+    miter_limit: f32,
+) -> Vec<Vec<Point>> {
+    let hw = thickness / 2.0;
+    let mut points = points.to_vec();
+    #[allow(clippy::float_cmp)]
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let mut contours = Vec::new();
+    if points.is_empty() || hw <= 0.0 {
+        return contours;
+    }
+
+    // A single point only consists of its caps
+    if points.len() == 1 {
+        let (x, y) = points[0];
+        match cap {
+            LineCap::Butt => (),
+            LineCap::Round => contours.push(circle((x, y), hw)),
+            LineCap::Square => contours.push(vec![
+                (x - hw, y - hw),
+                (x + hw, y - hw),
+                (x + hw, y + hw),
+                (x - hw, y + hw),
+            ]),
+        }
+        return contours;
+    }
+
+    let segment_count = if closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+    let segments = (0..segment_count).map(|i| (points[i], points[(i + 1) % points.len()]));
+
+    // Returns the unit direction and the normal scaled by `hw` of the given segment
+    let frame = |((x1, y1), (x2, y2)): (Point, Point)| {
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = dx.hypot(dy);
+        let (dx, dy) = (dx / length, dy / length);
+
+        ((dx, dy), (-dy * hw, dx * hw))
+    };
+
+    for (i, (mut a, mut b)) in segments.clone().enumerate() {
+        let ((dx, dy), (nx, ny)) = frame((a, b));
+
+        if !closed && cap == LineCap::Square {
+            if i == 0 {
+                a = (dx.mul_add(-hw, a.0), dy.mul_add(-hw, a.1));
+            }
+            if i == segment_count - 1 {
+                b = (dx.mul_add(hw, b.0), dy.mul_add(hw, b.1));
+            }
+        }
+
+        contours.push(vec![
+            (a.0 + nx, a.1 + ny),
+            (b.0 + nx, b.1 + ny),
+            (b.0 - nx, b.1 - ny),
+            (a.0 - nx, a.1 - ny),
+        ]);
+    }
+
+    // Joins between consecutive segments
+    let frames = segments.map(frame).collect::<Vec<_>>();
+    let join_count = if closed {
+        frames.len()
+    } else {
+        frames.len() - 1
+    };
+
+    for i in 0..join_count {
+        let ((d1x, d1y), (n1x, n1y)) = frames[i];
+        let ((d2x, d2y), (n2x, n2y)) = frames[(i + 1) % frames.len()];
+        let (px, py) = points[(i + 1) % points.len()];
+
+        let cross = d1x.mul_add(d2y, -d1y * d2x);
+        let dot = d1x.mul_add(d2x, d1y * d2y);
+        if cross.abs() < 1e-6 && dot > 0.0 {
+            continue;
+        }
+        if join == LineJoin::Round {
+            contours.push(circle((px, py), hw));
+            continue;
+        }
+
+        // The outer side of the turn
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let outer1 = (side * n1x + px, side * n1y + py);
+        let outer2 = (side * n2x + px, side * n2y + py);
+
+        let (mx, my) = (n1x + n2x, n1y + n2y);
+        let m_length = mx.hypot(my);
+        // The ratio of the miter length to half of the thickness is `1 / cos(theta / 2)`, where
+        // `theta` is the angle between the two normals
+        let cos_half = m_length / (2.0 * hw);
+
+        if join == LineJoin::Miter && cos_half > 1e-6 && cos_half.recip() <= miter_limit {
+            let scale = side * hw / cos_half / m_length;
+            contours.push(vec![
+                (px, py),
+                outer1,
+                (mx.mul_add(scale, px), my.mul_add(scale, py)),
+                outer2,
+            ]);
+        } else {
+            contours.push(vec![(px, py), outer1, outer2]);
+        }
+    }
+
+    if !closed && cap == LineCap::Round {
+        contours.push(circle(points[0], hw));
+        contours.push(circle(points[points.len() - 1], hw));
+    }
+
+    // Orient all contours the same way so that they are unioned by the non-zero fill rule
+    for contour in &mut contours {
+        if signed_area(contour) < 0.0 {
+            contour.reverse();
+        }
+    }
+    contours
+}
@@ -0,0 +1,332 @@
+//! Rectangular regions of images, and cropping images to them safely.
+
+use crate::{image::rebind_pixel, Error, Image, Pixel, Result};
+use alloc::{vec, vec::Vec};
+use core::num::NonZeroU32;
+
+/// A rectangular region with a position and dimensions. The position may be negative or lie
+/// outside of an image, which is handled according to an [`OutOfBounds`] policy when the region
+/// is used.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// let rect = Rect::from_bounds(-4, 2, 12, 10);
+/// assert_eq!(rect.dimensions(), (16, 8));
+///
+/// let image = Image::new(8, 8, Rgb::black());
+/// assert_eq!(rect.intersection(&image.bounds()), Some(Rect::new(0, 2, 8, 6)));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The x coordinate of the left edge of the rectangle.
+    pub x: i64,
+    /// The y coordinate of the top edge of the rectangle.
+    pub y: i64,
+    /// The width of the rectangle in pixels.
+    pub width: u32,
+    /// The height of the rectangle in pixels.
+    pub height: u32,
+}
+
+impl Rect {
+    /// Creates a new rectangle with the given top-left corner and dimensions.
+    #[must_use]
+    pub const fn new(x: i64, y: i64, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a new rectangle from the given bounds, where `x2` and `y2` are exclusive. The
+    /// corners may be given in any order.
+    ///
+    /// # Panics
+    /// * The width or height of the bounds exceeds `u32::MAX`.
+    #[must_use]
+    pub fn from_bounds(x1: i64, y1: i64, x2: i64, y2: i64) -> Self {
+        let (x1, x2) = (x1.min(x2), x1.max(x2));
+        let (y1, y2) = (y1.min(y2), y1.max(y2));
+        let size = |length: i64| u32::try_from(length).expect("rectangle is too large");
+
+        Self::new(x1, y1, size(x2 - x1), size(y2 - y1))
+    }
+
+    /// Returns the exclusive x coordinate of the right edge of the rectangle.
+    #[must_use]
+    pub const fn x2(&self) -> i64 {
+        self.x + self.width as i64
+    }
+
+    /// Returns the exclusive y coordinate of the bottom edge of the rectangle.
+    #[must_use]
+    pub const fn y2(&self) -> i64 {
+        self.y + self.height as i64
+    }
+
+    /// Returns the bounds of the rectangle as `(x1, y1, x2, y2)`, where `x2` and `y2` are
+    /// exclusive.
+    #[must_use]
+    pub const fn bounds(&self) -> (i64, i64, i64, i64) {
+        (self.x, self.y, self.x2(), self.y2())
+    }
+
+    /// Returns the dimensions of the rectangle as `(width, height)`.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns whether the rectangle has no area.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns whether the given point lies inside of the rectangle.
+    #[must_use]
+    pub const fn contains(&self, x: i64, y: i64) -> bool {
+        x >= self.x && x < self.x2() && y >= self.y && y < self.y2()
+    }
+
+    /// Returns whether the given rectangle lies entirely inside of this rectangle.
+    #[must_use]
+    pub const fn contains_rect(&self, other: &Self) -> bool {
+        other.x >= self.x && other.y >= self.y && other.x2() <= self.x2() && other.y2() <= self.y2()
+    }
+
+    /// Returns the region covered by both rectangles, or `None` if they don't overlap.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let (x1, y1) = (self.x.max(other.x), self.y.max(other.y));
+        let (x2, y2) = (self.x2().min(other.x2()), self.y2().min(other.y2()));
+
+        (x1 < x2 && y1 < y2).then(|| Self::new(x1, y1, (x2 - x1) as u32, (y2 - y1) as u32))
+    }
+
+    /// Returns this rectangle moved by the given offset.
+    #[must_use]
+    pub const fn translated(self, dx: i64, dy: i64) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
+}
+
+/// Determines what happens when a [`Rect`] exceeds the bounds of an image, for example when
+/// cropping with [`Image::crop_rect`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutOfBounds<P: Pixel> {
+    /// Shrink the rectangle to the part that lies inside of the image.
+    Clamp,
+    /// Return an [`Error::OutOfBounds`] error.
+    Error,
+    /// Keep the dimensions of the rectangle, filling the parts outside of the image with the given
+    /// pixel.
+    Fill(P),
+}
+
+impl<P: Pixel> Image<P> {
+    /// Returns the bounds of this image as a [`Rect`] at `(0, 0)`.
+    #[must_use]
+    pub const fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width(), self.height())
+    }
+
+    /// Resolves the given rectangle against the bounds of this image according to the given
+    /// policy, returning the region of this image to copy.
+    pub(crate) fn resolve_rect(&self, rect: Rect, policy: OutOfBounds<P>) -> Result<Option<Rect>> {
+        if rect.is_empty() {
+            return Err(Error::EmptyImageError);
+        }
+
+        let inside = rect.intersection(&self.bounds());
+        match policy {
+            OutOfBounds::Error if inside != Some(rect) => Err(self.out_of_bounds(rect.bounds())),
+            OutOfBounds::Clamp if inside.is_none() => Err(Error::EmptyImageError),
+            _ => Ok(inside),
+        }
+    }
+
+    /// Returns a copy of the given region of this image, handling parts of the region outside of
+    /// the image according to the given policy. This image is left untouched.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The region is empty, or lies entirely outside of the image when
+    ///   clamping.
+    /// * `OutOfBounds`: The region exceeds the bounds of the image with [`OutOfBounds::Error`].
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::new(16, 16, Rgb::white());
+    /// let rect = Rect::new(8, -8, 16, 16);
+    ///
+    /// let clamped = image.copy_rect(rect, OutOfBounds::Clamp)?;
+    /// assert_eq!(clamped.dimensions(), (8, 8));
+    ///
+    /// let filled = image.copy_rect(rect, OutOfBounds::Fill(Rgb::black()))?;
+    /// assert_eq!(filled.dimensions(), (16, 16));
+    /// assert_eq!(filled.pixel(0, 0), &Rgb::black());
+    /// assert_eq!(filled.pixel(0, 8), &Rgb::white());
+    ///
+    /// assert!(image.copy_rect(rect, OutOfBounds::Error).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn copy_rect(&self, rect: Rect, policy: OutOfBounds<P>) -> Result<Self> {
+        let inside = self.resolve_rect(rect, policy)?;
+        let target = match policy {
+            OutOfBounds::Fill(_) => rect,
+            // Clamping and erroring only ever copy the region inside of the image
+            _ => inside.unwrap(),
+        };
+        let len = target.width as usize * target.height as usize;
+        let mut data = match policy {
+            OutOfBounds::Fill(pixel) => vec![pixel; len],
+            // Every pixel is copied from the image, so there is nothing to fill
+            _ => Vec::with_capacity(len),
+        };
+        if let Some(inside) = inside {
+            let width = self.width() as usize;
+            let row = inside.width as usize;
+            let (dx, dy) = (
+                (inside.x - target.x) as usize,
+                (inside.y - target.y) as usize,
+            );
+
+            for y in 0..inside.height as usize {
+                let start = (inside.y as usize + y) * width + inside.x as usize;
+                let src = &self.data[start..start + row];
+                if matches!(policy, OutOfBounds::Fill(_)) {
+                    let out = (dy + y) * target.width as usize + dx;
+                    data[out..out + row].copy_from_slice(src);
+                } else {
+                    data.extend_from_slice(src);
+                }
+            }
+        }
+
+        let mut image = Self {
+            width: NonZeroU32::new(target.width).unwrap(),
+            height: NonZeroU32::new(target.height).unwrap(),
+            data,
+            format: self.format,
+            overlay: self.overlay,
+            palette: self.palette.clone(),
+            metadata: self.metadata.clone(),
+        };
+        // Paletted pixels still reference the palette of this image, so they are rebound to the
+        // palette of the copy
+        // SAFETY: the pixels are bound to the palette of the copy, which outlives them
+        if let Some(palette) = unsafe { image.detached_palette() } {
+            for pixel in &mut image.data {
+                *pixel = unsafe { rebind_pixel(Some(palette), *pixel) };
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Crops this image in place to the given region, handling parts of the region outside of
+    /// the image according to the given policy. Unlike [`Self::crop`], this never panics, and the
+    /// image is left untouched on error.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The region is empty, or lies entirely outside of the image when
+    ///   clamping.
+    /// * `OutOfBounds`: The region exceeds the bounds of the image with [`OutOfBounds::Error`].
+    ///
+    /// # See Also
+    /// * [`Self::copy_rect`] for an example.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn crop_rect(&mut self, rect: Rect, policy: OutOfBounds<P>) -> Result<()> {
+        let inside = self.resolve_rect(rect, policy)?;
+
+        match (inside, policy) {
+            // Crop in place when the result lies entirely inside of the image
+            (Some(inside), _) if inside == rect || !matches!(policy, OutOfBounds::Fill(_)) => {
+                let (x1, y1, x2, y2) = inside.bounds();
+                self.crop(x1 as u32, y1 as u32, x2 as u32, y2 as u32);
+            }
+            _ => *self = self.copy_rect(rect, policy)?,
+        }
+        Ok(())
+    }
+
+    /// Takes this image and crops it to the given region. Useful for method chaining.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The region is empty, or lies entirely outside of the image when
+    ///   clamping.
+    /// * `OutOfBounds`: The region exceeds the bounds of the image with [`OutOfBounds::Error`].
+    ///
+    /// # See Also
+    /// * [`Self::crop_rect`] for more information.
+    pub fn cropped_rect(mut self, rect: Rect, policy: OutOfBounds<P>) -> Result<Self> {
+        self.crop_rect(rect, policy)?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Paletted, PalettedRgb, Rgb, L};
+
+    #[test]
+    fn test_crop_rect() {
+        let image = Image::from_fn(4, 4, |x, y| L((y * 4 + x) as u8));
+
+        let mut cropped = image.clone();
+        cropped
+            .crop_rect(Rect::new(2, 2, 4, 4), OutOfBounds::Clamp)
+            .unwrap();
+        assert_eq!(cropped.data, [10, 11, 14, 15].map(L));
+
+        let mut filled = image.clone();
+        filled
+            .crop_rect(Rect::new(-1, 3, 3, 2), OutOfBounds::Fill(L(99)))
+            .unwrap();
+        assert_eq!(filled.data, [99, 12, 13, 99, 99, 99].map(L));
+
+        let mut untouched = image.clone();
+        assert!(untouched
+            .crop_rect(Rect::new(3, 0, 2, 2), OutOfBounds::Error)
+            .is_err());
+        assert!(untouched
+            .crop_rect(Rect::new(8, 8, 2, 2), OutOfBounds::Clamp)
+            .is_err());
+        assert_eq!(untouched.data, image.data);
+    }
+
+    #[test]
+    fn test_copy_paletted_rect() {
+        let palette = [Rgb::black(), Rgb::white(), Rgb::new(255, 0, 0)];
+        let image = Image::<PalettedRgb>::from_paletted_pixels(2, palette.to_vec(), [0, 1, 2, 0]);
+
+        let copied = image
+            .copy_rect(Rect::new(0, 1, 2, 1), OutOfBounds::Error)
+            .unwrap();
+        let clamped = image
+            .copy_rect(Rect::new(1, 0, 2, 2), OutOfBounds::Clamp)
+            .unwrap();
+        let fill = *image.pixel(0, 1);
+        let filled = image
+            .copy_rect(Rect::new(1, 1, 2, 1), OutOfBounds::Fill(fill))
+            .unwrap();
+        // The copies must resolve colors through their own palette
+        drop(image);
+
+        assert_eq!(copied.palette(), Some(&palette[..]));
+        assert_eq!(copied.pixel(0, 0).color(), Rgb::new(255, 0, 0));
+        assert_eq!(copied.pixel(1, 0).color(), Rgb::black());
+        assert_eq!(clamped.dimensions(), (1, 2));
+        assert_eq!(clamped.pixel(0, 0).color(), Rgb::white());
+        assert_eq!(filled.pixel(1, 0).color(), Rgb::new(255, 0, 0));
+    }
+}
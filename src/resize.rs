@@ -1,12 +1,13 @@
 //! An interfacing layer between `fast_image_resize` and this crate.
 
-use crate::{encodings::ColorType, Pixel};
+use crate::{color::wrap_hue, encodings::ColorType, parallel, Pixel};
 
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::num::NonZeroU32;
 use fast_image_resize::{
-    FilterType as ResizeFilterType, Image as ResizeImage, PixelType as ResizePixelType, ResizeAlg,
-    Resizer,
+    FilterType as ResizeFilterType, Image as ResizeImage, MulDiv, PixelType as ResizePixelType,
+    ResizeAlg, Resizer,
 };
-use std::num::NonZeroU32;
 
 /// A filtering algorithm that is used to resize an image.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -76,32 +77,75 @@ impl FilterType {
         dst_width: NonZeroU32,
         dst_height: NonZeroU32,
     ) -> Vec<P> {
+        if P::BIT_DEPTH == 32 {
+            return resize_f32(
+                ResizeAlg::from(*self),
+                data,
+                src_width,
+                src_height,
+                dst_width,
+                dst_height,
+            );
+        }
+
         let color_type = data[0].color_type();
+        // `fast_image_resize` only has pixel types with one to four channels
+        if matches!(color_type, ColorType::Custom(channels) if !(1..=4).contains(&channels)) {
+            return resize_channels(
+                ResizeAlg::from(*self),
+                data,
+                src_width,
+                src_height,
+                dst_width,
+                dst_height,
+            );
+        }
+
         let pixel_type = match P::BIT_DEPTH {
             1 | 2 | 4 | 8 => match color_type {
-                ColorType::L | ColorType::PaletteRgb | ColorType::PaletteRgba => {
-                    ResizePixelType::U8
-                }
-                ColorType::LA => ResizePixelType::U8x2,
-                ColorType::Rgb => ResizePixelType::U8x3,
-                ColorType::Rgba => ResizePixelType::U8x4,
-                ColorType::Dynamic => unreachable!(),
+                ColorType::L
+                | ColorType::PaletteRgb
+                | ColorType::PaletteRgba
+                | ColorType::Custom(1) => ResizePixelType::U8,
+                ColorType::LA | ColorType::Custom(2) => ResizePixelType::U8x2,
+                ColorType::Rgb | ColorType::Custom(3) => ResizePixelType::U8x3,
+                ColorType::Rgba | ColorType::Cmyk | ColorType::Custom(4) => ResizePixelType::U8x4,
+                ColorType::Custom(_) | ColorType::Dynamic => unreachable!(),
             },
             16 => match color_type {
-                ColorType::L | ColorType::PaletteRgb | ColorType::PaletteRgba => {
-                    ResizePixelType::U16
-                }
-                ColorType::LA => ResizePixelType::U16x2,
-                ColorType::Rgb => ResizePixelType::U16x3,
-                ColorType::Rgba => ResizePixelType::U16x4,
-                ColorType::Dynamic => unreachable!(),
+                ColorType::L
+                | ColorType::PaletteRgb
+                | ColorType::PaletteRgba
+                | ColorType::Custom(1) => ResizePixelType::U16,
+                ColorType::LA | ColorType::Custom(2) => ResizePixelType::U16x2,
+                ColorType::Rgb | ColorType::Custom(3) => ResizePixelType::U16x3,
+                ColorType::Rgba | ColorType::Cmyk | ColorType::Custom(4) => ResizePixelType::U16x4,
+                ColorType::Custom(_) | ColorType::Dynamic => unreachable!(),
             },
             _ => panic!("Unsupported bit depth"),
         };
 
-        let buffer = data.iter().flat_map(P::as_bytes).collect::<Vec<_>>();
+        let mut buffer = data.iter().flat_map(P::as_bytes).collect::<Vec<_>>();
+        // 16-bit pixels are stored as big-endian bytes, but `fast_image_resize` reads 16-bit
+        // pixel types in native byte order
+        if P::BIT_DEPTH == 16 {
+            for pair in buffer.chunks_exact_mut(2) {
+                let value = u16::from_be_bytes([pair[0], pair[1]]);
+                pair.copy_from_slice(&value.to_ne_bytes());
+            }
+        }
         // We are able to unwrap here since we validated the buffer throughout the creation of the image.
-        let image = ResizeImage::from_vec_u8(src_width, src_height, buffer, pixel_type).unwrap();
+        let mut image =
+            ResizeImage::from_vec_u8(src_width, src_height, buffer, pixel_type).unwrap();
+
+        // Resample in premultiplied alpha, otherwise the color of transparent pixels bleeds into
+        // their neighbors and causes dark fringes around edges. This is a no-op for pixel types
+        // without an alpha channel.
+        let mul_div = MulDiv::default();
+        let premultiplied = color_type.has_alpha()
+            && mul_div
+                .multiply_alpha_inplace(&mut image.view_mut())
+                .is_ok();
         let view = image.view();
 
         let mut dest = ResizeImage::new(dst_width, dst_height, pixel_type);
@@ -111,12 +155,175 @@ impl FilterType {
         // The pixel type is the same, we can unwrap here
         resizer.resize(&view, &mut dst_view).unwrap();
 
+        if premultiplied {
+            // The pixel type was already validated by `multiply_alpha_inplace`
+            mul_div.divide_alpha_inplace(&mut dst_view).unwrap();
+        }
+
+        let mut buffer = dest.into_vec();
+        if P::BIT_DEPTH == 16 {
+            for pair in buffer.chunks_exact_mut(2) {
+                let value = u16::from_ne_bytes([pair[0], pair[1]]);
+                pair.copy_from_slice(&value.to_be_bytes());
+            }
+        }
+
         let bpp = color_type.channels() * ((P::BIT_DEPTH as usize + 7) >> 3);
+        buffer.chunks_exact(bpp).map(P::from_bytes).collect()
+    }
+}
+
+/// Resizes 8-bit and 16-bit pixels with an unsupported number of channels by resampling each of
+/// their channels as a separate `U8` or `U16` image.
+fn resize_channels<P: Pixel>(
+    algorithm: ResizeAlg,
+    data: &[P],
+    src_width: NonZeroU32,
+    src_height: NonZeroU32,
+    dst_width: NonZeroU32,
+    dst_height: NonZeroU32,
+) -> Vec<P> {
+    let channels = data[0].color_type().channels();
+    let (size, pixel_type) = if P::BIT_DEPTH == 16 {
+        (2, ResizePixelType::U16)
+    } else {
+        (1, ResizePixelType::U8)
+    };
+
+    // 16-bit channels are big-endian, but `fast_image_resize` reads them in native byte order
+    let mut planes = vec![Vec::with_capacity(data.len() * size); channels];
+    for pixel in data {
+        let bytes = pixel.as_bytes();
+        for (plane, component) in planes.iter_mut().zip(bytes.as_ref().chunks_exact(size)) {
+            match *component {
+                [hi, lo] => plane.extend(u16::from_be_bytes([hi, lo]).to_ne_bytes()),
+                _ => plane.extend_from_slice(component),
+            }
+        }
+    }
+
+    // Each channel is resized independently, so they can be resized in parallel
+    let planes = parallel::map_vec(planes, |plane| {
+        // We are able to unwrap here since the buffer has exactly one channel per source pixel
+        let image = ResizeImage::from_vec_u8(src_width, src_height, plane, pixel_type).unwrap();
+        let mut dest = ResizeImage::new(dst_width, dst_height, pixel_type);
+        // The pixel type is the same, we can unwrap here
+        Resizer::new(algorithm)
+            .resize(&image.view(), &mut dest.view_mut())
+            .unwrap();
+
         dest.into_vec()
-            .chunks_exact(bpp)
-            .map(P::from_bytes)
-            .collect()
+    });
+
+    let mut bytes = Vec::with_capacity(channels * size);
+    (0..dst_width.get() as usize * dst_height.get() as usize)
+        .map(|i| {
+            bytes.clear();
+            for plane in &planes {
+                match plane[i * size..(i + 1) * size] {
+                    [a, b] => bytes.extend(u16::from_ne_bytes([a, b]).to_be_bytes()),
+                    ref component => bytes.extend_from_slice(component),
+                }
+            }
+
+            P::from_bytes(&bytes)
+        })
+        .collect()
+}
+
+/// Resizes float pixels by resampling each of their channels as a separate `F32` image, since
+/// `fast_image_resize` has no multi-channel float pixel types. Hue channels are resampled as the
+/// cosine and sine of their angle, so that they are averaged around the color wheel.
+fn resize_f32<P: Pixel>(
+    algorithm: ResizeAlg,
+    data: &[P],
+    src_width: NonZeroU32,
+    src_height: NonZeroU32,
+    dst_width: NonZeroU32,
+    dst_height: NonZeroU32,
+) -> Vec<P> {
+    let color_type = data[0].color_type();
+    let channels = color_type.channels();
+    let has_alpha = color_type.has_alpha();
+
+    let component = |bytes: &[u8], i: usize| {
+        f32::from_ne_bytes([
+            bytes[i * 4],
+            bytes[i * 4 + 1],
+            bytes[i * 4 + 2],
+            bytes[i * 4 + 3],
+        ])
+    };
+
+    // Just like 8-bit pixels, resample in premultiplied alpha to avoid dark fringes. The sine of
+    // the hue is kept in an extra plane after the other channels.
+    let hue = P::HUE_CHANNEL;
+    let mut planes =
+        vec![Vec::with_capacity(data.len() * 4); channels + usize::from(hue.is_some())];
+    for pixel in data {
+        let bytes = pixel.as_bytes();
+        let bytes = bytes.as_ref();
+        let alpha = if has_alpha {
+            component(bytes, channels - 1)
+        } else {
+            1.
+        };
+
+        for (i, plane) in planes[..channels].iter_mut().enumerate() {
+            let mut value = component(bytes, i);
+            if Some(i) == hue {
+                value = value.to_radians().cos();
+            }
+            if has_alpha && i != channels - 1 {
+                value *= alpha;
+            }
+            plane.extend(value.to_ne_bytes());
+        }
+        if let Some(i) = hue {
+            let value = component(bytes, i).to_radians().sin() * alpha;
+            planes[channels].extend(value.to_ne_bytes());
+        }
     }
+
+    // Each channel is resized independently, so they can be resized in parallel
+    let planes = parallel::map_vec(planes, |plane| {
+        // We are able to unwrap here since the buffer has exactly one f32 per source pixel
+        let image =
+            ResizeImage::from_vec_u8(src_width, src_height, plane, ResizePixelType::F32).unwrap();
+        let mut dest = ResizeImage::new(dst_width, dst_height, ResizePixelType::F32);
+        // The pixel type is the same, we can unwrap here
+        Resizer::new(algorithm)
+            .resize(&image.view(), &mut dest.view_mut())
+            .unwrap();
+
+        dest.into_vec()
+    });
+
+    let mut bytes = Vec::with_capacity(channels * 4);
+    (0..dst_width.get() as usize * dst_height.get() as usize)
+        .map(|i| {
+            bytes.clear();
+            let alpha = if has_alpha {
+                component(&planes[channels - 1], i)
+            } else {
+                1.
+            };
+
+            for (c, plane) in planes[..channels].iter().enumerate() {
+                let mut value = component(plane, i);
+                if Some(c) == hue {
+                    // The angle is unaffected by premultiplied alpha
+                    let sin = component(&planes[channels], i);
+                    value = wrap_hue(sin.atan2(value).to_degrees());
+                } else if has_alpha && c != channels - 1 {
+                    value = if alpha > 0. { value / alpha } else { 0. };
+                }
+                bytes.extend(value.to_ne_bytes());
+            }
+
+            P::from_bytes(&bytes)
+        })
+        .collect()
 }
 
 fn resize_tiled<P: Pixel>(
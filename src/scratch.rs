@@ -0,0 +1,160 @@
+//! Pools of reusable pixel buffers, so that operations which need temporary full-size buffers do
+//! not allocate and free them every time they are applied.
+//!
+//! Drawing an object with [`Image::draw_with_scratch`] lets it borrow its temporary buffers from
+//! a [`Scratch`] instead of allocating them, and returns them to the scratch once it is done.
+//! Currently, [`Dilation`], [`Erosion`], [`Morphology`] and [`Stroke`] borrow their buffers this
+//! way. Whole images can also be created from and returned to a scratch with
+//! [`Scratch::take_image`] and [`Scratch::recycle_image`].
+//!
+//! [`Dilation`]: crate::morph::Dilation
+//! [`Erosion`]: crate::morph::Erosion
+//! [`Morphology`]: crate::morph::Morphology
+//! [`Stroke`]: crate::morph::Stroke
+//!
+//! # Example
+//! ```no_run
+//! # use ril::prelude::*;
+//! use ril::morph::Stroke;
+//!
+//! # fn main() -> ril::Result<()> {
+//! let mut scratch = Scratch::new();
+//!
+//! for i in 0..1000 {
+//!     let mut image = Image::<Rgba>::open(format!("sprites/{i}.png"))?;
+//!     let stroke = Stroke::new(&image, 3, Rgba::black());
+//!
+//!     image.draw_with_scratch(&stroke, &mut scratch);
+//!     image.save_inferred(format!("out/{i}.png"))?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Image, ImageFormat, ImageMetadata, OverlayMode, Pixel};
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+/// A pool of reusable pixel buffers. See the [module-level documentation](crate::scratch) for
+/// more information.
+///
+/// A scratch keeps every buffer returned to it until it is cleared or dropped, so its memory usage
+/// is bounded by the largest amount of buffers an operation borrows at once.
+#[derive(Clone, Debug, Default)]
+pub struct Scratch<P: Pixel> {
+    buffers: Vec<Vec<P>>,
+}
+
+impl<P: Pixel> Scratch<P> {
+    /// Creates a new, empty scratch.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Returns the amount of buffers currently held by this scratch.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns whether this scratch holds no buffers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Returns the total amount of pixels the buffers held by this scratch can store.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.buffers.iter().map(Vec::capacity).sum()
+    }
+
+    /// Frees every buffer held by this scratch.
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+    }
+
+    /// Takes a buffer of `len` pixels set to `fill` from this scratch. The smallest held buffer
+    /// which is large enough is reused, and a new buffer is only allocated if none are held.
+    ///
+    /// Return the buffer with [`Self::recycle`] once it is no longer needed.
+    #[must_use]
+    pub fn take(&mut self, len: usize, fill: P) -> Vec<P> {
+        // Prefer the smallest buffer that fits, otherwise grow the largest one
+        let index = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.capacity() >= len)
+            .min_by_key(|(_, buffer)| buffer.capacity())
+            .or_else(|| {
+                self.buffers
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, buffer)| buffer.capacity())
+            })
+            .map(|(i, _)| i);
+
+        let mut buffer = index.map_or_else(Vec::new, |i| self.buffers.swap_remove(i));
+        buffer.clear();
+        buffer.resize(len, fill);
+        buffer
+    }
+
+    /// Returns the given buffer to this scratch so that it can be reused.
+    pub fn recycle(&mut self, buffer: Vec<P>) {
+        if buffer.capacity() > 0 {
+            self.buffers.push(buffer);
+        }
+    }
+
+    /// Creates a new image with the given width and height and with all pixels set to `fill`,
+    /// reusing a buffer from this scratch. This is otherwise equivalent to [`Image::new`].
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    #[must_use]
+    pub fn take_image(&mut self, width: u32, height: u32, fill: P) -> Image<P> {
+        let (width, height) = match (NonZeroU32::new(width), NonZeroU32::new(height)) {
+            (Some(width), Some(height)) => (width, height),
+            _ => panic!("width and height must be non-zero"),
+        };
+
+        Image {
+            width,
+            height,
+            data: self.take(width.get() as usize * height.get() as usize, fill),
+            format: ImageFormat::default(),
+            overlay: OverlayMode::default(),
+            palette: None,
+            metadata: ImageMetadata::default(),
+        }
+    }
+
+    /// Returns the pixel buffer of the given image to this scratch so that it can be reused.
+    pub fn recycle_image(&mut self, image: Image<P>) {
+        self.recycle(image.data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::L;
+
+    #[test]
+    fn test_scratch_reuse() {
+        let mut scratch = Scratch::new();
+        let buffer = scratch.take(64, L(0));
+        let ptr = buffer.as_ptr();
+        scratch.recycle(buffer);
+
+        let buffer = scratch.take(32, L(1));
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(buffer, [L(1); 32]);
+        assert!(scratch.is_empty());
+    }
+}
@@ -1,12 +1,33 @@
 //! Implements the animated image and image sequence interface.
 
-use crate::{Error, FrameIterator, Image, ImageFormat, Pixel, Result};
+#[cfg(feature = "resize")]
+use crate::ResizeAlgorithm;
+use crate::{
+    image::rebind_pixel, Draw, Error, FrameIterator, Image, ImageFormat, OverlayMode, Paste, Pixel,
+    Rectangle, Result, TrueColor,
+};
 use std::{
-    fs::File,
-    io::{Read, Write},
-    path::Path,
+    borrow::Cow,
+    io::{Cursor, Read, Write},
+    ops::{Bound, RangeBounds},
     time::Duration,
 };
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+use std::{
+    cmp::Ordering,
+    fs::File,
+    io::Seek,
+    path::{Path, PathBuf},
+};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+
+/// The delay of frames loaded with [`ImageSequence::from_paths`] and
+/// [`ImageSequence::from_dir_sorted`], which is 40 milliseconds, or 25 frames per second.
+pub const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(40);
 
 /// The method used to dispose a frame before transitioning to the next frame in an image sequence.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -25,9 +46,27 @@ impl Default for DisposalMethod {
     }
 }
 
+/// The method used to composite a frame onto the frames before it in an image sequence.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlendMethod {
+    /// The frame completely replaces the area it covers, including its transparent pixels.
+    #[default]
+    Source,
+    /// The frame is alpha-composited over the area it covers, so transparent pixels leave the
+    /// previous frame visible.
+    Over,
+}
+
 /// Represents a frame in an image sequence. It encloses an [`Image`] and extra metadata
 /// about the frame.
 ///
+/// # Partial frames
+/// Decoded animated images may only store the area that changed since the previous frame. Such
+/// frames cover part of the canvas starting at their [`offset`][Self::offset], and are combined
+/// with the frames before them according to their [`disposal`][Self::disposal] and
+/// [`blend`][Self::blend] methods. Use [`ImageSequence::coalesced`] to render every frame in full
+/// before processing frames independently.
+///
 /// # Support for paletted images
 /// Frames representing paletted images are currently unsupported. See documentation of
 /// [`ImageSequence`] for more information.
@@ -39,6 +78,8 @@ pub struct Frame<P: Pixel> {
     inner: Image<P>,
     delay: Duration,
     disposal: DisposalMethod,
+    offset: (u32, u32),
+    blend: BlendMethod,
 }
 
 impl<P: Pixel> Frame<P> {
@@ -49,6 +90,8 @@ impl<P: Pixel> Frame<P> {
             inner: image,
             delay: Duration::default(),
             disposal: DisposalMethod::default(),
+            offset: (0, 0),
+            blend: BlendMethod::default(),
         }
     }
 
@@ -76,6 +119,30 @@ impl<P: Pixel> Frame<P> {
         self
     }
 
+    /// Sets the position of the top-left corner of this frame on the canvas in place.
+    pub fn set_offset(&mut self, x: u32, y: u32) {
+        self.offset = (x, y);
+    }
+
+    /// Takes this frame and sets the position of its top-left corner on the canvas.
+    #[must_use]
+    pub const fn with_offset(mut self, x: u32, y: u32) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// Sets the method used to composite this frame onto the frames before it in place.
+    pub fn set_blend(&mut self, blend: BlendMethod) {
+        self.blend = blend;
+    }
+
+    /// Takes this frame and sets the method used to composite it onto the frames before it.
+    #[must_use]
+    pub const fn with_blend(mut self, blend: BlendMethod) -> Self {
+        self.blend = blend;
+        self
+    }
+
     /// Returns a reference to the image this frame contains.
     #[must_use]
     pub const fn image(&self) -> &Image<P> {
@@ -89,6 +156,8 @@ impl<P: Pixel> Frame<P> {
             inner: f(self.inner),
             delay: self.delay,
             disposal: self.disposal,
+            offset: self.offset,
+            blend: self.blend,
         }
     }
 
@@ -115,6 +184,18 @@ impl<P: Pixel> Frame<P> {
     pub const fn disposal(&self) -> DisposalMethod {
         self.disposal
     }
+
+    /// Returns the position of the top-left corner of this frame on the canvas.
+    #[must_use]
+    pub const fn offset(&self) -> (u32, u32) {
+        self.offset
+    }
+
+    /// Returns the method used to composite this frame onto the frames before it.
+    #[must_use]
+    pub const fn blend(&self) -> BlendMethod {
+        self.blend
+    }
 }
 
 impl<P: Pixel> From<Image<P>> for Frame<P> {
@@ -170,6 +251,103 @@ impl LoopCount {
     }
 }
 
+/// Determines how frames of different sizes are reconciled onto a common canvas when
+/// concatenating image sequences with [`ImageSequence::concat`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FitMode<P: Pixel> {
+    /// Centers every frame on a canvas as large as the largest width and height of all frames,
+    /// filling the remaining space with the given pixel. Frames are never scaled.
+    Pad(P),
+    /// Crops the center of every frame to a canvas as small as the smallest width and height of
+    /// all frames. Frames are never scaled.
+    Crop,
+    /// Stretches every frame to the dimensions of the first frame of the sequence being appended
+    /// to using the given resizing algorithm, ignoring aspect ratio.
+    #[cfg(feature = "resize")]
+    Stretch(ResizeAlgorithm),
+    /// Scales every frame to fit within the dimensions of the first frame of the sequence being
+    /// appended to using the given resizing algorithm, preserving aspect ratio, and centers it,
+    /// filling the remaining space with the given pixel.
+    #[cfg(feature = "resize")]
+    Letterbox(P, ResizeAlgorithm),
+}
+
+impl<P: Pixel> FitMode<P> {
+    /// Fits the given image onto a canvas with the given dimensions.
+    fn fit(self, image: &mut Image<P>, (width, height): (u32, u32)) {
+        if image.dimensions() == (width, height) {
+            return;
+        }
+
+        match self {
+            Self::Pad(fill) => {
+                let (w, h) = image.dimensions();
+                let (left, top) = ((width - w) / 2, (height - h) / 2);
+                image.pad(fill, top, width - w - left, height - h - top, left);
+            }
+            Self::Crop => {
+                let (x, y) = ((image.width() - width) / 2, (image.height() - height) / 2);
+                image.crop(x, y, x + width, y + height);
+            }
+            #[cfg(feature = "resize")]
+            Self::Stretch(algorithm) => image.resize(width, height, algorithm),
+            #[cfg(feature = "resize")]
+            Self::Letterbox(fill, algorithm) => {
+                let (w, h) = (f64::from(image.width()), f64::from(image.height()));
+                let scale = (f64::from(width) / w).min(f64::from(height) / h);
+                let w = ((w * scale).round() as u32).clamp(1, width);
+                let h = ((h * scale).round() as u32).clamp(1, height);
+
+                image.resize(w, h, algorithm);
+                Self::Pad(fill).fit(image, (width, height));
+            }
+        }
+    }
+}
+
+/// A static image or an animated image sequence that can be composited onto every frame of an
+/// image sequence with [`ImageSequence::overlay`].
+pub trait OverlaySource<P: Pixel> {
+    /// Returns every frame of the overlay along with how long it is shown for. Static overlays
+    /// return a single frame with no delay. Every frame must be fully rendered, so partial frames
+    /// are returned coalesced.
+    fn overlay_frames(&self) -> Vec<(Cow<'_, Image<P>>, Duration)>;
+}
+
+impl<P: Pixel> OverlaySource<P> for Image<P> {
+    fn overlay_frames(&self) -> Vec<(Cow<'_, Image<P>>, Duration)> {
+        vec![(Cow::Borrowed(self), Duration::ZERO)]
+    }
+}
+
+impl<P: Pixel> OverlaySource<P> for Frame<P> {
+    fn overlay_frames(&self) -> Vec<(Cow<'_, Image<P>>, Duration)> {
+        vec![(Cow::Borrowed(self.image()), Duration::ZERO)]
+    }
+}
+
+impl<P: Pixel> OverlaySource<P> for ImageSequence<P> {
+    fn overlay_frames(&self) -> Vec<(Cow<'_, Image<P>>, Duration)> {
+        if self.has_partial_frames() {
+            return self
+                .clone()
+                .coalesced()
+                .into_frames()
+                .into_iter()
+                .map(|frame| {
+                    let delay = frame.delay();
+                    (Cow::Owned(frame.into_image()), delay)
+                })
+                .collect();
+        }
+
+        self.frames
+            .iter()
+            .map(|frame| (Cow::Borrowed(frame.image()), frame.delay()))
+            .collect()
+    }
+}
+
 /// Represents a sequence of image frames such as an animated image.
 ///
 /// # See Also
@@ -247,41 +425,50 @@ impl<P: Pixel> ImageSequence<P> {
     /// If the image sequence is a single-frame static image or if the encoding format does not
     /// support animated images, this will just return an image sequence containing one frame.
     ///
-    /// # Note
-    /// The bound on `bytes` includes `Write` due to a Rust limitation. This will be looked into
-    /// in the future to not require `Write`.
-    ///
-    /// If you are limited by this trait bound, you can either specify the image format manually
-    /// using [`from_reader`], or you can try using [`ImageFormat::infer_encoding`] along with
-    /// [`from_reader`] manually instead. If you are able to use [`from_bytes`] instead, which takes
-    /// a byte slice instead of a `Read` stream, you can either that or [`from_bytes_inferred`],
-    /// too, which does not require a `Write` bound either.
-    ///
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     /// specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
-    pub fn from_reader_inferred<'a, R: Read + Write + 'a>(
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    pub fn from_reader_inferred<'a, R: Read + 'a>(
         mut bytes: R,
     ) -> Result<Box<dyn FrameIterator<P> + 'a>>
     where
         P: 'a,
     {
-        let mut buffer = Vec::new();
-        bytes.read_to_end(&mut buffer)?;
-
-        match ImageFormat::infer_encoding(&buffer) {
-            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat),
-            format => {
-                bytes.write_all(&buffer)?;
-                format.run_sequence_decoder(bytes)
+        match ImageFormat::infer_from_reader(&mut bytes)? {
+            (ImageFormat::Unknown, _) => Err(Error::UnknownEncodingFormat),
+            (format, signature) => {
+                format.run_sequence_decoder(Read::chain(Cursor::new(signature), bytes))
             }
         }
     }
 
+    /// Decodes the image sequence with the explicitly given image encoding from the given
+    /// asynchronous read stream of bytes.
+    ///
+    /// The stream is read to its end without blocking the executor, after which frames are decoded
+    /// lazily as an iterator, just like in [`Self::from_reader`]. Note that frames are decoded on
+    /// the thread which iterates over them, which blocks if that is an asynchronous task.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
+    /// * `IoError`: The stream could not be read.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader<'a>(
+        format: ImageFormat,
+        mut bytes: impl AsyncRead + Unpin,
+    ) -> Result<Box<dyn FrameIterator<P> + 'a>>
+    where
+        P: 'a,
+    {
+        let mut buffer = Vec::new();
+        bytes.read_to_end(&mut buffer).await?;
+
+        format.run_sequence_decoder(Cursor::new(buffer))
+    }
+
     /// Decodes an image sequence with the explicitly given image encoding from the byte slice.
     /// Could be useful in conjunction with the `include_bytes!` macro.
     ///
@@ -299,9 +486,7 @@ impl<P: Pixel> ImageSequence<P> {
     ///
     /// # Errors
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
     pub fn from_bytes<'a>(
         format: ImageFormat,
         bytes: &'a [u8],
@@ -332,9 +517,7 @@ impl<P: Pixel> ImageSequence<P> {
     /// * `DecodingError`: The image could not be decoded, maybe it is corrupt.
     /// * `UnknownEncodingFormat`: Could not infer the encoding from the image. Try explicitly
     /// specifying it.
-    ///
-    /// # Panics
-    /// * No decoder implementation for the given encoding format.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
     pub fn from_bytes_inferred<'a>(bytes: &'a [u8]) -> Result<Box<dyn FrameIterator<P> + 'a>>
     where
         P: 'a,
@@ -347,27 +530,142 @@ impl<P: Pixel> ImageSequence<P> {
 
     /// Opens a file from the given path and decodes it, returning an iterator over its frames.
     ///
-    /// The encoding of the image is automatically inferred. You can explicitly pass in an encoding
-    /// by using the [`from_reader`] method.
-    ///
-    /// # Note
-    /// Unlike the inference of [`Image::open`] this does **not** infer from raw bytes if inferring
-    /// from file extension fails; instead it immediately returns the error.
+    /// The encoding of the image is automatically inferred from the extension of the path, or from
+    /// the contents of the file if the path has no known extension. You can explicitly pass in an
+    /// encoding by using the [`Self::open_with_format`] method.
     ///
     /// # Errors
-    /// todo!()
+    /// * `IoError`: The file could not be opened.
+    /// * `UnknownEncodingFormat`: Could not infer the encoding from the path or the file.
+    /// * `DecodingError`: The header of the image could not be decoded.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// Each of these is wrapped in a `PathError` with the path of the file.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
     pub fn open<'a>(path: impl AsRef<Path> + 'a) -> Result<Box<dyn FrameIterator<P> + 'a>>
     where
         P: 'a,
     {
-        let file = File::open(path.as_ref())?;
+        let path = path.as_ref();
+        let open = || {
+            let mut file = File::open(path)?;
+
+            let format = match ImageFormat::from_path(path).unwrap_or_default() {
+                ImageFormat::Unknown => {
+                    let (format, _) = ImageFormat::infer_from_reader(&mut file)?;
+                    file.rewind()?;
+                    format
+                }
+                format => format,
+            };
+            if format == ImageFormat::Unknown {
+                return Err(Error::UnknownEncodingFormat);
+            }
 
-        let format = match ImageFormat::from_path(path)? {
-            ImageFormat::Unknown => return Err(Error::UnknownEncodingFormat),
-            format => format,
+            format.sequence_decoder(file)
         };
 
-        format.run_sequence_decoder(file)
+        match open() {
+            Ok(frames) => Ok(Box::new(frames.with_path(path))),
+            Err(err) => Err(err.with_path(path)),
+        }
+    }
+
+    /// Opens a file from the given path and lazily decodes it into an image sequence with the
+    /// explicitly given encoding, ignoring the extension of the path.
+    ///
+    /// # Errors
+    /// * An error occured while opening the file or decoding its header.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// # See Also
+    /// * [`Image::open_with_format`] for more information.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn open_with_format<'a>(
+        path: impl AsRef<Path> + 'a,
+        format: ImageFormat,
+    ) -> Result<Box<dyn FrameIterator<P> + 'a>>
+    where
+        P: 'a,
+    {
+        let path = path.as_ref();
+        let frames = File::open(path)
+            .map_err(Error::IoError)
+            .and_then(|file| format.sequence_decoder(file))
+            .map_err(|err| err.with_path(path))?;
+
+        Ok(Box::new(frames.with_path(path)))
+    }
+
+    /// Loads every image at the given paths as a frame of a new image sequence, in the given order.
+    /// Every frame has a delay of [`DEFAULT_FRAME_DELAY`].
+    ///
+    /// # Errors
+    /// * An image could not be opened or decoded. See [`Image::open`] for more information.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn from_paths(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> Result<Self> {
+        paths
+            .into_iter()
+            .map(|path| {
+                Image::open(path)
+                    .map(|image| Frame::from_image(image).with_delay(DEFAULT_FRAME_DELAY))
+            })
+            .collect()
+    }
+
+    /// Loads every image file in the given directory as a frame of a new image sequence, sorted by
+    /// filename. Numbers in filenames are compared by their value, so `frame_2.png` comes before
+    /// `frame_10.png` even without zero-padding. Files with unknown extensions are skipped.
+    ///
+    /// Every frame has a delay of [`DEFAULT_FRAME_DELAY`]. This is the reverse of
+    /// [`Self::save_frames`].
+    ///
+    /// # Errors
+    /// * The directory could not be read.
+    /// * An image could not be opened or decoded. See [`Image::open`] for more information.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let sequence = ImageSequence::<Rgba>::from_dir_sorted("render/frames")?;
+    /// sequence.save_inferred("render.gif")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn from_dir_sorted(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut paths = std::fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<std::io::Result<Vec<_>>>()
+            })
+            .map_err(|err| Error::IoError(err).with_path(path))?;
+
+        paths.retain(|path| {
+            path.is_file()
+                && !matches!(
+                    ImageFormat::from_path(path),
+                    Ok(ImageFormat::Unknown) | Err(_)
+                )
+        });
+        paths.sort_by(|a, b| natural_cmp(&file_name(a), &file_name(b)));
+
+        Self::from_paths(paths)
     }
 
     /// Encodes this image sequence with the given encoding and writes it to the given write buffer.
@@ -381,6 +679,38 @@ impl<P: Pixel> ImageSequence<P> {
         encoding.run_sequence_encoder(self, dest)
     }
 
+    /// Encodes this image sequence with the given encoding and writes it to the given
+    /// asynchronous write stream.
+    ///
+    /// The sequence is cloned and encoded on a blocking thread of the Tokio runtime, while the
+    /// encoded bytes are written to the stream without blocking the executor.
+    ///
+    /// # Errors
+    /// * An error occured during encoding.
+    /// * `IoError`: The stream could not be written to.
+    ///
+    /// # Panics
+    /// * No encoder implementation for the given encoding format.
+    /// * Not called within a Tokio runtime.
+    ///
+    /// # See Also
+    /// * [`AsyncEncoder`](crate::encode::AsyncEncoder) to encode without cloning the sequence.
+    #[cfg(feature = "tokio")]
+    pub async fn encode_async(
+        &self,
+        encoding: ImageFormat,
+        dest: &mut (impl AsyncWrite + Unpin),
+    ) -> Result<()>
+    where
+        P: Send + 'static,
+    {
+        let sequence = self.clone();
+        crate::encode::encode_blocking(dest, move |mut writer| {
+            sequence.encode(encoding, &mut writer)
+        })
+        .await
+    }
+
     /// Saves the image sequence with the given encoding to the given path.
     /// You can try saving to a memory buffer by using the [`encode`] method.
     ///
@@ -389,9 +719,16 @@ impl<P: Pixel> ImageSequence<P> {
     ///
     /// # Panics
     /// * No encoder implementation for the given encoding format.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
     pub fn save(&self, encoding: ImageFormat, path: impl AsRef<Path>) -> Result<()> {
-        let mut file = File::create(path).map_err(Error::IoError)?;
-        self.encode(encoding, &mut file)
+        let path = path.as_ref();
+        File::create(path)
+            .map_err(Error::IoError)
+            .and_then(|mut file| self.encode(encoding, &mut file))
+            .map_err(|err| err.with_path(path))
     }
 
     /// Saves the image sequence to the given path, inferring the encoding from the given
@@ -409,15 +746,67 @@ impl<P: Pixel> ImageSequence<P> {
     ///
     /// # Panics
     /// * No encoder implementation for the given encoding format.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
     pub fn save_inferred(&self, path: impl AsRef<Path>) -> Result<()> {
-        let encoding = ImageFormat::from_path(path.as_ref())?;
+        let path = path.as_ref();
+        let encoding = ImageFormat::from_path(path).map_err(|err| err.with_path(path))?;
 
         match encoding {
-            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat),
+            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat.with_path(path)),
             _ => self.save(encoding, path),
         }
     }
 
+    /// Saves every frame of this image sequence as a separate image, returning the paths that were
+    /// written in order. This is useful for handing animations off to tools such as ffmpeg.
+    ///
+    /// The path of each frame is created by replacing the `{}` placeholder in the given pattern
+    /// with the index of the frame, starting at zero. The index can be zero-padded to a width with
+    /// `{:0N}`, such as `{:04}` for `0000`, `0001`, and so on. The encoding of each frame is
+    /// inferred from the extension of its path.
+    ///
+    /// Frames loaded back with [`Self::from_dir_sorted`] are in the same order.
+    ///
+    /// # Errors
+    /// * Could not infer encoding format.
+    /// * An error occured during encoding.
+    ///
+    /// # Panics
+    /// * The pattern does not contain a `{}` or `{:0N}` placeholder.
+    /// * No encoder implementation for the inferred encoding format.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let sequence = ImageSequence::<Rgba>::open("sample.gif")?
+    ///     .collect::<ril::Result<ImageSequence<_>>>()?;
+    /// let paths = sequence.save_frames("out/frame_{:04}.png")?;
+    /// assert_eq!(paths[1].to_str(), Some("out/frame_0001.png"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn save_frames(&self, pattern: &str) -> Result<Vec<PathBuf>> {
+        self.frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let path = PathBuf::from(format_frame_path(pattern, i));
+                frame
+                    .save_inferred(&path)
+                    .map_err(|err| err.with_frame(i))?;
+                Ok(path)
+            })
+            .collect()
+    }
+
     /// Creates a new image sequence from the given frames.
     #[must_use]
     pub fn from_frames(frames: Vec<Frame<P>>) -> Self {
@@ -448,6 +837,114 @@ impl<P: Pixel> ImageSequence<P> {
         self.frames.extend(frames);
     }
 
+    /// Returns the delay new frames inserted at the given index should inherit.
+    fn neighboring_delay(&self, index: usize) -> Duration {
+        self.frames
+            .get(index)
+            .or_else(|| self.frames.last())
+            .map_or(Duration::ZERO, Frame::delay)
+    }
+
+    /// Inserts a frame at the given index in this image sequence, shifting all frames after it.
+    ///
+    /// If the frame has no delay, which is the default for [`Frame::from_image`], it inherits the
+    /// delay of the frame it is inserted before, or of the last frame if it is inserted at the
+    /// end, so that it matches the timing of the frames around it.
+    ///
+    /// # Panics
+    /// * `index` is greater than the amount of frames.
+    pub fn insert_frame(&mut self, index: usize, mut frame: Frame<P>) {
+        if frame.delay().is_zero() {
+            frame.set_delay(self.neighboring_delay(index));
+        }
+        self.frames.insert(index, frame);
+    }
+
+    /// Returns the index of the first frame in the given range.
+    fn range_start(range: &impl RangeBounds<usize>) -> usize {
+        match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        }
+    }
+
+    /// Removes the frames in the given range from this image sequence and returns them.
+    ///
+    /// The delays of the removed frames are added to the frame before the range, or to the frame
+    /// after it if the range starts at the first frame, so that the duration of the sequence stays
+    /// the same. The returned frames keep their own delays.
+    ///
+    /// # Panics
+    /// * The range is out of bounds or its start is greater than its end.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # use std::time::Duration;
+    /// let mut sequence = (0..4)
+    ///     .map(|i| Frame::from_image(Image::new(1, 1, L(i))))
+    ///     .collect::<ImageSequence<_>>()
+    ///     .with_fps(10.0);
+    ///
+    /// let removed = sequence.remove_range(1..3);
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(sequence.duration(), Duration::from_millis(400));
+    /// assert_eq!(sequence.iter().next().unwrap().delay(), Duration::from_millis(300));
+    /// ```
+    pub fn remove_range(&mut self, range: impl RangeBounds<usize>) -> Vec<Frame<P>> {
+        let start = Self::range_start(&range);
+        let removed = self.frames.drain(range).collect::<Vec<_>>();
+
+        if let Some(frame) = self.frames.get_mut(start.saturating_sub(1)) {
+            frame.delay += removed.iter().map(Frame::delay).sum::<Duration>();
+        }
+        removed
+    }
+
+    /// Replaces the frames in the given range with the given frames and returns the frames that
+    /// were replaced. The range may be empty to only insert frames, and the given frames may be
+    /// empty to only remove frames.
+    ///
+    /// Inserted frames that have no delay inherit the delay of the first replaced frame, or of
+    /// the frame they are inserted before if nothing is replaced. See [`Self::insert_frame`] for
+    /// more information.
+    ///
+    /// # Panics
+    /// * The range is out of bounds or its start is greater than its end.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # use std::time::Duration;
+    /// let mut sequence = (0..4)
+    ///     .map(|i| Frame::from_image(Image::new(1, 1, L(i))))
+    ///     .collect::<ImageSequence<_>>()
+    ///     .with_fps(10.0);
+    ///
+    /// // Replace the middle two frames with a single frame
+    /// let removed = sequence.splice(1..3, [Frame::from_image(Image::new(1, 1, L(255)))]);
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(sequence.len(), 3);
+    /// assert_eq!(sequence.iter().nth(1).unwrap().delay(), Duration::from_millis(100));
+    /// ```
+    pub fn splice(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        frames: impl IntoIterator<Item = Frame<P>>,
+    ) -> Vec<Frame<P>> {
+        let delay = self.neighboring_delay(Self::range_start(&range));
+        let frames = frames.into_iter().map(|frame| {
+            if frame.delay().is_zero() {
+                frame.with_delay(delay)
+            } else {
+                frame
+            }
+        });
+
+        self.frames.splice(range, frames).collect()
+    }
+
     /// Returns how many times this image sequence loops for.
     #[must_use]
     pub const fn loop_count(&self) -> LoopCount {
@@ -478,6 +975,412 @@ impl<P: Pixel> ImageSequence<P> {
         self.with_loop_count(LoopCount::Infinite)
     }
 
+    /// Appends the frames of the given image sequence to the end of this one in place, fitting
+    /// every frame of both sequences onto a common canvas with the given [`FitMode`] so that the
+    /// result can be encoded.
+    ///
+    /// Sequences with partial frames, i.e. frames with an offset, are
+    /// [coalesced][Self::coalesce] first, so that every frame is fitted as it is rendered.
+    ///
+    /// The resulting sequence loops infinitely if either sequence does, otherwise it loops as many
+    /// times as the sequence that loops the most.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut intro = ImageSequence::new()
+    ///     .with_frame(Frame::from_image(Image::new(32, 16, Rgb::white())))
+    ///     .looped_exactly(1);
+    /// let clip = ImageSequence::new()
+    ///     .with_frame(Frame::from_image(Image::new(16, 24, Rgb::black())))
+    ///     .looped_infinitely();
+    ///
+    /// intro.concat(clip, FitMode::Pad(Rgb::black()));
+    /// assert_eq!(intro.len(), 2);
+    /// assert!(intro.iter().all(|frame| frame.dimensions() == (32, 24)));
+    /// assert_eq!(intro.loop_count(), LoopCount::Infinite);
+    /// ```
+    pub fn concat(&mut self, mut other: Self, mode: FitMode<P>) {
+        self.coalesce_partial_frames();
+        other.coalesce_partial_frames();
+
+        self.loops = match (self.loops, other.loops) {
+            (LoopCount::Exactly(a), LoopCount::Exactly(b)) => LoopCount::Exactly(a.max(b)),
+            _ => LoopCount::Infinite,
+        };
+        self.frames.extend(other.frames);
+
+        let dimensions = self.frames.iter().map(|frame| frame.dimensions());
+        let canvas = match mode {
+            FitMode::Pad(_) => dimensions.fold((0, 0), |(w, h), (x, y)| (w.max(x), h.max(y))),
+            FitMode::Crop => {
+                dimensions.fold((u32::MAX, u32::MAX), |(w, h), (x, y)| (w.min(x), h.min(y)))
+            }
+            #[cfg(feature = "resize")]
+            FitMode::Stretch(_) | FitMode::Letterbox(..) => match self.first_frame() {
+                Some(frame) => frame.dimensions(),
+                None => return,
+            },
+        };
+
+        for frame in &mut self.frames {
+            mode.fit(frame.image_mut(), canvas);
+        }
+    }
+
+    /// Takes this image sequence and appends the frames of the given image sequence to the end of
+    /// it, fitting every frame onto a common canvas with the given [`FitMode`]. Useful for method
+    /// chaining.
+    ///
+    /// # See Also
+    /// * [`Self::concat`] for more information.
+    #[must_use]
+    pub fn concatenated(mut self, other: Self, mode: FitMode<P>) -> Self {
+        self.concat(other, mode);
+        self
+    }
+
+    /// Composites the given static image or animated image sequence onto every frame of this image
+    /// sequence at the given position, such as putting an animated sticker onto a GIF.
+    ///
+    /// If the overlay is animated, both sequences are resampled to a common timeline: frames of
+    /// this sequence are split wherever the overlay changes frames, and the overlay loops for as
+    /// long as this sequence lasts. The duration of this sequence stays the same.
+    ///
+    /// Partial frames, i.e. frames with an offset, of either sequence are
+    /// [coalesced][Self::coalesce] first, so that the overlay is positioned relative to the whole
+    /// canvas.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let mut sequence = ImageSequence::<Rgba>::open("sample.gif")?
+    ///     .collect::<ril::Result<ImageSequence<_>>>()?;
+    /// let sticker = ImageSequence::<Rgba>::open("sticker.gif")?
+    ///     .collect::<ril::Result<ImageSequence<_>>>()?;
+    ///
+    /// sequence.overlay(&sticker, 16, 16);
+    /// sequence.save_inferred("stickered.gif")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn overlay(&mut self, overlay: &impl OverlaySource<P>, x: i64, y: i64) {
+        let layers = overlay.overlay_frames();
+        let period = layers.iter().map(|&(_, delay)| delay).sum::<Duration>();
+        if layers.is_empty() {
+            return;
+        }
+        self.coalesce_partial_frames();
+        if period.is_zero() {
+            return self.map_frames_in_place(|image| image.paste(x, y, &layers[0].0));
+        }
+
+        let mut frames = Vec::with_capacity(self.frames.len());
+        let mut time = Duration::ZERO;
+        for mut frame in std::mem::take(&mut self.frames) {
+            let end = time + frame.delay();
+
+            loop {
+                let delays = layers.iter().map(|&(_, delay)| delay);
+                let (index, remaining) = frame_index_at(delays, period, time);
+                let next = (time + remaining).min(end);
+                let overlay = &*layers[index].0;
+
+                // The last part of the frame can reuse it instead of cloning it
+                if next == end {
+                    frame.set_delay(end - time);
+                    frame.image_mut().paste(x, y, overlay);
+                    frames.push(frame);
+                    break;
+                }
+
+                let mut part = frame.clone().with_delay(next - time);
+                part.image_mut().paste(x, y, overlay);
+                frames.push(part);
+                time = next;
+            }
+            time = end;
+        }
+
+        self.frames = frames;
+    }
+
+    /// Takes this image sequence and composites the given static image or animated image sequence
+    /// onto every frame at the given position. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::overlay`] for more information.
+    #[must_use]
+    pub fn overlaid(mut self, overlay: &impl OverlaySource<P>, x: i64, y: i64) -> Self {
+        self.overlay(overlay, x, y);
+        self
+    }
+
+    /// Sets the delay of every frame in this image sequence in place such that it plays at the
+    /// given amount of frames per second.
+    ///
+    /// Note that some encodings store delays at a lower precision. GIF, for example, stores delays
+    /// in hundredths of a second, so 30 frames per second is stored as 3 hundredths of a second
+    /// per frame.
+    ///
+    /// # Panics
+    /// * `fps` is not a positive, finite number.
+    pub fn set_fps(&mut self, fps: f32) {
+        assert!(
+            fps.is_finite() && fps > 0.0,
+            "frames per second must be positive and finite, got {}",
+            fps
+        );
+
+        let delay = Duration::from_secs_f64(1.0 / f64::from(fps));
+        for frame in &mut self.frames {
+            frame.set_delay(delay);
+        }
+    }
+
+    /// Takes this image sequence and sets the delay of every frame such that it plays at the given
+    /// amount of frames per second. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * `fps` is not a positive, finite number.
+    ///
+    /// # See Also
+    /// * [`Self::set_fps`] for more information.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # use std::time::Duration;
+    /// let sequence = (0..50)
+    ///     .map(|i| Frame::from_image(Image::new(8, 8, L(i))))
+    ///     .collect::<ImageSequence<_>>()
+    ///     .with_fps(25.0);
+    ///
+    /// assert_eq!(sequence.fps(), Some(25.0));
+    /// assert_eq!(sequence.duration(), Duration::from_secs(2));
+    /// ```
+    #[must_use]
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        self.set_fps(fps);
+        self
+    }
+
+    /// Returns the amount of frames per second this image sequence plays at, if every frame has
+    /// the same, non-zero delay. Otherwise, or if the sequence is empty, this returns `None`.
+    #[must_use]
+    pub fn fps(&self) -> Option<f32> {
+        let delay = self.first_frame()?.delay();
+        if delay.is_zero() || self.frames.iter().any(|frame| frame.delay() != delay) {
+            return None;
+        }
+
+        Some((1.0 / delay.as_secs_f64()) as f32)
+    }
+
+    /// Returns the total duration of a single loop of this image sequence, which is the sum of the
+    /// delays of every frame.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(Frame::delay).sum()
+    }
+
+    /// Composites every frame of this image sequence onto the frames before it in place, according
+    /// to the offset, disposal method and blend method of each frame, such that every frame is
+    /// fully rendered.
+    ///
+    /// Decoded GIF and APNG frames may only contain the area that changed since the previous
+    /// frame, so they render incorrectly when processed independently. After coalescing, every
+    /// frame covers the whole canvas, which is as large as needed to fit every frame.
+    ///
+    /// Areas that are cleared by [`DisposalMethod::Background`] or not covered by any frame are
+    /// filled with the default pixel, which is transparent for pixel types with alpha. Paletted
+    /// and dynamic pixels have no default, so the first fully transparent pixel of the first
+    /// frame is used instead, or its top-left pixel if it has none.
+    ///
+    /// Paletted frames are composited by palette index onto the palette of the first frame, so
+    /// every frame should share the same palette.
+    pub fn coalesce(&mut self) {
+        let first = match self.frames.first() {
+            Some(frame) => frame.image(),
+            None => return,
+        };
+        // The background is kept as a single pixel image so that paletted pixels stay bound to a
+        // palette, and are rebound to the palette of the canvas whenever they are drawn
+        let background = if P::COLOR_TYPE.is_paletted() || P::COLOR_TYPE.is_dynamic() {
+            let index = first
+                .data
+                .iter()
+                .position(|pixel| pixel.as_rgba().a == 0)
+                .unwrap_or(0) as u32;
+            let (x, y) = (index % first.width(), index / first.width());
+            first.clone().cropped(x, y, x + 1, y + 1)
+        } else {
+            Image::new(1, 1, P::default())
+        };
+
+        let (width, height) = self.frames.iter().fold((1, 1), |(w, h), frame| {
+            let (x, y) = frame.offset();
+            (w.max(x + frame.width()), h.max(y + frame.height()))
+        });
+        let mut canvas = background.clone();
+        let fill = canvas.data[0];
+        canvas.pad(fill, 0, width - 1, height - 1, 0);
+
+        for frame in &mut self.frames {
+            let (x, y) = frame.offset();
+            let previous = (frame.disposal() == DisposalMethod::Previous).then(|| canvas.clone());
+            let mode = match frame.blend() {
+                BlendMethod::Source => OverlayMode::Replace,
+                BlendMethod::Over => OverlayMode::Merge,
+            };
+            canvas.draw(
+                &Paste::new(frame.image())
+                    .with_position(i64::from(x), i64::from(y))
+                    .with_overlay_mode(mode),
+            );
+
+            let rendered = canvas.clone().with_overlay_mode(frame.overlay_mode());
+            match (frame.disposal(), previous) {
+                (DisposalMethod::Previous, Some(previous)) => canvas = previous,
+                (DisposalMethod::Background, _) => {
+                    // SAFETY: the fill is only drawn onto the canvas that owns the palette
+                    let fill =
+                        unsafe { rebind_pixel(canvas.detached_palette(), background.data[0]) };
+                    canvas.draw(
+                        &Rectangle::at(i64::from(x), i64::from(y))
+                            .with_size(frame.width(), frame.height())
+                            .with_fill(fill)
+                            .with_overlay_mode(OverlayMode::Replace),
+                    );
+                }
+                _ => (),
+            }
+
+            *frame = Frame::from_image(rendered).with_delay(frame.delay());
+        }
+    }
+
+    /// Takes this image sequence and composites every frame onto the frames before it, such that
+    /// every frame is fully rendered. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::coalesce`] for more information.
+    #[must_use]
+    pub fn coalesced(mut self) -> Self {
+        self.coalesce();
+        self
+    }
+
+    /// Returns whether any frame of this image sequence is a partial frame with an offset.
+    fn has_partial_frames(&self) -> bool {
+        self.frames.iter().any(|frame| frame.offset() != (0, 0))
+    }
+
+    /// Coalesces this image sequence in place if it has partial frames.
+    fn coalesce_partial_frames(&mut self) {
+        if self.has_partial_frames() {
+            self.coalesce();
+        }
+    }
+
+    /// Merges consecutive frames that are identical in place, adding the delay of every removed
+    /// frame to the frame before it, so that the sequence plays the same with fewer frames.
+    ///
+    /// Frames are identical if their pixels, offsets, disposal methods and blend methods are
+    /// equal. Decoded frames may only contain the area that changed since the previous frame, so
+    /// consider calling [`Self::coalesce`] first.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # use std::time::Duration;
+    /// let mut sequence = [0, 0, 0, 1, 1, 0]
+    ///     .into_iter()
+    ///     .map(|i| Frame::from_image(Image::new(1, 1, L(i))).with_delay(Duration::from_millis(50)))
+    ///     .collect::<ImageSequence<_>>();
+    /// sequence.optimize();
+    ///
+    /// let delays = sequence.iter().map(|frame| frame.delay().as_millis()).collect::<Vec<_>>();
+    /// assert_eq!(delays, [150, 100, 50]);
+    /// ```
+    pub fn optimize(&mut self) {
+        self.frames.dedup_by(|frame, previous| {
+            let identical = frame.dimensions() == previous.dimensions()
+                && frame.offset() == previous.offset()
+                && frame.disposal() == previous.disposal()
+                && frame.blend() == previous.blend()
+                && frame.data == previous.data;
+            if identical {
+                previous.delay += frame.delay;
+            }
+            identical
+        });
+    }
+
+    /// Takes this image sequence and merges consecutive frames that are identical. Useful for
+    /// method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::optimize`] for more information.
+    #[must_use]
+    pub fn optimized(mut self) -> Self {
+        self.optimize();
+        self
+    }
+
+    /// Returns the frame that is visible at the given time since the start of this image sequence,
+    /// according to the delay of every frame. Returns `None` if there are no frames.
+    ///
+    /// Times past the end of the sequence wrap around according to its loop count. Once the
+    /// sequence has finished looping, or if every frame has no delay, the last frame stays
+    /// visible.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # use std::time::Duration;
+    /// let sequence = (0..4)
+    ///     .map(|i| Frame::from_image(Image::new(1, 1, L(i))))
+    ///     .collect::<ImageSequence<_>>()
+    ///     .with_fps(10.0)
+    ///     .looped_exactly(2);
+    ///
+    /// let at = |ms| sequence.frame_at(Duration::from_millis(ms)).unwrap().pixel(0, 0).value();
+    /// assert_eq!(at(0), 0);
+    /// assert_eq!(at(250), 2);
+    /// assert_eq!(at(450), 0); // Second loop
+    /// assert_eq!(at(1000), 3); // Finished looping
+    /// ```
+    #[must_use]
+    pub fn frame_at(&self, time: Duration) -> Option<&Frame<P>> {
+        let period = self.duration();
+        let finished = match self.loops {
+            _ if period.is_zero() => true,
+            LoopCount::Infinite => false,
+            LoopCount::Exactly(loops) => {
+                time.as_nanos() >= period.as_nanos() * u128::from(loops.max(1))
+            }
+        };
+        if finished {
+            return self.frames.last();
+        }
+
+        let (index, _) = frame_index_at(self.frames.iter().map(Frame::delay), period, time);
+        self.frames.get(index)
+    }
+
+    /// Iterates through the frames in this image sequence by reference along with the time each
+    /// frame becomes visible at, relative to the start of the sequence. This only covers a single
+    /// loop of the sequence.
+    pub fn iter_timed(&self) -> impl Iterator<Item = (Duration, &Frame<P>)> {
+        self.frames.iter().scan(Duration::ZERO, |time, frame| {
+            let start = *time;
+            *time += frame.delay();
+            Some((start, frame))
+        })
+    }
+
     /// Consumes this image sequence and returns the frames it contains.
     #[must_use]
     #[allow(clippy::missing_const_for_fn)]
@@ -495,6 +1398,194 @@ impl<P: Pixel> ImageSequence<P> {
         self.frames.iter_mut()
     }
 
+    /// Takes this image sequence and applies the given function to the image of every frame,
+    /// preserving the delay, disposal method, offset and blend method of each frame along with the
+    /// loop count.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let sequence = ImageSequence::<Rgba>::open("sample.gif")?
+    ///     .collect::<ril::Result<ImageSequence<_>>>()?;
+    /// let grayscale = sequence.map_frames(|image| image.convert::<L>());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn map_frames<T: Pixel>(self, mut f: impl FnMut(Image<P>) -> Image<T>) -> ImageSequence<T> {
+        ImageSequence {
+            frames: self
+                .frames
+                .into_iter()
+                .map(|frame| frame.map_image(&mut f))
+                .collect(),
+            loops: self.loops,
+        }
+    }
+
+    /// Takes this image sequence and applies the given fallible function to the image of every
+    /// frame, preserving the delay, disposal method, offset and blend method of each frame along
+    /// with the loop count.
+    ///
+    /// # Errors
+    /// * The first error returned by the given function, wrapped in an [`Error::FrameError`] with
+    ///   the index of the frame. No more frames are processed after it.
+    pub fn try_map_frames<T: Pixel>(
+        self,
+        mut f: impl FnMut(Image<P>) -> Result<Image<T>>,
+    ) -> Result<ImageSequence<T>> {
+        Ok(ImageSequence {
+            frames: self
+                .frames
+                .into_iter()
+                .enumerate()
+                .map(|(i, frame)| {
+                    Ok(Frame {
+                        inner: f(frame.inner).map_err(|err| err.with_frame(i))?,
+                        delay: frame.delay,
+                        disposal: frame.disposal,
+                        offset: frame.offset,
+                        blend: frame.blend,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            loops: self.loops,
+        })
+    }
+
+    /// Draws the given object or shape onto the image of every frame, for example a
+    /// [`Watermark`](crate::Watermark).
+    ///
+    /// # See Also
+    /// * [`Image::draw`] for more information.
+    pub fn draw(&mut self, entity: &impl Draw<P>) {
+        self.map_frames_in_place(|image| image.draw(entity));
+    }
+
+    /// Applies the given function to the image of every frame in place.
+    pub fn map_frames_in_place(&mut self, mut f: impl FnMut(&mut Image<P>)) {
+        for frame in &mut self.frames {
+            f(frame.image_mut());
+        }
+    }
+
+    /// Resizes every frame of this image sequence to the given dimensions in place using the
+    /// given resizing algorithm.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # See Also
+    /// * [`Image::resize`] for more information.
+    #[cfg(feature = "resize")]
+    pub fn resize(&mut self, width: u32, height: u32, algorithm: ResizeAlgorithm) {
+        self.map_frames_in_place(|image| image.resize(width, height, algorithm));
+    }
+
+    /// Takes this image sequence and resizes every frame to the given dimensions using the given
+    /// resizing algorithm. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * `width` or `height` is zero.
+    ///
+    /// # See Also
+    /// * [`Image::resize`] for more information.
+    #[must_use]
+    #[cfg(feature = "resize")]
+    pub fn resized(mut self, width: u32, height: u32, algorithm: ResizeAlgorithm) -> Self {
+        self.resize(width, height, algorithm);
+        self
+    }
+
+    /// Crops every frame of this image sequence to the given bounding box in place.
+    ///
+    /// # Panics
+    /// * The width or height of the bounding box is less than 1.
+    ///
+    /// # See Also
+    /// * [`Image::crop`] for more information.
+    pub fn crop(&mut self, x1: u32, y1: u32, x2: u32, y2: u32) {
+        self.map_frames_in_place(|image| image.crop(x1, y1, x2, y2));
+    }
+
+    /// Takes this image sequence and crops every frame to the given bounding box. Useful for
+    /// method chaining.
+    ///
+    /// # Panics
+    /// * The width or height of the bounding box is less than 1.
+    ///
+    /// # See Also
+    /// * [`Image::crop`] for more information.
+    #[must_use]
+    pub fn cropped(mut self, x1: u32, y1: u32, x2: u32, y2: u32) -> Self {
+        self.crop(x1, y1, x2, y2);
+        self
+    }
+
+    /// Converts every frame of this image sequence to the given pixel type, preserving the delay
+    /// and disposal method of each frame along with the loop count.
+    ///
+    /// # See Also
+    /// * [`Image::convert`] for more information.
+    #[must_use]
+    pub fn convert<T: Pixel + From<P>>(self) -> ImageSequence<T> {
+        self.map_frames(Image::convert)
+    }
+
+    /// Reverses the order of the frames of this image sequence in place. Each frame keeps its own
+    /// delay.
+    pub fn reverse(&mut self) {
+        self.frames.reverse();
+    }
+
+    /// Takes this image sequence and reverses the order of its frames. Useful for method chaining.
+    #[must_use]
+    pub fn reversed(mut self) -> Self {
+        self.reverse();
+        self
+    }
+
+    /// Appends the frames of this image sequence in reverse order in place, so that it plays
+    /// forwards and then backwards. The first and last frames are not duplicated, so the sequence
+    /// loops seamlessly. Each frame keeps its own delay.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let mut sequence = (0..4)
+    ///     .map(|i| Frame::from_image(Image::new(1, 1, L(i))))
+    ///     .collect::<ImageSequence<_>>();
+    /// sequence.boomerang();
+    ///
+    /// let order = sequence.iter().map(|frame| frame.pixel(0, 0).value()).collect::<Vec<_>>();
+    /// assert_eq!(order, [0, 1, 2, 3, 2, 1]);
+    /// ```
+    pub fn boomerang(&mut self) {
+        let len = self.frames.len();
+        if len < 3 {
+            return;
+        }
+
+        let backwards = self.frames[1..len - 1]
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>();
+        self.frames.extend(backwards);
+    }
+
+    /// Takes this image sequence and appends its frames in reverse order, so that it plays
+    /// forwards and then backwards. Useful for method chaining.
+    ///
+    /// # See Also
+    /// * [`Self::boomerang`] for more information.
+    #[must_use]
+    pub fn boomeranged(mut self) -> Self {
+        self.boomerang();
+        self
+    }
+
     /// Returns whether there are no frames in the image sequence. If so, this will probably be
     /// invalid to encode.
     #[must_use]
@@ -549,3 +1640,377 @@ impl<P: Pixel> ImageSequence<P> {
         self.frames.get_unchecked_mut(0)
     }
 }
+
+/// An image sequence that decodes or produces its frames on demand instead of holding all of them
+/// in memory, which is useful for transcoding or processing long animations with constant memory.
+///
+/// This implements [`FrameIterator`], so frames can be consumed one at a time. Frames can be
+/// transformed lazily with [`Self::map_frames`] and then streamed straight into an encoder with
+/// [`Self::encode`], [`Self::save`], or [`Self::save_inferred`], which drop each frame as soon as
+/// it is encoded.
+///
+/// # Example
+/// Transcode an animated PNG into a GIF without decoding every frame upfront:
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// LazySequence::<Rgba>::open("long_animation.png")?
+///     .map_frames(|image| image.cropped(0, 0, 320, 240))
+///     .save_inferred("long_animation.gif")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct LazySequence<'a, P: Pixel> {
+    frames: Box<dyn Iterator<Item = Result<Frame<P>>> + 'a>,
+    len: u32,
+    loops: LoopCount,
+}
+
+impl<'a, P: Pixel + 'a> LazySequence<'a, P> {
+    /// Creates a new lazy image sequence from the given frame iterator, such as one returned by
+    /// [`ImageSequence::open`].
+    #[must_use]
+    pub fn new(frames: impl FrameIterator<P> + 'a) -> Self {
+        Self {
+            len: frames.len(),
+            loops: frames.loop_count(),
+            frames: Box::new(frames),
+        }
+    }
+
+    /// Creates a new lazy image sequence from the given iterator of frames. Since some encoders
+    /// must know the amount of frames before encoding, `len` must be the amount of frames the
+    /// iterator yields. The sequence loops infinitely by default.
+    #[must_use]
+    pub fn from_frames(frames: impl Iterator<Item = Frame<P>> + 'a, len: u32) -> Self {
+        Self {
+            frames: Box::new(frames.map(Ok)),
+            len,
+            loops: LoopCount::Infinite,
+        }
+    }
+
+    /// Opens a file from the given path, lazily decoding its frames as they are requested.
+    ///
+    /// # Errors
+    /// * Could not infer encoding format.
+    /// * An error occured while opening the file or decoding its header.
+    /// * `UnsupportedFormat`: No decoder for the encoding format is enabled.
+    ///
+    /// # See Also
+    /// * [`ImageSequence::open`] for more information.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn open(path: impl AsRef<Path> + 'a) -> Result<Self> {
+        ImageSequence::open(path).map(|frames| Self {
+            len: frames.len(),
+            loops: frames.loop_count(),
+            frames: Box::new(frames),
+        })
+    }
+
+    /// Takes this sequence and sets the amount of times it should loop over itself.
+    #[must_use]
+    pub const fn with_loop_count(mut self, loops: LoopCount) -> Self {
+        self.loops = loops;
+        self
+    }
+
+    /// Takes this sequence and lazily applies the given function to the image of every frame,
+    /// preserving the delay and disposal method of each frame along with the loop count. The
+    /// function is only called once a frame is requested.
+    #[must_use]
+    pub fn map_frames<T: Pixel + 'a>(
+        self,
+        mut f: impl FnMut(Image<P>) -> Image<T> + 'a,
+    ) -> LazySequence<'a, T> {
+        LazySequence {
+            frames: Box::new(
+                self.frames
+                    .map(move |frame| frame.map(|frame| frame.map_image(&mut f))),
+            ),
+            len: self.len,
+            loops: self.loops,
+        }
+    }
+
+    /// Lazily encodes the frames of this sequence with the given encoding and writes them to the
+    /// given write buffer, holding only one frame in memory at a time.
+    ///
+    /// # Errors
+    /// * The sequence has no frames.
+    /// * An error occured while decoding or encoding a frame.
+    ///
+    /// # Panics
+    /// * No encoder implementation for the given encoding format.
+    pub fn encode(self, encoding: ImageFormat, dest: &mut impl Write) -> Result<()> {
+        encoding.run_lazy_encoder(self, dest)
+    }
+
+    /// Lazily encodes the frames of this sequence with the given encoding and saves them to the
+    /// given path.
+    ///
+    /// # Errors
+    /// * The sequence has no frames.
+    /// * An error occured while decoding or encoding a frame.
+    ///
+    /// # Panics
+    /// * No encoder implementation for the given encoding format.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn save(self, encoding: ImageFormat, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        File::create(path)
+            .map_err(Error::IoError)
+            .and_then(|mut file| self.encode(encoding, &mut file))
+            .map_err(|err| err.with_path(path))
+    }
+
+    /// Lazily encodes the frames of this sequence and saves them to the given path, inferring the
+    /// encoding from the given path/filename extension.
+    ///
+    /// # Errors
+    /// * Could not infer encoding format.
+    /// * The sequence has no frames.
+    /// * An error occured while decoding or encoding a frame.
+    ///
+    /// # Panics
+    /// * No encoder implementation for the given encoding format.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn save_inferred(self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        match ImageFormat::from_path(path).map_err(|err| err.with_path(path))? {
+            ImageFormat::Unknown => Err(Error::UnknownEncodingFormat.with_path(path)),
+            encoding => self.save(encoding, path),
+        }
+    }
+}
+
+impl<'a, P: Pixel> Iterator for LazySequence<'a, P> {
+    type Item = Result<Frame<P>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.frames.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.frames.size_hint()
+    }
+}
+
+impl<'a, P: Pixel> FrameIterator<P> for LazySequence<'a, P> {
+    fn len(&self) -> u32 {
+        self.len
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        self.loops
+    }
+}
+
+/// Returns the index of the frame shown at the given time given the delays of every frame, looping
+/// over the given non-zero period, along with how much longer it is shown for.
+fn frame_index_at(
+    delays: impl IntoIterator<Item = Duration>,
+    period: Duration,
+    time: Duration,
+) -> (usize, Duration) {
+    let mut offset = Duration::from_nanos((time.as_nanos() % period.as_nanos()) as u64);
+    for (i, delay) in delays.into_iter().enumerate() {
+        if offset < delay {
+            return (i, delay - offset);
+        }
+        offset -= delay;
+    }
+
+    unreachable!("offset must be less than the period")
+}
+
+/// Replaces the `{}` or `{:0N}` placeholder in the given pattern with the given frame index.
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+fn format_frame_path(pattern: &str, index: usize) -> String {
+    let start = pattern
+        .find('{')
+        .expect("frame path pattern must contain a `{}` or `{:0N}` placeholder");
+    let len = pattern[start..]
+        .find('}')
+        .expect("frame path pattern placeholder must be closed with `}`");
+    let spec = &pattern[start + 1..start + len];
+
+    let index = match spec.strip_prefix(':') {
+        Some(width) => {
+            let width = width
+                .parse::<usize>()
+                .expect("frame path pattern placeholder must be `{}` or `{:0N}`");
+            format!("{:0width$}", index, width = width)
+        }
+        None if spec.is_empty() => index.to_string(),
+        None => panic!("frame path pattern placeholder must be `{{}}` or `{{:0N}}`"),
+    };
+
+    format!(
+        "{}{}{}",
+        &pattern[..start],
+        index,
+        &pattern[start + len + 1..]
+    )
+}
+
+/// Returns the filename of the given path as a string, or an empty string if there is none.
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Compares two strings such that runs of digits are compared by their numeric value.
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                        digits.push(*c);
+                        chars.next();
+                    }
+                    digits
+                };
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+
+                match x.len().cmp(&y.len()).then_with(|| x.cmp(y)) {
+                    Ordering::Equal => {}
+                    ordering => return ordering,
+                }
+            }
+            (Some(x), Some(y)) => match x.cmp(&y) {
+                Ordering::Equal => {
+                    a.next();
+                    b.next();
+                }
+                ordering => return ordering,
+            },
+        }
+    }
+}
+
+/// Linearly interpolates between the two given images, where `t` is the weight of `b`.
+fn blend<P: TrueColor>(a: &Image<P>, b: &Image<P>, t: f64) -> Image<P> {
+    let data = a
+        .data
+        .iter()
+        .zip(&b.data)
+        .map(|(a, b)| {
+            let (a, b) = (a.as_rgba_tuple(), b.as_rgba_tuple());
+            let lerp =
+                |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+            P::from_rgba_tuple((
+                lerp(a.0, b.0),
+                lerp(a.1, b.1),
+                lerp(a.2, b.2),
+                lerp(a.3, b.3),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    Image::from_pixels(a.width(), data).with_overlay_mode(a.overlay_mode())
+}
+
+impl<P: TrueColor> ImageSequence<P> {
+    /// Inserts alpha-blended intermediate frames between every pair of consecutive frames in
+    /// place, so that every frame except for the last becomes `factor` frames which crossfade into
+    /// the next frame. The delay of each of these frames is divided evenly between the new frames,
+    /// so the total duration of the sequence is kept.
+    ///
+    /// This can be used to smooth out animations with a low frame rate, or to create a crossfade
+    /// transition between two images. A factor of `0` or `1` leaves the sequence unchanged.
+    ///
+    /// # Panics
+    /// * Two consecutive frames have different dimensions.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # use std::time::Duration;
+    /// // Crossfade from black to white over a second
+    /// let black = Frame::from_image(Image::new(64, 64, Rgb::black()));
+    /// let white = Frame::from_image(Image::new(64, 64, Rgb::white()));
+    ///
+    /// let mut sequence = ImageSequence::new()
+    ///     .with_frame(black.with_delay(Duration::from_secs(1)))
+    ///     .with_frame(white);
+    /// sequence.interpolate(10);
+    ///
+    /// assert_eq!(sequence.len(), 11);
+    /// assert_eq!(sequence.iter().nth(5).unwrap().pixel(0, 0), &Rgb::new(128, 128, 128));
+    /// ```
+    pub fn interpolate(&mut self, factor: u32) {
+        if factor < 2 || self.frames.len() < 2 {
+            return;
+        }
+
+        let mut frames = Vec::with_capacity((self.frames.len() - 1) * factor as usize + 1);
+        for pair in self.frames.windows(2) {
+            let (current, next) = (&pair[0], &pair[1]);
+            assert_eq!(
+                current.dimensions(),
+                next.dimensions(),
+                "cannot interpolate between frames with different dimensions",
+            );
+
+            let delay = current.delay / factor;
+            frames.push(current.clone().with_delay(delay));
+            frames.extend((1..factor).map(|i| {
+                let t = f64::from(i) / f64::from(factor);
+                Frame {
+                    inner: blend(current, next, t),
+                    delay,
+                    disposal: current.disposal,
+                    offset: current.offset,
+                    blend: current.blend,
+                }
+            }));
+        }
+        frames.push(self.frames.pop().unwrap());
+
+        self.frames = frames;
+    }
+
+    /// Takes this image sequence and inserts alpha-blended intermediate frames between every pair
+    /// of consecutive frames. Useful for method chaining.
+    ///
+    /// # Panics
+    /// * Two consecutive frames have different dimensions.
+    ///
+    /// # See Also
+    /// * [`Self::interpolate`] for more information.
+    #[must_use]
+    pub fn interpolated(mut self, factor: u32) -> Self {
+        self.interpolate(factor);
+        self
+    }
+}
@@ -0,0 +1,302 @@
+//! Bidirectional levels and joining of Arabic letters in text before it is laid out.
+//!
+//! The layout engine positions one glyph per character in logical order, which breaks scripts
+//! written from right to left or whose letters change shape depending on their neighbors. This
+//! resolves the embedding levels of text using the Unicode Bidirectional Algorithm, so that every
+//! laid out line can be reordered into visual order, and substitutes Arabic letters with their
+//! contextual presentation forms, including the mandatory lam-alef ligatures.
+//!
+//! This is deliberately not a full shaping engine: the font's substitution and positioning tables
+//! are never consulted, so only scripts covered by the presentation forms block are joined. Indic
+//! scripts, combining marks and font-specific ligatures are laid out one glyph per character.
+//!
+//! The output is a sequence of string slices, each either borrowed from the original text or a
+//! static presentation form, so that it can be appended to a layout without any allocations
+//! tied to the lifetime of the layout.
+
+use std::ops::Range;
+use unicode_bidi::BidiInfo;
+
+/// A shaped piece of text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    /// A range of bytes of the original text.
+    Source(Range<usize>),
+    /// A substituted presentation form or mirrored character.
+    Static(&'static str),
+}
+
+/// Returns the presentation forms of the given Arabic letter. Letters that only join to the
+/// preceding letter have two forms (isolated, final), while letters that join on both sides have
+/// four forms (isolated, final, initial, medial).
+const fn forms(c: char) -> Option<&'static [&'static str]> {
+    Some(match c {
+        '\u{0621}' => &["\u{FE80}"],
+        '\u{0622}' => &["\u{FE81}", "\u{FE82}"],
+        '\u{0623}' => &["\u{FE83}", "\u{FE84}"],
+        '\u{0624}' => &["\u{FE85}", "\u{FE86}"],
+        '\u{0625}' => &["\u{FE87}", "\u{FE88}"],
+        '\u{0626}' => &["\u{FE89}", "\u{FE8A}", "\u{FE8B}", "\u{FE8C}"],
+        '\u{0627}' => &["\u{FE8D}", "\u{FE8E}"],
+        '\u{0628}' => &["\u{FE8F}", "\u{FE90}", "\u{FE91}", "\u{FE92}"],
+        '\u{0629}' => &["\u{FE93}", "\u{FE94}"],
+        '\u{062A}' => &["\u{FE95}", "\u{FE96}", "\u{FE97}", "\u{FE98}"],
+        '\u{062B}' => &["\u{FE99}", "\u{FE9A}", "\u{FE9B}", "\u{FE9C}"],
+        '\u{062C}' => &["\u{FE9D}", "\u{FE9E}", "\u{FE9F}", "\u{FEA0}"],
+        '\u{062D}' => &["\u{FEA1}", "\u{FEA2}", "\u{FEA3}", "\u{FEA4}"],
+        '\u{062E}' => &["\u{FEA5}", "\u{FEA6}", "\u{FEA7}", "\u{FEA8}"],
+        '\u{062F}' => &["\u{FEA9}", "\u{FEAA}"],
+        '\u{0630}' => &["\u{FEAB}", "\u{FEAC}"],
+        '\u{0631}' => &["\u{FEAD}", "\u{FEAE}"],
+        '\u{0632}' => &["\u{FEAF}", "\u{FEB0}"],
+        '\u{0633}' => &["\u{FEB1}", "\u{FEB2}", "\u{FEB3}", "\u{FEB4}"],
+        '\u{0634}' => &["\u{FEB5}", "\u{FEB6}", "\u{FEB7}", "\u{FEB8}"],
+        '\u{0635}' => &["\u{FEB9}", "\u{FEBA}", "\u{FEBB}", "\u{FEBC}"],
+        '\u{0636}' => &["\u{FEBD}", "\u{FEBE}", "\u{FEBF}", "\u{FEC0}"],
+        '\u{0637}' => &["\u{FEC1}", "\u{FEC2}", "\u{FEC3}", "\u{FEC4}"],
+        '\u{0638}' => &["\u{FEC5}", "\u{FEC6}", "\u{FEC7}", "\u{FEC8}"],
+        '\u{0639}' => &["\u{FEC9}", "\u{FECA}", "\u{FECB}", "\u{FECC}"],
+        '\u{063A}' => &["\u{FECD}", "\u{FECE}", "\u{FECF}", "\u{FED0}"],
+        '\u{0641}' => &["\u{FED1}", "\u{FED2}", "\u{FED3}", "\u{FED4}"],
+        '\u{0642}' => &["\u{FED5}", "\u{FED6}", "\u{FED7}", "\u{FED8}"],
+        '\u{0643}' => &["\u{FED9}", "\u{FEDA}", "\u{FEDB}", "\u{FEDC}"],
+        '\u{0644}' => &["\u{FEDD}", "\u{FEDE}", "\u{FEDF}", "\u{FEE0}"],
+        '\u{0645}' => &["\u{FEE1}", "\u{FEE2}", "\u{FEE3}", "\u{FEE4}"],
+        '\u{0646}' => &["\u{FEE5}", "\u{FEE6}", "\u{FEE7}", "\u{FEE8}"],
+        '\u{0647}' => &["\u{FEE9}", "\u{FEEA}", "\u{FEEB}", "\u{FEEC}"],
+        '\u{0648}' => &["\u{FEED}", "\u{FEEE}"],
+        '\u{0649}' => &["\u{FEEF}", "\u{FEF0}"],
+        '\u{064A}' => &["\u{FEF1}", "\u{FEF2}", "\u{FEF3}", "\u{FEF4}"],
+        // Letters used by Persian and Urdu
+        '\u{067E}' => &["\u{FB56}", "\u{FB57}", "\u{FB58}", "\u{FB59}"],
+        '\u{0686}' => &["\u{FB7A}", "\u{FB7B}", "\u{FB7C}", "\u{FB7D}"],
+        '\u{0698}' => &["\u{FB8A}", "\u{FB8B}"],
+        '\u{06A9}' => &["\u{FB8E}", "\u{FB8F}", "\u{FB90}", "\u{FB91}"],
+        '\u{06AF}' => &["\u{FB92}", "\u{FB93}", "\u{FB94}", "\u{FB95}"],
+        '\u{06CC}' => &["\u{FBFC}", "\u{FBFD}", "\u{FBFE}", "\u{FBFF}"],
+        _ => return None,
+    })
+}
+
+/// Returns the (isolated, final) forms of the ligature of lam followed by the given alef.
+const fn lam_alef(alef: char) -> Option<[&'static str; 2]> {
+    Some(match alef {
+        '\u{0622}' => ["\u{FEF5}", "\u{FEF6}"],
+        '\u{0623}' => ["\u{FEF7}", "\u{FEF8}"],
+        '\u{0625}' => ["\u{FEF9}", "\u{FEFA}"],
+        '\u{0627}' => ["\u{FEFB}", "\u{FEFC}"],
+        _ => return None,
+    })
+}
+
+/// Returns whether the given character is skipped when determining how its neighbors join, which
+/// is the case for Arabic diacritics.
+pub(crate) const fn is_transparent(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0610}'..='\u{061A}' | '\u{064B}'..='\u{065F}' | '\u{0670}' | '\u{06D6}'..='\u{06ED}'
+    )
+}
+
+/// Returns whether the given character causes its neighbors to join to it, i.e. tatweel and the
+/// zero width joiner.
+const fn is_join_causing(c: char) -> bool {
+    matches!(c, '\u{0640}' | '\u{200D}')
+}
+
+/// Returns whether the given character joins to the character following it.
+fn joins_next(c: char) -> bool {
+    is_join_causing(c) || matches!(forms(c), Some(forms) if forms.len() == 4)
+}
+
+/// Returns whether the given character joins to the character preceding it.
+fn joins_previous(c: char) -> bool {
+    is_join_causing(c) || matches!(forms(c), Some(forms) if forms.len() > 1)
+}
+
+/// Returns the mirrored counterpart of the given character, used for characters in right-to-left
+/// runs.
+const fn mirror(c: char) -> Option<&'static str> {
+    Some(match c {
+        '(' => ")",
+        ')' => "(",
+        '[' => "]",
+        ']' => "[",
+        '{' => "}",
+        '}' => "{",
+        '<' => ">",
+        '>' => "<",
+        '«' => "»",
+        '»' => "«",
+        _ => return None,
+    })
+}
+
+/// Substitutes the contextual forms of the characters in the given range of text, returning the
+/// shaped pieces in logical order along with the byte offset of the character they start at.
+fn substitute(text: &str, range: Range<usize>) -> Vec<(usize, Piece)> {
+    let chars = text[range.clone()]
+        .char_indices()
+        .map(|(i, c)| (range.start + i, c))
+        .collect::<Vec<_>>();
+    let neighbor = |mut i: usize, forward: bool| loop {
+        i = if forward { i + 1 } else { i.checked_sub(1)? };
+        match chars.get(i) {
+            Some(&(_, c)) if is_transparent(c) => continue,
+            other => return other.map(|&(_, c)| c),
+        }
+    };
+
+    let mut pieces = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let end = chars.get(i + 1).map_or(range.end, |&(next, _)| next);
+        let joined = matches!(neighbor(i, false), Some(c) if joins_next(c));
+
+        // Lam followed directly by alef forms a mandatory ligature
+        if let Some(ligature) = (c == '\u{0644}')
+            .then(|| chars.get(i + 1).and_then(|&(_, alef)| lam_alef(alef)))
+            .flatten()
+        {
+            pieces.push((start, Piece::Static(ligature[usize::from(joined)])));
+            i += 2;
+            continue;
+        }
+
+        let piece = match forms(c) {
+            Some(forms) => {
+                let joins =
+                    forms.len() == 4 && matches!(neighbor(i, true), Some(c) if joins_previous(c));
+                let form = match (joined && forms.len() > 1, joins) {
+                    (false, false) => 0,
+                    (true, false) => 1,
+                    (false, true) => 2,
+                    (true, true) => 3,
+                };
+                Piece::Static(forms[form])
+            }
+            None => Piece::Source(start..end),
+        };
+        pieces.push((start, piece));
+        i += 1;
+    }
+    pieces
+}
+
+/// Shapes the given text, returning the pieces of text in logical order along with the embedding
+/// level of every piece, which is odd for right-to-left text. Characters in right-to-left runs are
+/// mirrored, but not reordered: the layout has to wrap text in logical order, so every laid out
+/// line is reordered on its own with [`visual_order`] instead.
+pub(crate) fn shape(text: &str) -> Vec<(&str, u8)> {
+    let info = BidiInfo::new(text, None);
+    let mut pieces = Vec::new();
+
+    for paragraph in &info.paragraphs {
+        let range = paragraph.range.clone();
+        // The paragraph separator takes the level of the paragraph, regardless of its contents
+        let content = text[range.clone()].trim_end_matches(&['\n', '\r'][..]);
+        let line = range.start..range.start + content.len();
+
+        for (start, piece) in substitute(text, line.clone()) {
+            let level = info.levels[start];
+            let piece = match piece {
+                Piece::Source(range) if level.is_rtl() => text[range.clone()]
+                    .chars()
+                    .next()
+                    .and_then(mirror)
+                    .map_or(Piece::Source(range), Piece::Static),
+                piece => piece,
+            };
+            pieces.push((piece, level.number()));
+        }
+        if line.end < range.end {
+            pieces.push((Piece::Source(line.end..range.end), paragraph.level.number()));
+        }
+    }
+
+    // Merge adjacent ranges of the original text on the same level, so that the layout receives
+    // as few pieces of text as possible
+    let mut merged: Vec<(Piece, u8)> = Vec::with_capacity(pieces.len());
+    for (piece, level) in pieces {
+        match (merged.last_mut(), piece) {
+            (Some((Piece::Source(last), last_level)), Piece::Source(next))
+                if last.end == next.start && *last_level == level =>
+            {
+                last.end = next.end;
+            }
+            (_, piece) => merged.push((piece, level)),
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(piece, level)| match piece {
+            Piece::Source(range) => (&text[range], level),
+            Piece::Static(s) => (s, level),
+        })
+        .collect()
+}
+
+/// Returns the visual order of the items of a single line from left to right, given the embedding
+/// level of every item in logical order. Following rule L2 of the Unicode Bidirectional Algorithm,
+/// every run of items at or above each level is reversed, from the highest level down to the
+/// lowest odd level.
+pub(crate) fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order = (0..levels.len()).collect::<Vec<_>>();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let lowest_odd = levels.iter().map(|&level| level | 1).min().unwrap_or(1);
+
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            let run = order[i..]
+                .iter()
+                .take_while(|&&item| levels[item] >= level)
+                .count();
+            order[i..i + run].reverse();
+            i += run.max(1);
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shape, visual_order};
+
+    #[test]
+    fn test_shape() {
+        assert_eq!(shape("hello world"), [("hello world", 0)]);
+        // "سلام": seen (initial), lam-alef ligature (final), meem (isolated)
+        assert_eq!(
+            shape("\u{0633}\u{0644}\u{0627}\u{0645}"),
+            [("\u{FEB3}", 1), ("\u{FEFC}", 1), ("\u{FEE1}", 1)]
+        );
+        // Right-to-left runs are mirrored and kept in logical order
+        assert_eq!(
+            shape("ab \u{05D0}\u{05D1}(\u{05D2})\nc"),
+            [
+                ("ab ", 0),
+                ("\u{05D0}\u{05D1}", 1),
+                (")", 1),
+                ("\u{05D2}", 1),
+                ("(", 1),
+                ("\nc", 0)
+            ]
+        );
+        // "بَ" followed by "ل", both in isolation
+        assert_eq!(
+            shape("\u{0628}\u{064E} \u{0644}"),
+            [("\u{FE8F}", 1), ("\u{064E} ", 1), ("\u{FEDD}", 1)]
+        );
+    }
+
+    #[test]
+    fn test_visual_order() {
+        assert_eq!(visual_order(&[]), []);
+        assert_eq!(visual_order(&[0, 0, 1, 1, 1, 0]), [0, 1, 4, 3, 2, 5]);
+        // Left-to-right text embedded in right-to-left text keeps its order
+        assert_eq!(visual_order(&[1, 2, 2, 1]), [3, 1, 2, 0]);
+    }
+}
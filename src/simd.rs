@@ -0,0 +1,243 @@
+//! Explicit SIMD implementations of hot blending loops, enabled with the `simd` feature.
+//!
+//! These use SSE2 intrinsics, which are available on every `x86_64` target, so no runtime feature
+//! detection is needed. Results match the scalar implementations, except that multiplications
+//! and additions are not fused, which may rarely cause a channel to differ by one.
+
+use crate::{Pixel, Rgb, Rgba};
+use core::arch::x86_64::{
+    __m128, __m128i, _mm_add_epi16, _mm_add_ps, _mm_cvtepi32_ps, _mm_cvttps_epi32, _mm_div_ps,
+    _mm_loadu_si128, _mm_move_ss, _mm_mul_ps, _mm_mullo_epi16, _mm_packs_epi32, _mm_packus_epi16,
+    _mm_set1_epi16, _mm_set1_ps, _mm_setzero_si128, _mm_shuffle_ps, _mm_srli_epi16,
+    _mm_storeu_si128, _mm_sub_epi16, _mm_sub_ps, _mm_unpackhi_epi16, _mm_unpackhi_epi8,
+    _mm_unpacklo_epi16, _mm_unpacklo_epi8,
+};
+
+/// Broadcasts the alpha lane of the given RGBA vector to every lane.
+#[inline(always)]
+unsafe fn splat_alpha(v: __m128) -> __m128 {
+    _mm_shuffle_ps::<0xFF>(v, v)
+}
+
+/// Merges a single pixel with source-over compositing, where both pixels are given as vectors of
+/// their channels normalized to `0.0..=1.0`, and returns the merged channels scaled to
+/// `0.0..=255.0`.
+#[inline(always)]
+unsafe fn merge_pixel(base: __m128, overlay: __m128) -> __m128 {
+    let one = _mm_set1_ps(1.);
+    let base_a = splat_alpha(base);
+    let overlay_a = splat_alpha(overlay);
+
+    let a_diff = _mm_sub_ps(one, overlay_a);
+    let a = _mm_add_ps(_mm_mul_ps(a_diff, base_a), overlay_a);
+    let a_ratio = _mm_mul_ps(a_diff, base_a);
+    let color = _mm_div_ps(
+        _mm_add_ps(_mm_mul_ps(a_ratio, base), _mm_mul_ps(overlay_a, overlay)),
+        a,
+    );
+
+    // Put the merged alpha into the alpha lane: rotate it to the first lane, replace it, and
+    // rotate back
+    let rotated = _mm_shuffle_ps::<0x93>(color, color);
+    let rotated = _mm_move_ss(rotated, a);
+    let merged = _mm_shuffle_ps::<0x39>(rotated, rotated);
+
+    _mm_mul_ps(merged, _mm_set1_ps(255.))
+}
+
+/// Unpacks four RGBA pixels into four vectors of their channels normalized to `0.0..=1.0`.
+#[inline(always)]
+unsafe fn unpack(pixels: __m128i) -> [__m128; 4] {
+    let zero = _mm_setzero_si128();
+    let scale = _mm_set1_ps(255.);
+    let lo = _mm_unpacklo_epi8(pixels, zero);
+    let hi = _mm_unpackhi_epi8(pixels, zero);
+
+    [
+        _mm_div_ps(_mm_cvtepi32_ps(_mm_unpacklo_epi16(lo, zero)), scale),
+        _mm_div_ps(_mm_cvtepi32_ps(_mm_unpackhi_epi16(lo, zero)), scale),
+        _mm_div_ps(_mm_cvtepi32_ps(_mm_unpacklo_epi16(hi, zero)), scale),
+        _mm_div_ps(_mm_cvtepi32_ps(_mm_unpackhi_epi16(hi, zero)), scale),
+    ]
+}
+
+/// Packs four vectors of channels scaled to `0.0..=255.0` into four RGBA pixels, truncating
+/// each channel.
+#[inline(always)]
+unsafe fn pack([p0, p1, p2, p3]: [__m128; 4]) -> __m128i {
+    let lo = _mm_packs_epi32(_mm_cvttps_epi32(p0), _mm_cvttps_epi32(p1));
+    let hi = _mm_packs_epi32(_mm_cvttps_epi32(p2), _mm_cvttps_epi32(p3));
+
+    _mm_packus_epi16(lo, hi)
+}
+
+/// Merges every pixel of `dest` with the pixel returned by `src` for its index, like
+/// [`Rgba::merge`], four pixels at a time.
+pub(crate) fn merge_rgba_row(dest: &mut [Rgba], src: impl Fn(usize) -> Rgba) {
+    for (i, chunk) in dest.chunks_exact_mut(4).enumerate() {
+        let overlay = [src(i * 4), src(i * 4 + 1), src(i * 4 + 2), src(i * 4 + 3)];
+        // Fully transparent and fully opaque pixels are handled separately below, since
+        // blending them in floating point may not be exact
+        if overlay.iter().all(|pixel| pixel.a == 0 || pixel.a == 255) {
+            for (target, pixel) in chunk.iter_mut().zip(overlay) {
+                if pixel.a == 255 {
+                    *target = pixel;
+                }
+            }
+            continue;
+        }
+
+        // SAFETY: `Rgba` is `repr(C)` with four `u8` fields, so four pixels are exactly 16
+        // bytes. Unaligned loads and stores are used.
+        unsafe {
+            let base = unpack(_mm_loadu_si128(chunk.as_ptr().cast()));
+            let pixels = unpack(_mm_loadu_si128(overlay.as_ptr().cast()));
+            let merged = pack([
+                merge_pixel(base[0], pixels[0]),
+                merge_pixel(base[1], pixels[1]),
+                merge_pixel(base[2], pixels[2]),
+                merge_pixel(base[3], pixels[3]),
+            ]);
+            let original = [chunk[0], chunk[1], chunk[2], chunk[3]];
+
+            _mm_storeu_si128(chunk.as_mut_ptr().cast(), merged);
+            for ((target, pixel), original) in chunk.iter_mut().zip(overlay).zip(original) {
+                match pixel.a {
+                    0 => *target = original,
+                    255 => *target = pixel,
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    let offset = dest.len() - dest.len() % 4;
+    for (i, target) in dest[offset..].iter_mut().enumerate() {
+        *target = target.merge(src(offset + i));
+    }
+}
+
+/// Mixes every byte of `base` with the byte at the same lane of `overlay`, weighted by the byte at
+/// the same lane of `weight`, as `(base * (255 - weight) + overlay * weight) / 255` rounded down.
+#[inline(always)]
+unsafe fn mix(base: __m128i, overlay: __m128i, weight: __m128i) -> __m128i {
+    let zero = _mm_setzero_si128();
+    let max = _mm_set1_epi16(255);
+    let one = _mm_set1_epi16(1);
+    let mix_half = |base, overlay, weight| {
+        let sum = _mm_add_epi16(
+            _mm_mullo_epi16(base, _mm_sub_epi16(max, weight)),
+            _mm_mullo_epi16(overlay, weight),
+        );
+        // Exact division by 255 for sums up to 255 * 255, which fit in 16 bits
+        _mm_srli_epi16::<8>(_mm_add_epi16(
+            _mm_add_epi16(sum, one),
+            _mm_srli_epi16::<8>(sum),
+        ))
+    };
+
+    _mm_packus_epi16(
+        mix_half(
+            _mm_unpacklo_epi8(base, zero),
+            _mm_unpacklo_epi8(overlay, zero),
+            _mm_unpacklo_epi8(weight, zero),
+        ),
+        mix_half(
+            _mm_unpackhi_epi8(base, zero),
+            _mm_unpackhi_epi8(overlay, zero),
+            _mm_unpackhi_epi8(weight, zero),
+        ),
+    )
+}
+
+/// Merges every pixel of `dest` with the pixel at the same index of `src` and the alpha at the
+/// same index of `alpha`, like [`Rgb::merge_with_alpha`], sixteen pixels at a time.
+///
+/// Only as many pixels as the shortest of the three slices are merged.
+pub(crate) fn merge_rgb_row(dest: &mut [Rgb], src: &[Rgb], alpha: &[u8]) {
+    let len = dest.len().min(src.len()).min(alpha.len());
+    let (dest, src, alpha) = (&mut dest[..len], &src[..len], &alpha[..len]);
+
+    for ((target, pixels), alpha) in dest
+        .chunks_exact_mut(16)
+        .zip(src.chunks_exact(16))
+        .zip(alpha.chunks_exact(16))
+    {
+        // Every channel is weighted by the alpha of its pixel
+        let mut weights = [0_u8; 48];
+        for (weight, &alpha) in weights.chunks_exact_mut(3).zip(alpha) {
+            weight.fill(alpha);
+        }
+
+        // SAFETY: `Rgb` is `repr(C)` with three `u8` fields, so sixteen pixels are exactly 48
+        // bytes, or three vectors. Unaligned loads and stores are used.
+        unsafe {
+            let target = target.as_mut_ptr().cast::<__m128i>();
+            let pixels = pixels.as_ptr().cast::<__m128i>();
+            let weights = weights.as_ptr().cast::<__m128i>();
+            for i in 0..3 {
+                let mixed = mix(
+                    _mm_loadu_si128(target.add(i)),
+                    _mm_loadu_si128(pixels.add(i)),
+                    _mm_loadu_si128(weights.add(i)),
+                );
+                _mm_storeu_si128(target.add(i), mixed);
+            }
+        }
+    }
+
+    let offset = len - len % 16;
+    for ((target, &pixel), &alpha) in dest[offset..]
+        .iter_mut()
+        .zip(&src[offset..])
+        .zip(&alpha[offset..])
+    {
+        *target = target.merge_with_alpha(pixel, alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_merge_rgba_row() {
+        let base = (0..=255)
+            .map(|i| Rgba::new(i, 255 - i, i / 2, i.wrapping_mul(7)))
+            .collect::<Vec<_>>();
+        let overlay = (0..=255)
+            .map(|i| Rgba::new(255 - i, i / 3, i, i))
+            .collect::<Vec<_>>();
+
+        let mut merged = base.clone();
+        merge_rgba_row(&mut merged, |i| overlay[i]);
+
+        for ((&base, &overlay), merged) in base.iter().zip(&overlay).zip(merged) {
+            let expected = base.merge(overlay);
+            for (a, b) in expected.as_bytes().into_iter().zip(merged.as_bytes()) {
+                assert!(a.abs_diff(b) <= 1, "{expected:?} != {merged:?}");
+            }
+        }
+    }
+    #[test]
+    fn test_merge_rgb_row() {
+        let base = (0..=255)
+            .map(|i| Rgb::new(i, 255 - i, i / 2))
+            .collect::<Vec<_>>();
+        let overlay = (0..=255)
+            .map(|i| Rgb::new(255 - i, i / 3, i))
+            .collect::<Vec<_>>();
+        let alpha = (0..=255).map(|i: u8| i.wrapping_mul(7)).collect::<Vec<_>>();
+
+        let mut merged = base.clone();
+        merge_rgb_row(&mut merged, &overlay, &alpha);
+
+        for (i, merged) in merged.into_iter().enumerate() {
+            let expected = base[i].merge_with_alpha(overlay[i], alpha[i]);
+            for (a, b) in expected.as_bytes().into_iter().zip(merged.as_bytes()) {
+                assert!(a.abs_diff(b) <= 1, "{expected:?} != {merged:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Locates a smaller image within a larger image using template matching.
+
+use crate::{parallel, Image, Pixel, L, L32F};
+use alloc::{vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The method used to score how well a template matches an area of an image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MatchMethod {
+    /// The mean of the squared differences between the luminance of the template and the area,
+    /// normalized to the range `0.0..=1.0`. Lower scores are better, and `0.0` is an exact match.
+    ///
+    /// This is fast to reason about, but sensitive to changes in brightness or contrast.
+    SumOfSquaredDifferences,
+    /// The zero-mean normalized cross correlation between the luminance of the template and the
+    /// area, in the range `-1.0..=1.0`. Higher scores are better, and `1.0` is a perfect match.
+    ///
+    /// This is robust against uniform changes in brightness and contrast. Areas or templates with
+    /// a single flat color have a score of `0.0`.
+    NormalizedCrossCorrelation,
+}
+
+impl MatchMethod {
+    /// Returns whether the first score is a better match than the second score.
+    fn is_better(self, a: f32, b: f32) -> bool {
+        match self {
+            Self::SumOfSquaredDifferences => a < b,
+            Self::NormalizedCrossCorrelation => a > b,
+        }
+    }
+}
+
+/// The result of matching a template against an image, created with [`Image::match_template`].
+#[derive(Clone)]
+pub struct TemplateMatch {
+    /// The score of every position the template can be placed at, where the pixel at `(x, y)` is
+    /// the score of placing the top-left corner of the template at `(x, y)`. The image is as large
+    /// as the source image minus the size of the template, plus one.
+    pub scores: Image<L32F>,
+    /// The method the scores were computed with.
+    pub method: MatchMethod,
+}
+
+impl TemplateMatch {
+    /// Returns the position of the top-left corner of the best match along with its score, as a
+    /// `(x, y, score)` tuple. If multiple positions have the same score, the first one in row-major
+    /// order is returned.
+    #[must_use]
+    pub fn best(&self) -> (u32, u32, f32) {
+        self.scores
+            .pixels_with_coords()
+            .fold(
+                None,
+                |best: Option<(u32, u32, f32)>, (x, y, &L32F(score))| match best {
+                    Some((_, _, best_score)) if !self.method.is_better(score, best_score) => best,
+                    _ => Some((x, y, score)),
+                },
+            )
+            .unwrap()
+    }
+
+    /// Returns the positions of the top-left corners of every match with a score at least as good
+    /// as the given threshold, as `(x, y, score)` tuples in row-major order.
+    ///
+    /// Note that areas close to a good match usually also score well, so matches are not
+    /// guaranteed to be distinct.
+    pub fn matches(&self, threshold: f32) -> impl Iterator<Item = (u32, u32, f32)> + '_ {
+        self.scores
+            .pixels_with_coords()
+            .filter(move |(_, _, &L32F(score))| !self.method.is_better(threshold, score))
+            .map(|(x, y, &L32F(score))| (x, y, score))
+    }
+}
+
+/// Computes the summed-area table of the given values and of their squares, with an extra
+/// leading row and column of zeros.
+fn integral_images(values: &[u8], width: usize) -> (Vec<u64>, Vec<u64>) {
+    let stride = width + 1;
+    let height = values.len() / width;
+    let mut sums = vec![0; stride * (height + 1)];
+    let mut squares = vec![0; stride * (height + 1)];
+
+    for y in 0..height {
+        let (mut row_sum, mut row_squares) = (0, 0);
+        for x in 0..width {
+            let value = u64::from(values[y * width + x]);
+            row_sum += value;
+            row_squares += value * value;
+
+            let i = (y + 1) * stride + x + 1;
+            sums[i] = sums[i - stride] + row_sum;
+            squares[i] = squares[i - stride] + row_squares;
+        }
+    }
+
+    (sums, squares)
+}
+
+impl<P: Pixel + Into<L>> Image<P> {
+    /// Slides the given template over every position of this image and scores how well it
+    /// matches the area below it, using the luminance of both images. This is useful for finding
+    /// sprites or UI elements in screenshots.
+    ///
+    /// If the `rayon` feature is enabled, rows of positions are scored in parallel.
+    ///
+    /// # Panics
+    /// * The template is wider or taller than this image.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// let image = Image::from_fn(32, 32, |x, y| L(((x * 7) ^ (y * 13)) as u8));
+    /// let template = image.clone().cropped(10, 12, 18, 16);
+    ///
+    /// let result = image.match_template(&template, MatchMethod::SumOfSquaredDifferences);
+    /// assert_eq!(result.best(), (10, 12, 0.0));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn match_template<T: Pixel + Into<L>>(
+        &self,
+        template: &Image<T>,
+        method: MatchMethod,
+    ) -> TemplateMatch {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let (tw, th) = (template.width() as usize, template.height() as usize);
+        assert!(
+            tw <= width && th <= height,
+            "template must not be larger than the image"
+        );
+
+        let source = self.data.iter().map(Pixel::luminance).collect::<Vec<_>>();
+        let (sums, squares) = integral_images(&source, width);
+        let window = |table: &[u64], x: usize, y: usize| {
+            let stride = width + 1;
+            (table[(y + th) * stride + x + tw] + table[y * stride + x])
+                - (table[y * stride + x + tw] + table[(y + th) * stride + x])
+        };
+
+        let area = (tw * th) as f64;
+        let template = template
+            .data
+            .iter()
+            .map(|pixel| f64::from(pixel.luminance()))
+            .collect::<Vec<_>>();
+        let template_sum = template.iter().sum::<f64>();
+        let template_squares = template.iter().map(|t| t * t).sum::<f64>();
+        let template_variance = template_squares - template_sum * template_sum / area;
+        // Subtracting the mean makes the cross term equal to the zero-mean cross correlation
+        let kernel = match method {
+            MatchMethod::SumOfSquaredDifferences => template,
+            MatchMethod::NormalizedCrossCorrelation => {
+                let mean = template_sum / area;
+                template.into_iter().map(|t| t - mean).collect()
+            }
+        };
+
+        let (out_width, out_height) = (width - tw + 1, height - th + 1);
+        let scores = parallel::map_rows(out_height as u32, |y| {
+            let y = y as usize;
+            let (source, kernel, sums, squares) = (&source, &kernel, &sums, &squares);
+            let window = &window;
+
+            (0..out_width).map(move |x| {
+                let cross = kernel
+                    .chunks_exact(tw)
+                    .zip(source[y * width..].chunks(width))
+                    .flat_map(|(k, row)| k.iter().zip(&row[x..x + tw]))
+                    .map(|(k, &s)| k * f64::from(s))
+                    .sum::<f64>();
+                let sum = window(sums, x, y) as f64;
+                let squares = window(squares, x, y) as f64;
+
+                let score = match method {
+                    MatchMethod::SumOfSquaredDifferences => {
+                        (squares - 2.0 * cross + template_squares).max(0.0) / (area * 255.0 * 255.0)
+                    }
+                    MatchMethod::NormalizedCrossCorrelation => {
+                        let variance = squares - sum * sum / area;
+                        let denominator = (variance * template_variance).sqrt();
+                        if denominator > f64::EPSILON {
+                            (cross / denominator).clamp(-1.0, 1.0)
+                        } else {
+                            0.0
+                        }
+                    }
+                };
+                L32F(score as f32)
+            })
+        });
+
+        TemplateMatch {
+            scores: Image::from_pixels(out_width as u32, scores),
+            method,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized_cross_correlation() {
+        let image = Image::from_fn(40, 30, |x, y| L(((x * 7) ^ (y * 13)) as u8 / 2));
+        // Brightness and contrast changes should not affect the score
+        let template = image
+            .clone()
+            .cropped(20, 5, 32, 14)
+            .map_pixels(|L(value)| L(value * 2 / 3 + 40));
+
+        let result = image.match_template(&template, MatchMethod::NormalizedCrossCorrelation);
+        let (x, y, score) = result.best();
+        assert_eq!((x, y), (20, 5));
+        assert!(score > 0.999);
+        assert_eq!(result.scores.dimensions(), (29, 22));
+        assert!(result.matches(0.999).all(|(x, y, _)| (x, y) == (20, 5)));
+    }
+}
@@ -0,0 +1,322 @@
+//! Previews images directly in a terminal, which is useful for debugging generated images over SSH
+//! without saving them to files.
+
+use crate::{Image, Result, Rgb, TrueColor};
+use std::io::{self, Write};
+
+/// The escape sequence protocol used to render an image in a terminal.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TerminalProtocol {
+    /// Renders two pixels per character using the upper half block character `▀`, with the top
+    /// pixel as the 24-bit foreground color and the bottom pixel as the background color. This
+    /// works in almost every modern terminal. This is the default protocol.
+    #[default]
+    HalfBlocks,
+    /// Renders the image with sixel graphics, which are supported by terminals such as xterm,
+    /// foot, WezTerm and mlterm. Colors are reduced to a fixed palette of 216 colors.
+    Sixel,
+    /// Renders the image with the kitty graphics protocol, which is supported by kitty, WezTerm
+    /// and Ghostty. This preserves every color and the transparency of the image.
+    Kitty,
+}
+
+/// Options for previewing an image in a terminal with [`Image::display_terminal`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TerminalOptions {
+    /// The protocol used to render the image. Defaults to [`TerminalProtocol::HalfBlocks`].
+    pub protocol: TerminalProtocol,
+    /// The maximum width of the preview in terminal columns, or `None` to render the image at its
+    /// original size. Half block previews are downsampled with nearest neighbor sampling to fit,
+    /// and kitty previews are scaled by the terminal. Sixel previews are always rendered at their
+    /// original size. Defaults to `Some(80)`.
+    pub max_columns: Option<u32>,
+    /// The color transparent pixels are blended onto. This is ignored by the kitty protocol,
+    /// which supports transparency. Defaults to black.
+    pub background: Rgb,
+}
+
+impl Default for TerminalOptions {
+    fn default() -> Self {
+        Self {
+            protocol: TerminalProtocol::default(),
+            max_columns: Some(80),
+            background: Rgb::black(),
+        }
+    }
+}
+
+impl TerminalOptions {
+    /// Creates new terminal options with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the protocol used to render the image.
+    #[must_use]
+    pub const fn with_protocol(mut self, protocol: TerminalProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Sets the maximum width of the preview in terminal columns, or `None` to render the image
+    /// at its original size.
+    #[must_use]
+    pub const fn with_max_columns(mut self, max_columns: Option<u32>) -> Self {
+        self.max_columns = max_columns;
+        self
+    }
+
+    /// Sets the color transparent pixels are blended onto.
+    #[must_use]
+    pub const fn with_background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+}
+
+/// Blends the given pixel onto the given background, discarding its alpha.
+#[allow(clippy::cast_possible_truncation)]
+fn flatten(pixel: &impl TrueColor, background: Rgb) -> (u8, u8, u8) {
+    let (r, g, b, a) = pixel.as_rgba_tuple();
+    let blend = |fg: u8, bg: u8| {
+        ((u32::from(fg) * u32::from(a) + u32::from(bg) * u32::from(255 - a) + 127) / 255) as u8
+    };
+
+    (
+        blend(r, background.r),
+        blend(g, background.g),
+        blend(b, background.b),
+    )
+}
+
+/// Encodes the given bytes as standard base64 with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len() / 3 * 4 + 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, &byte)| n | u32::from(byte) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Prints a preview of this image to the standard output, using the given options.
+    ///
+    /// # Errors
+    /// * `IoError`: The preview could not be written to the standard output.
+    ///
+    /// # See Also
+    /// * [`Self::write_terminal`] to write the preview to any writer.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let image = Image::<Rgba>::open("sample.png")?;
+    /// image.display_terminal(&TerminalOptions::new().with_max_columns(Some(60)))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn display_terminal(&self, options: &TerminalOptions) -> Result<()> {
+        let stdout = io::stdout();
+        let mut stdout = io::BufWriter::new(stdout.lock());
+
+        self.write_terminal(options, &mut stdout)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Writes the escape sequences which preview this image in a terminal to the given writer,
+    /// using the given options.
+    ///
+    /// # Errors
+    /// * `IoError`: The preview could not be written to the writer.
+    pub fn write_terminal(&self, options: &TerminalOptions, dest: &mut impl Write) -> Result<()> {
+        match options.protocol {
+            TerminalProtocol::HalfBlocks => self.write_half_blocks(options, dest)?,
+            TerminalProtocol::Sixel => self.write_sixel(options, dest)?,
+            TerminalProtocol::Kitty => self.write_kitty(options, dest)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_half_blocks(
+        &self,
+        options: &TerminalOptions,
+        dest: &mut impl Write,
+    ) -> io::Result<()> {
+        let (width, height) = self.dimensions();
+        let columns = options
+            .max_columns
+            .map_or(width, |max| width.min(max.max(1)));
+        // Keep the aspect ratio, where each character holds two pixels vertically
+        let rows = (u64::from(height) * u64::from(columns) / u64::from(width)).max(1) as u32;
+        let sample = |x: u32, y: u32| {
+            let x = (u64::from(x) * u64::from(width) / u64::from(columns)) as u32;
+            let y = (u64::from(y) * u64::from(height) / u64::from(rows)) as u32;
+            flatten(self.pixel(x, y), options.background)
+        };
+
+        for y in (0..rows).step_by(2) {
+            for x in 0..columns {
+                let (r, g, b) = sample(x, y);
+                if y + 1 < rows {
+                    let (br, bg, bb) = sample(x, y + 1);
+                    write!(dest, "\x1b[38;2;{r};{g};{b};48;2;{br};{bg};{bb}m\u{2580}")?;
+                } else {
+                    write!(dest, "\x1b[38;2;{r};{g};{b};49m\u{2580}")?;
+                }
+            }
+            writeln!(dest, "\x1b[0m")?;
+        }
+
+        Ok(())
+    }
+
+    fn write_sixel(&self, options: &TerminalOptions, dest: &mut impl Write) -> io::Result<()> {
+        // Index of the closest color in a 6x6x6 color cube
+        let index = |pixel: &P| {
+            let (r, g, b) = flatten(pixel, options.background);
+            let level = |c: u8| (u16::from(c) * 5 + 127) / 255;
+            level(r) * 36 + level(g) * 6 + level(b)
+        };
+        let (width, height) = self.dimensions();
+
+        // P2 = 1 leaves pixels without a color set transparent
+        write!(dest, "\x1bP0;1q\"1;1;{width};{height}")?;
+        for i in 0..216 {
+            let (r, g, b) = (i / 36 * 20, i / 6 % 6 * 20, i % 6 * 20);
+            write!(dest, "#{i};2;{r};{g};{b}")?;
+        }
+
+        let mut used = [false; 216];
+        let mut sixels = vec![0_u8; width as usize];
+        for band in (0..height).step_by(6) {
+            let rows = band..(band + 6).min(height);
+
+            used.fill(false);
+            for y in rows.clone() {
+                for x in 0..width {
+                    used[index(self.pixel(x, y)) as usize] = true;
+                }
+            }
+
+            for color in (0..216).filter(|&color| used[color as usize]) {
+                for (x, sixel) in (0..width).zip(&mut sixels) {
+                    *sixel = rows
+                        .clone()
+                        .filter(|&y| index(self.pixel(x, y)) == color)
+                        .fold(0, |bits, y| bits | 1 << (y - band));
+                }
+
+                write!(dest, "#{color}")?;
+                let mut start = 0;
+                while start < sixels.len() {
+                    let sixel = sixels[start];
+                    let len = sixels[start..].iter().take_while(|&&s| s == sixel).count();
+                    let c = (sixel + 63) as char;
+                    match len {
+                        1..=3 => write!(dest, "{}", c.to_string().repeat(len))?,
+                        len => write!(dest, "!{len}{c}")?,
+                    }
+                    start += len;
+                }
+                write!(dest, "$")?;
+            }
+            write!(dest, "-")?;
+        }
+
+        writeln!(dest, "\x1b\\")
+    }
+
+    fn write_kitty(&self, options: &TerminalOptions, dest: &mut impl Write) -> io::Result<()> {
+        const CHUNK_SIZE: usize = 4096;
+
+        let (width, height) = self.dimensions();
+        let bytes = self
+            .data
+            .iter()
+            .flat_map(|pixel| {
+                let (r, g, b, a) = pixel.as_rgba_tuple();
+                [r, g, b, a]
+            })
+            .collect::<Vec<_>>();
+        let payload = base64(&bytes);
+        let columns = options
+            .max_columns
+            .filter(|&max| max < width)
+            .map_or_else(String::new, |max| format!(",c={max}"));
+
+        let mut chunks = payload.as_bytes().chunks(CHUNK_SIZE).peekable();
+        let mut first = true;
+        while let Some(chunk) = chunks.next() {
+            let more = u8::from(chunks.peek().is_some());
+            if first {
+                write!(
+                    dest,
+                    "\x1b_Ga=T,f=32,s={width},v={height}{columns},m={more};"
+                )?;
+                first = false;
+            } else {
+                write!(dest, "\x1b_Gm={more};")?;
+            }
+            dest.write_all(chunk)?;
+            write!(dest, "\x1b\\")?;
+        }
+
+        writeln!(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rgba;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_half_blocks() -> Result<()> {
+        let image = Image::from_fn(2, 3, |x, _| {
+            if x == 0 {
+                Rgba::new(255, 0, 0, 255)
+            } else {
+                Rgba::transparent()
+            }
+        });
+        let mut out = Vec::new();
+        image.write_terminal(&TerminalOptions::new(), &mut out)?;
+
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "\x1b[38;2;255;0;0;48;2;255;0;0m\u{2580}\x1b[38;2;0;0;0;48;2;0;0;0m\u{2580}\x1b[0m"
+        );
+        assert!(lines[1].ends_with("49m\u{2580}\x1b[0m"));
+
+        Ok(())
+    }
+}
@@ -2,14 +2,29 @@
 
 #![allow(clippy::cast_precision_loss, clippy::too_many_arguments)]
 
-use crate::{Draw, Error::FontError, Image, OverlayMode, Pixel};
+use crate::{
+    colr::{ColorGlyphs, ColorLayer},
+    fill::{BoundingBox, Fill, IntoFill},
+    pixel::Dynamic,
+    Draw,
+    Error::FontError,
+    Image, OverlayMode, Pixel,
+};
 
 pub use fontdue::layout::{BlockAlign, HorizontalAlign, VerticalAlign, WrapStyle};
 use fontdue::{
-    layout::{CoordinateSystem, Layout, LayoutSettings},
-    FontSettings,
+    layout::{CoordinateSystem, GlyphRasterConfig, Layout, LayoutSettings},
+    FontSettings, Metrics,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+use std::{
+    collections::HashMap,
+    io::Read,
+    ops::DerefMut,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
 };
-use std::{fs::File, io::Read, ops::DerefMut, path::Path};
+#[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
+use std::{fs::File, path::Path};
 
 /// Represents a single font along with its alternatives used to render text.
 /// Currently, this supports TrueType and OpenType fonts.
@@ -18,6 +33,7 @@ use std::{fs::File, io::Read, ops::DerefMut, path::Path};
 pub struct Font {
     inner: fontdue::Font,
     settings: FontSettings,
+    colors: Option<ColorGlyphs>,
 }
 
 impl Font {
@@ -32,8 +48,13 @@ impl Font {
     ///
     /// # Errors
     /// * Failed to load the font.
+    #[cfg(all(feature = "std", not(all(target_arch = "wasm32", target_os = "unknown"))))]
     pub fn open<P: AsRef<Path>>(path: P, optimal_size: f32) -> crate::Result<Self> {
-        Self::from_reader(File::open(path)?, optimal_size)
+        let path = path.as_ref();
+        File::open(path)
+            .map_err(crate::Error::IoError)
+            .and_then(|file| Self::from_reader(file, optimal_size))
+            .map_err(|err| err.with_path(path))
     }
 
     /// Loads the font from the given byte slice. Useful for the `include_bytes!` macro.
@@ -54,8 +75,13 @@ impl Font {
             load_substitutions: true,
         };
         let inner = fontdue::Font::from_bytes(bytes, settings).map_err(FontError)?;
+        let colors = ColorGlyphs::parse(bytes, settings.collection_index);
 
-        Ok(Self { inner, settings })
+        Ok(Self {
+            inner,
+            settings,
+            colors,
+        })
     }
 
     /// Loads the font from the given byte reader. See [`from_bytes`] if you already have a byte
@@ -79,9 +105,14 @@ impl Font {
         let mut out = Vec::new();
         buffer.read_to_end(&mut out)?;
 
+        let colors = ColorGlyphs::parse(&out, settings.collection_index);
         let inner = fontdue::Font::from_bytes(out, settings).map_err(FontError)?;
 
-        Ok(Self { inner, settings })
+        Ok(Self {
+            inner,
+            settings,
+            colors,
+        })
     }
 
     /// Returns a reference the [`fontdue::Font`] object associated with the font.
@@ -111,6 +142,141 @@ impl Font {
     pub const fn optimal_size(&self) -> f32 {
         self.settings.scale
     }
+
+    /// Returns whether this font has color glyphs, such as emoji, which are drawn in their own
+    /// colors instead of the fill of the text.
+    ///
+    /// Color glyphs are read from the `COLR` and `CPAL` tables of the font (version 0 layers).
+    /// Bitmap color glyphs (`CBDT` and `sbix` tables) are not supported.
+    #[must_use]
+    pub const fn has_color_glyphs(&self) -> bool {
+        self.colors.is_some()
+    }
+}
+
+/// The metrics and coverage bitmap of a rasterized glyph.
+type Rasterized = Arc<(Metrics, Vec<u8>)>;
+
+#[derive(Default)]
+struct GlyphCacheEntries {
+    /// Maps glyphs to their rasterization and the tick they were last used at.
+    glyphs: HashMap<GlyphRasterConfig, (Rasterized, u64)>,
+    tick: u64,
+}
+
+/// A cache of rasterized glyphs that can be shared between [`TextLayout`]s, so that drawing the
+/// same text repeatedly, e.g. when generating many images with the same font, only rasterizes
+/// every glyph once.
+///
+/// Glyphs are keyed by their font, glyph index and size, so a single cache can be used with any
+/// number of fonts. The cache is thread-safe and can be shared between threads.
+///
+/// By default the cache grows without bound. Use [`with_limit`][GlyphCache::with_limit] to evict
+/// the least recently used glyphs once the cache is full.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let font = Font::open("Arial.ttf", 36.0)?;
+/// let cache = GlyphCache::new().with_limit(1024);
+///
+/// for name in ["Alice", "Bob", "Carol"] {
+///     let mut image = Image::new(256, 64, Rgba::black());
+///     image.draw(
+///         &TextLayout::new()
+///             .with_glyph_cache(&cache)
+///             .with_basic_text(&font, name, Rgba::white()),
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct GlyphCache {
+    entries: Mutex<GlyphCacheEntries>,
+    limit: Option<usize>,
+}
+
+impl GlyphCache {
+    /// Creates a new, empty glyph cache without a size limit.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the cache to the given amount of glyphs. Once the cache is full, the least recently
+    /// used glyph is evicted to make room for a new one.
+    ///
+    /// # Panics
+    /// * `limit` is zero.
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        assert_ne!(limit, 0, "glyph cache limit must be greater than zero");
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns the maximum amount of glyphs this cache holds, or `None` if it is unbounded.
+    #[must_use]
+    pub const fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Returns the amount of glyphs currently in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries().glyphs.len()
+    }
+
+    /// Returns whether the cache holds no glyphs.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all glyphs from the cache.
+    pub fn clear(&self) {
+        self.entries().glyphs.clear();
+    }
+
+    fn entries(&self) -> MutexGuard<'_, GlyphCacheEntries> {
+        // The entries are always left in a consistent state, so poisoning can be ignored
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the cached rasterization of the given glyph, rasterizing it first if needed.
+    fn rasterize(&self, font: &fontdue::Font, key: GlyphRasterConfig) -> Rasterized {
+        {
+            let mut entries = self.entries();
+            entries.tick += 1;
+            let tick = entries.tick;
+            if let Some((glyph, last_used)) = entries.glyphs.get_mut(&key) {
+                *last_used = tick;
+                return glyph.clone();
+            }
+        }
+
+        // Rasterize without holding the lock, so other threads aren't blocked meanwhile
+        let glyph = Arc::new(font.rasterize_config(key));
+
+        let mut entries = self.entries();
+        if let Some(limit) = self.limit {
+            if entries.glyphs.len() >= limit && !entries.glyphs.contains_key(&key) {
+                let oldest = entries
+                    .glyphs
+                    .iter()
+                    .min_by_key(|(_, (_, last_used))| *last_used)
+                    .map(|(&glyph, _)| glyph);
+                if let Some(oldest) = oldest {
+                    entries.glyphs.remove(&oldest);
+                }
+            }
+        }
+        let tick = entries.tick;
+        entries.glyphs.insert(key, (glyph.clone(), tick));
+        glyph
+    }
 }
 
 /// Represents where text is anchored horizontally.
@@ -149,41 +315,97 @@ impl Default for VerticalAnchor {
     }
 }
 
+/// A fill which has been bound to a bounding box, returning the color at the given coordinates.
+type BoundFill<'a, P> = Box<dyn Fn(u32, u32) -> P + 'a>;
+
+/// A type-erased fill of a text segment, which binds the fill to the given bounding box.
+type FillFn<'a, P> = Box<dyn Fn(BoundingBox<u32>) -> BoundFill<'a, P> + 'a>;
+
 /// Represents additional data to render text or images inside a [`TextLayout`].
 #[derive(Copy, Clone)]
 enum SpanData<'a, P: Pixel> {
-    /// Parameters for rendering text.
-    Text(P, OverlayMode),
+    /// Parameters for rendering text. The fill is an index into [`TextLayout::fills`], and the
+    /// level is the bidirectional embedding level of the text, which is odd for right-to-left
+    /// text.
+    Text {
+        segment: usize,
+        fill: usize,
+        overlay: OverlayMode,
+        stroke: Option<(P, u32)>,
+        faux_bold: u32,
+        faux_italic: f32,
+        colors: Option<&'a ColorGlyphs>,
+        level: u8,
+    },
     /// Parameters for rendering an inline image.
-    InlineImg(&'a Image<P>),
+    InlineImg { segment: usize, image: &'a Image<P> },
+}
+
+impl<'a, P: Pixel> SpanData<'a, P> {
+    /// Returns the index of the segment this span was added by.
+    const fn segment(&self) -> usize {
+        match self {
+            Self::Text { segment, .. } | Self::InlineImg { segment, .. } => *segment,
+        }
+    }
 }
 
 /// Represents a text segment that can be added to [`TextLayout`].
+///
+/// The fill of the text can be a solid color or any other [`IntoFill`] type, such as a gradient or
+/// a pattern. Fills span the bounding box of the entire text layout the segment is added to, and
+/// are evaluated for every pixel covered by the text.
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let font = Font::open("Arial.ttf", 48.0)?;
+/// let gradient = LinearGradient::new()
+///     .with_color(Rgba::new(255, 0, 0, 255))
+///     .with_color(Rgba::new(0, 0, 255, 255));
+///
+/// let tile = Image::open("tile.png")?;
+/// let layout = TextLayout::new()
+///     .with_text(&TextSegment::new(&font, "Gradient ", gradient))
+///     .with_text(&TextSegment::new(&font, "and pattern", Pattern::new(&tile)));
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Clone)]
-pub struct TextSegment<'a, P: Pixel> {
+pub struct TextSegment<'a, F: IntoFill> {
     /// The content of the text segment.
     pub text: &'a str,
     /// The font to use to render the text.
     pub font: &'a Font,
     /// Font scale. By default, the font optimal scale is used.
     pub size: f32,
-    /// The fill color the text will be in.
-    pub fill: P,
+    /// The fill of the text, i.e. a solid color or a gradient.
+    pub fill: F,
     /// The overlay mode of the text. Note that anti-aliasing is still a bit funky with
     /// [`OverlayMode::Replace`], so it is best to use [`OverlayMode::Merge`] for this, which is
     /// the default.
     pub overlay: OverlayMode,
+    /// The color and width in pixels of the outline drawn around the text, if any.
+    pub stroke: Option<(F::Pixel, u32)>,
+    /// The number of pixels glyphs are emboldened by, to simulate a bold font.
+    pub faux_bold: u32,
+    /// The horizontal shear applied to glyphs, to simulate an italic font.
+    pub faux_italic: f32,
 }
 
-impl<'a, P: Pixel> TextSegment<'a, P> {
+impl<'a, F: IntoFill> TextSegment<'a, F> {
     /// Creates
-    pub fn new(font: &'a Font, text: &'a str, fill: P) -> Self {
+    pub fn new(font: &'a Font, text: &'a str, fill: F) -> Self {
         Self {
             text,
             font,
             size: font.settings.scale,
             fill,
             overlay: OverlayMode::Merge,
+            stroke: None,
+            faux_bold: 0,
+            faux_italic: 0.0,
         }
     }
 
@@ -192,10 +414,88 @@ impl<'a, P: Pixel> TextSegment<'a, P> {
         self
     }
 
-    pub fn with_ov(mut self, overlay: OverlayMode) -> Self {
+    /// Outlines the text with the given color and width in pixels. The outline is anti-aliased and
+    /// drawn under the fill of every glyph in the layout, so it never covers neighboring glyphs.
+    /// A width of `0` removes the outline.
+    ///
+    /// Note that the outline extends past the glyphs and is not accounted for in the dimensions
+    /// of the [`TextLayout`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let font = Font::open("Arial.ttf", 48.0)?;
+    /// let segment = TextSegment::new(&font, "Hello", Rgba::white()).with_stroke(Rgba::black(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_stroke(mut self, color: F::Pixel, width: u32) -> Self {
+        self.stroke = if width > 0 {
+            Some((color, width))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Simulates a bold font by growing every glyph by `strength` pixels in every direction, for
+    /// fonts without a bold variant. A strength of `0` disables this.
+    ///
+    /// Glyphs keep their advance, so emboldened glyphs sit closer together than in a true bold
+    /// font. Around `1` pixel for every 24 pixels of font size gives a convincing result.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let font = Font::open("Arial.ttf", 48.0)?;
+    /// let segment = TextSegment::new(&font, "Hello", Rgba::white()).with_faux_bold(2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_faux_bold(mut self, strength: u32) -> Self {
+        self.faux_bold = strength;
+        self
+    }
+
+    /// Simulates an italic font by shearing every glyph around the baseline, for fonts without an
+    /// italic variant. Every row of a glyph is shifted to the right by `shear` times its height
+    /// above the baseline, so positive values lean glyphs to the right. A shear of `0.0` disables
+    /// this.
+    ///
+    /// Most italic fonts lean by around `0.2`, i.e. about 12 degrees.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let font = Font::open("Arial.ttf", 48.0)?;
+    /// let segment = TextSegment::new(&font, "Hello", Rgba::white()).with_faux_italic(0.2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_faux_italic(mut self, shear: f32) -> Self {
+        self.faux_italic = shear;
+        self
+    }
+
+    /// Sets the overlay mode of the text.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, overlay: OverlayMode) -> Self {
         self.overlay = overlay;
         self
     }
+
+    /// Sets the overlay mode of the text.
+    #[must_use]
+    #[deprecated(note = "use `with_overlay_mode` instead")]
+    pub const fn with_ov(self, overlay: OverlayMode) -> Self {
+        self.with_overlay_mode(overlay)
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -227,6 +527,79 @@ impl<'a, P: Pixel> InlineImage<'a, P> {
     }
 }
 
+/// Determines how text that does not fit inside of a [`TextLayout`] is handled.
+///
+/// Text does not fit when it exceeds the maximum width or height of the layout, or has more lines
+/// than set by [`TextLayout::with_max_lines`]. Glyphs are never cut in half - they are either
+/// drawn completely or not at all.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Text exceeding the width or height of the layout is still drawn. Lines exceeding the
+    /// maximum amount of lines are not. This is the default.
+    Visible,
+    /// Text that does not fit is not drawn.
+    Clip,
+    /// Text that does not fit is not drawn, and every line that was cut off ends with an ellipsis
+    /// ("…") rendered in the font of the last glyph on the line.
+    Ellipsis,
+}
+
+impl Default for Overflow {
+    fn default() -> Self {
+        Self::Visible
+    }
+}
+
+/// The coverage of a placed glyph after applying faux styles, positioned in the image.
+struct GlyphCoverage {
+    x: i64,
+    y: i64,
+    width: usize,
+    bitmap: Vec<u8>,
+    /// The width of the outline and its coverage, which is `2 * width` pixels wider and taller
+    /// than the glyph.
+    stroke: Option<(u32, Vec<u8>)>,
+}
+
+/// A glyph positioned for drawing, after anchoring and truncation.
+struct PlacedGlyph<'l, 'a, P: Pixel> {
+    font: &'l fontdue::Font,
+    key: Option<GlyphRasterConfig>,
+    character: char,
+    whitespace: bool,
+    data: SpanData<'a, P>,
+    line: usize,
+    x: f32,
+    y: f32,
+    width: usize,
+    height: usize,
+}
+
+/// Information about a single glyph positioned by a [`TextLayout`].
+///
+/// # See Also
+/// * [`TextLayout::glyphs`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlyphInfo {
+    /// The character the glyph represents. This is unspecified for inline images.
+    pub character: char,
+    /// The index of the segment the glyph belongs to. Text segments and inline images are counted
+    /// together, in the order they were added to the layout.
+    pub segment: usize,
+    /// The index of the line the glyph is on.
+    pub line: usize,
+    /// The x coordinate of the left edge of the glyph, after anchoring.
+    pub x: f32,
+    /// The y coordinate of the top edge of the glyph, after anchoring.
+    pub y: f32,
+    /// The width of the glyph in pixels.
+    pub width: u32,
+    /// The height of the glyph in pixels.
+    pub height: u32,
+    /// Whether the glyph is an inline image instead of text.
+    pub is_image: bool,
+}
+
 /// Represents a high-level text layout that can layout text segments, maybe with different fonts.
 ///
 /// It can be used to layout text segments with different fonts and styles, and also inline images.
@@ -237,6 +610,12 @@ impl<'a, P: Pixel> InlineImage<'a, P> {
 /// This is does not implement [`Clone`] and therefore it is not cloneable!
 pub struct TextLayout<'a, P: Pixel> {
     layout: Layout<'a, SpanData<'a, P>>,
+    fills: Vec<FillFn<'a, P>>,
+    segments: usize,
+    cache: Option<&'a GlyphCache>,
+    max_lines: Option<usize>,
+    shadow: Option<((i64, i64), u32, P)>,
+    overflow: Overflow,
     settings: LayoutSettings,
     x_anchor: HorizontalAnchor,
     y_anchor: VerticalAnchor,
@@ -248,6 +627,12 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
     pub fn new() -> Self {
         Self {
             layout: Layout::new(CoordinateSystem::PositiveYDown),
+            fills: Vec::new(),
+            segments: 0,
+            cache: None,
+            max_lines: None,
+            shadow: None,
+            overflow: Overflow::default(),
             settings: LayoutSettings::default(),
             x_anchor: HorizontalAnchor::default(),
             y_anchor: VerticalAnchor::default(),
@@ -361,6 +746,69 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
         self
     }
 
+    /// Draws a shadow beneath the text, which is a copy of the text (including any outlines)
+    /// moved by the given offset, blurred by the given radius in pixels and filled with the given
+    /// color. A blur radius of `0` draws a hard shadow.
+    ///
+    /// Unlike most other settings, this can be set at any time.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let font = Font::open("Arial.ttf", 36.0)?;
+    /// let layout = TextLayout::new()
+    ///     .with_position(16, 16)
+    ///     .with_shadow((4, 4), 6, Rgba::new(0, 0, 0, 160))
+    ///     .with_basic_text(&font, "Shadowed", Rgba::white());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_shadow(mut self, offset: (i64, i64), blur_radius: u32, color: P) -> Self {
+        self.shadow = Some((offset, blur_radius, color));
+        self
+    }
+
+    /// Limits the text to the given amount of lines. Lines past the limit are not drawn, and the
+    /// last line ends with an ellipsis if the overflow mode is [`Overflow::Ellipsis`].
+    ///
+    /// Unlike most other settings, this can be set at any time.
+    ///
+    /// # Panics
+    /// * `lines` is zero.
+    #[must_use]
+    pub fn with_max_lines(mut self, lines: usize) -> Self {
+        assert_ne!(lines, 0, "text must be allowed at least one line");
+        self.max_lines = Some(lines);
+        self
+    }
+
+    /// Sets how text that does not fit inside of the width, height or maximum amount of lines of
+    /// the layout is handled. See [`Overflow`] for more information.
+    ///
+    /// Unlike most other settings, this can be set at any time.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let font = Font::open("Arial.ttf", 24.0)?;
+    /// let layout = TextLayout::<Rgba>::new()
+    ///     .with_wrap(WrapStyle::Word)
+    ///     .with_width(200)
+    ///     .with_max_lines(2)
+    ///     .with_overflow(Overflow::Ellipsis)
+    ///     .with_basic_text(&font, "A rather long caption that won't fit", Rgba::white());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
     /// Sets both horizontal and vertical anchor and alignment of the text to be centered.
     /// This makes the position of the text be the center as opposed to the top-left corner.
     ///
@@ -377,22 +825,180 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
         self
     }
 
+    /// Rasterizes glyphs through the given [`GlyphCache`] when drawing this layout, so that
+    /// glyphs which were already rasterized by other layouts sharing the cache are reused.
+    #[must_use]
+    pub fn with_glyph_cache(mut self, cache: &'a GlyphCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Rasterizes the given glyph, going through the glyph cache if one is set.
+    fn rasterize(&self, font: &fontdue::Font, key: GlyphRasterConfig) -> Rasterized {
+        match self.cache {
+            Some(cache) => cache.rasterize(font, key),
+            None => Arc::new(font.rasterize_config(key)),
+        }
+    }
+
+    /// Rasterizes the given glyph and applies the faux styles and outline of its segment. Returns
+    /// `None` for glyphs without any coverage.
+    fn coverage(&self, glyph: &PlacedGlyph<P>) -> Option<GlyphCoverage> {
+        let (key, bold, italic, stroke) = match (glyph.key, glyph.data) {
+            (
+                Some(key),
+                SpanData::Text {
+                    faux_bold,
+                    faux_italic,
+                    stroke,
+                    ..
+                },
+            ) if !glyph.whitespace => (key, faux_bold, faux_italic, stroke),
+            _ => return None,
+        };
+        let raster = self.rasterize(glyph.font, key);
+        let (metrics, bitmap) = &*raster;
+        if metrics.width == 0 || metrics.height == 0 {
+            return None;
+        }
+
+        let mut coverage = GlyphCoverage {
+            x: glyph.x as i64,
+            y: glyph.y as i64,
+            width: metrics.width,
+            bitmap: bitmap.clone(),
+            stroke: None,
+        };
+        if bold > 0 {
+            coverage.bitmap = dilate_coverage(&coverage.bitmap, coverage.width, bold);
+            coverage.x -= i64::from(bold);
+            coverage.y -= i64::from(bold);
+            coverage.width += 2 * bold as usize;
+        }
+        if italic.abs() > f32::EPSILON {
+            // Shear around the baseline, so that glyphs stay on their line
+            let baseline = metrics.height as i64 + i64::from(metrics.ymin) + glyph.y as i64;
+            let (bitmap, width, offset) = shear_coverage(
+                &coverage.bitmap,
+                coverage.width,
+                (baseline - coverage.y) as f32,
+                italic,
+            );
+            coverage.bitmap = bitmap;
+            coverage.width = width;
+            coverage.x += offset;
+        }
+        if let Some((_, width)) = stroke {
+            let outline = dilate_coverage(&coverage.bitmap, coverage.width, width);
+            coverage.stroke = Some((width, outline));
+        }
+        Some(coverage)
+    }
+
+    /// Draws the layers of a color glyph in place of the given glyph. Layers without a color of
+    /// their own are drawn with the fill of the text.
+    fn draw_color_glyph(
+        &self,
+        image: &mut Image<P>,
+        glyph: &PlacedGlyph<P>,
+        key: GlyphRasterConfig,
+        layers: &[ColorLayer],
+        overlay: OverlayMode,
+        colors: &mut Vec<P>,
+        fill: &BoundFill<P>,
+    ) {
+        // Layers are positioned relative to the origin of the base glyph
+        let base = glyph.font.metrics_indexed(key.glyph_index, key.px);
+        let origin = glyph.x as i64 - i64::from(base.xmin);
+        let baseline = glyph.y as i64 + base.height as i64 + i64::from(base.ymin);
+
+        for &(glyph_index, color) in layers {
+            let raster = self.rasterize(glyph.font, GlyphRasterConfig { glyph_index, ..key });
+            let (metrics, bitmap) = &*raster;
+            if metrics.width == 0 || metrics.height == 0 {
+                continue;
+            }
+
+            let x = origin + i64::from(metrics.xmin);
+            let y = baseline - metrics.height as i64 - i64::from(metrics.ymin);
+            match color {
+                Some(color) => {
+                    let color = P::from_dynamic(Dynamic::Rgba(color));
+                    blend_coverage(
+                        image,
+                        x,
+                        y,
+                        bitmap,
+                        metrics.width,
+                        overlay,
+                        colors,
+                        |_, _| color,
+                    );
+                }
+                None => blend_coverage(image, x, y, bitmap, metrics.width, overlay, colors, fill),
+            }
+        }
+    }
+
     /// Adds a text segment to the text layout.
-    pub fn push_text(&mut self, segment: &TextSegment<'a, P>) {
-        let user_data = SpanData::Text(segment.fill, segment.overlay);
-        self.layout
-            .append(&fontdue::layout::Span::text_with_user_data(
-                segment.text,
-                segment.size,
-                segment.font.inner(),
-                user_data,
-            ));
+    ///
+    /// With the `arabic-shaping` feature, Arabic letters are replaced with their contextual
+    /// presentation forms before the segment is laid out, and right-to-left text is reordered into
+    /// visual order for every line after it has been wrapped.
+    ///
+    /// This is not full OpenType shaping: scripts which rely on the font's own substitution and
+    /// positioning tables, such as Indic scripts, and combining marks are still laid out one
+    /// glyph per character.
+    pub fn push_text<F>(&mut self, segment: &TextSegment<'a, F>)
+    where
+        F: IntoFill<Pixel = P>,
+        F::Fill: 'a,
+    {
+        let fill = segment.fill.clone().into_fill();
+        self.fills
+            .push(Box::new(move |bounding_box: BoundingBox<u32>| -> BoundFill<'a, P> {
+                let fill = fill.clone().with_bounding_box(bounding_box);
+                Box::new(move |x, y| fill.get_pixel(x, y))
+            }));
+
+        let (index, fill) = (self.segments, self.fills.len() - 1);
+        let user_data = |level| SpanData::Text {
+            segment: index,
+            fill,
+            overlay: segment.overlay,
+            stroke: segment.stroke,
+            faux_bold: segment.faux_bold,
+            faux_italic: segment.faux_italic,
+            colors: segment.font.colors.as_ref(),
+            level,
+        };
+        self.segments += 1;
+        // Shaped text is split into pieces borrowed from the segment or static presentation forms,
+        // which are kept in logical order so that lines are wrapped in logical order
+        #[cfg(feature = "arabic-shaping")]
+        let pieces = crate::shaping::shape(segment.text);
+        #[cfg(not(feature = "arabic-shaping"))]
+        let pieces = [(segment.text, 0)];
+
+        for (text, level) in pieces {
+            self.layout
+                .append(&fontdue::layout::Span::text_with_user_data(
+                    text,
+                    segment.size,
+                    segment.font.inner(),
+                    user_data(level),
+                ));
+        }
     }
 
     /// Takes this text layout and returns it with the given text segment added to the text layout.
     /// Useful for method chaining.
     #[must_use]
-    pub fn with_text(mut self, segment: &TextSegment<'a, P>) -> Self {
+    pub fn with_text<F>(mut self, segment: &TextSegment<'a, F>) -> Self
+    where
+        F: IntoFill<Pixel = P>,
+        F::Fill: 'a,
+    {
         self.push_text(segment);
         self
     }
@@ -405,7 +1011,11 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
     /// # Note
     /// The overlay mode is set to [`OverlayMode::Merge`] and not the image's overlay mode, since
     /// anti-aliasing is funky with the replace overlay mode.
-    pub fn push_basic_text(&mut self, font: &'a Font, text: &'a str, fill: P) {
+    pub fn push_basic_text<F>(&mut self, font: &'a Font, text: &'a str, fill: F)
+    where
+        F: IntoFill<Pixel = P>,
+        F::Fill: 'a,
+    {
         self.push_text(&TextSegment::new(font, text, fill));
     }
 
@@ -419,14 +1029,22 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
     /// # See Also
     /// * [`push_basic_text`][TextLayout::push_basic_text]
     #[must_use]
-    pub fn with_basic_text(mut self, font: &'a Font, text: &'a str, fill: P) -> Self {
+    pub fn with_basic_text<F>(mut self, font: &'a Font, text: &'a str, fill: F) -> Self
+    where
+        F: IntoFill<Pixel = P>,
+        F::Fill: 'a,
+    {
         self.push_basic_text(font, text, fill);
         self
     }
 
     /// Adds an inline to the text layout.
     pub fn push_image(&mut self, img: &InlineImage<'a, P>) {
-        let user_data = SpanData::InlineImg(img.image);
+        let user_data = SpanData::InlineImg {
+            segment: self.segments,
+            image: img.image,
+        };
+        self.segments += 1;
         let (w, h) = img.image.dimensions();
         self.layout.append(&fontdue::layout::Span::block(
             w as usize,
@@ -481,7 +1099,7 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
     /// If you want both width and height, use [`dimensions`][TextLayout::dimensions].
     #[must_use]
     pub fn height(&self) -> u32 {
-        self.layout.height() as u32
+        self.text_height() as u32
     }
 
     /// Returns the width and height of the text. This is a slightly expensive operation and should
@@ -521,6 +1139,196 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
         (x, y, x + width, y + height)
     }
 
+    /// Returns an iterator over every glyph in this layout, in the order they were laid out and
+    /// positioned exactly where they would be drawn. This is useful for hit-testing, or to draw
+    /// highlights behind specific words before drawing the text.
+    ///
+    /// Like [`width`][TextLayout::width], this is a slightly expensive operation when the text is
+    /// anchored to anything but the top-left corner.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let font = Font::open("Arial.ttf", 36.0)?;
+    /// let layout = TextLayout::<Rgba>::new()
+    ///     .with_position(16, 16)
+    ///     .with_basic_text(&font, "Hello, world!", Rgba::white());
+    ///
+    /// // Find the glyph at the given point
+    /// let (x, y) = (40.0, 30.0);
+    /// let hit = layout.glyphs().find(|glyph| {
+    ///     (glyph.x..glyph.x + glyph.width as f32).contains(&x)
+    ///         && (glyph.y..glyph.y + glyph.height as f32).contains(&y)
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn glyphs(&self) -> impl Iterator<Item = GlyphInfo> {
+        self.place()
+            .into_iter()
+            .map(|glyph| GlyphInfo {
+                character: glyph.character,
+                segment: glyph.data.segment(),
+                line: glyph.line,
+                x: glyph.x,
+                y: glyph.y,
+                width: glyph.width as u32,
+                height: glyph.height as u32,
+                is_image: matches!(glyph.data, SpanData::InlineImg { .. }),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns the amount of lines that are drawn, taking the maximum amount of lines and, unless
+    /// overflow is visible, the maximum height into account.
+    fn kept_lines(&self) -> usize {
+        let lines = match self.layout.lines() {
+            Some(lines) => lines,
+            None => return 0,
+        };
+        let mut kept = lines.len().min(self.max_lines.unwrap_or(usize::MAX));
+
+        let clip = self.overflow != Overflow::Visible;
+        if let (Some(max_height), true) = (self.settings.max_height, clip) {
+            let mut height = 0.0;
+            let fitting = lines
+                .iter()
+                .take_while(|line| {
+                    height += line.max_new_line_size * self.settings.line_height;
+                    height <= max_height
+                })
+                .count();
+            kept = kept.min(fitting);
+        }
+        kept
+    }
+
+    /// Returns the height of the lines that are drawn.
+    fn text_height(&self) -> f32 {
+        let kept = self.kept_lines();
+        match self.layout.lines() {
+            Some(lines) if kept < lines.len() => lines[..kept]
+                .iter()
+                .map(|line| line.max_new_line_size * self.settings.line_height)
+                .sum(),
+            _ => self.layout.height(),
+        }
+    }
+
+    /// Positions every glyph that is drawn, applying the anchor and truncating overflowing text.
+    fn place(&self) -> Vec<PlacedGlyph<'_, 'a, P>> {
+        let lines = match self.layout.lines() {
+            Some(lines) => lines,
+            None => return Vec::new(),
+        };
+        let glyphs = self.layout.glyphs();
+        let (ox, oy) = self.offsets();
+        let kept = self.kept_lines();
+        let right = match self.overflow {
+            Overflow::Visible => None,
+            _ => self.settings.max_width.map(|width| self.settings.x + width),
+        };
+        let fits = |x: f32| !matches!(right, Some(right) if x > right);
+
+        let mut placed = Vec::new();
+        for (i, line) in lines[..kept].iter().enumerate() {
+            let line_glyphs = &glyphs[line.glyph_start..=line.glyph_end];
+            let mut visible = line_glyphs
+                .iter()
+                .take_while(|glyph| fits(glyph.x + glyph.width as f32))
+                .count();
+            let truncated = visible < line_glyphs.len() || (i + 1 == kept && kept < lines.len());
+
+            // The ellipsis takes the font, size and style of the last text glyph on the line
+            let mut ellipsis = None;
+            if let (Overflow::Ellipsis, true) = (self.overflow, truncated) {
+                let last = line_glyphs[..visible.max(1)]
+                    .iter()
+                    .rev()
+                    .find(|glyph| glyph.key.is_some() && !glyph.char_data.is_whitespace())
+                    .or_else(|| line_glyphs.iter().find(|glyph| glyph.key.is_some()));
+
+                if let Some(last) = last {
+                    let font = last.font;
+                    let px = last.key.unwrap().px;
+                    // Fall back to three periods for fonts without an ellipsis glyph
+                    let (character, count) = match font.lookup_glyph_index('…') {
+                        0 => ('.', 3),
+                        _ => ('…', 1),
+                    };
+                    let index = font.lookup_glyph_index(character);
+                    let metrics = font.metrics_indexed(index, px);
+                    let advance = metrics.advance_width.ceil() * count as f32;
+
+                    // The pen position after every glyph
+                    let pens = line_glyphs[..visible]
+                        .iter()
+                        .map(|glyph| match glyph.key {
+                            Some(key) => {
+                                let metrics = font.metrics_indexed(key.glyph_index, key.px);
+                                glyph.x - metrics.bounds.xmin.floor() + metrics.advance_width.ceil()
+                            }
+                            None => glyph.x + glyph.width as f32,
+                        })
+                        .collect::<Vec<_>>();
+                    // Remove glyphs until the ellipsis fits after the last remaining glyph
+                    while visible > 0 {
+                        let glyph = &line_glyphs[visible - 1];
+                        if !glyph.char_data.is_whitespace() && fits(pens[visible - 1] + advance) {
+                            break;
+                        }
+                        visible -= 1;
+                    }
+
+                    let start = match visible {
+                        0 => line_glyphs[0].x,
+                        _ => pens[visible - 1],
+                    };
+                    let key = GlyphRasterConfig {
+                        glyph_index: index,
+                        px,
+                        font_hash: font.file_hash(),
+                    };
+                    let step = metrics.advance_width.ceil();
+                    let top = (-metrics.bounds.height - metrics.bounds.ymin).floor();
+                    ellipsis = Some((0..count).map(move |n| PlacedGlyph {
+                        font,
+                        key: Some(key),
+                        character,
+                        whitespace: false,
+                        data: last.user_data,
+                        line: i,
+                        x: (start + step * n as f32 + metrics.bounds.xmin).floor() + ox,
+                        y: line.baseline_y + top + oy,
+                        width: metrics.width,
+                        height: metrics.height,
+                    }));
+                }
+            }
+
+            #[cfg(feature = "arabic-shaping")]
+            let start = placed.len();
+            placed.extend(line_glyphs[..visible].iter().map(|glyph| PlacedGlyph {
+                font: glyph.font,
+                key: glyph.key,
+                character: glyph.parent,
+                whitespace: glyph.char_data.is_whitespace(),
+                data: glyph.user_data,
+                line: i,
+                x: glyph.x + ox,
+                y: glyph.y + oy,
+                width: glyph.width,
+                height: glyph.height,
+            }));
+            #[cfg(feature = "arabic-shaping")]
+            reorder_line(&mut placed[start..]);
+            placed.extend(ellipsis.into_iter().flatten());
+        }
+        placed
+    }
+
     fn offsets(&self) -> (f32, f32) {
         let ox = match self.x_anchor {
             HorizontalAnchor::Left => 0.0,
@@ -549,13 +1357,13 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
             VerticalAnchor::Center => {
                 self.settings
                     .max_height
-                    .unwrap_or_else(|| self.layout.height())
+                    .unwrap_or_else(|| self.text_height())
                     * -0.5
             }
             VerticalAnchor::Bottom => {
                 self.settings
                     .max_height
-                    .unwrap_or_else(|| self.layout.height())
+                    .unwrap_or_else(|| self.text_height())
                     * -1.0
             }
         };
@@ -563,52 +1371,384 @@ impl<'a, P: Pixel> TextLayout<'a, P> {
     }
 }
 
+/// Moves the glyphs of a single line from logical into visual order, keeping the line where it
+/// starts. Every base glyph is moved together with the diacritics following it, and inline images
+/// take the embedding level of the glyph before them.
+#[cfg(feature = "arabic-shaping")]
+fn reorder_line<P: Pixel>(glyphs: &mut [PlacedGlyph<'_, '_, P>]) {
+    let mut clusters: Vec<core::ops::Range<usize>> = Vec::new();
+    let mut levels = Vec::new();
+    let mut level = 0;
+    for (i, glyph) in glyphs.iter().enumerate() {
+        level = match glyph.data {
+            SpanData::Text { level, .. } => level,
+            SpanData::InlineImg { .. } => level,
+        };
+        match clusters.last_mut() {
+            Some(cluster) if crate::shaping::is_transparent(glyph.character) => cluster.end = i + 1,
+            _ => {
+                clusters.push(i..i + 1);
+                levels.push(level);
+            }
+        }
+    }
+    // Lines without right-to-left text are already in visual order
+    if levels.iter().all(|level| level % 2 == 0) {
+        return;
+    }
+
+    // The pen position before every glyph, undoing the horizontal bearing of the glyph, and the
+    // pen position after the last glyph
+    let pens = glyphs
+        .iter()
+        .map(|glyph| match glyph.key {
+            Some(key) => {
+                let metrics = glyph.font.metrics_indexed(key.glyph_index, key.px);
+                glyph.x - metrics.bounds.xmin.floor()
+            }
+            None => glyph.x,
+        })
+        .collect::<Vec<_>>();
+    let last = &glyphs[glyphs.len() - 1];
+    let end = match last.key {
+        Some(key) => {
+            let metrics = last.font.metrics_indexed(key.glyph_index, key.px);
+            pens[pens.len() - 1] + metrics.advance_width
+        }
+        None => last.x + last.width as f32,
+    };
+
+    let mut pen = pens[0];
+    let mut shifts = vec![0.0; glyphs.len()];
+    for i in crate::shaping::visual_order(&levels) {
+        let cluster = clusters[i].clone();
+        let advance = pens.get(cluster.end).copied().unwrap_or(end) - pens[cluster.start];
+        shifts[cluster.clone()].fill(pen - pens[cluster.start]);
+        pen += advance;
+    }
+    for (glyph, shift) in glyphs.iter_mut().zip(shifts) {
+        glyph.x += shift;
+    }
+}
+
+/// Blends the given coverage bitmap onto the image with its top-left corner at the given
+/// position, taking the color of every covered pixel from the given function.
+fn blend_coverage<P: Pixel>(
+    image: &mut Image<P>,
+    x: i64,
+    y: i64,
+    coverage: &[u8],
+    stride: usize,
+    overlay: OverlayMode,
+    colors: &mut Vec<P>,
+    color: impl Fn(u32, u32) -> P,
+) {
+    let (width, height) = image.dimensions();
+
+    for (row, y) in coverage.chunks_exact(stride).zip(y..) {
+        if y < 0 || y >= i64::from(height) {
+            continue;
+        }
+
+        // Clip the row to the image once instead of checking every pixel
+        let start = (-x).max(0) as usize;
+        let end = (i64::from(width) - x).clamp(0, row.len() as i64) as usize;
+        let offset = y as usize * width as usize;
+
+        let mut i = start;
+        while i < end {
+            // Skip pixels the glyph does not cover, then blend the next run of covered pixels as
+            // a whole slice
+            if row[i] == 0 {
+                i += 1;
+                continue;
+            }
+            let run = row[i..end].iter().take_while(|&&v| v != 0).count();
+            let dx = (x + i as i64) as usize;
+
+            colors.clear();
+            colors.extend((dx..dx + run).map(|px| color(px as u32, y as u32)));
+            P::overlay_row_with_alpha(
+                &mut image.data[offset + dx..offset + dx + run],
+                colors,
+                overlay,
+                &row[i..i + run],
+            );
+            i += run;
+        }
+    }
+}
+
+/// Blurs every row of the given coverage bitmap with a box of the given radius, treating pixels
+/// outside of the bitmap as uncovered.
+fn blur_rows(coverage: &mut [u8], width: usize, radius: usize) {
+    let size = (2 * radius + 1) as u32;
+    let mut row = Vec::with_capacity(width);
+
+    for chunk in coverage.chunks_exact_mut(width) {
+        row.clear();
+        row.extend_from_slice(chunk);
+
+        let mut sum = row[..radius.min(width)]
+            .iter()
+            .map(|&v| u32::from(v))
+            .sum::<u32>();
+        for (x, value) in chunk.iter_mut().enumerate() {
+            if let Some(&entering) = row.get(x + radius) {
+                sum += u32::from(entering);
+            }
+            *value = (sum / size) as u8;
+            if let Some(leaving) = (x + radius).checked_sub(2 * radius).map(|x| row[x]) {
+                sum -= u32::from(leaving);
+            }
+        }
+    }
+}
+
+/// Returns the transpose of the given bitmap.
+fn transpose(coverage: &[u8], width: usize) -> Vec<u8> {
+    let height = coverage.len() / width;
+    (0..width)
+        .flat_map(|x| (0..height).map(move |y| coverage[y * width + x]))
+        .collect()
+}
+
+/// Blurs the given coverage bitmap in both directions with three passes of a box blur, which
+/// closely approximates a gaussian blur reaching `radius` pixels in every direction.
+fn blur_coverage(coverage: &mut Vec<u8>, width: usize, radius: u32) {
+    let height = coverage.len() / width;
+    let radius = (radius as usize + 2) / 3;
+
+    for _ in 0..3 {
+        blur_rows(coverage, width, radius);
+    }
+    let mut transposed = transpose(coverage, width);
+    for _ in 0..3 {
+        blur_rows(&mut transposed, height, radius);
+    }
+    *coverage = transpose(&transposed, height);
+}
+
+/// Shears the given coverage bitmap horizontally, shifting every row to the right by `shear`
+/// times its distance above `baseline`, given in pixels from the top of the bitmap. Pixels are
+/// split between the two pixels they overlap after shifting, to keep edges anti-aliased.
+///
+/// Returns the sheared bitmap, its width and the horizontal offset of its left edge.
+fn shear_coverage(
+    coverage: &[u8],
+    width: usize,
+    baseline: f32,
+    shear: f32,
+) -> (Vec<u8>, usize, i64) {
+    let height = coverage.len() / width;
+    let shift = |y: usize| shear * (baseline - y as f32 - 0.5);
+    let (top, bottom) = (shift(0), shift(height - 1));
+    let left = top.min(bottom).floor();
+    let out_width = width + (top.max(bottom) - left) as usize + 1;
+    let mut out = vec![0; out_width * height];
+
+    for (y, row) in coverage.chunks_exact(width).enumerate() {
+        let shift = shift(y) - left;
+        let whole = shift as usize;
+        let fract = shift.fract();
+        let at = |x: usize, offset: usize| {
+            x.checked_sub(offset)
+                .and_then(|x| row.get(x))
+                .map_or(0.0, |&value| f32::from(value))
+        };
+
+        for (x, target) in out[y * out_width..][..out_width].iter_mut().enumerate() {
+            let (a, b) = (at(x, whole), at(x, whole + 1));
+            *target = (a + (b - a) * fract).round() as u8;
+        }
+    }
+    (out, out_width, left as i64)
+}
+
+/// Grows the given glyph coverage bitmap by `radius` pixels in every direction using an
+/// anti-aliased disk, returning a bitmap that is `2 * radius` pixels wider and taller.
+fn dilate_coverage(coverage: &[u8], width: usize, radius: u32) -> Vec<u8> {
+    let r = radius as i64;
+    let kernel = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+        .filter_map(|(dx, dy)| {
+            let distance = ((dx * dx + dy * dy) as f32).sqrt();
+            let weight = ((radius as f32 + 0.5 - distance).clamp(0.0, 1.0) * 255.0).round() as u16;
+            if weight > 0 {
+                Some((dx, dy, weight))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let height = coverage.len() / width;
+    let stride = width + 2 * radius as usize;
+    let mut out = vec![0; stride * (height + 2 * radius as usize)];
+
+    for (y, row) in coverage.chunks_exact(width).enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            for &(dx, dy, weight) in &kernel {
+                let i = (y as i64 + r + dy) as usize * stride + (x as i64 + r + dx) as usize;
+                out[i] = out[i].max((u16::from(value) * weight / 255) as u8);
+            }
+        }
+    }
+    out
+}
+
 impl<'a, P: Pixel> Draw<P> for TextLayout<'a, P> {
     fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
-        if let Some(lines) = self.layout.lines() {
-            let image = &mut *image;
-            let glyphs = self.layout.glyphs();
-            let (ox, oy) = self.offsets();
-            for line in lines.iter() {
-                for glyph in &glyphs[line.glyph_start..=line.glyph_end] {
-                    let font = glyph.font;
-                    let x = (glyph.x + ox) as i64;
-                    let y = (glyph.y + oy) as i64;
-                    match glyph.user_data {
-                        SpanData::Text(fill, overlay) => {
-                            let (metrics, bitmap) = font.rasterize_config(glyph.key.unwrap());
-                            if metrics.width == 0
-                                || glyph.char_data.is_whitespace()
-                                || metrics.height == 0
-                            {
-                                continue;
-                            }
+        let image = &mut *image;
+        let placed = self.place();
+        let bounding_box = self.bounding_box();
+        let fills = self
+            .fills
+            .iter()
+            .map(|fill| fill(bounding_box))
+            .collect::<Vec<_>>();
+        // Rasterize every glyph once, since shadows, outlines and fills are drawn in separate
+        // passes
+        let coverages = placed
+            .iter()
+            .map(|glyph| self.coverage(glyph))
+            .collect::<Vec<_>>();
+        // Reused between runs of covered pixels
+        let mut colors = Vec::new();
 
-                            for (row, y) in bitmap.chunks_exact(metrics.width).zip(y..) {
-                                for (value, x) in row.iter().zip(x..) {
-                                    let (x, y) = if x < 0 || y < 0 {
-                                        continue;
-                                    } else {
-                                        (x as u32, y as u32)
-                                    };
-
-                                    let value = *value;
-                                    if value == 0 {
-                                        continue;
-                                    }
-
-                                    if let Some(pixel) = image.get_pixel(x, y) {
-                                        *image.pixel_mut(x, y) =
-                                            pixel.overlay_with_alpha(fill, overlay, value);
-                                    }
-                                }
-                            }
+        if let Some(((dx, dy), blur, color)) = self.shadow {
+            // Combine the coverage of every glyph into a single mask, so that overlapping glyphs
+            // don't darken the shadow, and leave room for the blur around it
+            let margin = ((blur as usize + 2) / 3 * 3) as i64;
+            let bounds = coverages
+                .iter()
+                .flatten()
+                .map(|coverage| {
+                    let grow = coverage.stroke.as_ref().map_or(0, |&(w, _)| i64::from(w));
+                    let height = (coverage.bitmap.len() / coverage.width) as i64;
+                    (
+                        coverage.x - grow,
+                        coverage.y - grow,
+                        coverage.x + coverage.width as i64 + grow,
+                        coverage.y + height + grow,
+                    )
+                })
+                .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)));
+
+            if let Some((x1, y1, x2, y2)) = bounds {
+                let (x1, y1) = (x1 - margin, y1 - margin);
+                let width = (x2 + margin - x1) as usize;
+                let height = (y2 + margin - y1) as usize;
+                let mut mask = vec![0_u8; width * height];
+
+                for coverage in coverages.iter().flatten() {
+                    let (bitmap, stride, grow) = match coverage.stroke {
+                        Some((w, ref stroke)) => {
+                            (stroke, coverage.width + 2 * w as usize, i64::from(w))
                         }
-                        SpanData::InlineImg(other) => {
-                            image.paste(x, y, other);
+                        None => (&coverage.bitmap, coverage.width, 0),
+                    };
+                    let x = (coverage.x - grow - x1) as usize;
+                    let y = (coverage.y - grow - y1) as usize;
+
+                    for (row, y) in bitmap.chunks_exact(stride).zip(y..) {
+                        let out = &mut mask[y * width + x..][..stride];
+                        for (target, &value) in out.iter_mut().zip(row) {
+                            *target = (*target).max(value);
                         }
                     }
                 }
+
+                if blur > 0 {
+                    blur_coverage(&mut mask, width, blur);
+                }
+                blend_coverage(
+                    image,
+                    x1 + dx,
+                    y1 + dy,
+                    &mask,
+                    width,
+                    OverlayMode::Merge,
+                    &mut colors,
+                    |_, _| color,
+                );
+            }
+        }
+
+        // Outlines are drawn in a separate pass first, so that they never cover the fill of
+        // neighboring glyphs
+        for (glyph, coverage) in placed.iter().zip(&coverages) {
+            if let (
+                Some(GlyphCoverage {
+                    x,
+                    y,
+                    width,
+                    stroke: Some((stroke_width, stroke)),
+                    ..
+                }),
+                SpanData::Text {
+                    overlay,
+                    stroke: Some((color, _)),
+                    ..
+                },
+            ) = (coverage, glyph.data)
+            {
+                let grow = i64::from(*stroke_width);
+                let stride = width + 2 * *stroke_width as usize;
+                blend_coverage(
+                    image,
+                    x - grow,
+                    y - grow,
+                    stroke,
+                    stride,
+                    overlay,
+                    &mut colors,
+                    |_, _| color,
+                );
+            }
+        }
+
+        for (glyph, coverage) in placed.iter().zip(&coverages) {
+            // Color glyphs are drawn as a stack of layers in place of the base glyph
+            if let (
+                SpanData::Text {
+                    fill,
+                    overlay,
+                    colors: Some(color_glyphs),
+                    ..
+                },
+                Some(key),
+            ) = (glyph.data, glyph.key)
+            {
+                let layers = color_glyphs.layers(key.glyph_index);
+                if let (Some(layers), false) = (layers, glyph.whitespace) {
+                    let fill = &fills[fill];
+                    self.draw_color_glyph(image, glyph, key, layers, overlay, &mut colors, fill);
+                    continue;
+                }
+            }
+
+            match (glyph.data, coverage) {
+                (SpanData::Text { fill, overlay, .. }, Some(coverage)) => {
+                    let fill = &fills[fill];
+                    blend_coverage(
+                        image,
+                        coverage.x,
+                        coverage.y,
+                        &coverage.bitmap,
+                        coverage.width,
+                        overlay,
+                        &mut colors,
+                        |x, y| fill(x, y),
+                    );
+                }
+                (SpanData::InlineImg { image: other, .. }, _) => {
+                    image.paste(glyph.x as i64, glyph.y as i64, other);
+                }
+                _ => {}
             }
         }
     }
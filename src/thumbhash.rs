@@ -0,0 +1,327 @@
+//! Encoding and decoding of [ThumbHash](https://evanw.github.io/thumbhash/) placeholders, which
+//! are compact binary descriptions of a blurred version of an image. Compared to
+//! [BlurHash](crate::blurhash), ThumbHash preserves transparency and the aspect ratio of the
+//! image, and usually captures more detail in fewer bytes.
+//!
+//! See [`Image::to_thumbhash`] and [`Image::from_thumbhash`].
+
+use crate::{Error, Image, Result, TrueColor};
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// The largest width and height of an image which can be encoded into a ThumbHash.
+const MAX_SIZE: u32 = 100;
+
+/// Encodes the given channel with the discrete cosine transform into its DC term, its AC terms
+/// normalized to `0.0..=1.0` and the scale of the AC terms.
+fn encode_channel(channel: &[f32], width: u32, nx: u32, ny: u32) -> (f32, Vec<f32>, f32) {
+    let height = channel.len() as u32 / width;
+    let (mut dc, mut ac, mut scale) = (0., Vec::new(), 0_f32);
+
+    for cy in 0..ny {
+        let fy = (0..height)
+            .map(|y| (PI / height as f32 * cy as f32 * (y as f32 + 0.5)).cos())
+            .collect::<Vec<_>>();
+
+        for cx in (0..).take_while(|cx| cx * ny < nx * (ny - cy)) {
+            let fx = (0..width)
+                .map(|x| (PI / width as f32 * cx as f32 * (x as f32 + 0.5)).cos())
+                .collect::<Vec<_>>();
+
+            let mut f = 0.;
+            for (row, &fy) in channel.chunks_exact(width as usize).zip(&fy) {
+                for (value, &fx) in row.iter().zip(&fx) {
+                    f += value * fx * fy;
+                }
+            }
+            f /= channel.len() as f32;
+
+            if cx > 0 || cy > 0 {
+                ac.push(f);
+                scale = scale.max(f.abs());
+            } else {
+                dc = f;
+            }
+        }
+    }
+
+    if scale > 0. {
+        for f in &mut ac {
+            *f = 0.5 + 0.5 / scale * *f;
+        }
+    }
+    (dc, ac, scale)
+}
+
+/// Reads the AC terms of a channel with the given amount of components from the given nibbles,
+/// scaled by the given scale.
+fn decode_channel(
+    nibbles: &mut impl Iterator<Item = u8>,
+    nx: u32,
+    ny: u32,
+    scale: f32,
+) -> Result<Vec<f32>> {
+    let mut ac = Vec::new();
+    for cy in 0..ny {
+        for _ in (u32::from(cy == 0)..).take_while(|cx| cx * ny < nx * (ny - cy)) {
+            let nibble = nibbles
+                .next()
+                .ok_or_else(|| Error::DecodingError("thumbhash is too short".into()))?;
+            ac.push((f32::from(nibble) / 7.5 - 1.) * scale);
+        }
+    }
+    Ok(ac)
+}
+
+impl<P: TrueColor> Image<P> {
+    /// Encodes this image into a [ThumbHash](https://evanw.github.io/thumbhash/), which is
+    /// usually around 25 bytes long. Unlike BlurHash, the hash preserves the alpha channel and the
+    /// aspect ratio of the image.
+    ///
+    /// # Panics
+    /// * The image is wider or taller than 100 pixels. Larger images gain nothing from being
+    ///   encoded at full size, so downscale them first.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let (red, clear) = (Rgba::new(255, 0, 0, 255), Rgba::transparent());
+    /// let hash = Image::vertical_gradient(64, 48, red, clear).to_thumbhash();
+    ///
+    /// let placeholder = Image::<Rgba>::from_thumbhash(&hash)?;
+    /// assert_eq!(placeholder.width(), 32);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn to_thumbhash(&self) -> Vec<u8> {
+        let (width, height) = self.dimensions();
+        assert!(
+            width <= MAX_SIZE && height <= MAX_SIZE,
+            "{width}x{height} image doesn't fit in {MAX_SIZE}x{MAX_SIZE}, downscale it first"
+        );
+
+        let pixels = self
+            .data
+            .iter()
+            .map(|pixel| {
+                let (r, g, b, a) = pixel.as_rgba_tuple();
+                let alpha = f32::from(a) / 255.;
+                ([r, g, b].map(|c| alpha / 255. * f32::from(c)), alpha)
+            })
+            .collect::<Vec<_>>();
+
+        // Determine the average color, weighted by alpha
+        let mut average = [0.; 3];
+        let mut total_alpha = 0.;
+        for (color, alpha) in &pixels {
+            for (avg, c) in average.iter_mut().zip(color) {
+                *avg += c;
+            }
+            total_alpha += alpha;
+        }
+        if total_alpha > 0. {
+            average = average.map(|c| c / total_alpha);
+        }
+
+        let has_alpha = total_alpha < (width * height) as f32;
+        // Use fewer luminance components if there is an alpha channel
+        let limit = if has_alpha { 5. } else { 7. };
+        let max_side = width.max(height) as f32;
+        let lx = ((limit * width as f32 / max_side).round() as u32).max(1);
+        let ly = ((limit * height as f32 / max_side).round() as u32).max(1);
+
+        // Convert the image into LPQA, composited atop the average color
+        let len = pixels.len();
+        let (mut l, mut p, mut q, mut a) = (
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+        );
+        for ([r, g, b], alpha) in pixels {
+            let r = average[0].mul_add(1. - alpha, r);
+            let g = average[1].mul_add(1. - alpha, g);
+            let b = average[2].mul_add(1. - alpha, b);
+            l.push((r + g + b) / 3.);
+            p.push((r + g) / 2. - b);
+            q.push(r - g);
+            a.push(alpha);
+        }
+
+        let (l_dc, l_ac, l_scale) = encode_channel(&l, width, lx.max(3), ly.max(3));
+        let (p_dc, p_ac, p_scale) = encode_channel(&p, width, 3, 3);
+        let (q_dc, q_ac, q_scale) = encode_channel(&q, width, 3, 3);
+        let alpha = has_alpha.then(|| encode_channel(&a, width, 5, 5));
+
+        let is_landscape = width > height;
+        let header24 = (63. * l_dc).round() as u32
+            | ((31.5 + 31.5 * p_dc).round() as u32) << 6
+            | ((31.5 + 31.5 * q_dc).round() as u32) << 12
+            | ((31. * l_scale).round() as u32) << 18
+            | u32::from(has_alpha) << 23;
+        let header16 = (if is_landscape { ly } else { lx })
+            | ((63. * p_scale).round() as u32) << 3
+            | ((63. * q_scale).round() as u32) << 9
+            | u32::from(is_landscape) << 15;
+
+        let mut hash = Vec::with_capacity(32);
+        hash.extend_from_slice(&header24.to_le_bytes()[..3]);
+        hash.extend_from_slice(&header16.to_le_bytes()[..2]);
+        if let Some((a_dc, _, a_scale)) = alpha {
+            hash.push((15. * a_dc).round() as u8 | ((15. * a_scale).round() as u8) << 4);
+        }
+
+        // Pack the AC terms as nibbles, low nibble first
+        let ac = [
+            Some(&l_ac),
+            Some(&p_ac),
+            Some(&q_ac),
+            alpha.as_ref().map(|(_, ac, _)| ac),
+        ];
+        let nibbles = ac
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|f| (15. * f).round() as u8)
+            .collect::<Vec<_>>();
+        hash.extend(
+            nibbles
+                .chunks(2)
+                .map(|pair| pair[0] | pair.get(1).map_or(0, |&high| high << 4)),
+        );
+
+        hash
+    }
+
+    /// Decodes the given [ThumbHash](https://evanw.github.io/thumbhash/) into an image. The
+    /// image is 32 pixels along its longer side and has approximately the aspect ratio of the
+    /// original image.
+    ///
+    /// # Errors
+    /// * `DecodingError`: The hash is malformed or too short.
+    pub fn from_thumbhash(hash: &[u8]) -> Result<Self> {
+        if hash.len() < 5 {
+            return Err(Error::DecodingError("thumbhash is too short".into()));
+        }
+
+        let header24 = u32::from_le_bytes([hash[0], hash[1], hash[2], 0]);
+        let header16 = u32::from_le_bytes([hash[3], hash[4], 0, 0]);
+        let l_dc = (header24 & 63) as f32 / 63.;
+        let p_dc = ((header24 >> 6) & 63) as f32 / 31.5 - 1.;
+        let q_dc = ((header24 >> 12) & 63) as f32 / 31.5 - 1.;
+        let l_scale = ((header24 >> 18) & 31) as f32 / 31.;
+        let has_alpha = header24 >> 23 != 0;
+        let p_scale = ((header16 >> 3) & 63) as f32 / 63.;
+        let q_scale = ((header16 >> 9) & 63) as f32 / 63.;
+        let is_landscape = header16 >> 15 != 0;
+
+        let limit = if has_alpha { 5 } else { 7 };
+        let (lx, ly) = if is_landscape {
+            (limit, header16 & 7)
+        } else {
+            (header16 & 7, limit)
+        };
+        if lx == 0 || ly == 0 {
+            return Err(Error::DecodingError(
+                "thumbhash has no luminance components".into(),
+            ));
+        }
+        let ratio = lx as f32 / ly as f32;
+        let (lx, ly) = (lx.max(3), ly.max(3));
+
+        let ac_start = if has_alpha { 6 } else { 5 };
+        let (a_dc, a_scale) = match (has_alpha, hash.get(5)) {
+            (false, _) => (1., 0.),
+            (true, Some(&byte)) => (f32::from(byte & 15) / 15., f32::from(byte >> 4) / 15.),
+            (true, None) => return Err(Error::DecodingError("thumbhash is too short".into())),
+        };
+
+        let mut nibbles = hash[ac_start..]
+            .iter()
+            .flat_map(|&byte| [byte & 15, byte >> 4]);
+        let l_ac = decode_channel(&mut nibbles, lx, ly, l_scale)?;
+        // Boost the saturation to compensate for quantization
+        let p_ac = decode_channel(&mut nibbles, 3, 3, p_scale * 1.25)?;
+        let q_ac = decode_channel(&mut nibbles, 3, 3, q_scale * 1.25)?;
+        let a_ac = if has_alpha {
+            decode_channel(&mut nibbles, 5, 5, a_scale)?
+        } else {
+            Vec::new()
+        };
+
+        let (width, height) = if ratio > 1. {
+            (32, (32. / ratio).round() as u32)
+        } else {
+            ((32. * ratio).round() as u32, 32)
+        };
+        let (nx, ny) = if has_alpha {
+            (lx.max(5), ly.max(5))
+        } else {
+            (lx, ly)
+        };
+
+        // Sums the AC terms of a channel with the given amount of components at a pixel
+        let sum = |ac: &[f32], nx: u32, ny: u32, fx: &[f32], fy: &[f32]| {
+            let mut terms = ac.iter();
+            let mut total = 0.;
+            for cy in 0..ny {
+                let fy2 = fy[cy as usize] * 2.;
+                for cx in (u32::from(cy == 0)..).take_while(|cx| cx * ny < nx * (ny - cy)) {
+                    total += terms.next().copied().unwrap_or_default() * fx[cx as usize] * fy2;
+                }
+            }
+            total
+        };
+
+        let image = Self::from_fn(width, height, |x, y| {
+            let fx = (0..nx)
+                .map(|cx| (PI / width as f32 * (x as f32 + 0.5) * cx as f32).cos())
+                .collect::<Vec<_>>();
+            let fy = (0..ny)
+                .map(|cy| (PI / height as f32 * (y as f32 + 0.5) * cy as f32).cos())
+                .collect::<Vec<_>>();
+
+            let l = l_dc + sum(&l_ac, lx, ly, &fx, &fy);
+            let p = p_dc + sum(&p_ac, 3, 3, &fx, &fy);
+            let q = q_dc + sum(&q_ac, 3, 3, &fx, &fy);
+            let a = if has_alpha {
+                a_dc + sum(&a_ac, 5, 5, &fx, &fy)
+            } else {
+                a_dc
+            };
+
+            let b = l - 2. / 3. * p;
+            let r = (3. * l - b + q) / 2.;
+            let g = r - q;
+            let [r, g, b, a] = [r, g, b, a].map(|c| (c.min(1.) * 255.).max(0.) as u8);
+            P::from_rgba_tuple((r, g, b, a))
+        });
+
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Image, Pixel, Rgba};
+
+    #[test]
+    fn test_thumbhash_roundtrip() {
+        let color = Rgba::new(40, 120, 200, 255);
+        let hash = Image::new(40, 20, color).to_thumbhash();
+        let decoded = Image::<Rgba>::from_thumbhash(&hash).unwrap();
+
+        // The aspect ratio is approximated from the amount of luminance components, 7 by 4
+        assert_eq!(decoded.dimensions(), (32, 18));
+        for pixel in decoded.data {
+            for (a, b) in pixel.as_bytes().into_iter().zip(color.as_bytes()) {
+                assert!(a.abs_diff(b) <= 4, "{pixel:?} != {color:?}");
+            }
+        }
+        assert!(Image::<Rgba>::from_thumbhash(&hash[..6]).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Affine transformations of images, such as translating, scaling, rotating and shearing.
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// A 2D affine transformation, which maps the point `(x, y)` to
+/// `(a * x + b * y + c, d * x + e * y + f)`. Affine transformations can translate, scale, rotate
+/// and shear images, and any combination of these is also an affine transformation.
+///
+/// Transformations operate on pixel coordinates, where the center of the pixel at `(x, y)` lies at
+/// `(x, y)` and the y-axis points down. Combine transformations with [`Self::then`] or the
+/// `then_*` methods, which apply the given transformation after this one.
+///
+/// # See Also
+/// - [`Image::transform`][crate::Image::transform] to apply a transformation to an image.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// // Scale by 2 and then rotate by 90 degrees clockwise
+/// let affine = Affine::scale(2.0, 2.0).then_rotate(90.0);
+/// let (x, y) = affine.apply(1.0, 0.0);
+///
+/// assert!(x.abs() < 1e-9 && (y - 2.0).abs() < 1e-9);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Affine {
+    matrix: [f64; 6],
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    /// The identity transformation, which maps every point to itself.
+    pub const IDENTITY: Self = Self::new([1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+
+    /// Creates a transformation from the coefficients `[a, b, c, d, e, f]`, which map the point
+    /// `(x, y)` to `(a * x + b * y + c, d * x + e * y + f)`.
+    #[must_use]
+    pub const fn new(matrix: [f64; 6]) -> Self {
+        Self { matrix }
+    }
+
+    /// Returns the coefficients `[a, b, c, d, e, f]` of this transformation.
+    #[must_use]
+    pub const fn matrix(&self) -> [f64; 6] {
+        self.matrix
+    }
+
+    /// Creates a transformation which moves points by the given offset.
+    #[must_use]
+    pub const fn translate(x: f64, y: f64) -> Self {
+        Self::new([1.0, 0.0, x, 0.0, 1.0, y])
+    }
+
+    /// Creates a transformation which scales points about the origin by the given horizontal and
+    /// vertical factors. Negative factors mirror points.
+    #[must_use]
+    pub const fn scale(x: f64, y: f64) -> Self {
+        Self::new([x, 0.0, 0.0, 0.0, y, 0.0])
+    }
+
+    /// Creates a transformation which rotates points clockwise about the origin by the given
+    /// amount of degrees.
+    #[must_use]
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+
+        Self::new([cos, -sin, 0.0, sin, cos, 0.0])
+    }
+
+    /// Creates a transformation which shears points by the given horizontal and vertical factors.
+    /// A horizontal factor of `1.0` moves each point to the right by its y-coordinate.
+    #[must_use]
+    pub const fn shear(x: f64, y: f64) -> Self {
+        Self::new([1.0, x, 0.0, y, 1.0, 0.0])
+    }
+
+    /// Returns the transformation which applies this transformation and then the given one.
+    #[must_use]
+    pub fn then(self, other: Self) -> Self {
+        let [a1, b1, c1, d1, e1, f1] = self.matrix;
+        let [a2, b2, c2, d2, e2, f2] = other.matrix;
+
+        Self::new([
+            a2.mul_add(a1, b2 * d1),
+            a2.mul_add(b1, b2 * e1),
+            a2.mul_add(c1, b2.mul_add(f1, c2)),
+            d2.mul_add(a1, e2 * d1),
+            d2.mul_add(b1, e2 * e1),
+            d2.mul_add(c1, e2.mul_add(f1, f2)),
+        ])
+    }
+
+    /// Applies this transformation and then moves points by the given offset.
+    #[must_use]
+    pub fn then_translate(self, x: f64, y: f64) -> Self {
+        self.then(Self::translate(x, y))
+    }
+
+    /// Applies this transformation and then scales points about the origin.
+    #[must_use]
+    pub fn then_scale(self, x: f64, y: f64) -> Self {
+        self.then(Self::scale(x, y))
+    }
+
+    /// Applies this transformation and then rotates points clockwise about the origin.
+    #[must_use]
+    pub fn then_rotate(self, degrees: f64) -> Self {
+        self.then(Self::rotate(degrees))
+    }
+
+    /// Applies this transformation and then shears points.
+    #[must_use]
+    pub fn then_shear(self, x: f64, y: f64) -> Self {
+        self.then(Self::shear(x, y))
+    }
+
+    /// Returns this transformation applied about the given point instead of the origin, for
+    /// example to rotate or scale an image about its center.
+    #[must_use]
+    pub fn about(self, (x, y): (f64, f64)) -> Self {
+        Self::translate(-x, -y).then(self).then_translate(x, y)
+    }
+
+    /// Returns the transformation which undoes this transformation, or `None` if it is not
+    /// invertible, for example because it scales by zero.
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d, e, f] = self.matrix;
+        let det = a.mul_add(e, -b * d);
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+
+        Some(Self::new([
+            e / det,
+            -b / det,
+            b.mul_add(f, -e * c) / det,
+            -d / det,
+            a / det,
+            d.mul_add(c, -a * f) / det,
+        ]))
+    }
+
+    /// Maps the given point with this transformation.
+    #[must_use]
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let [a, b, c, d, e, f] = self.matrix;
+
+        (a.mul_add(x, b.mul_add(y, c)), d.mul_add(x, e.mul_add(y, f)))
+    }
+}
@@ -0,0 +1,492 @@
+//! Borrowed views into rectangular regions of images, which read and write the pixels of the
+//! region in place instead of copying them out.
+
+use crate::{image::copy_rebound, Draw, Image, OutOfBounds, Pixel, Rect, Result, L};
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+/// An immutable view into a rectangular region of an [`Image`], which reads the pixels of the
+/// region in place without copying them.
+///
+/// Coordinates passed to the methods of a view are relative to the top-left corner of the region.
+///
+/// # See Also
+/// - [`Image::view`] to create a view.
+/// - [`ImageViewMut`] for a view that can modify the pixels of the region.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let image = Image::from_fn(8, 8, |x, y| L((y * 8 + x) as u8));
+/// let view = image.view(Rect::new(2, 4, 4, 2))?;
+///
+/// assert_eq!(view.dimensions(), (4, 2));
+/// assert_eq!(view.pixel(0, 0), &L(34));
+/// assert_eq!(view.rows().last(), Some(&[L(42), L(43), L(44), L(45)][..]));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ImageView<'a, P: Pixel> {
+    image: &'a Image<P>,
+    rect: Rect,
+}
+
+impl<'a, P: Pixel> ImageView<'a, P> {
+    /// Returns the region of the image this view covers.
+    #[must_use]
+    pub const fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the width of this view.
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.rect.width
+    }
+
+    /// Returns the height of this view.
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.rect.height
+    }
+
+    /// Returns the dimensions of this view as `(width, height)`.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        self.rect.dimensions()
+    }
+
+    /// Returns the image this view borrows from.
+    #[must_use]
+    pub const fn image(&self) -> &'a Image<P> {
+        self.image
+    }
+
+    /// Returns the pixel at the given coordinates relative to this view.
+    ///
+    /// # Panics
+    /// * The coordinates lie outside of this view.
+    #[must_use]
+    pub fn pixel(&self, x: u32, y: u32) -> &'a P {
+        self.get_pixel(x, y).unwrap_or_else(|| {
+            panic!(
+                "pixel ({x}, {y}) is out of bounds for view with dimensions {:?}",
+                self.dimensions()
+            )
+        })
+    }
+
+    /// Returns the pixel at the given coordinates relative to this view, or `None` if they lie
+    /// outside of this view.
+    #[must_use]
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<&'a P> {
+        (x < self.width() && y < self.height()).then(|| &self.row(y)[x as usize])
+    }
+
+    /// Returns the row of pixels at the given y coordinate relative to this view.
+    fn row(&self, y: u32) -> &'a [P] {
+        let start = row_start(self.image, self.rect, y);
+        &self.image.data[start..start + self.width() as usize]
+    }
+
+    /// Returns an iterator over the rows of this view, where each row is a slice of pixels.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [P]> + 'a {
+        let view = *self;
+        (0..self.height()).map(move |y| view.row(y))
+    }
+
+    /// Copies the pixels of this view into a new image, which keeps the format, overlay mode,
+    /// palette and metadata of the viewed image.
+    #[must_use]
+    pub fn to_image(&self) -> Image<P> {
+        self.image
+            .copy_rect(self.rect, OutOfBounds::Error)
+            .expect("view lies inside of its image")
+    }
+
+    /// Extracts the band at the given index from the pixels of this view, like
+    /// [`Banded::band`][crate::Banded::band] does for whole images.
+    ///
+    /// # Panics
+    /// * The pixel type does not have a channel at the given index.
+    #[must_use]
+    pub fn band(&self, idx: usize) -> Image<L> {
+        let data = self
+            .rows()
+            .flatten()
+            .map(|pixel| L(pixel.as_bytes().as_ref()[idx]))
+            .collect::<Vec<_>>();
+
+        Image {
+            width: NonZeroU32::new(self.width()).unwrap(),
+            height: NonZeroU32::new(self.height()).unwrap(),
+            data,
+            format: self.image.format,
+            overlay: self.image.overlay,
+            palette: None,
+            metadata: self.image.metadata.clone(),
+        }
+    }
+
+    /// Encodes the pixels of this view with the given encoding and writes them to the given
+    /// buffer. Since encoders operate on whole images, the region is copied into a temporary
+    /// image first.
+    ///
+    /// # Errors
+    /// * An error occured during encoding.
+    ///
+    /// # See Also
+    /// * [`Image::encode`] for more information.
+    #[cfg(feature = "std")]
+    pub fn encode(
+        &self,
+        encoding: crate::ImageFormat,
+        dest: &mut impl std::io::Write,
+    ) -> Result<()> {
+        self.to_image().encode(encoding, dest)
+    }
+}
+
+/// A mutable view into a rectangular region of an [`Image`], which reads and writes the pixels of
+/// the region in place without copying them.
+///
+/// Coordinates passed to the methods of a view are relative to the top-left corner of the region,
+/// and anything drawn or pasted onto the view is clipped to the region.
+///
+/// # See Also
+/// - [`Image::view_mut`] to create a view.
+/// - [`ImageView`] for an immutable view.
+///
+/// # Example
+/// ```
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let mut image = Image::new(8, 8, Rgb::black());
+/// let mut view = image.view_mut(Rect::new(4, 4, 4, 4))?;
+///
+/// // Drawing at (0, 0) of the view draws at (4, 4) of the image, and is clipped to the view
+/// view.draw(&Rectangle::at(0, 0).with_size(8, 2).with_fill(Rgb::white()));
+///
+/// assert_eq!(image.pixel(4, 4), &Rgb::white());
+/// assert_eq!(image.pixel(7, 5), &Rgb::white());
+/// assert_eq!(image.pixel(3, 4), &Rgb::black());
+/// assert_eq!(image.pixel(4, 6), &Rgb::black());
+/// # Ok(())
+/// # }
+/// ```
+pub struct ImageViewMut<'a, P: Pixel> {
+    image: &'a mut Image<P>,
+    rect: Rect,
+}
+
+impl<'a, P: Pixel> ImageViewMut<'a, P> {
+    /// Returns the region of the image this view covers.
+    #[must_use]
+    pub const fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the width of this view.
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.rect.width
+    }
+
+    /// Returns the height of this view.
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.rect.height
+    }
+
+    /// Returns the dimensions of this view as `(width, height)`.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        self.rect.dimensions()
+    }
+
+    /// Returns an immutable view of the same region.
+    #[must_use]
+    pub fn as_view(&self) -> ImageView<'_, P> {
+        ImageView {
+            image: self.image,
+            rect: self.rect,
+        }
+    }
+
+    /// Returns the pixel at the given coordinates relative to this view.
+    ///
+    /// # Panics
+    /// * The coordinates lie outside of this view.
+    #[must_use]
+    pub fn pixel(&self, x: u32, y: u32) -> &P {
+        self.as_view().pixel(x, y)
+    }
+
+    /// Returns a mutable reference to the pixel at the given coordinates relative to this view.
+    ///
+    /// # Panics
+    /// * The coordinates lie outside of this view.
+    pub fn pixel_mut(&mut self, x: u32, y: u32) -> &mut P {
+        assert!(
+            x < self.width() && y < self.height(),
+            "pixel ({x}, {y}) is out of bounds for view with dimensions {:?}",
+            self.dimensions()
+        );
+
+        &mut self.row_mut(y)[x as usize]
+    }
+
+    /// Sets the pixel at the given coordinates relative to this view.
+    ///
+    /// # Panics
+    /// * The coordinates lie outside of this view.
+    pub fn set_pixel(&mut self, x: u32, y: u32, pixel: P) {
+        *self.pixel_mut(x, y) = pixel;
+    }
+
+    /// Returns the row of pixels at the given y coordinate relative to this view.
+    fn row_mut(&mut self, y: u32) -> &mut [P] {
+        let start = row_start(self.image, self.rect, y);
+        &mut self.image.data[start..start + self.rect.width as usize]
+    }
+
+    /// Returns an iterator over the rows of this view, where each row is a slice of pixels.
+    pub fn rows(&self) -> impl Iterator<Item = &[P]> {
+        let ImageView { image, rect } = self.as_view();
+
+        (0..rect.height).map(move |y| {
+            let start = row_start(image, rect, y);
+            &image.data[start..start + rect.width as usize]
+        })
+    }
+
+    /// Returns an iterator over the rows of this view, where each row is a mutable slice of
+    /// pixels.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [P]> {
+        let (x, width) = (self.rect.x as usize, self.rect.width as usize);
+        let stride = self.image.width() as usize;
+
+        self.image
+            .data
+            .chunks_exact_mut(stride)
+            .skip(self.rect.y as usize)
+            .take(self.rect.height as usize)
+            .map(move |row| &mut row[x..x + width])
+    }
+
+    /// Copies the pixels of the given image, which must have the same dimensions as this view,
+    /// into this view. Paletted pixels are rebound to the palette of the viewed image.
+    pub(crate) fn copy_from_image(&mut self, image: &Image<P>) {
+        // SAFETY: pixels are only rebound right before they are written into the viewed image
+        let palette = unsafe { self.image.detached_palette() };
+
+        for (row, src) in self.rows_mut().zip(image.rows()) {
+            unsafe { copy_rebound(palette, row, src) };
+        }
+    }
+
+    /// Sets every pixel of this view to the given pixel.
+    pub fn fill(&mut self, pixel: P) {
+        self.rows_mut().for_each(|row| row.fill(pixel));
+    }
+
+    /// Draws the given object or shape onto this view, with coordinates relative to the view.
+    /// Anything outside of the view is clipped.
+    ///
+    /// Shapes are drawn straight onto the pixels of the region. Other objects which do not
+    /// override [`Draw::draw_view`] are drawn onto a temporary copy of the region, which is then
+    /// copied back.
+    pub fn draw(&mut self, entity: &impl Draw<P>) {
+        entity.draw_view(self);
+    }
+
+    /// Pastes the given image onto this view at the given coordinates relative to the view,
+    /// abiding by the overlay mode of the viewed image. Anything outside of the view is clipped.
+    ///
+    /// # See Also
+    /// * [`Image::paste`] for pasting onto whole images.
+    pub fn paste(&mut self, x: i64, y: i64, image: &Image<P>) {
+        self.paste_view(x, y, &image.as_view());
+    }
+
+    /// Pastes the pixels of the given view onto this view at the given coordinates relative to
+    /// this view, abiding by the overlay mode of the viewed image. Anything outside of this view is
+    /// clipped.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn paste_view(&mut self, x: i64, y: i64, view: &ImageView<P>) {
+        let mode = self.image.overlay_mode();
+        let (x1, x2) = (
+            x.max(0),
+            (x + i64::from(view.width())).min(i64::from(self.width())),
+        );
+        if x1 >= x2 {
+            return;
+        }
+        let (start, end) = ((x1 - x) as usize, (x2 - x) as usize);
+
+        for (row, src) in (y..).zip(view.rows()) {
+            if row >= i64::from(self.height()) {
+                break;
+            }
+            if row >= 0 {
+                let dest = &mut self.row_mut(row as u32)[x1 as usize..x2 as usize];
+                P::overlay_row(dest, &src[start..end], mode);
+            }
+        }
+    }
+}
+
+/// Returns the index of the first pixel of the given row of the given region of the image.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn row_start<P: Pixel>(image: &Image<P>, rect: Rect, y: u32) -> usize {
+    (rect.y as usize + y as usize) * image.width() as usize + rect.x as usize
+}
+
+impl<P: Pixel> Image<P> {
+    /// Returns an immutable view into the given region of this image, which reads its pixels in
+    /// place without copying them.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The region is empty.
+    /// * `OutOfBounds`: The region exceeds the bounds of this image.
+    ///
+    /// # See Also
+    /// * [`ImageView`] for an example.
+    /// * [`Self::copy_rect`] to copy the region into a new image instead.
+    pub fn view(&self, rect: Rect) -> Result<ImageView<'_, P>> {
+        self.resolve_rect(rect, OutOfBounds::Error)?;
+
+        Ok(ImageView { image: self, rect })
+    }
+
+    /// Returns a mutable view into the given region of this image, which reads and writes its
+    /// pixels in place without copying them.
+    ///
+    /// # Errors
+    /// * `EmptyImageError`: The region is empty.
+    /// * `OutOfBounds`: The region exceeds the bounds of this image.
+    ///
+    /// # See Also
+    /// * [`ImageViewMut`] for an example.
+    pub fn view_mut(&mut self, rect: Rect) -> Result<ImageViewMut<'_, P>> {
+        self.resolve_rect(rect, OutOfBounds::Error)?;
+
+        Ok(ImageViewMut { image: self, rect })
+    }
+
+    /// Returns an immutable view covering this whole image.
+    #[must_use]
+    pub const fn as_view(&self) -> ImageView<'_, P> {
+        ImageView {
+            image: self,
+            rect: self.bounds(),
+        }
+    }
+
+    /// Returns a mutable view covering this whole image.
+    #[must_use]
+    pub fn as_view_mut(&mut self) -> ImageViewMut<'_, P> {
+        let rect = self.bounds();
+
+        ImageViewMut { image: self, rect }
+    }
+
+    /// Pastes the pixels of the given view onto this image at the given x and y coordinates,
+    /// abiding by the overlay mode of this image, without copying the viewed region first.
+    ///
+    /// # Example
+    /// ```
+    /// # use ril::prelude::*;
+    /// # fn main() -> ril::Result<()> {
+    /// let atlas = Image::from_fn(16, 16, |x, _| if x < 8 { Rgb::white() } else { Rgb::black() });
+    /// let mut image = Image::new(8, 8, Rgb::white());
+    ///
+    /// image.paste_view(2, 2, &atlas.view(Rect::new(8, 0, 8, 8))?);
+    /// assert_eq!(image.pixel(1, 1), &Rgb::white());
+    /// assert_eq!(image.pixel(2, 2), &Rgb::black());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn paste_view(&mut self, x: i64, y: i64, view: &ImageView<P>) {
+        self.as_view_mut().paste_view(x, y, view);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views() {
+        let mut image = Image::from_fn(4, 4, |x, y| L((y * 4 + x) as u8));
+        let source = Image::from_fn(3, 3, |x, y| L(100 + (y * 3 + x) as u8));
+
+        let view = image.view(Rect::new(1, 1, 2, 3)).unwrap();
+        assert_eq!(view.band(0).data, [5, 6, 9, 10, 13, 14].map(L));
+        assert!(image.view(Rect::new(3, 0, 2, 2)).is_err());
+        assert!(image.view(Rect::new(0, 0, 0, 2)).is_err());
+
+        let mut view = image.view_mut(Rect::new(1, 1, 2, 2)).unwrap();
+        view.paste_view(-1, 1, &source.view(Rect::new(0, 0, 3, 2)).unwrap());
+        view.set_pixel(1, 0, L(0));
+        assert_eq!(
+            image.data,
+            [0, 1, 2, 3, 4, 5, 0, 7, 8, 101, 102, 11, 12, 13, 14, 15].map(L)
+        );
+    }
+
+    #[test]
+    fn test_draw_view() {
+        use crate::{Ellipse, FloodFill, Line};
+
+        let rect = Rect::new(2, 3, 4, 3);
+        let line = Line::new((0, 0), (5, 2), L(200)).with_thickness(2);
+        let circle = Ellipse::circle(3, 1, 2)
+            .with_fill(L(100))
+            .with_antialiased(true);
+        let fill = FloodFill::new(0, 2, L(50));
+
+        let mut image = Image::new(8, 8, L(0));
+        let mut view = image.view_mut(rect).unwrap();
+        view.draw(&line);
+        view.draw(&circle);
+        view.draw(&fill);
+
+        // Drawing onto the view in place matches drawing onto a copy of the region
+        let mut region = Image::new(4, 3, L(0));
+        region.draw(&line);
+        region.draw(&circle);
+        region.draw(&fill);
+        assert_eq!(image.view(rect).unwrap().to_image().data, region.data);
+
+        // Everything outside of the view is clipped, including the flood fill
+        for (i, pixel) in image.data.iter().enumerate() {
+            let (x, y) = ((i % 8) as u32, (i / 8) as u32);
+            if !(2..6).contains(&x) || !(3..6).contains(&y) {
+                assert_eq!(
+                    pixel,
+                    &L(0),
+                    "pixel ({x}, {y}) was drawn outside of the view"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_view_paletted() {
+        use crate::{Clip, Paletted, PalettedRgb, Rectangle, Rgb};
+
+        let palette = vec![Rgb::black(), Rgb::white()];
+        let mut image = Image::<PalettedRgb>::from_paletted_pixels(3, palette, [0, 0, 0, 0, 0, 1]);
+        let white = *image.pixel(2, 1);
+        let rectangle = Rectangle::from_bounding_box(0, 0, 2, 2).with_fill(white);
+
+        // Clips are drawn onto a copy of the region, whose pixels are copied back into the view
+        let mut view = image.view_mut(Rect::new(1, 0, 2, 2)).unwrap();
+        view.draw(&Clip::rect(rectangle, 0, 0, 1, 1));
+        let colors = image.data.iter().map(|pixel| pixel.color().r);
+        assert_eq!(colors.collect::<Vec<_>>(), [0, 255, 0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,339 @@
+//! Implements watermarking images and image sequences with another image, either placed once at an
+//! anchor or tiled across the whole image.
+
+use crate::{Draw, Image, OverlayMode, Pixel, TrueColor};
+use core::ops::DerefMut;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Where a watermark placed once is anchored on the image.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WatermarkAnchor {
+    /// The top-left corner of the image.
+    TopLeft,
+    /// The center of the top edge of the image.
+    Top,
+    /// The top-right corner of the image.
+    TopRight,
+    /// The center of the left edge of the image.
+    Left,
+    /// The center of the image.
+    Center,
+    /// The center of the right edge of the image.
+    Right,
+    /// The bottom-left corner of the image.
+    BottomLeft,
+    /// The center of the bottom edge of the image.
+    Bottom,
+    /// The bottom-right corner of the image. This is the default.
+    #[default]
+    BottomRight,
+}
+
+impl WatermarkAnchor {
+    /// Returns where along each axis this anchor lies, where `0` is the start, `1` is the center
+    /// and `2` is the end.
+    const fn factors(self) -> (u8, u8) {
+        match self {
+            Self::TopLeft => (0, 0),
+            Self::Top => (1, 0),
+            Self::TopRight => (2, 0),
+            Self::Left => (0, 1),
+            Self::Center => (1, 1),
+            Self::Right => (2, 1),
+            Self::BottomLeft => (0, 2),
+            Self::Bottom => (1, 2),
+            Self::BottomRight => (2, 2),
+        }
+    }
+}
+
+/// How a [`Watermark`] is placed on the image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WatermarkPlacement {
+    /// Places the watermark once at the given anchor, keeping the given horizontal and vertical
+    /// margin in pixels from the edges of the image. The margin is ignored along centered axes.
+    Anchored {
+        /// Where the watermark is anchored.
+        anchor: WatermarkAnchor,
+        /// The horizontal and vertical margin in pixels.
+        margin: (u32, u32),
+    },
+    /// Repeats the watermark across the whole image in rows, leaving the given horizontal and
+    /// vertical spacing in pixels between tiles. Every other row is offset by half a tile, and
+    /// rotating the watermark rotates the rows with it, which gives the usual diagonal pattern.
+    Tiled {
+        /// The horizontal and vertical spacing between tiles in pixels.
+        spacing: (u32, u32),
+    },
+}
+
+/// A watermark, which draws an image onto another image with some opacity, either once at an
+/// anchor or tiled across the whole image. The watermark can be scaled and rotated by any angle
+/// without resizing or rotating the watermark image beforehand, since it is sampled with bilinear
+/// interpolation while drawing.
+///
+/// To watermark every frame of an image sequence, use [`ImageSequence::draw`].
+///
+/// [`ImageSequence::draw`]: crate::ImageSequence::draw
+///
+/// # Example
+/// ```no_run
+/// # use ril::prelude::*;
+/// # fn main() -> ril::Result<()> {
+/// let logo = Image::<Rgba>::open("logo.png")?;
+/// let mut image = Image::<Rgba>::open("photo.png")?;
+///
+/// // A small logo in the bottom-right corner
+/// image.draw(
+///     &Watermark::new(&logo)
+///         .with_anchor(WatermarkAnchor::BottomRight, 16)
+///         .with_relative_width(0.2)
+///         .with_opacity(0.6),
+/// );
+///
+/// // The logo repeated diagonally across the whole image
+/// image.draw(
+///     &Watermark::new(&logo)
+///         .tiled(48, 48)
+///         .with_rotation(-30.)
+///         .with_opacity(0.15),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Watermark<'a, P: Pixel> {
+    /// The watermark image.
+    pub image: &'a Image<P>,
+    /// How the watermark is placed. Defaults to the bottom-right corner with no margin.
+    pub placement: WatermarkPlacement,
+    /// The opacity of the watermark, from `0.0` (fully transparent) to `1.0` (opaque). Defaults
+    /// to `1.0`.
+    pub opacity: f32,
+    /// The clockwise rotation of the watermark in degrees. Defaults to `0.0`.
+    pub rotation: f32,
+    /// The factor by which the watermark image is scaled. Defaults to `1.0`. This is ignored if
+    /// [`relative_width`](Self::relative_width) is set.
+    pub scale: f32,
+    /// The width of the watermark relative to the width of the image it is drawn onto, for
+    /// example `0.25` for a quarter of its width, preserving the aspect ratio of the watermark.
+    pub relative_width: Option<f32>,
+    /// The overlay mode to use when drawing the watermark, or `None` to use the image's overlay
+    /// mode.
+    pub overlay: Option<OverlayMode>,
+}
+
+impl<'a, P: Pixel> Watermark<'a, P> {
+    /// Creates a new watermark from the given image, placed once in the bottom-right corner at
+    /// its original size.
+    #[must_use]
+    pub fn new(image: &'a Image<P>) -> Self {
+        Self {
+            image,
+            placement: WatermarkPlacement::Anchored {
+                anchor: WatermarkAnchor::default(),
+                margin: (0, 0),
+            },
+            opacity: 1.,
+            rotation: 0.,
+            scale: 1.,
+            relative_width: None,
+            overlay: None,
+        }
+    }
+
+    /// Places the watermark once at the given anchor, keeping the given margin in pixels from the
+    /// edges of the image.
+    #[must_use]
+    pub const fn with_anchor(mut self, anchor: WatermarkAnchor, margin: u32) -> Self {
+        self.placement = WatermarkPlacement::Anchored {
+            anchor,
+            margin: (margin, margin),
+        };
+        self
+    }
+
+    /// Repeats the watermark across the whole image, leaving the given horizontal and vertical
+    /// spacing in pixels between tiles.
+    ///
+    /// # See Also
+    /// * [`WatermarkPlacement::Tiled`] for more information.
+    #[must_use]
+    pub const fn tiled(mut self, spacing_x: u32, spacing_y: u32) -> Self {
+        self.placement = WatermarkPlacement::Tiled {
+            spacing: (spacing_x, spacing_y),
+        };
+        self
+    }
+
+    /// Sets how the watermark is placed.
+    #[must_use]
+    pub const fn with_placement(mut self, placement: WatermarkPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Sets the opacity of the watermark, from `0.0` (fully transparent) to `1.0` (opaque).
+    /// Values outside of this range are clamped.
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0., 1.);
+        self
+    }
+
+    /// Sets the clockwise rotation of the watermark in degrees.
+    #[must_use]
+    pub const fn with_rotation(mut self, degrees: f32) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /// Sets the factor by which the watermark image is scaled.
+    ///
+    /// # Panics
+    /// * `scale` is not positive.
+    #[must_use]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        assert!(scale > 0., "scale must be positive");
+        self.scale = scale;
+        self.relative_width = None;
+        self
+    }
+
+    /// Scales the watermark so that its width is the given fraction of the width of the image it
+    /// is drawn onto, preserving its aspect ratio. This adapts to images of different sizes.
+    ///
+    /// # Panics
+    /// * `relative_width` is not positive.
+    #[must_use]
+    pub fn with_relative_width(mut self, relative_width: f32) -> Self {
+        assert!(relative_width > 0., "relative width must be positive");
+        self.relative_width = Some(relative_width);
+        self
+    }
+
+    /// Sets the overlay mode to use when drawing the watermark.
+    #[must_use]
+    pub const fn with_overlay_mode(mut self, mode: OverlayMode) -> Self {
+        self.overlay = Some(mode);
+        self
+    }
+}
+
+impl<'a, P: TrueColor> Watermark<'a, P> {
+    /// Samples the watermark at the given offset from its center in scaled pixels, and returns the
+    /// sampled pixel with the alpha to blend it with, if the offset lies within the watermark.
+    fn sample(&self, (u, v): (f32, f32), scale: f32) -> Option<(P, u8)> {
+        let (width, height) = self.image.dimensions();
+        // The centers of the watermark's pixels lie at integer coordinates
+        let x = u / scale + width as f32 / 2. - 0.5;
+        let y = v / scale + height as f32 / 2. - 0.5;
+
+        // Anti-alias the edges by how much of the pixel is covered by the watermark
+        let coverage =
+            |c: f32, length: u32| ((c + 0.5).min(length as f32 - 0.5 - c) + 0.5).clamp(0., 1.);
+        let coverage = coverage(x, width) * coverage(y, height);
+
+        let alpha = (coverage * self.opacity * 255.).round() as u8;
+        (alpha > 0).then(|| {
+            (
+                self.image.sample_bilinear(f64::from(x), f64::from(y)),
+                alpha,
+            )
+        })
+    }
+}
+
+impl<'a, P: TrueColor> Draw<P> for Watermark<'a, P> {
+    fn draw<I: DerefMut<Target = Image<P>>>(&self, mut image: I) {
+        let overlay = self.overlay.unwrap_or(image.overlay);
+        let (width, height) = (image.width() as f32, image.height() as f32);
+        let scale = self.relative_width.map_or(self.scale, |relative| {
+            relative * width / self.image.width() as f32
+        });
+        let (mark_width, mark_height) = (
+            self.image.width() as f32 * scale,
+            self.image.height() as f32 * scale,
+        );
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        // Rotates a point given relative to the center of the watermark into its own axes
+        let unrotate = |dx: f32, dy: f32| (dx * cos + dy * sin, dy * cos - dx * sin);
+
+        let plot = |image: &mut Image<P>, x: u32, y: u32, offset: (f32, f32)| {
+            if let Some((pixel, alpha)) = self.sample(offset, scale) {
+                image.overlay_pixel_with_alpha(x, y, pixel, overlay, alpha);
+            }
+        };
+
+        match self.placement {
+            WatermarkPlacement::Anchored { anchor, margin } => {
+                // The bounding box of the rotated watermark
+                let bounds_width = (mark_width * cos).abs() + (mark_height * sin).abs();
+                let bounds_height = (mark_width * sin).abs() + (mark_height * cos).abs();
+                let position = |factor: u8, length: f32, bounds: f32, margin: u32| match factor {
+                    0 => margin as f32,
+                    1 => (length - bounds) / 2.,
+                    _ => length - bounds - margin as f32,
+                };
+                let (fx, fy) = anchor.factors();
+                let x1 = position(fx, width, bounds_width, margin.0);
+                let y1 = position(fy, height, bounds_height, margin.1);
+                let (cx, cy) = (x1 + bounds_width / 2., y1 + bounds_height / 2.);
+
+                let xs = x1.floor().max(0.) as u32..((x1 + bounds_width).ceil().min(width)) as u32;
+                for y in y1.floor().max(0.) as u32..((y1 + bounds_height).ceil().min(height)) as u32
+                {
+                    for x in xs.clone() {
+                        let offset = unrotate(x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                        plot(&mut image, x, y, offset);
+                    }
+                }
+            }
+            WatermarkPlacement::Tiled { spacing } => {
+                let tile_width = mark_width + spacing.0 as f32;
+                let tile_height = mark_height + spacing.1 as f32;
+                // Wraps a coordinate into the tile centered around zero
+                let wrap = |c: f32, length: f32| {
+                    let c = c + length / 2.;
+                    c - (c / length).floor() * length - length / 2.
+                };
+
+                for y in 0..image.height() {
+                    for x in 0..image.width() {
+                        let (u, v) =
+                            unrotate(x as f32 + 0.5 - width / 2., y as f32 + 0.5 - height / 2.);
+                        // Offset every other row by half a tile
+                        let row = ((v + tile_height / 2.) / tile_height).floor();
+                        let u = u + (row - (row / 2.).floor() * 2.) * tile_width / 2.;
+
+                        plot(
+                            &mut image,
+                            x,
+                            y,
+                            (wrap(u, tile_width), wrap(v, tile_height)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rgba;
+
+    #[test]
+    fn test_watermark_anchor() {
+        let mark = Image::new(4, 4, Rgba::black());
+        let image = Image::new(16, 16, Rgba::white())
+            .with(&Watermark::new(&mark).with_anchor(WatermarkAnchor::BottomRight, 2));
+
+        assert_eq!(image.pixel(10, 10), &Rgba::black());
+        assert_eq!(image.pixel(13, 13), &Rgba::black());
+        assert_eq!(image.pixel(9, 9), &Rgba::white());
+        assert_eq!(image.pixel(14, 14), &Rgba::white());
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(all(feature = "tokio", feature = "png"))]
+
+use ril::prelude::*;
+
+#[tokio::test]
+async fn test_async_png() -> ril::Result<()> {
+    let image = Image::from_fn(16, 16, |x, y| Rgb::new(x as u8 * 16, y as u8 * 16, 128));
+
+    let mut bytes = Vec::new();
+    image.encode_async(ImageFormat::Png, &mut bytes).await?;
+    let mut blocking = Vec::new();
+    image.encode(ImageFormat::Png, &mut blocking)?;
+    assert_eq!(bytes, blocking);
+
+    let decoded = Image::<Rgb>::from_async_reader(ImageFormat::Png, bytes.as_slice()).await?;
+    assert_eq!(decoded.data, image.data);
+    let decoded = Image::<Rgb>::from_async_reader_inferred(bytes.as_slice()).await?;
+    assert_eq!(decoded.data, image.data);
+
+    let path = std::env::temp_dir().join("ril_async.png");
+    image.save_async(ImageFormat::Png, &path).await?;
+    let opened = Image::<Rgb>::open_async(&path).await?;
+    assert_eq!(opened.data, image.data);
+    std::fs::remove_file(&path)?;
+
+    let frames = ImageSequence::<Rgb>::from_async_reader(ImageFormat::Png, bytes.as_slice())
+        .await?
+        .collect::<ril::Result<Vec<_>>>()?;
+    assert_eq!(frames.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_encoder_decoder() -> ril::Result<()> {
+    use ril::encode::{AsyncDecoder, AsyncEncoder};
+    use ril::encodings::png::{PngDecoder, PngEncoder};
+
+    // Noise barely compresses, so the encoded image spans several chunks
+    let image = Image::from_fn(256, 256, |x, y| {
+        let hash = (x * 256 + y).wrapping_mul(0x9e37_79b9).rotate_left(13) ^ 0x2545_f491;
+        let hash = hash.wrapping_mul(0x85eb_ca6b);
+        Rgb::new(hash as u8, (hash >> 8) as u8, (hash >> 16) as u8)
+    });
+
+    let mut blocking = Vec::new();
+    image.encode(ImageFormat::Png, &mut blocking)?;
+    assert!(blocking.len() > 64 * 1024);
+
+    let mut bytes = Vec::new();
+    PngEncoder::encode_static_async(image.clone(), &mut bytes).await?;
+    assert_eq!(bytes, blocking);
+
+    let decoded: Image<Rgb> = PngDecoder::new().decode_async(bytes.as_slice()).await?;
+    assert_eq!(decoded.data, image.data);
+
+    let sequence = ImageSequence::new().with_frame(Frame::from_image(image.clone()));
+    let mut bytes = Vec::new();
+    PngEncoder::encode_sequence_async(sequence.clone(), &mut bytes).await?;
+    let mut expected = Vec::new();
+    sequence.encode(ImageFormat::Png, &mut expected)?;
+    assert_eq!(bytes, expected);
+
+    Ok(())
+}
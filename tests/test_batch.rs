@@ -0,0 +1,22 @@
+#![cfg(all(feature = "rayon", feature = "png"))]
+
+use ril::batch::Batch;
+use ril::prelude::*;
+use std::path::Path;
+
+#[test]
+fn test_batch_process() {
+    let dir = std::env::temp_dir().join("ril_test_batch");
+    let report = Batch::new()
+        .with_output_dir(&dir)
+        .with_format(ImageFormat::Png)
+        .process(
+            ["tests/sample.png", "tests/missing.png"],
+            |image: Image<Rgba>| image.inverted(),
+        );
+
+    assert_eq!(report.succeeded, [dir.join("sample.png")]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, Path::new("tests/missing.png"));
+    assert!(Image::<Rgba>::open(dir.join("sample.png")).is_ok());
+}
@@ -0,0 +1,1083 @@
+use ril::morph::{
+    Dilation, DistanceMetric, DropShadow, Erosion, HitOrMiss, KernelImage, KernelShape,
+    MorphOperation, Morphology, Stroke, StrokePlacement,
+};
+use ril::prelude::*;
+
+#[test]
+fn test_antialiased_line() {
+    let mut image = Image::new(8, 8, Rgb::black());
+    let line =
+        Line::new((0, 0), (0, 0), Rgb::white()).with_subpixel_endpoints((1.0, 2.5), (6.0, 2.5));
+    image.draw(&line);
+
+    // The line lies exactly between two rows, so both are covered by half.
+    for x in 2..=5 {
+        assert_eq!(image.pixel(x, 2).r, 128);
+        assert_eq!(image.pixel(x, 3).r, 128);
+    }
+    // The endpoints lie on the center of their pixels, so only half of them is covered.
+    assert_eq!(image.pixel(1, 2).r, 64);
+    assert_eq!(image.pixel(6, 3).r, 64);
+
+    assert_eq!(image.pixel(0, 2).r, 0);
+    assert_eq!(image.pixel(7, 2).r, 0);
+    assert_eq!(image.pixel(3, 1).r, 0);
+}
+
+#[test]
+fn test_short_antialiased_lines() {
+    // Both endpoints lie in the same pixel, which is covered once by the length of the line.
+    let mut image = Image::new(4, 4, Rgb::black());
+    image.draw(
+        &Line::new((0, 0), (0, 0), Rgb::white()).with_subpixel_endpoints((1.75, 2.0), (2.25, 2.0)),
+    );
+    assert_eq!(image.pixel(2, 2).r, 128);
+    assert_eq!(image.pixel(1, 2).r, 0);
+    assert_eq!(image.pixel(3, 2).r, 0);
+
+    // A line of a single pixel is blended only once, even with a translucent color.
+    let color = Rgba::new(255, 255, 255, 128);
+    let mut image = Image::new(4, 4, Rgba::black());
+    image.draw(&Line::new((2, 1), (2, 1), color).with_antialiased(true));
+
+    let mut expected = Image::new(4, 4, Rgba::black());
+    expected.overlay_pixel_with_alpha(2, 1, color, OverlayMode::Merge, 255);
+    assert_eq!(image.data, expected.data);
+}
+
+#[test]
+fn test_polyline() {
+    let mut image = Image::new(8, 8, Rgb::black());
+    image.draw(&Polyline::new([(1.0, 3.0), (6.0, 3.0)], Rgb::white()).with_thickness(2.0));
+
+    assert_eq!(image.pixel(3, 3).r, 255);
+    assert_eq!(image.pixel(3, 2).r, 128);
+    assert_eq!(image.pixel(3, 4).r, 128);
+    assert_eq!(image.pixel(1, 3).r, 128);
+    assert_eq!(image.pixel(0, 3).r, 0);
+
+    // Square caps extend the stroke by half of its thickness.
+    let mut image = Image::new(8, 8, Rgb::black());
+    image.draw(
+        &Polyline::new([(1.0, 3.0), (6.0, 3.0)], Rgb::white())
+            .with_thickness(2.0)
+            .with_cap(LineCap::Square),
+    );
+    assert_eq!(image.pixel(1, 3).r, 255);
+    assert_eq!(image.pixel(0, 3).r, 128);
+
+    // A miter join fills the outer corner completely.
+    let mut image = Image::new(8, 8, Rgb::black());
+    image.draw(
+        &Polyline::new([(1.0, 2.0), (5.0, 2.0), (5.0, 6.0)], Rgb::white()).with_thickness(2.0),
+    );
+    assert_eq!(image.pixel(5, 2).r, 255);
+    assert_eq!(image.pixel(6, 1).r, 64);
+}
+
+#[test]
+fn test_bezier() {
+    let mut image = Image::new(16, 16, Rgb::black());
+    image.draw(
+        &Bezier::quadratic((2.0, 12.0), (8.0, 0.0), (14.0, 12.0), Rgb::white())
+            .with_thickness(2.0)
+            .with_antialiased(false),
+    );
+
+    // The apex of the curve lies halfway between its chord and its control point.
+    assert_eq!(image.pixel(8, 6).r, 255);
+    assert_eq!(image.pixel(2, 12).r, 255);
+    assert_eq!(image.pixel(8, 12).r, 0);
+    assert_eq!(image.pixel(8, 1).r, 0);
+
+    // A cubic curve with collinear control points is a straight line.
+    let mut image = Image::new(16, 16, Rgb::black());
+    image.draw(
+        &Bezier::cubic(
+            (2.0, 8.0),
+            (6.0, 8.0),
+            (10.0, 8.0),
+            (13.0, 8.0),
+            Rgb::white(),
+        )
+        .with_thickness(2.0),
+    );
+    assert_eq!(image.pixel(8, 8).r, 255);
+    assert_eq!(image.pixel(8, 7).r, 128);
+    assert_eq!(image.pixel(8, 10).r, 0);
+}
+
+#[test]
+fn test_polygon_fill_rule() {
+    let star = [(32, 4), (49, 58), (4, 24), (60, 24), (15, 58)];
+
+    for antialiased in [false, true] {
+        let mut image = Image::new(64, 64, Rgb::black());
+        image.draw(
+            &Polygon::from_vertices(star)
+                .with_fill(Rgb::white())
+                .with_antialiased(antialiased),
+        );
+        assert_eq!(image.pixel(32, 36).r, 0);
+        assert_eq!(image.pixel(32, 14).r, 255);
+
+        let mut image = Image::new(64, 64, Rgb::black());
+        image.draw(
+            &Polygon::from_vertices(star)
+                .with_fill(Rgb::white())
+                .with_fill_rule(FillRule::NonZero)
+                .with_antialiased(antialiased),
+        );
+        assert_eq!(image.pixel(32, 36).r, 255);
+    }
+}
+
+#[test]
+fn test_rounded_rectangle() {
+    let mut image = Image::new(32, 32, Rgb::black());
+    image.draw(
+        &Rectangle::from_bounding_box(0, 0, 32, 32)
+            .with_fill(Rgb::white())
+            .with_corner_radii(8, 0, 8, 0),
+    );
+
+    assert_eq!(image.pixel(0, 0).r, 0);
+    assert_eq!(image.pixel(31, 0).r, 255);
+    assert_eq!(image.pixel(31, 31).r, 0);
+    assert_eq!(image.pixel(0, 31).r, 255);
+    assert_eq!(image.pixel(16, 16).r, 255);
+}
+
+#[test]
+fn test_path() {
+    let mut image = Image::new(16, 16, Rgb::black());
+    image.draw(
+        &Path::new()
+            .move_to(2.0, 2.0)
+            .line_to(13.0, 2.0)
+            .quad_to((13.0, 13.0), (2.0, 13.0))
+            .close()
+            .with_fill(Rgb::white())
+            .with_antialiased(false),
+    );
+
+    assert_eq!(image.pixel(4, 4).r, 255);
+    assert_eq!(image.pixel(12, 12).r, 0);
+    assert_eq!(image.pixel(1, 8).r, 0);
+}
+
+#[test]
+fn test_path_stroke_fill() {
+    let (red, green, blue) = (
+        Rgb::new(255, 0, 0),
+        Rgb::new(0, 255, 0),
+        Rgb::new(0, 0, 255),
+    );
+    let tile = Image::from_pixels(1, vec![red, green, blue]);
+
+    let mut image = Image::new(16, 16, Rgb::black());
+    image.draw(
+        &Path::new()
+            .move_to(2.0, 8.0)
+            .line_to(13.0, 8.0)
+            .with_stroke(PathStroke::new(Pattern::new(&tile), 3.0))
+            .with_antialiased(false),
+    );
+
+    // The pattern starts at the top of the stroke, not at the points of the path
+    assert_eq!(image.pixel(5, 6), &Rgb::black());
+    assert_eq!(image.pixel(5, 7), &green);
+    assert_eq!(image.pixel(5, 8), &blue);
+    assert_eq!(image.pixel(5, 9), &red);
+    assert_eq!(image.pixel(5, 10), &Rgb::black());
+}
+
+#[test]
+fn test_paste_with_opacity() {
+    let mut image = Image::new(4, 4, Rgba::black());
+    let overlay = Image::new(2, 2, Rgba::white());
+    image.paste_with_opacity(1, 1, &overlay, 0.5);
+
+    let pixel = *image.pixel(1, 1);
+    assert!((127..=128).contains(&pixel.r));
+    assert_eq!(pixel.a, 255);
+    assert_eq!(image.pixel(0, 0).r, 0);
+
+    image.paste_with_opacity(1, 1, &overlay, 0.0);
+    assert_eq!(*image.pixel(1, 1), pixel);
+}
+
+#[test]
+fn test_paste_with_alpha_mask() {
+    let gray = |value| Rgb::new(value, value, value);
+    let mut image = Image::new(4, 2, Rgb::black());
+    let overlay = Image::new(3, 1, gray(200));
+    let mask = Image::from_pixels(3, vec![L(0), L(128), L(255)]);
+    image.paste_with_alpha_mask(1, 1, &overlay, &mask);
+    assert_eq!(image.data, [0, 0, 0, 0, 0, 0, 100, 200].map(gray));
+
+    // Alpha masks are combined with binary masks
+    let mut image = Image::new(3, 1, Rgb::black());
+    let binary = Image::from_pixels(3, vec![BitPixel::on(), BitPixel::off(), BitPixel::on()]);
+    let paste = Paste::new(&overlay)
+        .with_mask(&binary)
+        .with_alpha_mask(&mask);
+    image.draw(&paste);
+    assert_eq!(image.data, [0, 0, 200].map(gray));
+}
+
+#[test]
+#[should_panic]
+fn test_paste_with_mismatched_alpha_mask() {
+    let overlay = Image::new(3, 1, Rgb::white());
+    let _ = Paste::new(&overlay).with_alpha_mask(&Image::new(2, 1, L(255)));
+}
+
+#[test]
+fn test_masked() {
+    let gray = |value| Rgb::new(value, value, value);
+    let mask = Image::from_pixels(3, vec![L(0), L(128), L(255)]);
+    let rectangle = Rectangle::from_bounding_box(0, 0, 4, 2).with_fill(gray(200));
+
+    let mut image = Image::new(4, 2, Rgb::black());
+    image.draw(&Masked::new(rectangle.clone(), &mask).with_position(1, 1));
+    assert_eq!(image.data, [0, 0, 0, 0, 0, 0, 100, 200].map(gray));
+
+    // Pixels of the mask outside of the image are clipped
+    let mut image = Image::new(2, 1, Rgb::black());
+    image.draw(&Masked::new(rectangle, &mask).with_position(-1, 0));
+    assert_eq!(image.data, [100, 200].map(gray));
+}
+
+#[test]
+fn test_masked_paletted() {
+    let palette = vec![Rgb::black(), Rgb::white()];
+    let mut image = Image::<PalettedRgb>::from_paletted_pixels(3, palette, [0, 0, 0, 0, 0, 1]);
+    let white = *image.pixel(2, 1);
+    let rectangle = Rectangle::from_bounding_box(0, 0, 3, 2).with_fill(white);
+    let mask = Image::from_pixels(2, vec![L(255), L(0)]);
+
+    image.draw(&Masked::new(rectangle, &mask));
+    let colors = image.data.iter().map(|pixel| pixel.color().r);
+    assert_eq!(colors.collect::<Vec<_>>(), [255, 0, 0, 0, 0, 255]);
+}
+
+#[test]
+fn test_clip() {
+    let rectangle = Rectangle::from_bounding_box(0, 0, 4, 3).with_fill(L(255));
+
+    let mut image = Image::new(4, 3, L(0));
+    image.draw(&Clip::rect(rectangle.clone(), 1, 1, 3, 9));
+    assert_eq!(
+        image.data,
+        [0, 0, 0, 0, 0, 255, 255, 0, 0, 255, 255, 0].map(L)
+    );
+
+    // Regions outside of the image are clipped out entirely
+    let mut image = Image::new(4, 3, L(0));
+    image.draw(&Clip::rect(rectangle.clone(), 4, 0, 8, 3));
+    assert_eq!(image.data, [L(0); 12]);
+
+    // Pixels outside of the mask are left untouched
+    let mask = Image::from_fn(3, 2, |x, y| BitPixel(x == y));
+    let mut image = Image::new(4, 3, L(0));
+    image.draw(&Clip::mask(rectangle, &mask));
+    assert_eq!(image.data, [255, 0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0].map(L));
+}
+
+#[test]
+fn test_clip_paletted() {
+    let palette = vec![Rgb::black(), Rgb::white()];
+    let mut image = Image::<PalettedRgb>::from_paletted_pixels(3, palette, [0, 0, 0, 0, 0, 1]);
+    let white = *image.pixel(2, 1);
+    let rectangle = Rectangle::from_bounding_box(0, 0, 3, 2).with_fill(white);
+
+    image.draw(&Clip::rect(rectangle.clone(), 1, 0, 2, 2));
+    image.draw(&Clip::mask(
+        rectangle,
+        &Image::from_pixels(1, vec![BitPixel(true)]),
+    ));
+    let colors = image
+        .data
+        .iter()
+        .map(|pixel| pixel.color().r)
+        .collect::<Vec<_>>();
+    assert_eq!(colors, [255, 255, 0, 0, 255, 255]);
+}
+
+#[test]
+fn test_negative_positions() {
+    let mut image = Image::new(8, 8, L(0));
+    image.draw(&Rectangle::at(-4, -2).with_size(6, 4).with_fill(L(255)));
+    assert_eq!(image.pixel(0, 0).value(), 255);
+    assert_eq!(image.pixel(1, 1).value(), 255);
+    assert_eq!(image.pixel(2, 0).value(), 0);
+    assert_eq!(image.pixel(0, 2).value(), 0);
+
+    let mut image = Image::new(8, 8, L(0));
+    image.draw(
+        &Ellipse::circle(-1, 4, 3)
+            .with_fill(L(255))
+            .with_antialiased(false),
+    );
+    assert_eq!(image.pixel(0, 4).value(), 255);
+    assert_eq!(image.pixel(2, 4).value(), 255);
+    assert_eq!(image.pixel(3, 4).value(), 0);
+
+    let mut image = Image::new(8, 8, L(0));
+    let source = Image::new(4, 4, L(255));
+    let kernel = KernelImage::from_shape(KernelShape::Rect, 1, 1);
+    image.draw(&Dilation::new(&source, &kernel).with_position(6, -2));
+    assert_eq!(image.pixel(7, 1).value(), 255);
+    assert_eq!(image.pixel(5, 1).value(), 0);
+    assert_eq!(image.pixel(7, 2).value(), 0);
+}
+
+#[test]
+fn test_subpixel_shapes() {
+    let mut image = Image::new(8, 8, Rgb::black());
+    image.draw(
+        &Rectangle::at(0, 0)
+            .with_subpixel_bounds(1.5, 1.0, 6.0, 7.0)
+            .with_fill(Rgb::white()),
+    );
+    assert_eq!(image.pixel(0, 4).r, 0);
+    assert!((127..=128).contains(&image.pixel(1, 4).r));
+    assert_eq!(image.pixel(3, 4).r, 255);
+    assert_eq!(image.pixel(6, 4).r, 0);
+
+    let mut image = Image::new(16, 16, Rgb::black());
+    image.draw(
+        &Ellipse::new()
+            .with_subpixel_position(7.5, 7.5)
+            .with_subpixel_radii(4.0, 4.0)
+            .with_fill(Rgb::white()),
+    );
+    // Edges lie halfway into pixels 3 and 12, symmetric around the center between pixels 7 and 8
+    let (left, right) = (image.pixel(3, 7).r, image.pixel(12, 7).r);
+    assert!((100..=155).contains(&left) && left.abs_diff(right) <= 2);
+    assert_eq!(image.pixel(7, 7).r, 255);
+}
+
+#[test]
+fn test_morphology() {
+    let mut source = Image::new(16, 16, L(0));
+    source.draw(&Rectangle::from_bounding_box(4, 4, 12, 12).with_fill(L(255)));
+    *source.pixel_mut(1, 1) = L(255);
+    *source.pixel_mut(8, 8) = L(0);
+    let kernel = KernelImage::from_shape(KernelShape::Rect, 3, 3);
+    let apply =
+        |operation| Image::new(16, 16, L(0)).with(&Morphology::new(&source, &kernel, operation));
+
+    // The speck is removed while the square and its hole are kept intact
+    let opening = apply(MorphOperation::Opening);
+    assert_eq!(opening.pixel(1, 1).value(), 0);
+    assert_eq!(opening.pixel(4, 4).value(), 255);
+    assert_eq!(opening.pixel(11, 11).value(), 255);
+    assert_eq!(opening.pixel(8, 8).value(), 0);
+
+    // The hole is filled while the speck is kept
+    let closing = apply(MorphOperation::Closing);
+    assert_eq!(closing.pixel(8, 8).value(), 255);
+    assert_eq!(closing.pixel(1, 1).value(), 255);
+    assert_eq!(apply(MorphOperation::BlackHat).pixel(8, 8).value(), 255);
+
+    let top_hat = apply(MorphOperation::TopHat);
+    assert_eq!(top_hat.pixel(1, 1).value(), 255);
+    assert_eq!(top_hat.pixel(6, 6).value(), 0);
+
+    // The gradient only covers the edges of the square
+    let gradient = apply(MorphOperation::Gradient);
+    assert_eq!(gradient.pixel(4, 8).value(), 255);
+    assert_eq!(gradient.pixel(3, 8).value(), 255);
+    assert_eq!(gradient.pixel(6, 6).value(), 0);
+}
+
+#[test]
+fn test_custom_kernels() {
+    let diamond: Image<BitPixel> = KernelImage::from_shape(KernelShape::Diamond, 5, 5).into();
+    let rows = diamond
+        .data
+        .chunks(5)
+        .map(|row| row.iter().filter(|pixel| pixel.value()).count())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, [1, 3, 5, 3, 1]);
+
+    // A kernel that only reaches to the right dilates in the opposite direction
+    let mut source = Image::new(8, 1, L(0));
+    *source.pixel_mut(4, 0) = L(255);
+    let kernel = KernelImage::from_image(&Image::from_pixels(3, vec![L(0), L(0), L(255)]));
+    let dilated = Image::new(8, 1, L(0)).with(&Dilation::new(&source, &kernel));
+    assert_eq!(dilated.pixel(3, 0).value(), 255);
+    assert_eq!(dilated.pixel(5, 0).value(), 0);
+
+    let kernel = KernelImage::from_weights(3, &[0.0, 0.0, 1.0]);
+    let weighted = Image::new(8, 1, L(0)).with(&Dilation::new(&source, &kernel));
+    assert_eq!(weighted.data, dilated.data);
+}
+
+#[test]
+fn test_separable_morphology() {
+    let data = (0..23 * 17_u32)
+        .map(|i| L((i * 97 % 251) as u8))
+        .collect::<Vec<_>>();
+    let source = Image::from_pixels(23, data);
+    let kernel = KernelImage::from_shape(KernelShape::Rect, 5, 3);
+
+    // Compare against folding every cell of the kernel, anchored at (2, 1)
+    let reference = |erode: bool| {
+        let mut image = Image::new(23, 17, L(0));
+        for (x, y) in (0..17_i32).flat_map(|y| (0..23_i32).map(move |x| (x, y))) {
+            let window = (-2..=2).flat_map(|dx| (-1..=1).map(move |dy| (x + dx, y + dy)));
+            let values = window.filter(|&(x, y)| x >= 0 && y >= 0).map(|(x, y)| {
+                source
+                    .get_pixel(x as u32, y as u32)
+                    .map_or(0, |p| p.value())
+            });
+            let value = if erode { values.min() } else { values.max() };
+            *image.pixel_mut(x as u32, y as u32) = L(value.unwrap());
+        }
+        image
+    };
+
+    let dilated = Image::new(23, 17, L(0)).with(&Dilation::new(&source, &kernel));
+    let eroded = Image::new(23, 17, L(0)).with(&Erosion::new(&source, &kernel));
+    assert_eq!(dilated.data, reference(false).data);
+    assert_eq!(eroded.data, reference(true).data);
+}
+
+#[test]
+fn test_convolution_filters() {
+    let data = (0..19 * 13_u32)
+        .map(|i| L((i * 97 % 251) as u8))
+        .collect::<Vec<_>>();
+    let source = Image::from_pixels(19, data);
+
+    // A separable kernel gives the same result as the full kernel it is the product of
+    let (row, column) = ([1.0, 2.0, 1.0], [0.25, 0.5, 0.25]);
+    let full = column
+        .iter()
+        .flat_map(|c| row.iter().map(move |r| r * c / 4.0))
+        .collect::<Vec<_>>();
+    for edge in [
+        EdgeMode::Clamp,
+        EdgeMode::Reflect,
+        EdgeMode::Wrap,
+        EdgeMode::Constant(L(40)),
+    ] {
+        let separable = Convolution::separable(row.map(|w| w / 4.0), column).with_edge_mode(edge);
+        let full = Convolution::new(3, 3, full.clone()).with_edge_mode(edge);
+        assert_eq!(
+            source.clone().with(&separable).data,
+            source.clone().with(&full).data
+        );
+    }
+
+    // Blurs keep solid colors, and transparent pixels do not bleed into opaque ones
+    let image = Image::from_fn(16, 16, |x, _| {
+        if x < 8 {
+            Rgba::new(255, 0, 0, 255)
+        } else {
+            Rgba::new(0, 0, 255, 0)
+        }
+    });
+    for blurred in [
+        image.clone().with(&GaussianBlur::new(2.0)),
+        image.clone().with(&BoxBlur::new(3)),
+    ] {
+        assert_eq!(blurred.pixel(0, 8), &Rgba::new(255, 0, 0, 255));
+        let edge = blurred.pixel(8, 8);
+        assert!(edge.a > 0 && edge.a < 255);
+        assert_eq!((edge.r, edge.b), (255, 0));
+    }
+
+    // Sharpening keeps flat areas and increases the contrast of edges
+    let step = Image::from_fn(8, 8, |x, _| L(if x < 4 { 64 } else { 192 }));
+    let sharpened = step.clone().with(&Sharpen::new(1.0));
+    assert_eq!(sharpened.pixel(0, 0).value(), 64);
+    assert!(sharpened.pixel(3, 0).value() < 64 && sharpened.pixel(4, 0).value() > 192);
+
+    let edges = step.with(&Sobel::new());
+    assert_eq!(edges.pixel(0, 0).value(), 0);
+    assert_eq!(edges.pixel(3, 0).value(), 255);
+}
+
+#[test]
+fn test_distance_transform() {
+    // A single background pixel in the center of a 5x5 image
+    let mut image = Image::new(5, 5, BitPixel::on());
+    *image.pixel_mut(2, 2) = BitPixel::off();
+    let distance = |metric, x, y| image.distance_transform(metric).pixel(x, y).0;
+
+    assert_eq!(distance(DistanceMetric::Manhattan, 2, 2), 0.0);
+    assert_eq!(distance(DistanceMetric::Manhattan, 0, 0), 4.0);
+    assert_eq!(distance(DistanceMetric::Manhattan, 4, 1), 3.0);
+    assert_eq!(distance(DistanceMetric::Chebyshev, 0, 0), 2.0);
+    assert_eq!(distance(DistanceMetric::Chebyshev, 4, 1), 2.0);
+    assert_eq!(distance(DistanceMetric::Euclidean, 4, 2), 2.0);
+    assert_eq!(distance(DistanceMetric::Euclidean, 0, 0), 8_f32.sqrt());
+    assert_eq!(distance(DistanceMetric::Euclidean, 3, 0), 5_f32.sqrt());
+
+    let image = Image::new(3, 2, BitPixel::on());
+    let distances = image.distance_transform(DistanceMetric::Euclidean);
+    assert!(distances
+        .data
+        .iter()
+        .all(|distance| distance.0.is_infinite()));
+}
+
+#[test]
+fn test_flood_fill() {
+    let mut image = Image::new(32, 32, Rgb::white());
+    image.draw(
+        &Rectangle::<Rgb>::at(8, 8)
+            .with_size(16, 16)
+            .with_border(Border::new(Rgb::black(), 1)),
+    );
+    image.set_pixel(12, 12, Rgb::new(250, 250, 250));
+
+    let mut exact = image.clone();
+    exact.draw(&FloodFill::new(16, 16, Rgb::new(255, 0, 0)));
+    assert_eq!(exact.pixel(16, 16), &Rgb::new(255, 0, 0));
+    assert_eq!(exact.pixel(12, 12), &Rgb::new(250, 250, 250));
+    assert_eq!(exact.pixel(7, 16), &Rgb::black());
+    assert_eq!(exact.pixel(0, 0), &Rgb::white());
+
+    // The off-white pixel is within the tolerance, but the border is not
+    image.draw(&FloodFill::new(16, 16, Rgb::new(255, 0, 0)).with_tolerance(5));
+    assert_eq!(image.pixel(12, 12), &Rgb::new(255, 0, 0));
+    assert_eq!(image.pixel(0, 0), &Rgb::white());
+}
+
+#[test]
+fn test_skeletonize() {
+    // A thick square ring thins down to a closed loop around the same hole
+    let mut image = Image::new(16, 16, BitPixel::off());
+    for (x, y) in (2..14).flat_map(|y| (2..14).map(move |x| (x, y))) {
+        let border = x < 5 || x >= 11 || y < 5 || y >= 11;
+        image.set_pixel(x, y, BitPixel(border));
+    }
+
+    let skeleton = image.skeletonize();
+    assert!(skeleton.pixel(3, 8).value());
+    assert!(skeleton.pixel(8, 12).value());
+    assert!(!skeleton.pixel(2, 8).value() && !skeleton.pixel(4, 8).value());
+    assert!(!skeleton.pixel(8, 8).value());
+
+    // Every pixel of the skeleton lies within the original shape and is part of a line
+    for (x, y) in (0..16).flat_map(|y| (0..16).map(move |x| (x, y))) {
+        if skeleton.pixel(x, y).value() {
+            assert!(image.pixel(x, y).value());
+            let thick = (x..x + 2).all(|x| (y..y + 2).all(|y| skeleton.pixel(x, y).value()));
+            assert!(!thick, "skeleton is thicker than one pixel at ({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn test_hit_or_miss() {
+    // The endpoints of a horizontal line
+    let mut image = Image::new(8, 3, BitPixel::off());
+    for x in 2..6 {
+        image.set_pixel(x, 1, BitPixel::on());
+    }
+
+    let end = KernelImage::from_weights(3, &[0.0, -1.0, 0.0, -1.0, 1.0, 1.0, 0.0, -1.0, 0.0]);
+    let mut matches = Image::new(8, 3, BitPixel::on());
+    matches.draw(&HitOrMiss::new(&image, &end));
+    assert!(matches.pixel(2, 1).value());
+    assert_eq!(matches.data.iter().filter(|pixel| pixel.value()).count(), 1);
+
+    // The same pattern with a separate background kernel
+    let hit = KernelImage::from_weights(3, &[0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+    let miss = KernelImage::from_weights(3, &[0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+    let mut separate = Image::new(8, 3, BitPixel::off());
+    separate.draw(&HitOrMiss::new(&image, &hit).with_background(&miss));
+    assert_eq!(separate.data, matches.data);
+}
+
+#[test]
+fn test_stroke_placement() {
+    // A square with a semi-transparent center
+    let mut shape = Image::new(16, 16, Rgba::transparent());
+    for (x, y) in (4..12).flat_map(|y| (4..12).map(move |x| (x, y))) {
+        let alpha = if (6..10).contains(&x) && (6..10).contains(&y) {
+            128
+        } else {
+            255
+        };
+        shape.set_pixel(x, y, Rgba::new(255, 255, 255, alpha));
+    }
+
+    let mut outside = shape.clone();
+    outside.draw(&Stroke::new(&shape, 3, Rgba::black()));
+    assert_eq!(outside.pixel(3, 8), &Rgba::black());
+    assert_eq!(outside.pixel(4, 8), &Rgba::white());
+    // The stroke does not show through the semi-transparent pixels
+    assert_eq!(outside.pixel(8, 8), shape.pixel(8, 8));
+
+    let mut inside = shape.clone();
+    inside.draw(&Stroke::new(&shape, 3, Rgba::black()).with_placement(StrokePlacement::Inside));
+    assert_eq!(inside.pixel(4, 8), &Rgba::black());
+    assert_eq!(inside.pixel(2, 8), &Rgba::transparent());
+
+    let mut center = shape.clone();
+    let stroke = Stroke::new(&shape, 6, Rgba::black()).with_placement(StrokePlacement::Center);
+    center.draw(&stroke);
+    assert_eq!(center.pixel(3, 8), &Rgba::black());
+    assert_eq!(center.pixel(4, 8), &Rgba::black());
+    assert_eq!(center.pixel(8, 8), shape.pixel(8, 8));
+
+    // Strokes can be filled with textures
+    let texture = Image::new(16, 16, Rgba::new(255, 0, 0, 255));
+    let mut textured = shape.clone();
+    textured.draw(&Stroke::new(&shape, 3, &texture));
+    assert_eq!(textured.pixel(3, 8), &Rgba::new(255, 0, 0, 255));
+}
+
+#[test]
+fn test_drop_shadow() {
+    let red = Rgba::new(255, 0, 0, 255);
+    let logo = Image::new(4, 4, red);
+    let canvas = Image::new(16, 16, Rgba::white());
+
+    let hard = canvas
+        .clone()
+        .with(&DropShadow::new(&logo, (2, 2), 0, Rgba::black()).with_position(4, 4));
+    assert_eq!(hard.pixel(4, 4), &red);
+    assert_eq!(hard.pixel(7, 7), &red);
+    assert_eq!(hard.pixel(9, 9), &Rgba::black());
+    assert_eq!(hard.pixel(10, 8), &Rgba::white());
+
+    let spread = canvas.clone().with(
+        &DropShadow::new(&logo, (2, 2), 0, Rgba::black())
+            .with_position(4, 4)
+            .with_spread(1),
+    );
+    assert_eq!(spread.pixel(10, 8), &Rgba::black());
+    assert_eq!(spread.pixel(11, 8), &Rgba::white());
+
+    let soft = canvas.with(&DropShadow::new(&logo, (2, 2), 4, Rgba::black()).with_position(4, 4));
+    assert_eq!(soft.pixel(5, 5), &red);
+    assert!((1..255).contains(&soft.pixel(11, 8).r));
+    assert!(soft.pixel(9, 9).r < soft.pixel(11, 8).r);
+}
+
+#[test]
+fn test_per_call_overlay_mode() {
+    let base = Image::new(8, 8, Rgba::new(0, 0, 255, 255));
+    let color = Rgba::new(255, 0, 0, 128);
+    let merged = Rgba::new(0, 0, 255, 255).merge(color);
+
+    // Each drawable overrides the overlay mode of the image, which merges by default
+    let check = |draw: &dyn Fn(&mut Image<Rgba>, OverlayMode)| {
+        let mut image = base.clone();
+        draw(&mut image, OverlayMode::Replace);
+        assert_eq!(image.pixel(4, 4), &color);
+        assert_eq!(image.overlay_mode(), OverlayMode::Merge);
+
+        let mut image = base.clone();
+        draw(&mut image, OverlayMode::Merge);
+        assert_eq!(image.pixel(4, 4), &merged);
+    };
+    check(&|image, mode| {
+        image.draw(&Line::new((0, 4), (7, 4), color).with_overlay_mode(mode));
+    });
+    check(&|image, mode| {
+        image.draw(
+            &Line::new((0, 4), (7, 4), color)
+                .with_thickness(3)
+                .with_overlay_mode(mode),
+        );
+    });
+    check(&|image, mode| {
+        image.draw(
+            &Polyline::new([(0.0, 4.0), (4.0, 4.0), (7.0, 4.0)], color)
+                .with_antialiased(false)
+                .with_overlay_mode(mode),
+        );
+    });
+    check(&|image, mode| {
+        image.draw(
+            &Path::new()
+                .move_to(0.0, 0.0)
+                .line_to(8.0, 0.0)
+                .line_to(8.0, 8.0)
+                .line_to(0.0, 8.0)
+                .close()
+                .with_fill(color)
+                .with_antialiased(false)
+                .with_overlay_mode(mode),
+        );
+    });
+    check(&|image, mode| {
+        image.draw(
+            &Polygon::from_vertices([(0, 0), (7, 0), (7, 7), (0, 7)])
+                .with_fill(color)
+                .with_overlay_mode(mode),
+        );
+    });
+    check(&|image, mode| {
+        image.draw(
+            &Rectangle::from_bounding_box(0, 0, 8, 8)
+                .with_fill(color)
+                .with_overlay_mode(mode),
+        );
+    });
+    check(&|image, mode| {
+        image.draw(
+            &Ellipse::circle(4, 4, 3)
+                .with_fill(color)
+                .with_overlay_mode(mode),
+        );
+    });
+
+    // Morphological operations replace pixels by default, but can merge them instead
+    let source = Image::new(8, 8, color);
+    let kernel = KernelImage::from_shape(KernelShape::Rect, 3, 3);
+    let mut image = base.clone();
+    image.draw(&Dilation::new(&source, &kernel));
+    assert_eq!(image.pixel(4, 4), &color);
+    let mut image = base.clone();
+    image.draw(&Dilation::new(&source, &kernel).with_overlay_mode(OverlayMode::Merge));
+    assert_eq!(image.pixel(4, 4), &merged);
+    let mut image = base.clone();
+    image.draw(&Erosion::new(&source, &kernel).with_overlay_mode(OverlayMode::Merge));
+    assert_eq!(image.pixel(4, 4), &merged);
+
+    // Strokes are drawn underneath the image by default, but can be drawn on top of it
+    let mut shape = Image::new(8, 8, Rgba::transparent());
+    for (x, y) in (3..6).flat_map(|y| (3..6).map(move |x| (x, y))) {
+        shape.set_pixel(x, y, Rgba::white());
+    }
+    let stroke = Stroke::new(&shape, 3, color);
+    let mut image = Image::new(8, 8, Rgba::white());
+    image.draw(&stroke);
+    assert_eq!(image.pixel(2, 4), &Rgba::white());
+    let mut image = Image::new(8, 8, Rgba::white());
+    image.draw(&stroke.with_overlay_mode(OverlayMode::Replace));
+    assert_eq!(image.pixel(2, 4), &color);
+
+    // Vignettes always merge, unless told otherwise
+    let vignette = Vignette::new(Rgb::black())
+        .with_strength(1.0)
+        .with_radius(0.0);
+    let merged = Image::new(8, 8, Rgb::white()).with(&vignette);
+    let mut replaced = Image::new(8, 8, Rgb::white()).with_overlay_mode(OverlayMode::Replace);
+    replaced.draw(&vignette);
+    assert_eq!(replaced.data, merged.data);
+    let replaced = Image::new(8, 8, Rgb::white())
+        .with(&vignette.clone().with_overlay_mode(OverlayMode::Replace));
+    assert_eq!(replaced.pixel(0, 0), &Rgb::black());
+    assert_ne!(merged.pixel(0, 0), &Rgb::black());
+}
+
+#[test]
+fn test_draw_ext_chaining() {
+    fn decorate(image: &mut Image<L>) -> &mut Image<L> {
+        image
+            .with(&Rectangle::from_bounding_box(0, 0, 4, 2).with_fill(L(100)))
+            .with(&Rectangle::from_bounding_box(2, 0, 4, 2).with_fill(L(200)))
+    }
+
+    let mut image = Image::new(4, 3, L(0));
+    // Chained draws are applied in order to the borrowed image
+    decorate(&mut image).set_pixel(0, 2, L(50));
+    assert_eq!(
+        image.data,
+        [100, 100, 200, 200, 100, 100, 200, 200, 50, 0, 0, 0].map(L)
+    );
+
+    // Owned images chain the same way
+    let owned = Image::new(4, 3, L(0))
+        .with(&Rectangle::from_bounding_box(0, 0, 4, 2).with_fill(L(100)))
+        .with(&Rectangle::from_bounding_box(2, 0, 4, 2).with_fill(L(200)));
+    assert_eq!(owned.data[..8], image.data[..8]);
+}
+
+#[test]
+fn test_layer_stack() {
+    let blue = Image::new(2, 1, Rgb::new(0, 0, 255));
+    let hidden = Image::new(4, 1, Rgb::new(255, 0, 0));
+    let mask = Image::from_pixels(2, vec![L(255), L(0)]);
+    let group = LayerStack::new(2, 1, Rgb::new(255, 0, 0)).with_layer(Layer::draw(
+        Rectangle::at(1, 0).with_size(1, 1).with_fill(Rgb::black()),
+    ));
+
+    let stack = LayerStack::new(4, 1, Rgb::black())
+        .with_layer(Layer::draw(
+            Rectangle::from_bounding_box(0, 0, 4, 1).with_fill(Rgb::white()),
+        ))
+        .with_layer(Layer::image(&hidden).with_visible(false))
+        .with_layer(Layer::image(&blue).with_position(2, 0).with_opacity(128))
+        .with_layer(
+            Layer::draw(Rectangle::from_bounding_box(0, 0, 4, 1).with_fill(Rgb::new(0, 255, 0)))
+                .with_mask(&mask),
+        )
+        // Groups are clipped to the bounds of the stack
+        .with_layer(Layer::group(group).with_position(3, 0));
+    assert_eq!(stack.len(), 5);
+
+    let image = stack.flatten();
+    assert_eq!(image.dimensions(), (4, 1));
+    assert_eq!(
+        image.data,
+        [
+            Rgb::new(0, 255, 0),
+            Rgb::white(),
+            Rgb::white().merge_with_alpha(Rgb::new(0, 0, 255), 128),
+            Rgb::new(255, 0, 0),
+        ]
+    );
+
+    // Drawing a stack onto an image disregards the background of the stack
+    let mut image = Image::new(4, 1, Rgb::new(9, 9, 9));
+    image.draw(&LayerStack::new(4, 1, Rgb::black()).with_layer(Layer::image(&blue)));
+    assert_eq!(image.pixel(0, 0), &Rgb::new(0, 0, 255));
+    assert_eq!(image.pixel(2, 0), &Rgb::new(9, 9, 9));
+}
+
+#[test]
+fn test_layer_stack_paletted() {
+    let palette = vec![Rgb::black(), Rgb::white()];
+    let mut image = Image::<PalettedRgb>::from_paletted_pixels(3, palette, [0, 0, 1]);
+    let white = *image.pixel(2, 0);
+    let mask = Image::from_pixels(2, vec![L(255), L(0)]);
+    let layer = Layer::draw(Rectangle::from_bounding_box(0, 0, 3, 1).with_fill(white));
+
+    image.draw(&LayerStack::new(3, 1, white).with_layer(layer.with_mask(&mask)));
+    let colors = image.data.iter().map(|pixel| pixel.color().r);
+    assert_eq!(colors.collect::<Vec<_>>(), [255, 0, 255]);
+}
+
+#[test]
+fn test_parallel_filters() {
+    // Rows are processed in parallel, so a non-square image catches rows and columns being mixed up
+    let (width, height) = (31, 19);
+    let source = Image::from_fn(width, height, |x, y| L(((x * 37 + y * 101) % 256) as u8));
+    let at = |x: i64, y: i64| {
+        let (x, y) = (x.clamp(0, width as i64 - 1), y.clamp(0, height as i64 - 1));
+        f64::from(source.pixel(x as u32, y as u32).value())
+    };
+
+    // Compare against convolving each pixel in turn with an asymmetric kernel anchored at (1, 2)
+    let weights = (1..=15)
+        .map(f64::from)
+        .map(|w| w / 120.0)
+        .collect::<Vec<_>>();
+    let reference = Image::from_fn(width, height, |x, y| {
+        let sum = (0..15_i64)
+            .map(|i| weights[i as usize] * at(x as i64 + i % 3 - 1, y as i64 + i / 3 - 2))
+            .sum::<f64>();
+        L(sum.round() as u8)
+    });
+    let convolved = source
+        .clone()
+        .with(&Convolution::new(3, 5, weights.clone()));
+    for (a, b) in convolved.data.iter().zip(&reference.data) {
+        assert!(a.value().abs_diff(b.value()) <= 1, "{a:?} != {b:?}");
+    }
+
+    // The separable kernel [1, 2] x [1, 1, 2] is anchored at (1, 1)
+    let reference = Image::from_fn(width, height, |x, y| {
+        let (x, y) = (x as i64, y as i64);
+        let sum = [
+            (-1, -1, 1.0),
+            (0, -1, 2.0),
+            (-1, 0, 1.0),
+            (0, 0, 2.0),
+            (-1, 1, 2.0),
+            (0, 1, 4.0),
+        ]
+        .into_iter()
+        .map(|(dx, dy, weight)| weight / 12.0 * at(x + dx, y + dy))
+        .sum::<f64>();
+        L(sum.round() as u8)
+    });
+    let filter = Convolution::separable([1.0 / 3.0, 2.0 / 3.0], [0.25, 0.25, 0.5]);
+    let convolved = source.clone().with(&filter);
+    for (a, b) in convolved.data.iter().zip(&reference.data) {
+        assert!(a.value().abs_diff(b.value()) <= 1, "{a:?} != {b:?}");
+    }
+
+    // Diamond kernels visit every point for every pixel instead of taking the separable path
+    let kernel = KernelImage::from_shape(KernelShape::Diamond, 5, 5);
+    let reference = |erode: bool| {
+        Image::from_fn(width, height, |x, y| {
+            let (x, y) = (x as i64, y as i64);
+            let window = (-2..=2_i64)
+                .flat_map(|dx| (-2..=2_i64).map(move |dy| (dx, dy)))
+                .filter(|(dx, dy)| dx.abs() + dy.abs() <= 2)
+                .map(|(dx, dy)| (x + dx, y + dy));
+            let values = window.filter(|&(x, y)| x >= 0 && y >= 0).map(|(x, y)| {
+                source
+                    .get_pixel(x as u32, y as u32)
+                    .map_or(0, |p| p.value())
+            });
+            L(if erode { values.min() } else { values.max() }.unwrap())
+        })
+    };
+
+    let dilated = Image::new(width, height, L(0)).with(&Dilation::new(&source, &kernel));
+    let eroded = Image::new(width, height, L(0)).with(&Erosion::new(&source, &kernel));
+    assert_eq!(dilated.data, reference(false).data);
+    assert_eq!(eroded.data, reference(true).data);
+}
+
+#[test]
+fn test_star() {
+    // Tips alternate with inner vertices, starting with a tip straight up from the center
+    let star = Polygon::<Rgb>::star(4, (32, 32), 20, 8);
+    assert_eq!(
+        star.vertices(),
+        [
+            (32, 12),
+            (38, 26),
+            (52, 32),
+            (38, 38),
+            (32, 52),
+            (26, 38),
+            (12, 32),
+            (26, 26),
+        ]
+    );
+
+    let star = Polygon::<Rgb>::star(5, (32, 32), 30, 12);
+    assert_eq!(star.vertices().len(), 10);
+    assert_eq!(star.vertices()[0], (32, 2));
+    for (i, &(x, y)) in star.vertices().iter().enumerate() {
+        let distance = (f64::from(x) - 32.0).hypot(f64::from(y) - 32.0);
+        let expected = if i % 2 == 0 { 30.0 } else { 12.0 };
+        assert!(
+            (distance - expected).abs() < 1.0,
+            "vertex {i} is {distance} away from the center"
+        );
+    }
+
+    // The angle rotates the star, with an angle of zero pointing the first tip to the right
+    let star = Polygon::<Rgb>::star_rotated(4, (32, 32), 20, 8, 0.0);
+    assert_eq!(star.vertices()[0], (52, 32));
+    assert_eq!(star.vertices()[2], (32, 52));
+    let star = Polygon::<Rgb>::star_rotated(4, (32, 32), 20, 8, std::f64::consts::FRAC_PI_4);
+    assert_eq!(star.vertices()[0], (46, 18));
+    assert_eq!(star.vertices()[1], (40, 32));
+
+    // Only the points of the star are filled, not the space between them
+    let mut image = Image::new(64, 64, Rgb::black());
+    image.draw(&Polygon::star(4, (32, 32), 20, 8).with_fill(Rgb::white()));
+    for (x, y) in [(32, 32), (32, 16), (48, 32), (32, 48), (16, 32)] {
+        assert_eq!(image.pixel(x, y).r, 255, "({x}, {y}) is not filled");
+    }
+    for (x, y) in [(43, 21), (43, 43), (21, 43), (21, 21)] {
+        assert_eq!(image.pixel(x, y).r, 0, "({x}, {y}) is filled");
+    }
+}
+
+#[test]
+#[should_panic(expected = "n must be greater than or equal to 2")]
+fn test_star_too_few_points() {
+    let _ = Polygon::<Rgb>::star(1, (32, 32), 20, 8);
+}
+
+#[test]
+fn test_ellipse_rotation() {
+    let draw = |ellipse: Ellipse<Rgb>| {
+        Image::new(64, 64, Rgb::black()).with(
+            &ellipse
+                .with_position(32, 32)
+                .with_radii(20, 6)
+                .with_fill(Rgb::white())
+                .with_antialiased(false),
+        )
+    };
+
+    let image = draw(Ellipse::new());
+    assert_eq!(image.pixel(50, 32).r, 255);
+    assert_eq!(image.pixel(32, 36).r, 255);
+    assert_eq!(image.pixel(32, 45).r, 0);
+
+    // A quarter turn swaps the axes of the ellipse
+    let image = draw(Ellipse::new().with_rotation(core::f32::consts::FRAC_PI_2));
+    assert_eq!(image.pixel(32, 50).r, 255);
+    assert_eq!(image.pixel(36, 32).r, 255);
+    assert_eq!(image.pixel(45, 32).r, 0);
+
+    // Rotation is clockwise, so the major axis now runs from the top left to the bottom right
+    let image = draw(Ellipse::new().with_rotation_degrees(45.0));
+    assert_eq!(image.pixel(44, 44).r, 255);
+    assert_eq!(image.pixel(20, 20).r, 255);
+    assert_eq!(image.pixel(44, 20).r, 0);
+    assert_eq!(image.pixel(20, 44).r, 0);
+    assert_eq!(image.pixel(50, 32).r, 0);
+    assert_eq!(
+        image.data,
+        draw(Ellipse::new().with_rotation(core::f32::consts::FRAC_PI_4)).data
+    );
+}
+
+#[test]
+fn test_ellipse_antialiasing() {
+    let draw = |antialiased| {
+        Image::new(32, 32, Rgb::black()).with(
+            &Ellipse::circle(16, 16, 10)
+                .with_fill(Rgb::white())
+                .with_rotation_degrees(30.0)
+                .with_antialiased(antialiased),
+        )
+    };
+    let is_partial = |pixel: &Rgb| pixel.r > 0 && pixel.r < 255;
+
+    // Without antialiasing, pixels are either fully covered or not at all
+    let aliased = draw(false);
+    assert!(!aliased.data.iter().any(is_partial));
+    assert_eq!(aliased.pixel(16, 16).r, 255);
+    assert_eq!(aliased.pixel(16, 7).r, 255);
+    assert_eq!(aliased.pixel(16, 3).r, 0);
+
+    // With antialiasing, edge pixels are partially covered while the interior and exterior are not
+    let antialiased = draw(true);
+    assert!(antialiased.data.iter().any(is_partial));
+    assert_eq!(antialiased.pixel(16, 16).r, 255);
+    assert_eq!(antialiased.pixel(16, 3).r, 0);
+    assert_eq!(antialiased.pixel(0, 0).r, 0);
+
+    // Integer radii include the pixels on the edge, which puts the edge half a pixel further out
+    for (i, pixel) in antialiased.data.iter().enumerate() {
+        let (x, y) = ((i % 32) as f32 + 0.5, (i / 32) as f32 + 0.5);
+        let distance = (x - 16.0).hypot(y - 16.0);
+        if is_partial(pixel) {
+            assert!(
+                (distance - 10.5).abs() < 1.5,
+                "partially covered pixel is {distance} away from the center"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_vignette() {
+    let white = Image::new(8, 8, Rgb::white());
+    let vignette = Vignette::new(Rgb::black());
+    let image = white.clone().with(&vignette);
+
+    // The center is untouched, and the falloff is symmetrical and strongest at the corners
+    assert_eq!(image.pixel(3, 4), &Rgb::white());
+    let (corner, edge) = (image.pixel(0, 0).r, image.pixel(0, 4).r);
+    assert!(corner < edge && edge < 255, "{corner} {edge}");
+    assert_eq!(image.pixel(7, 7), image.pixel(0, 0));
+    assert_eq!(image.pixel(7, 0), image.pixel(0, 7));
+
+    // Vignettes without strength, or which start beyond the corners, do nothing
+    let image = white.clone().with(&vignette.clone().with_strength(0.0));
+    assert_eq!(image.data, white.data);
+    let image = white.clone().with(&vignette.clone().with_radius(1.0));
+    assert_eq!(image.data, white.data);
+
+    // Out of range values are clamped
+    let vignette = vignette.with_strength(2.0).with_radius(-1.0);
+    assert_eq!((vignette.strength, vignette.radius), (1.0, 0.0));
+    let image = white.with(&vignette);
+    assert!(image.pixel(0, 0).r < corner);
+    assert_ne!(image.pixel(3, 3), &Rgb::white());
+}
@@ -40,3 +40,55 @@ fn test_gif_palette_decode() -> ril::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_gif_dynamic_image_decode() -> ril::Result<()> {
+    let image = Image::<Dynamic>::open("tests/sample.gif")?;
+
+    assert_eq!(image.dimensions(), (256, 256));
+    assert_eq!(image.pixel(0, 0).as_rgb(), COLORS[0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_gif_palette_image_decode() -> ril::Result<()> {
+    let image = Image::<PalettedRgb>::open("tests/sample.gif")?;
+
+    assert_eq!(image.dimensions(), (256, 256));
+    assert_eq!(image.pixel(0, 0).color(), COLORS[0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_gif_sequence_round_trip() -> ril::Result<()> {
+    let mut sequence = ImageSequence::new().looped_exactly(3);
+    for (i, color) in COLORS.into_iter().enumerate() {
+        let mut image = Image::new(16, 16, Rgba::from(color));
+        image.set_pixel(0, 0, Rgba::transparent());
+
+        sequence.push_frame(
+            Frame::from_image(image)
+                .with_delay(Duration::from_millis(100 * (i as u64 + 1)))
+                .with_disposal(DisposalMethod::Background),
+        );
+    }
+
+    let mut buffer = Vec::new();
+    sequence.encode(ImageFormat::Gif, &mut buffer)?;
+
+    let decoded = ImageSequence::<Rgba>::from_bytes(ImageFormat::Gif, &buffer)?;
+    assert_eq!(decoded.len(), COLORS.len() as u32);
+    assert_eq!(decoded.loop_count(), LoopCount::Exactly(3));
+
+    let decoded = decoded.collect::<ril::Result<ImageSequence<_>>>()?;
+    for ((frame, expected), color) in decoded.iter().zip(sequence.iter()).zip(COLORS) {
+        assert_eq!(frame.delay(), expected.delay());
+        assert_eq!(frame.disposal(), DisposalMethod::Background);
+        assert_eq!(frame.pixel(0, 0).a, 0);
+        assert_eq!(frame.pixel(8, 8), &Rgba::from(color));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "gradient")]
+
+use ril::prelude::*;
+
+/// Draws a horizontal black to white gradient from `x = 4` to `x = 8` over a single row.
+fn draw_row(repeat: GradientRepeat) -> Image<Rgb> {
+    let gradient = LinearGradient::new()
+        .with_color(Rgb::black())
+        .with_color(Rgb::white())
+        .with_endpoints(
+            GradientPosition::Absolute(4, 0),
+            GradientPosition::Absolute(8, 0),
+        )
+        .with_repeat(repeat);
+
+    Image::new(16, 1, Rgb::new(255, 0, 0)).with(&gradient)
+}
+
+#[test]
+fn test_gradient_endpoints() {
+    let image = draw_row(GradientRepeat::Pad);
+    assert_eq!(image.pixel(4, 0), &Rgb::black());
+    assert_eq!(image.pixel(8, 0), &Rgb::white());
+
+    // The gradient gets lighter between its endpoints
+    let values = (4..=8).map(|x| image.pixel(x, 0).r).collect::<Vec<_>>();
+    assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Endpoints are relative to the image when drawing directly onto it
+    let vertical = LinearGradient::new()
+        .with_color(Rgb::black())
+        .with_color(Rgb::white())
+        .with_endpoints(
+            GradientPosition::Relative(0.0, 1.0),
+            GradientPosition::Relative(0.0, 0.0),
+        );
+    let image = Image::new(1, 8, Rgb::new(255, 0, 0)).with(&vertical);
+    assert!(image.pixel(0, 0).r > image.pixel(0, 7).r);
+}
+
+#[test]
+fn test_gradient_repeat() {
+    // Colors beyond the endpoints are extended
+    let image = draw_row(GradientRepeat::Pad);
+    assert_eq!(image.pixel(0, 0), &Rgb::black());
+    assert_eq!(image.pixel(12, 0), &Rgb::white());
+
+    // 1.25 renders the same color as 0.25, and -0.75 too
+    let image = draw_row(GradientRepeat::Repeat);
+    assert_eq!(image.pixel(9, 0), image.pixel(5, 0));
+    assert_eq!(image.pixel(1, 0), image.pixel(5, 0));
+    assert_ne!(image.pixel(5, 0), image.pixel(7, 0));
+
+    // 1.25 renders the same color as 0.75, and 1.75 as 0.25
+    let image = draw_row(GradientRepeat::Reflect);
+    assert_eq!(image.pixel(9, 0), image.pixel(7, 0));
+    assert_eq!(image.pixel(11, 0), image.pixel(5, 0));
+    assert_ne!(image.pixel(5, 0), image.pixel(7, 0));
+}
+
+#[test]
+fn test_gradient_region() {
+    let gradient = LinearGradient::new()
+        .with_color(Rgb::new(255, 0, 0))
+        .with_color(Rgb::new(0, 0, 255));
+
+    // Drawing over a region matches drawing over an image with the dimensions of the region
+    let region = Rect::new(4, 1, 8, 2);
+    let mut image = Image::new(16, 4, Rgb::black());
+    image.draw(&gradient.clone().with_region(region));
+    let expected = Image::new(8, 2, Rgb::black()).with(&gradient);
+    let drawn = image.copy_rect(region, OutOfBounds::Error).unwrap();
+    assert_eq!(drawn.data, expected.data);
+
+    // Pixels outside of the region are left untouched
+    for (i, pixel) in image.data.iter().enumerate() {
+        let (x, y) = ((i % 16) as i64, (i / 16) as i64);
+        if !region.contains(x, y) {
+            assert_eq!(pixel, &Rgb::black(), "pixel ({x}, {y}) was drawn");
+        }
+    }
+
+    // Regions exceeding the image are clipped to it
+    let mut image = Image::new(16, 4, Rgb::black());
+    image.draw(&gradient.with_region(Rect::new(12, -2, 8, 8)));
+    assert_ne!(image.pixel(15, 3), &Rgb::black());
+    assert_eq!(image.pixel(11, 0), &Rgb::black());
+}
@@ -0,0 +1,302 @@
+#![cfg(any(
+    feature = "image",
+    feature = "ndarray",
+    feature = "embedded-graphics",
+    feature = "wgpu",
+    feature = "bytemuck"
+))]
+
+use ril::prelude::*;
+
+/// A non-square image whose pixels all differ, to catch swapped axes and misordered channels.
+fn sample() -> Image<Rgba> {
+    Image::from_fn(3, 2, |x, y| {
+        Rgba::new(x as u8 * 100, y as u8 * 200, 50 + x as u8, 255 - y as u8)
+    })
+}
+
+fn assert_same<P: Pixel>(left: &Image<P>, right: &Image<P>) {
+    assert_eq!(left.dimensions(), right.dimensions());
+    for (a, b) in left.data.iter().zip(&right.data) {
+        assert_eq!(a.as_bytes().as_ref(), b.as_bytes().as_ref());
+    }
+}
+
+#[cfg(feature = "image")]
+mod image_interop {
+    use super::*;
+    use image::{DynamicImage, ImageBuffer};
+
+    fn round_trip<P>(image: Image<P>)
+    where
+        P: Pixel,
+        Image<P>: Into<DynamicImage> + TryFrom<DynamicImage, Error = ril::Error>,
+    {
+        let dynamic = image.clone().into();
+        assert_same(&Image::<P>::try_from(dynamic).unwrap(), &image);
+    }
+
+    #[test]
+    fn test_buffer_round_trips() {
+        let image = sample().convert::<Rgb>();
+        let buffer = ImageBuffer::from(image.clone());
+        assert_eq!(buffer.dimensions(), (3, 2));
+        assert_eq!(buffer.get_pixel(2, 1).0, [200, 200, 52]);
+        assert_same(&Image::try_from(buffer).unwrap(), &image);
+
+        let image = sample().convert::<L32F>();
+        let buffer = ImageBuffer::from(image.clone());
+        assert_eq!(buffer.get_pixel(2, 1).0, [image.pixel(2, 1).0]);
+        assert_same(&Image::try_from(buffer).unwrap(), &image);
+    }
+
+    #[test]
+    fn test_dynamic_round_trips() {
+        round_trip(sample().convert::<L>());
+        round_trip(sample().convert::<Rgb>());
+        round_trip(sample());
+        round_trip(sample().convert::<Rgb32F>());
+        round_trip(sample().convert::<Rgba32F>());
+
+        // Dynamic images of other color types are converted by the image crate first
+        let dynamic = DynamicImage::from(sample());
+        let luma = dynamic.to_luma8();
+        let image = Image::<L>::try_from(dynamic).unwrap();
+        assert_eq!(image.dimensions(), (3, 2));
+        assert_eq!(image.pixel(2, 1).0, luma.get_pixel(2, 1).0[0]);
+    }
+
+    #[test]
+    fn test_empty_buffer() {
+        let buffer = ImageBuffer::<image::Rgba<u8>, Vec<u8>>::new(0, 4);
+        assert!(matches!(
+            Image::<Rgba>::try_from(buffer),
+            Err(ril::Error::EmptyImageError)
+        ));
+    }
+}
+
+#[cfg(feature = "ndarray")]
+mod ndarray_interop {
+    use super::*;
+    use ndarray::{Array3, Axis};
+
+    #[test]
+    fn test_ndarray_layout() {
+        let image = sample();
+        let array = image.to_ndarray();
+
+        // Height comes first, then width, then channels
+        assert_eq!(array.dim(), (2, 3, 4));
+        assert_eq!(array[[1, 2, 0]], 200);
+        assert_eq!(array[[1, 2, 1]], 200);
+        assert_eq!(array[[1, 2, 2]], 52);
+        assert_eq!(array[[1, 2, 3]], 254);
+        assert_eq!(array[[0, 1, 0]], 100);
+        assert_same(&Image::<Rgba>::from_ndarray(&array).unwrap(), &image);
+    }
+
+    #[test]
+    fn test_ndarray_round_trips() {
+        let image = sample().convert::<L>();
+        assert_eq!(image.to_ndarray().dim(), (2, 3, 1));
+        assert_same(
+            &Image::<L>::from_ndarray(&image.to_ndarray()).unwrap(),
+            &image,
+        );
+
+        let image = sample().convert::<Rgb>();
+        assert_same(
+            &Image::<Rgb>::from_ndarray(&image.to_ndarray()).unwrap(),
+            &image,
+        );
+
+        let image = sample().convert::<L32F>();
+        assert_same(
+            &Image::<L32F>::from_ndarray(&image.to_ndarray()).unwrap(),
+            &image,
+        );
+
+        let image = sample().convert::<Rgb32F>();
+        assert_same(
+            &Image::<Rgb32F>::from_ndarray(&image.to_ndarray()).unwrap(),
+            &image,
+        );
+
+        let image = sample().convert::<Rgba32F>();
+        assert_eq!(image.to_ndarray().dim(), (2, 3, 4));
+        assert_same(
+            &Image::<Rgba32F>::from_ndarray(&image.to_ndarray()).unwrap(),
+            &image,
+        );
+    }
+
+    #[test]
+    fn test_ndarray_memory_layout() {
+        // A transposed view of a width, height, channel array is read in logical order
+        let mut array = Array3::<u8>::zeros((3, 2, 3));
+        array[[2, 1, 0]] = 255;
+        let transposed = array.view().permuted_axes([1, 0, 2]);
+
+        let image = Image::<Rgb>::from_ndarray(&transposed).unwrap();
+        assert_eq!(image.dimensions(), (3, 2));
+        assert_eq!(image.pixel(2, 1), &Rgb::new(255, 0, 0));
+        assert_eq!(image.pixel(1, 1), &Rgb::black());
+
+        // Arrays with the wrong amount of channels or no pixels are rejected
+        assert!(Image::<Rgb>::from_ndarray(&array.slice_axis(Axis(2), (0..2).into())).is_err());
+        assert!(Image::<Rgb>::from_ndarray(&Array3::<u8>::zeros((0, 2, 3))).is_err());
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics_interop {
+    use super::*;
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{Point, Size},
+        pixelcolor::{BinaryColor, Gray8, Rgb888},
+        primitives::Rectangle as EgRectangle,
+        Pixel as EgPixel,
+    };
+
+    #[test]
+    fn test_draw_iter() {
+        let mut image = sample();
+        let color = Rgb888::new(255, 128, 0);
+        image
+            .draw_iter([
+                EgPixel(Point::new(2, 1), color),
+                // Pixels outside of the image are ignored
+                EgPixel(Point::new(-1, 0), color),
+                EgPixel(Point::new(3, 0), color),
+                EgPixel(Point::new(0, 2), color),
+            ])
+            .unwrap();
+
+        let mut expected = sample();
+        expected.set_pixel(2, 1, Rgba::new(255, 128, 0, 255));
+        assert_same(&image, &expected);
+    }
+
+    #[test]
+    fn test_fill_and_clear() {
+        let mut image = Image::new(3, 2, L(0));
+        image
+            .fill_solid(
+                &EgRectangle::new(Point::new(1, 0), Size::new(4, 1)),
+                Gray8::new(200),
+            )
+            .unwrap();
+        assert_eq!(image.data, [0, 200, 200, 0, 0, 0].map(L));
+
+        image.clear(Gray8::new(50)).unwrap();
+        assert!(image.data.iter().all(|&p| p == L(50)));
+
+        let mut image = Image::new(2, 2, BitPixel::off());
+        image
+            .draw_iter([EgPixel(Point::new(1, 0), BinaryColor::On)])
+            .unwrap();
+        assert_eq!(image.data, [false, true, false, false].map(BitPixel));
+    }
+}
+
+#[cfg(feature = "wgpu")]
+mod wgpu_interop {
+    use super::*;
+
+    /// Requests a device from the default adapter, or returns `None` if there is no adapter, in
+    /// which case the test is skipped.
+    async fn device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        let adapter = wgpu::Instance::default()
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await?;
+
+        adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .ok()
+    }
+
+    #[tokio::test]
+    async fn test_wgpu_round_trips() -> ril::Result<()> {
+        let (device, queue) = match device().await {
+            Some(device) => device,
+            None => return Ok(()),
+        };
+
+        // Rows of three pixels are padded when read back, which must be removed again
+        let image = sample();
+        let texture = image.to_wgpu_texture(&device, &queue);
+        assert_eq!((texture.width(), texture.height()), (3, 2));
+        assert_eq!(texture.format(), wgpu::TextureFormat::Rgba8UnormSrgb);
+        assert_same(
+            &Image::<Rgba>::from_wgpu_texture(&device, &queue, &texture)?,
+            &image,
+        );
+
+        // Rgb images are uploaded with an opaque alpha channel, which is discarded when reading
+        let image = sample().convert::<Rgb>();
+        let texture = image.to_wgpu_texture(&device, &queue);
+        assert_same(
+            &Image::<Rgb>::from_wgpu_texture(&device, &queue, &texture)?,
+            &image,
+        );
+        let rgba = Image::<Rgba>::from_wgpu_texture(&device, &queue, &texture)?;
+        assert!(rgba.data.iter().all(|pixel| pixel.a == 255));
+
+        let image = sample().convert::<L>();
+        let texture = image.to_wgpu_texture(&device, &queue);
+        assert_same(
+            &Image::<L>::from_wgpu_texture(&device, &queue, &texture)?,
+            &image,
+        );
+        // Textures of other formats are rejected
+        assert!(matches!(
+            Image::<Rgba>::from_wgpu_texture(&device, &queue, &texture),
+            Err(ril::Error::UnsupportedColorType)
+        ));
+
+        let image = sample().convert::<L32F>();
+        let texture = image.to_wgpu_texture(&device, &queue);
+        assert_same(
+            &Image::<L32F>::from_wgpu_texture(&device, &queue, &texture)?,
+            &image,
+        );
+
+        let image = sample().convert::<Rgba32F>();
+        let texture = image.to_wgpu_texture(&device, &queue);
+        assert_same(
+            &Image::<Rgba32F>::from_wgpu_texture(&device, &queue, &texture)?,
+            &image,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck_interop {
+    use super::*;
+
+    #[test]
+    fn test_cast_slices() {
+        let image = sample();
+        let bytes: &[u8] = bytemuck::cast_slice(&image.data);
+        assert_eq!(bytes.len(), 3 * 2 * 4);
+        assert_eq!(&bytes[..8], [0, 0, 50, 255, 100, 0, 51, 255]);
+
+        let pixels: &[Rgba] = bytemuck::cast_slice(bytes);
+        assert_same(&Image::from_pixels(3, pixels), &image);
+
+        let pixels = [Rgb16::new(1, 256, 65535)];
+        let words: &[u16] = bytemuck::cast_slice(&pixels);
+        assert_eq!(words, [1, 256, 65535]);
+
+        let image = sample().convert::<Rgba32F>();
+        let floats: &[f32] = bytemuck::cast_slice(&image.data);
+        let pixel = image.pixel(1, 0);
+        assert_eq!(floats[4..8], [pixel.r, pixel.g, pixel.b, pixel.a]);
+        assert_eq!(bytemuck::cast::<L, u8>(L(42)), 42);
+    }
+}
@@ -7,3 +7,89 @@ fn test_jpeg() -> ril::Result<()> {
 
     image.save_inferred("tests/out/jpg_encode_output.jpg")
 }
+
+#[test]
+fn test_jpeg_cmyk() -> ril::Result<()> {
+    let image = Image::new(16, 16, Cmyk::new(0, 255, 255, 0));
+
+    let mut bytes = Vec::new();
+    image.encode(ImageFormat::Jpeg, &mut bytes)?;
+
+    let decoded = Image::<Cmyk>::from_bytes(ImageFormat::Jpeg, &bytes)?;
+    let Cmyk { c, m, y, k } = *decoded.pixel(8, 8);
+    assert!(c < 8 && m > 247 && y > 247 && k < 8);
+
+    // Decoding into RGB converts naively
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Jpeg, &bytes)?;
+    let Rgb { r, g, b } = *decoded.pixel(8, 8);
+    assert!(r > 247 && g < 8 && b < 8);
+
+    Ok(())
+}
+
+#[test]
+fn test_jpeg_options() -> ril::Result<()> {
+    use ril::encodings::jpeg::{JpegEncoder, JpegEncoderOptions, JpegSubsampling};
+
+    // Alternating columns of red and blue, which 4:2:0 subsampling blurs together
+    let image = Image::from_fn(16, 16, |x, _| {
+        if x % 2 == 0 {
+            Rgb::new(255, 0, 0)
+        } else {
+            Rgb::new(0, 0, 255)
+        }
+    });
+    let encode = |options: JpegEncoderOptions| -> ril::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let metadata = EncoderMetadata::from(&image).with_config(options);
+        let mut encoder = JpegEncoder::new(&mut bytes, metadata)?;
+        encoder.add_frame(&image)?;
+        encoder.finish()?;
+        Ok(bytes)
+    };
+
+    let options = JpegEncoderOptions::new().with_quality(100);
+    let full = encode(options.with_subsampling(JpegSubsampling::Yuv444))?;
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Jpeg, &full)?;
+    assert!(decoded.pixel(8, 8).r > 200 && decoded.pixel(9, 8).b > 200);
+
+    let subsampled = encode(options.with_subsampling(JpegSubsampling::Yuv420))?;
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Jpeg, &subsampled)?;
+    assert!(decoded.pixel(9, 8).b < 200);
+
+    // Progressive images are marked with a SOF2 marker
+    let progressive = encode(options.with_progressive(true))?;
+    assert!(progressive.windows(2).any(|marker| marker == [0xFF, 0xC2]));
+    assert!(!full.windows(2).any(|marker| marker == [0xFF, 0xC2]));
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Jpeg, &progressive)?;
+    assert_eq!(decoded.dimensions(), (16, 16));
+
+    Ok(())
+}
+
+#[test]
+fn test_jpeg_exif_orientation() -> ril::Result<()> {
+    use ril::encodings::jpeg::JpegDecoder;
+
+    // A big-endian TIFF header followed by an IFD with only an orientation tag of 6
+    let exif = b"MM\0*\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0\x06\0\0\0\0\0\0".to_vec();
+    let image = Image::new(32, 16, Rgb::new(255, 0, 0))
+        .with_metadata(ImageMetadata::new().with_exif(exif.clone()));
+    let mut bytes = Vec::new();
+    image.encode(ImageFormat::Jpeg, &mut bytes)?;
+
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Jpeg, &bytes)?;
+    assert_eq!(decoded.metadata().exif(), Some(exif.as_slice()));
+    assert_eq!(
+        decoded.metadata().orientation(),
+        Some(Orientation::Rotate90)
+    );
+
+    let oriented: Image<Rgb> = JpegDecoder::new()
+        .with_apply_orientation(true)
+        .decode(bytes.as_slice())?;
+    assert_eq!(oriented.dimensions(), (16, 32));
+    assert_eq!(oriented.metadata().orientation(), Some(Orientation::Normal));
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Tests for the core image functionality when ril is built without the `std` feature.
+#![cfg(not(feature = "std"))]
+
+use ril::prelude::*;
+
+#[test]
+fn test_core_without_std() {
+    let mut image = Image::new(4, 3, Rgba::black());
+    image.draw(&Rectangle::from_bounding_box(1, 1, 3, 3).with_fill(Rgba::new(255, 0, 0, 255)));
+    assert_eq!(image.pixel(0, 0), &Rgba::black());
+    assert_eq!(image.pixel(1, 1), &Rgba::new(255, 0, 0, 255));
+    assert_eq!(image.pixel(2, 2), &Rgba::new(255, 0, 0, 255));
+    assert_eq!(image.pixel(3, 2), &Rgba::black());
+
+    image.mirror();
+    assert_eq!(image.pixel(1, 1), &Rgba::new(255, 0, 0, 255));
+    assert_eq!(image.pixel(2, 1), &Rgba::new(255, 0, 0, 255));
+    assert_eq!(image.pixel(3, 1), &Rgba::black());
+
+    image.crop(1, 1, 3, 3);
+    assert_eq!(image.dimensions(), (2, 2));
+
+    let image = image.convert::<L>();
+    assert_eq!(image.data, [L(76); 4]);
+}
+
+#[test]
+fn test_float_math_without_std() {
+    let mut image = Image::new(1, 1, Rgb::black());
+    image.paste_with_opacity(0, 0, &Image::new(1, 1, Rgb::white()), 0.5);
+    assert_eq!(image.pixel(0, 0), &Rgb::new(128, 128, 128));
+
+    let linear = Rgba::black().overlay(Rgba::new(255, 255, 255, 128), OverlayMode::MergeLinear);
+    assert_eq!(linear, Rgba::new(188, 188, 188, 255));
+}
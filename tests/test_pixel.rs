@@ -0,0 +1,784 @@
+use ril::prelude::*;
+
+#[test]
+fn test_float_pixels() -> ril::Result<()> {
+    let rgba = Rgba::new(255, 128, 0, 64);
+    assert_eq!(Rgba::from(Rgba32F::from(rgba)), rgba);
+    assert_eq!(Rgb32F::from(Rgb::white()), Rgb32F::white());
+    assert_eq!(L::from(L32F(2.0)), L(255));
+    assert_eq!(L::from(L32F(-1.0)), L(0));
+
+    // 8-bit data is scaled into the range 0.0..=1.0
+    let pixel = Rgb32F::from_raw_parts(ColorType::Rgb, 8, &[255, 0, 51])?;
+    assert_eq!(pixel, Rgb32F::new(1.0, 0.0, 0.2));
+
+    let pixel = Rgba32F::from_raw_parts(ColorType::Rgba, 32, Rgba32F::white().as_bytes().as_ref())?;
+    assert_eq!(pixel, Rgba32F::white());
+
+    let merged = Rgba32F::black().merge(Rgba32F::new(1.0, 1.0, 1.0, 0.5));
+    assert_eq!(merged, Rgba32F::new(0.5, 0.5, 0.5, 1.0));
+
+    Ok(())
+}
+
+#[test]
+fn test_tonemap() {
+    assert_eq!(Tonemap::Clamp.apply(4.0), 1.0);
+    assert_eq!(Tonemap::Reinhard.apply(1.0), 0.5);
+    assert!(Tonemap::Aces.apply(100.0) <= 1.0);
+    assert!((Tonemap::Exposure(2.0).apply(0.5) - 0.632).abs() < 1e-3);
+
+    let image = Image::new(1, 1, Rgba32F::new(3.0, 1.0, 0.0, 0.5)).tonemapped(Tonemap::Reinhard);
+    assert_eq!(image.pixel(0, 0), &Rgba32F::new(0.75, 0.5, 0.0, 0.5));
+
+    // Encoding into sRGB brightens midtones
+    let image = Image::new(1, 1, Rgb32F::new(1.0, 1.0, 1.0)).to_sdr(Tonemap::Reinhard);
+    assert_eq!(image.pixel(0, 0), &Rgb::new(188, 188, 188));
+}
+
+#[test]
+fn test_hdr_to_sdr() {
+    let hdr = Image::from_pixels(
+        4,
+        [
+            Rgb32F::new(-1.0, 0.0, 1.0),
+            Rgb32F::new(4.0, 1000.0, 0.18),
+            Rgb32F::new(0.5, 0.5, 0.5),
+            Rgb32F::new(2.0, 2.0, 2.0),
+        ],
+    );
+
+    // Negative components are black, and clamped highlights are white
+    let clamped = hdr.to_sdr(Tonemap::Clamp);
+    assert_eq!(clamped.pixel(0, 0), &Rgb::new(0, 0, 255));
+    assert_eq!(clamped.pixel(1, 0).r, 255);
+    assert_eq!(clamped.pixel(1, 0).g, 255);
+    // Midtones are encoded into sRGB, unlike with a plain conversion
+    let converted = hdr.clone().tonemapped(Tonemap::Clamp).convert::<Rgb>();
+    assert!(clamped.pixel(1, 0).b > converted.pixel(1, 0).b);
+
+    // Operators which roll off highlights keep them apart from each other
+    for operator in [Tonemap::Reinhard, Tonemap::Aces, Tonemap::Exposure(1.0)] {
+        let image = hdr.to_sdr(operator);
+        assert!(image.pixel(2, 0).r < image.pixel(3, 0).r, "{operator:?}");
+        assert!(image.pixel(3, 0).r < image.pixel(1, 0).g, "{operator:?}");
+        assert_eq!(image.pixel(0, 0).r, 0, "{operator:?}");
+    }
+
+    // Higher exposures brighten the image, and no exposure at all turns it black
+    let dim = hdr.to_sdr(Tonemap::Exposure(0.5));
+    let bright = hdr.to_sdr(Tonemap::Exposure(2.0));
+    assert!(dim.pixel(2, 0).r < bright.pixel(2, 0).r);
+    assert!(hdr
+        .to_sdr(Tonemap::Exposure(0.0))
+        .data
+        .iter()
+        .all(|pixel| *pixel == Rgb::black()));
+
+    // Alpha is left untouched
+    let hdr = Image::new(1, 1, Rgba32F::new(4.0, 0.0, 0.0, 0.5));
+    assert_eq!(
+        hdr.to_sdr(Tonemap::Clamp).pixel(0, 0),
+        &Rgba::new(255, 0, 0, 128)
+    );
+}
+
+#[test]
+fn test_normalize() {
+    let mut image = Image::from_fn(2, 1, |x, _| {
+        Rgba16::new(1000, 2000, 3000 * (x as u16 + 1), 500)
+    });
+    image.normalize();
+
+    assert_eq!(image.pixel(0, 0), &Rgba16::new(0, 13107, 26214, 500));
+    assert_eq!(image.pixel(1, 0), &Rgba16::new(0, 13107, u16::MAX, 500));
+}
+
+#[test]
+fn test_linear_blending() {
+    let overlay = Rgba::new(255, 255, 255, 128);
+    let srgb = Rgba::black().overlay(overlay, OverlayMode::Merge);
+    let linear = Rgba::black().overlay(overlay, OverlayMode::MergeLinear);
+
+    assert_eq!(srgb, Rgba::new(128, 128, 128, 255));
+    assert_eq!(linear, Rgba::new(188, 188, 188, 255));
+    assert_eq!(Rgba::black().merge_linear(Rgba::white()), Rgba::white());
+
+    // Pixels without alpha are blended in linear light when antialiasing
+    let rgb = Rgb::black().overlay_with_alpha(Rgb::white(), OverlayMode::MergeLinear, 128);
+    let l = L(0).overlay_with_alpha(L(255), OverlayMode::MergeLinear, 128);
+    assert_eq!(rgb, Rgb::new(188, 188, 188));
+    assert_eq!(l, L(188));
+    assert_eq!(
+        Rgb::black().overlay_with_alpha(Rgb::white(), OverlayMode::Merge, 128),
+        Rgb::new(128, 128, 128)
+    );
+}
+
+#[test]
+fn test_blend_modes() {
+    let overlay = Rgba::new(0, 0, 255, 128);
+    let blended = Rgba::new(255, 0, 0, 255).overlay(overlay, BlendMode::Difference.into());
+    assert_eq!(blended, Rgba::new(255, 0, 128, 255));
+
+    // Blending onto a transparent pixel is the same as merging.
+    let overlay = Rgba::new(10, 20, 30, 200);
+    assert_eq!(
+        Rgba::transparent().blend(overlay, BlendMode::Multiply),
+        overlay
+    );
+
+    let mut image = Image::new(2, 2, Rgb::new(200, 100, 50))
+        .with_overlay_mode(OverlayMode::Blend(BlendMode::Darken));
+    image.paste(0, 0, &Image::new(1, 1, Rgb::new(100, 150, 50)));
+    assert_eq!(image.pixel(0, 0), &Rgb::new(100, 100, 50));
+    assert_eq!(image.pixel(1, 1), &Rgb::new(200, 100, 50));
+
+    assert_eq!(BlendMode::Screen.apply(0.5, 0.5), 0.75);
+    assert_eq!(BlendMode::Overlay.apply(0.25, 1.0), 0.5);
+    assert_eq!(BlendMode::HardLight.apply(1.0, 0.25), 0.5);
+}
+
+#[test]
+fn test_hsl_hsv() {
+    let color = Rgb::new(51, 153, 204);
+    assert_eq!(Rgb::from(Hsl::from(color)), color);
+    assert_eq!(Rgb::from(Hsv::from(color)), color);
+
+    let hsv = Hsv::from(color);
+    assert_eq!(hsv.h, 200.0);
+    assert_eq!(hsv.hue_rotated(-240.0).h, 320.0);
+
+    assert_eq!(
+        Rgb::new(255, 0, 0).modulate(240.0, 1.0, 0.5),
+        Rgb::new(0, 0, 128)
+    );
+}
+
+#[test]
+fn test_color_space_images() -> ril::Result<()> {
+    let image = Image::from_fn(16, 16, |x, y| Rgb::new(x as u8 * 17, y as u8 * 17, 200));
+
+    // Every 8-bit color survives a round trip through each color space
+    assert_eq!(
+        image.clone().convert::<Hsl>().convert::<Rgb>().data,
+        image.data
+    );
+    assert_eq!(
+        image.clone().convert::<Hsv>().convert::<Rgb>().data,
+        image.data
+    );
+    assert_eq!(
+        image.clone().convert::<Lab>().convert::<Rgb>().data,
+        image.data
+    );
+
+    let hsl = Image::new(2, 2, Rgb::new(255, 0, 0))
+        .convert::<Hsl>()
+        .map_pixels(|hsl| hsl.modulate(120.0, 1.0, 1.0));
+    assert_eq!(hsl.pixel(0, 0), &Hsl::new(120.0, 1.0, 0.5));
+
+    // Images of color space pixels are encoded as RGB
+    let mut bytes = Vec::new();
+    hsl.encode(ImageFormat::Png, &mut bytes)?;
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Png, &bytes)?;
+    assert_eq!(decoded.pixel(1, 1), &Rgb::new(0, 255, 0));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "resize")]
+fn test_resize_hue() {
+    // Hues on either side of red should average to red rather than cyan
+    let hsl = Image::from_pixels(2, vec![Hsl::new(350.0, 1.0, 0.5), Hsl::new(10.0, 1.0, 0.5)]);
+    let hsv = Image::from_pixels(2, vec![Hsv::new(350.0, 1.0, 1.0), Hsv::new(10.0, 1.0, 1.0)]);
+
+    let hsl = hsl.resized(1, 1, ResizeAlgorithm::Box);
+    assert_eq!(hsl.pixel(0, 0).as_rgb(), Rgb::new(255, 0, 0));
+    let hsv = hsv.resized(1, 1, ResizeAlgorithm::Box);
+    assert_eq!(hsv.pixel(0, 0).as_rgb(), Rgb::new(255, 0, 0));
+}
+
+#[test]
+fn test_perceptual_colors() {
+    let color = Rgb::new(51, 153, 204);
+    assert_eq!(Rgb::from(Lab::from(color)), color);
+    assert_eq!(Rgb::from(Oklab::from(color)), color);
+    assert_eq!(Rgb::from(Oklch::from(color)), color);
+
+    let white = Oklab::from(Rgb::white());
+    assert!((white.l - 1.0).abs() < 1e-3 && white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+
+    // Reference pair from Sharma et al., "The CIEDE2000 Color-Difference Formula"
+    let a = Lab::new(50.0, 2.6772, -79.7751);
+    let b = Lab::new(50.0, 0.0, -82.7485);
+    assert!((a.delta_e_2000(b) - 2.0425).abs() < 1e-3);
+    assert!((a.delta_e(b) - 4.0011).abs() < 1e-3);
+}
+
+#[test]
+fn test_paletted_editing() {
+    let palette = [
+        Rgb::black(),
+        Rgb::white(),
+        Rgb::black(),
+        Rgb::new(255, 0, 0),
+    ];
+    let mut image = Image::<PalettedRgb>::from_paletted_pixels(2, palette.to_vec(), [0, 3, 2, 0]);
+
+    image.compact_palette();
+    assert_eq!(
+        image.palette(),
+        Some(&[Rgb::black(), Rgb::new(255, 0, 0)][..])
+    );
+    assert_eq!(image.palette_indices().collect::<Vec<_>>(), [0, 1, 0, 0]);
+
+    // Cloned images must resolve colors through their own palette
+    let cloned = image.clone();
+    drop(image);
+    assert_eq!(cloned.pixel(1, 0).color(), Rgb::new(255, 0, 0));
+}
+
+#[test]
+fn test_channels() {
+    assert_eq!(Rgba::NUM_CHANNELS, 4);
+    assert_eq!(L32F::NUM_CHANNELS, 1);
+
+    let pixel = Rgba::new(10, 20, 30, 40);
+    assert_eq!(pixel.channel(3), 40);
+    assert_eq!(pixel.map_channels(|c| c * 2), Rgba::new(20, 40, 60, 80));
+
+    let dynamic = Dynamic::Rgb(Rgb::new(1, 2, 3));
+    assert_eq!(dynamic.channel(2), DynamicSubpixel::U8(3));
+}
+
+#[test]
+fn test_16_bit_histogram() {
+    let histogram = Image::new(1, 1, L16(1)).histogram();
+    assert_eq!(histogram.bin_count(), 65_536);
+    assert_eq!(histogram.channel(0).unwrap()[1], 1);
+
+    let histogram = Image::from_fn(2, 1, |x, _| Rgb16::new(258, 0, 1000 * x as u16)).histogram();
+    assert_eq!(histogram.channel(0).unwrap()[258], 2);
+    assert_eq!(histogram.channel(2).unwrap()[1000], 1);
+    assert_eq!(histogram.mean(2), 500.);
+}
+
+#[test]
+fn test_premultiplied_alpha() {
+    let pixel = Rgba::new(200, 100, 50, 128);
+    assert_eq!(pixel.premultiplied(), Rgba::new(100, 50, 25, 128));
+    assert_eq!(
+        pixel.premultiplied().unpremultiplied(),
+        Rgba::new(199, 100, 50, 128)
+    );
+    assert_eq!(Rgba::white().premultiplied(), Rgba::white());
+    // Fully transparent pixels lose their color
+    assert_eq!(
+        Rgba::new(200, 100, 50, 0).premultiplied(),
+        Rgba::new(0, 0, 0, 0)
+    );
+    assert_eq!(
+        Rgba::new(200, 100, 50, 0).unpremultiplied(),
+        Rgba::transparent()
+    );
+
+    let image = Image::from_pixels(2, vec![pixel, Rgba::white()]);
+    let premultiplied = image.clone().premultiplied();
+    assert_eq!(premultiplied.data, [pixel.premultiplied(), Rgba::white()]);
+    assert_eq!(
+        premultiplied.unpremultiplied().data,
+        [pixel.premultiplied().unpremultiplied(), Rgba::white()]
+    );
+
+    let dynamic = Dynamic::Rgba(pixel);
+    assert_eq!(
+        dynamic.premultiplied(),
+        Dynamic::Rgba(pixel.premultiplied())
+    );
+    assert_eq!(Dynamic::L(L(7)).premultiplied(), Dynamic::L(L(7)));
+}
+
+#[test]
+fn test_pixel_iterators() {
+    let mut image = Image::from_fn(3, 2, |x, y| L((x + y * 3) as u8));
+
+    let coords = image
+        .pixels_with_coords()
+        .map(|(x, y, pixel)| (x, y, pixel.value()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        coords,
+        [
+            (0, 0, 0),
+            (1, 0, 1),
+            (2, 0, 2),
+            (0, 1, 3),
+            (1, 1, 4),
+            (2, 1, 5)
+        ]
+    );
+
+    for (x, y, pixel) in image.pixels_with_coords_mut() {
+        *pixel = L((x * 10 + y) as u8);
+    }
+    assert_eq!(image.data, [0, 10, 20, 1, 11, 21].map(L));
+
+    let rows = image.rows_mut().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2);
+    rows.into_iter().last().unwrap().fill(L(255));
+    assert_eq!(image.data, [0, 10, 20, 255, 255, 255].map(L));
+}
+
+#[test]
+fn test_histogram() {
+    let image = Image::from_fn(4, 2, |x, y| {
+        Rgba::new(x as u8 * 10, 0, 255, if y == 0 { 255 } else { 0 })
+    });
+    let histogram = image.histogram();
+
+    assert_eq!(histogram.channel_count(), 4);
+    assert_eq!(histogram.bin_count(), 256);
+    assert_eq!(histogram.pixel_count(), 8);
+    for bins in histogram.channels() {
+        assert_eq!(bins.iter().sum::<u32>(), 8);
+    }
+    assert_eq!(histogram.channel(0).unwrap()[10], 2);
+    assert_eq!(histogram.channel(1).unwrap()[0], 8);
+    assert_eq!(histogram.channel(3).unwrap()[0], 4);
+    assert_eq!(histogram.channel(3).unwrap()[255], 4);
+    assert_eq!(histogram.channel(4), None);
+
+    let cumulative = histogram.cumulative(0);
+    assert_eq!(cumulative.len(), 256);
+    assert_eq!((cumulative[9], cumulative[10], cumulative[255]), (2, 4, 8));
+
+    assert_eq!(histogram.percentile(0, 0.5), 10);
+    // At least one pixel must be covered, even for a fraction of zero
+    assert_eq!(histogram.percentile(0, 0.0), 0);
+    assert_eq!(histogram.percentile(0, 1.0), 30);
+    assert_eq!(histogram.percentile(0, 2.0), 30);
+    assert!((histogram.mean(0) - 15.0).abs() < f64::EPSILON);
+
+    let image = Image::from_pixels(2, [Rgb::black(), Rgb::white()]);
+    let histogram = image.luminance_histogram();
+    assert_eq!(histogram.channel_count(), 1);
+    assert_eq!(histogram.channel(0).unwrap()[0], 1);
+    assert_eq!(histogram.channel(0).unwrap()[255], 1);
+
+    // Paletted images count palette indices, unlike their luminance histogram
+    let palette = [Rgb::black(), Rgb::white()];
+    let image = Image::<PalettedRgb>::from_paletted_pixels(2, palette.to_vec(), [1, 1, 0, 1]);
+    let histogram = image.histogram();
+    assert_eq!(histogram.channel_count(), 1);
+    assert_eq!(histogram.channel(0).unwrap()[..2], [1, 3]);
+    assert_eq!(image.luminance_histogram().channel(0).unwrap()[255], 3);
+}
+
+#[test]
+#[should_panic(expected = "up to 16 bits per channel")]
+fn test_float_histogram() {
+    let _ = Image::new(1, 1, Rgb32F::new(0.5, 0.5, 0.5)).histogram();
+}
+
+#[test]
+fn test_auto_contrast() {
+    let image = Image::from_pixels(2, [Rgb::new(100, 50, 20), Rgb::new(200, 150, 120)]);
+
+    // Auto levels stretches every channel on its own, neutralizing the color cast
+    let leveled = image.clone().auto_leveled(0.0);
+    assert_eq!(leveled.data, [Rgb::black(), Rgb::white()]);
+
+    // Auto contrast stretches all channels by the same amount
+    let contrasted = image.auto_contrasted(0.0);
+    assert_eq!(contrasted.pixel(0, 0).r, 113);
+    assert_eq!(contrasted.pixel(0, 0).b, 0);
+    assert_eq!(contrasted.pixel(1, 0).r, 255);
+    assert_eq!(contrasted.pixel(1, 0).b, 142);
+
+    // The darkest and brightest 10% of pixels are ignored when determining the range
+    let image = Image::from_fn(10, 10, |x, y| {
+        let value = 50 + (x + y * 10) as u8;
+        Rgb::new(value, value, value)
+    });
+    let clipped = image.auto_contrasted(10.0);
+    assert_eq!(clipped.pixel(9, 0).r, 0);
+    assert_eq!(clipped.pixel(0, 0).r, 0);
+    assert_eq!(clipped.pixel(9, 2).r, 64);
+    assert_eq!(clipped.pixel(9, 8).r, 255);
+    assert_eq!(clipped.pixel(9, 9).r, 255);
+
+    // Images without a range of values are left unchanged
+    let image = Image::new(2, 2, Rgb::new(80, 80, 80));
+    assert_eq!(image.clone().auto_leveled(0.0).data, image.data);
+    assert_eq!(image.clone().auto_contrasted(5.0).data, image.data);
+
+    let image = Image::from_pixels(
+        2,
+        [Rgba::new(100, 50, 20, 77), Rgba::new(200, 150, 120, 200)],
+    );
+    assert_eq!(
+        image.auto_leveled(0.0).data,
+        [Rgba::new(0, 0, 0, 77), Rgba::new(255, 255, 255, 200)]
+    );
+}
+
+#[test]
+fn test_brightness_contrast_gamma() {
+    let image = Image::new(1, 1, Rgba::new(100, 20, 250, 128));
+
+    // Brightness is clamped on both ends, and alpha is left untouched
+    assert_eq!(
+        image.clone().brightness_adjusted(-50).pixel(0, 0),
+        &Rgba::new(50, 0, 200, 128)
+    );
+    assert_eq!(
+        image.clone().brightness_adjusted(10).pixel(0, 0),
+        &Rgba::new(110, 30, 255, 128)
+    );
+    assert_eq!(
+        image.brightness_adjusted(0).pixel(0, 0),
+        &Rgba::new(100, 20, 250, 128)
+    );
+
+    let image = Image::from_pixels(3, [27, 227, 127].map(|v| Rgb::new(v, v, v)));
+    let values = |image: Image<Rgb>| image.data.iter().map(|p| p.r).collect::<Vec<_>>();
+    assert_eq!(values(image.clone().contrast_adjusted(0.5)), [77, 177, 127]);
+    assert_eq!(values(image.clone().contrast_adjusted(2.0)), [0, 255, 127]);
+    assert_eq!(values(image.clone().contrast_adjusted(1.0)), [27, 227, 127]);
+    // A factor of zero flattens the image to the midpoint
+    assert_eq!(values(image.contrast_adjusted(0.0)), [128, 128, 128]);
+
+    let image = Image::from_pixels(4, [0, 64, 128, 255].map(|v| Rgb::new(v, v, v)));
+    assert_eq!(
+        values(image.clone().gamma_adjusted(2.0)),
+        [0, 128, 181, 255]
+    );
+    assert_eq!(values(image.clone().gamma_adjusted(0.5)), [0, 16, 64, 255]);
+    assert_eq!(values(image.gamma_adjusted(1.0)), [0, 64, 128, 255]);
+}
+
+#[test]
+#[should_panic(expected = "gamma must be positive")]
+fn test_zero_gamma() {
+    Image::new(1, 1, Rgb::white()).adjust_gamma(0.0);
+}
+
+#[test]
+fn test_white_balance() {
+    let gray = Image::new(1, 1, Rgba::new(128, 128, 128, 100));
+
+    // Daylight leaves the image unchanged
+    assert_eq!(
+        gray.clone().temperature_adjusted(6500.0).pixel(0, 0),
+        &Rgba::new(128, 128, 128, 100)
+    );
+
+    let warm = *gray.clone().temperature_adjusted(3000.0).pixel(0, 0);
+    assert!(warm.r > warm.g && warm.g > warm.b);
+    assert_eq!(warm.a, 100);
+
+    let cool = *gray.clone().temperature_adjusted(10000.0).pixel(0, 0);
+    assert!(cool.b > cool.r);
+
+    // Temperatures outside of the supported range are clamped
+    assert_eq!(
+        gray.clone().temperature_adjusted(500.0).pixel(0, 0),
+        gray.temperature_adjusted(1000.0).pixel(0, 0)
+    );
+
+    let image = Image::from_pixels(
+        2,
+        [Rgba::new(200, 100, 100, 255), Rgba::new(100, 50, 50, 0)],
+    );
+    assert_eq!(
+        image.auto_white_balanced().data,
+        [Rgba::new(133, 133, 133, 255), Rgba::new(67, 67, 67, 0)]
+    );
+
+    // Channels without any values cannot be scaled and are left unchanged
+    let image = Image::new(1, 1, Rgb::new(0, 100, 200));
+    assert_eq!(
+        image.auto_white_balanced().pixel(0, 0),
+        &Rgb::new(0, 100, 100)
+    );
+}
+
+#[test]
+fn test_grayscale_weights() {
+    let red = Image::new(1, 1, Rgb::new(255, 0, 0));
+    let gray = |image: &Image<Rgb>, weights| image.clone().grayscale(weights).pixel(0, 0).value();
+
+    assert_eq!(gray(&red, GrayscaleWeights::Rec601), 76);
+    assert_eq!(gray(&red, GrayscaleWeights::Rec709), 54);
+    assert_eq!(gray(&red, GrayscaleWeights::Average), 85);
+    assert_eq!(gray(&red, GrayscaleWeights::Custom([1.0, 0.0, 0.0])), 255);
+    assert_eq!(GrayscaleWeights::Rec709.weights(), [0.2126, 0.7152, 0.0722]);
+
+    // Results are rounded, unlike converting into `L`
+    let green = Image::new(1, 1, Rgb::new(0, 255, 0));
+    assert_eq!(gray(&green, GrayscaleWeights::Rec601), 150);
+    assert_eq!(green.convert::<L>().pixel(0, 0).value(), 149);
+
+    let image = Image::new(1, 1, Rgb::new(30, 60, 90));
+    assert_eq!(gray(&image, GrayscaleWeights::Average), 60);
+
+    // Weights adding up to more than one are clamped
+    let white = Image::new(1, 1, Rgb::white());
+    assert_eq!(gray(&white, GrayscaleWeights::Custom([0.5; 3])), 255);
+
+    // Alpha is discarded
+    let image = Image::new(1, 1, Rgba::new(255, 255, 255, 0));
+    assert_eq!(
+        image.grayscale(GrayscaleWeights::Rec709).pixel(0, 0),
+        &L(255)
+    );
+}
+
+#[test]
+fn test_duotone_sepia() {
+    let (dark, light) = (Rgb::new(0, 0, 128), Rgb::new(255, 255, 0));
+
+    // Midtones land halfway between the two colors
+    let image = Image::new(1, 1, Rgb::new(128, 128, 128));
+    assert_eq!(
+        image.duotoned(dark, light).pixel(0, 0),
+        &Rgb::new(128, 128, 64)
+    );
+
+    let image = Image::from_pixels(2, [Rgba::new(0, 0, 0, 50), Rgba::new(255, 255, 255, 200)]);
+    assert_eq!(
+        image.duotoned(dark, light).data,
+        [Rgba::new(0, 0, 128, 50), Rgba::new(255, 255, 0, 200)]
+    );
+
+    let image = Image::from_pixels(
+        3,
+        [
+            Rgba::new(255, 255, 255, 10),
+            Rgba::new(0, 0, 0, 255),
+            Rgba::new(100, 100, 100, 255),
+        ],
+    );
+    assert_eq!(
+        image.sepia_toned().data,
+        [
+            // Channels which exceed the maximum value are clamped
+            Rgba::new(255, 255, 239, 10),
+            Rgba::new(0, 0, 0, 255),
+            Rgba::new(135, 120, 94, 255),
+        ]
+    );
+}
+
+#[test]
+fn test_solarize_posterize() {
+    let image = Image::new(1, 1, Rgba::new(0, 100, 255, 60));
+
+    // The threshold itself is inverted, so a threshold of zero inverts every channel
+    assert_eq!(
+        image.clone().solarized(0).pixel(0, 0),
+        &Rgba::new(255, 155, 0, 60)
+    );
+    assert_eq!(
+        image.clone().solarized(255).pixel(0, 0),
+        &Rgba::new(0, 100, 0, 60)
+    );
+    assert_eq!(image.solarized(101).pixel(0, 0), &Rgba::new(0, 100, 0, 60));
+
+    let image = Image::from_pixels(4, [0, 127, 128, 255].map(|v| Rgba::new(v, v, v, 100)));
+    assert_eq!(
+        image.clone().posterized(2).data,
+        [0, 0, 255, 255].map(|v| Rgba::new(v, v, v, 100))
+    );
+    assert_eq!(
+        image.posterized(3).data,
+        [0, 128, 128, 255].map(|v| Rgba::new(v, v, v, 100))
+    );
+}
+
+#[test]
+#[should_panic(expected = "levels must be at least 2")]
+fn test_posterize_single_level() {
+    Image::new(1, 1, Rgb::white()).posterize(1);
+}
+
+#[test]
+fn test_thresholds() {
+    let image = Image::from_pixels(3, [L(99), L(100), L(101)]);
+
+    // Only pixels strictly brighter than the threshold are on
+    assert_eq!(
+        image.threshold(100).data,
+        [BitPixel::off(), BitPixel::off(), BitPixel::on()]
+    );
+    assert!(image.threshold(255).data.iter().all(|p| !p.value()));
+
+    // Uniform images have no threshold separating them
+    let image = Image::new(2, 2, L(50));
+    assert_eq!(image.otsu_threshold(), 0);
+
+    // A dark and a bright half, each with a darker detail, like a shadow across a document
+    let image = Image::from_pixels(8, [40, 10, 40, 40, 200, 150, 200, 200].map(L));
+    assert_eq!(image.otsu_threshold(), 40);
+    let global = image.threshold_otsu();
+    assert!(!global.pixel(1, 0).value());
+    assert!(global.pixel(5, 0).value());
+
+    let adaptive = image.threshold_adaptive(3, 5);
+    for x in [0, 2, 4, 6, 7] {
+        assert!(adaptive.pixel(x, 0).value());
+    }
+    assert!(!adaptive.pixel(1, 0).value());
+    assert!(!adaptive.pixel(5, 0).value());
+
+    // Flat areas are off unless the constant is positive
+    let image = Image::from_pixels(5, [100, 100, 50, 100, 100].map(L));
+    assert!(!image.threshold_adaptive(3, 0).pixel(0, 0).value());
+    assert_eq!(
+        image.threshold_adaptive(3, 5).data,
+        [true, true, false, true, true].map(BitPixel)
+    );
+    // A block size of one compares every pixel to itself
+    assert!(image
+        .threshold_adaptive(1, 1)
+        .data
+        .iter()
+        .all(|p| p.value()));
+}
+
+#[test]
+#[should_panic(expected = "block size must be greater than zero")]
+fn test_adaptive_threshold_empty_block() {
+    let _ = Image::new(1, 1, L(0)).threshold_adaptive(0, 0);
+}
+
+#[test]
+fn test_band_arithmetic() {
+    let image = Image::new(1, 1, Rgb::new(10, 20, 30));
+    assert_eq!(image.swizzle("bgr").pixel(0, 0), &Rgb::new(30, 20, 10));
+    // Patterns are case-insensitive and may repeat or fill bands
+    assert_eq!(image.swizzle("RR0").pixel(0, 0), &Rgb::new(10, 10, 0));
+    assert_eq!(image.swizzle("1gB").pixel(0, 0), &Rgb::new(255, 20, 30));
+
+    let image = Image::new(1, 1, Rgba::new(10, 20, 30, 40));
+    assert_eq!(
+        image.swizzle("argb").pixel(0, 0),
+        &Rgba::new(40, 10, 20, 30)
+    );
+
+    let image = Image::new(1, 1, Rgb::new(100, 100, 100));
+    assert_eq!(
+        image
+            .scale_bands(&[-1.0, 1.0, 3.0], &[255.0, 0.0, 0.0])
+            .pixel(0, 0),
+        &Rgb::new(155, 100, 255)
+    );
+    // Negative results are clamped to zero
+    assert_eq!(
+        image
+            .scale_bands(&[1.0, 1.0, 0.5], &[-200.0, 0.0, 0.25])
+            .pixel(0, 0),
+        &Rgb::new(0, 100, 50)
+    );
+
+    let a = Image::from_pixels(2, [L(200), L(255)]);
+    let b = Image::from_pixels(2, [L(100), L(128)]);
+    assert_eq!(a.saturating_add(&b).data, [L(255), L(255)]);
+    assert_eq!(a.saturating_sub(&b).data, [L(100), L(127)]);
+    assert_eq!(b.saturating_sub(&a).data, [L(0), L(0)]);
+    // A value of 255 multiplies by one
+    assert_eq!(a.multiply(&b).data, [L(78), L(128)]);
+}
+
+#[test]
+#[should_panic(expected = "exactly one character per band")]
+fn test_swizzle_pattern_length() {
+    let _ = Image::new(1, 1, Rgba::new(10, 20, 30, 40)).swizzle("rgb");
+}
+
+#[test]
+#[should_panic(expected = "unknown character")]
+fn test_swizzle_unknown_character() {
+    let _ = Image::new(1, 1, Rgb::new(10, 20, 30)).swizzle("rgx");
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_swizzle_missing_band() {
+    // Rgb images have no alpha band to select
+    let _ = Image::new(1, 1, Rgb::new(10, 20, 30)).swizzle("rga");
+}
+
+#[test]
+#[should_panic(expected = "bands must have the same dimensions")]
+fn test_band_dimension_mismatch() {
+    let _ = Image::new(2, 1, L(0)).saturating_add(&Image::new(1, 2, L(0)));
+}
+
+#[test]
+fn test_quantize_to_palette() {
+    let image = Image::from_fn(4, 1, |x, _| Rgb::new(x as u8 * 85, 0, 0));
+    let palette = [Rgb::black(), Rgb::new(255, 0, 0)];
+    assert_eq!(
+        ril::Quantizer::new().map_to_palette(&image, &palette),
+        [0, 0, 1, 1]
+    );
+    // Ties are resolved to the first color
+    assert_eq!(
+        ril::Quantizer::new().map_to_palette(&image, &[Rgb::white(), Rgb::white()]),
+        [0; 4]
+    );
+
+    let quantized: Image<PalettedRgb> = image.quantize_to_palette(palette.to_vec(), None);
+    assert_eq!(quantized.palette(), Some(&palette[..]));
+    assert_eq!(
+        quantized.palette_indices().collect::<Vec<_>>(),
+        [0, 0, 1, 1]
+    );
+    assert_eq!(quantized.pixel(3, 0).color(), Rgb::new(255, 0, 0));
+
+    // Without dithering, gray is rounded to the closest color
+    let gray = Image::new(8, 8, Rgb::new(128, 128, 128));
+    let palette = vec![Rgb::black(), Rgb::white()];
+    let quantized: Image<PalettedRgb> = gray.clone().quantize_to_palette(palette.clone(), None);
+    assert!(quantized.palette_indices().all(|index| index == 1));
+    // Dithering mixes both colors instead
+    for dither in [
+        Dither::FloydSteinberg,
+        Dither::Atkinson,
+        Dither::Bayer2x2,
+        Dither::Bayer4x4,
+        Dither::Bayer8x8,
+    ] {
+        let quantized: Image<PalettedRgb> = gray
+            .clone()
+            .quantize_to_palette(palette.clone(), Some(dither));
+        let white = quantized
+            .palette_indices()
+            .filter(|&index| index == 1)
+            .count();
+        assert!((16..=48).contains(&white), "{dither:?} {white}");
+    }
+
+    // Colors of the palette are kept, even when dithering
+    let image = Image::from_fn(4, 4, |x, y| palette[((x + y) % 2) as usize]);
+    let quantized: Image<PalettedRgb> =
+        image.quantize_to_palette(palette.clone(), Some(Dither::FloydSteinberg));
+    for (x, y, pixel) in quantized.pixels_with_coords() {
+        assert_eq!(pixel.color(), palette[((x + y) % 2) as usize]);
+    }
+
+    // Alpha is taken into account
+    let image = Image::new(1, 1, Rgba::new(0, 0, 0, 10));
+    let palette = vec![Rgba::black(), Rgba::transparent()];
+    let quantized: Image<PalettedRgba> = image.quantize_to_palette(palette, None);
+    assert_eq!(quantized.pixel(0, 0).color(), Rgba::transparent());
+}
+
+#[test]
+#[should_panic(expected = "palette must have between 1 and 256 colors")]
+fn test_quantize_to_empty_palette() {
+    let _ = ril::Quantizer::new().map_to_palette(&Image::new(1, 1, Rgb::white()), &[]);
+}
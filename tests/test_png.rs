@@ -108,3 +108,400 @@ fn test_gh_17() -> ril::Result<()> {
     image.mask_alpha(&mask);
     image.save_inferred("tests/out/gh_17.png")
 }
+
+#[test]
+fn test_png_error_context() {
+    let err = Image::<Rgb>::from_bytes(ImageFormat::Png, b"\x89PNG\r\n\x1a\nnot a png")
+        .err()
+        .expect("decoding a corrupt image should fail");
+    assert!(matches!(
+        err,
+        ril::Error::CodecDecodingError {
+            format: ImageFormat::Png,
+            ..
+        }
+    ));
+    assert!(std::error::Error::source(&err).is_some());
+
+    let err = Image::<Rgb>::open("tests/missing.png")
+        .err()
+        .expect("opening a missing file should fail");
+    assert!(err.to_string().starts_with("tests/missing.png: "));
+    assert!(matches!(err.root_cause(), ril::Error::IoError(_)));
+}
+
+#[test]
+fn test_16_bit_png() -> ril::Result<()> {
+    // Values that are not multiples of 257 would be lost if reduced to 8 bits
+    let image = Image::from_fn(64, 16, |x, y| {
+        Rgba16::new(
+            x as u16 * 1000 + 1,
+            y as u16 * 4000 + 3,
+            12_345,
+            65_535 - x as u16,
+        )
+    });
+
+    let mut bytes = Vec::new();
+    image.encode(ImageFormat::Png, &mut bytes)?;
+
+    let decoded = Image::<Rgba16>::from_bytes(ImageFormat::Png, &bytes)?;
+    assert_eq!(decoded.data, image.data);
+
+    let opaque = Image::<Rgb16>::from_bytes(ImageFormat::Png, &bytes)?;
+    assert_eq!(opaque.pixel(1, 0), &Rgb16::new(1001, 3, 12_345));
+
+    // Decoding into 8-bit pixels and converting between depths both round to the nearest value
+    let reduced = Image::<Rgba>::from_bytes(ImageFormat::Png, &bytes)?;
+    assert_eq!(reduced.data, image.clone().convert::<Rgba>().data);
+    assert_eq!(reduced.pixel(63, 0), &Rgba::new(245, 0, 48, 255));
+    assert_eq!(
+        reduced.convert::<Rgba16>().pixel(63, 0),
+        &Rgba16::new(62_965, 0, 12_336, 65_535)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_float_png() -> ril::Result<()> {
+    fn roundtrip<P: Pixel>(image: &Image<P>) -> ril::Result<Image<Rgba16>> {
+        let mut bytes = Vec::new();
+        image.encode(ImageFormat::Png, &mut bytes)?;
+        Image::from_bytes(ImageFormat::Png, &bytes)
+    }
+
+    // Floating point pixels are stored as 16-bit RGBA, clamping out of range values
+    let decoded = roundtrip(&Image::new(2, 2, L32F(0.5)))?;
+    assert_eq!(
+        decoded.data,
+        [Rgba16::new(32_768, 32_768, 32_768, 65_535); 4]
+    );
+
+    let decoded = roundtrip(&Image::new(2, 2, Rgb32F::new(0.25, 1.5, -1.)))?;
+    assert_eq!(decoded.data, [Rgba16::new(16_384, 65_535, 0, 65_535); 4]);
+
+    let decoded = roundtrip(&Image::new(2, 2, Rgba32F::new(0., 1., 0.5, 0.75)))?;
+    assert_eq!(decoded.data, [Rgba16::new(0, 65_535, 32_768, 49_151); 4]);
+
+    Ok(())
+}
+
+#[test]
+fn test_interlaced_png() -> ril::Result<()> {
+    use ril::encodings::png::{FilterType, PngEncoder, PngEncoderOptions};
+
+    // Odd dimensions leave some of the passes with partial or no columns and rows
+    for (width, height) in [(1, 1), (5, 3), (37, 21)] {
+        let image = Image::from_fn(width, height, |x, y| {
+            Rgba::new((x * 7) as u8, (y * 11) as u8, (x ^ y) as u8, 255 - x as u8)
+        });
+
+        for filter in [FilterType::NoFilter, FilterType::Avg, FilterType::Paeth] {
+            let mut bytes = Vec::new();
+            let options = PngEncoderOptions::new()
+                .with_filter(filter)
+                .with_interlacing(true);
+            let mut encoder = PngEncoder::new(
+                &mut bytes,
+                EncoderMetadata::from(&image).with_config(options),
+            )?;
+            encoder.add_frame(&image)?;
+            encoder.finish()?;
+
+            // The interlace method is the last byte of the IHDR chunk
+            assert_eq!(bytes[28], 1);
+            let decoded = Image::<Rgba>::from_bytes(ImageFormat::Png, &bytes)?;
+            assert_eq!(decoded.data, image.data);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_png_metadata() -> ril::Result<()> {
+    let metadata = ImageMetadata::new()
+        .with_text("Author", "ril")
+        .with_text("Comment", "long text is compressed ".repeat(64))
+        .with_text("Title", "Тест 🦀")
+        .with_icc_profile(vec![1, 2, 3, 4, 5])
+        .with_gamma(1.0 / 2.2)
+        .with_dpi(300, 72);
+    let image = Image::new(4, 4, Rgb::new(255, 0, 0)).with_metadata(metadata.clone());
+
+    let mut bytes = Vec::new();
+    image.encode(ImageFormat::Png, &mut bytes)?;
+    let decoded = Image::<Rgb>::from_bytes(ImageFormat::Png, &bytes)?;
+
+    assert_eq!(decoded.metadata(), &metadata);
+    assert_eq!(decoded.metadata().text("Title"), Some("Тест 🦀"));
+    assert_eq!(decoded.metadata().dpi(), Some((300, 72)));
+    assert_eq!(decoded.metadata().gamma(), Some(0.45455));
+
+    // Metadata is kept through edits
+    let mirrored = decoded.mirrored().convert::<Rgba>();
+    assert_eq!(mirrored.metadata(), &metadata);
+
+    Ok(())
+}
+
+#[test]
+fn test_png_exif_orientation() -> ril::Result<()> {
+    use ril::encodings::png::PngDecoder;
+
+    // A little-endian TIFF header followed by an IFD with only an orientation tag
+    let exif = |orientation: u16| {
+        let mut exif = b"II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0".to_vec();
+        exif.extend(orientation.to_le_bytes());
+        exif.extend([0; 6]);
+        exif
+    };
+    let upright = Image::from_fn(3, 2, |x, y| COLORS[(y * 3 + x) as usize]);
+
+    for (value, stored) in [
+        (1, upright.clone()),
+        (2, upright.clone().mirrored()),
+        (3, upright.clone().rotated(180)),
+        (4, upright.clone().flipped()),
+        (5, upright.clone().rotated(90).mirrored()),
+        (6, upright.clone().rotated(270)),
+        (7, upright.clone().rotated(270).mirrored()),
+        (8, upright.clone().rotated(90)),
+    ] {
+        let stored = stored.with_metadata(ImageMetadata::new().with_exif(exif(value)));
+        let mut bytes = Vec::new();
+        stored.encode(ImageFormat::Png, &mut bytes)?;
+
+        let decoded = Image::<Rgb>::from_bytes(ImageFormat::Png, &bytes)?;
+        assert_eq!(
+            decoded.metadata().orientation(),
+            Orientation::from_exif(value)
+        );
+        assert_eq!(decoded.data, stored.data);
+
+        let oriented: Image<Rgb> = PngDecoder::new()
+            .with_apply_orientation(true)
+            .decode(bytes.as_slice())?;
+        assert_eq!(oriented.dimensions(), (3, 2));
+        assert_eq!(oriented.data, upright.data, "orientation {value}");
+        assert_eq!(oriented.metadata().orientation(), Some(Orientation::Normal));
+
+        let mut rows = Vec::new();
+        PngDecoder::new().with_apply_orientation(true).decode_rows(
+            bytes.as_slice(),
+            &mut |_y, row: &[Rgb]| {
+                rows.extend_from_slice(row);
+                Ok(())
+            },
+        )?;
+        assert_eq!(rows, upright.data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_apng_frame_optimization() -> ril::Result<()> {
+    use ril::encodings::png::{PngEncoder, PngEncoderOptions};
+
+    // A small square moving across a detailed background
+    let background = Image::from_fn(128, 128, |x, y| {
+        Rgba::new(x as u8 * 2, y as u8 * 2, 64, 255)
+    });
+    let mut seq = ImageSequence::new();
+    for i in 0..8 {
+        let mut image = background.clone();
+        image.draw(
+            &Rectangle::at(i * 12, 40)
+                .with_size(8, 8)
+                .with_fill(Rgba::white()),
+        );
+        seq.push_frame(Frame::from_image(image));
+    }
+    // Frames that did not change are still written
+    seq.push_frame(seq.iter().last().unwrap().clone());
+
+    let mut full = Vec::new();
+    seq.encode(ImageFormat::Png, &mut full)?;
+
+    let mut optimized = Vec::new();
+    let metadata = EncoderMetadata::from(&seq)
+        .with_config(PngEncoderOptions::new().with_frame_optimization(true));
+    let mut encoder = PngEncoder::new(&mut optimized, metadata)?;
+    for frame in seq.iter() {
+        encoder.add_frame(frame)?;
+    }
+    encoder.finish()?;
+    assert!(optimized.len() * 3 < full.len());
+
+    let decoded = ImageSequence::<Rgba>::from_bytes(ImageFormat::Png, &optimized)?
+        .collect::<ril::Result<ImageSequence<_>>>()?;
+    assert_eq!(decoded.len(), seq.len());
+    // Only the area the square moved across is written for the second frame
+    let second = decoded.iter().nth(1).unwrap();
+    assert_eq!(second.offset(), (0, 40));
+    assert_eq!(second.dimensions(), (20, 8));
+
+    for (frame, original) in decoded.coalesced().iter().zip(seq.iter()) {
+        assert_eq!(frame.data, original.data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_png_decode_rows() -> ril::Result<()> {
+    use ril::encodings::png::{PngEncoder, PngEncoderOptions};
+
+    #[derive(Default)]
+    struct Collect {
+        dimensions: (u32, u32),
+        pixels: Vec<Rgb>,
+    }
+
+    impl RowSink<Rgb> for Collect {
+        fn start(&mut self, width: u32, height: u32) -> ril::Result<()> {
+            self.dimensions = (width, height);
+            Ok(())
+        }
+
+        fn row(&mut self, y: u32, pixels: &[Rgb]) -> ril::Result<()> {
+            assert_eq!(self.pixels.len(), (y * self.dimensions.0) as usize);
+            self.pixels.extend_from_slice(pixels);
+            Ok(())
+        }
+    }
+
+    let image = Image::<Rgb>::open("tests/sample.png")?;
+    let mut interlaced = Vec::new();
+    let options = PngEncoderOptions::new().with_interlacing(true);
+    let mut encoder = PngEncoder::new(
+        &mut interlaced,
+        EncoderMetadata::from(&image).with_config(options),
+    )?;
+    encoder.add_frame(&image)?;
+    encoder.finish()?;
+
+    for bytes in [std::fs::read("tests/sample.png")?, interlaced] {
+        let mut sink = Collect::default();
+        ImageFormat::Png.run_row_decoder(bytes.as_slice(), &mut sink)?;
+        assert_eq!(sink.dimensions, image.dimensions());
+        assert_eq!(sink.pixels, image.data);
+    }
+
+    // Errors returned by the sink stop decoding
+    let mut rows = 0;
+    let result = ImageFormat::Png.run_row_decoder(
+        std::fs::File::open("tests/sample.png")?,
+        &mut |y, _: &[Rgb]| {
+            rows += 1;
+            if y == 9 {
+                Err(ril::Error::EmptyImageError)
+            } else {
+                Ok(())
+            }
+        },
+    );
+    assert!(result.is_err());
+    assert_eq!(rows, 10);
+
+    Ok(())
+}
+
+#[test]
+fn test_infer_png() -> ril::Result<()> {
+    use std::io::Read;
+
+    /// Returns a single byte per read, like a slow network stream
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            (&mut self.0).take(1).read(buf)
+        }
+    }
+
+    let bytes = std::fs::read("tests/sample.png")?;
+    assert_eq!(ImageFormat::infer_encoding(&bytes), ImageFormat::Png);
+    // Samples that are too short to hold a signature must not panic
+    assert_eq!(ImageFormat::infer_encoding(b"II*\0"), ImageFormat::Tiff);
+    assert_eq!(ImageFormat::infer_encoding(b""), ImageFormat::Unknown);
+
+    let image = Image::<Rgb>::from_reader_inferred(Trickle(&bytes))?;
+    assert_eq!(image.dimensions(), (1024, 1024));
+    let frames = ImageSequence::<Rgb>::from_reader_inferred(Trickle(&bytes))?;
+    assert_eq!(frames.len(), 1);
+
+    // Files without an extension are inferred from their contents
+    let path = std::env::temp_dir().join("ril_png_without_extension");
+    std::fs::write(&path, &bytes)?;
+    let opened = Image::<Rgb>::open(&path)?;
+    assert_eq!(opened.data, image.data);
+    let frames = ImageSequence::<Rgb>::open(&path)?;
+    assert_eq!(frames.len(), 1);
+    std::fs::remove_file(&path)?;
+
+    // Formats without an enabled decoder are an error instead of a panic
+    let mut unsupported = vec![(&b"BM\0\0\0\0\0\0\0\0\0\0"[..], ImageFormat::Bmp)];
+    if !cfg!(feature = "tiff") {
+        unsupported.push((b"II*\0\0\0\0\0\0\0\0\0", ImageFormat::Tiff));
+    }
+    let path = std::env::temp_dir().join("ril_unsupported_without_extension");
+    for (bytes, format) in unsupported {
+        std::fs::write(&path, bytes)?;
+        for err in [
+            Image::<Rgb>::open(&path).err(),
+            ImageSequence::<Rgb>::open(&path).err(),
+        ] {
+            let cause = err.as_ref().map(ril::Error::root_cause);
+            assert!(matches!(cause, Some(ril::Error::UnsupportedFormat(f)) if *f == format));
+        }
+    }
+    std::fs::remove_file(&path)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_open_with_format() -> ril::Result<()> {
+    // Save the samples with an extension that does not match their contents
+    let path = std::env::temp_dir().join("ril_png_with_wrong_extension.gif");
+    std::fs::copy("tests/sample.png", &path)?;
+
+    assert!(Image::<Rgb>::open(&path).is_err());
+    let image = Image::<Rgb>::open_with_format(&path, ImageFormat::Png)?;
+    assert_eq!(image.data, Image::<Rgb>::open("tests/sample.png")?.data);
+
+    let apng_path = std::env::temp_dir().join("ril_apng_with_wrong_extension.gif");
+    std::fs::copy("tests/apng_sample.png", &apng_path)?;
+
+    let expected =
+        ImageSequence::<Rgba>::open("tests/apng_sample.png")?.collect::<ril::Result<Vec<_>>>()?;
+    let frames = ImageSequence::<Rgba>::open_with_format(&apng_path, ImageFormat::Png)?
+        .collect::<ril::Result<Vec<_>>>()?;
+    assert_eq!(frames.len(), expected.len());
+    assert!(frames.len() > 1);
+    for (frame, expected) in frames.iter().zip(&expected) {
+        assert_eq!(frame.image().data, expected.image().data);
+    }
+
+    std::fs::remove_file(&path)?;
+    std::fs::remove_file(&apng_path)?;
+
+    // Errors carry the path of the file
+    let missing = std::env::temp_dir().join("ril_missing_file");
+    match Image::<Rgb>::open_with_format(&missing, ImageFormat::Png) {
+        Err(ril::Error::PathError { path, source }) => {
+            assert_eq!(path, missing);
+            assert!(matches!(*source, ril::Error::IoError(_)));
+        }
+        _ => panic!("expected a path error"),
+    }
+    assert!(matches!(
+        ImageSequence::<Rgb>::open_with_format(&missing, ImageFormat::Png),
+        Err(ril::Error::PathError { .. })
+    ));
+
+    Ok(())
+}
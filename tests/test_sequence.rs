@@ -0,0 +1,555 @@
+use ril::prelude::*;
+use std::time::Duration;
+
+fn sample_sequence() -> ImageSequence<Rgba> {
+    (0..4)
+        .map(|i| {
+            Frame::from_image(Image::new(8, 8, Rgba::new(i * 60, 0, 0, 255)))
+                .with_delay(Duration::from_millis(u64::from(i + 1) * 10))
+                .with_disposal(DisposalMethod::Background)
+        })
+        .collect::<ImageSequence<_>>()
+        .looped_exactly(3)
+}
+
+#[test]
+fn test_map_frames() {
+    let sequence = sample_sequence().map_frames(|image| image.convert::<L>().cropped(0, 0, 4, 4));
+
+    assert_eq!(sequence.len(), 4);
+    assert_eq!(sequence.loop_count(), LoopCount::Exactly(3));
+    for (i, frame) in sequence.iter().enumerate() {
+        assert_eq!(frame.dimensions(), (4, 4));
+        assert_eq!(frame.delay(), Duration::from_millis(i as u64 * 10 + 10));
+        assert_eq!(frame.disposal(), DisposalMethod::Background);
+    }
+
+    let result = sample_sequence().try_map_frames::<Rgba>(|_| Err(ril::Error::EmptyImageError));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_map_frames_keeps_offset_and_blend() -> ril::Result<()> {
+    let frame = |value: u8| {
+        Frame::from_image(Image::new(2, 2, Rgb::new(value, value, value)))
+            .with_offset(1, 2)
+            .with_blend(BlendMethod::Over)
+    };
+    let sequence = ImageSequence::from_frames(vec![frame(0), frame(100)]);
+
+    let mapped = sequence
+        .clone()
+        .try_map_frames(|image| Ok(image.convert::<Rgba>()))?;
+    let interpolated = sequence.interpolated(4);
+    assert_eq!(interpolated.len(), 5);
+    assert_eq!(
+        interpolated.iter().nth(2).unwrap().pixel(0, 0),
+        &Rgb::new(50, 50, 50)
+    );
+
+    // Intermediate frames should be placed where the frames they are blended from are
+    for (offset, blend) in mapped
+        .iter()
+        .map(|frame| (frame.offset(), frame.blend()))
+        .chain(
+            interpolated
+                .iter()
+                .map(|frame| (frame.offset(), frame.blend())),
+        )
+    {
+        assert_eq!(offset, (1, 2));
+        assert_eq!(blend, BlendMethod::Over);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_crop_and_convert_sequence() {
+    let sequence = sample_sequence().cropped(2, 2, 6, 5).convert::<Rgb>();
+
+    assert_eq!(sequence.loop_count(), LoopCount::Exactly(3));
+    for (i, frame) in sequence.iter().enumerate() {
+        assert_eq!(frame.dimensions(), (4, 3));
+        assert_eq!(frame.pixel(0, 0), &Rgb::new(i as u8 * 60, 0, 0));
+        assert_eq!(frame.delay(), Duration::from_millis(i as u64 * 10 + 10));
+    }
+}
+
+#[test]
+fn test_from_dir_sorted() -> ril::Result<()> {
+    let dir = std::path::Path::new("tests/out/sequence_frames");
+    std::fs::create_dir_all(dir)?;
+    // Unpadded numbers must be sorted by value, not lexicographically
+    for i in [0_u8, 2, 10] {
+        Image::new(2, 2, L(i)).save_inferred(dir.join(format!("frame_{}.png", i)))?;
+    }
+    std::fs::write(dir.join("notes.txt"), "not a frame")?;
+
+    let sequence = ImageSequence::<L>::from_dir_sorted(dir)?;
+    let values = sequence.iter().map(|frame| frame.pixel(0, 0).value());
+    assert_eq!(values.collect::<Vec<_>>(), [0, 2, 10]);
+    assert!(sequence
+        .iter()
+        .all(|frame| frame.delay() == ril::sequence::DEFAULT_FRAME_DELAY));
+    Ok(())
+}
+
+#[test]
+fn test_save_frames() -> ril::Result<()> {
+    std::fs::create_dir_all("tests/out/sequence_export")?;
+    let sequence = sample_sequence();
+    let paths = sequence.save_frames("tests/out/sequence_export/frame_{:03}.png")?;
+
+    assert_eq!(paths.len(), 4);
+    assert_eq!(
+        paths[2],
+        std::path::Path::new("tests/out/sequence_export/frame_002.png")
+    );
+
+    let loaded = ImageSequence::<Rgba>::from_dir_sorted("tests/out/sequence_export")?;
+    for (frame, expected) in loaded.iter().zip(sequence.iter()) {
+        assert_eq!(frame.data, expected.data);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_lazy_transcode() -> ril::Result<()> {
+    let expected = LazySequence::<Rgb>::open("tests/apng_sample.png")?.into_sequence()?;
+
+    let mut buffer = Vec::new();
+    LazySequence::<Rgb>::open("tests/apng_sample.png")?
+        .map_frames(|image| image.cropped(0, 0, 4, 4))
+        .encode(ImageFormat::Png, &mut buffer)?;
+
+    let decoded = ImageSequence::<Rgb>::from_bytes(ImageFormat::Png, &buffer)?;
+    assert_eq!(decoded.loop_count(), expected.loop_count());
+    let decoded = decoded.collect::<ril::Result<ImageSequence<_>>>()?;
+    assert_eq!(decoded.len(), expected.len());
+    for (frame, expected) in decoded.iter().zip(expected.iter()) {
+        assert_eq!(frame.dimensions(), (4, 4));
+        assert_eq!(frame.pixel(0, 0), expected.pixel(0, 0));
+        assert_eq!(frame.delay(), expected.delay());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_concat_crop() {
+    let other = ImageSequence::new()
+        .with_frame(Frame::from_image(Image::from_fn(10, 6, |x, y| {
+            Rgba::new(x as u8, y as u8, 0, 255)
+        })))
+        .looped_exactly(5);
+    let sequence = sample_sequence().concatenated(other, FitMode::Crop);
+
+    assert_eq!(sequence.len(), 5);
+    assert_eq!(sequence.loop_count(), LoopCount::Exactly(5));
+    assert!(sequence.iter().all(|frame| frame.dimensions() == (8, 6)));
+    // The center of the wider frame is kept
+    assert_eq!(
+        sequence.iter().last().unwrap().pixel(0, 0),
+        &Rgba::new(1, 0, 0, 255)
+    );
+}
+
+#[test]
+fn test_concat_partial_frames() {
+    let red = Rgba::new(255, 0, 0, 255);
+    let blue = Rgba::new(0, 0, 255, 255);
+    // The second frame only covers the bottom right corner of the first one
+    let partial = ImageSequence::new()
+        .with_frame(Frame::from_image(Image::new(4, 4, red)))
+        .with_frame(Frame::from_image(Image::new(2, 2, blue)).with_offset(2, 2));
+    let other = ImageSequence::new().with_frame(Frame::from_image(Image::new(6, 6, Rgba::white())));
+    let frames = partial
+        .concatenated(other, FitMode::Pad(Rgba::transparent()))
+        .into_frames();
+
+    assert_eq!(frames.len(), 3);
+    assert!(frames
+        .iter()
+        .all(|frame| frame.dimensions() == (6, 6) && frame.offset() == (0, 0)));
+    // Partial frames are rendered onto the frames before them, then centered as a whole
+    assert_eq!(frames[1].pixel(1, 1), &red);
+    assert_eq!(frames[1].pixel(4, 4), &blue);
+    assert_eq!(frames[1].pixel(0, 0), &Rgba::transparent());
+}
+
+#[test]
+fn test_animated_overlay() {
+    let sticker = ImageSequence::new()
+        .with_frame(
+            Frame::from_image(Image::new(2, 2, Rgba::white()))
+                .with_delay(Duration::from_millis(25)),
+        )
+        .with_frame(
+            Frame::from_image(Image::new(2, 2, Rgba::black()))
+                .with_delay(Duration::from_millis(25)),
+        );
+    let sequence = sample_sequence().overlaid(&sticker, 1, 1);
+
+    // Frames are split wherever either sequence changes frames
+    let delays = sequence.iter().map(|frame| frame.delay().as_millis());
+    assert_eq!(delays.collect::<Vec<_>>(), [10, 15, 5, 20, 10, 15, 25]);
+    let colors = sequence.iter().map(|frame| *frame.pixel(1, 1));
+    assert_eq!(
+        colors.collect::<Vec<_>>(),
+        [
+            Rgba::white(),
+            Rgba::white(),
+            Rgba::black(),
+            Rgba::black(),
+            Rgba::white(),
+            Rgba::white(),
+            Rgba::black(),
+        ]
+    );
+    assert_eq!(sequence.duration(), sample_sequence().duration());
+
+    // Static overlays do not split frames
+    let sequence = sample_sequence().overlaid(&Image::new(2, 2, Rgba::white()), -1, -1);
+    assert_eq!(sequence.len(), 4);
+    assert!(sequence
+        .iter()
+        .all(|frame| frame.pixel(0, 0) == &Rgba::white()));
+}
+
+#[test]
+fn test_overlay_partial_frames() {
+    // The second frame of the sticker only changes its bottom right pixel
+    let sticker = ImageSequence::new()
+        .with_frame(
+            Frame::from_image(Image::new(2, 2, Rgba::white()))
+                .with_delay(Duration::from_millis(25)),
+        )
+        .with_frame(
+            Frame::from_image(Image::new(1, 1, Rgba::black()))
+                .with_delay(Duration::from_millis(25))
+                .with_offset(1, 1),
+        );
+    let sequence = sample_sequence().overlaid(&sticker, 1, 1);
+
+    assert!(sequence
+        .iter()
+        .all(|frame| frame.pixel(1, 1) == &Rgba::white()));
+    let frames = sequence.into_frames();
+    assert_eq!(frames[0].pixel(2, 2), &Rgba::white());
+    assert_eq!(frames[2].pixel(2, 2), &Rgba::black());
+
+    // Partial frames of the sequence being composited onto are rendered in full first
+    let red = Rgba::new(255, 0, 0, 255);
+    let blue = Rgba::new(0, 0, 255, 255);
+    let sequence = ImageSequence::new()
+        .with_frame(Frame::from_image(Image::new(4, 4, red)))
+        .with_frame(Frame::from_image(Image::new(2, 2, blue)).with_offset(2, 2))
+        .overlaid(&Image::new(1, 1, Rgba::white()), 0, 0);
+
+    let frames = sequence.into_frames();
+    assert_eq!(frames[1].dimensions(), (4, 4));
+    assert_eq!(frames[1].pixel(0, 0), &Rgba::white());
+    assert_eq!(frames[1].pixel(1, 1), &red);
+    assert_eq!(frames[1].pixel(3, 3), &blue);
+}
+
+#[test]
+fn test_iter_timed() {
+    let sequence = sample_sequence();
+    let starts = sequence.iter_timed().map(|(start, _)| start.as_millis());
+    assert_eq!(starts.collect::<Vec<_>>(), [0, 10, 30, 60]);
+
+    // Looped exactly three times, so the last frame is visible after 300 milliseconds
+    let frame = sequence.frame_at(Duration::from_millis(235)).unwrap();
+    assert_eq!(frame.pixel(0, 0), &Rgba::new(120, 0, 0, 255));
+    let frame = sequence.frame_at(Duration::from_millis(305)).unwrap();
+    assert_eq!(frame.pixel(0, 0), &Rgba::new(180, 0, 0, 255));
+}
+
+#[test]
+fn test_edit_frames() {
+    let delays = |sequence: &ImageSequence<Rgba>| {
+        let delays = sequence.iter().map(|frame| frame.delay().as_millis());
+        delays.collect::<Vec<_>>()
+    };
+    let white = Frame::from_image(Image::new(8, 8, Rgba::white()));
+
+    // Frames without a delay inherit the delay of the frame they are inserted before
+    let mut sequence = sample_sequence();
+    sequence.insert_frame(1, white.clone());
+    sequence.insert_frame(5, white.clone());
+    assert_eq!(delays(&sequence), [10, 20, 20, 30, 40, 40]);
+
+    // Removed frames give their time to the frame before them, or after them at the start
+    let mut sequence = sample_sequence();
+    let removed = sequence.remove_range(1..3);
+    assert_eq!(removed.len(), 2);
+    assert_eq!(removed[0].delay(), Duration::from_millis(20));
+    assert_eq!(delays(&sequence), [60, 40]);
+    assert_eq!(sequence.duration(), sample_sequence().duration());
+
+    let mut sequence = sample_sequence();
+    sequence.remove_range(..2);
+    assert_eq!(delays(&sequence), [60, 40]);
+    assert_eq!(
+        sequence.first_frame().unwrap().pixel(0, 0),
+        &Rgba::new(120, 0, 0, 255)
+    );
+
+    let mut sequence = sample_sequence();
+    assert!(sequence.remove_range(2..2).is_empty());
+    assert_eq!(delays(&sequence), [10, 20, 30, 40]);
+    assert_eq!(sequence.remove_range(..).len(), 4);
+    assert!(sequence.is_empty());
+
+    // Spliced frames inherit the delay of the first replaced frame
+    let mut sequence = sample_sequence();
+    let removed = sequence.splice(1..3, [white.clone(), white]);
+    assert_eq!(removed.len(), 2);
+    assert_eq!(delays(&sequence), [10, 20, 20, 40]);
+}
+
+#[test]
+#[should_panic]
+fn test_remove_range_out_of_bounds() {
+    sample_sequence().remove_range(3..5);
+}
+
+#[test]
+fn test_coalesce_partial_frames() -> ril::Result<()> {
+    let red = Rgba::new(255, 0, 0, 255);
+    let blue = Rgba::new(0, 0, 255, 255);
+    let green = Rgba::new(0, 255, 0, 255);
+    let sequence = ImageSequence::new()
+        .with_frame(Frame::from_image(Image::new(4, 4, red)))
+        .with_frame(
+            Frame::from_image(Image::new(2, 2, blue))
+                .with_offset(1, 1)
+                .with_blend(BlendMethod::Over)
+                .with_disposal(DisposalMethod::Background),
+        )
+        .with_frame(Frame::from_image(Image::new(1, 1, green)));
+
+    // Offsets, disposal and blend methods should survive an APNG round trip
+    let mut buffer = Vec::new();
+    sequence.encode(ImageFormat::Png, &mut buffer)?;
+    let decoded = ImageSequence::<Rgba>::from_bytes(ImageFormat::Png, &buffer)?
+        .collect::<ril::Result<ImageSequence<_>>>()?;
+
+    for sequence in [sequence, decoded] {
+        let frames = sequence.coalesced().into_frames();
+        assert!(frames.iter().all(|frame| frame.dimensions() == (4, 4)));
+        assert_eq!(frames[1].pixel(0, 0), &red);
+        assert_eq!(frames[1].pixel(2, 2), &blue);
+        assert_eq!(frames[2].pixel(0, 0), &green);
+        assert_eq!(frames[2].pixel(1, 1), &Rgba::transparent());
+        assert_eq!(frames[2].pixel(3, 3), &red);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_coalesce_dynamic_frames() {
+    let red = Rgba::new(255, 0, 0, 255);
+    let blue = Rgba::new(0, 0, 255, 255);
+    let green = Rgba::new(0, 255, 0, 255);
+    // Dynamic pixels have no default, so the background is taken from the first frame
+    let mut first = Image::new(4, 4, Dynamic::Rgba(red));
+    first.set_pixel(3, 3, Dynamic::Rgba(Rgba::transparent()));
+    let sequence = ImageSequence::new()
+        .with_frame(Frame::from_image(first))
+        .with_frame(
+            Frame::from_image(Image::new(2, 2, Dynamic::Rgba(blue)))
+                .with_offset(4, 4)
+                .with_disposal(DisposalMethod::Background),
+        )
+        .with_frame(Frame::from_image(Image::new(1, 1, Dynamic::Rgba(green))));
+
+    let frames = sequence.coalesced().into_frames();
+    assert!(frames.iter().all(|frame| frame.dimensions() == (6, 6)));
+    assert_eq!(frames[0].pixel(5, 5).as_rgba(), Rgba::transparent());
+    assert_eq!(frames[1].pixel(0, 0).as_rgba(), red);
+    assert_eq!(frames[1].pixel(4, 4).as_rgba(), blue);
+    assert_eq!(frames[2].pixel(0, 0).as_rgba(), green);
+    assert_eq!(frames[2].pixel(4, 4).as_rgba(), Rgba::transparent());
+}
+
+#[test]
+fn test_coalesce_paletted_frames() {
+    let red = Rgba::new(255, 0, 0, 255);
+    let blue = Rgba::new(0, 0, 255, 255);
+    let green = Rgba::new(0, 255, 0, 255);
+    let palette = vec![red, Rgba::transparent(), blue, green];
+    let mut indices = [0; 16];
+    indices[15] = 1;
+    let sequence = ImageSequence::new()
+        .with_frame(Frame::from_image(
+            Image::<PalettedRgba>::from_paletted_pixels(4, palette.clone(), indices),
+        ))
+        .with_frame(
+            Frame::from_image(Image::from_paletted_pixels(2, palette.clone(), [2; 4]))
+                .with_offset(4, 4)
+                .with_disposal(DisposalMethod::Background),
+        )
+        .with_frame(Frame::from_image(Image::from_paletted_pixels(
+            1,
+            palette,
+            [3],
+        )));
+
+    let frames = sequence.coalesced().into_frames();
+    assert!(frames.iter().all(|frame| frame.dimensions() == (6, 6)));
+    assert_eq!(frames[0].pixel(5, 5).color(), Rgba::transparent());
+    assert_eq!(frames[1].pixel(0, 0).color(), red);
+    assert_eq!(frames[1].pixel(4, 4).color(), blue);
+    assert_eq!(frames[2].pixel(0, 0).color(), green);
+    assert_eq!(frames[2].pixel(4, 4).color(), Rgba::transparent());
+}
+
+#[test]
+fn test_concat_dynamic_partial_frames() {
+    let red = Dynamic::Rgba(Rgba::new(255, 0, 0, 255));
+    let blue = Dynamic::Rgba(Rgba::new(0, 0, 255, 255));
+    let partial = ImageSequence::new()
+        .with_frame(Frame::from_image(Image::new(4, 4, red)))
+        .with_frame(Frame::from_image(Image::new(2, 2, blue)).with_offset(2, 2));
+    let other = ImageSequence::new().with_frame(Frame::from_image(Image::new(4, 4, red)));
+    let frames = partial.concatenated(other, FitMode::Pad(red)).into_frames();
+
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[1].pixel(1, 1), &red);
+    assert_eq!(frames[1].pixel(3, 3), &blue);
+}
+
+#[test]
+fn test_optimize() {
+    let delay = Duration::from_millis(40);
+    let frame = |value: u8| Frame::from_image(Image::new(4, 4, L(value))).with_delay(delay);
+
+    let sequence = ImageSequence::from_frames(vec![
+        frame(0),
+        frame(0),
+        frame(0).with_disposal(DisposalMethod::Background),
+        frame(1),
+        frame(1).with_offset(1, 0),
+    ])
+    .optimized();
+
+    let delays = sequence.iter().map(Frame::delay).collect::<Vec<_>>();
+    assert_eq!(delays, [delay * 2, delay, delay, delay]);
+    assert_eq!(sequence.duration(), delay * 5);
+}
+
+/// A custom pixel type which stores its channels in BGR order.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct Bgr([u8; 3]);
+
+impl Pixel for Bgr {
+    const COLOR_TYPE: ColorType = ColorType::Custom(3);
+    const BIT_DEPTH: u8 = 8;
+
+    type Subpixel = u8;
+    type Color = Self;
+    type Data = [u8; 3];
+
+    fn map_subpixels<F, A>(self, f: F, _: A) -> Self
+    where
+        F: Fn(u8) -> u8,
+        A: Fn(u8) -> u8,
+    {
+        Self(self.0.map(f))
+    }
+
+    fn channel(&self, index: usize) -> u8 {
+        self.0[index]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self([bytes[0], bytes[1], bytes[2]])
+    }
+
+    fn as_bytes(&self) -> [u8; 3] {
+        self.0
+    }
+
+    fn merge_with_alpha(self, other: Self, alpha: u8) -> Self {
+        if alpha < 128 {
+            self
+        } else {
+            other
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self([0, 1, 2].map(|i| self.0[i].max(other.0[i])))
+    }
+
+    fn min(self, other: Self) -> Self {
+        Self([0, 1, 2].map(|i| self.0[i].min(other.0[i])))
+    }
+
+    fn as_rgb(&self) -> Rgb {
+        let [b, g, r] = self.0;
+        Rgb::new(r, g, b)
+    }
+
+    fn as_rgba(&self) -> Rgba {
+        Rgba::from(self.as_rgb())
+    }
+}
+
+impl std::ops::Not for Bgr {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0.map(|c| !c))
+    }
+}
+
+impl std::ops::Mul for Bgr {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self([0, 1, 2].map(|i| (u16::from(self.0[i]) * u16::from(other.0[i]) / 255) as u8))
+    }
+}
+
+impl std::ops::Mul<f32> for Bgr {
+    type Output = Self;
+
+    fn mul(self, factor: f32) -> Self {
+        Self(
+            self.0
+                .map(|c| (f32::from(c) * factor).clamp(0., 255.) as u8),
+        )
+    }
+}
+
+#[test]
+fn test_custom_pixel_sequence() -> ril::Result<()> {
+    let sequence = (0..3)
+        .map(|i| {
+            Frame::from_image(Image::new(4, 4, Bgr([i * 80, 0, 255])))
+                .with_delay(Duration::from_millis(u64::from(i + 1) * 10))
+        })
+        .collect::<ImageSequence<_>>()
+        .looped_exactly(2);
+
+    // Custom pixels are converted into RGBA for both eager and lazy encoding
+    let mut bytes = Vec::new();
+    sequence.encode(ImageFormat::Png, &mut bytes)?;
+    let mut lazy = Vec::new();
+    LazySequence::from_frames(sequence.iter().cloned(), 3)
+        .with_loop_count(LoopCount::Exactly(2))
+        .encode(ImageFormat::Png, &mut lazy)?;
+    assert_eq!(bytes, lazy);
+
+    let decoded = ImageSequence::<Rgb>::from_bytes(ImageFormat::Png, &bytes)?;
+    assert_eq!(decoded.loop_count(), LoopCount::Exactly(2));
+    let decoded = decoded.collect::<ril::Result<ImageSequence<_>>>()?;
+    assert_eq!(decoded.len(), 3);
+    for (i, frame) in decoded.iter().enumerate() {
+        assert_eq!(frame.pixel(3, 3), &Rgb::new(255, 0, i as u8 * 80));
+        assert_eq!(frame.delay(), Duration::from_millis(i as u64 * 10 + 10));
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "serde")]
+
+use ril::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+fn bytes<P: Pixel>(image: &Image<P>) -> Vec<u8> {
+    image
+        .data
+        .iter()
+        .flat_map(|pixel| pixel.as_bytes().as_ref().to_vec())
+        .collect()
+}
+
+fn round_trip<P>(pixels: [P; 4])
+where
+    P: Pixel + Serialize + DeserializeOwned,
+    Image<P>: Serialize + DeserializeOwned,
+{
+    let image = Image::from_pixels(2, pixels);
+    let json = serde_json::to_string(&image).unwrap();
+    let decoded = serde_json::from_str::<Image<P>>(&json).unwrap();
+
+    assert_eq!(decoded.dimensions(), (2, 2));
+    assert_eq!(bytes(&decoded), bytes(&image));
+
+    for pixel in pixels {
+        let json = serde_json::to_string(&pixel).unwrap();
+        let decoded = serde_json::from_str::<P>(&json).unwrap();
+
+        assert_eq!(decoded.as_bytes().as_ref(), pixel.as_bytes().as_ref());
+    }
+}
+
+#[test]
+fn test_pixel_round_trips() {
+    round_trip([
+        BitPixel::on(),
+        BitPixel::off(),
+        BitPixel::off(),
+        BitPixel::on(),
+    ]);
+    round_trip([L(0), L(64), L(128), L(255)]);
+    round_trip([
+        Rgb::new(255, 0, 0),
+        Rgb::new(0, 255, 0),
+        Rgb::new(0, 0, 255),
+        Rgb::new(12, 34, 56),
+    ]);
+    round_trip([
+        Rgba::new(255, 0, 0, 255),
+        Rgba::new(0, 255, 0, 128),
+        Rgba::new(0, 0, 255, 0),
+        Rgba::new(12, 34, 56, 78),
+    ]);
+    round_trip([L16(0), L16(256), L16(4660), L16(65535)]);
+    round_trip([
+        Rgb16::new(65535, 0, 0),
+        Rgb16::new(0, 65535, 0),
+        Rgb16::new(0, 0, 65535),
+        Rgb16::new(1234, 5678, 9012),
+    ]);
+    round_trip([
+        Rgba16::new(65535, 0, 0, 65535),
+        Rgba16::new(0, 65535, 0, 32768),
+        Rgba16::new(0, 0, 65535, 0),
+        Rgba16::new(1234, 5678, 9012, 3456),
+    ]);
+    round_trip([L32F(0.0), L32F(0.25), L32F(1.0), L32F(2.5)]);
+    round_trip([
+        Rgb32F::new(1.0, 0.0, 0.0),
+        Rgb32F::new(0.0, 1.0, 0.0),
+        Rgb32F::new(0.0, 0.0, 1.0),
+        Rgb32F::new(0.1, 0.2, 0.3),
+    ]);
+    round_trip([
+        Rgba32F::new(1.0, 0.0, 0.0, 1.0),
+        Rgba32F::new(0.0, 1.0, 0.0, 0.5),
+        Rgba32F::new(0.0, 0.0, 1.0, 0.0),
+        Rgba32F::new(0.1, 0.2, 0.3, 0.4),
+    ]);
+    round_trip([
+        Cmyk::new(255, 0, 0, 0),
+        Cmyk::new(0, 255, 0, 0),
+        Cmyk::new(0, 0, 255, 0),
+        Cmyk::new(0, 0, 0, 255),
+    ]);
+}
+
+#[test]
+fn test_serialized_format() -> serde_json::Result<()> {
+    assert_eq!(
+        serde_json::to_string(&Rgb::new(255, 128, 0))?,
+        r#"{"r":255,"g":128,"b":0}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&Image::new(2, 1, L(255)))?,
+        r#"{"width":2,"height":1,"data":"//8="}"#
+    );
+
+    // The pixel data must match the dimensions of the image
+    let result = serde_json::from_str::<Image<L>>(r#"{"width":2,"height":2,"data":"//8="}"#);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sprite {
+    #[serde(with = "ril::interop::serde::hex")]
+    tint: Rgba,
+    #[cfg(feature = "png")]
+    #[serde(with = "ril::interop::serde::png")]
+    image: Image<Rgba>,
+}
+
+#[test]
+fn test_with_modules() -> serde_json::Result<()> {
+    let sprite = Sprite {
+        tint: Rgba::new(255, 128, 0, 128),
+        #[cfg(feature = "png")]
+        image: Image::from_fn(3, 2, |x, y| Rgba::new(x as u8 * 80, y as u8 * 200, 0, 255)),
+    };
+    let json = serde_json::to_string(&sprite)?;
+    assert!(json.starts_with(r##"{"tint":"#ff800080""##));
+
+    let decoded = serde_json::from_str::<Sprite>(&json)?;
+    assert_eq!(decoded.tint, sprite.tint);
+    #[cfg(feature = "png")]
+    assert_eq!(decoded.image.data, sprite.image.data);
+
+    Ok(())
+}
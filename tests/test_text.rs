@@ -62,3 +62,242 @@ fn test_resize_gradient() -> ril::Result<()> {
         .resized(2048, 512, ResizeAlgorithm::Bilinear)
         .save_inferred("tests/out/resize_gradient_output_resized.png")
 }
+
+#[test]
+fn test_text_fill() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 48.0)?;
+    let mut image = Image::new(256, 64, Rgba::black());
+
+    let gradient = LinearGradient::new()
+        .with_color(Rgba::new(255, 0, 0, 255))
+        .with_color(Rgba::new(0, 0, 255, 255));
+
+    let (cx, cy) = image.center();
+    let layout = TextLayout::new()
+        .with_position(cx, cy)
+        .centered()
+        .with_basic_text(&font, "gradient", gradient);
+    let glyphs = layout.glyphs().collect::<Vec<_>>();
+
+    image.draw(&layout);
+    let drawn = drawn_pixels(&image, Rgba::black());
+    assert!(!drawn.is_empty());
+    for &(x, y, pixel) in &drawn {
+        assert!(
+            in_glyph(&glyphs, x, y),
+            "({x}, {y}) is outside of every glyph"
+        );
+        // Only the red and blue stops of the gradient are blended onto the black background
+        assert_eq!(pixel.g, 0, "({x}, {y}) is not filled by the gradient");
+    }
+
+    // The gradient runs from red on the left to blue on the right
+    let leftmost = drawn.iter().min_by_key(|(x, ..)| x).unwrap().2;
+    let rightmost = drawn.iter().max_by_key(|(x, ..)| x).unwrap().2;
+    assert!(leftmost.r > leftmost.b);
+    assert!(rightmost.b > rightmost.r);
+    image.save_inferred("tests/out/text_fill_output.png")
+}
+
+/// Returns the coordinates and values of every pixel of the image which differs from the
+/// background.
+fn drawn_pixels(image: &Image<Rgba>, background: Rgba) -> Vec<(u32, u32, Rgba)> {
+    (0..image.height())
+        .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .map(|(x, y)| (x, y, *image.pixel(x, y)))
+        .filter(|&(.., pixel)| pixel != background)
+        .collect()
+}
+
+/// Returns whether the given pixel lies within the bounds of any of the given glyphs, allowing a
+/// pixel of leeway for rounding and antialiasing.
+fn in_glyph(glyphs: &[GlyphInfo], x: u32, y: u32) -> bool {
+    let (x, y) = (x as f32, y as f32);
+
+    glyphs.iter().any(|glyph| {
+        (glyph.x - 1.0..glyph.x + glyph.width as f32 + 1.0).contains(&x)
+            && (glyph.y - 1.0..glyph.y + glyph.height as f32 + 1.0).contains(&y)
+    })
+}
+
+#[test]
+fn test_text_stroke() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 48.0)?;
+    let mut image = Image::new(256, 64, Rgba::transparent());
+    let stroke = Rgba::new(255, 0, 0, 255);
+
+    let (cx, cy) = image.center();
+    let segment = TextSegment::new(&font, "stroke", Rgba::white()).with_stroke(stroke, 3);
+    let layout = TextLayout::new()
+        .with_position(cx, cy)
+        .centered()
+        .with_text(&segment);
+
+    image.draw(&layout);
+    // The fill is drawn over the outline
+    assert!(image.data.iter().any(|&pixel| pixel == Rgba::white()));
+    assert!(image.data.iter().any(|&pixel| pixel == stroke));
+    image.save_inferred("tests/out/text_stroke_output.png")
+}
+
+#[test]
+fn test_text_shadow() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 48.0)?;
+    let shadow = Rgba::new(255, 0, 0, 255);
+    let layout = TextLayout::new()
+        .with_position(8, 8)
+        .with_shadow((0, 32), 4, shadow)
+        .with_basic_text(&font, "shadow", Rgba::white());
+
+    let mut image = Image::new(256, 128, Rgba::black());
+    image.draw(&layout);
+    // The shadow is offset far enough to not be covered by the text
+    assert!(image.data.iter().any(|&pixel| pixel == Rgba::white()));
+    assert!(image.data.iter().any(|pixel| pixel.r > 128 && pixel.g == 0));
+    image.save_inferred("tests/out/text_shadow_output.png")
+}
+
+#[test]
+fn test_text_faux_styles() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 32.0)?;
+    let render = |segment: TextSegment<Rgba>| {
+        let layout = TextLayout::new().with_position(8, 8).with_text(&segment);
+        Image::new(192, 64, Rgba::black()).with(&layout)
+    };
+    let coverage = |image: &Image<Rgba>| image.data.iter().filter(|pixel| pixel.r > 0).count();
+
+    let regular = render(TextSegment::new(&font, "faux", Rgba::white()));
+    let bold = render(TextSegment::new(&font, "faux", Rgba::white()).with_faux_bold(2));
+    let italic = render(TextSegment::new(&font, "faux", Rgba::white()).with_faux_italic(0.25));
+
+    assert!(coverage(&bold) > coverage(&regular));
+    assert_ne!(italic.data, regular.data);
+    bold.save_inferred("tests/out/text_faux_bold_output.png")?;
+    italic.save_inferred("tests/out/text_faux_italic_output.png")
+}
+
+#[test]
+fn test_glyph_cache() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 20.0)?;
+    let cache = GlyphCache::new().with_limit(4);
+    let render = |cache: Option<&GlyphCache>| {
+        let mut layout = TextLayout::new().with_position(4, 4);
+        if let Some(cache) = cache {
+            layout = layout.with_glyph_cache(cache);
+        }
+        let layout = layout.with_basic_text(&font, "cached", Rgba::white());
+        Image::new(128, 32, Rgba::black()).with(&layout)
+    };
+
+    let expected = render(None);
+    assert_eq!(render(Some(&cache)).data, expected.data);
+    assert_eq!(cache.len(), 4);
+    assert_eq!(render(Some(&cache)).data, expected.data);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_text_glyphs() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 20.0)?;
+    let layout = TextLayout::<Rgba>::new()
+        .with_position(32, 32)
+        .with_basic_text(&font, "ab", Rgba::white())
+        .with_basic_text(&font, "\nc", Rgba::white());
+
+    let glyphs = layout.glyphs().collect::<Vec<_>>();
+    let visible = glyphs
+        .iter()
+        .filter(|glyph| !glyph.character.is_whitespace())
+        .map(|glyph| (glyph.character, glyph.segment, glyph.line))
+        .collect::<Vec<_>>();
+    assert_eq!(visible, [('a', 0, 0), ('b', 0, 0), ('c', 1, 1)]);
+    assert!(glyphs
+        .iter()
+        .all(|glyph| glyph.x >= 32.0 && !glyph.is_image));
+    Ok(())
+}
+
+#[test]
+fn test_text_overflow() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 20.0)?;
+    let text = "the quick brown fox jumps over the lazy dog";
+    let layout = |overflow| {
+        TextLayout::<Rgba>::new()
+            .with_wrap(WrapStyle::Word)
+            .with_width(120)
+            .with_max_lines(2)
+            .with_overflow(overflow)
+            .with_basic_text(&font, text, Rgba::white())
+    };
+
+    let clipped = layout(Overflow::Clip).glyphs().collect::<Vec<_>>();
+    assert!(clipped.iter().all(|glyph| glyph.line < 2));
+    assert!(clipped.len() < text.len());
+
+    let ellipsized = layout(Overflow::Ellipsis).glyphs().collect::<Vec<_>>();
+    let last = ellipsized.last().unwrap();
+    assert_eq!((last.character, last.line), ('…', 1));
+    assert!(last.x + last.width as f32 <= 120.0);
+    Ok(())
+}
+
+#[test]
+fn test_text_pattern() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 48.0)?;
+    let mut image = Image::new(256, 64, Rgba::black());
+    let (red, blue) = (Rgba::new(255, 0, 0, 255), Rgba::new(0, 0, 255, 255));
+    let tile = Image::from_fn(8, 8, |x, _| if x < 4 { red } else { blue });
+
+    let (cx, cy) = image.center();
+    let layout = TextLayout::new()
+        .with_position(cx, cy)
+        .centered()
+        .with_text(&TextSegment::new(&font, "pattern", Pattern::new(&tile)));
+    let glyphs = layout.glyphs().collect::<Vec<_>>();
+
+    image.draw(&layout);
+    assert!(image.data.iter().any(|&pixel| pixel == red));
+    assert!(image.data.iter().any(|&pixel| pixel == blue));
+    for (x, y, pixel) in drawn_pixels(&image, Rgba::black()) {
+        assert!(
+            in_glyph(&glyphs, x, y),
+            "({x}, {y}) is outside of every glyph"
+        );
+        // Each pixel is either a red or a blue stripe of the tile, blended onto the background
+        assert!(
+            pixel.g == 0 && (pixel.r == 0 || pixel.b == 0),
+            "({x}, {y}) is {pixel:?}"
+        );
+    }
+    image.save_inferred("tests/out/text_pattern_output.png")
+}
+
+#[cfg(feature = "arabic-shaping")]
+#[test]
+fn test_wrapped_rtl_text() -> ril::Result<()> {
+    let font = Font::open("tests/test_font_inter.ttf", 20.0)?;
+    let text = "\u{05D0}\u{05D1} \u{05D2}\u{05D3} \u{05D4}\u{05D5}";
+    let layout = TextLayout::<Rgba>::new()
+        .with_wrap(WrapStyle::Word)
+        .with_width(40)
+        .with_basic_text(&font, text, Rgba::white());
+
+    // Lines are wrapped in logical order, so the first word is on the first line...
+    let glyphs = layout
+        .glyphs()
+        .filter(|glyph| !glyph.character.is_whitespace())
+        .collect::<Vec<_>>();
+    assert_eq!((glyphs[0].character, glyphs[0].line), ('\u{05D0}', 0));
+    assert!(glyphs.last().unwrap().line > 0);
+
+    // ...and every line is laid out from right to left on its own
+    for pair in glyphs.windows(2) {
+        if pair[0].line == pair[1].line {
+            assert!(pair[1].x < pair[0].x);
+        }
+    }
+    Ok(())
+}
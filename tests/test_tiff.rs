@@ -0,0 +1,76 @@
+#![cfg(feature = "tiff")]
+
+mod test_png;
+
+use ril::encodings::tiff::{TiffCompression, TiffEncoder, TiffEncoderOptions};
+use ril::prelude::*;
+use test_png::COLORS;
+
+#[test]
+fn test_tiff_round_trip() -> ril::Result<()> {
+    let image = Image::from_fn(64, 48, |x, y| {
+        Rgba::new(x as u8 * 4, y as u8 * 5, 128, 255 - x as u8)
+    });
+
+    for compression in [
+        TiffCompression::None,
+        TiffCompression::Lzw,
+        TiffCompression::Deflate,
+        TiffCompression::PackBits,
+    ] {
+        let mut bytes = Vec::new();
+        let metadata = EncoderMetadata::from(&image)
+            .with_config(TiffEncoderOptions::new().with_compression(compression));
+        let mut encoder = TiffEncoder::new(&mut bytes, metadata)?;
+        encoder.add_frame(&image)?;
+        encoder.finish()?;
+
+        assert_eq!(ImageFormat::infer_encoding(&bytes), ImageFormat::Tiff);
+        let decoded = Image::<Rgba>::from_bytes(ImageFormat::Tiff, &bytes)?;
+        assert_eq!(decoded.dimensions(), (64, 48));
+        assert_eq!(decoded.data, image.data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_tiff_pages() -> ril::Result<()> {
+    let mut sequence = ImageSequence::new();
+    for color in COLORS {
+        sequence.push_frame(Frame::from_image(Image::new(16, 16, color)));
+    }
+
+    let mut bytes = Vec::new();
+    sequence.encode(ImageFormat::Tiff, &mut bytes)?;
+
+    let decoded = ImageSequence::<Rgb>::from_bytes(ImageFormat::Tiff, &bytes)?;
+    assert_eq!(decoded.len(), COLORS.len() as u32);
+
+    let decoded = decoded.collect::<ril::Result<ImageSequence<_>>>()?;
+    assert_eq!(decoded.len(), COLORS.len());
+    for (frame, ref color) in decoded.iter().zip(COLORS) {
+        assert_eq!(frame.pixel(8, 8), color);
+    }
+
+    // The first page is decoded when the file is opened as a single image
+    let first = Image::<Rgb>::from_bytes(ImageFormat::Tiff, &bytes)?;
+    assert_eq!(first.pixel(0, 0), &COLORS[0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_tiled_tiff() -> ril::Result<()> {
+    // 40x24 RGB image stored in LZW-compressed 16x16 tiles, so the tiles on the right and bottom
+    // edges are padded past the image
+    let image = Image::<Rgb>::open("tests/tiled_sample.tif")?;
+    assert_eq!(image.dimensions(), (40, 24));
+
+    let expected = Image::from_fn(40, 24, |x, y| {
+        Rgb::new(x as u8 * 6, y as u8 * 10, (x * y % 256) as u8)
+    });
+    assert_eq!(image.data, expected.data);
+
+    Ok(())
+}
@@ -0,0 +1,501 @@
+use ril::prelude::*;
+
+fn sample() -> Image<L> {
+    // 3x2:
+    // 0 1 2
+    // 3 4 5
+    Image::from_fn(3, 2, |x, y| L((y * 3 + x) as u8))
+}
+
+fn values(image: &Image<L>) -> Vec<u8> {
+    image.data.iter().map(|p| p.0).collect()
+}
+
+#[test]
+fn test_flip_in_place() {
+    let mut image = sample();
+    image.flip_horizontal_in_place();
+    assert_eq!(values(&image), [2, 1, 0, 5, 4, 3]);
+
+    let mut image = sample();
+    image.flip_vertical_in_place();
+    assert_eq!(values(&image), [3, 4, 5, 0, 1, 2]);
+}
+
+#[test]
+fn test_rotate_in_place() {
+    let mut image = sample();
+    image.rotate_90();
+    assert_eq!(image.dimensions(), (2, 3));
+    assert_eq!(values(&image), [3, 0, 4, 1, 5, 2]);
+
+    let mut image = sample();
+    image.rotate_270();
+    assert_eq!(image.dimensions(), (2, 3));
+    assert_eq!(values(&image), [2, 5, 1, 4, 0, 3]);
+
+    let image = Image::from_fn(7, 5, |x, y| L((y * 7 + x) as u8));
+    assert_eq!(
+        values(&image.clone().rotated(90).rotated(90).rotated(90).rotated(90)),
+        values(&image),
+    );
+}
+
+#[test]
+fn test_crop_and_pad_in_place() {
+    let mut image = sample();
+    image.crop(1, 0, 3, 2);
+    assert_eq!(image.dimensions(), (2, 2));
+    assert_eq!(values(&image), [1, 2, 4, 5]);
+
+    let mut image = sample();
+    image.pad(L(9), 1, 0, 0, 2);
+    assert_eq!(image.dimensions(), (5, 3));
+    assert_eq!(
+        values(&image),
+        [9, 9, 9, 9, 9, 9, 9, 0, 1, 2, 9, 9, 3, 4, 5],
+    );
+
+    let mut converted = Image::new(1, 1, Rgb::white());
+    sample().convert_into(&mut converted);
+    assert_eq!(converted.dimensions(), (3, 2));
+    assert_eq!(converted.pixel(2, 1), &Rgb::new(5, 5, 5));
+}
+
+#[test]
+fn test_checked_access() {
+    let mut image = sample();
+    *image.try_pixel_mut(2, 1).unwrap() = L(9);
+    assert_eq!(values(&image), [0, 1, 2, 3, 4, 9]);
+
+    let err = image.try_pixel_mut(3, 0).unwrap_err();
+    assert!(matches!(
+        err,
+        ril::Error::OutOfBounds {
+            region: (3, 0, 4, 1),
+            dimensions: (3, 2),
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Pixel (3, 0) is out of bounds of an image with dimensions 3x2"
+    );
+    assert!(image.try_pixel_mut(0, u32::MAX).is_err());
+
+    // Failed crops leave the image untouched
+    let mut image = sample();
+    assert!(matches!(
+        image.try_crop(0, 0, 4, 2),
+        Err(ril::Error::OutOfBounds {
+            region: (0, 0, 4, 2),
+            ..
+        })
+    ));
+    assert!(matches!(
+        image.try_crop(2, 0, 2, 2),
+        Err(ril::Error::EmptyImageError)
+    ));
+    assert!(matches!(
+        image.try_crop(2, 1, 1, 2),
+        Err(ril::Error::EmptyImageError)
+    ));
+    assert_eq!(values(&image), [0, 1, 2, 3, 4, 5]);
+    image.try_crop(1, 0, 3, 2).unwrap();
+    assert_eq!(values(&image), [1, 2, 4, 5]);
+
+    // Pasted images must lie entirely within the image, unlike with paste
+    let mut image = sample();
+    let overlay = Image::new(2, 1, L(9));
+    let err = image.try_paste(2, 0, &overlay).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Region (2, 0) to (4, 1) is out of bounds of an image with dimensions 3x2"
+    );
+    assert!(matches!(
+        image.try_paste(-1, 0, &overlay),
+        Err(ril::Error::OutOfBounds {
+            region: (-1, 0, 1, 1),
+            ..
+        })
+    ));
+    assert_eq!(values(&image), [0, 1, 2, 3, 4, 5]);
+    image.try_paste(1, 1, &overlay).unwrap();
+    assert_eq!(values(&image), [0, 1, 2, 3, 9, 9]);
+
+    // Paletted images keep their palette when cropped
+    let palette = [Rgb::black(), Rgb::white()];
+    let mut image = Image::<PalettedRgb>::from_paletted_pixels(3, palette.to_vec(), [0, 1, 0]);
+    assert!(image.try_crop(1, 0, 4, 1).is_err());
+    image.try_crop(1, 0, 2, 1).unwrap();
+    assert_eq!(image.pixel(0, 0).color(), Rgb::white());
+    assert_eq!(image.palette(), Some(&palette[..]));
+}
+
+#[test]
+fn test_image_builder() {
+    let image = Image::<Rgb>::builder(3, 2).build().unwrap();
+    assert_eq!(image.dimensions(), (3, 2));
+    assert_eq!(image.data, [Rgb::black(); 6]);
+    assert_eq!(image.overlay_mode(), OverlayMode::Merge);
+    assert_eq!(image.format(), ImageFormat::Unknown);
+
+    let builder = ImageBuilder::new(1, 1)
+        .with_dimensions(2, 4)
+        .with_background(Rgba::new(10, 20, 30, 40))
+        .with_overlay_mode(OverlayMode::Replace)
+        .with_format(ImageFormat::Png);
+    assert_eq!((builder.width, builder.height), (2, 4));
+
+    let mut image = builder.clone().build().unwrap();
+    assert_eq!(image.dimensions(), (2, 4));
+    assert_eq!(image.pixel(1, 3), &Rgba::new(10, 20, 30, 40));
+    assert_eq!(image.format(), ImageFormat::Png);
+    // The overlay mode applies to anything drawn onto the image
+    let fill = Rgba::new(255, 0, 0, 128);
+    image.draw(&Rectangle::from_bounding_box(0, 0, 2, 4).with_fill(fill));
+    assert_eq!(image.pixel(0, 0), &fill);
+
+    // Images without pixels cannot be built
+    for (width, height) in [(0, 4), (2, 0), (0, 0)] {
+        assert!(matches!(
+            builder.clone().with_dimensions(width, height).build(),
+            Err(ril::Error::EmptyImageError)
+        ));
+    }
+}
+
+#[test]
+fn test_process_tiles() {
+    let image = Image::from_fn(7, 5, |x, y| L((y * 7 + x) as u8));
+
+    let mut tiled = image.clone();
+    tiled.process_tiles(3, 2, 1, |tile| {
+        assert!(tile.width() <= 5 && tile.height() <= 4);
+        !tile
+    });
+    assert_eq!(values(&tiled), values(&!image));
+
+    // Tiles read the original pixels around them, even after neighboring tiles are written back
+    let sum = |image: &Image<L>| {
+        image.map_with_neighborhood(1, EdgeMode::Clamp, |window| {
+            L(window.pixels().map(|p| p.value() / 9).sum())
+        })
+    };
+    let image = Image::from_fn(7, 5, |x, y| L((y * 7 + x) as u8 * 5));
+    let mut tiled = image.clone();
+    tiled.process_tiles(3, 2, 1, |tile| sum(&tile));
+    assert_eq!(values(&tiled), values(&sum(&image)));
+
+    // Tiles of paletted images keep the palette
+    let palette = [Rgb::black(), Rgb::white()];
+    let mut image = Image::<PalettedRgb>::from_paletted_pixels(2, palette.to_vec(), [0, 1, 1, 0]);
+    image.process_tiles(1, 1, 0, |tile| {
+        assert_eq!(tile.palette(), Some(&palette[..]));
+        tile
+    });
+    assert_eq!(image.pixel(1, 0).color(), Rgb::white());
+}
+
+#[test]
+fn test_trim() {
+    let image = Image::from_fn(6, 4, |x, y| match (x, y) {
+        (2..=3, 1..=2) => Rgb::black(),
+        _ => Rgb::new(250, 255, 252),
+    });
+
+    let (trimmed, bounds) = image.clone().trimmed(TrimMode::Tolerance(Rgb::white(), 5));
+    assert_eq!(bounds, Some((2, 1, 4, 3)));
+    assert_eq!(trimmed.dimensions(), (2, 2));
+
+    let (untouched, bounds) = image.trimmed(TrimMode::Color(Rgb::white()));
+    assert_eq!(bounds, Some((0, 0, 6, 4)));
+    assert_eq!(untouched.dimensions(), (6, 4));
+}
+
+#[test]
+fn test_content_bounds() {
+    let image = sample();
+
+    // Bounds are exclusive on the right and bottom, like crop
+    assert_eq!(image.content_bounds(|p| p.0 >= 4), Some((1, 1, 3, 2)));
+    assert_eq!(image.content_bounds(|p| p.0 == 5), Some((2, 1, 3, 2)));
+    assert_eq!(image.content_bounds(|_| true), Some((0, 0, 3, 2)));
+    assert_eq!(image.content_bounds(|p| p.0 > 5), None);
+
+    // Querying the bounds leaves the image untouched
+    assert_eq!(values(&image), [0, 1, 2, 3, 4, 5]);
+
+    let palette = [Rgb::black(), Rgb::white()];
+    let image = Image::<PalettedRgb>::from_paletted_pixels(3, palette.to_vec(), [0, 0, 0, 1, 0, 1]);
+    assert_eq!(
+        image.content_bounds(|p| p.color() == Rgb::white()),
+        Some((0, 1, 3, 2))
+    );
+}
+
+#[test]
+fn test_average_color_and_preview() {
+    let image = Image::from_fn(4, 2, |x, _| {
+        if x < 2 {
+            Rgba::new(255, 0, 0, 255)
+        } else {
+            Rgba::transparent()
+        }
+    });
+
+    // Transparent pixels only lower the alpha, not the color
+    assert_eq!(image.average_color(), Rgba::new(255, 0, 0, 128));
+
+    let preview = image.preview(2);
+    assert_eq!(preview.dimensions(), (2, 2));
+    assert_eq!(*preview.pixel(0, 1), Rgba::new(255, 0, 0, 255));
+    assert_eq!(*preview.pixel(1, 0), Rgba::transparent());
+
+    // Upscaling still covers every preview pixel
+    assert_eq!(image.preview(8).pixel(7, 7).a, 0);
+}
+
+#[test]
+fn test_map_with_neighborhood_edges() {
+    // 0 1 2
+    // 3 4 5
+    let image = sample();
+    let left = |edge| image.map_with_neighborhood(1, edge, |window| window.get(-1, 0));
+
+    assert_eq!(values(&left(EdgeMode::Clamp)), [0, 0, 1, 3, 3, 4]);
+    assert_eq!(values(&left(EdgeMode::Reflect)), [1, 0, 1, 4, 3, 4]);
+    assert_eq!(values(&left(EdgeMode::Wrap)), [2, 0, 1, 5, 3, 4]);
+    assert_eq!(values(&left(EdgeMode::Constant(L(9)))), [9, 0, 1, 9, 3, 4]);
+
+    let sums = image.map_with_neighborhood(1, EdgeMode::Constant(L(0)), |window| {
+        L(window.pixels().map(|p| p.value()).sum())
+    });
+    assert_eq!(values(&sums), [8, 15, 12, 8, 15, 12]);
+}
+
+#[test]
+fn test_rotate_about() {
+    // 0 1 2
+    // 3 4 5
+    let image = sample().map_pixels(|L(v)| Rgb::new(v, v, v));
+    let value = |image: &Image<Rgb>, x, y| image.pixel(x, y).r;
+
+    // Rotating a quarter turn about pixel (1, 1) keeps the canvas and moves pixel (2, 1) below it
+    let (rotated, offset) =
+        image
+            .clone()
+            .rotated_about(90.0, (1.0, 1.0), RotationCanvas::Keep, Rgb::new(9, 9, 9));
+    assert_eq!(offset, (0, 0));
+    assert_eq!(rotated.dimensions(), (3, 2));
+    assert_eq!(value(&rotated, 1, 1), 4);
+    assert_eq!(value(&rotated, 1, 0), 3);
+    assert_eq!(value(&rotated, 0, 0), 9);
+
+    // Expanding the canvas gives the same result as a lossless rotation
+    let (rotated, offset) =
+        image
+            .clone()
+            .rotated_about(90.0, (1.0, 1.0), RotationCanvas::Expand, Rgb::black());
+    assert_eq!(offset, (1, 0));
+    assert_eq!(rotated.data, image.rotated(90).data);
+}
+
+#[test]
+fn test_affine_transform() {
+    let image = sample().map_pixels(|L(v)| Rgb::new(v, v, v));
+    let value = |image: &Image<Rgb>, x, y| image.pixel(x, y).r;
+
+    let affine = Affine::rotate(30.0)
+        .then_scale(2.0, 0.5)
+        .then_translate(3.0, -1.0);
+    let (x, y) = affine.inverse().unwrap().then(affine).apply(5.0, 7.0);
+    assert!((x - 5.0).abs() < 1e-9 && (y - 7.0).abs() < 1e-9);
+    assert!(Affine::scale(0.0, 1.0).inverse().is_none());
+
+    // Mirroring about the center of the image is lossless
+    let mirror = Affine::scale(-1.0, 1.0).about((1.0, 0.5));
+    let (mirrored, offset) =
+        image
+            .clone()
+            .transformed(mirror, RotationCanvas::Expand, Rgb::black());
+    assert_eq!(offset, (0, 0));
+    assert_eq!(mirrored.data, image.clone().mirrored().data);
+
+    // Translating by whole pixels into an explicit canvas shifts pixels without blending them
+    let (moved, offset) = image.transformed(
+        Affine::translate(2.0, 1.0),
+        RotationCanvas::Bounds {
+            x: 0,
+            y: 0,
+            width: 4,
+            height: 3,
+        },
+        Rgb::new(9, 9, 9),
+    );
+    assert_eq!(offset, (0, 0));
+    assert_eq!(moved.dimensions(), (4, 3));
+    assert_eq!(value(&moved, 2, 1), 0);
+    assert_eq!(value(&moved, 3, 2), 4);
+    assert_eq!(value(&moved, 1, 1), 9);
+}
+
+#[test]
+#[cfg(feature = "resize")]
+fn test_resize_premultiplied() {
+    // Resampling next to fully transparent pixels must not darken the opaque color
+    let image = Image::from_pixels(2, vec![Rgba::white(), Rgba::new(0, 0, 0, 0)]);
+    let resized = image.resized(4, 1, ResizeAlgorithm::Bilinear);
+
+    for pixel in resized.data.iter().filter(|pixel| pixel.a > 0) {
+        assert!(
+            pixel.r >= 254 && pixel.g >= 254 && pixel.b >= 254,
+            "{pixel:?}"
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "resize")]
+fn test_resize_16_bit() {
+    // 16-bit channels must be resampled as whole values rather than as separate bytes
+    let gradient = [0, 1000, 2000, 3000];
+    let image = Image::from_pixels(4, gradient.map(L16).to_vec());
+    let resized = image.resized(2, 1, ResizeAlgorithm::Box);
+    assert_eq!(resized.data, [L16(500), L16(2500)]);
+
+    let pixels = gradient.map(|value| Rgba16::new(value, 60_000 - value, 40_000, u16::MAX));
+    let image = Image::from_pixels(4, pixels.to_vec());
+    let resized = image.resized(2, 1, ResizeAlgorithm::Box);
+    assert_eq!(
+        resized.data,
+        [
+            Rgba16::new(500, 59_500, 40_000, u16::MAX),
+            Rgba16::new(2500, 57_500, 40_000, u16::MAX),
+        ]
+    );
+}
+
+#[test]
+fn test_pixelate() {
+    // 4x2, with red values of:
+    // 0  10 20 30
+    // 40 50 60 70
+    let image = Image::from_fn(4, 2, |x, y| Rgb::new((x * 10 + y * 40) as u8, 0, 0));
+    let red = |image: &Image<Rgb>| image.data.iter().map(|p| p.r).collect::<Vec<_>>();
+
+    assert_eq!(
+        red(&image.clone().pixelated(2)),
+        [25, 25, 45, 45, 25, 25, 45, 45]
+    );
+    assert_eq!(red(&image.clone().pixelated(1)), red(&image));
+    // Blocks larger than the image average the whole image
+    assert_eq!(red(&image.clone().pixelated(16)), [35; 8]);
+    // Blocks at the edges are smaller
+    assert_eq!(
+        red(&image.clone().pixelated(3)),
+        [30, 30, 30, 50, 30, 30, 30, 50]
+    );
+
+    // Regions are clipped to the image
+    let mut region = image.clone();
+    region.pixelate_region(2, 2, 0, 100, 100);
+    assert_eq!(red(&region), [0, 10, 45, 45, 40, 50, 45, 45]);
+
+    // Empty regions and regions outside of the image leave it untouched
+    let mut region = image.clone();
+    region.pixelate_region(2, 3, 0, 1, 2);
+    region.pixelate_region(2, 10, 10, 20, 20);
+    assert_eq!(red(&region), red(&image));
+
+    // Alpha is averaged along with the color
+    let image = Image::from_pixels(2, [Rgba::new(255, 0, 0, 255), Rgba::new(0, 0, 255, 0)]);
+    assert_eq!(image.pixelated(2).data, [Rgba::new(128, 0, 128, 128); 2]);
+}
+
+#[test]
+#[should_panic(expected = "block size must be non-zero")]
+fn test_pixelate_empty_block() {
+    Image::new(2, 2, Rgb::white()).pixelate(0);
+}
+
+#[test]
+fn test_chromatic_aberration() {
+    let image = Image::from_fn(3, 1, |x, _| {
+        if x == 1 {
+            Rgba::new(255, 255, 255, 100)
+        } else {
+            Rgba::new(0, 0, 0, 255)
+        }
+    });
+
+    // Red is shifted right and blue left, while green and alpha stay in place
+    assert_eq!(
+        image
+            .clone()
+            .chromatic_aberrated(Aberration::Linear(1.0, 0.0))
+            .data,
+        [
+            Rgba::new(0, 0, 255, 255),
+            Rgba::new(0, 255, 0, 100),
+            Rgba::new(255, 0, 0, 255),
+        ]
+    );
+    // Fractional shifts are interpolated
+    let shifted = image
+        .clone()
+        .chromatic_aberrated(Aberration::Linear(0.5, 0.0));
+    assert_eq!(shifted.pixel(1, 0), &Rgba::new(128, 255, 128, 100));
+    // Shifts without an offset leave the image untouched
+    for aberration in [Aberration::Linear(0.0, 0.0), Aberration::Radial(0.0)] {
+        assert_eq!(
+            image.clone().chromatic_aberrated(aberration).data,
+            image.data
+        );
+    }
+
+    // Radial shifts grow from nothing at the center to the given amount at the corners, and
+    // sample the nearest edge pixel outside of the image
+    let image = Image::from_fn(5, 1, |x, _| Rgb::new(x as u8 * 50, 0, x as u8 * 50));
+    assert_eq!(
+        image.chromatic_aberrated(Aberration::Radial(2.0)).data,
+        [
+            Rgb::new(100, 0, 0),
+            Rgb::new(100, 0, 0),
+            Rgb::new(100, 0, 100),
+            Rgb::new(100, 0, 200),
+            Rgb::new(100, 0, 200),
+        ]
+    );
+
+    let image = Image::new(1, 1, Rgb::new(10, 20, 30));
+    assert_eq!(
+        image
+            .chromatic_aberrated(Aberration::Radial(5.0))
+            .pixel(0, 0),
+        &Rgb::new(10, 20, 30)
+    );
+}
+
+#[test]
+fn test_swirl() {
+    let image = Image::from_fn(5, 5, |x, y| Rgb::new(x as u8 * 50, y as u8 * 50, 0));
+
+    // Pixels one pixel away from the center are rotated by a quarter of the angle, which turns
+    // them halfway around the center
+    let swirled = image.clone().swirled((2, 2), 2, 720.0);
+    assert_eq!(swirled.pixel(3, 2), image.pixel(1, 2));
+    assert_eq!(swirled.pixel(1, 2), image.pixel(3, 2));
+    assert_eq!(swirled.pixel(2, 1), image.pixel(2, 3));
+    // The center and everything outside of the radius are untouched
+    assert_eq!(swirled.pixel(2, 2), image.pixel(2, 2));
+    assert_eq!(swirled.pixel(2, 0), image.pixel(2, 0));
+    assert_eq!(swirled.pixel(0, 0), image.pixel(0, 0));
+
+    // Swirls without an angle or a radius do nothing
+    assert_eq!(image.clone().swirled((2, 2), 4, 0.0).data, image.data);
+    assert_eq!(image.clone().swirled((2, 2), 0, 180.0).data, image.data);
+
+    // Centers far outside of the image leave it untouched
+    let swirled = image.clone().swirled((100, 100), 10, 180.0);
+    assert_eq!(swirled.data, image.data);
+}
@@ -46,3 +46,30 @@ fn test_animated_webp_decode() -> ril::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_animated_webp_round_trip() -> ril::Result<()> {
+    let mut sequence = ImageSequence::new().looped_exactly(2);
+    for (i, color) in COLORS.into_iter().enumerate() {
+        sequence.push_frame(
+            Frame::from_image(Image::new(16, 16, Rgba::from(color)))
+                .with_delay(Duration::from_millis(50 * (i as u64 + 1))),
+        );
+    }
+
+    let mut buffer = Vec::new();
+    sequence.encode(ImageFormat::WebP, &mut buffer)?;
+
+    let decoded = ImageSequence::<Rgb>::from_bytes(ImageFormat::WebP, &buffer)?;
+    assert_eq!(decoded.len(), COLORS.len() as u32);
+    assert_eq!(decoded.loop_count(), LoopCount::Exactly(2));
+
+    let decoded = decoded.collect::<ril::Result<ImageSequence<_>>>()?;
+    assert_eq!(decoded.len(), COLORS.len());
+    for (frame, expected) in decoded.iter().zip(sequence.iter()) {
+        assert_eq!(frame.dimensions(), (16, 16));
+        assert_eq!(frame.delay(), expected.delay());
+    }
+
+    Ok(())
+}